@@ -335,8 +335,8 @@ mod tests {
 
     #[test]
     fn test_consume() {
-        assert_eq!(vec![0].into_iter().nth(0), Some(0));
-        assert_eq!(vec![0, 1, 2].into_iter().consume().nth(0), None);
+        assert_eq!(vec![0].into_iter().next(), Some(0));
+        assert_eq!(vec![0, 1, 2].into_iter().consume().next(), None);
     }
 
     #[test]
@@ -411,7 +411,7 @@ mod tests {
 
     #[test]
     fn test_some() {
-        assert_eq!((0..10).filter(|&x| x == 2).some(), true);
-        assert_eq!((0..10).filter(|&x| x == 11).some(), false);
+        assert!((0..10).filter(|&x| x == 2).some());
+        assert!(!(0..10).filter(|&x| x == 11).some());
     }
 }
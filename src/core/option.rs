@@ -43,7 +43,7 @@ mod tests
     fn test_has()
     {
         assert!(Some(Component::ParentDir).has(Component::ParentDir));
-        assert_eq!(Some(Component::ParentDir).has(Component::ParentDir), true);
-        assert_eq!(None.has(Component::ParentDir), false);
+        assert!(Some(Component::ParentDir).has(Component::ParentDir));
+        assert!(!None.has(Component::ParentDir));
     }
 }
@@ -4,10 +4,47 @@ use std::{
     str,
 };
 
-use crate::errors::*;
+use crate::{
+    errors::*,
+    sys::{self, VirtualFileSystem},
+};
 
 /// Provides string manipulation extensions for the [`str`] and [`String`] types
 pub trait StringExt {
+    /// Returns a new [`PathBuf`] from the string with the home prefix and environment variables
+    /// expanded
+    ///
+    /// * Expands the home symbol `~` and environment variables i.e. `$HOME` or `${HOME}`
+    ///
+    /// ### Errors
+    /// * PathError::InvalidExpansion(PathBuf) when the home prefix isn't at the start
+    /// * PathError::MultipleHomeSymbols(PathBuf) when more than one home prefix is given
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let home = sys::home_dir().unwrap();
+    /// assert_eq!("~/foo".expand().unwrap(), home.mash("foo"));
+    /// ```
+    fn expand(&self) -> RvResult<PathBuf>;
+
+    /// Returns a new [`String`] with each `{{ name }}` template variable replaced by its value
+    ///
+    /// * Whitespace around the variable name is ignored
+    /// * Text after an unterminated `{{` is left as is
+    ///
+    /// ### Errors
+    /// * StringError::UndefinedVariable(String) when a variable has no value given
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// assert_eq!("port={{ port }}".render(&[("port", "80")]).unwrap(), "port=80");
+    /// ```
+    fn render<K: AsRef<str>, V: AsRef<str>>(&self, vars: &[(K, V)]) -> RvResult<String>;
+
     /// Returns the length in characters rather than bytes i.e. this is a human understandable
     /// value. However it is more costly to perform.
     ///
@@ -21,6 +58,25 @@ pub trait StringExt {
     /// ```
     fn size(&self) -> usize;
 
+    /// Returns a new [`PathBuf`] from the string resolved to absolute form by the given Vfs
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Relative paths are resolved from the Vfs's current working directory
+    ///
+    /// ### Errors
+    /// * PathError::Empty when the string is empty
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let home = sys::home_dir().unwrap();
+    /// assert_eq!("~/.config/app".to_abs(&vfs).unwrap(), home.mash(".config/app"));
+    /// assert_eq!("foo".to_string().to_abs(&vfs).unwrap(), vfs.cwd().unwrap().mash("foo"));
+    /// ```
+    fn to_abs<V: VirtualFileSystem>(&self, vfs: &V) -> RvResult<PathBuf>;
+
     /// Convert the string into a bool
     ///
     /// * Returns `true` if non-empty and any value other than `0` or case insensitive `false`
@@ -38,6 +94,16 @@ pub trait StringExt {
     /// ```
     fn to_bool(&self) -> bool;
 
+    /// Returns a new [`PathBuf`] from the string as is
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// assert_eq!("/foo/bar".to_path(), PathBuf::from("/foo/bar"));
+    /// ```
+    fn to_path(&self) -> PathBuf;
+
     /// Returns a new [`String`] with the given `suffix` trimmed off else the original `String`.
     ///
     /// ### Examples
@@ -50,10 +116,37 @@ pub trait StringExt {
 }
 
 impl StringExt for str {
+    fn expand(&self) -> RvResult<PathBuf> {
+        sys::expand(self)
+    }
+
+    fn render<K: AsRef<str>, V: AsRef<str>>(&self, vars: &[(K, V)]) -> RvResult<String> {
+        let mut rendered = String::with_capacity(self.len());
+        let mut rest = self;
+        while let Some(start) = rest.find("{{") {
+            let end = match rest[start + 2..].find("}}") {
+                Some(end) => end,
+                None => break,
+            };
+            let name = rest[start + 2..start + 2 + end].trim();
+            let (_, value) =
+                vars.iter().find(|(k, _)| k.as_ref() == name).ok_or_else(|| StringError::undefined_variable(name))?;
+            rendered.push_str(&rest[..start]);
+            rendered.push_str(value.as_ref());
+            rest = &rest[start + 2 + end + 2..];
+        }
+        rendered.push_str(rest);
+        Ok(rendered)
+    }
+
     fn size(&self) -> usize {
         self.chars().count()
     }
 
+    fn to_abs<V: VirtualFileSystem>(&self, vfs: &V) -> RvResult<PathBuf> {
+        vfs.abs(self)
+    }
+
     /// Convert the string into a bool
     ///
     /// * Returns `true` if non-empty and any value other than `0` or case insensitive `false`
@@ -73,6 +166,10 @@ impl StringExt for str {
         self.to_string().to_bool()
     }
 
+    fn to_path(&self) -> PathBuf {
+        PathBuf::from(self)
+    }
+
     fn trim_suffix<T: Into<String>>(&self, suffix: T) -> String {
         let target = suffix.into();
         match self.ends_with(&target) {
@@ -83,10 +180,22 @@ impl StringExt for str {
 }
 
 impl StringExt for String {
+    fn expand(&self) -> RvResult<PathBuf> {
+        self.as_str().expand()
+    }
+
+    fn render<K: AsRef<str>, V: AsRef<str>>(&self, vars: &[(K, V)]) -> RvResult<String> {
+        self.as_str().render(vars)
+    }
+
     fn size(&self) -> usize {
         self.chars().count()
     }
 
+    fn to_abs<V: VirtualFileSystem>(&self, vfs: &V) -> RvResult<PathBuf> {
+        self.as_str().to_abs(vfs)
+    }
+
     /// Convert the string into a bool
     ///
     /// * Returns `true` if non-empty and any value other than `0` or case insensitive `false`
@@ -107,6 +216,10 @@ impl StringExt for String {
         !(x.is_empty() || x == "false" || x == "0")
     }
 
+    fn to_path(&self) -> PathBuf {
+        PathBuf::from(self)
+    }
+
     fn trim_suffix<T: Into<String>>(&self, suffix: T) -> String {
         let target = suffix.into();
         match self.ends_with(&target) {
@@ -177,31 +290,76 @@ mod tests {
 
     #[test]
     fn test_str_to_bool() {
-        assert_eq!("foo".to_bool(), true);
-        assert_eq!("true".to_bool(), true);
-        assert_eq!("TRUE".to_bool(), true);
-        assert_eq!("".to_bool(), false);
-        assert_eq!("0".to_bool(), false);
-        assert_eq!("false".to_bool(), false);
-        assert_eq!("FALSE".to_bool(), false);
+        assert!("foo".to_bool());
+        assert!("true".to_bool());
+        assert!("TRUE".to_bool());
+        assert!(!"".to_bool());
+        assert!(!"0".to_bool());
+        assert!(!"false".to_bool());
+        assert!(!"FALSE".to_bool());
     }
 
     #[test]
     fn test_string_to_bool() {
-        assert_eq!("foo".to_string().to_bool(), true);
-        assert_eq!("true".to_string().to_bool(), true);
-        assert_eq!("TRUE".to_string().to_bool(), true);
-        assert_eq!("".to_string().to_bool(), false);
-        assert_eq!("0".to_string().to_bool(), false);
-        assert_eq!("false".to_string().to_bool(), false);
-        assert_eq!("FALSE".to_string().to_bool(), false);
+        assert!("foo".to_string().to_bool());
+        assert!("true".to_string().to_bool());
+        assert!("TRUE".to_string().to_bool());
+        assert!(!"".to_string().to_bool());
+        assert!(!"0".to_string().to_bool());
+        assert!(!"false".to_string().to_bool());
+        assert!(!"FALSE".to_string().to_bool());
+    }
+
+    #[test]
+    fn test_str_expand() {
+        let home = sys::home_dir().unwrap();
+        assert_eq!("~".expand().unwrap(), home);
+        assert_eq!("~/foo".to_string().expand().unwrap(), home.mash("foo"));
+        assert_eq!("foo/bar".expand().unwrap(), PathBuf::from("foo/bar"));
+        assert_eq!(
+            "foo/~".expand().unwrap_err().downcast_ref::<PathError>(),
+            Some(&PathError::invalid_expansion("foo/~"))
+        );
+    }
+
+    #[test]
+    fn test_str_render() {
+        let vars = [("name", "foo"), ("port", "80")];
+        assert_eq!("".render(&vars).unwrap(), "");
+        assert_eq!("no vars".render(&vars).unwrap(), "no vars");
+        assert_eq!("{{name}}:{{ port }}".render(&vars).unwrap(), "foo:80");
+        assert_eq!("a {{  name }} b {{name}}".to_string().render(&vars).unwrap(), "a foo b foo");
+        assert_eq!("{{ name }} {{ port".render(&vars).unwrap(), "foo {{ port");
+        assert_eq!(
+            "{{ foo }}".render(&vars).unwrap_err().downcast_ref::<StringError>(),
+            Some(&StringError::undefined_variable("foo"))
+        );
+    }
+
+    #[test]
+    fn test_str_to_abs() {
+        let vfs = Memfs::new();
+        let home = sys::home_dir().unwrap();
+        assert_eq!("/foo/../bar".to_abs(&vfs).unwrap(), PathBuf::from("/bar"));
+        assert_eq!("~/.config/app".to_abs(&vfs).unwrap(), home.mash(".config/app"));
+        assert_vfs_mkdir_p!(vfs, "/work");
+        assert!(vfs.set_cwd("/work").is_ok());
+        assert_eq!("foo".to_string().to_abs(&vfs).unwrap(), PathBuf::from("/work/foo"));
+        assert_eq!("".to_abs(&vfs).unwrap_err().downcast_ref::<PathError>(), Some(&PathError::Empty));
+    }
+
+    #[test]
+    fn test_str_to_path() {
+        assert_eq!("".to_path(), PathBuf::new());
+        assert_eq!("/foo/bar".to_path(), PathBuf::from("/foo/bar"));
+        assert_eq!("foo".to_string().to_path(), PathBuf::from("foo"));
     }
 
     #[test]
     fn test_str_trim_suffix() {
-        assert_eq!("foo".trim_suffix("boo"), "foo"); // no change
-        assert_eq!("foo".trim_suffix("oo"), "f");
-        assert_eq!("ƒoo".trim_suffix("o"), "ƒo"); // fancy f!
+        assert_eq!(StringExt::trim_suffix("foo", "boo"), "foo"); // no change
+        assert_eq!(StringExt::trim_suffix("foo", "oo"), "f");
+        assert_eq!(StringExt::trim_suffix("ƒoo", "o"), "ƒo"); // fancy f!
     }
 
     #[test]
@@ -0,0 +1,158 @@
+use std::{
+    error::Error as StdError,
+    fmt,
+    path::{Path, PathBuf},
+};
+
+use super::{RvError, RvResult};
+
+// Maximum number of failures to list in the display summary
+const SUMMARY_MAX: usize = 3;
+
+/// An error aggregating the per path failures of a recursive operation run with `continue_on_error`
+#[derive(Debug)]
+pub struct BatchError
+{
+    errors: Vec<(PathBuf, RvError)>,
+}
+impl BatchError
+{
+    /// Returns the failures as path and error pairs in the order they occurred
+    pub fn errors(&self) -> &[(PathBuf, RvError)]
+    {
+        &self.errors
+    }
+
+    /// Returns true if no failures were collected
+    pub fn is_empty(&self) -> bool
+    {
+        self.errors.is_empty()
+    }
+
+    /// Returns the number of failures collected
+    pub fn len(&self) -> usize
+    {
+        self.errors.len()
+    }
+
+    /// Returns the paths that failed in the order they occurred
+    pub fn paths(&self) -> Vec<&Path>
+    {
+        self.errors.iter().map(|(path, _)| path.as_path()).collect()
+    }
+
+    /// Consumes the error returning the failures as path and error pairs in the order they occurred
+    pub fn into_errors(self) -> Vec<(PathBuf, RvError)>
+    {
+        self.errors
+    }
+}
+
+impl StdError for BatchError {}
+
+impl fmt::Display for BatchError
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        write!(f, "Operation failed for {} path(s)", self.errors.len())?;
+        for (i, (path, err)) in self.errors.iter().take(SUMMARY_MAX).enumerate() {
+            write!(f, "{} {}: {}", if i == 0 { ":" } else { ";" }, path.display(), err)?;
+        }
+        if self.errors.len() > SUMMARY_MAX {
+            write!(f, "; and {} more", self.errors.len() - SUMMARY_MAX)?;
+        }
+        Ok(())
+    }
+}
+
+// Internal type used to collect per path failures for recursive operations. Failures are returned
+// immediately unless continuing on error in which case they are collected and returned as a single
+// `BatchError` once the operation completes.
+pub(crate) struct Batch
+{
+    cont: bool,
+    errors: Vec<(PathBuf, RvError)>,
+}
+impl Batch
+{
+    // Create a new batch that collects failures when `cont` is true
+    pub(crate) fn new(cont: bool) -> Self
+    {
+        Batch { cont, errors: vec![] }
+    }
+
+    // Check the result of the operation on the given path collecting the failure if continuing.
+    // Returns `None` for collected failures so that the caller can skip on to the next path.
+    pub(crate) fn check<T, U: AsRef<Path>>(&mut self, path: U, result: RvResult<T>) -> RvResult<Option<T>>
+    {
+        match result {
+            Ok(x) => Ok(Some(x)),
+            Err(err) if self.cont => {
+                self.errors.push((path.as_ref().to_path_buf(), err));
+                Ok(None)
+            },
+            Err(err) => Err(err),
+        }
+    }
+
+    // Complete the batch returning a `BatchError` if any failures were collected
+    pub(crate) fn finish(self) -> RvResult<()>
+    {
+        match self.errors.is_empty() {
+            true => Ok(()),
+            false => Err(BatchError { errors: self.errors }.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use std::path::{Path, PathBuf};
+
+    use crate::errors::*;
+
+    #[test]
+    fn test_batch()
+    {
+        // Failures are returned immediately by default
+        let mut batch = Batch::new(false);
+        assert_eq!(batch.check("foo", Ok(1)).unwrap(), Some(1));
+        assert!(batch.check::<(), _>("foo", Err(PathError::Empty.into())).is_err());
+        assert!(batch.finish().is_ok());
+
+        // Failures are collected when continuing on error
+        let mut batch = Batch::new(true);
+        assert_eq!(batch.check::<(), _>("foo", Err(PathError::Empty.into())).unwrap(), None);
+        assert_eq!(batch.check("bar", Ok(())).unwrap(), Some(()));
+        assert_eq!(batch.check::<(), _>("blah", Err(PathError::does_not_exist("blah").into())).unwrap(), None);
+        let batch_err = batch.finish().unwrap_err();
+        let err = batch_err.downcast_ref::<BatchError>().unwrap();
+        assert!(!err.is_empty());
+        assert_eq!(err.len(), 2);
+        assert_eq!(err.paths(), vec![Path::new("foo"), Path::new("blah")]);
+        assert_eq!(err.errors()[0].0, PathBuf::from("foo"));
+        assert_eq!(err.errors()[0].1.downcast_ref::<PathError>(), Some(&PathError::Empty));
+        match batch_err {
+            RvError::Batch(err) => assert_eq!(err.into_errors().len(), 2),
+            _ => panic!("expected a batch error"),
+        }
+    }
+
+    #[test]
+    fn test_batch_display()
+    {
+        let mut batch = Batch::new(true);
+        assert!(batch.check::<(), _>("foo", Err(PathError::Empty.into())).is_ok());
+        assert_eq!(batch.finish().unwrap_err().to_string(), "Operation failed for 1 path(s): foo: path empty");
+
+        let mut batch = Batch::new(true);
+        for path in ["a", "b", "c", "d", "e"] {
+            assert!(batch.check::<(), _>(path, Err(PathError::Empty.into())).is_ok());
+        }
+        assert_eq!(
+            batch.finish().unwrap_err().to_string(),
+            "Operation failed for 5 path(s): a: path empty; b: path empty; c: path empty; and 2 more"
+        );
+    }
+}
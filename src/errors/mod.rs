@@ -10,6 +10,7 @@
 //! assert!(err.downcast_mut::<std::env::VarError>().is_some());
 //! assert!(err.source().is_none());
 //! ```
+mod batch;
 mod core;
 mod file;
 mod iter;
@@ -20,6 +21,7 @@ mod vfs;
 
 use std::{error::Error as StdError, fmt, io, time::SystemTimeError};
 
+pub use batch::*;
 pub use file::*;
 pub use iter::*;
 pub use path::*;
@@ -35,9 +37,20 @@ pub type RvResult<T> = std::result::Result<T, RvError>;
 /// An error that provides a common error for Rivia wrapping other internal errors
 #[derive(Debug)]
 pub enum RvError {
+    /// Batch error
+    Batch(BatchError),
+
+    /// Bincode error
+    #[cfg(feature = "bincode")]
+    Bincode(bincode::Error),
+
     /// Core error
     Core(CoreError),
 
+    /// CSV error
+    #[cfg(feature = "csv")]
+    Csv(csv::Error),
+
     /// File error
     File(FileError),
 
@@ -47,6 +60,10 @@ pub enum RvError {
     /// A interator error
     Iter(IterError),
 
+    /// JSON error
+    #[cfg(feature = "json")]
+    Json(serde_json::Error),
+
     /// Nix low level error
     Nix(nix::errno::Errno),
 
@@ -99,10 +116,17 @@ impl StdError for RvError {}
 impl fmt::Display for RvError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
+            RvError::Batch(ref err) => write!(f, "{}", err),
+            #[cfg(feature = "bincode")]
+            RvError::Bincode(ref err) => write!(f, "{}", err),
             RvError::Core(ref err) => write!(f, "{}", err),
+            #[cfg(feature = "csv")]
+            RvError::Csv(ref err) => write!(f, "{}", err),
             RvError::File(ref err) => write!(f, "{}", err),
             RvError::Io(ref err) => write!(f, "{}", err),
             RvError::Iter(ref err) => write!(f, "{}", err),
+            #[cfg(feature = "json")]
+            RvError::Json(ref err) => write!(f, "{}", err),
             RvError::Nix(ref err) => write!(f, "{}", err),
             RvError::Path(ref err) => write!(f, "{}", err),
             RvError::String(ref err) => write!(f, "{}", err),
@@ -118,10 +142,17 @@ impl fmt::Display for RvError {
 impl AsRef<dyn StdError> for RvError {
     fn as_ref(&self) -> &(dyn StdError + 'static) {
         match *self {
+            RvError::Batch(ref err) => err,
+            #[cfg(feature = "bincode")]
+            RvError::Bincode(ref err) => err,
             RvError::Core(ref err) => err,
+            #[cfg(feature = "csv")]
+            RvError::Csv(ref err) => err,
             RvError::File(ref err) => err,
             RvError::Io(ref err) => err,
             RvError::Iter(ref err) => err,
+            #[cfg(feature = "json")]
+            RvError::Json(ref err) => err,
             RvError::Nix(ref err) => err,
             RvError::Path(ref err) => err,
             RvError::String(ref err) => err,
@@ -137,10 +168,17 @@ impl AsRef<dyn StdError> for RvError {
 impl AsMut<dyn StdError> for RvError {
     fn as_mut(&mut self) -> &mut (dyn StdError + 'static) {
         match *self {
+            RvError::Batch(ref mut err) => err,
+            #[cfg(feature = "bincode")]
+            RvError::Bincode(ref mut err) => err,
             RvError::Core(ref mut err) => err,
+            #[cfg(feature = "csv")]
+            RvError::Csv(ref mut err) => err,
             RvError::File(ref mut err) => err,
             RvError::Io(ref mut err) => err,
             RvError::Iter(ref mut err) => err,
+            #[cfg(feature = "json")]
+            RvError::Json(ref mut err) => err,
             RvError::Nix(ref mut err) => err,
             RvError::Path(ref mut err) => err,
             RvError::String(ref mut err) => err,
@@ -153,12 +191,32 @@ impl AsMut<dyn StdError> for RvError {
     }
 }
 
+impl From<BatchError> for RvError {
+    fn from(err: BatchError) -> RvError {
+        RvError::Batch(err)
+    }
+}
+
+#[cfg(feature = "bincode")]
+impl From<bincode::Error> for RvError {
+    fn from(err: bincode::Error) -> RvError {
+        RvError::Bincode(err)
+    }
+}
+
 impl From<CoreError> for RvError {
     fn from(err: CoreError) -> RvError {
         RvError::Core(err)
     }
 }
 
+#[cfg(feature = "csv")]
+impl From<csv::Error> for RvError {
+    fn from(err: csv::Error) -> RvError {
+        RvError::Csv(err)
+    }
+}
+
 impl From<FileError> for RvError {
     fn from(err: FileError) -> RvError {
         RvError::File(err)
@@ -177,6 +235,13 @@ impl From<IterError> for RvError {
     }
 }
 
+#[cfg(feature = "json")]
+impl From<serde_json::Error> for RvError {
+    fn from(err: serde_json::Error) -> RvError {
+        RvError::Json(err)
+    }
+}
+
 impl From<nix::errno::Errno> for RvError {
     fn from(err: nix::errno::Errno) -> RvError {
         RvError::Nix(err)
@@ -247,6 +312,26 @@ mod tests {
         assert!(err.downcast_mut::<CoreError>().is_some());
         assert!(err.source().is_none());
 
+        #[cfg(feature = "bincode")]
+        {
+            let mut err = RvError::from(bincode::Error::from(bincode::ErrorKind::SizeLimit));
+            assert_eq!("the size limit has been reached", err.to_string());
+            assert_eq!("the size limit has been reached", err.as_ref().to_string());
+            assert_eq!("the size limit has been reached", err.as_mut().to_string());
+            assert!(err.downcast_ref::<bincode::Error>().is_some());
+            assert!(err.downcast_mut::<bincode::Error>().is_some());
+        }
+
+        #[cfg(feature = "csv")]
+        {
+            let mut err = RvError::from(csv::Error::from(io::Error::new(io::ErrorKind::InvalidData, "foo")));
+            assert_eq!("foo", err.to_string());
+            assert_eq!("foo", err.as_ref().to_string());
+            assert_eq!("foo", err.as_mut().to_string());
+            assert!(err.downcast_ref::<csv::Error>().is_some());
+            assert!(err.downcast_mut::<csv::Error>().is_some());
+        }
+
         let mut err = RvError::from(io::Error::new(io::ErrorKind::AlreadyExists, "foo"));
         assert_eq!("foo", err.to_string());
         assert_eq!("foo", err.as_ref().to_string());
@@ -263,6 +348,16 @@ mod tests {
         assert!(err.downcast_mut::<IterError>().is_some());
         assert!(err.source().is_none());
 
+        #[cfg(feature = "json")]
+        {
+            let mut err = RvError::from(serde_json::from_str::<u32>("").unwrap_err());
+            assert_eq!("EOF while parsing a value at line 1 column 0", err.to_string());
+            assert_eq!("EOF while parsing a value at line 1 column 0", err.as_ref().to_string());
+            assert_eq!("EOF while parsing a value at line 1 column 0", err.as_mut().to_string());
+            assert!(err.downcast_ref::<serde_json::Error>().is_some());
+            assert!(err.downcast_mut::<serde_json::Error>().is_some());
+        }
+
         let mut err = RvError::from(FileError::FailedToExtractString);
         assert_eq!("Failed to extract string from file", err.to_string());
         assert_eq!("Failed to extract string from file", err.as_ref().to_string());
@@ -306,7 +401,8 @@ mod tests {
         assert!(err.downcast_mut::<std::time::SystemTimeError>().is_some());
         assert!(err.source().is_none());
 
-        let mut err = RvError::from(std::str::from_utf8(&vec![0, 159, 146, 150]).unwrap_err());
+        let bytes = vec![0, 159, 146, 150];
+        let mut err = RvError::from(std::str::from_utf8(&bytes).unwrap_err());
         assert_eq!(err.to_string(), "invalid utf-8 sequence of 1 bytes from index 1");
         assert_eq!(err.as_ref().to_string(), "invalid utf-8 sequence of 1 bytes from index 1");
         assert_eq!(err.as_mut().to_string(), "invalid utf-8 sequence of 1 bytes from index 1");
@@ -20,6 +20,9 @@ pub enum PathError
     /// An error indicating that the path is empty.
     Empty,
 
+    /// An error indicating that the path escapes the base directory it was given within.
+    EscapesBase(PathBuf),
+
     /// An error indicating that the path exists already.
     ExistsAlready(PathBuf),
 
@@ -35,6 +38,9 @@ pub enum PathError
     /// An error indicating that the path failed to expand properly.
     InvalidExpansion(PathBuf),
 
+    /// An error indicating that the path is not a single entry name.
+    InvalidName(PathBuf),
+
     /// An error indicating that the path is not a directory.
     IsNotDir(PathBuf),
 
@@ -58,6 +64,15 @@ pub enum PathError
 
     /// An error indicating that the path does not have a valid parent path.
     ParentNotFound(PathBuf),
+
+    /// An error indicating that resolving the link required too many link hops.
+    TooManyLinks(PathBuf),
+
+    /// An error indicating that the path is not a directory, file or symlink.
+    UnsupportedFileType(PathBuf),
+
+    /// An error indicating that the path was removed while being traversed.
+    Vanished(PathBuf),
 }
 impl PathError
 {
@@ -79,6 +94,12 @@ impl PathError
         PathError::DoesNotExist(path.as_ref().to_path_buf())
     }
 
+    /// Return an error indicating that the path escapes the base directory it was given within
+    pub fn escapes_base<T: AsRef<Path>>(path: T) -> PathError
+    {
+        PathError::EscapesBase(path.as_ref().to_path_buf())
+    }
+
     /// Return an error indicating that the path exists already
     pub fn exists_already<T: AsRef<Path>>(path: T) -> PathError
     {
@@ -139,6 +160,12 @@ impl PathError
         PathError::InvalidExpansion(path.as_ref().to_path_buf())
     }
 
+    /// Return an error indicating that the path is not a single entry name
+    pub fn invalid_name<T: AsRef<Path>>(path: T) -> PathError
+    {
+        PathError::InvalidName(path.as_ref().to_path_buf())
+    }
+
     /// Return an error indicating that link looping was detected
     pub fn link_looping<T: AsRef<Path>>(path: T) -> PathError
     {
@@ -156,6 +183,24 @@ impl PathError
     {
         PathError::ParentNotFound(path.as_ref().to_path_buf())
     }
+
+    /// Return an error indicating that resolving the link required too many link hops
+    pub fn too_many_links<T: AsRef<Path>>(path: T) -> PathError
+    {
+        PathError::TooManyLinks(path.as_ref().to_path_buf())
+    }
+
+    /// Return an error indicating that the path is not a directory, file or symlink
+    pub fn unsupported_file_type<T: AsRef<Path>>(path: T) -> PathError
+    {
+        PathError::UnsupportedFileType(path.as_ref().to_path_buf())
+    }
+
+    /// Return an error indicating that the path was removed while being traversed
+    pub fn vanished<T: AsRef<Path>>(path: T) -> PathError
+    {
+        PathError::Vanished(path.as_ref().to_path_buf())
+    }
 }
 
 impl StdError for PathError {}
@@ -183,6 +228,9 @@ impl fmt::Display for PathError
                 write!(f, "Target path does not exist: {}", path.display())
             },
             PathError::Empty => write!(f, "path empty"),
+            PathError::EscapesBase(ref path) => {
+                write!(f, "Target path escapes its base directory: {}", path.display())
+            },
             PathError::ExistsAlready(ref path) => {
                 write!(f, "Target path exists already: {}", path.display())
             },
@@ -198,6 +246,9 @@ impl fmt::Display for PathError
             PathError::InvalidExpansion(ref path) => {
                 write!(f, "Target path has an invalid expansion: {}", path.display())
             },
+            PathError::InvalidName(ref path) => {
+                write!(f, "Target path is not a valid entry name: {}", path.display())
+            },
             PathError::IsNotDir(ref path) => {
                 write!(f, "Target path is not a directory: {}", path.display())
             },
@@ -222,6 +273,15 @@ impl fmt::Display for PathError
             PathError::ParentNotFound(ref path) => {
                 write!(f, "Target path's parent not found: {}", path.display())
             },
+            PathError::TooManyLinks(ref path) => {
+                write!(f, "Target path requires too many link hops to resolve: {}", path.display())
+            },
+            PathError::UnsupportedFileType(ref path) => {
+                write!(f, "Target path has an unsupported file type: {}", path.display())
+            },
+            PathError::Vanished(ref path) => {
+                write!(f, "Target path vanished during traversal: {}", path.display())
+            },
         }
     }
 }
@@ -295,6 +355,11 @@ mod tests
             "Target path does not exist: foo"
         );
         assert_eq!(format!("{}", PathError::Empty), "path empty");
+        assert_eq!(PathError::escapes_base(Path::new("foo")), PathError::EscapesBase(PathBuf::from("foo")));
+        assert_eq!(
+            format!("{}", PathError::escapes_base(PathBuf::from("foo"))),
+            "Target path escapes its base directory: foo"
+        );
         assert_eq!(PathError::exists_already(Path::new("foo")), PathError::ExistsAlready(PathBuf::from("foo")));
         assert_eq!(
             format!("{}", PathError::ExistsAlready(PathBuf::from("foo"))),
@@ -329,6 +394,11 @@ mod tests
             format!("{}", PathError::invalid_expansion(PathBuf::from("foo"))),
             "Target path has an invalid expansion: foo"
         );
+        assert_eq!(PathError::invalid_name(Path::new("foo")), PathError::InvalidName(PathBuf::from("foo")));
+        assert_eq!(
+            format!("{}", PathError::invalid_name(PathBuf::from("foo"))),
+            "Target path is not a valid entry name: foo"
+        );
         assert_eq!(PathError::is_not_dir(Path::new("foo")), PathError::IsNotDir(PathBuf::from("foo")));
         assert_eq!(
             format!("{}", PathError::is_not_dir(PathBuf::from("foo"))),
@@ -367,6 +437,24 @@ mod tests
             format!("{}", PathError::multiple_home_symbols(PathBuf::from("foo"))),
             "Target path has multiple home symbols: foo"
         );
+        assert_eq!(PathError::too_many_links(Path::new("foo")), PathError::TooManyLinks(PathBuf::from("foo")));
+        assert_eq!(
+            format!("{}", PathError::too_many_links(PathBuf::from("foo"))),
+            "Target path requires too many link hops to resolve: foo"
+        );
+        assert_eq!(
+            PathError::unsupported_file_type(Path::new("foo")),
+            PathError::UnsupportedFileType(PathBuf::from("foo"))
+        );
+        assert_eq!(
+            format!("{}", PathError::unsupported_file_type(PathBuf::from("foo"))),
+            "Target path has an unsupported file type: foo"
+        );
+        assert_eq!(PathError::vanished(Path::new("foo")), PathError::Vanished(PathBuf::from("foo")));
+        assert_eq!(
+            format!("{}", PathError::vanished(PathBuf::from("foo"))),
+            "Target path vanished during traversal: foo"
+        );
     }
 
     #[test]
@@ -6,6 +6,18 @@ pub enum StringError
 {
     /// An error indicating a failure to convert the file value to a string.
     FailedToString,
+
+    /// An error indicating that a template variable was not given a value.
+    UndefinedVariable(String),
+}
+
+impl StringError
+{
+    /// Return an error indicating that a template variable was not given a value
+    pub fn undefined_variable<T: AsRef<str>>(name: T) -> StringError
+    {
+        StringError::UndefinedVariable(name.as_ref().to_string())
+    }
 }
 
 impl StdError for StringError {}
@@ -16,6 +28,7 @@ impl fmt::Display for StringError
     {
         match *self {
             StringError::FailedToString => write!(f, "failed to convert value to string"),
+            StringError::UndefinedVariable(ref name) => write!(f, "undefined template variable: {}", name),
         }
     }
 }
@@ -29,5 +42,7 @@ mod tests
     fn test_errors()
     {
         assert_eq!(format!("{}", StringError::FailedToString), "failed to convert value to string");
+        assert_eq!(StringError::undefined_variable("foo"), StringError::UndefinedVariable("foo".to_string()));
+        assert_eq!(format!("{}", StringError::undefined_variable("foo")), "undefined template variable: foo");
     }
 }
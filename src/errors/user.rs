@@ -6,6 +6,15 @@ pub enum UserError
 {
     /// An error indicating that the use does not exist.
     DoesNotExistById(u32),
+
+    /// An error indicating that the user does not exist.
+    DoesNotExistByName(String),
+
+    /// An error indicating that the group does not exist.
+    GroupDoesNotExistByName(String),
+
+    /// An error indicating that the owner spec is not of the form `user[:group]` or `:group`.
+    InvalidOwnerSpec(String),
 }
 impl UserError
 {
@@ -14,6 +23,24 @@ impl UserError
     {
         UserError::DoesNotExistById(uid)
     }
+
+    /// Return an error indicating that the user does not exist
+    pub fn does_not_exist_by_name<T: AsRef<str>>(name: T) -> UserError
+    {
+        UserError::DoesNotExistByName(name.as_ref().to_string())
+    }
+
+    /// Return an error indicating that the group does not exist
+    pub fn group_does_not_exist_by_name<T: AsRef<str>>(name: T) -> UserError
+    {
+        UserError::GroupDoesNotExistByName(name.as_ref().to_string())
+    }
+
+    /// Return an error indicating that the owner spec is invalid
+    pub fn invalid_owner_spec<T: AsRef<str>>(spec: T) -> UserError
+    {
+        UserError::InvalidOwnerSpec(spec.as_ref().to_string())
+    }
 }
 
 impl StdError for UserError {}
@@ -24,6 +51,9 @@ impl fmt::Display for UserError
     {
         match *self {
             UserError::DoesNotExistById(ref uid) => write!(f, "user does not exist: {}", uid),
+            UserError::DoesNotExistByName(ref name) => write!(f, "user does not exist: {}", name),
+            UserError::GroupDoesNotExistByName(ref name) => write!(f, "group does not exist: {}", name),
+            UserError::InvalidOwnerSpec(ref spec) => write!(f, "invalid owner spec: {}", spec),
         }
     }
 }
@@ -38,5 +68,14 @@ mod tests
     {
         assert_eq!(UserError::does_not_exist_by_id(1000), UserError::DoesNotExistById(1000));
         assert_eq!(format!("{}", UserError::DoesNotExistById(1000)), "user does not exist: 1000");
+        assert_eq!(UserError::does_not_exist_by_name("foo"), UserError::DoesNotExistByName("foo".to_string()));
+        assert_eq!(format!("{}", UserError::does_not_exist_by_name("foo")), "user does not exist: foo");
+        assert_eq!(
+            UserError::group_does_not_exist_by_name("foo"),
+            UserError::GroupDoesNotExistByName("foo".to_string())
+        );
+        assert_eq!(format!("{}", UserError::group_does_not_exist_by_name("foo")), "group does not exist: foo");
+        assert_eq!(UserError::invalid_owner_spec("a:b:c"), UserError::InvalidOwnerSpec("a:b:c".to_string()));
+        assert_eq!(format!("{}", UserError::invalid_owner_spec("a:b:c")), "invalid owner spec: a:b:c");
     }
 }
@@ -1,9 +1,20 @@
-use std::{error::Error as StdError, fmt};
+use std::{error::Error as StdError, fmt, path::PathBuf};
+
+use crate::sys::VfsOp;
 
 /// An error indicating something went wrong with a Rivia VFS operation
 #[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub enum VfsError
 {
+    /// An error indicating that the operation was cancelled via its cancel token
+    Cancelled,
+
+    /// An error indicating that the file descriptors ran out while opening the given path
+    DescriptorLimit(PathBuf),
+
+    /// An error indicating that the ACL given for the path is malformed
+    InvalidAcl(PathBuf),
+
     /// An error indicating that the chmod pattern is invalid
     InvalidChmod(String),
 
@@ -19,9 +30,39 @@ pub enum VfsError
     /// An error indicating that the symbolic chmod target is invalid
     InvalidChmodTarget(String),
 
+    /// An error indicating that the given archive is corrupt or truncated
+    InvalidArchive(PathBuf),
+
+    /// An error indicating that the given Vfs setting is unknown or its value is invalid
+    InvalidSetting(String, String),
+
+    /// An error indicating that the operation on the path was denied by a policy
+    PolicyDenied(VfsOp, PathBuf),
+
+    /// An error indicating that the operation would exceed the quota set on the given directory
+    QuotaExceeded(PathBuf),
+
+    /// An error indicating that the operation on the path was rejected by a read only Vfs
+    ReadOnly(VfsOp, PathBuf),
+
+    /// An error indicating that the given file's data couldn't be cloned as a reflink
+    ReflinkUnsupported(PathBuf),
+
+    /// An error indicating that the removal would cross into another device at the given path
+    RemoveCrossesDevice(PathBuf),
+
+    /// An error indicating that the removal of the given path is outside of the given base
+    RemoveOutsideBase(PathBuf, PathBuf),
+
+    /// An error indicating that the removal targeted the root directory
+    RemoveRoot,
+
     /// An error indicating that the virtual filesystem is unavailable
     Unavailable,
 
+    /// An error indicating that the given version of the file isn't in its history
+    VersionNotFound(PathBuf, usize),
+
     /// An error indicating that the underlying vfs implementation was the wrong one
     WrongProvider,
 }
@@ -41,6 +82,11 @@ impl fmt::Display for VfsError
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
     {
         match *self {
+            VfsError::Cancelled => write!(f, "Vfs operation was cancelled"),
+            VfsError::DescriptorLimit(ref path) => {
+                write!(f, "Vfs ran out of file descriptors opening: {}", path.display())
+            },
+            VfsError::InvalidAcl(ref path) => write!(f, "Vfs invalid ACL given for: {}", path.display()),
             VfsError::InvalidChmod(ref sym) => write!(f, "Invalid chmod symbols given: {}", sym),
             VfsError::InvalidChmodGroup(ref sym) => write!(f, "Invalid chmod group given: {}", sym),
             VfsError::InvalidChmodOp(ref sym) => {
@@ -52,7 +98,31 @@ impl fmt::Display for VfsError
             VfsError::InvalidChmodTarget(ref sym) => {
                 write!(f, "Invalid chmod target given: {}", sym)
             },
+            VfsError::InvalidArchive(ref path) => write!(f, "Vfs invalid archive given: {}", path.display()),
+            VfsError::InvalidSetting(ref key, ref value) => {
+                write!(f, "Vfs invalid setting given: {}={}", key, value)
+            },
+            VfsError::PolicyDenied(ref op, ref path) => {
+                write!(f, "Vfs policy denied {:?} operation for: {}", op, path.display())
+            },
+            VfsError::QuotaExceeded(ref path) => write!(f, "Vfs quota exceeded for: {}", path.display()),
+            VfsError::ReadOnly(ref op, ref path) => {
+                write!(f, "Vfs is read only rejecting {:?} operation for: {}", op, path.display())
+            },
+            VfsError::ReflinkUnsupported(ref path) => {
+                write!(f, "Vfs reflink isn't supported for: {}", path.display())
+            },
+            VfsError::RemoveCrossesDevice(ref path) => {
+                write!(f, "Vfs remove refused to cross a device boundary at: {}", path.display())
+            },
+            VfsError::RemoveOutsideBase(ref path, ref base) => {
+                write!(f, "Vfs remove refused for: {} outside of base: {}", path.display(), base.display())
+            },
+            VfsError::RemoveRoot => write!(f, "Vfs remove refused for the root directory"),
             VfsError::Unavailable => write!(f, "Virtual filesystem is unavailable"),
+            VfsError::VersionNotFound(ref path, version) => {
+                write!(f, "Vfs version {} not found for: {}", version, path.display())
+            },
             VfsError::WrongProvider => write!(f, "Wrong Virtual filesystem provider was given"),
         }
     }
@@ -61,7 +131,9 @@ impl fmt::Display for VfsError
 #[cfg(test)]
 mod tests
 {
-    use crate::errors::*;
+    use std::path::PathBuf;
+
+    use crate::{errors::*, sys::VfsOp};
 
     fn vfs_unavailable() -> RvResult<VfsError>
     {
@@ -84,6 +156,12 @@ mod tests
     #[test]
     fn test_vfs_errors()
     {
+        assert_eq!(VfsError::Cancelled.to_string(), "Vfs operation was cancelled");
+        assert_eq!(
+            VfsError::DescriptorLimit(PathBuf::from("/tmp")).to_string(),
+            "Vfs ran out of file descriptors opening: /tmp"
+        );
+        assert_eq!(VfsError::InvalidAcl(PathBuf::from("/tmp")).to_string(), "Vfs invalid ACL given for: /tmp");
         assert_eq!(VfsError::InvalidChmod("foo".to_string()).to_string(), "Invalid chmod symbols given: foo");
         assert_eq!(VfsError::InvalidChmodGroup("foo".to_string()).to_string(), "Invalid chmod group given: foo");
         assert_eq!(VfsError::InvalidChmodOp("foo".to_string()).to_string(), "Invalid chmod operation given: foo");
@@ -92,7 +170,41 @@ mod tests
             "Invalid chmod permissions given: foo"
         );
         assert_eq!(VfsError::InvalidChmodTarget("foo".to_string()).to_string(), "Invalid chmod target given: foo");
+        assert_eq!(
+            VfsError::InvalidArchive(PathBuf::from("/tmp/file.tar")).to_string(),
+            "Vfs invalid archive given: /tmp/file.tar"
+        );
+        assert_eq!(
+            VfsError::InvalidSetting("umask".to_string(), "9".to_string()).to_string(),
+            "Vfs invalid setting given: umask=9"
+        );
+        assert_eq!(
+            VfsError::PolicyDenied(VfsOp::Write, PathBuf::from("/etc/hosts")).to_string(),
+            "Vfs policy denied Write operation for: /etc/hosts"
+        );
+        assert_eq!(VfsError::QuotaExceeded(PathBuf::from("/tmp")).to_string(), "Vfs quota exceeded for: /tmp");
+        assert_eq!(
+            VfsError::ReadOnly(VfsOp::Remove, PathBuf::from("/etc/hosts")).to_string(),
+            "Vfs is read only rejecting Remove operation for: /etc/hosts"
+        );
+        assert_eq!(
+            VfsError::ReflinkUnsupported(PathBuf::from("/tmp/file")).to_string(),
+            "Vfs reflink isn't supported for: /tmp/file"
+        );
+        assert_eq!(
+            VfsError::RemoveCrossesDevice(PathBuf::from("/tmp/mnt")).to_string(),
+            "Vfs remove refused to cross a device boundary at: /tmp/mnt"
+        );
+        assert_eq!(
+            VfsError::RemoveOutsideBase(PathBuf::from("/home"), PathBuf::from("/tmp")).to_string(),
+            "Vfs remove refused for: /home outside of base: /tmp"
+        );
+        assert_eq!(VfsError::RemoveRoot.to_string(), "Vfs remove refused for the root directory");
         assert_eq!(VfsError::Unavailable.to_string(), "Virtual filesystem is unavailable");
+        assert_eq!(
+            VfsError::VersionNotFound(PathBuf::from("/file"), 2).to_string(),
+            "Vfs version 2 not found for: /file"
+        );
         assert_eq!(VfsError::WrongProvider.to_string(), "Wrong Virtual filesystem provider was given");
     }
 }
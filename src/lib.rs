@@ -54,6 +54,27 @@
 //! favor of a single point of entry into the VFS operations and much cleaner ergonomics i.e. always
 //! use the Filesystem backend trait implementation via Vfs for every Filesystem related operation.
 //!
+//! ### 9P/NFS export of Memfs
+//! Exporting a `Memfs` instance over a unix socket via a small 9P server was considered as a way
+//! to allow container-based integration tests to mount in-memory fixtures without privileges.
+//! However a network protocol server, its wire format and the session handling it requires are well
+//! outside the scope of a minimal dependency systems api. Such an export would better live in its
+//! own crate built on top of the public `VirtualFileSystem` api, thus it won't be implemented here.
+//!
+//! ### Absolute link normalization
+//! Normalizing links towards absolute targets, i.e. the inverse of `relink`, was considered for
+//! symmetry. However the Vfs `symlink` contract always produces links relative to the link's own
+//! location and `Memfs` tracks link targets in that same relative form, so an absolute link could
+//! only ever be produced by `Stdfs`. Rather than have the backends disagree, link normalization is
+//! only provided in the relative direction via `relink` and `sys::normalize_links`.
+//!
+//! ### Embedding whole fixture directories
+//! Embedding an entire fixture directory into a `Memfs` at compile time, i.e. `include_dir` style,
+//! was considered to complement `Memfs::seed`. However enumerating a directory at compile time
+//! requires a procedural macro and thus a separate proc-macro crate along with its `syn` and
+//! `quote` dependencies. Rather than take on that weight the `memfs_seed!` macro embeds the
+//! explicitly listed fixture files using only `include_bytes!`.
+//!
 //! ### Using Rivia
 //! ```
 //! use rivia::prelude::*;
@@ -82,21 +103,27 @@ pub mod prelude {
 
     // Export macros by name
     pub use crate::{
-        assert_vfs_copyfile, assert_vfs_exists, assert_vfs_is_dir, assert_vfs_is_file, assert_vfs_is_symlink,
-        assert_vfs_mkdir_m, assert_vfs_mkdir_p, assert_vfs_mkfile, assert_vfs_no_dir, assert_vfs_no_exists,
-        assert_vfs_no_file, assert_vfs_no_symlink, assert_vfs_read_all, assert_vfs_readlink,
-        assert_vfs_readlink_abs, assert_vfs_remove, assert_vfs_remove_all, assert_vfs_setup, assert_vfs_symlink,
-        assert_vfs_write_all, cfgblock, defer, function, function_fqn, panic_compare_msg, panic_msg, trying,
-        unwrap_or_false,
+        assert_vfs_copyfile, assert_vfs_exists, assert_vfs_hardlink, assert_vfs_is_dir, assert_vfs_is_file,
+        assert_vfs_is_hardlink, assert_vfs_is_symlink, assert_vfs_mkdir_m, assert_vfs_mkdir_p, assert_vfs_mkfile,
+        assert_vfs_no_dir, assert_vfs_no_exists, assert_vfs_no_file, assert_vfs_no_hardlink,
+        assert_vfs_no_symlink, assert_vfs_read_all, assert_vfs_readlink, assert_vfs_readlink_abs,
+        assert_vfs_remove, assert_vfs_remove_all, assert_vfs_setup, assert_vfs_symlink, assert_vfs_write_all,
+        cfgblock, defer, function, function_fqn, memfs_seed, panic_compare_msg, panic_msg, trying, unwrap_or_false,
     };
     // Export internal types
     pub use crate::{
         core::*,
         errors::*,
         sys::{
-            self, user, Chmod, Chown, Copier, Entries, EntriesIter, Entry, Memfs, MemfsEntry, PathExt, ReadSeek,
-            Stdfs, StdfsEntry, Vfs, VfsEntry, VirtualFileSystem,
+            self, format, report, user, Acl, AclEntry, AclTag, Advice, Backend, CancelToken, Chmod, Chown, Copier,
+            CopyReport, DirHandle, Ensured, Entries, EntriesIter, EntriesStats, Entry, Installer, Kind, Manifest,
+            Memfs, MemfsEntry, MemfsEntryInfo, MemfsMeta, Mover, OpContext, OpMetrics, Overlayfs, PathExt,
+            PathRules, PathViolation, ReadSeek, ReadWriteSeek, Reader, Reflink, ResolvedLink, Stdfs, StdfsEntry,
+            StdfsMeta, TreeNode, Vfs, VfsBuilder, VfsConfig, VfsEntry, VfsEvent, VfsMetadata, VfsOp, VfsPolicy,
+            VfsStatFs, VfsWatcher, VfsWriter, VirtualFileSystem,
         },
         testing,
     };
+    #[cfg(feature = "digest")]
+    pub use crate::sys::Algo;
 }
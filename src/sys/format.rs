@@ -0,0 +1,142 @@
+//! Provides human readable formatting of file metadata
+//!
+//! * Sizes are formatted with binary units in the spirit of `ls -lh`
+//! * Modes are formatted as the permission strings of `ls -l`
+//!
+//! ### How to use the Rivia `format` module
+//! ```
+//! use rivia::prelude::*;
+//!
+//! let vfs = Vfs::memfs();
+//! assert_vfs_write_all!(vfs, "file", "foobar");
+//! let meta = vfs.entry("file").unwrap().metadata();
+//! assert_eq!(format::size(meta.size), "6 B");
+//! assert_eq!(format::mode_string(meta.mode), "-rw-r--r--");
+//! ```
+
+// Binary unit suffixes for each power of 1024
+const UNITS: [&str; 7] = ["B", "KiB", "MiB", "GiB", "TiB", "PiB", "EiB"];
+
+// File type bits of a mode
+const S_IFMT: u32 = 0o170000;
+const S_IFSOCK: u32 = 0o140000;
+const S_IFLNK: u32 = 0o120000;
+const S_IFBLK: u32 = 0o060000;
+const S_IFDIR: u32 = 0o040000;
+const S_IFCHR: u32 = 0o020000;
+const S_IFIFO: u32 = 0o010000;
+
+/// Returns the given number of bytes as a human readable size with binary units
+///
+/// * Sizes below 1 KiB are given in bytes without a fraction
+/// * Larger sizes are rounded to a single decimal place e.g. `1.5 KiB`
+///
+/// ### Examples
+/// ```
+/// use rivia::prelude::*;
+///
+/// assert_eq!(format::size(512), "512 B");
+/// assert_eq!(format::size(1536), "1.5 KiB");
+/// assert_eq!(format::size(5 * 1024 * 1024), "5.0 MiB");
+/// ```
+pub fn size(bytes: u64) -> String {
+    let mut unit = 0;
+    while unit + 1 < UNITS.len() && bytes >= 1 << (10 * (unit + 1)) {
+        unit += 1;
+    }
+    if unit == 0 {
+        return format!("{} {}", bytes, UNITS[0]);
+    }
+
+    // Rounding may carry the value over into the next unit e.g. 1023.96 KiB
+    let mut tenths = (bytes as f64 * 10.0 / (1u64 << (10 * unit)) as f64).round() as u64;
+    if tenths >= 10240 && unit + 1 < UNITS.len() {
+        unit += 1;
+        tenths = (bytes as f64 * 10.0 / (1u64 << (10 * unit)) as f64).round() as u64;
+    }
+    format!("{}.{} {}", tenths / 10, tenths % 10, UNITS[unit])
+}
+
+/// Returns the given mode as a permission string in the style of `ls -l`
+///
+/// * The first character gives the file type e.g. `d` for directories and `l` for links
+/// * Setuid, setgid and sticky bits are shown as `s` and `t` or `S` and `T` when not executable
+///
+/// ### Examples
+/// ```
+/// use rivia::prelude::*;
+///
+/// assert_eq!(format::mode_string(0o100644), "-rw-r--r--");
+/// assert_eq!(format::mode_string(0o40755), "drwxr-xr-x");
+/// assert_eq!(format::mode_string(0o41777), "drwxrwxrwt");
+/// ```
+pub fn mode_string(mode: u32) -> String {
+    let mut str = String::with_capacity(10);
+    str.push(match mode & S_IFMT {
+        S_IFSOCK => 's',
+        S_IFLNK => 'l',
+        S_IFBLK => 'b',
+        S_IFDIR => 'd',
+        S_IFCHR => 'c',
+        S_IFIFO => 'p',
+        _ => '-',
+    });
+
+    // Owner, group and other triplets with their special bit and its characters
+    for (shift, special, set, unset) in [(6, 0o4000, 's', 'S'), (3, 0o2000, 's', 'S'), (0, 0o1000, 't', 'T')] {
+        let bits = (mode >> shift) & 0o7;
+        str.push(if bits & 0o4 != 0 { 'r' } else { '-' });
+        str.push(if bits & 0o2 != 0 { 'w' } else { '-' });
+        str.push(match (bits & 0o1 != 0, mode & special != 0) {
+            (true, true) => set,
+            (false, true) => unset,
+            (true, false) => 'x',
+            (false, false) => '-',
+        });
+    }
+    str
+}
+
+// Unit tests
+// -------------------------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    #[test]
+    fn test_size() {
+        assert_eq!(format::size(0), "0 B");
+        assert_eq!(format::size(1023), "1023 B");
+        assert_eq!(format::size(1024), "1.0 KiB");
+        assert_eq!(format::size(1536), "1.5 KiB");
+        assert_eq!(format::size(1024 * 1024 - 1), "1.0 MiB");
+        assert_eq!(format::size(1024 * 1024 - 52), "1023.9 KiB");
+        assert_eq!(format::size(3 * 1024 * 1024 * 1024 / 2), "1.5 GiB");
+        assert_eq!(format::size(1 << 40), "1.0 TiB");
+        assert_eq!(format::size(1 << 50), "1.0 PiB");
+        assert_eq!(format::size(u64::MAX), "16.0 EiB");
+    }
+
+    #[test]
+    fn test_mode_string() {
+        assert_eq!(format::mode_string(0o100644), "-rw-r--r--");
+        assert_eq!(format::mode_string(0o100000), "----------");
+        assert_eq!(format::mode_string(0o40755), "drwxr-xr-x");
+        assert_eq!(format::mode_string(0o120777), "lrwxrwxrwx");
+        assert_eq!(format::mode_string(0o140755), "srwxr-xr-x");
+        assert_eq!(format::mode_string(0o60660), "brw-rw----");
+        assert_eq!(format::mode_string(0o20620), "crw--w----");
+        assert_eq!(format::mode_string(0o10644), "prw-r--r--");
+
+        // Special bits with and without execute
+        assert_eq!(format::mode_string(0o104755), "-rwsr-xr-x");
+        assert_eq!(format::mode_string(0o104644), "-rwSr--r--");
+        assert_eq!(format::mode_string(0o102755), "-rwxr-sr-x");
+        assert_eq!(format::mode_string(0o102745), "-rwxr-Sr-x");
+        assert_eq!(format::mode_string(0o41777), "drwxrwxrwt");
+        assert_eq!(format::mode_string(0o41776), "drwxrwxrwT");
+
+        // Permissions only
+        assert_eq!(format::mode_string(0o644), "-rw-r--r--");
+    }
+}
@@ -0,0 +1,486 @@
+use std::{fmt, path::Path};
+
+use crate::errors::*;
+
+// Extended attributes the kernel exposes POSIX ACLs through
+pub(crate) const XATTR_ACCESS: &str = "system.posix_acl_access";
+pub(crate) const XATTR_DEFAULT: &str = "system.posix_acl_default";
+
+// Layout of the extended attribute value i.e. a version header followed by (tag, perms, id) entries
+const XATTR_VERSION: u32 = 2;
+const XATTR_HEADER_LEN: usize = 4;
+const XATTR_ENTRY_LEN: usize = 8;
+const XATTR_UNDEFINED_ID: u32 = u32::MAX;
+
+/// Identifies who an [`AclEntry`] grants permissions to
+///
+/// Variants are ordered the same way the kernel orders entries
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum AclTag
+{
+    UserObj,    // owner of the entry
+    User(u32),  // named user by uid
+    GroupObj,   // owning group of the entry
+    Group(u32), // named group by gid
+    Mask,       // upper bound on the permissions granted to named users and all groups
+    Other,      // everyone else
+}
+
+impl AclTag
+{
+    // Returns the kernel's tag value and id for this tag
+    fn raw(&self) -> (u16, u32)
+    {
+        match *self {
+            AclTag::UserObj => (0x01, XATTR_UNDEFINED_ID),
+            AclTag::User(uid) => (0x02, uid),
+            AclTag::GroupObj => (0x04, XATTR_UNDEFINED_ID),
+            AclTag::Group(gid) => (0x08, gid),
+            AclTag::Mask => (0x10, XATTR_UNDEFINED_ID),
+            AclTag::Other => (0x20, XATTR_UNDEFINED_ID),
+        }
+    }
+
+    // Returns the tag for the given kernel tag value and id
+    fn from_raw(tag: u16, id: u32) -> Option<AclTag>
+    {
+        match tag {
+            0x01 => Some(AclTag::UserObj),
+            0x02 => Some(AclTag::User(id)),
+            0x04 => Some(AclTag::GroupObj),
+            0x08 => Some(AclTag::Group(id)),
+            0x10 => Some(AclTag::Mask),
+            0x20 => Some(AclTag::Other),
+            _ => None,
+        }
+    }
+}
+
+/// Provides a single entry of an [`Acl`] granting `perms` to the given tag
+///
+/// * `perms` are the read, write and execute bits i.e. 0o4, 0o2 and 0o1
+///
+/// ### Examples
+/// ```
+/// use rivia::prelude::*;
+///
+/// assert_eq!(AclEntry { tag: AclTag::User(1000), perms: 0o6 }.to_string(), "user:1000:rw-");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct AclEntry
+{
+    pub tag: AclTag, // who the permissions are granted to
+    pub perms: u32,  // read, write and execute bits
+}
+
+impl fmt::Display for AclEntry
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        let (kind, id) = match self.tag {
+            AclTag::UserObj => ("user", None),
+            AclTag::User(uid) => ("user", Some(uid)),
+            AclTag::GroupObj => ("group", None),
+            AclTag::Group(gid) => ("group", Some(gid)),
+            AclTag::Mask => ("mask", None),
+            AclTag::Other => ("other", None),
+        };
+        let bit = |x: u32, c: char| if self.perms & x != 0 { c } else { '-' };
+        let id = id.map(|x| x.to_string()).unwrap_or_default();
+        write!(f, "{}:{}:{}{}{}", kind, id, bit(4, 'r'), bit(2, 'w'), bit(1, 'x'))
+    }
+}
+
+/// Provides a typed model of a POSIX access control list
+///
+/// * `access` entries control access to the path itself and always include the owner, owning
+///   group and other entries which mirror the permission bits of the path's mode
+/// * `default` entries are only valid for directories and are inherited by new entries created
+///   within them, empty when the directory has no default ACL
+/// * A mask entry is added automatically when setting an ACL with named users or groups
+///
+/// ### Examples
+/// ```
+/// use rivia::prelude::*;
+///
+/// let vfs = Vfs::memfs();
+/// assert_vfs_mkfile!(vfs, "file");
+/// assert!(vfs.chmod("file", 0o640).is_ok());
+/// assert!(vfs.set_acl("file", vfs.get_acl("file").unwrap().user(5, 0o6)).is_ok());
+/// assert_eq!(vfs.get_acl("file").unwrap().to_string(), "user::rw-,user:5:rw-,group::r--,mask::rw-,other::---");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Acl
+{
+    pub access: Vec<AclEntry>,  // entries controlling access to the path
+    pub default: Vec<AclEntry>, // entries inherited by new entries in a directory
+}
+
+impl Acl
+{
+    /// Returns the minimal ACL equivalent to the permission bits of the given mode
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// assert_eq!(Acl::from_mode(0o100754).to_string(), "user::rwx,group::r-x,other::r--");
+    /// ```
+    pub fn from_mode(mode: u32) -> Self
+    {
+        Acl {
+            access: vec![
+                AclEntry { tag: AclTag::UserObj, perms: (mode >> 6) & 0o7 },
+                AclEntry { tag: AclTag::GroupObj, perms: (mode >> 3) & 0o7 },
+                AclEntry { tag: AclTag::Other, perms: mode & 0o7 },
+            ],
+            default: vec![],
+        }
+    }
+
+    /// Grant the given permissions to the given named user replacing any existing entry for them
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let acl = Acl::from_mode(0o640).user(5, 0o4);
+    /// assert_eq!(acl.perms(AclTag::User(5)), Some(0o4));
+    /// ```
+    pub fn user(self, uid: u32, perms: u32) -> Self
+    {
+        self.grant(AclTag::User(uid), perms)
+    }
+
+    /// Grant the given permissions to the given named group replacing any existing entry for it
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let acl = Acl::from_mode(0o640).group(7, 0o6);
+    /// assert_eq!(acl.perms(AclTag::Group(7)), Some(0o6));
+    /// ```
+    pub fn group(self, gid: u32, perms: u32) -> Self
+    {
+        self.grant(AclTag::Group(gid), perms)
+    }
+
+    /// Copy the access entries to the default entries so that new entries created in the
+    /// directory inherit them
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let acl = Acl::from_mode(0o750).group(7, 0o5).inherit();
+    /// assert_eq!(acl.default, acl.access);
+    /// ```
+    pub fn inherit(mut self) -> Self
+    {
+        self.default = self.access.clone();
+        self
+    }
+
+    /// Returns true if the ACL is fully represented by the permission bits of a mode
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// assert_eq!(Acl::from_mode(0o640).is_minimal(), true);
+    /// assert_eq!(Acl::from_mode(0o640).user(5, 0o4).is_minimal(), false);
+    /// ```
+    pub fn is_minimal(&self) -> bool
+    {
+        self.default.is_empty() && !self.is_extended()
+    }
+
+    /// Returns the permission bits of the mode implied by the access entries
+    ///
+    /// * The group bits reflect the mask entry when there is one just like the kernel does
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// assert_eq!(Acl::from_mode(0o640).mode(), 0o640);
+    /// assert_eq!(Acl::from_mode(0o600).user(5, 0o6).mode(), 0o600);
+    /// ```
+    pub fn mode(&self) -> u32
+    {
+        let user = self.perms(AclTag::UserObj).unwrap_or(0);
+        let group = self.perms(AclTag::Mask).or_else(|| self.perms(AclTag::GroupObj)).unwrap_or(0);
+        (user << 6) | (group << 3) | self.perms(AclTag::Other).unwrap_or(0)
+    }
+
+    /// Returns the permissions the access entries grant to the given tag if it has an entry
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// assert_eq!(Acl::from_mode(0o640).perms(AclTag::GroupObj), Some(0o4));
+    /// assert_eq!(Acl::from_mode(0o640).perms(AclTag::Mask), None);
+    /// ```
+    pub fn perms(&self, tag: AclTag) -> Option<u32>
+    {
+        self.access.iter().find(|x| x.tag == tag).map(|x| x.perms)
+    }
+
+    // Add or replace the access entry for the given tag
+    fn grant(mut self, tag: AclTag, perms: u32) -> Self
+    {
+        match self.access.iter_mut().find(|x| x.tag == tag) {
+            Some(entry) => entry.perms = perms,
+            None => self.access.push(AclEntry { tag, perms }),
+        }
+        self
+    }
+
+    /// Returns true if the access entries include more than the entries mirroring the mode
+    pub(crate) fn is_extended(&self) -> bool
+    {
+        self.access.iter().any(|x| !matches!(x.tag, AclTag::UserObj | AclTag::GroupObj | AclTag::Other))
+    }
+
+    /// Update the access entries that mirror the permission bits to match the given mode
+    pub(crate) fn with_mode(mut self, mode: u32) -> Self
+    {
+        let masked = self.perms(AclTag::Mask).is_some();
+        for entry in self.access.iter_mut() {
+            match entry.tag {
+                AclTag::UserObj => entry.perms = (mode >> 6) & 0o7,
+                AclTag::GroupObj if !masked => entry.perms = (mode >> 3) & 0o7,
+                AclTag::Mask => entry.perms = (mode >> 3) & 0o7,
+                AclTag::Other => entry.perms = mode & 0o7,
+                _ => {},
+            }
+        }
+        self
+    }
+
+    /// Validate the ACL sorting its entries and adding any missing masks
+    ///
+    /// ### Errors
+    /// * VfsError::InvalidAcl(PathBuf) when the entries are malformed
+    pub(crate) fn normalize(mut self, path: &Path) -> RvResult<Self>
+    {
+        self.access = normalize(self.access, path)?;
+        if !self.default.is_empty() {
+            self.default = normalize(self.default, path)?;
+        }
+        Ok(self)
+    }
+
+    /// Encode the given entries as the kernel's extended attribute value
+    pub(crate) fn to_xattr(entries: &[AclEntry]) -> Vec<u8>
+    {
+        let mut value = Vec::with_capacity(XATTR_HEADER_LEN + entries.len() * XATTR_ENTRY_LEN);
+        value.extend_from_slice(&XATTR_VERSION.to_le_bytes());
+        for entry in entries {
+            let (tag, id) = entry.tag.raw();
+            value.extend_from_slice(&tag.to_le_bytes());
+            value.extend_from_slice(&(entry.perms as u16).to_le_bytes());
+            value.extend_from_slice(&id.to_le_bytes());
+        }
+        value
+    }
+
+    /// Decode the given kernel extended attribute value returning None if it is malformed
+    pub(crate) fn from_xattr(value: &[u8]) -> Option<Vec<AclEntry>>
+    {
+        let (header, body) = value.split_at_checked(XATTR_HEADER_LEN)?;
+        if u32::from_le_bytes(header.try_into().ok()?) != XATTR_VERSION || body.len() % XATTR_ENTRY_LEN != 0 {
+            return None;
+        }
+        body.chunks(XATTR_ENTRY_LEN)
+            .map(|x| {
+                let tag = u16::from_le_bytes([x[0], x[1]]);
+                let perms = u16::from_le_bytes([x[2], x[3]]) as u32;
+                let id = u32::from_le_bytes([x[4], x[5], x[6], x[7]]);
+                Some(AclEntry { tag: AclTag::from_raw(tag, id)?, perms })
+            })
+            .collect()
+    }
+}
+
+impl fmt::Display for Acl
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        let access = self.access.iter().map(|x| x.to_string());
+        let default = self.default.iter().map(|x| format!("default:{}", x));
+        write!(f, "{}", access.chain(default).collect::<Vec<_>>().join(","))
+    }
+}
+
+// Sort the given entries, check that they form a valid ACL and add a mask if one is required
+fn normalize(mut entries: Vec<AclEntry>, path: &Path) -> RvResult<Vec<AclEntry>>
+{
+    entries.sort();
+    let duplicates = entries.windows(2).any(|x| x[0].tag == x[1].tag);
+    let has = |tag: AclTag| entries.iter().any(|x| x.tag == tag);
+    if duplicates
+        || entries.iter().any(|x| x.perms > 0o7)
+        || !has(AclTag::UserObj)
+        || !has(AclTag::GroupObj)
+        || !has(AclTag::Other)
+    {
+        return Err(VfsError::InvalidAcl(path.to_path_buf()).into());
+    }
+
+    // Named entries require a mask which defaults to the union of the permissions it bounds
+    let named = entries.iter().any(|x| matches!(x.tag, AclTag::User(_) | AclTag::Group(_)));
+    if named && !has(AclTag::Mask) {
+        let perms = entries
+            .iter()
+            .filter(|x| matches!(x.tag, AclTag::User(_) | AclTag::GroupObj | AclTag::Group(_)))
+            .fold(0, |acc, x| acc | x.perms);
+        entries.push(AclEntry { tag: AclTag::Mask, perms });
+        entries.sort();
+    }
+    Ok(entries)
+}
+
+// Unit tests
+// -------------------------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests
+{
+    use crate::prelude::*;
+
+    #[test]
+    fn test_vfs_acl()
+    {
+        test_acl(assert_vfs_setup!(Vfs::memfs()));
+        test_acl(assert_vfs_setup!(Vfs::stdfs()));
+    }
+    fn test_acl((vfs, tmpdir): (Vfs, PathBuf))
+    {
+        let dir = tmpdir.mash("dir");
+        let file = dir.mash("file");
+        let link = tmpdir.mash("link");
+        assert!(vfs.mkdir_m(&dir, 0o750).is_ok());
+        assert!(vfs.mkfile_m(&file, 0o640).is_ok());
+        assert_vfs_symlink!(vfs, &link, &file);
+
+        // Doesn't exist
+        let missing = tmpdir.mash("missing");
+        assert_eq!(
+            vfs.get_acl(&missing).unwrap_err().downcast_ref::<PathError>(),
+            Some(&PathError::does_not_exist(&missing))
+        );
+        assert_eq!(
+            vfs.set_acl(&missing, Acl::from_mode(0o640)).unwrap_err().downcast_ref::<PathError>(),
+            Some(&PathError::does_not_exist(&missing))
+        );
+
+        // Minimal ACLs mirror the mode
+        assert_eq!(vfs.get_acl(&file).unwrap(), Acl::from_mode(0o640));
+        assert_eq!(vfs.get_acl(&dir).unwrap(), Acl::from_mode(0o750));
+        assert!(vfs.set_acl(&file, Acl::from_mode(0o600)).is_ok());
+        assert_eq!(vfs.mode(&file).unwrap(), 0o100600);
+
+        // Extended ACLs survive and drive the group bits through the mask
+        assert!(vfs.set_acl(&file, Acl::from_mode(0o640).user(5, 0o6).group(7, 0o4)).is_ok());
+        let acl = vfs.get_acl(&file).unwrap();
+        assert_eq!(acl.to_string(), "user::rw-,user:5:rw-,group::r--,group:7:r--,mask::rw-,other::---");
+        assert_eq!(vfs.mode(&file).unwrap(), 0o100660);
+
+        // Changing the mode updates the mask rather than dropping the named entries
+        assert!(vfs.chmod(&file, 0o640).is_ok());
+        let acl = vfs.get_acl(&file).unwrap();
+        assert_eq!(acl.perms(AclTag::User(5)), Some(0o6));
+        assert_eq!(acl.perms(AclTag::Mask), Some(0o4));
+
+        // Links are followed
+        assert_eq!(vfs.get_acl(&link).unwrap(), acl);
+        assert!(vfs.set_acl(&link, Acl::from_mode(0o644)).is_ok());
+        assert_eq!(vfs.get_acl(&file).unwrap(), Acl::from_mode(0o644));
+        assert_eq!(vfs.mode(&file).unwrap(), 0o100644);
+
+        // Default entries are only valid for directories
+        let acl = Acl::from_mode(0o750).group(7, 0o5).inherit();
+        assert_eq!(
+            vfs.set_acl(&file, acl.clone()).unwrap_err().downcast_ref::<PathError>(),
+            Some(&PathError::is_not_dir(&file))
+        );
+        assert!(vfs.set_acl(&dir, acl).is_ok());
+        let acl = vfs.get_acl(&dir).unwrap();
+        assert_eq!(acl.default, acl.access);
+        assert_eq!(acl.perms(AclTag::Group(7)), Some(0o5));
+        assert!(vfs.set_acl(&dir, Acl::from_mode(0o755)).is_ok());
+        assert_eq!(vfs.get_acl(&dir).unwrap(), Acl::from_mode(0o755));
+
+        // Malformed
+        let mut acl = Acl::from_mode(0o640);
+        acl.access.clear();
+        assert_eq!(
+            vfs.set_acl(&file, acl).unwrap_err().downcast_ref::<VfsError>(),
+            Some(&VfsError::InvalidAcl(file.clone()))
+        );
+
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+
+    #[test]
+    fn test_xattr()
+    {
+        let acl = Acl::from_mode(0o640).user(5, 0o6).group(7, 0o4).normalize(Path::new("/foo")).unwrap();
+        let value = Acl::to_xattr(&acl.access);
+        assert_eq!(value.len(), 4 + 6 * 8);
+        assert_eq!(&value[..12], &[2, 0, 0, 0, 1, 0, 6, 0, 0xff, 0xff, 0xff, 0xff]);
+        assert_eq!(&value[12..20], &[2, 0, 6, 0, 5, 0, 0, 0]);
+        assert_eq!(Acl::from_xattr(&value), Some(acl.access));
+
+        // Malformed values
+        assert_eq!(Acl::from_xattr(&[]), None);
+        assert_eq!(Acl::from_xattr(&[1, 0, 0, 0]), None);
+        assert_eq!(Acl::from_xattr(&value[..value.len() - 1]), None);
+        assert_eq!(Acl::from_xattr(&[2, 0, 0, 0, 0x40, 0, 0, 0, 0, 0, 0, 0]), None);
+    }
+
+    #[test]
+    fn test_normalize()
+    {
+        let path = Path::new("/foo");
+
+        // Entries are sorted and a mask is added for named entries
+        let acl = Acl::from_mode(0o640).group(7, 0o1).user(5, 0o2).normalize(path).unwrap();
+        assert_eq!(acl.to_string(), "user::rw-,user:5:-w-,group::r--,group:7:--x,mask::rwx,other::---");
+        assert_eq!(acl.mode(), 0o670);
+
+        // Existing masks are left alone
+        let mut acl = Acl::from_mode(0o640).user(5, 0o6);
+        acl.access.push(AclEntry { tag: AclTag::Mask, perms: 0o4 });
+        assert_eq!(acl.normalize(path).unwrap().perms(AclTag::Mask), Some(0o4));
+
+        // Minimal ACLs don't get a mask
+        assert_eq!(Acl::from_mode(0o640).normalize(path).unwrap(), Acl::from_mode(0o640));
+
+        // Malformed entries
+        let err = Some(&VfsError::InvalidAcl(path.to_path_buf()));
+        let mut acl = Acl::from_mode(0o640);
+        acl.access.push(AclEntry { tag: AclTag::Other, perms: 0o4 });
+        assert_eq!(acl.normalize(path).unwrap_err().downcast_ref::<VfsError>(), err);
+        let mut acl = Acl::from_mode(0o640);
+        acl.access.remove(0);
+        assert_eq!(acl.normalize(path).unwrap_err().downcast_ref::<VfsError>(), err);
+        let acl = Acl::from_mode(0o640).user(5, 0o10);
+        assert_eq!(acl.normalize(path).unwrap_err().downcast_ref::<VfsError>(), err);
+        let mut acl = Acl::from_mode(0o750);
+        acl.default.push(AclEntry { tag: AclTag::UserObj, perms: 0o7 });
+        assert_eq!(acl.normalize(path).unwrap_err().downcast_ref::<VfsError>(), err);
+    }
+
+    #[test]
+    fn test_with_mode()
+    {
+        assert_eq!(Acl::from_mode(0o640).with_mode(0o751), Acl::from_mode(0o751));
+
+        // The mask rather than the owning group tracks the group bits
+        let acl = Acl::from_mode(0o640).user(5, 0o6).normalize(Path::new("/foo")).unwrap().with_mode(0o701);
+        assert_eq!(acl.to_string(), "user::rwx,user:5:rw-,group::r--,mask::---,other::--x");
+    }
+}
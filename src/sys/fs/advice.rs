@@ -0,0 +1,58 @@
+use nix::fcntl::PosixFadviseAdvice;
+
+/// Hints to the kernel about how file data will be accessed
+///
+/// Used with `advise` to manage the page cache of a file and with `Reader::advise` to tune the
+/// readahead of a file handle. Memfs has no page cache so hints are accepted but ignored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Advice
+{
+    /// No special treatment, the default
+    Normal,
+
+    /// Data will be read sequentially so read ahead aggressively
+    Sequential,
+
+    /// Data will be read in random order so don't read ahead
+    Random,
+
+    /// Data will be needed soon so start loading it into the page cache
+    WillNeed,
+
+    /// Data won't be needed again so drop it from the page cache
+    DontNeed,
+}
+
+impl From<Advice> for PosixFadviseAdvice
+{
+    fn from(advice: Advice) -> Self
+    {
+        match advice {
+            Advice::Normal => PosixFadviseAdvice::POSIX_FADV_NORMAL,
+            Advice::Sequential => PosixFadviseAdvice::POSIX_FADV_SEQUENTIAL,
+            Advice::Random => PosixFadviseAdvice::POSIX_FADV_RANDOM,
+            Advice::WillNeed => PosixFadviseAdvice::POSIX_FADV_WILLNEED,
+            Advice::DontNeed => PosixFadviseAdvice::POSIX_FADV_DONTNEED,
+        }
+    }
+}
+
+// Unit tests
+// -------------------------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests
+{
+    use nix::fcntl::PosixFadviseAdvice;
+
+    use crate::prelude::*;
+
+    #[test]
+    fn test_advice()
+    {
+        assert_eq!(PosixFadviseAdvice::from(Advice::Normal), PosixFadviseAdvice::POSIX_FADV_NORMAL);
+        assert_eq!(PosixFadviseAdvice::from(Advice::Sequential), PosixFadviseAdvice::POSIX_FADV_SEQUENTIAL);
+        assert_eq!(PosixFadviseAdvice::from(Advice::Random), PosixFadviseAdvice::POSIX_FADV_RANDOM);
+        assert_eq!(PosixFadviseAdvice::from(Advice::WillNeed), PosixFadviseAdvice::POSIX_FADV_WILLNEED);
+        assert_eq!(PosixFadviseAdvice::from(Advice::DontNeed), PosixFadviseAdvice::POSIX_FADV_DONTNEED);
+    }
+}
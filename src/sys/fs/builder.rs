@@ -0,0 +1,498 @@
+use std::{
+    env,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    errors::*,
+    sys::{Memfs, Overlayfs, Stdfs, Vfs, VfsConfig, VfsPolicy, VirtualFileSystem},
+};
+
+// Settings that may be given via the environment or a config file
+const SETTINGS: [&str; 5] = ["backend", "cwd", "history", "readonly", "umask"];
+
+/// Provides a classification of the Vfs backend implementations
+///
+/// ### Examples
+/// ```
+/// use rivia::prelude::*;
+///
+/// let vfs = Vfs::builder().backend(Backend::Memfs).build().unwrap();
+/// assert!(matches!(vfs, Vfs::Memfs(_)));
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Backend
+{
+    #[default]
+    Stdfs, // the host filesystem
+    Memfs,     // an in memory filesystem
+    Overlayfs, // an in memory filesystem layered over the host filesystem
+}
+
+/// Provides a builder pattern for constructing a Vfs along with its per instance options
+///
+/// Use `Vfs::builder` to create a new builder. Options are applied in the following order such
+/// that the directories the backend needs exist before any restrictions take effect.
+///
+/// 1. The backend is created with the config and umask
+/// 2. Memfs quota directories are created along with their quotas and the history is enabled
+/// 3. The cwd is set, Memfs and Overlayfs create it if needed
+/// 4. The policy is attached followed by the readonly restriction
+///
+/// * `umask`, `quota` and `history` are simulated by Memfs only and fail the build on Stdfs with
+///   `VfsError::WrongProvider`, Overlayfs applies the `umask` to its upper layer
+/// * Stdfs sets the cwd of the process as a whole
+///
+/// ### Examples
+/// ```
+/// use rivia::prelude::*;
+///
+/// let vfs = Vfs::builder().backend(Backend::Memfs).cwd("/work").umask(0o027).build().unwrap();
+/// assert_eq!(vfs.cwd().unwrap(), PathBuf::from("/work"));
+/// assert_vfs_mkfile!(vfs, "file");
+/// assert_eq!(vfs.mode("/work/file").unwrap(), 0o100640);
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct VfsBuilder
+{
+    backend: Backend,                 // backend implementation to create
+    config: VfsConfig,                // tunable defaults of the backend
+    cwd: Option<PathBuf>,             // current working directory
+    history: Option<usize>,           // Memfs versions kept per file
+    policy: Option<VfsPolicy>,        // policy confining the operations
+    quotas: Vec<(PathBuf, u64, u64)>, // Memfs directory byte and inode limits
+    readonly: bool,                   // deny all mutating operations
+    umask: Option<u32>,               // Memfs permission bits removed from new entries
+}
+
+impl VfsBuilder
+{
+    /// Set the backend implementation to create
+    ///
+    /// * Default is `Backend::Stdfs`
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::builder().backend(Backend::Memfs).build().unwrap();
+    /// assert!(matches!(vfs, Vfs::Memfs(_)));
+    /// ```
+    pub fn backend(mut self, backend: Backend) -> Self
+    {
+        self.backend = backend;
+        self
+    }
+
+    /// Set the tunable defaults of the backend
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let config = VfsConfig { max_descriptors: 10, ..Default::default() };
+    /// let vfs = Vfs::builder().backend(Backend::Memfs).config(config).build().unwrap();
+    /// assert_eq!(vfs.config(), config);
+    /// ```
+    pub fn config(mut self, config: VfsConfig) -> Self
+    {
+        self.config = config;
+        self
+    }
+
+    /// Set the current working directory
+    ///
+    /// * Memfs creates the directory if needed
+    /// * Stdfs sets the cwd of the process as a whole
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::builder().backend(Backend::Memfs).cwd("/work").build().unwrap();
+    /// assert_eq!(vfs.cwd().unwrap(), PathBuf::from("/work"));
+    /// ```
+    pub fn cwd<T: AsRef<Path>>(mut self, path: T) -> Self
+    {
+        self.cwd = Some(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Create a new builder with the backend and options given by the environment
+    ///
+    /// Allows integration tests and sandboxed CI runs to swap backends without code changes and is
+    /// intended as the basis for initializing the global Vfs of the `rivia-vfs` crate.
+    ///
+    /// * `RIVIA_VFS_CONFIG` names a config file of `key = value` lines with `#` comments
+    /// * `RIVIA_VFS` sets the `backend` to one of `memfs`, `overlayfs` or `stdfs`
+    /// * `RIVIA_VFS_CWD`, `RIVIA_VFS_HISTORY`, `RIVIA_VFS_READONLY` and `RIVIA_VFS_UMASK` set the
+    ///   `cwd`, `history`, `readonly` and `umask` respectively with the umask given in octal
+    /// * Environment variables override the config file and empty variables are ignored
+    ///
+    /// ### Errors
+    /// * VfsError::InvalidSetting(String, String) when a setting is unknown or its value is invalid
+    /// * PathError::DoesNotExist(PathBuf) when the config file doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// std::env::set_var("RIVIA_VFS", "memfs");
+    /// std::env::set_var("RIVIA_VFS_CWD", "/work");
+    /// let vfs = VfsBuilder::from_env().unwrap().build().unwrap();
+    /// assert!(matches!(vfs, Vfs::Memfs(_)));
+    /// assert_eq!(vfs.cwd().unwrap(), PathBuf::from("/work"));
+    /// ```
+    pub fn from_env() -> RvResult<Self>
+    {
+        let var = |x: &str| env::var(x).ok().filter(|x| !x.is_empty());
+        let mut builder = VfsBuilder::default();
+        if let Some(path) = var("RIVIA_VFS_CONFIG") {
+            for line in Stdfs::read_lines(path)? {
+                let line = line.trim();
+                if line.is_empty() || line.starts_with('#') {
+                    continue;
+                }
+                let (key, value) = line.split_once('=').unwrap_or((line, ""));
+                builder = builder._set(key.trim(), value.trim())?;
+            }
+        }
+        for key in SETTINGS {
+            let name = match key {
+                "backend" => "RIVIA_VFS".to_string(),
+                _ => format!("RIVIA_VFS_{}", key.to_uppercase()),
+            };
+            if let Some(value) = var(&name) {
+                builder = builder._set(key, &value)?;
+            }
+        }
+        Ok(builder)
+    }
+
+    /// Set the number of prior versions Memfs keeps per file
+    ///
+    /// * Memfs only, see `Memfs::set_history`
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::builder().backend(Backend::Memfs).history(2).build().unwrap();
+    /// assert_vfs_write_all!(vfs, "file", "foo");
+    /// assert_vfs_write_all!(vfs, "file", "bar");
+    /// if let Vfs::Memfs(memfs) = &vfs {
+    ///     assert_eq!(memfs.read_at_version("file", 1).unwrap(), "foo");
+    /// }
+    /// ```
+    pub fn history(mut self, max: usize) -> Self
+    {
+        self.history = Some(max);
+        self
+    }
+
+    /// Set the policy confining the operations the Vfs may perform
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let policy = VfsPolicy::allow_by_default().deny_all("/etc/**");
+    /// let vfs = Vfs::builder().backend(Backend::Memfs).policy(policy).build().unwrap();
+    /// assert_vfs_mkdir_p!(vfs, "/etc");
+    /// assert!(vfs.mkfile("/etc/hosts").is_err());
+    /// ```
+    pub fn policy(mut self, policy: VfsPolicy) -> Self
+    {
+        self.policy = Some(policy);
+        self
+    }
+
+    /// Add a quota limiting the bytes and entries beneath the given directory
+    ///
+    /// * Memfs only, see `Memfs::set_quota`
+    /// * The directory is created if needed
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::builder().backend(Backend::Memfs).quota("/tenant", 3, 10).build().unwrap();
+    /// assert!(vfs.write_all("/tenant/file", "foobar").is_err());
+    /// ```
+    pub fn quota<T: AsRef<Path>>(mut self, path: T, bytes: u64, inodes: u64) -> Self
+    {
+        self.quotas.push((path.as_ref().to_path_buf(), bytes, inodes));
+        self
+    }
+
+    /// Deny all mutating operations once the Vfs is built
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::builder().backend(Backend::Memfs).cwd("/work").readonly(true).build().unwrap();
+    /// assert_vfs_exists!(vfs, "/work");
+    /// assert!(vfs.mkfile("file").is_err());
+    /// ```
+    pub fn readonly(mut self, yes: bool) -> Self
+    {
+        self.readonly = yes;
+        self
+    }
+
+    /// Set the permission bits removed from the modes of new Memfs entries
+    ///
+    /// * Memfs only, overrides the modes of the config
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::builder().backend(Backend::Memfs).umask(0o077).build().unwrap();
+    /// assert_vfs_mkdir_p!(vfs, "dir");
+    /// assert_eq!(vfs.mode("dir").unwrap(), 0o40700);
+    /// ```
+    pub fn umask(mut self, umask: u32) -> Self
+    {
+        self.umask = Some(umask);
+        self
+    }
+
+    /// Build the Vfs applying the options
+    ///
+    /// ### Errors
+    /// * VfsError::WrongProvider when Memfs only options are given for Stdfs or Overlayfs
+    /// * PathError::DoesNotExist(PathBuf) when the Stdfs cwd doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// assert!(Vfs::builder().backend(Backend::Memfs).umask(0o022).build().is_ok());
+    /// assert_eq!(
+    ///     Vfs::builder().umask(0o022).build().unwrap_err().downcast_ref::<VfsError>(),
+    ///     Some(&VfsError::WrongProvider)
+    /// );
+    /// ```
+    pub fn build(self) -> RvResult<Vfs>
+    {
+        let mut config = self.config;
+        if let Some(umask) = self.umask {
+            config.dir_mode = 0o777 & !umask;
+            config.file_mode = 0o666 & !umask;
+        }
+
+        let vfs = match self.backend {
+            Backend::Stdfs => {
+                if self.umask.is_some() || self.history.is_some() || !self.quotas.is_empty() {
+                    return Err(VfsError::WrongProvider.into());
+                }
+                Vfs::Stdfs(Stdfs::with_config(config))
+            },
+            Backend::Memfs => {
+                let memfs = Memfs::with_config(config);
+                for (path, bytes, inodes) in &self.quotas {
+                    memfs.mkdir_p(path)?;
+                    memfs.set_quota(path, *bytes, *inodes)?;
+                }
+                if let Some(max) = self.history {
+                    memfs.set_history(max);
+                }
+                if let Some(path) = &self.cwd {
+                    memfs.mkdir_p(path)?;
+                }
+                Vfs::Memfs(memfs)
+            },
+            Backend::Overlayfs => {
+                if self.history.is_some() || !self.quotas.is_empty() {
+                    return Err(VfsError::WrongProvider.into());
+                }
+                let overlayfs = Overlayfs::new(Vfs::Stdfs(Stdfs::with_config(config)));
+                if let Some(path) = &self.cwd {
+                    overlayfs.mkdir_p(path)?;
+                }
+                Vfs::Overlayfs(overlayfs)
+            },
+        };
+
+        if let Some(path) = &self.cwd {
+            vfs.set_cwd(path)?;
+        }
+        if let Some(policy) = self.policy {
+            policy.attach(&vfs);
+        }
+        match self.readonly {
+            true => Ok(Vfs::readonly(vfs)),
+            false => Ok(vfs),
+        }
+    }
+
+    // Apply the given setting parsed from its string form
+    fn _set(mut self, key: &str, value: &str) -> RvResult<Self>
+    {
+        let invalid = || VfsError::InvalidSetting(key.to_string(), value.to_string());
+        match key {
+            "backend" => {
+                self.backend = match value.to_lowercase().as_str() {
+                    "memfs" => Backend::Memfs,
+                    "overlayfs" => Backend::Overlayfs,
+                    "stdfs" => Backend::Stdfs,
+                    _ => return Err(invalid().into()),
+                }
+            },
+            "cwd" => self.cwd = Some(PathBuf::from(value)),
+            "history" => self.history = Some(value.parse().map_err(|_| invalid())?),
+            "readonly" => {
+                self.readonly = match value.to_lowercase().as_str() {
+                    "1" | "true" | "yes" => true,
+                    "0" | "false" | "no" => false,
+                    _ => return Err(invalid().into()),
+                }
+            },
+            "umask" => self.umask = Some(u32::from_str_radix(value, 8).map_err(|_| invalid())?),
+            _ => return Err(invalid().into()),
+        }
+        Ok(self)
+    }
+}
+
+// Unit tests
+// -------------------------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests
+{
+    use crate::prelude::*;
+
+    #[test]
+    fn test_builder_from_env()
+    {
+        let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs());
+        let config = tmpdir.mash("rivia.conf");
+        let vars = ["RIVIA_VFS", "RIVIA_VFS_CONFIG", "RIVIA_VFS_CWD", "RIVIA_VFS_READONLY", "RIVIA_VFS_UMASK"];
+        let err = || VfsBuilder::from_env().unwrap_err().downcast_ref::<VfsError>().cloned();
+
+        // Config file with environment overrides
+        assert_vfs_write_all!(vfs, &config, "# comment\n\nbackend = memfs\ncwd = /work\numask = 077\n");
+        std::env::set_var("RIVIA_VFS_CONFIG", &config);
+        std::env::set_var("RIVIA_VFS_CWD", "/other");
+        std::env::set_var("RIVIA_VFS_READONLY", "");
+        let memfs = VfsBuilder::from_env().unwrap().build().unwrap();
+        assert!(matches!(memfs, Vfs::Memfs(_)));
+        assert_eq!(memfs.cwd().unwrap(), PathBuf::from("/other"));
+        assert_vfs_mkdir_p!(memfs, "dir");
+        assert_eq!(memfs.mode("dir").unwrap(), 0o40700);
+
+        // Readonly
+        std::env::set_var("RIVIA_VFS_READONLY", "true");
+        let memfs = Vfs::from_env().unwrap();
+        assert!(memfs.mkfile("file").is_err());
+
+        // Invalid settings
+        std::env::set_var("RIVIA_VFS_UMASK", "9");
+        assert_eq!(err(), Some(VfsError::InvalidSetting("umask".to_string(), "9".to_string())));
+        std::env::set_var("RIVIA_VFS", "foo");
+        assert_eq!(err(), Some(VfsError::InvalidSetting("backend".to_string(), "foo".to_string())));
+        assert!(vfs.write_all(&config, "foo = bar").is_ok());
+        assert_eq!(err(), Some(VfsError::InvalidSetting("foo".to_string(), "bar".to_string())));
+
+        // Stdfs by default
+        for var in vars {
+            std::env::remove_var(var);
+        }
+        assert!(matches!(Vfs::from_env().unwrap(), Vfs::Stdfs(_)));
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+
+    #[test]
+    fn test_builder_memfs()
+    {
+        let vfs = Vfs::builder()
+            .backend(Backend::Memfs)
+            .cwd("/work")
+            .umask(0o027)
+            .quota("/work/tenant", 3, 10)
+            .history(1)
+            .policy(VfsPolicy::allow_by_default().deny_all("/etc/**"))
+            .build()
+            .unwrap();
+        assert!(matches!(vfs, Vfs::Memfs(_)));
+        assert_eq!(vfs.cwd().unwrap(), PathBuf::from("/work"));
+
+        // Modes follow the umask
+        assert_vfs_mkdir_p!(vfs, "dir");
+        assert_vfs_write_all!(vfs, "file", "foo");
+        assert_eq!(vfs.mode("/work/dir").unwrap(), 0o40750);
+        assert_eq!(vfs.mode("/work/file").unwrap(), 0o100640);
+        assert_eq!(vfs.mode("/work/tenant").unwrap(), 0o40750);
+
+        // Quotas, history and policy
+        assert!(vfs.write_all("tenant/file", "foobar").is_err());
+        assert_vfs_write_all!(vfs, "file", "bar");
+        if let Vfs::Memfs(memfs) = &vfs {
+            assert_eq!(memfs.versions("file").unwrap(), vec![1]);
+        }
+        assert!(vfs.mkdir_p("/etc/foo").is_err());
+
+        // Readonly
+        let vfs = Vfs::builder().backend(Backend::Memfs).cwd("/work").readonly(true).build().unwrap();
+        assert_vfs_exists!(vfs, "/work");
+        assert!(vfs.mkfile("file").is_err());
+        assert_eq!(
+            vfs.mkdir_p("/foo").unwrap_err().downcast_ref::<VfsError>(),
+            Some(&VfsError::ReadOnly(VfsOp::Create, PathBuf::from("/foo")))
+        );
+    }
+
+    #[test]
+    fn test_builder_overlayfs()
+    {
+        let (stdfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs());
+        let file1 = tmpdir.mash("file1");
+        let file2 = tmpdir.mash("work/file2");
+        assert_vfs_write_all!(stdfs, &file1, "foo");
+
+        let builder = Vfs::builder().backend(Backend::Overlayfs).cwd(tmpdir.mash("work")).umask(0o027);
+        let vfs = builder.build().unwrap();
+        assert!(matches!(vfs, Vfs::Overlayfs(_)));
+        assert_eq!(vfs.cwd().unwrap(), tmpdir.mash("work"));
+
+        // Host files are seen but changes stay in memory
+        assert_vfs_read_all!(vfs, &file1, "foo");
+        assert_vfs_write_all!(vfs, &file1, "bar");
+        assert_vfs_write_all!(vfs, "file2", "foobar");
+        assert_eq!(vfs.mode(&file2).unwrap(), 0o100640);
+        assert_vfs_read_all!(stdfs, &file1, "foo");
+        assert_vfs_no_exists!(stdfs, tmpdir.mash("work"));
+
+        // Memfs only options
+        let err = |x: VfsBuilder| x.build().unwrap_err().downcast_ref::<VfsError>().cloned();
+        let builder = || Vfs::builder().backend(Backend::Overlayfs);
+        assert_eq!(err(builder().history(1)), Some(VfsError::WrongProvider));
+        assert_eq!(err(builder().quota("/foo", 1, 1)), Some(VfsError::WrongProvider));
+
+        assert_vfs_remove_all!(stdfs, &tmpdir);
+    }
+
+    #[test]
+    fn test_builder_stdfs()
+    {
+        let config = VfsConfig { max_descriptors: 10, ..Default::default() };
+        let vfs = Vfs::builder().config(config).build().unwrap();
+        assert!(matches!(vfs, Vfs::Stdfs(_)));
+        assert_eq!(vfs.config(), config);
+
+        // Memfs only options
+        let err = |x: VfsBuilder| x.build().unwrap_err().downcast_ref::<VfsError>().cloned();
+        assert_eq!(err(Vfs::builder().umask(0o022)), Some(VfsError::WrongProvider));
+        assert_eq!(err(Vfs::builder().history(1)), Some(VfsError::WrongProvider));
+        assert_eq!(err(Vfs::builder().quota("/foo", 1, 1)), Some(VfsError::WrongProvider));
+
+        // Readonly
+        let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs());
+        let readonly = Vfs::builder().readonly(true).build().unwrap();
+        assert!(readonly.mkfile(tmpdir.mash("file")).is_err());
+        assert_vfs_no_exists!(vfs, tmpdir.mash("file"));
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+}
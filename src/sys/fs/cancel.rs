@@ -0,0 +1,108 @@
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+use crate::errors::{RvResult, VfsError};
+
+/// Provides a cheaply cloneable token for cancelling long running operations
+///
+/// * Clones share the same state so cancelling any one of them cancels them all
+/// * Operations given a token check it between entries and fail with `VfsError::Cancelled`
+/// * Once cancelled a token stays cancelled
+///
+/// ### Examples
+/// ```
+/// use rivia::prelude::*;
+///
+/// let token = CancelToken::new();
+/// let clone = token.clone();
+/// assert_eq!(token.is_cancelled(), false);
+/// clone.cancel();
+/// assert_eq!(token.is_cancelled(), true);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    /// Create a new token that hasn't been cancelled
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let token = CancelToken::new();
+    /// assert_eq!(token.is_cancelled(), false);
+    /// ```
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Signal all operations using this token to stop
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let token = CancelToken::new();
+    /// token.cancel();
+    /// assert_eq!(token.is_cancelled(), true);
+    /// ```
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    /// Returns true if the token has been cancelled
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let token = CancelToken::new();
+    /// assert_eq!(token.is_cancelled(), false);
+    /// ```
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+
+    // Returns a `VfsError::Cancelled` if the token has been cancelled
+    pub(crate) fn check(&self) -> RvResult<()> {
+        match self.is_cancelled() {
+            true => Err(VfsError::Cancelled.into()),
+            false => Ok(()),
+        }
+    }
+}
+
+// Tokens are only equal when they share the same state
+impl PartialEq for CancelToken {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+impl Eq for CancelToken {}
+
+// Unit tests
+// -------------------------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    #[test]
+    fn test_cancel_token() {
+        let token = CancelToken::new();
+        let clone = token.clone();
+        assert_eq!(token, clone);
+        assert_ne!(token, CancelToken::new());
+        assert!(token.check().is_ok());
+
+        // Cancelling a clone cancels the original
+        clone.cancel();
+        assert!(token.is_cancelled());
+        assert_eq!(token.check().unwrap_err().downcast_ref::<VfsError>(), Some(&VfsError::Cancelled));
+
+        // Cancelling again is a no-op
+        token.cancel();
+        assert!(clone.is_cancelled());
+    }
+}
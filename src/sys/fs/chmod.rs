@@ -1,8 +1,10 @@
 use std::path::PathBuf;
 
+use nix::sys::stat;
+
 use crate::{
     errors::{RvResult, VfsError},
-    sys::{Entry, VfsEntry},
+    sys::{self, Entry, VfsEntry},
 };
 
 /// Provides a builder pattern for flexibly changing file permissions
@@ -27,13 +29,14 @@ use crate::{
 ///
 /// # Symbolic form
 /// `Chmod` supports a symbol form via the `sym` option, inspired by linux's chmod. The supported
-/// syntax is a repeatable pattern following this form `[dfa]:[ugoa][-+=][rwx]`. All segments are
+/// syntax is a repeatable pattern following this form `[dfa]:[ugoa][-+=][rwxX]`. All segments are
 /// required. The first segment calls out the target filesystem type i.e. `d` directories, `f` files
 /// or `a` both. The second segment is separated from the first by a colon and calls out the group
 /// to target i.e. `u` user, `g` group, `o` other, or `a` all. The second segment calls out the
 /// operation to perform `-` subtractive, `+` addative, or `=` an assignment. The third segment
-/// calls out the permission to subtracet, add or assign. Finally the pattern can be repeated by
-/// separating repetitions with a comma.
+/// calls out the permission to subtracet, add or assign where `X` is execute only for directories
+/// or entries already executable by someone. Finally the pattern can be repeated by separating
+/// repetitions with a comma.
 ///
 /// ```
 /// use rivia::prelude::*;
@@ -45,6 +48,11 @@ use crate::{
 /// assert!(vfs.chmod_b(&file).unwrap().sym("f:a+x").exec().is_ok());
 /// assert_eq!(vfs.is_exec(&file), true);
 /// ```
+///
+/// # GNU form
+/// `Chmod` also accepts GNU chmod style mode strings e.g. `644` or `u=rw,go=r` via the `mode_str`
+/// option which are translated into the octal or symbolic forms above. The `umask` option can be
+/// used to leave the permission bits set in the current umask unchanged.
 pub struct Chmod {
     pub(crate) opts: ChmodOpts,
     pub(crate) exec: Box<dyn Fn(ChmodOpts) -> RvResult<()>>, // provider callback
@@ -54,12 +62,24 @@ pub struct Chmod {
 // from the options allowing for sharing options between different vfs providers.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub(crate) struct ChmodOpts {
-    pub(crate) path: PathBuf,   // path to chmod
-    pub(crate) dirs: u32,       // mode to use for dirs
-    pub(crate) files: u32,      // mode to use for files
-    pub(crate) follow: bool,    // follow links
-    pub(crate) recursive: bool, // chmod recursively
-    pub(crate) sym: String,     // add permissions via symbols
+    pub(crate) path: PathBuf,         // path to chmod
+    pub(crate) dirs: u32,             // mode to use for dirs
+    pub(crate) files: u32,            // mode to use for files
+    pub(crate) follow: bool,          // follow links
+    pub(crate) links: bool,           // change the targets of links
+    pub(crate) recursive: bool,       // chmod recursively
+    pub(crate) sym: String,           // add permissions via symbols
+    pub(crate) umask: u32,            // permission bits to leave unchanged
+    pub(crate) excludes: Vec<String>, // globs of paths to skip along with their contents
+    pub(crate) cont: bool,            // collect failures and continue rather than aborting
+}
+
+impl ChmodOpts {
+    // Returns true if the given entry or one of its parents was excluded
+    pub(crate) fn excluded(&self, entry: &VfsEntry) -> bool {
+        let path = if entry.following() { entry.alt() } else { entry.path() };
+        sys::glob_excluded(&self.path, path, &self.excludes)
+    }
 }
 
 impl Chmod {
@@ -136,6 +156,56 @@ impl Chmod {
         self
     }
 
+    /// Continue on past failures collecting them rather than aborting on the first one
+    ///
+    /// * Default: false
+    /// * Failures are returned once the traversal completes as a single [`BatchError`]
+    /// * Useful when fixing permissions over a tree that contains a few problematic entries
+    ///
+    /// [`BatchError`]: crate::errors::BatchError
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Memfs::new();
+    /// let dir = vfs.root().mash("dir");
+    /// let file1 = dir.mash("file1");
+    /// let file2 = dir.mash("file2");
+    /// assert_vfs_mkdir_p!(vfs, &dir);
+    /// assert_vfs_mkfile!(vfs, &file1);
+    /// assert_vfs_mkfile!(vfs, &file2);
+    /// let err = vfs.chmod_b(&dir).unwrap().sym("f:z+x").continue_on_error().exec().unwrap_err();
+    /// assert_eq!(err.downcast_ref::<BatchError>().unwrap().paths(), vec![&file1, &file2]);
+    /// ```
+    pub fn continue_on_error(mut self) -> Self {
+        self.opts.cont = true;
+        self
+    }
+
+    /// Exclude paths matching the given glob along with everything beneath them
+    ///
+    /// * Globs are matched against the absolute path of each entry
+    /// * May be called multiple times to exclude multiple globs
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Memfs::new();
+    /// let dir = vfs.root().mash("dir");
+    /// let file = dir.mash("file");
+    /// assert_vfs_mkdir_p!(vfs, &dir);
+    /// assert_vfs_mkfile!(vfs, &file);
+    /// assert!(vfs.chmod_b(vfs.root()).unwrap().exclude_glob("**/dir").all(0o700).exec().is_ok());
+    /// assert_eq!(vfs.mode(&dir).unwrap(), 0o40755);
+    /// assert_eq!(vfs.mode(&file).unwrap(), 0o100644);
+    /// ```
+    pub fn exclude_glob(mut self, glob: &str) -> Self {
+        self.opts.excludes.push(glob.to_string());
+        self
+    }
+
     /// Follow links so that the directories/files they point to are also affected
     ///
     /// * Default: false
@@ -158,6 +228,32 @@ impl Chmod {
         self
     }
 
+    /// Change the permissions of the targets of links found during the traversal
+    ///
+    /// * Links don't carry permissions of their own so their targets are changed instead
+    /// * Unlike `follow` the directories that links point to are not traversed
+    /// * Dangling links are skipped
+    /// * Default: false
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Memfs::new();
+    /// let dir = vfs.root().mash("dir");
+    /// let file = vfs.root().mash("file");
+    /// let link = dir.mash("link");
+    /// assert_vfs_mkdir_p!(vfs, &dir);
+    /// assert_vfs_mkfile!(vfs, &file);
+    /// assert_vfs_symlink!(vfs, &link, &file);
+    /// assert!(vfs.chmod_b(&dir).unwrap().links().files(0o600).exec().is_ok());
+    /// assert_eq!(vfs.mode(&file).unwrap(), 0o100600);
+    /// ```
+    pub fn links(mut self) -> Self {
+        self.opts.links = true;
+        self
+    }
+
     /// Remove write and execute permissions for all groups for files only
     ///
     /// ### Examples
@@ -242,9 +338,71 @@ impl Chmod {
         self
     }
 
+    /// Update the `mode` using a GNU chmod style mode string
+    ///
+    /// * Accepts the octal form e.g. `644` or `0755` which is applied to both directories and files
+    /// * Accepts the symbolic form `[ugoa]*([-+=][rwxX]*)+` e.g. `u=rw,go=r` or `+x`
+    /// * Omitting the group is the same as `a` and an empty assignment e.g. `o=` clears the group
+    /// * Replaces any previously set octal or symbolic settings
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Memfs::new();
+    /// let dir = vfs.root().mash("dir");
+    /// let file = dir.mash("file");
+    /// assert_vfs_mkdir_p!(vfs, &dir);
+    /// assert_vfs_mkfile!(vfs, &file);
+    /// assert!(vfs.chmod_b(&dir).unwrap().mode_str("u=rw,go=").exec().is_ok());
+    /// assert_eq!(vfs.mode(&dir).unwrap(), 0o40600);
+    /// assert_eq!(vfs.mode(&file).unwrap(), 0o100600);
+    /// assert!(vfs.chmod_b(&dir).unwrap().mode_str("755").exec().is_ok());
+    /// assert_eq!(vfs.mode(&dir).unwrap(), 0o40755);
+    /// assert_eq!(vfs.mode(&file).unwrap(), 0o100755);
+    /// ```
+    pub fn mode_str(mut self, mode: &str) -> Self {
+        self.opts.dirs = 0;
+        self.opts.files = 0;
+        self.opts.sym = "".to_string();
+
+        // Octal form with zero being converted to symbolic as zero signals no octal value
+        if !mode.is_empty() && mode.len() <= 4 && mode.chars().all(|x| x.is_digit(8)) {
+            match u32::from_str_radix(mode, 8).unwrap() {
+                0 => self.opts.sym = "a:a-rwx".to_string(),
+                octal => self = self.all(octal),
+            }
+            return self;
+        }
+
+        self.opts.sym = sym_from_gnu(mode);
+        self
+    }
+
+    /// Apply modes relative to the current process umask
+    ///
+    /// * Permission bits set in the umask are left unchanged on the target entries
+    /// * Matches GNU chmod's handling of symbolic modes given without a group
+    /// * Default: false
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Memfs::new();
+    /// let file = vfs.root().mash("file");
+    /// assert_vfs_mkfile!(vfs, &file);
+    /// assert!(vfs.chmod_b(&file).unwrap().umask().mode_str("600").exec().is_ok());
+    /// assert_eq!(vfs.mode(&file).unwrap() & 0o700, 0o600);
+    /// ```
+    pub fn umask(mut self) -> Self {
+        self.opts.umask = current_umask();
+        self
+    }
+
     /// Update the `mode` using symbols inspired by linux's chmod
     ///
-    /// * Uses the following repeatable pattern `[dfa]:[ugoa][-+=][rwx]`
+    /// * Uses the following repeatable pattern `[dfa]:[ugoa][-+=][rwxX]`
     /// * All segments are required
     /// * The first segment calls out the target filesystem type i.e. `d` directories, `f` files or
     ///   `a` both.
@@ -253,6 +411,8 @@ impl Chmod {
     /// * The second segment calls out the operation to perform `-` subtractive, `+` addative, or
     ///   `=` an assignment.
     /// * The third segment calls out the permission to subtract, add or assign.
+    /// * The `X` permission is execute only for directories or entries already executable by
+    ///   someone e.g. `a:u+rwX`
     /// * Finally the pattern can be repeated by separating repetitions with a comma.
     ///
     /// ### Examples
@@ -305,14 +465,15 @@ enum State {
 /// Update the `mode` using symbols inspired by linux's chmod if given
 ///
 /// * Octal mode takes priority if given
-/// * Symbolic mode takes the following repeatable pattern `[dfa]:[ugoa][-+=][rwx]`
+/// * Symbolic mode takes the following repeatable pattern `[dfa]:[ugoa][-+=][rwxX]`
 /// * All segments are required, repeats are comma separated
 /// * The 1st seg calls out the entry type i.e. `d` directories, `f` files or `a` both
 /// * The 2nd seg is separated from the first by a colon and calls out the group to target i.e. `u`
 ///   user, `g` group, `o` other, or `a` all
 /// * The 3rd seg calls out the operation to perform `-` subtractive, `+` addative, or `=` an
 ///   assignment
-/// * The fourth segment calls out the permission to subtract, add or assign
+/// * The fourth segment calls out the permission to subtract, add or assign with `X` being execute
+///   only for directories or entries already executable by someone
 pub(crate) fn mode(entry: &VfsEntry, octal: u32, sym: &str) -> RvResult<u32> {
     // Octal mode takes priority
     if octal != 0 {
@@ -375,13 +536,17 @@ pub(crate) fn mode(entry: &VfsEntry, octal: u32, sym: &str) -> RvResult<u32> {
             },
             State::Perms => {
                 let mut perm = 0;
+                let mut given = false;
                 while state == State::Perms {
                     match c {
-                        'r' | 'w' | 'x' => {
-                            // Accumulate current permission
+                        'r' | 'w' | 'x' | 'X' => {
+                            // Accumulate current permission with `X` only applying execute to
+                            // directories or entries that are already executable for someone.
+                            given = true;
                             match c {
                                 'r' => perm |= 0o0444,
                                 'w' => perm |= 0o0222,
+                                'X' if !entry.is_dir() && mode & 0o0111 == 0 => (),
                                 _ => perm |= 0o0111,
                             }
 
@@ -398,7 +563,7 @@ pub(crate) fn mode(entry: &VfsEntry, octal: u32, sym: &str) -> RvResult<u32> {
                         _ => return Err(VfsError::InvalidChmodPermissions(sym.to_string()).into()),
                     }
                 }
-                if perm == 0 {
+                if !given {
                     return Err(VfsError::InvalidChmodPermissions(sym.to_string()).into());
                 }
 
@@ -415,6 +580,55 @@ pub(crate) fn mode(entry: &VfsEntry, octal: u32, sym: &str) -> RvResult<u32> {
     Ok(mode)
 }
 
+// Apply the umask to the new mode leaving the masked bits of the old mode unchanged
+pub(crate) fn umask_mode(old: u32, new: u32, umask: u32) -> u32 {
+    match new == 0 || umask == 0 {
+        true => new,
+        false => (old & umask) | (new & !umask),
+    }
+}
+
+// Translate a GNU chmod symbolic mode e.g. `u=rw,go=r` into the `[dfa]:[ugoa][-+=][rwxX]` form.
+// Invalid input is passed through as is to be reported when the mode is computed.
+pub(crate) fn sym_from_gnu(mode: &str) -> String {
+    let mut sym = vec![];
+    for clause in mode.split(',') {
+        let (group, actions) = clause.split_at(clause.find(['-', '+', '=']).unwrap_or(clause.len()));
+        let group = if group.is_empty() { "a" } else { group };
+        if actions.is_empty() {
+            sym.push(format!("a:{}", group));
+        }
+
+        // Split out each operation with its permissions e.g. `u+r-w` => `+r`, `-w`
+        let ops: Vec<usize> =
+            actions.match_indices(['-', '+', '=']).map(|(i, _)| i).chain([actions.len()]).collect();
+        for x in ops.windows(2) {
+            let (op, perms) = actions[x[0]..x[1]].split_at(1);
+            match (op, perms.is_empty()) {
+                ("=", true) => sym.push(format!("a:{}-rwx", group)),
+                (_, true) => (),
+                _ => sym.push(format!("a:{}{}{}", group, op, perms)),
+            }
+        }
+    }
+    sym.join(",")
+}
+
+// Read the current process umask. Linux exposes it without modification in the process status
+// else fall back on setting and restoring it.
+fn current_umask() -> u32 {
+    if let Ok(status) = std::fs::read_to_string("/proc/self/status") {
+        if let Some(line) = status.lines().find(|x| x.starts_with("Umask:")) {
+            if let Ok(mask) = u32::from_str_radix(line.trim_start_matches("Umask:").trim(), 8) {
+                return mask;
+            }
+        }
+    }
+    let mask = stat::umask(stat::Mode::empty());
+    stat::umask(mask);
+    mask.bits()
+}
+
 // handle pop gracefully
 fn _pop(chars: &mut Vec<char>, sym: &str) -> RvResult<char> {
     if !chars.is_empty() {
@@ -518,27 +732,27 @@ mod tests {
         // setup
         assert!(vfs.mkfile_m(&file1, 0o644).is_ok());
         assert_eq!(vfs.mode(&file1).unwrap(), 0o100644);
-        assert_eq!(vfs.is_exec(&file1), false);
+        assert!(!vfs.is_exec(&file1));
 
         // add_x
         assert!(vfs.chmod_b(&file1).unwrap().sym("f:a+x").exec().is_ok());
         assert_eq!(vfs.mode(&file1).unwrap(), 0o100755);
-        assert_eq!(vfs.is_exec(&file1), true);
+        assert!(vfs.is_exec(&file1));
 
         // sub_x
         assert!(vfs.chmod_b(&file1).unwrap().sym("f:a-x").exec().is_ok());
         assert_eq!(vfs.mode(&file1).unwrap(), 0o100644);
-        assert_eq!(vfs.is_exec(&file1), false);
+        assert!(!vfs.is_exec(&file1));
 
         // sub_w
         assert!(vfs.chmod_b(&file1).unwrap().sym("f:a-w").exec().is_ok());
         assert_eq!(vfs.mode(&file1).unwrap(), 0o100444);
-        assert_eq!(vfs.is_readonly(&file1), true);
+        assert!(vfs.is_readonly(&file1));
 
         // add_w
         assert!(vfs.chmod_b(&file1).unwrap().sym("f:a+w").exec().is_ok());
         assert_eq!(vfs.mode(&file1).unwrap(), 0o100666);
-        assert_eq!(vfs.is_readonly(&file1), false);
+        assert!(!vfs.is_readonly(&file1));
 
         // sub_r
         assert!(vfs.chmod_b(&file1).unwrap().sym("f:a-r").exec().is_ok());
@@ -556,7 +770,167 @@ mod tests {
         // readonly
         assert!(vfs.chmod_b(&file1).unwrap().readonly().exec().is_ok());
         assert_eq!(vfs.mode(&file1).unwrap(), 0o100444);
-        assert_eq!(vfs.is_readonly(&file1), true);
+        assert!(vfs.is_readonly(&file1));
+
+        // cleanup
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+
+    #[test]
+    fn test_vfs_chmod_b_mode_str() {
+        test_chmod_b_mode_str(assert_vfs_setup!(Vfs::memfs()));
+        test_chmod_b_mode_str(assert_vfs_setup!(Vfs::stdfs()));
+    }
+    fn test_chmod_b_mode_str((vfs, tmpdir): (Vfs, PathBuf)) {
+        let dir1 = tmpdir.mash("dir1");
+        let file1 = dir1.mash("file1");
+
+        // setup
+        assert_vfs_mkdir_m!(vfs, &dir1, 0o40755);
+        assert!(vfs.mkfile_m(&file1, 0o644).is_ok());
+
+        // octal
+        assert!(vfs.chmod_b(&file1).unwrap().mode_str("0600").exec().is_ok());
+        assert_eq!(vfs.mode(&file1).unwrap(), 0o100600);
+        assert!(vfs.chmod_b(&dir1).unwrap().mode_str("750").exec().is_ok());
+        assert_eq!(vfs.mode(&dir1).unwrap(), 0o40750);
+        assert_eq!(vfs.mode(&file1).unwrap(), 0o100750);
+        assert!(vfs.chmod_b(&file1).unwrap().mode_str("000").exec().is_ok());
+        assert_eq!(vfs.mode(&file1).unwrap(), 0o100000);
+
+        // symbolic
+        assert!(vfs.chmod_b(&file1).unwrap().mode_str("u=rw,go=r").exec().is_ok());
+        assert_eq!(vfs.mode(&file1).unwrap(), 0o100644);
+        assert!(vfs.chmod_b(&file1).unwrap().mode_str("+x").exec().is_ok());
+        assert_eq!(vfs.mode(&file1).unwrap(), 0o100755);
+        assert!(vfs.chmod_b(&file1).unwrap().mode_str("ug+w-x,o=").exec().is_ok());
+        assert_eq!(vfs.mode(&file1).unwrap(), 0o100660);
+
+        // replaces previous settings
+        assert!(vfs.chmod_b(&file1).unwrap().all(0o777).mode_str("a-w").exec().is_ok());
+        assert_eq!(vfs.mode(&file1).unwrap(), 0o100440);
+
+        // invalid
+        assert!(vfs.chmod_b(&file1).unwrap().mode_str("u=foo").exec().is_err());
+        assert!(vfs.chmod_b(&file1).unwrap().mode_str("u").exec().is_err());
+        assert!(vfs.chmod_b(&file1).unwrap().mode_str("").exec().is_err());
+        assert_eq!(vfs.mode(&file1).unwrap(), 0o100440);
+
+        // conditional execute only applies to dirs and executables
+        assert!(vfs.chmod_b(&dir1).unwrap().mode_str("a=,u+rwX").exec().is_ok());
+        assert_eq!(vfs.mode(&dir1).unwrap(), 0o40700);
+        assert_eq!(vfs.mode(&file1).unwrap(), 0o100600);
+        assert!(vfs.chmod_b(&dir1).unwrap().mode_str("u+x,go+rX").exec().is_ok());
+        assert_eq!(vfs.mode(&dir1).unwrap(), 0o40755);
+        assert_eq!(vfs.mode(&file1).unwrap(), 0o100755);
+
+        // umask leaves masked bits unchanged
+        let mut chmod = vfs.chmod_b(&file1).unwrap().mode_str("777");
+        chmod.opts.umask = 0o022;
+        assert!(chmod.exec().is_ok());
+        assert_eq!(vfs.mode(&file1).unwrap(), 0o100755);
+        let mut chmod = vfs.chmod_b(&file1).unwrap().mode_str("a=");
+        chmod.opts.umask = 0o077;
+        assert!(chmod.exec().is_ok());
+        assert_eq!(vfs.mode(&file1).unwrap(), 0o100055);
+
+        // cleanup
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+
+    #[test]
+    fn test_vfs_chmod_continue_on_error() {
+        test_chmod_continue_on_error(assert_vfs_setup!(Vfs::memfs()));
+        test_chmod_continue_on_error(assert_vfs_setup!(Vfs::stdfs()));
+    }
+    fn test_chmod_continue_on_error((vfs, tmpdir): (Vfs, PathBuf)) {
+        let dir = tmpdir.mash("dir");
+        let file1 = dir.mash("file1");
+        let file2 = dir.mash("file2");
+        assert_vfs_mkdir_p!(vfs, &dir);
+        assert_vfs_mkfile!(vfs, &file1);
+        assert_vfs_mkfile!(vfs, &file2);
+
+        // Abort on the first failure by default
+        let err = vfs.chmod_b(&dir).unwrap().dirs(0o700).sym("f:z+x").exec().unwrap_err();
+        assert_eq!(err.downcast_ref::<VfsError>(), Some(&VfsError::InvalidChmodGroup("f:z+x".to_string())));
+        assert_eq!(vfs.mode(&dir).unwrap(), 0o40755);
+
+        // Collect failures for each file while still updating the directory
+        let err = vfs.chmod_b(&dir).unwrap().dirs(0o700).sym("f:z+x").continue_on_error().exec().unwrap_err();
+        let err = err.downcast_ref::<BatchError>().unwrap();
+        assert_eq!(err.paths(), vec![&file1, &file2]);
+        assert!(err.errors().iter().all(|(_, x)| x.downcast_ref::<VfsError>().is_some()));
+        assert_eq!(vfs.mode(&dir).unwrap(), 0o40700);
+
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+
+    #[test]
+    fn test_vfs_chmod_exclude_glob() {
+        test_chmod_exclude_glob(assert_vfs_setup!(Vfs::memfs()));
+        test_chmod_exclude_glob(assert_vfs_setup!(Vfs::stdfs()));
+    }
+    fn test_chmod_exclude_glob((vfs, tmpdir): (Vfs, PathBuf)) {
+        let dir1 = tmpdir.mash("dir1");
+        let file1 = dir1.mash("file1");
+        let vendor = dir1.mash("vendor");
+        let file2 = vendor.mash("file2");
+        let file3 = tmpdir.mash("file3.bak");
+
+        assert_vfs_mkdir_p!(vfs, &vendor);
+        assert!(vfs.mkfile_m(&file1, 0o644).is_ok());
+        assert!(vfs.mkfile_m(&file2, 0o644).is_ok());
+        assert!(vfs.mkfile_m(&file3, 0o644).is_ok());
+
+        // excluded subtrees and files are skipped
+        let chmod = vfs.chmod_b(&tmpdir).unwrap().exclude_glob("**/vendor").exclude_glob("**/*.bak");
+        assert!(chmod.files(0o600).exec().is_ok());
+        assert_eq!(vfs.mode(&file1).unwrap(), 0o100600);
+        assert_eq!(vfs.mode(&vendor).unwrap(), 0o40755);
+        assert_eq!(vfs.mode(&file2).unwrap(), 0o100644);
+        assert_eq!(vfs.mode(&file3).unwrap(), 0o100644);
+
+        // excluding the root skips everything
+        assert!(vfs.chmod_b(&dir1).unwrap().exclude_glob("**/dir1").all(0o700).exec().is_ok());
+        assert_eq!(vfs.mode(&dir1).unwrap(), 0o40755);
+        assert_eq!(vfs.mode(&file1).unwrap(), 0o100600);
+
+        // cleanup
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+
+    #[test]
+    fn test_vfs_chmod_links() {
+        test_chmod_links(assert_vfs_setup!(Vfs::memfs()));
+        test_chmod_links(assert_vfs_setup!(Vfs::stdfs()));
+    }
+    fn test_chmod_links((vfs, tmpdir): (Vfs, PathBuf)) {
+        let dir1 = tmpdir.mash("dir1");
+        let dir2 = tmpdir.mash("dir2");
+        let file1 = dir2.mash("file1");
+        let file2 = tmpdir.mash("file2");
+        let link1 = dir1.mash("link1");
+        let link2 = dir1.mash("link2");
+
+        assert_vfs_mkdir_p!(vfs, &dir1);
+        assert_vfs_mkdir_p!(vfs, &dir2);
+        assert!(vfs.mkfile_m(&file1, 0o644).is_ok());
+        assert!(vfs.mkfile_m(&file2, 0o644).is_ok());
+        assert_vfs_symlink!(vfs, &link1, &dir2);
+        assert_vfs_symlink!(vfs, &link2, &file2);
+
+        // links are skipped by default
+        assert!(vfs.chmod_b(&dir1).unwrap().mode_str("u+x").exec().is_ok());
+        assert_eq!(vfs.mode(&dir2).unwrap(), 0o40755);
+        assert_eq!(vfs.mode(&file2).unwrap(), 0o100644);
+
+        // link targets are changed without traversing linked directories
+        assert!(vfs.chmod_b(&dir1).unwrap().links().mode_str("go-rx").exec().is_ok());
+        assert_eq!(vfs.mode(&dir1).unwrap(), 0o40700);
+        assert_eq!(vfs.mode(&dir2).unwrap(), 0o40700);
+        assert_eq!(vfs.mode(&file2).unwrap(), 0o100600);
+        assert_eq!(vfs.mode(&file1).unwrap(), 0o100644);
 
         // cleanup
         assert_vfs_remove_all!(vfs, &tmpdir);
@@ -653,13 +1027,21 @@ mod tests {
         test_chmod_symbolic(
             Box::new(|m: u32| -> VfsEntry {
                 StdfsEntry {
+                    meta: Default::default(),
                     path: PathBuf::new(),
                     alt: PathBuf::new(),
                     rel: PathBuf::new(),
+                    chain: vec![],
                     dir: true,
                     file: false,
                     link: false,
                     mode: m,
+                    size: 0,
+                    accessed: std::time::SystemTime::UNIX_EPOCH,
+                    modified: std::time::SystemTime::UNIX_EPOCH,
+                    created: std::time::SystemTime::UNIX_EPOCH,
+                    uid: 0,
+                    gid: 0,
                     follow: false,
                     cached: false,
                 }
@@ -667,13 +1049,21 @@ mod tests {
             }),
             Box::new(|m: u32| -> VfsEntry {
                 StdfsEntry {
+                    meta: Default::default(),
                     path: PathBuf::new(),
                     alt: PathBuf::new(),
                     rel: PathBuf::new(),
+                    chain: vec![],
                     dir: false,
                     file: true,
                     link: false,
                     mode: m,
+                    size: 0,
+                    accessed: std::time::SystemTime::UNIX_EPOCH,
+                    modified: std::time::SystemTime::UNIX_EPOCH,
+                    created: std::time::SystemTime::UNIX_EPOCH,
+                    uid: 0,
+                    gid: 0,
                     follow: false,
                     cached: false,
                 }
@@ -682,10 +1072,10 @@ mod tests {
         );
     }
     fn test_chmod_symbolic(d: Box<dyn Fn(u32) -> VfsEntry>, f: Box<dyn Fn(u32) -> VfsEntry>) {
-        assert_eq!(0o0700 & 0o0444 | 0o0000, 0o0400); // u+r
-        assert_eq!(0o0770 & 0o0444 | 0o0000, 0o0440); // ug+r
-        assert_eq!(!(0o0700 & 0o0444) & 0o0444, 0o0044); // u-r
-        assert_eq!(!(0o0770 & 0o0444) & 0o0444, 0o0004); // ug-r
+        assert_eq!(sys::mode(&f(0o0000), 0, "a:u+r").unwrap(), 0o0400);
+        assert_eq!(sys::mode(&f(0o0000), 0, "a:ug+r").unwrap(), 0o0440);
+        assert_eq!(sys::mode(&f(0o0444), 0, "a:u-r").unwrap(), 0o0044);
+        assert_eq!(sys::mode(&f(0o0444), 0, "a:ug-r").unwrap(), 0o0004);
 
         // Repeating tests
         // -----------------------------------------------------------------------------------------
@@ -693,6 +1083,18 @@ mod tests {
         assert_eq!(sys::mode(&f(0o0077), 0, "f:u=rwx,f:g=rw,f:o=r").unwrap(), 0o0764);
         assert_eq!(sys::mode(&f(0o0077), 0, "f:u=rwx,f:g=rw,f:o-rwx").unwrap(), 0o0760);
 
+        // Conditional execute tests
+        // -----------------------------------------------------------------------------------------
+        assert_eq!(sys::mode(&f(0o0600), 0, "a:a+X").unwrap(), 0o0600);
+        assert_eq!(sys::mode(&f(0o0700), 0, "a:a+X").unwrap(), 0o0711);
+        assert_eq!(sys::mode(&d(0o0600), 0, "a:a+X").unwrap(), 0o0711);
+        assert_eq!(sys::mode(&f(0o0000), 0, "a:u+rwX").unwrap(), 0o0600);
+        assert_eq!(sys::mode(&f(0o0000), 0, "a:u+x,a:go+rX").unwrap(), 0o0155);
+        assert_eq!(sys::mode(&f(0o0755), 0, "a:a=rX").unwrap(), 0o0555);
+        assert_eq!(sys::mode(&f(0o0644), 0, "a:a=rX").unwrap(), 0o0444);
+        assert_eq!(sys::mode(&f(0o0755), 0, "a:a-X").unwrap(), 0o0644);
+        assert_eq!(sys::mode(&f(0o0644), 0, "a:a-X").unwrap(), 0o0644);
+
         // Target tests
         // -----------------------------------------------------------------------------------------
 
@@ -784,31 +1186,47 @@ mod tests {
         assert_eq!(sys::mode(&f(0o0400), 0, "f:u+rwxrwx").unwrap(), 0o0700);
     }
 
+    #[test]
+    fn test_sym_from_gnu() {
+        assert_eq!(sys::sym_from_gnu("u=rw,go=r"), "a:u=rw,a:go=r");
+        assert_eq!(sys::sym_from_gnu("+x"), "a:a+x");
+        assert_eq!(sys::sym_from_gnu("u+r-w=x"), "a:u+r,a:u-w,a:u=x");
+        assert_eq!(sys::sym_from_gnu("o=,g+"), "a:o-rwx");
+        assert_eq!(sys::sym_from_gnu("u"), "a:u");
+    }
+
+    #[test]
+    fn test_umask_mode() {
+        assert_eq!(sys::umask_mode(0o100644, 0, 0o022), 0);
+        assert_eq!(sys::umask_mode(0o100644, 0o777, 0), 0o777);
+        assert_eq!(sys::umask_mode(0o100664, 0o777, 0o022), 0o775);
+    }
+
     #[test]
     fn test_revoking_mode() {
         // test other octet
-        assert_eq!(sys::revoking_mode(0o0777, 0o0777), false);
-        assert_eq!(sys::revoking_mode(0o0776, 0o0775), false);
-        assert_eq!(sys::revoking_mode(0o0770, 0o0771), false);
-        assert_eq!(sys::revoking_mode(0o0776, 0o0772), true);
-        assert_eq!(sys::revoking_mode(0o0775, 0o0776), true);
-        assert_eq!(sys::revoking_mode(0o0775, 0o0774), true);
+        assert!(!sys::revoking_mode(0o0777, 0o0777));
+        assert!(!sys::revoking_mode(0o0776, 0o0775));
+        assert!(!sys::revoking_mode(0o0770, 0o0771));
+        assert!(sys::revoking_mode(0o0776, 0o0772));
+        assert!(sys::revoking_mode(0o0775, 0o0776));
+        assert!(sys::revoking_mode(0o0775, 0o0774));
 
         // Test group octet
-        assert_eq!(sys::revoking_mode(0o0777, 0o0777), false);
-        assert_eq!(sys::revoking_mode(0o0767, 0o0757), false);
-        assert_eq!(sys::revoking_mode(0o0707, 0o0717), false);
-        assert_eq!(sys::revoking_mode(0o0767, 0o0727), true);
-        assert_eq!(sys::revoking_mode(0o0757, 0o0767), true);
-        assert_eq!(sys::revoking_mode(0o0757, 0o0747), true);
+        assert!(!sys::revoking_mode(0o0777, 0o0777));
+        assert!(!sys::revoking_mode(0o0767, 0o0757));
+        assert!(!sys::revoking_mode(0o0707, 0o0717));
+        assert!(sys::revoking_mode(0o0767, 0o0727));
+        assert!(sys::revoking_mode(0o0757, 0o0767));
+        assert!(sys::revoking_mode(0o0757, 0o0747));
 
         // Test owner octet
-        assert_eq!(sys::revoking_mode(0o0777, 0o0777), false);
-        assert_eq!(sys::revoking_mode(0o0677, 0o0577), false);
-        assert_eq!(sys::revoking_mode(0o0077, 0o0177), false);
-        assert_eq!(sys::revoking_mode(0o0677, 0o0277), true);
-        assert_eq!(sys::revoking_mode(0o0577, 0o0677), true);
-        assert_eq!(sys::revoking_mode(0o0577, 0o0477), true);
-        assert_eq!(sys::revoking_mode(0o0577, 0o0177), true);
+        assert!(!sys::revoking_mode(0o0777, 0o0777));
+        assert!(!sys::revoking_mode(0o0677, 0o0577));
+        assert!(!sys::revoking_mode(0o0077, 0o0177));
+        assert!(sys::revoking_mode(0o0677, 0o0277));
+        assert!(sys::revoking_mode(0o0577, 0o0677));
+        assert!(sys::revoking_mode(0o0577, 0o0477));
+        assert!(sys::revoking_mode(0o0577, 0o0177));
     }
 }
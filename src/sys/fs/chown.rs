@@ -1,6 +1,9 @@
 use std::path::PathBuf;
 
-use crate::errors::RvResult;
+use crate::{
+    errors::{RvResult, UserError},
+    sys::{self, user, Entry, VfsEntry},
+};
 
 /// Provides a builder pattern for flexibly changing file ownership
 ///
@@ -28,11 +31,24 @@ pub struct Chown
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub(crate) struct ChownOpts
 {
-    pub(crate) path: PathBuf,    // path to chown
-    pub(crate) uid: Option<u32>, // uid to use
-    pub(crate) gid: Option<u32>, // uid to use
-    pub(crate) follow: bool,     // follow links
-    pub(crate) recursive: bool,  // chown recursiveily
+    pub(crate) path: PathBuf,         // path to chown
+    pub(crate) uid: Option<u32>,      // uid to use
+    pub(crate) gid: Option<u32>,      // uid to use
+    pub(crate) follow: bool,          // follow links
+    pub(crate) links: bool,           // change the ownership of links themselves
+    pub(crate) recursive: bool,       // chown recursiveily
+    pub(crate) excludes: Vec<String>, // globs of paths to skip along with their contents
+    pub(crate) cont: bool,            // collect failures and continue rather than aborting
+}
+
+impl ChownOpts
+{
+    // Returns true if the given entry or one of its parents was excluded
+    pub(crate) fn excluded(&self, entry: &VfsEntry) -> bool
+    {
+        let path = if entry.following() { entry.alt() } else { entry.path() };
+        sys::glob_excluded(&self.path, path, &self.excludes)
+    }
 }
 
 impl Chown
@@ -95,6 +111,106 @@ impl Chown
         self
     }
 
+    /// Set the ownership from a `chown` style spec of the form `user[:group]` or `:group`
+    ///
+    /// * Users and groups may be given by name or numeric id e.g. `deploy:www-data` or `1000:1000`
+    /// * Names are resolved first falling back on numeric ids as `chown` does
+    /// * `user` changes only the user, `:group` only the group and `user:` sets the group to the
+    ///   user's login group
+    ///
+    /// ### Errors
+    /// * UserError::InvalidOwnerSpec(String) when the spec is empty or has more than one `:`
+    /// * UserError::DoesNotExistByName(String) when the user doesn't exist
+    /// * UserError::GroupDoesNotExistByName(String) when the group doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Memfs::new();
+    /// let file1 = vfs.root().mash("file1");
+    /// assert_vfs_mkfile!(vfs, &file1);
+    /// assert!(vfs.chown_b(&file1).unwrap().spec("5:root").unwrap().exec().is_ok());
+    /// assert_eq!(vfs.uid(&file1).unwrap(), 5);
+    /// assert_eq!(vfs.gid(&file1).unwrap(), 0);
+    /// ```
+    pub fn spec<T: AsRef<str>>(mut self, spec: T) -> RvResult<Self>
+    {
+        let spec = spec.as_ref();
+        let (name, group) = match spec.split_once(':') {
+            Some((name, group)) => (name, Some(group)),
+            None => (spec, None),
+        };
+        if name.is_empty() && group.is_none_or(|x| x.is_empty()) || group.is_some_and(|x| x.contains(':')) {
+            return Err(UserError::invalid_owner_spec(spec).into());
+        }
+
+        if !name.is_empty() {
+            let uid = user::uid_from_name(name).or_else(|e| name.parse::<u32>().map_err(|_| e))?;
+            self.opts.uid = Some(uid);
+            if group == Some("") {
+                self.opts.gid = Some(user::from_uid(uid)?.gid);
+            }
+        }
+        if let Some(group) = group.filter(|x| !x.is_empty()) {
+            self.opts.gid = Some(user::gid_from_name(group).or_else(|e| group.parse::<u32>().map_err(|_| e))?);
+        }
+        Ok(self)
+    }
+
+    /// Continue on past failures collecting them rather than aborting on the first one
+    ///
+    /// * Default: false
+    /// * Failures are returned once the traversal completes as a single [`BatchError`]
+    ///
+    /// [`BatchError`]: crate::errors::BatchError
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Memfs::new();
+    /// let dir = vfs.root().mash("dir");
+    /// let file = dir.mash("file");
+    /// let link = dir.mash("link");
+    /// assert_vfs_mkdir_p!(vfs, &dir);
+    /// assert_vfs_mkfile!(vfs, &file);
+    /// assert_vfs_symlink!(vfs, &link, &dir);
+    /// let err = vfs.chown_b(&dir).unwrap().uid(5).follow().continue_on_error().exec().unwrap_err();
+    /// assert_eq!(err.downcast_ref::<BatchError>().unwrap().len(), 1);
+    /// assert_eq!(vfs.uid(&file).unwrap(), 5);
+    /// ```
+    pub fn continue_on_error(mut self) -> Self
+    {
+        self.opts.cont = true;
+        self
+    }
+
+    /// Exclude paths matching the given glob along with everything beneath them
+    ///
+    /// * Globs are matched against the absolute path of each entry
+    /// * May be called multiple times to exclude multiple globs
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Memfs::new();
+    /// let dir = vfs.root().mash("dir");
+    /// let file = dir.mash("file");
+    /// assert_vfs_mkdir_p!(vfs, &dir);
+    /// assert_vfs_mkfile!(vfs, &file);
+    /// assert!(vfs.chown_b(vfs.root()).unwrap().exclude_glob("**/dir").uid(5).exec().is_ok());
+    /// assert_eq!(vfs.uid(vfs.root()).unwrap(), 5);
+    /// assert_eq!(vfs.uid(&dir).unwrap(), 1000);
+    /// assert_eq!(vfs.uid(&file).unwrap(), 1000);
+    /// ```
+    pub fn exclude_glob(mut self, glob: &str) -> Self
+    {
+        self.opts.excludes.push(glob.to_string());
+        self
+    }
+
     /// Follow links so that the path they point to are also affected
     ///
     /// * Default: false
@@ -120,6 +236,30 @@ impl Chown
         self
     }
 
+    /// Change the ownership of links themselves rather than skipping them
+    ///
+    /// * Uses `lchown` semantics so link targets are only affected when `follow` is also set
+    /// * Default: false
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Memfs::new();
+    /// let file = vfs.root().mash("file");
+    /// let link = vfs.root().mash("link");
+    /// assert_vfs_mkfile!(vfs, &file);
+    /// assert_vfs_symlink!(vfs, &link, &file);
+    /// assert!(vfs.chown_b(&link).unwrap().uid(5).links().exec().is_ok());
+    /// assert_eq!(vfs.uid(&file).unwrap(), 1000);
+    /// assert_eq!(vfs.uid(&link).unwrap(), 5);
+    /// ```
+    pub fn links(mut self) -> Self
+    {
+        self.opts.links = true;
+        self
+    }
+
     /// Follow paths recursively when set to true
     ///
     /// * Default: true
@@ -200,6 +340,104 @@ mod tests
         assert_vfs_remove_all!(vfs, &tmpdir);
     }
 
+    #[test]
+    fn test_vfs_chown_continue_on_error()
+    {
+        test_chown_continue_on_error(assert_vfs_setup!(Vfs::memfs()));
+        test_chown_continue_on_error(assert_vfs_setup!(Vfs::stdfs()));
+    }
+    fn test_chown_continue_on_error((vfs, tmpdir): (Vfs, PathBuf))
+    {
+        let dir = tmpdir.mash("dir");
+        let file1 = dir.mash("file1");
+        let link = dir.mash("link");
+        let file2 = dir.mash("zfile2");
+        assert_vfs_mkdir_p!(vfs, &dir);
+        assert_vfs_mkfile!(vfs, &file1);
+        assert_vfs_symlink!(vfs, &link, &dir);
+        assert_vfs_mkfile!(vfs, &file2);
+        let (uid, gid) = vfs.owner(&file1).unwrap();
+
+        // Abort on the looping link by default
+        let err = vfs.chown_b(&dir).unwrap().owner(uid, gid).follow().exec().unwrap_err();
+        assert_eq!(err.downcast_ref::<PathError>(), Some(&PathError::link_looping(&dir)));
+
+        // Collect the failure and carry on with the rest of the tree
+        let err = vfs.chown_b(&dir).unwrap().owner(uid, gid).follow().continue_on_error().exec().unwrap_err();
+        let err = err.downcast_ref::<BatchError>().unwrap();
+        assert_eq!(err.len(), 1);
+        assert_eq!(err.errors()[0].1.downcast_ref::<PathError>(), Some(&PathError::link_looping(&dir)));
+        assert_eq!(vfs.owner(&file2).unwrap(), (uid, gid));
+
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+
+    #[test]
+    fn test_vfs_chown_exclude_glob()
+    {
+        test_chown_exclude_glob(assert_vfs_setup!(Vfs::memfs()));
+        test_chown_exclude_glob(assert_vfs_setup!(Vfs::stdfs()));
+    }
+    fn test_chown_exclude_glob((vfs, tmpdir): (Vfs, PathBuf))
+    {
+        let vendor = tmpdir.mash("vendor");
+        let file1 = tmpdir.mash("file1");
+        let file2 = vendor.mash("file2");
+
+        assert_vfs_mkdir_p!(vfs, &vendor);
+        assert_vfs_mkfile!(vfs, &file1);
+        assert_vfs_mkfile!(vfs, &file2);
+        let (uid, gid) = vfs.owner(&file1).unwrap();
+
+        // only memfs can change to arbitrary ids without privileges
+        let (uid2, gid2) = if let Vfs::Memfs(_) = vfs { (5, 5) } else { (uid, gid) };
+        assert!(vfs.chown_b(&tmpdir).unwrap().exclude_glob("**/vendor").owner(uid2, gid2).exec().is_ok());
+        assert_eq!(vfs.owner(&tmpdir).unwrap(), (uid2, gid2));
+        assert_eq!(vfs.owner(&file1).unwrap(), (uid2, gid2));
+        assert_eq!(vfs.owner(&vendor).unwrap(), (uid, gid));
+        assert_eq!(vfs.owner(&file2).unwrap(), (uid, gid));
+
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+
+    #[test]
+    fn test_vfs_chown_links()
+    {
+        test_chown_links(assert_vfs_setup!(Vfs::memfs()));
+        test_chown_links(assert_vfs_setup!(Vfs::stdfs()));
+    }
+    fn test_chown_links((vfs, tmpdir): (Vfs, PathBuf))
+    {
+        let file1 = tmpdir.mash("file1");
+        let link1 = tmpdir.mash("link1");
+
+        assert_vfs_mkfile!(vfs, &file1);
+        assert_vfs_symlink!(vfs, &link1, &file1);
+        let (uid, gid) = vfs.owner(&file1).unwrap();
+
+        // stdfs links can only be verified not to error without privileges
+        assert!(vfs.chown_b(&link1).unwrap().links().owner(uid, gid).exec().is_ok());
+        assert!(vfs.chown_b(&link1).unwrap().links().follow().owner(uid, gid).exec().is_ok());
+        if let Vfs::Memfs(_) = vfs {
+            // links are skipped by default
+            assert!(vfs.chown_b(&link1).unwrap().owner(5, 5).exec().is_ok());
+            assert_eq!(vfs.owner(&link1).unwrap(), (uid, gid));
+            assert_eq!(vfs.owner(&file1).unwrap(), (uid, gid));
+
+            // only the link is changed
+            assert!(vfs.chown_b(&link1).unwrap().links().owner(5, 5).exec().is_ok());
+            assert_eq!(vfs.owner(&link1).unwrap(), (5, 5));
+            assert_eq!(vfs.owner(&file1).unwrap(), (uid, gid));
+
+            // both the link and its target are changed
+            assert!(vfs.chown_b(&link1).unwrap().links().follow().owner(6, 6).exec().is_ok());
+            assert_eq!(vfs.owner(&link1).unwrap(), (6, 6));
+            assert_eq!(vfs.owner(&file1).unwrap(), (6, 6));
+        }
+
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+
     #[test]
     fn test_vfs_chown_follow()
     {
@@ -229,4 +467,42 @@ mod tests
 
         assert_vfs_remove_all!(vfs, &tmpdir);
     }
+
+    #[test]
+    fn test_vfs_chown_spec()
+    {
+        test_chown_spec(assert_vfs_setup!(Vfs::memfs()));
+        test_chown_spec(assert_vfs_setup!(Vfs::stdfs()));
+    }
+    fn test_chown_spec((vfs, tmpdir): (Vfs, PathBuf))
+    {
+        let file1 = tmpdir.mash("file1");
+        assert_vfs_mkfile!(vfs, &file1);
+        let usr = user::from_uid(user::getuid()).unwrap();
+        let (uid, gid) = (usr.uid, user::getgid());
+
+        // Invalid specs
+        for spec in ["", ":", "a:b:c", "::"] {
+            let err = vfs.chown_b(&file1).unwrap().spec(spec).err().unwrap();
+            assert_eq!(err.downcast_ref::<UserError>(), Some(&UserError::invalid_owner_spec(spec)));
+        }
+        let err = vfs.chown_b(&file1).unwrap().spec("rivia-foo:").err().unwrap();
+        assert_eq!(err.downcast_ref::<UserError>(), Some(&UserError::does_not_exist_by_name("rivia-foo")));
+        let err = vfs.chown_b(&file1).unwrap().spec(":rivia-foo").err().unwrap();
+        assert_eq!(err.downcast_ref::<UserError>(), Some(&UserError::group_does_not_exist_by_name("rivia-foo")));
+
+        // Names and numeric ids
+        assert!(vfs.chown_b(&file1).unwrap().spec(format!("{}:{}", usr.name, gid)).unwrap().exec().is_ok());
+        assert_eq!(vfs.owner(&file1).unwrap(), (uid, gid));
+        assert!(vfs.chown_b(&file1).unwrap().spec(uid.to_string()).unwrap().exec().is_ok());
+        assert_eq!(vfs.owner(&file1).unwrap(), (uid, gid));
+        assert!(vfs.chown_b(&file1).unwrap().spec(format!(":{}", gid)).unwrap().exec().is_ok());
+        assert_eq!(vfs.owner(&file1).unwrap(), (uid, gid));
+
+        // User's login group
+        assert!(vfs.chown_b(&file1).unwrap().spec(format!("{}:", usr.name)).unwrap().exec().is_ok());
+        assert_eq!(vfs.owner(&file1).unwrap(), (uid, usr.gid));
+
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
 }
@@ -0,0 +1,119 @@
+pub(crate) const DEFAULT_MAX_DESCRIPTORS: u16 = 50;
+pub(crate) const DEFAULT_MAX_CONTENT_SIZE: u64 = 10 * 1024 * 1024;
+pub(crate) const DEFAULT_DIR_MODE: u32 = 0o755;
+pub(crate) const DEFAULT_FILE_MODE: u32 = 0o644;
+
+/// Provides the tunable defaults of a Vfs backend instance
+///
+/// Use `Memfs::with_config` or `Stdfs::with_config` to create a backend with the given config and
+/// the Vfs function `config` to read it back.
+///
+/// * `max_descriptors` and `max_content_size` are the defaults of the [`Entries`] iterators
+///   created by the backend which can still be overridden per iterator
+/// * `dir_mode` and `file_mode` are the modes Memfs gives new directories and files when none is
+///   specified. Stdfs leaves the modes of new entries to the process umask.
+///
+/// [`Entries`]: crate::sys::Entries
+///
+/// ### Examples
+/// ```
+/// use rivia::prelude::*;
+///
+/// let vfs = Vfs::Memfs(Memfs::with_config(VfsConfig { file_mode: 0o600, ..Default::default() }));
+/// assert_vfs_mkfile!(vfs, "file");
+/// assert_eq!(vfs.mode("file").unwrap(), 0o100600);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct VfsConfig
+{
+    pub max_descriptors: u16,  // directories held open at once while traversing
+    pub max_content_size: u64, // largest file whose contents will be searched
+    pub dir_mode: u32,         // mode of new Memfs directories
+    pub file_mode: u32,        // mode of new Memfs files
+}
+
+impl Default for VfsConfig
+{
+    fn default() -> Self
+    {
+        Self {
+            max_descriptors: DEFAULT_MAX_DESCRIPTORS,
+            max_content_size: DEFAULT_MAX_CONTENT_SIZE,
+            dir_mode: DEFAULT_DIR_MODE,
+            file_mode: DEFAULT_FILE_MODE,
+        }
+    }
+}
+
+// Unit tests
+// -------------------------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests
+{
+    use crate::prelude::*;
+
+    #[test]
+    fn test_vfs_config()
+    {
+        test_config(assert_vfs_setup!(Vfs::memfs()));
+        test_config(assert_vfs_setup!(Vfs::stdfs()));
+    }
+    fn test_config((vfs, tmpdir): (Vfs, PathBuf))
+    {
+        assert_eq!(vfs.config(), VfsConfig {
+            max_descriptors: 50,
+            max_content_size: 10 * 1024 * 1024,
+            dir_mode: 0o755,
+            file_mode: 0o644
+        });
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+
+    #[test]
+    fn test_memfs_with_config()
+    {
+        let config = VfsConfig { max_content_size: 3, dir_mode: 0o700, file_mode: 0o600, ..Default::default() };
+        let vfs = Vfs::Memfs(Memfs::with_config(config));
+        assert_eq!(vfs.config(), config);
+        assert_eq!(vfs.mode(vfs.root()).unwrap(), 0o40700);
+
+        // Default modes of new entries
+        let dir = vfs.root().mash("dir1/dir2");
+        let file1 = dir.mash("file1");
+        let file2 = dir.mash("file2");
+        assert_vfs_mkdir_p!(vfs, &dir);
+        assert_vfs_write_all!(vfs, &file1, "foo");
+        assert_vfs_write_all!(vfs, &file2, "foofoo");
+        assert_eq!(vfs.mode(&dir).unwrap(), 0o40700);
+        assert_eq!(vfs.mode(&file1).unwrap(), 0o100600);
+        assert!(vfs.mkdir_m(vfs.root().mash("dir3"), 0o750).is_ok());
+        assert_eq!(vfs.mode(vfs.root().mash("dir3")).unwrap(), 0o40750);
+
+        // Entries defaults
+        #[cfg(feature = "regex")]
+        {
+            let regex = regex::Regex::new("foo").unwrap();
+            let iter = vfs.entries(&dir).unwrap().contents_match(regex).into_iter();
+            assert_iter_eq(iter.map(|x| x.unwrap().path_buf()), vec![file1]);
+        }
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn test_stdfs_with_config()
+    {
+        let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs());
+        let config = VfsConfig { max_content_size: 3, ..Default::default() };
+        let stdfs = Vfs::Stdfs(Stdfs::with_config(config));
+        assert_eq!(stdfs.config(), config);
+
+        let file1 = tmpdir.mash("file1");
+        let file2 = tmpdir.mash("file2");
+        assert_vfs_write_all!(vfs, &file1, "foo");
+        assert_vfs_write_all!(vfs, &file2, "foofoo");
+        let regex = regex::Regex::new("foo").unwrap();
+        let iter = stdfs.entries(&tmpdir).unwrap().contents_match(regex).into_iter();
+        assert_iter_eq(iter.map(|x| x.unwrap().path_buf()), vec![file1]);
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+}
@@ -0,0 +1,249 @@
+use std::{
+    fmt,
+    path::Path,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+
+use crate::{errors::RvResult, sys::CancelToken};
+
+/// Provides a snapshot of the metrics collected by an [`OpContext`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct OpMetrics {
+    /// Number of entries processed
+    pub entries: u64,
+
+    /// Number of file content bytes processed
+    pub bytes: u64,
+}
+
+// Shared counters backing the metrics
+#[derive(Debug, Default)]
+struct OpCounters {
+    entries: AtomicU64,
+    bytes: AtomicU64,
+}
+
+// Progress callback invoked with the path just processed and the metrics so far
+type ProgressFn = dyn Fn(&Path, &OpMetrics) + Send + Sync;
+
+/// Provides a thread safe context shared by long running operations for cancellation, progress
+/// reporting and metrics collection
+///
+/// * Clones share the same state so a UI thread can hold one while the operation uses another
+/// * Pass it to an operation's builder via `context` e.g. `Copier::context` or `Entries::context`
+/// * Metrics accumulate across every operation the context is passed to
+///
+/// ### Examples
+/// ```
+/// use rivia::prelude::*;
+///
+/// let vfs = Memfs::new();
+/// let file1 = vfs.root().mash("file1");
+/// let file2 = vfs.root().mash("file2");
+/// assert_vfs_write_all!(vfs, &file1, "this is a test");
+/// let ctx = OpContext::new();
+/// assert!(vfs.copy_b(&file1, &file2).unwrap().context(&ctx).exec().is_ok());
+/// assert_eq!(ctx.metrics(), OpMetrics { entries: 1, bytes: 14 });
+/// ```
+#[derive(Clone, Default)]
+pub struct OpContext {
+    cancel: CancelToken,
+    progress: Option<Arc<ProgressFn>>,
+    counters: Arc<OpCounters>,
+}
+
+impl OpContext {
+    /// Create a new context that isn't cancelled and has no progress callback
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let ctx = OpContext::new();
+    /// assert_eq!(ctx.is_cancelled(), false);
+    /// assert_eq!(ctx.metrics(), OpMetrics::default());
+    /// ```
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Use the given cancellation token rather than the context's own
+    ///
+    /// * Allows a single token to cancel several contexts
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let token = CancelToken::new();
+    /// let ctx = OpContext::new().cancel_token(&token);
+    /// token.cancel();
+    /// assert_eq!(ctx.is_cancelled(), true);
+    /// ```
+    pub fn cancel_token(mut self, token: &CancelToken) -> Self {
+        self.cancel = token.clone();
+        self
+    }
+
+    /// Set the callback invoked after each entry is processed
+    ///
+    /// * Receives the path just processed and the metrics so far
+    /// * May be invoked from whichever thread is running the operation
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// let vfs = Memfs::new();
+    /// let file1 = vfs.root().mash("file1");
+    /// let file2 = vfs.root().mash("file2");
+    /// assert_vfs_mkfile!(vfs, &file1);
+    /// let paths = Arc::new(Mutex::new(vec![]));
+    /// let ctx = OpContext::new().progress({
+    ///     let paths = paths.clone();
+    ///     move |path, _| paths.lock().unwrap().push(path.to_path_buf())
+    /// });
+    /// assert!(vfs.copy_b(&file1, &file2).unwrap().context(&ctx).exec().is_ok());
+    /// assert_eq!(*paths.lock().unwrap(), vec![file1]);
+    /// ```
+    pub fn progress<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&Path, &OpMetrics) + Send + Sync + 'static,
+    {
+        self.progress = Some(Arc::new(f));
+        self
+    }
+
+    /// Signal all operations using this context to stop
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let ctx = OpContext::new();
+    /// ctx.cancel();
+    /// assert_eq!(ctx.is_cancelled(), true);
+    /// ```
+    pub fn cancel(&self) {
+        self.cancel.cancel();
+    }
+
+    /// Returns true if the context has been cancelled
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let ctx = OpContext::new();
+    /// assert_eq!(ctx.is_cancelled(), false);
+    /// ```
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel.is_cancelled()
+    }
+
+    /// Returns a snapshot of the metrics collected so far
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let ctx = OpContext::new();
+    /// assert_eq!(ctx.metrics(), OpMetrics { entries: 0, bytes: 0 });
+    /// ```
+    pub fn metrics(&self) -> OpMetrics {
+        OpMetrics {
+            entries: self.counters.entries.load(Ordering::SeqCst),
+            bytes: self.counters.bytes.load(Ordering::SeqCst),
+        }
+    }
+
+    // Returns a `VfsError::Cancelled` if the context has been cancelled
+    pub(crate) fn check(&self) -> RvResult<()> {
+        self.cancel.check()
+    }
+
+    // Add the given number of content bytes to the metrics
+    pub(crate) fn add_bytes(&self, n: u64) {
+        self.counters.bytes.fetch_add(n, Ordering::SeqCst);
+    }
+
+    // Count the given path as processed and report progress
+    pub(crate) fn add_entry(&self, path: &Path) {
+        self.counters.entries.fetch_add(1, Ordering::SeqCst);
+        if let Some(progress) = &self.progress {
+            progress(path, &self.metrics());
+        }
+    }
+}
+
+impl fmt::Debug for OpContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OpContext")
+            .field("cancel", &self.cancel)
+            .field("progress", &self.progress.is_some())
+            .field("metrics", &self.metrics())
+            .finish()
+    }
+}
+
+// Contexts are only equal when they share the same state
+impl PartialEq for OpContext {
+    fn eq(&self, other: &Self) -> bool {
+        self.cancel == other.cancel
+            && Arc::ptr_eq(&self.counters, &other.counters)
+            && match (&self.progress, &other.progress) {
+                (Some(x), Some(y)) => Arc::ptr_eq(x, y),
+                (None, None) => true,
+                _ => false,
+            }
+    }
+}
+impl Eq for OpContext {}
+
+// Unit tests
+// -------------------------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use crate::prelude::*;
+
+    #[test]
+    fn test_op_context() {
+        let token = CancelToken::new();
+        let ctx = OpContext::new().cancel_token(&token);
+        let clone = ctx.clone();
+        assert_eq!(ctx, clone);
+        assert_ne!(ctx, OpContext::new());
+        assert!(ctx.check().is_ok());
+
+        // Metrics are shared between clones
+        clone.add_bytes(10);
+        clone.add_entry(Path::new("foo"));
+        assert_eq!(ctx.metrics(), OpMetrics { entries: 1, bytes: 10 });
+
+        // Cancelling the token cancels the context
+        token.cancel();
+        assert!(ctx.is_cancelled());
+        assert_eq!(ctx.check().unwrap_err().downcast_ref::<VfsError>(), Some(&VfsError::Cancelled));
+    }
+
+    #[test]
+    fn test_op_context_progress() {
+        let seen = Arc::new(Mutex::new(vec![]));
+        let ctx = OpContext::new().progress({
+            let seen = seen.clone();
+            move |path, metrics| seen.lock().unwrap().push((path.to_path_buf(), *metrics))
+        });
+        ctx.add_bytes(5);
+        ctx.add_entry(Path::new("foo"));
+        ctx.add_entry(Path::new("bar"));
+        let seen = seen.lock().unwrap();
+        assert_eq!(seen[0], (PathBuf::from("foo"), OpMetrics { entries: 1, bytes: 5 }));
+        assert_eq!(seen[1], (PathBuf::from("bar"), OpMetrics { entries: 2, bytes: 5 }));
+    }
+}
@@ -1,6 +1,97 @@
-use std::path::PathBuf;
+use std::{
+    fmt, mem,
+    path::{Component, Path, PathBuf},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
 
-use crate::errors::RvResult;
+use crate::{
+    errors::{RvError, RvResult},
+    sys::{CancelToken, OpContext},
+};
+
+/// Provides the strategies for cloning file data with the Copier `reflink` option
+///
+/// Mirrors the `--reflink` option of GNU `cp` where a reflink shares the source file's data
+/// extents with the copy on filesystems with copy on write support e.g. btrfs or xfs until
+/// either is modified.
+///
+/// ### Examples
+/// ```
+/// use rivia::prelude::*;
+///
+/// let vfs = Memfs::new();
+/// let file1 = vfs.root().mash("file1");
+/// let file2 = vfs.root().mash("file2");
+/// assert_vfs_write_all!(vfs, &file1, "file1");
+/// assert!(vfs.copy_b(&file1, &file2).unwrap().reflink(Reflink::Auto).exec().is_ok());
+/// assert_vfs_read_all!(vfs, &file2, "file1");
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Reflink
+{
+    Auto,   // reflink when supported falling back on a regular copy
+    Always, // reflink failing when not supported
+    #[default]
+    Never, // always make a regular copy
+}
+
+/// Describes what a copy actually did as returned by the Copier `exec_report`
+///
+/// * Destination paths are reported for the entries created
+/// * Source paths are reported for the entries skipped and the failures collected
+///
+/// ### Examples
+/// ```
+/// use rivia::prelude::*;
+///
+/// let vfs = Memfs::new();
+/// let dir1 = vfs.root().mash("dir1");
+/// let file1 = dir1.mash("file1");
+/// let dir2 = vfs.root().mash("dir2");
+/// assert_vfs_mkdir_p!(vfs, &dir1);
+/// assert_vfs_write_all!(vfs, &file1, "foobar");
+/// let report = vfs.copy_b(&dir1, &dir2).unwrap().exec_report().unwrap();
+/// assert_eq!(report.dirs, vec![dir2.clone()]);
+/// assert_eq!(report.copied, vec![dir2.mash("file1")]);
+/// assert_eq!(report.bytes, 6);
+/// ```
+#[derive(Debug, Default)]
+pub struct CopyReport
+{
+    pub copied: Vec<PathBuf>,            // files whose data was copied
+    pub cloned: Vec<PathBuf>,            // files whose data was cloned as a reflink
+    pub linked: Vec<PathBuf>,            // files hard linked to their source
+    pub symlinks: Vec<PathBuf>,          // links recreated rather than followed
+    pub dirs: Vec<PathBuf>,              // directories created
+    pub skipped: Vec<PathBuf>,           // source entries left uncopied e.g. stripped or flattened away
+    pub bytes: u64,                      // bytes of file data copied or cloned
+    pub duration: Duration,              // time the copy took
+    pub errors: Vec<(PathBuf, RvError)>, // failures collected when continuing on error
+}
+
+impl CopyReport
+{
+    /// Returns the number of files copied, cloned or hard linked
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Memfs::new();
+    /// let file1 = vfs.root().mash("file1");
+    /// let file2 = vfs.root().mash("file2");
+    /// assert_vfs_write_all!(vfs, &file1, "foobar");
+    /// assert_eq!(vfs.copy_b(&file1, &file2).unwrap().exec_report().unwrap().files(), 1);
+    /// ```
+    pub fn files(&self) -> usize
+    {
+        self.copied.len() + self.cloned.len() + self.linked.len()
+    }
+}
 
 /// Provides a builder pattern for flexibly copying files
 ///
@@ -28,16 +119,198 @@ pub struct Copier
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub(crate) struct CopyOpts
 {
-    pub(crate) src: PathBuf,      // source file
-    pub(crate) dst: PathBuf,      // destination path
-    pub(crate) mode: Option<u32>, // mode to use
-    pub(crate) cdirs: bool,       // chmod only dirs when true
-    pub(crate) cfiles: bool,      // chmod only files when true
-    pub(crate) follow: bool,      // follow links when copying files
+    pub(crate) src: PathBuf,                   // source file
+    pub(crate) dst: PathBuf,                   // destination path
+    pub(crate) mode: Option<u32>,              // mode to use
+    pub(crate) cdirs: bool,                    // chmod only dirs when true
+    pub(crate) cfiles: bool,                   // chmod only files when true
+    pub(crate) follow: bool,                   // follow links when copying files
+    pub(crate) link: bool,                     // hard link files rather than copying their data
+    pub(crate) reflink: Reflink,               // strategy for cloning file data
+    pub(crate) rewrite: bool,                  // retarget links pointing inside the source into the destination
+    pub(crate) deref: bool,                    // copy the targets of links pointing outside the source
+    pub(crate) cont: bool,                     // collect failures and continue rather than aborting
+    pub(crate) ctx: Option<OpContext>,         // context for cancellation, progress and metrics
+    pub(crate) flatten: bool,                  // copy files directly into the destination skipping dirs
+    pub(crate) strip: usize,                   // leading path components to strip from destination paths
+    pub(crate) context: bool,                  // preserve the SELinux security context of each entry
+    pub(crate) progress: Option<CopyProgress>, // callback reporting the bytes copied
+    pub(crate) report: Option<CopyRecorder>,   // report of the entries copied
+}
+
+// Progress callback invoked with the bytes copied so far, the total bytes to copy and the file
+// just copied
+type CopyProgressFn = dyn Fn(u64, u64, &Path) + Send + Sync;
+
+// Tracks the bytes copied against the total bytes to copy for reporting progress. Clones share the
+// same counters so that nested copies of dereferenced links add to the same totals.
+#[derive(Clone)]
+pub(crate) struct CopyProgress
+{
+    f: Arc<CopyProgressFn>, // callback to report progress to
+    copied: Arc<AtomicU64>, // bytes copied so far
+    total: Arc<AtomicU64>,  // bytes to copy in total
+}
+
+impl CopyProgress
+{
+    // Add the given number of bytes to the total bytes to copy
+    pub(crate) fn add_total(&self, n: u64)
+    {
+        self.total.fetch_add(n, Ordering::SeqCst);
+    }
+
+    // Reset the counters for a new copy
+    pub(crate) fn reset(&self)
+    {
+        self.copied.store(0, Ordering::SeqCst);
+        self.total.store(0, Ordering::SeqCst);
+    }
+
+    // Count the given file's bytes as copied and report progress
+    pub(crate) fn add_file(&self, path: &Path, n: u64)
+    {
+        let copied = self.copied.fetch_add(n, Ordering::SeqCst) + n;
+        (self.f)(copied, self.total.load(Ordering::SeqCst), path);
+    }
+}
+
+impl fmt::Debug for CopyProgress
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        f.debug_struct("CopyProgress")
+            .field("copied", &self.copied.load(Ordering::SeqCst))
+            .field("total", &self.total.load(Ordering::SeqCst))
+            .finish()
+    }
+}
+
+// Progress is only equal when it shares the same state
+impl PartialEq for CopyProgress
+{
+    fn eq(&self, other: &Self) -> bool
+    {
+        Arc::ptr_eq(&self.f, &other.f) && Arc::ptr_eq(&self.copied, &other.copied)
+    }
+}
+impl Eq for CopyProgress {}
+
+// Collects the report of a copy. Clones share the same report so that nested copies of
+// dereferenced links add to it as well.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct CopyRecorder(Arc<Mutex<CopyReport>>);
+
+impl CopyRecorder
+{
+    // Take the report collected so far leaving an empty one in its place
+    fn take(&self) -> CopyReport
+    {
+        mem::take(&mut *self.0.lock().unwrap())
+    }
+}
+
+// Recorders are only equal when they share the same report
+impl PartialEq for CopyRecorder
+{
+    fn eq(&self, other: &Self) -> bool
+    {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+impl Eq for CopyRecorder {}
+
+impl CopyOpts
+{
+    // Update the report with the given function when one is being collected
+    pub(crate) fn record<F: FnOnce(&mut CopyReport)>(&self, f: F)
+    {
+        if let Some(report) = &self.report {
+            f(&mut report.0.lock().unwrap());
+        }
+    }
+
+    // Map the given source path, relative to the copy's source base, to its destination path under
+    // `dst_root` applying the `flatten` and `strip` options. Returns `None` when all of the path's
+    // components have been stripped off.
+    pub(crate) fn dst_of(&self, dst_root: &Path, rel: &Path) -> Option<PathBuf>
+    {
+        let names: Vec<_> = rel
+            .components()
+            .filter_map(|x| match x {
+                Component::Normal(x) => Some(x),
+                _ => None,
+            })
+            .collect();
+        if names.is_empty() {
+            return Some(dst_root.to_path_buf());
+        }
+        if self.flatten {
+            return names.last().map(|x| dst_root.join(x));
+        }
+        if names.len() <= self.strip {
+            return None;
+        }
+        Some(names[self.strip..].iter().fold(dst_root.to_path_buf(), |path, x| path.join(x)))
+    }
 }
 
 impl Copier
 {
+    /// Set a token that can be used to cancel the copy from elsewhere e.g. a UI thread
+    ///
+    /// * Default: no token
+    /// * Checked between entries and between chunks of large files
+    /// * Once cancelled the copy stops with a `VfsError::Cancelled` leaving any entries already
+    ///   copied in place but never a partially written file
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Memfs::new();
+    /// let file1 = vfs.root().mash("file1");
+    /// let file2 = vfs.root().mash("file2");
+    /// assert_vfs_mkfile!(vfs, &file1);
+    /// let token = CancelToken::new();
+    /// token.cancel();
+    /// let err = vfs.copy_b(&file1, &file2).unwrap().cancel_token(&token).exec().unwrap_err();
+    /// assert_eq!(err.downcast_ref::<VfsError>(), Some(&VfsError::Cancelled));
+    /// assert_vfs_no_exists!(vfs, &file2);
+    /// ```
+    pub fn cancel_token(mut self, token: &CancelToken) -> Self
+    {
+        self.opts.ctx = Some(self.opts.ctx.take().unwrap_or_default().cancel_token(token));
+        self
+    }
+
+    /// Set the context used to cancel the copy, report progress and collect metrics
+    ///
+    /// * Default: no context
+    /// * Replaces any token previously set with `cancel_token`
+    /// * Each entry copied is counted and reported to the context's progress callback along with
+    ///   the bytes of file content copied
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Memfs::new();
+    /// let dir1 = vfs.root().mash("dir1");
+    /// let file1 = dir1.mash("file1");
+    /// let dir2 = vfs.root().mash("dir2");
+    /// assert_vfs_mkdir_p!(vfs, &dir1);
+    /// assert_vfs_write_all!(vfs, &file1, "this is a test");
+    /// let ctx = OpContext::new();
+    /// assert!(vfs.copy_b(&dir1, &dir2).unwrap().context(&ctx).exec().is_ok());
+    /// assert_eq!(ctx.metrics(), OpMetrics { entries: 2, bytes: 14 });
+    /// ```
+    pub fn context(mut self, ctx: &OpContext) -> Self
+    {
+        self.opts.ctx = Some(ctx.clone());
+        self
+    }
+
     /// Apply chmod to all files and directories
     ///
     /// ### Examples
@@ -118,6 +391,90 @@ impl Copier
         self
     }
 
+    /// Continue on past failures collecting them rather than aborting on the first one
+    ///
+    /// * Default: false
+    /// * Failures are returned once the traversal completes as a single [`BatchError`]
+    ///
+    /// [`BatchError`]: crate::errors::BatchError
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Memfs::new();
+    /// let dir1 = vfs.root().mash("dir1");
+    /// let file1 = dir1.mash("file1");
+    /// let link1 = dir1.mash("link1");
+    /// let dir2 = vfs.root().mash("dir2");
+    /// assert_vfs_mkdir_p!(vfs, &dir1);
+    /// assert_vfs_mkfile!(vfs, &file1);
+    /// assert_vfs_symlink!(vfs, &link1, &dir1);
+    /// let err = vfs.copy_b(&dir1, &dir2).unwrap().follow(true).continue_on_error().exec().unwrap_err();
+    /// assert_eq!(err.downcast_ref::<BatchError>().unwrap().len(), 1);
+    /// assert_vfs_is_file!(vfs, dir2.mash("file1"));
+    /// ```
+    pub fn continue_on_error(mut self) -> Self
+    {
+        self.opts.cont = true;
+        self
+    }
+
+    /// Update the `dereference_external` option
+    ///
+    /// * Default: false
+    /// * When `true` links pointing outside the source tree are replaced with a copy of their target
+    /// * Has no effect when `follow` is set as all links are already being followed
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Memfs::new();
+    /// let file1 = vfs.root().mash("file1");
+    /// let dir1 = vfs.root().mash("dir1");
+    /// let link1 = dir1.mash("link1");
+    /// let dir2 = vfs.root().mash("dir2");
+    /// let link2 = dir2.mash("link1");
+    /// assert_vfs_write_all!(vfs, &file1, "file1");
+    /// assert_vfs_mkdir_p!(vfs, &dir1);
+    /// assert_vfs_symlink!(vfs, &link1, &file1);
+    /// assert!(vfs.copy_b(&dir1, &dir2).unwrap().dereference_external(true).exec().is_ok());
+    /// assert_vfs_no_symlink!(vfs, &link2);
+    /// assert_vfs_read_all!(vfs, &link2, "file1");
+    /// ```
+    pub fn dereference_external(mut self, yes: bool) -> Self
+    {
+        self.opts.deref = yes;
+        self
+    }
+
+    /// Copy files directly into the destination without recreating the directories above them
+    ///
+    /// * Default: false
+    /// * Directories other than the destination itself are skipped
+    /// * Files with the same name overwrite each other in traversal order
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Memfs::new();
+    /// let dir1 = vfs.root().mash("dir1");
+    /// let file1 = dir1.mash("a/b/file1");
+    /// let dir2 = vfs.root().mash("dir2");
+    /// assert_vfs_mkdir_p!(vfs, file1.dir().unwrap());
+    /// assert_vfs_write_all!(vfs, &file1, "file1");
+    /// assert!(vfs.copy_b(&dir1, &dir2).unwrap().flatten().exec().is_ok());
+    /// assert_vfs_read_all!(vfs, dir2.mash("file1"), "file1");
+    /// assert_vfs_no_exists!(vfs, dir2.mash("a"));
+    /// ```
+    pub fn flatten(mut self) -> Self
+    {
+        self.opts.flatten = true;
+        self
+    }
+
     /// Update the `follow` option
     ///
     /// * Default: false
@@ -143,6 +500,184 @@ impl Copier
         self
     }
 
+    /// Update the `link` option
+    ///
+    /// * Default: false
+    /// * When `true` files are hard linked into the destination rather than having their data
+    ///   copied much like `cp -l` falling back on a copy when crossing devices
+    /// * Directories are still created and links still recreated as directed by the other options
+    /// * Linked files share the source's data and metadata so `chmod_*` and `preserve_context`
+    ///   don't apply to them as that would change the source as well
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Memfs::new();
+    /// let file1 = vfs.root().mash("file1");
+    /// let file2 = vfs.root().mash("file2");
+    /// assert_vfs_write_all!(vfs, &file1, "file1");
+    /// assert!(vfs.copy_b(&file1, &file2).unwrap().link(true).exec().is_ok());
+    /// assert_eq!(vfs.same_file(&file1, &file2), true);
+    /// assert_vfs_read_all!(vfs, &file2, "file1");
+    /// ```
+    pub fn link(mut self, yes: bool) -> Self
+    {
+        self.opts.link = yes;
+        self
+    }
+
+    /// Preserve the SELinux security context of each entry copied
+    ///
+    /// * Default: false
+    /// * Entries without a context in the source are left with the default for the destination
+    /// * Links that are recreated rather than followed carry over the context of the link itself
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Memfs::new();
+    /// let file1 = vfs.root().mash("file1");
+    /// let file2 = vfs.root().mash("file2");
+    /// assert_vfs_mkfile!(vfs, &file1);
+    /// assert!(vfs.set_selinux_context(&file1, "system_u:object_r:etc_t:s0").is_ok());
+    /// assert!(vfs.copy_b(&file1, &file2).unwrap().preserve_context().exec().is_ok());
+    /// assert_eq!(vfs.get_selinux_context(&file2).unwrap().unwrap(), "system_u:object_r:etc_t:s0");
+    /// ```
+    #[cfg(feature = "selinux")]
+    pub fn preserve_context(mut self) -> Self
+    {
+        self.opts.context = true;
+        self
+    }
+
+    /// Report progress to the given callback after each file is copied
+    ///
+    /// * Default: no callback
+    /// * Called with the bytes copied so far, the total bytes to copy and the file just copied
+    /// * The total is summed up from the source tree before copying begins with the targets of
+    ///   dereferenced links being added as they are encountered
+    /// * Directories and links being recreated aren't reported as they have no content to copy
+    /// * Combine with `cancel_token` to abort long copies from a UI
+    ///
+    /// ### Examples
+    /// ```
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Memfs::new();
+    /// let dir1 = vfs.root().mash("dir1");
+    /// let file1 = dir1.mash("file1");
+    /// let file2 = dir1.mash("file2");
+    /// assert_vfs_mkdir_p!(vfs, &dir1);
+    /// assert_vfs_write_all!(vfs, &file1, "foo");
+    /// assert_vfs_write_all!(vfs, &file2, "bar");
+    /// let seen = Arc::new(Mutex::new(vec![]));
+    /// let copier = vfs.copy_b(&dir1, vfs.root().mash("dir2")).unwrap().progress({
+    ///     let seen = seen.clone();
+    ///     move |copied, total, _| seen.lock().unwrap().push((copied, total))
+    /// });
+    /// assert!(copier.exec().is_ok());
+    /// assert_eq!(*seen.lock().unwrap(), vec![(3, 6), (6, 6)]);
+    /// ```
+    pub fn progress<F>(mut self, f: F) -> Self
+    where
+        F: Fn(u64, u64, &Path) + Send + Sync + 'static,
+    {
+        self.opts.progress =
+            Some(CopyProgress { f: Arc::new(f), copied: Default::default(), total: Default::default() });
+        self
+    }
+
+    /// Update the `reflink` option
+    ///
+    /// * Default: Reflink::Never
+    /// * `Reflink::Auto` clones the data of files when the filesystem supports it falling back
+    ///   on a regular copy otherwise
+    /// * `Reflink::Always` clones the data of files failing when the filesystem doesn't support it
+    /// * Memfs always clones the data of files so every strategy succeeds
+    /// * The `link` option takes precedence as there is no data to clone when hard linking
+    ///
+    /// ### Errors
+    /// * VfsError::ReflinkUnsupported(PathBuf) when `Reflink::Always` is given and the file can't
+    ///   be cloned e.g. the filesystem lacks copy on write support or the copy crosses devices
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Memfs::new();
+    /// let file1 = vfs.root().mash("file1");
+    /// let file2 = vfs.root().mash("file2");
+    /// assert_vfs_write_all!(vfs, &file1, "file1");
+    /// assert!(vfs.copy_b(&file1, &file2).unwrap().reflink(Reflink::Always).exec().is_ok());
+    /// assert_vfs_read_all!(vfs, &file2, "file1");
+    /// ```
+    pub fn reflink(mut self, reflink: Reflink) -> Self
+    {
+        self.opts.reflink = reflink;
+        self
+    }
+
+    /// Update the `rewrite_links` option
+    ///
+    /// * Default: false
+    /// * When `true` links pointing inside the source tree are retargeted to the same location
+    ///   inside the destination tree rather than continuing to point back into the source
+    /// * Has no effect when `follow` is set as all links are already being followed
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Memfs::new();
+    /// let dir1 = vfs.root().mash("dir1");
+    /// let file1 = dir1.mash("file1");
+    /// let link1 = dir1.mash("link1");
+    /// let dir2 = vfs.root().mash("dir2");
+    /// let file2 = dir2.mash("file1");
+    /// let link2 = dir2.mash("link1");
+    /// assert_vfs_mkdir_p!(vfs, &dir1);
+    /// assert_vfs_mkfile!(vfs, &file1);
+    /// assert_vfs_symlink!(vfs, &link1, &file1);
+    /// assert!(vfs.copy_b(&dir1, &dir2).unwrap().rewrite_links(true).exec().is_ok());
+    /// assert_vfs_readlink_abs!(vfs, &link2, &file2);
+    /// ```
+    pub fn rewrite_links(mut self, yes: bool) -> Self
+    {
+        self.opts.rewrite = yes;
+        self
+    }
+
+    /// Strip the given number of leading components from the destination paths much like `tar`
+    ///
+    /// * Default: 0
+    /// * Applies to the paths as they would otherwise be created beneath the destination
+    /// * Entries with no components left are skipped e.g. the stripped directories themselves
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Memfs::new();
+    /// let dir1 = vfs.root().mash("pkg");
+    /// let file1 = dir1.mash("root/usr/bin/file1");
+    /// let dir2 = vfs.root().mash("usr/bin");
+    /// assert_vfs_mkdir_p!(vfs, file1.dir().unwrap());
+    /// assert_vfs_mkdir_p!(vfs, &dir2);
+    /// assert_vfs_write_all!(vfs, &file1, "file1");
+    /// assert!(vfs.copy_b(&dir1, &dir2).unwrap().strip_components(4).exec().is_ok());
+    /// assert_vfs_read_all!(vfs, dir2.mash("file1"), "file1");
+    /// assert_vfs_no_exists!(vfs, dir2.mash("pkg"));
+    /// ```
+    pub fn strip_components(mut self, n: usize) -> Self
+    {
+        self.opts.strip = n;
+        self
+    }
+
     /// Execute the [`Copier`] builder current options.
     ///
     /// ### Examples
@@ -158,8 +693,53 @@ impl Copier
     /// ```
     pub fn exec(&self) -> RvResult<()>
     {
+        if let Some(progress) = &self.opts.progress {
+            progress.reset();
+        }
         (self.exec)(self.opts.clone())
     }
+
+    /// Execute the [`Copier`] builder current options returning a report of what was copied
+    ///
+    /// * Reports the files copied, cloned and hard linked along with the links recreated, the
+    ///   directories created, the entries skipped, the bytes copied and the time taken
+    /// * See [`CopyReport`] for details
+    /// * When continuing on error the failures are returned in the report's `errors` rather than
+    ///   as a `BatchError`
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Memfs::new();
+    /// let file1 = vfs.root().mash("file1");
+    /// let file2 = vfs.root().mash("file2");
+    /// let file3 = vfs.root().mash("file3");
+    /// assert_vfs_write_all!(vfs, &file1, "foobar");
+    /// let report = vfs.copy_b(&file1, &file2).unwrap().exec_report().unwrap();
+    /// assert_eq!(report.copied, vec![file2]);
+    /// let report = vfs.copy_b(&file1, &file3).unwrap().link(true).exec_report().unwrap();
+    /// assert_eq!(report.linked, vec![file3]);
+    /// ```
+    pub fn exec_report(&self) -> RvResult<CopyReport>
+    {
+        if let Some(progress) = &self.opts.progress {
+            progress.reset();
+        }
+        let recorder = CopyRecorder::default();
+        let start = Instant::now();
+        let result = (self.exec)(CopyOpts { report: Some(recorder.clone()), ..self.opts.clone() });
+        let mut report = recorder.take();
+        report.duration = start.elapsed();
+        match result {
+            Ok(()) => Ok(report),
+            Err(RvError::Batch(err)) => {
+                report.errors = err.into_errors();
+                Ok(report)
+            },
+            Err(err) => Err(err),
+        }
+    }
 }
 
 // Unit tests
@@ -167,6 +747,8 @@ impl Copier
 #[cfg(test)]
 mod tests
 {
+    use std::sync::{Arc, Mutex};
+
     use crate::prelude::*;
 
     #[test]
@@ -202,6 +784,184 @@ mod tests
         assert_vfs_remove_all!(vfs, &tmpdir);
     }
 
+    #[test]
+    fn test_vfs_copy_continue_on_error()
+    {
+        test_copy_continue_on_error(assert_vfs_setup!(Vfs::memfs()));
+        test_copy_continue_on_error(assert_vfs_setup!(Vfs::stdfs()));
+    }
+    fn test_copy_continue_on_error((vfs, tmpdir): (Vfs, PathBuf))
+    {
+        let dir1 = tmpdir.mash("dir1");
+        let file1 = dir1.mash("file1");
+        let link1 = dir1.mash("link1");
+        let file2 = dir1.mash("zfile2");
+        let dir2 = tmpdir.mash("dir2");
+        let dir3 = tmpdir.mash("dir3");
+        assert_vfs_mkdir_p!(vfs, &dir1);
+        assert_vfs_write_all!(vfs, &file1, "file1");
+        assert_vfs_symlink!(vfs, &link1, &dir1);
+        assert_vfs_write_all!(vfs, &file2, "file2");
+
+        // Abort on the looping link by default
+        let err = vfs.copy_b(&dir1, &dir2).unwrap().follow(true).exec().unwrap_err();
+        assert_eq!(err.downcast_ref::<PathError>(), Some(&PathError::link_looping(&dir1)));
+
+        // Collect the failure and carry on with the rest of the tree
+        let err = vfs.copy_b(&dir1, &dir3).unwrap().follow(true).continue_on_error().exec().unwrap_err();
+        let err = err.downcast_ref::<BatchError>().unwrap();
+        assert_eq!(err.len(), 1);
+        assert_eq!(err.errors()[0].1.downcast_ref::<PathError>(), Some(&PathError::link_looping(&dir1)));
+        assert_vfs_read_all!(vfs, dir3.mash("file1"), "file1");
+        assert_vfs_read_all!(vfs, dir3.mash("zfile2"), "file2");
+
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+
+    #[test]
+    fn test_vfs_copy_cancel()
+    {
+        test_copy_cancel(assert_vfs_setup!(Vfs::memfs()));
+        test_copy_cancel(assert_vfs_setup!(Vfs::stdfs()));
+    }
+    fn test_copy_cancel((vfs, tmpdir): (Vfs, PathBuf))
+    {
+        let dir1 = tmpdir.mash("dir1");
+        let file1 = dir1.mash("file1");
+        let dir2 = tmpdir.mash("dir2");
+        let dir3 = tmpdir.mash("dir3");
+        assert_vfs_mkdir_p!(vfs, &dir1);
+        assert_vfs_write_all!(vfs, &file1, "file1");
+
+        // Cancelled before starting
+        let token = CancelToken::new();
+        token.cancel();
+        let err = vfs.copy_b(&dir1, &dir2).unwrap().cancel_token(&token).exec().unwrap_err();
+        assert_eq!(err.downcast_ref::<VfsError>(), Some(&VfsError::Cancelled));
+        assert_vfs_no_exists!(vfs, &dir2);
+
+        // Cancellation isn't collected when continuing on error
+        let err = vfs.copy_b(&dir1, &dir2).unwrap().cancel_token(&token).continue_on_error().exec().unwrap_err();
+        assert_eq!(err.downcast_ref::<VfsError>(), Some(&VfsError::Cancelled));
+
+        // Not cancelled
+        let token = CancelToken::new();
+        assert!(vfs.copy_b(&dir1, &dir3).unwrap().cancel_token(&token).exec().is_ok());
+        assert_vfs_read_all!(vfs, dir3.mash("file1"), "file1");
+
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+
+    #[test]
+    fn test_vfs_copy_context()
+    {
+        test_copy_context(assert_vfs_setup!(Vfs::memfs()));
+        test_copy_context(assert_vfs_setup!(Vfs::stdfs()));
+    }
+    fn test_copy_context((vfs, tmpdir): (Vfs, PathBuf))
+    {
+        let dir1 = tmpdir.mash("dir1");
+        let file1 = dir1.mash("file1");
+        let link1 = dir1.mash("link1");
+        let dir2 = tmpdir.mash("dir2");
+        let dir3 = tmpdir.mash("dir3");
+        assert_vfs_mkdir_p!(vfs, &dir1);
+        assert_vfs_write_all!(vfs, &file1, "file1");
+        assert_vfs_symlink!(vfs, &link1, "file1");
+
+        // Progress is reported for every entry copied
+        let paths = Arc::new(Mutex::new(vec![]));
+        let ctx = OpContext::new().progress({
+            let paths = paths.clone();
+            move |path, _| paths.lock().unwrap().push(path.to_path_buf())
+        });
+        assert!(vfs.copy_b(&dir1, &dir2).unwrap().context(&ctx).exec().is_ok());
+        let mut paths = paths.lock().unwrap().clone();
+        paths.sort();
+        assert_eq!(paths, vec![dir1.clone(), file1.clone(), link1.clone()]);
+        assert_eq!(ctx.metrics(), OpMetrics { entries: 3, bytes: 5 });
+
+        // Metrics accumulate across operations
+        assert!(vfs.copy_b(&dir1, &dir3).unwrap().context(&ctx).exec().is_ok());
+        assert_eq!(ctx.metrics(), OpMetrics { entries: 6, bytes: 10 });
+
+        // Cancelling the context stops the copy
+        ctx.cancel();
+        let err = vfs.copy_b(&dir1, tmpdir.mash("dir4")).unwrap().context(&ctx).exec().unwrap_err();
+        assert_eq!(err.downcast_ref::<VfsError>(), Some(&VfsError::Cancelled));
+
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+
+    #[test]
+    fn test_vfs_copy_progress()
+    {
+        test_copy_progress(assert_vfs_setup!(Vfs::memfs()));
+        test_copy_progress(assert_vfs_setup!(Vfs::stdfs()));
+    }
+    fn test_copy_progress((vfs, tmpdir): (Vfs, PathBuf))
+    {
+        let dir1 = tmpdir.mash("dir1");
+        let file1 = dir1.mash("file1");
+        let file2 = dir1.mash("sub/file2");
+        let link1 = dir1.mash("link1");
+        let file3 = tmpdir.mash("file3");
+        assert_vfs_mkdir_p!(vfs, dir1.mash("sub"));
+        assert_vfs_write_all!(vfs, &file1, "foo");
+        assert_vfs_write_all!(vfs, &file2, "foobar");
+        assert_vfs_write_all!(vfs, &file3, "foobarbaz");
+        assert_vfs_symlink!(vfs, &link1, &file3);
+
+        // Files are reported as they are copied against the total
+        let seen = Arc::new(Mutex::new(vec![]));
+        let progress = {
+            let seen = seen.clone();
+            move |copied, total, path: &Path| seen.lock().unwrap().push((copied, total, path.to_path_buf()))
+        };
+        let copier = vfs.copy_b(&dir1, tmpdir.mash("dir2")).unwrap().progress(progress);
+        assert!(copier.exec().is_ok());
+        let mut seen = seen.lock().unwrap().drain(..).collect::<Vec<_>>();
+        seen.sort_by_key(|x| x.0);
+        assert_eq!(seen.len(), 2);
+        assert_eq!(seen.last().unwrap().0, 9);
+        assert!(seen.iter().all(|x| x.1 == 9));
+        let mut paths = seen.into_iter().map(|x| x.2).collect::<Vec<_>>();
+        paths.sort();
+        assert_eq!(paths, vec![file1.clone(), file2.clone()]);
+
+        // Following links and dereferencing external links add their targets to the total
+        for copier in [
+            vfs.copy_b(&dir1, tmpdir.mash("dir3")).unwrap().follow(true),
+            vfs.copy_b(&dir1, tmpdir.mash("dir4")).unwrap().dereference_external(true),
+        ] {
+            let totals = Arc::new(Mutex::new(vec![]));
+            let copier = copier.progress({
+                let totals = totals.clone();
+                move |copied, total, _| totals.lock().unwrap().push((copied, total))
+            });
+            assert!(copier.exec().is_ok());
+            assert_eq!(totals.lock().unwrap().last(), Some(&(18, 18)));
+
+            // Executing again starts over
+            assert!(copier.exec().is_ok());
+            assert_eq!(totals.lock().unwrap().len(), 6);
+            assert_eq!(totals.lock().unwrap().last(), Some(&(18, 18)));
+        }
+
+        // Cancelling from the callback stops the copy after the current file
+        let token = CancelToken::new();
+        let dir5 = tmpdir.mash("dir5");
+        let copier = vfs.copy_b(&dir1, &dir5).unwrap().cancel_token(&token).progress({
+            let token = token.clone();
+            move |_, _, _| token.cancel()
+        });
+        let err = copier.exec().unwrap_err();
+        assert_eq!(err.downcast_ref::<VfsError>(), Some(&VfsError::Cancelled));
+        assert_eq!(vfs.all_files(&dir5).unwrap().iter().filter(|x| !vfs.is_symlink(x)).count(), 1);
+
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+
     #[test]
     fn test_vfs_copy_file()
     {
@@ -462,4 +1222,350 @@ mod tests
 
         assert_vfs_remove_all!(vfs, &tmpdir);
     }
+
+    #[test]
+    fn test_vfs_copy_flatten()
+    {
+        test_copy_flatten(assert_vfs_setup!(Vfs::memfs()));
+        test_copy_flatten(assert_vfs_setup!(Vfs::stdfs()));
+    }
+    fn test_copy_flatten((vfs, tmpdir): (Vfs, PathBuf))
+    {
+        let dir1 = tmpdir.mash("dir1");
+        let file1 = dir1.mash("file1");
+        let file2 = dir1.mash("a/file2");
+        let file3 = dir1.mash("a/b/file3");
+        let dir2 = tmpdir.mash("dir2");
+        let dir3 = tmpdir.mash("dir3");
+        let file4 = tmpdir.mash("file4");
+        assert_vfs_mkdir_p!(vfs, file3.dir().unwrap());
+        assert_vfs_write_all!(vfs, &file1, "file1");
+        assert_vfs_write_all!(vfs, &file2, "file2");
+        assert_vfs_write_all!(vfs, &file3, "file3");
+
+        // Copy to a new directory
+        assert!(vfs.copy_b(&dir1, &dir2).unwrap().flatten().exec().is_ok());
+        let mut paths = vfs.paths(&dir2).unwrap();
+        paths.sort();
+        assert_eq!(paths, vec![dir2.mash("file1"), dir2.mash("file2"), dir2.mash("file3")]);
+
+        // Copy into an existing directory
+        assert_vfs_mkdir_p!(vfs, &dir3);
+        assert!(vfs.copy_b(&dir1, &dir3).unwrap().flatten().exec().is_ok());
+        let mut paths = vfs.paths(&dir3).unwrap();
+        paths.sort();
+        assert_eq!(paths, vec![dir3.mash("file1"), dir3.mash("file2"), dir3.mash("file3")]);
+        assert_vfs_read_all!(vfs, dir3.mash("file3"), "file3");
+
+        // Single files are copied as usual
+        assert!(vfs.copy_b(&file2, &file4).unwrap().flatten().exec().is_ok());
+        assert_vfs_read_all!(vfs, &file4, "file2");
+
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+
+    #[test]
+    fn test_vfs_copy_link()
+    {
+        test_copy_link(assert_vfs_setup!(Vfs::memfs()));
+        test_copy_link(assert_vfs_setup!(Vfs::stdfs()));
+    }
+    fn test_copy_link((vfs, tmpdir): (Vfs, PathBuf))
+    {
+        let dir1 = tmpdir.mash("dir1");
+        let file1 = dir1.mash("file1");
+        let file2 = dir1.mash("a/file2");
+        let link1 = dir1.mash("link1");
+        let dir2 = tmpdir.mash("dir2");
+        let file3 = tmpdir.mash("file3");
+        assert_vfs_mkdir_p!(vfs, file2.dir().unwrap());
+        assert_vfs_write_all!(vfs, &file1, "file1");
+        assert_vfs_write_all!(vfs, &file2, "file2");
+        assert_vfs_symlink!(vfs, &link1, "file1");
+        assert!(vfs.chmod(&file1, 0o644).is_ok());
+
+        // Files are linked while dirs are created and links recreated
+        let copier = vfs.copy_b(&dir1, &dir2).unwrap().link(true).chmod_files(0o600);
+        assert!(copier.exec().is_ok());
+        assert!(vfs.same_file(&file1, dir2.mash("file1")));
+        assert!(vfs.same_file(&file2, dir2.mash("a/file2")));
+        assert_vfs_is_hardlink!(vfs, dir2.mash("file1"));
+        assert_vfs_is_dir!(vfs, dir2.mash("a"));
+        assert_vfs_is_symlink!(vfs, dir2.mash("link1"));
+        assert_eq!(vfs.mode(&file1).unwrap(), 0o100644);
+
+        // Linked files share their data
+        vfs.write_all(&file1, "foobar").unwrap();
+        assert_vfs_read_all!(vfs, dir2.mash("file1"), "foobar");
+
+        // Existing destination files are replaced
+        assert!(vfs.copy_b(&file2, &file3).unwrap().exec().is_ok());
+        assert!(!vfs.same_file(&file2, &file3));
+        assert!(vfs.copy_b(&file1, &file3).unwrap().link(true).exec().is_ok());
+        assert!(vfs.same_file(&file1, &file3));
+        assert_vfs_read_all!(vfs, &file3, "foobar");
+
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+
+    #[cfg(feature = "selinux")]
+    #[test]
+    fn test_vfs_copy_preserve_context()
+    {
+        test_copy_preserve_context(assert_vfs_setup!(Vfs::memfs()));
+        test_copy_preserve_context(assert_vfs_setup!(Vfs::stdfs()));
+    }
+    #[cfg(feature = "selinux")]
+    fn test_copy_preserve_context((vfs, tmpdir): (Vfs, PathBuf))
+    {
+        let dir1 = tmpdir.mash("dir1");
+        let file1 = dir1.mash("file1");
+        let link1 = dir1.mash("link1");
+        let dir2 = tmpdir.mash("dir2");
+        let dir3 = tmpdir.mash("dir3");
+        let etc = "system_u:object_r:etc_t:s0";
+        let link = "system_u:object_r:link_t:s0";
+        let var = "system_u:object_r:var_t:s0";
+        assert_vfs_mkdir_p!(vfs, &dir1);
+        assert_vfs_mkfile!(vfs, &file1);
+        assert_vfs_symlink!(vfs, &link1, "file1");
+
+        // Doesn't exist
+        let missing = tmpdir.mash("missing");
+        assert_eq!(
+            vfs.get_selinux_context(&missing).unwrap_err().downcast_ref::<PathError>(),
+            Some(&PathError::does_not_exist(&missing))
+        );
+        assert_eq!(
+            vfs.set_selinux_context(&missing, etc).unwrap_err().downcast_ref::<PathError>(),
+            Some(&PathError::does_not_exist(&missing))
+        );
+
+        // Labeling requires a filesystem with security attribute support
+        if vfs.set_selinux_context(&dir1, var).is_err() {
+            assert_vfs_remove_all!(vfs, &tmpdir);
+            return;
+        }
+        assert!(vfs.set_selinux_context(&file1, etc).is_ok());
+        assert!(vfs.set_selinux_context(&link1, link).is_ok());
+        assert_eq!(vfs.get_selinux_context(&dir1).unwrap().unwrap(), var);
+        assert_eq!(vfs.get_selinux_context(&file1).unwrap().unwrap(), etc);
+        assert_eq!(vfs.get_selinux_context(&link1).unwrap().unwrap(), link);
+
+        // Contexts are preserved when requested
+        assert!(vfs.copy_b(&dir1, &dir2).unwrap().preserve_context().exec().is_ok());
+        assert_eq!(vfs.get_selinux_context(&dir2).unwrap().unwrap(), var);
+        assert_eq!(vfs.get_selinux_context(dir2.mash("file1")).unwrap().unwrap(), etc);
+        assert_eq!(vfs.get_selinux_context(dir2.mash("link1")).unwrap().unwrap(), link);
+
+        // Contexts aren't carried over by default
+        assert!(vfs.copy_b(&dir1, &dir3).unwrap().exec().is_ok());
+        assert_ne!(vfs.get_selinux_context(dir3.mash("file1")).unwrap().as_deref(), Some(etc));
+
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+
+    #[test]
+    fn test_vfs_copy_reflink()
+    {
+        test_copy_reflink(assert_vfs_setup!(Vfs::memfs()));
+        test_copy_reflink(assert_vfs_setup!(Vfs::stdfs()));
+    }
+    fn test_copy_reflink((vfs, tmpdir): (Vfs, PathBuf))
+    {
+        let file1 = tmpdir.mash("file1");
+        let file2 = tmpdir.mash("file2");
+        let file3 = tmpdir.mash("file3");
+        let file4 = tmpdir.mash("file4");
+        assert_vfs_write_all!(vfs, &file1, "file1");
+
+        // Never and Auto always succeed
+        assert!(vfs.copy_b(&file1, &file2).unwrap().reflink(Reflink::Never).exec().is_ok());
+        assert_vfs_read_all!(vfs, &file2, "file1");
+        assert!(vfs.copy_b(&file1, &file3).unwrap().reflink(Reflink::Auto).exec().is_ok());
+        assert_vfs_read_all!(vfs, &file3, "file1");
+        assert!(!vfs.same_file(&file1, &file3));
+
+        // Always depends on the filesystem's support leaving nothing behind on failure
+        match vfs.copy_b(&file1, &file4).unwrap().reflink(Reflink::Always).exec() {
+            Ok(_) => {
+                assert_vfs_read_all!(vfs, &file4, "file1");
+            },
+            Err(err) => {
+                assert_eq!(err.downcast_ref::<VfsError>(), Some(&VfsError::ReflinkUnsupported(file1.clone())));
+                assert_vfs_no_exists!(vfs, &file4);
+            },
+        }
+
+        // Clones are independent of the source
+        vfs.write_all(&file1, "foobar").unwrap();
+        assert_vfs_read_all!(vfs, &file3, "file1");
+
+        // Hard linking takes precedence
+        assert!(vfs.copy_b(&file1, &file3).unwrap().link(true).reflink(Reflink::Always).exec().is_ok());
+        assert!(vfs.same_file(&file1, &file3));
+
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+
+    #[test]
+    fn test_vfs_copy_report()
+    {
+        test_copy_report(assert_vfs_setup!(Vfs::memfs()));
+        test_copy_report(assert_vfs_setup!(Vfs::stdfs()));
+    }
+    fn test_copy_report((vfs, tmpdir): (Vfs, PathBuf))
+    {
+        let dir1 = tmpdir.mash("dir1");
+        let sub1 = dir1.mash("sub1");
+        let file1 = dir1.mash("file1");
+        let file2 = sub1.mash("file2");
+        let link1 = dir1.mash("link1");
+        let loop1 = sub1.mash("loop1");
+        let dir2 = tmpdir.mash("dir2");
+        let dir3 = tmpdir.mash("dir3");
+        let dir4 = tmpdir.mash("dir4");
+        let dir5 = tmpdir.mash("dir5");
+        assert_vfs_mkdir_p!(vfs, &sub1);
+        assert_vfs_write_all!(vfs, &file1, "foo");
+        assert_vfs_write_all!(vfs, &file2, "foobar");
+        assert_vfs_symlink!(vfs, &link1, "file1");
+
+        // Copied files, links and dirs
+        let mut report = vfs.copy_b(&dir1, &dir2).unwrap().exec_report().unwrap();
+        report.dirs.sort();
+        report.copied.sort();
+        assert_eq!(report.dirs, vec![dir2.clone(), dir2.mash("sub1")]);
+        assert_eq!(report.copied, vec![dir2.mash("file1"), dir2.mash("sub1/file2")]);
+        assert_eq!(report.symlinks, vec![dir2.mash("link1")]);
+        assert_eq!(report.files(), 2);
+        assert_eq!(report.bytes, 9);
+        assert!(report.cloned.is_empty() && report.linked.is_empty());
+        assert!(report.skipped.is_empty() && report.errors.is_empty());
+
+        // Hard linked files add no bytes
+        let report = vfs.copy_b(&file1, dir3.mash("file1")).unwrap().link(true).exec_report().unwrap();
+        assert_eq!(report.dirs, vec![dir3.clone()]);
+        assert_eq!(report.linked, vec![dir3.mash("file1")]);
+        assert_eq!(report.bytes, 0);
+
+        // Cloned files are reported when the filesystem supports it
+        let report = vfs.copy_b(&file2, dir3.mash("file2")).unwrap().reflink(Reflink::Auto).exec_report().unwrap();
+        assert_eq!(report.files(), 1);
+        assert_eq!(report.bytes, 6);
+        assert_eq!(report.copied.len() + report.cloned.len(), 1);
+
+        // Stripped and flattened entries are skipped
+        let mut report = vfs.copy_b(&dir1, &dir4).unwrap().strip_components(1).exec_report().unwrap();
+        report.skipped.sort();
+        assert_eq!(report.skipped, vec![file1.clone(), link1.clone(), sub1.clone()]);
+        assert_eq!(report.copied, vec![dir4.mash("file2")]);
+        let report = vfs.copy_b(&dir1, tmpdir.mash("dir6")).unwrap().flatten().exec_report().unwrap();
+        assert_eq!(report.skipped, vec![sub1.clone()]);
+        let report = vfs.copy_b(&dir1, &dir1).unwrap().exec_report().unwrap();
+        assert_eq!(report.skipped, vec![dir1.clone()]);
+
+        // Failures are reported when continuing on error
+        assert_vfs_symlink!(vfs, &loop1, &dir1);
+        let copier = vfs.copy_b(&dir1, &dir5).unwrap().follow(true).continue_on_error();
+        let report = copier.exec_report().unwrap();
+        assert_eq!(report.errors.len(), 1);
+        assert_eq!(report.errors[0].1.downcast_ref::<PathError>(), Some(&PathError::link_looping(&dir1)));
+        assert_vfs_read_all!(vfs, dir5.mash("file1"), "foo");
+        let err = vfs.copy_b(&dir1, &dir5).unwrap().follow(true).exec_report().unwrap_err();
+        assert_eq!(err.downcast_ref::<PathError>(), Some(&PathError::link_looping(&dir1)));
+
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+
+    #[test]
+    fn test_vfs_copy_rewrite_links()
+    {
+        test_copy_rewrite_links(assert_vfs_setup!(Vfs::memfs()));
+        test_copy_rewrite_links(assert_vfs_setup!(Vfs::stdfs()));
+    }
+    fn test_copy_rewrite_links((vfs, tmpdir): (Vfs, PathBuf))
+    {
+        let src = tmpdir.mash("src");
+        let srcfile = src.mash("file");
+        let srclink = src.mash("link");
+        let srcextfile = src.mash("extfile");
+        let srcextdir = src.mash("extdir");
+        let ext = tmpdir.mash("ext");
+        let extfile = ext.mash("file");
+        assert_vfs_mkdir_p!(vfs, &src);
+        assert_vfs_write_all!(vfs, &srcfile, "inside");
+        assert_vfs_mkdir_p!(vfs, &ext);
+        assert_vfs_write_all!(vfs, &extfile, "outside");
+        assert_vfs_symlink!(vfs, &srclink, &srcfile);
+        assert_vfs_symlink!(vfs, &srcextfile, &extfile);
+        assert_vfs_symlink!(vfs, &srcextdir, &ext);
+
+        // default keeps links pointing back into the source
+        let dst1 = tmpdir.mash("dst1");
+        assert!(vfs.copy_b(&src, &dst1).unwrap().exec().is_ok());
+        assert_vfs_readlink_abs!(vfs, dst1.mash("link"), &srcfile);
+        assert_vfs_readlink_abs!(vfs, dst1.mash("extfile"), &extfile);
+
+        // rewrite internal links only
+        let dst2 = tmpdir.mash("dst2");
+        assert!(vfs.copy_b(&src, &dst2).unwrap().rewrite_links(true).exec().is_ok());
+        assert_vfs_readlink_abs!(vfs, dst2.mash("link"), dst2.mash("file"));
+        assert_vfs_readlink_abs!(vfs, dst2.mash("extfile"), &extfile);
+        assert_vfs_readlink_abs!(vfs, dst2.mash("extdir"), &ext);
+
+        // rewrite internal links when copying into an existing directory
+        let dst3 = tmpdir.mash("dst3");
+        assert_vfs_mkdir_p!(vfs, &dst3);
+        assert!(vfs.copy_b(&src, &dst3).unwrap().rewrite_links(true).exec().is_ok());
+        assert_vfs_readlink_abs!(vfs, dst3.mash("src/link"), dst3.mash("src/file"));
+
+        // dereference external links and rewrite internal ones
+        let dst4 = tmpdir.mash("dst4");
+        assert!(vfs.copy_b(&src, &dst4).unwrap().rewrite_links(true).dereference_external(true).exec().is_ok());
+        assert_vfs_readlink_abs!(vfs, dst4.mash("link"), dst4.mash("file"));
+        assert_vfs_no_symlink!(vfs, dst4.mash("extfile"));
+        assert_vfs_read_all!(vfs, dst4.mash("extfile"), "outside");
+        assert_vfs_no_symlink!(vfs, dst4.mash("extdir"));
+        assert_vfs_is_dir!(vfs, dst4.mash("extdir"));
+        assert_vfs_read_all!(vfs, dst4.mash("extdir/file"), "outside");
+
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+
+    #[test]
+    fn test_vfs_copy_strip_components()
+    {
+        test_copy_strip_components(assert_vfs_setup!(Vfs::memfs()));
+        test_copy_strip_components(assert_vfs_setup!(Vfs::stdfs()));
+    }
+    fn test_copy_strip_components((vfs, tmpdir): (Vfs, PathBuf))
+    {
+        let pkg = tmpdir.mash("pkg");
+        let file1 = pkg.mash("root/file1");
+        let file2 = pkg.mash("root/usr/bin/file2");
+        let file3 = pkg.mash("root/usr/bin/sub/file3");
+        let bin = tmpdir.mash("usr/bin");
+        let dir1 = tmpdir.mash("dir1");
+        assert_vfs_mkdir_p!(vfs, file3.dir().unwrap());
+        assert_vfs_write_all!(vfs, &file1, "file1");
+        assert_vfs_write_all!(vfs, &file2, "file2");
+        assert_vfs_write_all!(vfs, &file3, "file3");
+
+        // Copy into an existing directory strips the source directory name as well
+        assert_vfs_mkdir_p!(vfs, &bin);
+        assert!(vfs.copy_b(&pkg, &bin).unwrap().strip_components(4).exec().is_ok());
+        let mut paths = vfs.all_paths(&bin).unwrap();
+        paths.sort();
+        assert_eq!(paths, vec![bin.mash("file2"), bin.mash("sub"), bin.mash("sub/file3")]);
+        assert_vfs_read_all!(vfs, bin.mash("sub/file3"), "file3");
+
+        // Copy to a new directory
+        assert!(vfs.copy_b(&pkg, &dir1).unwrap().strip_components(1).exec().is_ok());
+        assert_vfs_read_all!(vfs, dir1.mash("file1"), "file1");
+        assert_vfs_read_all!(vfs, dir1.mash("usr/bin/file2"), "file2");
+        assert_vfs_no_exists!(vfs, dir1.mash("root"));
+
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
 }
@@ -0,0 +1,111 @@
+use serde::de::DeserializeOwned;
+
+use crate::{errors::*, sys::ReadSeek};
+
+/// Provides a streaming iterator over the rows of a CSV file deserialized into the given type
+///
+/// Use the Vfs function `read_csv_iter` to create a new instance. The first row is treated as the
+/// header row and is used to map fields by name when deserializing into structs.
+///
+/// ### Examples
+/// ```
+/// use rivia::prelude::*;
+///
+/// let vfs = Vfs::memfs();
+/// let file = vfs.root().mash("file");
+/// assert_vfs_write_all!(vfs, &file, "name,age\nfoo,1\nbar,2\n");
+/// let mut iter = vfs.read_csv_iter::<(String, u32), _>(&file, b',').unwrap();
+/// assert_eq!(iter.next().unwrap().unwrap(), ("foo".to_string(), 1));
+/// assert_eq!(iter.next().unwrap().unwrap(), ("bar".to_string(), 2));
+/// assert!(iter.next().is_none());
+/// ```
+pub struct CsvIter<T>
+{
+    iter: ::csv::DeserializeRecordsIntoIter<Box<dyn ReadSeek>, T>, // rows of the vfs read handle
+}
+
+impl<T: DeserializeOwned> CsvIter<T>
+{
+    /// Create a new iterator over the rows of the given reader split on the given delimiter
+    pub(crate) fn new(reader: Box<dyn ReadSeek>, delimiter: u8) -> Self
+    {
+        Self { iter: ::csv::ReaderBuilder::new().delimiter(delimiter).from_reader(reader).into_deserialize() }
+    }
+}
+
+impl<T: DeserializeOwned> Iterator for CsvIter<T>
+{
+    type Item = RvResult<T>;
+
+    fn next(&mut self) -> Option<RvResult<T>>
+    {
+        self.iter.next().map(|x| x.map_err(RvError::from))
+    }
+}
+
+// Unit tests
+// -------------------------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests
+{
+    use serde::Deserialize;
+
+    use crate::prelude::*;
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Row
+    {
+        name: String,
+        age: u32,
+    }
+
+    #[test]
+    fn test_vfs_read_csv()
+    {
+        test_read_csv(assert_vfs_setup!(Vfs::memfs()));
+        test_read_csv(assert_vfs_setup!(Vfs::stdfs()));
+    }
+    fn test_read_csv((vfs, tmpdir): (Vfs, PathBuf))
+    {
+        let dir1 = tmpdir.mash("dir1");
+        let file1 = tmpdir.mash("file1");
+        let file2 = tmpdir.mash("file2");
+
+        // Errors
+        assert_eq!(
+            vfs.read_csv::<Row, _>(&file1).unwrap_err().downcast_ref::<PathError>(),
+            Some(&PathError::does_not_exist(&file1))
+        );
+        assert_vfs_mkdir_p!(vfs, &dir1);
+        assert_eq!(
+            vfs.read_csv_iter::<Row, _>(&dir1, b',').err().unwrap().downcast_ref::<PathError>(),
+            Some(&PathError::is_not_file(&dir1))
+        );
+
+        // Header only
+        assert_vfs_write_all!(vfs, &file1, "name,age\n");
+        assert_eq!(vfs.read_csv::<Row, _>(&file1).unwrap(), vec![]);
+
+        // Fields are mapped by header name regardless of column order
+        assert!(vfs.write_all(&file1, "age,name\n1,foo\n2,\"bar, baz\"").is_ok());
+        assert_eq!(vfs.read_csv::<Row, _>(&file1).unwrap(), vec![
+            Row { name: "foo".into(), age: 1 },
+            Row { name: "bar, baz".into(), age: 2 },
+        ]);
+
+        // Tab separated
+        assert_vfs_write_all!(vfs, &file2, "name\tage\nfoo, bar\t1\n");
+        assert_eq!(vfs.read_tsv::<Row, _>(&file2).unwrap(), vec![Row { name: "foo, bar".into(), age: 1 }]);
+
+        // Streaming surfaces bad rows without ending the iteration
+        assert!(vfs.write_all(&file1, "name,age\nfoo,1\nbar,x\nbaz,3\n").is_ok());
+        let mut iter = vfs.read_csv_iter::<Row, _>(&file1, b',').unwrap();
+        assert_eq!(iter.next().unwrap().unwrap(), Row { name: "foo".into(), age: 1 });
+        assert!(iter.next().unwrap().unwrap_err().is::<::csv::Error>());
+        assert_eq!(iter.next().unwrap().unwrap(), Row { name: "baz".into(), age: 3 });
+        assert!(iter.next().is_none());
+        assert!(vfs.read_csv::<Row, _>(&file1).is_err());
+
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+}
@@ -0,0 +1,238 @@
+use std::{io, os::unix::ffi::OsStrExt, path::Path};
+
+use sha2::{Digest, Sha224, Sha256, Sha384, Sha512};
+
+use crate::{
+    errors::*,
+    sys::{self, Entry, VfsEntry, VirtualFileSystem},
+};
+
+// Tags prefixed to each node's hash so that differing kinds of nodes never collide
+const TAG_DIR: &[u8] = b"rivia:dir\0";
+const TAG_FILE: &[u8] = b"rivia:file\0";
+const TAG_LINK: &[u8] = b"rivia:link\0";
+const TAG_OTHER: &[u8] = b"rivia:other\0";
+
+/// Hash algorithms available to the Vfs `digest` function
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Algo
+{
+    Sha224,
+    Sha256,
+    Sha384,
+    Sha512,
+}
+
+/// Returns the hash of the given file's contents as a lower case hex string
+///
+/// * The contents are streamed through the hasher rather than being read into memory
+pub(crate) fn file_digest<T: VirtualFileSystem + ?Sized>(vfs: &T, path: &Path, algo: Algo) -> RvResult<String>
+{
+    let mut reader = vfs.read(vfs.resolve_link(path, sys::DEFAULT_MAX_LINKS)?.target)?;
+    let digest = match algo {
+        Algo::Sha224 => stream_digest::<Sha224, _>(&mut reader)?,
+        Algo::Sha256 => stream_digest::<Sha256, _>(&mut reader)?,
+        Algo::Sha384 => stream_digest::<Sha384, _>(&mut reader)?,
+        Algo::Sha512 => stream_digest::<Sha512, _>(&mut reader)?,
+    };
+    Ok(hex(&digest))
+}
+
+/// Returns the SHA-256 Merkle hash of the given entry as a lower case hex string
+///
+/// * Files hash their permission bits and contents
+/// * Links hash their target as stored without following it
+/// * Other file types e.g. fifos and sockets hash their mode without being read
+/// * Directories hash their permission bits and each child's name and hash ordered by the bytes
+///   of the name so that the result doesn't depend on the order the backend lists entries in
+/// * The name of the given entry itself isn't part of the hash so a tree may be moved or
+///   renamed without changing it
+pub(crate) fn tree_digest<T: VirtualFileSystem + ?Sized>(vfs: &T, path: &Path) -> RvResult<String>
+{
+    let entry = vfs.entry(path)?;
+    Ok(hex(&node_digest(vfs, &entry)?))
+}
+
+// Hash everything the given reader has to offer with the given hasher returning the raw digest
+fn stream_digest<D: Digest + io::Write, R: io::Read + ?Sized>(reader: &mut R) -> RvResult<Vec<u8>>
+{
+    let mut hasher = D::new();
+    io::copy(reader, &mut hasher)?;
+    Ok(hasher.finalize().to_vec())
+}
+
+// Format the given raw digest as a lower case hex string
+fn hex(digest: &[u8]) -> String
+{
+    digest.iter().map(|x| format!("{:02x}", x)).collect()
+}
+
+// Hash the given entry recursively returning the raw digest
+fn node_digest<T: VirtualFileSystem + ?Sized>(vfs: &T, entry: &VfsEntry) -> RvResult<[u8; 32]>
+{
+    let mut hasher = Sha256::new();
+    if entry.is_symlink() {
+        let target = vfs.readlink(entry.path())?;
+        hasher.update(TAG_LINK);
+        hasher.update(target.as_os_str().as_bytes());
+    } else if entry.is_dir() {
+        hasher.update(TAG_DIR);
+        hasher.update((entry.mode() & 0o7777).to_be_bytes());
+
+        // Sort by the raw bytes of the name rather than relying on the backend's ordering
+        let entries = vfs.entries(entry.path())?.min_depth(1).max_depth(1);
+        let mut children = entries.into_iter().collect::<RvResult<Vec<_>>>()?;
+        children.sort_by(|x, y| x.file_name().map(|x| x.as_bytes()).cmp(&y.file_name().map(|x| x.as_bytes())));
+        for child in children.iter() {
+            let name = child.file_name().map(|x| x.as_bytes()).unwrap_or_default();
+            hasher.update((name.len() as u64).to_be_bytes());
+            hasher.update(name);
+            hasher.update(node_digest(vfs, child)?);
+        }
+    } else if entry.is_file() {
+        hasher.update(TAG_FILE);
+        hasher.update((entry.mode() & 0o7777).to_be_bytes());
+        io::copy(&mut vfs.read(entry.path())?, &mut hasher)?;
+    } else {
+        hasher.update(TAG_OTHER);
+        hasher.update(entry.mode().to_be_bytes());
+    }
+    Ok(hasher.finalize().into())
+}
+
+// Unit tests
+// -------------------------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests
+{
+    use crate::prelude::*;
+
+    #[test]
+    fn test_vfs_digest()
+    {
+        test_digest(assert_vfs_setup!(Vfs::memfs()));
+        test_digest(assert_vfs_setup!(Vfs::stdfs()));
+    }
+    fn test_digest((vfs, tmpdir): (Vfs, PathBuf))
+    {
+        let dir1 = tmpdir.mash("dir1");
+        let file1 = tmpdir.mash("file1");
+        let file2 = tmpdir.mash("file2");
+        let link1 = tmpdir.mash("link1");
+        assert_vfs_mkdir_p!(vfs, &dir1);
+
+        // Errors
+        let err = vfs.digest(&file1, Algo::Sha256).unwrap_err();
+        assert_eq!(err.downcast_ref::<PathError>(), Some(&PathError::does_not_exist(&file1)));
+        let err = vfs.digest(&dir1, Algo::Sha256).unwrap_err();
+        assert_eq!(err.downcast_ref::<PathError>(), Some(&PathError::is_not_file(&dir1)));
+
+        // Known digests
+        assert_vfs_write_all!(vfs, &file1, "foobar");
+        assert_eq!(
+            vfs.digest(&file1, Algo::Sha224).unwrap(),
+            "de76c3e567fca9d246f5f8d3b2e704a38c3c5e258988ab525f941db8"
+        );
+        assert_eq!(
+            vfs.digest(&file1, Algo::Sha256).unwrap(),
+            "c3ab8ff13720e8ad9047dd39466b3c8974e592c2fa383d4a3960714caef0c4f2"
+        );
+        assert_eq!(vfs.digest(&file1, Algo::Sha384).unwrap().len(), 96);
+        assert_eq!(vfs.digest(&file1, Algo::Sha512).unwrap().len(), 128);
+
+        // Empty files and links
+        assert_vfs_mkfile!(vfs, &file2);
+        assert_eq!(
+            vfs.digest(&file2, Algo::Sha256).unwrap(),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+        assert_vfs_symlink!(vfs, &link1, &file1);
+        assert_eq!(vfs.digest(&link1, Algo::Sha256).unwrap(), vfs.digest(&file1, Algo::Sha256).unwrap());
+
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+
+    #[test]
+    fn test_vfs_tree_digest()
+    {
+        let memfs = test_tree_digest(assert_vfs_setup!(Vfs::memfs()));
+        let stdfs = test_tree_digest(assert_vfs_setup!(Vfs::stdfs()));
+        assert_eq!(memfs, stdfs);
+
+        // The hash is stable across releases so it may be persisted as a cache key
+        assert_eq!(memfs, "47d397839719ba8ad827d2f4fd9f0a5203895a71ce41514204635606683d826b");
+    }
+    fn test_tree_digest((vfs, tmpdir): (Vfs, PathBuf)) -> String
+    {
+        let tree = tmpdir.mash("tree");
+        let file1 = tree.mash("file1");
+        let dir1 = tree.mash("dir1");
+        let file2 = dir1.mash("file2");
+        let link1 = tree.mash("link1");
+
+        // Doesn't exist
+        assert_eq!(
+            vfs.tree_digest(&tree).unwrap_err().downcast_ref::<PathError>(),
+            Some(&PathError::does_not_exist(&tree))
+        );
+
+        // Modes are set explicitly so the hash doesn't depend on the umask
+        assert_vfs_mkdir_p!(vfs, &dir1);
+        assert_vfs_write_all!(vfs, &file1, "foo");
+        assert_vfs_write_all!(vfs, &file2, "bar");
+        assert!(vfs.symlink(&link1, "file1").is_ok());
+        assert!(vfs.chmod_b(&tree).unwrap().dirs(0o755).files(0o644).exec().is_ok());
+        let digest = vfs.tree_digest(&tree).unwrap();
+        assert_eq!(digest.len(), 64);
+        assert_eq!(vfs.tree_digest(&tree).unwrap(), digest);
+
+        // Files and dirs hash on their own and differ from each other
+        let file_digest = vfs.tree_digest(&file1).unwrap();
+        assert_ne!(file_digest, digest);
+        assert_ne!(vfs.tree_digest(&file2).unwrap(), file_digest);
+        assert_ne!(vfs.tree_digest(&dir1).unwrap(), digest);
+
+        // Links hash their target rather than following it
+        assert_ne!(vfs.tree_digest(&link1).unwrap(), file_digest);
+
+        // Contents change the hash
+        vfs.write_all(&file2, "baz").unwrap();
+        assert_ne!(vfs.tree_digest(&tree).unwrap(), digest);
+        vfs.write_all(&file2, "bar").unwrap();
+        assert_eq!(vfs.tree_digest(&tree).unwrap(), digest);
+
+        // Modes change the hash
+        assert!(vfs.chmod(&file2, 0o600).is_ok());
+        assert_ne!(vfs.tree_digest(&tree).unwrap(), digest);
+        assert!(vfs.chmod(&file2, 0o644).is_ok());
+        assert_eq!(vfs.tree_digest(&tree).unwrap(), digest);
+
+        // Names change the hash
+        assert!(vfs.move_p(&file2, dir1.mash("file3")).is_ok());
+        assert_ne!(vfs.tree_digest(&tree).unwrap(), digest);
+        assert!(vfs.move_p(dir1.mash("file3"), &file2).is_ok());
+        assert_eq!(vfs.tree_digest(&tree).unwrap(), digest);
+
+        // Link targets change the hash
+        assert_vfs_remove!(vfs, &link1);
+        assert!(vfs.symlink(&link1, "dir1/file2").is_ok());
+        assert_ne!(vfs.tree_digest(&tree).unwrap(), digest);
+        assert_vfs_remove!(vfs, &link1);
+        assert!(vfs.symlink(&link1, "file1").is_ok());
+        assert_eq!(vfs.tree_digest(&tree).unwrap(), digest);
+
+        // Empty dirs are part of the structure
+        assert!(vfs.mkdir_m(tree.mash("dir2"), 0o755).is_ok());
+        assert_ne!(vfs.tree_digest(&tree).unwrap(), digest);
+        assert_vfs_remove!(vfs, tree.mash("dir2"));
+        assert_eq!(vfs.tree_digest(&tree).unwrap(), digest);
+
+        // Moving the tree doesn't change the hash
+        let moved = tmpdir.mash("moved");
+        assert!(vfs.move_p(&tree, &moved).is_ok());
+        assert_eq!(vfs.tree_digest(&moved).unwrap(), digest);
+
+        assert_vfs_remove_all!(vfs, &tmpdir);
+        digest
+    }
+}
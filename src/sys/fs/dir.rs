@@ -0,0 +1,598 @@
+use std::{
+    fmt,
+    fs::File,
+    io::{Read, Write},
+    os::fd::{AsRawFd, FromRawFd, OwnedFd},
+    path::{Component, Path, PathBuf},
+    sync::Arc,
+};
+
+use nix::{
+    errno::Errno,
+    fcntl::{self, OFlag},
+    sys::stat::{self, FileStat, Mode, SFlag},
+    unistd::{self, UnlinkatFlags},
+};
+
+use crate::{
+    errors::*,
+    sys::{Entries, Memfs, Overlayfs, PathExt, Stdfs, VfsHooks, VfsOp, VirtualFileSystem},
+};
+
+/// Provides a handle to an open directory for operating on the entries it contains by name
+///
+/// Use the Vfs function `open_dir` to create a new instance. Names are resolved relative to the
+/// open directory rather than by path which avoids repeated path resolution and the races that
+/// come with it.
+///
+/// * Stdfs holds the directory open and uses the `openat` family of syscalls so operations stay
+///   scoped to the opened directory even if its path is renamed or replaced
+/// * Memfs scopes lookups to the directory's path
+/// * Names must be a single entry name i.e. no separators, `.` or `..`
+///
+/// ### Examples
+/// ```
+/// use rivia::prelude::*;
+///
+/// let vfs = Vfs::memfs();
+/// let dir = vfs.root().mash("dir");
+/// assert_vfs_mkdir_p!(vfs, &dir);
+/// let handle = vfs.open_dir(&dir).unwrap();
+/// assert_eq!(handle.mkfile("file").unwrap(), dir.mash("file"));
+/// assert_vfs_is_file!(vfs, dir.mash("file"));
+/// ```
+pub struct DirHandle
+{
+    path: PathBuf,   // abs path of the opened directory
+    handle: DirKind, // backend specific directory handle
+}
+
+// Backend specific directory handles
+enum DirKind
+{
+    Stdfs(OwnedFd, Arc<VfsHooks>),
+    Memfs(Memfs),
+    Overlayfs(Overlayfs),
+    Readonly(Box<DirHandle>),
+}
+
+impl DirHandle
+{
+    /// Open the given Stdfs directory wrapping mutations with the given hooks
+    ///
+    /// * `path` is expected to already be in absolute form
+    pub(crate) fn stdfs(hooks: Arc<VfsHooks>, path: PathBuf) -> RvResult<Self>
+    {
+        let flags = OFlag::O_DIRECTORY | OFlag::O_RDONLY | OFlag::O_CLOEXEC;
+        let fd = fcntl::open(&path, flags, Mode::empty()).map_err(|e| at_err(e, &path))?;
+        let fd = unsafe { OwnedFd::from_raw_fd(fd) };
+        Ok(Self { path, handle: DirKind::Stdfs(fd, hooks) })
+    }
+
+    /// Open the given Memfs directory
+    ///
+    /// * `path` is expected to already be in absolute form
+    pub(crate) fn memfs(vfs: Memfs, path: PathBuf) -> RvResult<Self>
+    {
+        if !vfs.exists(&path) {
+            return Err(PathError::does_not_exist(&path).into());
+        } else if !vfs.is_dir(&path) {
+            return Err(PathError::is_not_dir(&path).into());
+        }
+        Ok(Self { path, handle: DirKind::Memfs(vfs) })
+    }
+
+    /// Open the given Overlayfs directory
+    ///
+    /// * `path` is expected to already be in absolute form
+    pub(crate) fn overlayfs(vfs: Overlayfs, path: PathBuf) -> RvResult<Self>
+    {
+        if !vfs.exists(&path) {
+            return Err(PathError::does_not_exist(&path).into());
+        } else if !vfs.is_dir(&path) {
+            return Err(PathError::is_not_dir(&path).into());
+        }
+        Ok(Self { path, handle: DirKind::Overlayfs(vfs) })
+    }
+
+    /// Wrap the given handle so that all mutating operations are rejected
+    pub(crate) fn readonly(handle: DirHandle) -> Self
+    {
+        Self { path: handle.path.clone(), handle: DirKind::Readonly(Box::new(handle)) }
+    }
+
+    /// Returns the absolute path of the opened directory
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let handle = vfs.open_dir(vfs.root()).unwrap();
+    /// assert_eq!(handle.path(), vfs.root());
+    /// ```
+    pub fn path(&self) -> &Path
+    {
+        &self.path
+    }
+
+    /// Returns a new [`Entries`] for traversing the opened directory
+    ///
+    /// * Traversal is done by path from the opened directory's path
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let handle = vfs.open_dir(vfs.root()).unwrap();
+    /// assert_eq!(handle.mkfile("file").unwrap(), vfs.root().mash("file"));
+    /// let mut iter = handle.entries().unwrap().into_iter();
+    /// assert_eq!(iter.next().unwrap().unwrap().path(), vfs.root());
+    /// assert_eq!(iter.next().unwrap().unwrap().path(), vfs.root().mash("file"));
+    /// assert!(iter.next().is_none());
+    /// ```
+    pub fn entries(&self) -> RvResult<Entries>
+    {
+        match &self.handle {
+            DirKind::Stdfs(..) => Stdfs::entries(&self.path),
+            DirKind::Memfs(vfs) => vfs.entries(&self.path),
+            DirKind::Overlayfs(vfs) => vfs.entries(&self.path),
+            DirKind::Readonly(handle) => handle.entries(),
+        }
+    }
+
+    /// Returns true if the given name exists in the opened directory
+    ///
+    /// * Links are followed to report on the target they point to
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let handle = vfs.open_dir(vfs.root()).unwrap();
+    /// assert_eq!(handle.exists("file"), false);
+    /// assert!(handle.mkfile("file").is_ok());
+    /// assert_eq!(handle.exists("file"), true);
+    /// ```
+    pub fn exists<T: AsRef<Path>>(&self, name: T) -> bool
+    {
+        self.stat(name.as_ref()).map(|x| x.is_some()).unwrap_or(false)
+    }
+
+    /// Returns true if the given name is a directory in the opened directory
+    ///
+    /// * Links are followed to report on the target they point to
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let handle = vfs.open_dir(vfs.root()).unwrap();
+    /// assert!(handle.mkdir("dir").is_ok());
+    /// assert_eq!(handle.is_dir("dir"), true);
+    /// ```
+    pub fn is_dir<T: AsRef<Path>>(&self, name: T) -> bool
+    {
+        matches!(self.stat(name.as_ref()), Ok(Some(SFlag::S_IFDIR)))
+    }
+
+    /// Returns true if the given name is a file in the opened directory
+    ///
+    /// * Links are followed to report on the target they point to
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let handle = vfs.open_dir(vfs.root()).unwrap();
+    /// assert!(handle.mkfile("file").is_ok());
+    /// assert_eq!(handle.is_file("file"), true);
+    /// ```
+    pub fn is_file<T: AsRef<Path>>(&self, name: T) -> bool
+    {
+        matches!(self.stat(name.as_ref()), Ok(Some(SFlag::S_IFREG)))
+    }
+
+    /// Creates the given directory in the opened directory if it doesn't already exist
+    ///
+    /// * Returns the absolute path of the directory
+    ///
+    /// ### Errors
+    /// * PathError::InvalidName(PathBuf) when the given name isn't a single entry name
+    /// * PathError::IsNotDir(PathBuf) when the given name exists but is not a directory
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let handle = vfs.open_dir(vfs.root()).unwrap();
+    /// assert_eq!(handle.mkdir("dir").unwrap(), vfs.root().mash("dir"));
+    /// assert_vfs_is_dir!(vfs, vfs.root().mash("dir"));
+    /// ```
+    pub fn mkdir<T: AsRef<Path>>(&self, name: T) -> RvResult<PathBuf>
+    {
+        let name = name.as_ref();
+        let path = self.child(name)?;
+        match &self.handle {
+            DirKind::Stdfs(fd, hooks) => {
+                if !self.is_dir(name) {
+                    hooks.exec(VfsOp::Create, &path, || {
+                        match stat::mkdirat(fd.as_raw_fd(), name, Mode::from_bits_truncate(0o777)) {
+                            Err(Errno::EEXIST) => Err(PathError::is_not_dir(&path).into()),
+                            result => result.map_err(|e| at_err(e, &path)),
+                        }
+                    })?;
+                }
+            },
+            DirKind::Memfs(vfs) => {
+                vfs.mkdir_p(&path)?;
+            },
+            DirKind::Overlayfs(vfs) => {
+                vfs.mkdir_p(&path)?;
+            },
+            DirKind::Readonly(_) => return Err(VfsError::ReadOnly(VfsOp::Create, path).into()),
+        }
+        Ok(path)
+    }
+
+    /// Creates an empty file in the opened directory if it doesn't already exist
+    ///
+    /// * Returns the absolute path of the file
+    ///
+    /// ### Errors
+    /// * PathError::InvalidName(PathBuf) when the given name isn't a single entry name
+    /// * PathError::IsNotFile(PathBuf) when the given name exists but is not a file
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let handle = vfs.open_dir(vfs.root()).unwrap();
+    /// assert_eq!(handle.mkfile("file").unwrap(), vfs.root().mash("file"));
+    /// assert_vfs_is_file!(vfs, vfs.root().mash("file"));
+    /// ```
+    pub fn mkfile<T: AsRef<Path>>(&self, name: T) -> RvResult<PathBuf>
+    {
+        let name = name.as_ref();
+        let path = self.child(name)?;
+        match &self.handle {
+            DirKind::Stdfs(fd, hooks) => {
+                if !self.is_file(name) {
+                    hooks.exec(VfsOp::Create, &path, || {
+                        let flags = OFlag::O_WRONLY | OFlag::O_CREAT | OFlag::O_CLOEXEC;
+                        openat(fd, name, &path, flags).map(|_| ())
+                    })?;
+                }
+            },
+            DirKind::Memfs(vfs) => {
+                vfs.mkfile(&path)?;
+            },
+            DirKind::Overlayfs(vfs) => {
+                vfs.mkfile(&path)?;
+            },
+            DirKind::Readonly(_) => return Err(VfsError::ReadOnly(VfsOp::Create, path).into()),
+        }
+        Ok(path)
+    }
+
+    /// Opens the given directory in the opened directory
+    ///
+    /// ### Errors
+    /// * PathError::InvalidName(PathBuf) when the given name isn't a single entry name
+    /// * PathError::DoesNotExist(PathBuf) when the given name doesn't exist
+    /// * PathError::IsNotDir(PathBuf) when the given name is not a directory
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let handle = vfs.open_dir(vfs.root()).unwrap();
+    /// assert!(handle.mkdir("dir").is_ok());
+    /// let handle = handle.open_dir("dir").unwrap();
+    /// assert_eq!(handle.path(), vfs.root().mash("dir"));
+    /// ```
+    pub fn open_dir<T: AsRef<Path>>(&self, name: T) -> RvResult<DirHandle>
+    {
+        let name = name.as_ref();
+        let path = self.child(name)?;
+        match &self.handle {
+            DirKind::Stdfs(fd, hooks) => {
+                let flags = OFlag::O_DIRECTORY | OFlag::O_RDONLY | OFlag::O_CLOEXEC;
+                let file = openat(fd, name, &path, flags)?;
+                Ok(Self { path, handle: DirKind::Stdfs(file.into(), hooks.clone()) })
+            },
+            DirKind::Memfs(vfs) => DirHandle::memfs(vfs.clone(), path),
+            DirKind::Overlayfs(vfs) => DirHandle::overlayfs(vfs.clone(), path),
+            DirKind::Readonly(handle) => handle.open_dir(name).map(DirHandle::readonly),
+        }
+    }
+
+    /// Returns all the data from the given file in the opened directory as a String
+    ///
+    /// ### Errors
+    /// * PathError::InvalidName(PathBuf) when the given name isn't a single entry name
+    /// * PathError::DoesNotExist(PathBuf) when the given name doesn't exist
+    /// * PathError::IsNotFile(PathBuf) when the given name is not a file
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let handle = vfs.open_dir(vfs.root()).unwrap();
+    /// assert!(handle.write_all("file", "foobar").is_ok());
+    /// assert_eq!(handle.read_all("file").unwrap(), "foobar");
+    /// ```
+    pub fn read_all<T: AsRef<Path>>(&self, name: T) -> RvResult<String>
+    {
+        let name = name.as_ref();
+        let path = self.child(name)?;
+        match &self.handle {
+            DirKind::Stdfs(fd, _) => {
+                let mut file = openat(fd, name, &path, OFlag::O_RDONLY | OFlag::O_CLOEXEC)?;
+                if !file.metadata()?.is_file() {
+                    return Err(PathError::is_not_file(&path).into());
+                }
+                let mut data = String::new();
+                file.read_to_string(&mut data)?;
+                Ok(data)
+            },
+            DirKind::Memfs(vfs) => vfs.read_all(&path),
+            DirKind::Overlayfs(vfs) => vfs.read_all(&path),
+            DirKind::Readonly(handle) => handle.read_all(name),
+        }
+    }
+
+    /// Removes the given file, link or empty directory from the opened directory
+    ///
+    /// * Link exclusion i.e. removes the link themselves not what its points to
+    /// * Does nothing if the given name doesn't exist
+    ///
+    /// ### Errors
+    /// * PathError::InvalidName(PathBuf) when the given name isn't a single entry name
+    /// * PathError::DirContainsFiles(PathBuf) when the given directory isn't empty
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let handle = vfs.open_dir(vfs.root()).unwrap();
+    /// assert!(handle.mkfile("file").is_ok());
+    /// assert!(handle.remove("file").is_ok());
+    /// assert_eq!(handle.exists("file"), false);
+    /// ```
+    pub fn remove<T: AsRef<Path>>(&self, name: T) -> RvResult<()>
+    {
+        let name = name.as_ref();
+        let path = self.child(name)?;
+        match &self.handle {
+            DirKind::Stdfs(fd, hooks) => {
+                let flag = match stat::fstatat(fd.as_raw_fd(), name, fcntl::AtFlags::AT_SYMLINK_NOFOLLOW) {
+                    Ok(x) if kind(&x) == SFlag::S_IFDIR => UnlinkatFlags::RemoveDir,
+                    Ok(_) => UnlinkatFlags::NoRemoveDir,
+                    Err(_) => return Ok(()),
+                };
+                hooks.exec(VfsOp::Remove, &path, || {
+                    unistd::unlinkat(Some(fd.as_raw_fd()), name, flag).map_err(|e| at_err(e, &path))
+                })
+            },
+            DirKind::Memfs(vfs) => vfs.remove(&path),
+            DirKind::Overlayfs(vfs) => vfs.remove(&path),
+            DirKind::Readonly(_) => Err(VfsError::ReadOnly(VfsOp::Remove, path).into()),
+        }
+    }
+
+    /// Write the given data to the given file in the opened directory
+    ///
+    /// * Create the file first if it doesn't exist or truncating it first if it does
+    ///
+    /// ### Errors
+    /// * PathError::InvalidName(PathBuf) when the given name isn't a single entry name
+    /// * PathError::IsNotFile(PathBuf) when the given name exists but is not a file
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let handle = vfs.open_dir(vfs.root()).unwrap();
+    /// assert!(handle.write_all("file", "foobar").is_ok());
+    /// assert_vfs_read_all!(vfs, vfs.root().mash("file"), "foobar");
+    /// ```
+    pub fn write_all<T: AsRef<Path>, U: AsRef<[u8]>>(&self, name: T, data: U) -> RvResult<()>
+    {
+        let name = name.as_ref();
+        let path = self.child(name)?;
+        match &self.handle {
+            DirKind::Stdfs(fd, hooks) => hooks.exec(VfsOp::Write, &path, || {
+                let flags = OFlag::O_WRONLY | OFlag::O_CREAT | OFlag::O_TRUNC | OFlag::O_CLOEXEC;
+                let mut file = openat(fd, name, &path, flags)?;
+                file.write_all(data.as_ref())?;
+                file.flush()?;
+                Ok(())
+            }),
+            DirKind::Memfs(vfs) => vfs.write_all(&path, data),
+            DirKind::Overlayfs(vfs) => vfs.write_all(&path, data),
+            DirKind::Readonly(_) => Err(VfsError::ReadOnly(VfsOp::Write, path).into()),
+        }
+    }
+
+    // Returns the absolute path of the given name after ensuring it is a single entry name
+    fn child(&self, name: &Path) -> RvResult<PathBuf>
+    {
+        let mut components = name.components();
+        match (components.next(), components.next()) {
+            (Some(Component::Normal(_)), None) => Ok(self.path.mash(name)),
+            _ => Err(PathError::invalid_name(name).into()),
+        }
+    }
+
+    // Returns the kind of the given name following links or None if it doesn't exist
+    fn stat(&self, name: &Path) -> RvResult<Option<SFlag>>
+    {
+        let path = self.child(name)?;
+        match &self.handle {
+            DirKind::Stdfs(fd, _) => match stat::fstatat(fd.as_raw_fd(), name, fcntl::AtFlags::empty()) {
+                Ok(x) => Ok(Some(kind(&x))),
+                Err(_) => Ok(None),
+            },
+            DirKind::Memfs(vfs) => Ok(vfs_kind(vfs, &path)),
+            DirKind::Overlayfs(vfs) => Ok(vfs_kind(vfs, &path)),
+            DirKind::Readonly(handle) => handle.stat(name),
+        }
+    }
+}
+
+// Returns the kind of the given path following links or None if it doesn't exist
+fn vfs_kind<T: VirtualFileSystem>(vfs: &T, path: &Path) -> Option<SFlag>
+{
+    if vfs.is_dir(path) {
+        Some(SFlag::S_IFDIR)
+    } else if vfs.is_file(path) {
+        Some(SFlag::S_IFREG)
+    } else if vfs.exists(path) {
+        Some(SFlag::S_IFLNK)
+    } else {
+        None
+    }
+}
+
+impl fmt::Debug for DirHandle
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        let backend = match &self.handle {
+            DirKind::Stdfs(..) => "Stdfs",
+            DirKind::Memfs(_) => "Memfs",
+            DirKind::Overlayfs(_) => "Overlayfs",
+            DirKind::Readonly(_) => "Readonly",
+        };
+        f.debug_struct("DirHandle").field("path", &self.path).field("backend", &backend).finish()
+    }
+}
+
+// Returns the file type bits of the given stat
+fn kind(stat: &FileStat) -> SFlag
+{
+    SFlag::from_bits_truncate(stat.st_mode & SFlag::S_IFMT.bits())
+}
+
+// Open the given name relative to the given directory with the given flags
+fn openat(fd: &OwnedFd, name: &Path, path: &Path, flags: OFlag) -> RvResult<File>
+{
+    let fd = fcntl::openat(fd.as_raw_fd(), name, flags, Mode::from_bits_truncate(0o666))
+        .map_err(|e| at_err(e, path))?;
+    Ok(File::from(unsafe { OwnedFd::from_raw_fd(fd) }))
+}
+
+// Normalize the errors of the at syscalls to path errors where possible
+fn at_err(err: Errno, path: &Path) -> RvError
+{
+    match err {
+        Errno::ENOENT => PathError::does_not_exist(path).into(),
+        Errno::ENOTDIR => PathError::is_not_dir(path).into(),
+        Errno::EISDIR => PathError::is_not_file(path).into(),
+        Errno::ENOTEMPTY => PathError::dir_contains_files(path).into(),
+        err => err.into(),
+    }
+}
+
+// Unit tests
+// -------------------------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests
+{
+    use crate::prelude::*;
+
+    #[test]
+    fn test_vfs_open_dir()
+    {
+        test_open_dir(assert_vfs_setup!(Vfs::memfs()));
+        test_open_dir(assert_vfs_setup!(Vfs::stdfs()));
+    }
+    fn test_open_dir((vfs, tmpdir): (Vfs, PathBuf))
+    {
+        let dir1 = tmpdir.mash("dir1");
+        let dir2 = dir1.mash("dir2");
+        let file1 = tmpdir.mash("file1");
+        let file2 = dir1.mash("file2");
+
+        // Errors
+        assert_eq!(
+            vfs.open_dir(&dir1).unwrap_err().downcast_ref::<PathError>(),
+            Some(&PathError::does_not_exist(&dir1))
+        );
+        assert_vfs_mkfile!(vfs, &file1);
+        assert_eq!(
+            vfs.open_dir(&file1).unwrap_err().downcast_ref::<PathError>(),
+            Some(&PathError::is_not_dir(&file1))
+        );
+        let handle = vfs.open_dir(&tmpdir).unwrap();
+        for name in ["", ".", "..", "dir1/file2", "/file1"] {
+            assert_eq!(
+                handle.mkfile(name).unwrap_err().downcast_ref::<PathError>(),
+                Some(&PathError::invalid_name(name))
+            );
+        }
+        assert_eq!(
+            handle.mkdir("file1").unwrap_err().downcast_ref::<PathError>(),
+            Some(&PathError::is_not_dir(&file1))
+        );
+
+        // Create entries relative to the handle
+        assert_eq!(handle.path(), &tmpdir);
+        assert_eq!(handle.mkdir("dir1").unwrap(), dir1);
+        assert_eq!(handle.mkdir("dir1").unwrap(), dir1);
+        assert_vfs_is_dir!(vfs, &dir1);
+        assert_eq!(
+            handle.mkfile("dir1").unwrap_err().downcast_ref::<PathError>(),
+            Some(&PathError::is_not_file(&dir1))
+        );
+        assert!(handle.exists("dir1"));
+        assert!(handle.is_dir("dir1"));
+        assert!(!handle.is_file("dir1"));
+        assert!(handle.is_file("file1"));
+        assert!(!handle.exists("foo"));
+
+        // Nested handles
+        let child = handle.open_dir("dir1").unwrap();
+        assert_eq!(child.path(), &dir1);
+        assert_eq!(
+            child.open_dir("file2").unwrap_err().downcast_ref::<PathError>(),
+            Some(&PathError::does_not_exist(&file2))
+        );
+        assert!(child.write_all("file2", "foobar").is_ok());
+        assert_vfs_read_all!(vfs, &file2, "foobar");
+        assert_eq!(child.read_all("file2").unwrap(), "foobar");
+        assert_eq!(child.mkfile("file2").unwrap(), file2);
+        assert_eq!(child.read_all("file2").unwrap(), "foobar");
+        assert_eq!(
+            handle.read_all("dir1").unwrap_err().downcast_ref::<PathError>(),
+            Some(&PathError::is_not_file(&dir1))
+        );
+        assert_eq!(child.mkdir("dir2").unwrap(), dir2);
+        let paths = child.entries().unwrap().sort_by_name().into_iter().map(|x| x.unwrap().path_buf());
+        assert_iter_eq(paths, vec![dir1.clone(), dir2.clone(), file2.clone()]);
+
+        // Remove
+        assert_eq!(
+            handle.remove("dir1").unwrap_err().downcast_ref::<PathError>(),
+            Some(&PathError::dir_contains_files(&dir1))
+        );
+        assert!(child.remove("file2").is_ok());
+        assert!(child.remove("file2").is_ok());
+        assert!(child.remove("dir2").is_ok());
+        assert!(handle.remove("dir1").is_ok());
+        assert_vfs_no_exists!(vfs, &dir1);
+
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+}
@@ -0,0 +1,144 @@
+use std::path::{Path, PathBuf};
+
+/// Reports whether a `*_ensured` operation created its path or found it already in place
+///
+/// Converge style callers can act on the outcome without pre-checking existence which would both
+/// double the work and race with other processes.
+///
+/// ### Examples
+/// ```
+/// use rivia::prelude::*;
+///
+/// let vfs = Vfs::memfs();
+/// let dir = vfs.root().mash("dir");
+/// assert_eq!(vfs.mkdir_p_ensured(&dir).unwrap(), Ensured::Created(dir.clone()));
+/// assert_eq!(vfs.mkdir_p_ensured(&dir).unwrap(), Ensured::Existed(dir.clone()));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Ensured
+{
+    /// The path didn't exist and was created
+    Created(PathBuf),
+
+    /// The path already existed as requested and was left untouched
+    Existed(PathBuf),
+}
+
+impl Ensured
+{
+    /// Returns true if the path was created
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// assert_eq!(vfs.mkfile_ensured("file").unwrap().created(), true);
+    /// assert_eq!(vfs.mkfile_ensured("file").unwrap().created(), false);
+    /// ```
+    pub fn created(&self) -> bool
+    {
+        matches!(self, Ensured::Created(_))
+    }
+
+    /// Returns the absolute path that was ensured
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// assert_eq!(vfs.mkfile_ensured("file").unwrap().path(), vfs.root().mash("file"));
+    /// ```
+    pub fn path(&self) -> &Path
+    {
+        match self {
+            Ensured::Created(x) | Ensured::Existed(x) => x,
+        }
+    }
+
+    /// Consumes the result returning the absolute path that was ensured
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// assert_eq!(vfs.mkfile_ensured("file").unwrap().into_path(), vfs.root().mash("file"));
+    /// ```
+    pub fn into_path(self) -> PathBuf
+    {
+        match self {
+            Ensured::Created(x) | Ensured::Existed(x) => x,
+        }
+    }
+}
+
+// Unit tests
+// -------------------------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests
+{
+    use crate::prelude::*;
+
+    #[test]
+    fn test_vfs_ensured()
+    {
+        test_ensured(assert_vfs_setup!(Vfs::memfs()));
+        test_ensured(assert_vfs_setup!(Vfs::stdfs()));
+    }
+    fn test_ensured((vfs, tmpdir): (Vfs, PathBuf))
+    {
+        let dir1 = tmpdir.mash("dir1");
+        let dir2 = dir1.mash("dir2");
+        let file1 = dir1.mash("file1");
+        let link1 = dir1.mash("link1");
+        let link2 = dir1.mash("link2");
+
+        // Directories including missing parents
+        assert_eq!(vfs.mkdir_p_ensured(&dir2).unwrap(), Ensured::Created(dir2.clone()));
+        assert_eq!(vfs.mkdir_p_ensured(&dir2).unwrap(), Ensured::Existed(dir2.clone()));
+        assert_eq!(vfs.mkdir_p_ensured(&dir1).unwrap(), Ensured::Existed(dir1.clone()));
+        assert_vfs_is_dir!(vfs, &dir2);
+
+        // Files
+        assert_eq!(vfs.mkfile_ensured(&file1).unwrap(), Ensured::Created(file1.clone()));
+        assert!(vfs.write_all(&file1, "foo").is_ok());
+        assert_eq!(vfs.mkfile_ensured(&file1).unwrap(), Ensured::Existed(file1.clone()));
+        assert_vfs_read_all!(vfs, &file1, "foo");
+
+        // Links given relative and absolute targets
+        assert_eq!(vfs.symlink_ensured(&link1, "file1").unwrap(), Ensured::Created(link1.clone()));
+        assert_eq!(vfs.symlink_ensured(&link1, "file1").unwrap(), Ensured::Existed(link1.clone()));
+        assert_eq!(vfs.symlink_ensured(&link1, &file1).unwrap(), Ensured::Existed(link1.clone()));
+        assert_vfs_readlink_abs!(vfs, &link1, &file1);
+
+        // Wrong kinds already in place are errors
+        assert_eq!(
+            vfs.mkdir_p_ensured(&file1).unwrap_err().downcast_ref::<PathError>(),
+            Some(&PathError::is_not_dir(&file1))
+        );
+        assert_eq!(
+            vfs.mkfile_ensured(&dir2).unwrap_err().downcast_ref::<PathError>(),
+            Some(&PathError::is_not_file(&dir2))
+        );
+        assert_eq!(
+            vfs.mkfile_ensured(file1.mash("file2")).unwrap_err().downcast_ref::<PathError>(),
+            Some(&PathError::is_not_dir(&file1))
+        );
+        assert_eq!(
+            vfs.symlink_ensured(&link1, &dir2).unwrap_err().downcast_ref::<PathError>(),
+            Some(&PathError::exists_already(&link1))
+        );
+        assert_eq!(
+            vfs.symlink_ensured(&file1, &dir2).unwrap_err().downcast_ref::<PathError>(),
+            Some(&PathError::exists_already(&file1))
+        );
+
+        // Dangling links are fine as long as they point to the target
+        assert_eq!(vfs.symlink_ensured(&link2, "missing").unwrap(), Ensured::Created(link2.clone()));
+        assert_eq!(vfs.symlink_ensured(&link2, "missing").unwrap(), Ensured::Existed(link2.clone()));
+
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+}
@@ -1,13 +1,34 @@
-use std::{cmp::Ordering, fmt, path::Path};
+use std::{
+    cmp::Ordering,
+    collections::HashMap,
+    fmt,
+    path::{Path, PathBuf},
+    sync::{
+        mpsc::{self, Receiver, SyncSender},
+        Arc, Condvar, Mutex,
+    },
+    thread,
+    time::{Duration, Instant, SystemTime},
+};
+
+#[cfg(feature = "regex")]
+use std::io::Read;
+
+#[cfg(feature = "regex")]
+use regex::Regex;
 
 use super::entry_iter::EntryIter;
 use crate::{
     errors::*,
-    sys::{Entry, VfsEntry},
-    trying,
+    sys::{self, CancelToken, Entry, Kind, OpContext, PathExt, VfsEntry, VfsMetadata},
 };
 
-pub(crate) const DEFAULT_MAX_DESCRIPTORS: u16 = 50;
+// Number of leading bytes checked for a NUL byte to detect binary files, same as grep
+#[cfg(feature = "regex")]
+const BINARY_CHECK_SIZE: usize = 8 * 1024;
+
+// Number of results the parallel workers may get ahead of the iterator before blocking
+const WALK_CHANNEL_SIZE: usize = 1024;
 
 /// Provides a builder pattern for constructing iterators for travsersing a virtual file system
 ///
@@ -55,10 +76,28 @@ pub struct Entries {
     pub(crate) root: VfsEntry,
     pub(crate) dirs: bool,
     pub(crate) files: bool,
+    pub(crate) symlinks: bool,
     pub(crate) follow: bool,
+    pub(crate) skip_vanished: bool,
     pub(crate) min_depth: usize,
     pub(crate) max_depth: usize,
+    pub(crate) max_links: usize,
+    pub(crate) max_items: usize,
+    pub(crate) timeout: Option<Duration>,
+    pub(crate) ctx: Option<OpContext>,
+    pub(crate) modified_after: Option<SystemTime>,
+    pub(crate) modified_before: Option<SystemTime>,
+    pub(crate) created_after: Option<SystemTime>,
+    pub(crate) larger_than: Option<u64>,
+    pub(crate) smaller_than: Option<u64>,
+    #[cfg(feature = "regex")]
+    pub(crate) contents_match: Option<Regex>,
+    pub(crate) glob: Option<String>,
+    pub(crate) includes: Vec<String>,
+    pub(crate) excludes: Vec<String>,
+    pub(crate) max_content_size: u64,
     pub(crate) max_descriptors: u16,
+    pub(crate) parallel: usize,
     pub(crate) dirs_first: bool,
     pub(crate) files_first: bool,
     pub(crate) sort_by_name: bool,
@@ -68,7 +107,10 @@ pub struct Entries {
     #[allow(clippy::type_complexity)]
     pub(crate) sort: Option<Box<dyn Fn(&VfsEntry, &VfsEntry) -> Ordering + Send + Sync + 'static>>,
     #[allow(clippy::type_complexity)]
-    pub(crate) iter_from: Box<dyn Fn(&Path, bool) -> RvResult<EntryIter> + Send + Sync + 'static>,
+    pub(crate) iter_from: Arc<dyn Fn(&Path, bool) -> RvResult<EntryIter> + Send + Sync + 'static>,
+    #[cfg(feature = "regex")]
+    #[allow(clippy::type_complexity)]
+    pub(crate) read_from: Box<dyn Fn(&Path) -> RvResult<Box<dyn sys::ReadSeek>> + Send + Sync + 'static>,
 }
 
 impl Entries {
@@ -91,6 +133,7 @@ impl Entries {
     pub fn dirs(mut self) -> Self {
         self.dirs = true;
         self.files = false;
+        self.symlinks = false;
         self
     }
 
@@ -112,6 +155,56 @@ impl Entries {
     pub fn files(mut self) -> Self {
         self.dirs = false;
         self.files = true;
+        self.symlinks = false;
+        self
+    }
+
+    /// Filter entries down to just links
+    ///
+    /// * Default is `false`
+    /// * Links are yielded regardless of the kind of target they point to
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// assert_vfs_mkfile!(vfs, "file");
+    /// assert_vfs_symlink!(vfs, "link", "file");
+    /// let mut iter = vfs.entries(vfs.root()).unwrap().symlinks().into_iter();
+    /// assert_eq!(iter.next().unwrap().unwrap().path(), vfs.root().mash("link"));
+    /// assert!(iter.next().is_none());
+    /// ```
+    pub fn symlinks(mut self) -> Self {
+        self.dirs = false;
+        self.files = false;
+        self.symlinks = true;
+        self
+    }
+
+    /// Filter entries down to those matching any of the given kinds
+    ///
+    /// * Default is all kinds
+    /// * Replaces any previous `dirs`, `files` or `symlinks` filter
+    /// * An empty list of kinds disables filtering by kind
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// assert_vfs_mkdir_p!(vfs, "dir");
+    /// assert_vfs_mkfile!(vfs, "file");
+    /// assert_vfs_symlink!(vfs, "link", "dir");
+    /// let mut iter = vfs.entries(vfs.root()).unwrap().any_of(&[Kind::File, Kind::Symlink]).sort_by_name().into_iter();
+    /// assert_eq!(iter.next().unwrap().unwrap().path(), vfs.root().mash("file"));
+    /// assert_eq!(iter.next().unwrap().unwrap().path(), vfs.root().mash("link"));
+    /// assert!(iter.next().is_none());
+    /// ```
+    pub fn any_of(mut self, kinds: &[Kind]) -> Self {
+        self.dirs = kinds.contains(&Kind::Dir);
+        self.files = kinds.contains(&Kind::File);
+        self.symlinks = kinds.contains(&Kind::Symlink);
         self
     }
 
@@ -141,6 +234,28 @@ impl Entries {
         self
     }
 
+    /// Skip entries that are removed while being traversed
+    ///
+    /// * Default is `true`
+    /// * Entries removed between reading their directory and reading their metadata, as happens
+    ///   when scanning live directories, are skipped and reported by `EntriesIter::vanished`
+    /// * When `false` such entries are yielded as `PathError::Vanished` errors
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// assert_vfs_mkfile!(vfs, "file");
+    /// let mut iter = vfs.entries(vfs.root()).unwrap().skip_vanished(false).into_iter();
+    /// assert_eq!(iter.by_ref().count(), 2);
+    /// assert!(iter.vanished().is_empty());
+    /// ```
+    pub fn skip_vanished(mut self, yes: bool) -> Self {
+        self.skip_vanished = yes;
+        self
+    }
+
     /// Set the min depth that Entries should traverse
     ///
     /// * Default is `0`
@@ -148,7 +263,7 @@ impl Entries {
     /// * To only include the given path and not recurse set `max_depth(0)`
     /// * By default recursion is unbounded. use `max_depth(VALUE)` to bound it
     /// * Setting `min_depth` first will autocorrect later calls to `max_depth` to be consistent
-    /// in relation to `min_depth`. The inverse would be true if `max_depth` was called first.
+    ///   in relation to `min_depth`. The inverse would be true if `max_depth` was called first.
     ///
     /// ### Examples
     /// ```
@@ -176,7 +291,7 @@ impl Entries {
     /// * To only include the given path and not recurse set `max_depth(0)`
     /// * By default recursion is unbounded. use `max_depth(VALUE)` to bound it
     /// * Setting `min_depth` first will autocorrect later calls to `max_depth` to be consistent
-    /// in relation to `min_depth`. The inverse would be true if `max_depth` was called first.
+    ///   in relation to `min_depth`. The inverse would be true if `max_depth` was called first.
     ///
     /// ### Examples
     /// ```
@@ -199,6 +314,399 @@ impl Entries {
         self
     }
 
+    /// Set the max number of link hops allowed when resolving links
+    ///
+    /// * Default is `40` which matches the Linux kernel's limit and is also the upper bound
+    /// * Links requiring more hops to resolve are yielded as a `PathError::TooManyLinks` error
+    /// * To reject all links set `max_links(0)`
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let file = vfs.root().mash("file");
+    /// let link1 = vfs.root().mash("link1");
+    /// let link2 = vfs.root().mash("link2");
+    /// assert_vfs_mkfile!(vfs, &file);
+    /// assert_vfs_symlink!(vfs, &link1, &file);
+    /// assert_vfs_symlink!(vfs, &link2, &link1);
+    /// let mut iter = vfs.entries(&link2).unwrap().max_links(1).into_iter();
+    /// assert_eq!(iter.next().unwrap().unwrap_err().to_string(), PathError::too_many_links(&link2).to_string());
+    /// ```
+    pub fn max_links(mut self, max: usize) -> Self {
+        self.max_links = max.min(sys::DEFAULT_MAX_LINKS);
+        self
+    }
+
+    /// Set the max number of entries to yield before terminating the traversal early
+    ///
+    /// * Default is `std::usize::MAX`
+    /// * Use `EntriesIter::truncated` to check if entries were left unvisited
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// assert_vfs_mkfile!(vfs, "file1");
+    /// assert_vfs_mkfile!(vfs, "file2");
+    /// let mut iter = vfs.entries(vfs.root()).unwrap().max_items(2).into_iter();
+    /// assert_eq!(iter.by_ref().count(), 2);
+    /// assert_eq!(iter.truncated(), true);
+    /// ```
+    pub fn max_items(mut self, max: usize) -> Self {
+        self.max_items = max;
+        self
+    }
+
+    /// Walk subdirectories on a pool of the given number of worker threads
+    ///
+    /// * Default is `1` i.e. the traversal runs on the calling thread
+    /// * Results are sent back over a channel in no particular order other than directories being
+    ///   yielded before their contents
+    /// * Filters are still applied on the calling thread as the results are yielded
+    /// * Each worker holds at most one directory open at a time
+    /// * Sorting, `contents_first` and `pre_op` require an ordered traversal so they fall back on
+    ///   the calling thread
+    /// * Most useful for large trees on Stdfs where reading directories dominates the runtime
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// assert_vfs_mkdir_p!(vfs, "dir1");
+    /// assert_vfs_mkfile!(vfs, "dir1/file1");
+    /// assert_vfs_mkfile!(vfs, "file2");
+    /// let mut paths = vfs.entries(vfs.root()).unwrap().parallel(4).files().collect_paths().unwrap();
+    /// paths.sort();
+    /// assert_eq!(paths, vec![vfs.root().mash("dir1/file1"), vfs.root().mash("file2")]);
+    /// ```
+    pub fn parallel(mut self, workers: usize) -> Self {
+        self.parallel = workers;
+        self
+    }
+
+    /// Set the max duration the traversal is allowed to run before terminating early
+    ///
+    /// * Default is no timeout
+    /// * The timeout starts when the iterator is created
+    /// * Checked between entries thus a single slow filesystem call may overrun it
+    /// * Use `EntriesIter::truncated` to check if entries were left unvisited
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    /// use std::time::Duration;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// assert_vfs_mkfile!(vfs, "file1");
+    /// let mut iter = vfs.entries(vfs.root()).unwrap().timeout(Duration::ZERO).into_iter();
+    /// assert!(iter.next().is_none());
+    /// assert_eq!(iter.truncated(), true);
+    /// ```
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Set a token that can be used to cancel the traversal from elsewhere e.g. a UI thread
+    ///
+    /// * Default is no token
+    /// * Checked between entries thus a single slow filesystem call may delay it
+    /// * Once cancelled a `VfsError::Cancelled` is yielded and the traversal terminates
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// assert_vfs_mkfile!(vfs, "file1");
+    /// let token = CancelToken::new();
+    /// let mut iter = vfs.entries(vfs.root()).unwrap().cancel_token(&token).into_iter();
+    /// assert_eq!(iter.next().unwrap().unwrap().path(), vfs.root());
+    /// token.cancel();
+    /// assert_eq!(iter.next().unwrap().unwrap_err().downcast_ref::<VfsError>(), Some(&VfsError::Cancelled));
+    /// assert!(iter.next().is_none());
+    /// ```
+    pub fn cancel_token(mut self, token: &CancelToken) -> Self {
+        self.ctx = Some(self.ctx.take().unwrap_or_default().cancel_token(token));
+        self
+    }
+
+    /// Set the context used to cancel the traversal, report progress and collect metrics
+    ///
+    /// * Default is no context
+    /// * Replaces any token previously set with `cancel_token`
+    /// * Each entry yielded is counted and reported to the context's progress callback
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// assert_vfs_mkfile!(vfs, "file1");
+    /// let ctx = OpContext::new();
+    /// assert_eq!(vfs.entries(vfs.root()).unwrap().context(&ctx).into_iter().count(), 2);
+    /// assert_eq!(ctx.metrics().entries, 2);
+    /// ```
+    pub fn context(mut self, ctx: &OpContext) -> Self {
+        self.ctx = Some(ctx.clone());
+        self
+    }
+
+    /// Filter entries down to those modified within the given duration of now
+    ///
+    /// * Default is no filtering
+    /// * Equivalent to `find -mtime -N` with the window computed when this is called
+    /// * Directories that are filtered out are still traversed
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    /// use std::time::Duration;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// assert_vfs_mkfile!(vfs, "file1");
+    /// let mut iter = vfs.entries(vfs.root()).unwrap().files().modified_within(Duration::from_secs(60)).into_iter();
+    /// assert_eq!(iter.next().unwrap().unwrap().path(), vfs.root().mash("file1"));
+    /// assert!(iter.next().is_none());
+    /// ```
+    pub fn modified_within(mut self, duration: Duration) -> Self {
+        self.modified_after = Some(SystemTime::now().checked_sub(duration).unwrap_or(SystemTime::UNIX_EPOCH));
+        self
+    }
+
+    /// Filter entries down to those last modified before the given time
+    ///
+    /// * Default is no filtering
+    /// * Directories that are filtered out are still traversed
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    /// use std::time::SystemTime;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// assert_vfs_mkfile!(vfs, "file1");
+    /// let mut iter = vfs.entries(vfs.root()).unwrap().modified_before(SystemTime::UNIX_EPOCH).into_iter();
+    /// assert!(iter.next().is_none());
+    /// ```
+    pub fn modified_before(mut self, time: SystemTime) -> Self {
+        self.modified_before = Some(time);
+        self
+    }
+
+    /// Filter entries down to those created after the given time
+    ///
+    /// * Default is no filtering
+    /// * Falls back on the modified time when the filesystem doesn't track creation times
+    /// * Directories that are filtered out are still traversed
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    /// use std::time::SystemTime;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// assert_vfs_mkfile!(vfs, "file1");
+    /// let mut iter = vfs.entries(vfs.root()).unwrap().files().created_after(SystemTime::UNIX_EPOCH).into_iter();
+    /// assert_eq!(iter.next().unwrap().unwrap().path(), vfs.root().mash("file1"));
+    /// assert!(iter.next().is_none());
+    /// ```
+    pub fn created_after(mut self, time: SystemTime) -> Self {
+        self.created_after = Some(time);
+        self
+    }
+
+    /// Filter entries down to files larger than the given number of bytes
+    ///
+    /// * Default is no filtering
+    /// * Only files are matched i.e. directories are filtered out but still traversed
+    /// * Uses the size cached on the entry during traversal so no additional calls are made
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// assert_vfs_write_all!(vfs, "file1", "foo");
+    /// assert_vfs_write_all!(vfs, "file2", "foobar");
+    /// let mut iter = vfs.entries(vfs.root()).unwrap().larger_than(3).into_iter();
+    /// assert_eq!(iter.next().unwrap().unwrap().path(), vfs.root().mash("file2"));
+    /// assert!(iter.next().is_none());
+    /// ```
+    pub fn larger_than(mut self, bytes: u64) -> Self {
+        self.larger_than = Some(bytes);
+        self
+    }
+
+    /// Filter entries down to files smaller than the given number of bytes
+    ///
+    /// * Default is no filtering
+    /// * Only files are matched i.e. directories are filtered out but still traversed
+    /// * Uses the size cached on the entry during traversal so no additional calls are made
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// assert_vfs_write_all!(vfs, "file1", "foo");
+    /// assert_vfs_write_all!(vfs, "file2", "foobar");
+    /// let mut iter = vfs.entries(vfs.root()).unwrap().smaller_than(6).into_iter();
+    /// assert_eq!(iter.next().unwrap().unwrap().path(), vfs.root().mash("file1"));
+    /// assert!(iter.next().is_none());
+    /// ```
+    pub fn smaller_than(mut self, bytes: u64) -> Self {
+        self.smaller_than = Some(bytes);
+        self
+    }
+
+    /// Filter entries down to files whose contents match the given regex
+    ///
+    /// * Default is no filtering
+    /// * Only files are matched i.e. directories are filtered out but still traversed
+    /// * Files are only opened once they've passed all other filters
+    /// * Files larger than `max_content_size` and binary files i.e. those with a NUL byte in their
+    ///   first 8K are skipped
+    ///
+    /// ### Examples
+    /// ```
+    /// use regex::Regex;
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// assert_vfs_write_all!(vfs, "file1", "foo");
+    /// assert_vfs_write_all!(vfs, "file2", "foobar");
+    /// let regex = Regex::new("ba[rz]").unwrap();
+    /// let mut iter = vfs.entries(vfs.root()).unwrap().contents_match(regex).into_iter();
+    /// assert_eq!(iter.next().unwrap().unwrap().path(), vfs.root().mash("file2"));
+    /// assert!(iter.next().is_none());
+    /// ```
+    #[cfg(feature = "regex")]
+    pub fn contents_match(mut self, regex: Regex) -> Self {
+        self.contents_match = Some(regex);
+        self
+    }
+
+    /// Set the size of the largest file whose contents will be searched by `contents_match`
+    ///
+    /// * Default is 10MiB
+    /// * Larger files are filtered out without being opened
+    ///
+    /// ### Examples
+    /// ```
+    /// use regex::Regex;
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// assert_vfs_write_all!(vfs, "file1", "foo");
+    /// assert_vfs_write_all!(vfs, "file2", "foofoo");
+    /// let regex = Regex::new("foo").unwrap();
+    /// let mut iter = vfs.entries(vfs.root()).unwrap().contents_match(regex).max_content_size(3).into_iter();
+    /// assert_eq!(iter.next().unwrap().unwrap().path(), vfs.root().mash("file1"));
+    /// assert!(iter.next().is_none());
+    /// ```
+    #[cfg(feature = "regex")]
+    pub fn max_content_size(mut self, bytes: u64) -> Self {
+        self.max_content_size = bytes;
+        self
+    }
+
+    /// Filter entries down to those whose paths match the given glob pattern
+    ///
+    /// * Default is no filtering
+    /// * Supports `*`, `?`, `**` and character classes e.g. `[a-z]`
+    /// * Relative patterns are matched against paths relative to the root of the traversal
+    /// * Directories that don't match are filtered out but still traversed
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// assert_vfs_mkdir_p!(vfs, "dir1");
+    /// assert_vfs_mkfile!(vfs, "dir1/file1.conf");
+    /// assert_vfs_mkfile!(vfs, "dir1/file2.txt");
+    /// let mut iter = vfs.entries(vfs.root()).unwrap().glob("**/*.conf").into_iter();
+    /// assert_eq!(iter.next().unwrap().unwrap().path(), vfs.root().mash("dir1/file1.conf"));
+    /// assert!(iter.next().is_none());
+    /// ```
+    pub fn glob(mut self, pattern: &str) -> Self {
+        self.glob = Some(self.root.path().join(pattern).to_string_lossy().into_owned());
+        self
+    }
+
+    /// Filter entries down to those whose paths match any of the included glob patterns
+    ///
+    /// * Default is no filtering
+    /// * May be called multiple times to include multiple patterns
+    /// * Patterns without a separator match the entry's name at any depth e.g. `*.rs`
+    /// * Patterns with a separator are matched against paths relative to the root of the traversal
+    /// * Directories that don't match are filtered out but still traversed
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// assert_vfs_mkdir_p!(vfs, "src");
+    /// assert_vfs_mkfile!(vfs, "src/lib.rs");
+    /// assert_vfs_mkfile!(vfs, "README.md");
+    /// let mut iter = vfs.entries(vfs.root()).unwrap().include("*.rs").into_iter();
+    /// assert_eq!(iter.next().unwrap().unwrap().path(), vfs.root().mash("src/lib.rs"));
+    /// assert!(iter.next().is_none());
+    /// ```
+    pub fn include(mut self, pattern: &str) -> Self {
+        let pattern = self.pattern(pattern);
+        self.includes.push(pattern);
+        self
+    }
+
+    /// Exclude entries whose paths match the given glob pattern along with everything beneath them
+    ///
+    /// * Default is no exclusions
+    /// * May be called multiple times to exclude multiple patterns
+    /// * Patterns are resolved the same as for `include`
+    /// * Excluded directories are pruned so their contents are never read
+    /// * A trailing `/**` excludes the directory itself as well e.g. `target/**`
+    /// * Links are matched by their own path rather than their target's
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// assert_vfs_mkdir_p!(vfs, "target/debug");
+    /// assert_vfs_mkfile!(vfs, "target/debug/main");
+    /// assert_vfs_mkfile!(vfs, "main.rs");
+    /// let mut iter = vfs.entries(vfs.root()).unwrap().exclude("target/**").min_depth(1).into_iter();
+    /// assert_eq!(iter.next().unwrap().unwrap().path(), vfs.root().mash("main.rs"));
+    /// assert!(iter.next().is_none());
+    /// ```
+    pub fn exclude(mut self, pattern: &str) -> Self {
+        if let Some(dir) = pattern.strip_suffix("/**") {
+            let dir = self.pattern(dir);
+            self.excludes.push(dir);
+        }
+        let pattern = self.pattern(pattern);
+        self.excludes.push(pattern);
+        self
+    }
+
+    // Resolve the given include or exclude pattern to an absolute pattern rooted at the traversal
+    fn pattern(&self, pattern: &str) -> String {
+        let root = self.root.path();
+        match pattern.contains('/') {
+            true => root.join(pattern),
+            false => root.join("**").join(pattern),
+        }
+        .to_string_lossy()
+        .into_owned()
+    }
+
     /// Set the pre-operation function to run over each directory before processing
     ///
     /// * Defaults to `None`
@@ -284,21 +792,141 @@ impl Entries {
         self.sort = Some(Box::new(cmp));
         self
     }
-}
 
-impl fmt::Debug for Entries {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> std::result::Result<(), fmt::Error> {
-        f.debug_struct("Entries")
-            .field("root", &self.root)
-            .field("dirs", &self.dirs)
-            .field("files", &self.files)
-            .field("follow", &self.follow)
-            .field("min_depth", &self.min_depth)
-            .field("max_depth", &self.max_depth)
-            .field("max_descriptors", &self.max_descriptors)
-            .field("dirs_first", &self.dirs_first)
-            .field("files_first", &self.files_first)
-            .field("contents_first", &self.contents_first)
+    /// Iterate over the entries collecting the paths of those that are directories
+    ///
+    /// * Applies the current filters and sorting on top of limiting the results to directories
+    /// * Fails on the first error encountered
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let dir1 = vfs.root().mash("dir1");
+    /// let file1 = vfs.root().mash("file1");
+    /// assert_vfs_mkdir_p!(vfs, &dir1);
+    /// assert_vfs_mkfile!(vfs, &file1);
+    /// assert_eq!(vfs.entries(vfs.root()).unwrap().min_depth(1).collect_dirs().unwrap(), vec![dir1]);
+    /// ```
+    pub fn collect_dirs(self) -> RvResult<Vec<PathBuf>> {
+        self.dirs().collect_paths()
+    }
+
+    /// Iterate over the entries collecting the paths of those that are files
+    ///
+    /// * Applies the current filters and sorting on top of limiting the results to files
+    /// * Fails on the first error encountered
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let dir1 = vfs.root().mash("dir1");
+    /// let file1 = dir1.mash("file1");
+    /// assert_vfs_mkdir_p!(vfs, &dir1);
+    /// assert_vfs_mkfile!(vfs, &file1);
+    /// assert_eq!(vfs.entries(vfs.root()).unwrap().collect_files().unwrap(), vec![file1]);
+    /// ```
+    pub fn collect_files(self) -> RvResult<Vec<PathBuf>> {
+        self.files().collect_paths()
+    }
+
+    /// Iterate over the entries collecting their paths
+    ///
+    /// * Applies the current filters and sorting
+    /// * Fails on the first error encountered
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let dir1 = vfs.root().mash("dir1");
+    /// let file1 = dir1.mash("file1");
+    /// assert_vfs_mkdir_p!(vfs, &dir1);
+    /// assert_vfs_mkfile!(vfs, &file1);
+    /// let paths = vfs.entries(vfs.root()).unwrap().sort_by_name().collect_paths().unwrap();
+    /// assert_eq!(paths, vec![vfs.root(), dir1, file1]);
+    /// ```
+    pub fn collect_paths(self) -> RvResult<Vec<PathBuf>> {
+        self.into_iter().map(|x| x.map(|x| x.path_buf())).collect()
+    }
+
+    /// Iterate over the entries assembling them into a nested [`TreeNode`] structure
+    ///
+    /// * The root of the traversal is always the root of the tree even when filtered out
+    /// * Children are kept in the order they were yielded so sorting options apply
+    /// * Entries whose parent directory was filtered out are attached to their nearest ancestor
+    /// * Fails on the first error encountered
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// assert_vfs_mkdir_p!(vfs, "dir1");
+    /// assert_vfs_mkfile!(vfs, "dir1/file1");
+    /// let tree = vfs.entries(vfs.root()).unwrap().into_tree().unwrap();
+    /// assert_eq!(tree.children[0].name, "dir1");
+    /// assert_eq!(tree.children[0].children[0].name, "file1");
+    /// assert_eq!(tree.children[0].children[0].kind, Kind::File);
+    /// ```
+    pub fn into_tree(self) -> RvResult<TreeNode> {
+        let root = self.root.clone().follow(self.follow);
+        let mut nodes = vec![Some(TreeNode::from(&root))];
+        let mut index = HashMap::from([(root.path_buf(), 0)]);
+        for entry in self {
+            let entry = entry?;
+            if entry.path() != root.path() {
+                index.insert(entry.path_buf(), nodes.len());
+                nodes.push(Some(TreeNode::from(&entry)));
+            }
+        }
+
+        // Link each node to its nearest ancestor in the tree falling back on the root
+        let mut children = vec![vec![]; nodes.len()];
+        for (i, node) in nodes.iter().enumerate().skip(1) {
+            let path = &node.as_ref().unwrap().path;
+            let parent = path.ancestors().skip(1).find_map(|x| index.get(x)).map_or(0, |x| *x);
+            children[parent].push(i);
+        }
+        Ok(TreeNode::assemble(&mut nodes, &children, 0))
+    }
+}
+
+impl fmt::Debug for Entries {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> std::result::Result<(), fmt::Error> {
+        let mut f = f.debug_struct("Entries");
+        f.field("root", &self.root)
+            .field("dirs", &self.dirs)
+            .field("files", &self.files)
+            .field("symlinks", &self.symlinks)
+            .field("follow", &self.follow)
+            .field("skip_vanished", &self.skip_vanished)
+            .field("min_depth", &self.min_depth)
+            .field("max_depth", &self.max_depth)
+            .field("max_links", &self.max_links)
+            .field("max_items", &self.max_items)
+            .field("timeout", &self.timeout)
+            .field("ctx", &self.ctx)
+            .field("modified_after", &self.modified_after)
+            .field("modified_before", &self.modified_before)
+            .field("created_after", &self.created_after)
+            .field("larger_than", &self.larger_than)
+            .field("smaller_than", &self.smaller_than);
+        #[cfg(feature = "regex")]
+        f.field("contents_match", &self.contents_match);
+        f.field("glob", &self.glob)
+            .field("includes", &self.includes)
+            .field("excludes", &self.excludes)
+            .field("max_content_size", &self.max_content_size)
+            .field("max_descriptors", &self.max_descriptors)
+            .field("parallel", &self.parallel)
+            .field("dirs_first", &self.dirs_first)
+            .field("files_first", &self.files_first)
+            .field("contents_first", &self.contents_first)
             .field("sort_by_name", &self.sort_by_name)
             .finish()
     }
@@ -310,26 +938,118 @@ impl IntoIterator for Entries {
     type Item = RvResult<VfsEntry>;
 
     fn into_iter(self) -> EntriesIter {
+        let deadline = self.timeout.map(|x| Instant::now() + x);
         let mut iter = EntriesIter {
             opts: self,
             started: false,
+            yielded: 0,
+            deadline,
+            truncated: false,
             open_descriptors: 0,
+            vanished: vec![],
+            stats: EntriesStats::default(),
+            walked: None,
             filter: None,
             deferred: vec![],
             iters: vec![],
         };
 
         // Create any configured filters
+        let mut kinds = vec![];
+        if iter.opts.dirs {
+            kinds.push(Kind::Dir);
+        }
         if iter.opts.files {
-            iter.filter = Some(Box::new(|x: &VfsEntry| -> bool { x.is_file() }));
-        } else if iter.opts.dirs {
-            iter.filter = Some(Box::new(|x: &VfsEntry| -> bool { x.is_dir() }));
+            kinds.push(Kind::File);
+        }
+        if iter.opts.symlinks {
+            kinds.push(Kind::Symlink);
+        }
+        if !kinds.is_empty() {
+            iter.filter = Some(Box::new(move |x: &VfsEntry| -> bool { kinds.iter().any(|y| x.is_kind(*y)) }));
         }
 
         iter
     }
 }
 
+/// Describes the coverage of a traversal as returned by the EntriesIter `stats`
+///
+/// * Counts are updated as the traversal progresses so they're complete once it's exhausted
+/// * Entries filtered out aren't counted as yielded but directories are still visited
+///
+/// ### Examples
+/// ```
+/// use rivia::prelude::*;
+///
+/// let vfs = Vfs::memfs();
+/// assert_vfs_mkdir_p!(vfs, "dir1");
+/// assert_vfs_mkfile!(vfs, "dir1/file1");
+/// let mut iter = vfs.entries(vfs.root()).unwrap().into_iter();
+/// assert_eq!(iter.by_ref().count(), 3);
+/// assert_eq!(iter.stats(), EntriesStats { dirs: 2, files: 1, symlinks: 0, errors: 0, max_depth: 2 });
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct EntriesStats {
+    pub dirs: usize,      // directories visited i.e. opened to traverse their contents
+    pub files: usize,     // files yielded
+    pub symlinks: usize,  // links yielded regardless of their target
+    pub errors: usize,    // errors yielded in place of entries e.g. looping links
+    pub max_depth: usize, // deepest level reached where the root is at depth 0
+}
+
+/// Describes an entry and its children as assembled by the Entries `into_tree`
+///
+/// * `kind` reports `Kind::Symlink` for links and the kind of the entry otherwise
+/// * Useful for rendering and recursive algorithms that are awkward over a flat iterator
+///
+/// ### Examples
+/// ```
+/// use rivia::prelude::*;
+///
+/// let vfs = Vfs::memfs();
+/// assert_vfs_write_all!(vfs, "file1", "foo");
+/// let tree = vfs.entries(vfs.root()).unwrap().into_tree().unwrap();
+/// assert_eq!(tree.kind, Kind::Dir);
+/// assert_eq!(tree.children[0].path, vfs.root().mash("file1"));
+/// assert_eq!(tree.children[0].metadata.size, 3);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TreeNode {
+    pub name: String,            // name of the entry or the full path for the root directory
+    pub path: PathBuf,           // absolute path of the entry
+    pub kind: Kind,              // kind of the entry
+    pub metadata: VfsMetadata,   // cached filesystem properties of the entry
+    pub children: Vec<TreeNode>, // child nodes in the order they were yielded
+}
+
+impl TreeNode {
+    // Take the node at the given index out of the given nodes along with its children recursively
+    fn assemble(nodes: &mut [Option<TreeNode>], children: &[Vec<usize>], i: usize) -> TreeNode {
+        let mut node = nodes[i].take().unwrap();
+        node.children = children[i].iter().map(|x| TreeNode::assemble(nodes, children, *x)).collect();
+        node
+    }
+}
+
+impl From<&VfsEntry> for TreeNode {
+    fn from(entry: &VfsEntry) -> Self {
+        let path = if entry.following() { entry.alt() } else { entry.path() };
+        let kind = match entry.is_symlink() {
+            true => Kind::Symlink,
+            false if entry.is_dir() => Kind::Dir,
+            false => Kind::File,
+        };
+        TreeNode {
+            name: path.base().unwrap_or_else(|_| path.to_string_lossy().into_owned()),
+            path: path.to_path_buf(),
+            kind,
+            metadata: entry.metadata(),
+            children: vec![],
+        }
+    }
+}
+
 /// Actual underlying iterator for traversing a virtual file system
 ///
 /// Use the VFS builder functions to construct an instance e.g. vfs.entries or Stdfs::entries.
@@ -358,6 +1078,15 @@ pub struct EntriesIter {
     // Flag to track root being processed which might not be a directory
     started: bool,
 
+    // Number of entries yielded so far
+    yielded: usize,
+
+    // Point in time after which the traversal is terminated early
+    deadline: Option<Instant>,
+
+    // Flag to track the traversal being terminated early with entries left unvisited
+    truncated: bool,
+
     // Number of open file descriptors
     open_descriptors: u16,
 
@@ -367,6 +1096,15 @@ pub struct EntriesIter {
     // Stack of deferred directories to return after their contents
     deferred: Vec<VfsEntry>,
 
+    // Entries skipped because they were removed while being traversed
+    vanished: Vec<PathBuf>,
+
+    // Coverage of the traversal so far
+    stats: EntriesStats,
+
+    // Channel the results of a parallel traversal are received over once started
+    walked: Option<Receiver<Walked>>,
+
     // Optional filter that yields only entries that match the predicate
     #[allow(clippy::type_complexity)]
     filter: Option<Box<dyn FnMut(&VfsEntry) -> bool>>,
@@ -377,6 +1115,18 @@ impl EntriesIter {
     /// None will be returned if the given entry was filtered out.
     fn process(&mut self, entry: VfsEntry) -> Option<RvResult<VfsEntry>> {
         let depth = self.iters.len(); // save depth before possible recursion
+        self.stats.max_depth = self.stats.max_depth.max(depth);
+
+        // Throw an error if the link requires too many hops to resolve
+        if entry.link_hops() > self.opts.max_links {
+            let path = if entry.following() { entry.alt() } else { entry.path() };
+            return Some(Err(PathError::too_many_links(path).into()));
+        }
+
+        // Prune excluded entries before their contents are read
+        if matches(&self.opts.excludes, &entry) {
+            return None;
+        }
 
         if entry.is_dir() && (!entry.is_symlink() || self.opts.follow) {
             // Throw an error if link looping is detected
@@ -390,7 +1140,12 @@ impl EntriesIter {
                 if let Some(pre_op) = &mut self.opts.pre_op {
                     trying!((pre_op)(&entry));
                 }
-                self.iters.push(trying!((self.opts.iter_from)(entry.path(), self.opts.follow)));
+                let iter = match self.open_iter(entry.path()) {
+                    Err(err) if self.vanish(&err) => return None,
+                    result => trying!(result),
+                };
+                self.iters.push(iter);
+                self.stats.dirs += 1;
 
                 // Cache entries if we've hit our open file descriptors max or if were sorting the
                 // entries.
@@ -412,6 +1167,12 @@ impl EntriesIter {
             }
         }
 
+        self.filter_entry(entry, depth)
+    }
+
+    // Apply the filters to the given entry found at the given depth. None will be returned if the
+    // entry was filtered out or deferred.
+    fn filter_entry(&mut self, entry: VfsEntry, depth: usize) -> Option<RvResult<VfsEntry>> {
         // Return None if min depth marker is not satisfied
         if depth < self.opts.min_depth {
             return None;
@@ -430,39 +1191,99 @@ impl EntriesIter {
             }
         }
 
+        // Filter on timestamps as directed
+        if matches!(self.opts.modified_after, Some(x) if entry.modified() < x)
+            || matches!(self.opts.modified_before, Some(x) if entry.modified() >= x)
+            || matches!(self.opts.created_after, Some(x) if entry.created() <= x)
+        {
+            return None;
+        }
+
+        // Filter on file size as directed
+        if ((self.opts.larger_than.is_some() || self.opts.smaller_than.is_some()) && !entry.is_file())
+            || matches!(self.opts.larger_than, Some(x) if entry.size() <= x)
+            || matches!(self.opts.smaller_than, Some(x) if entry.size() >= x)
+        {
+            return None;
+        }
+
+        // Filter on glob pattern as directed
+        if matches!(&self.opts.glob, Some(x) if !sys::glob_match(x, &entry.path().to_string_lossy())) {
+            return None;
+        }
+
+        // Filter on include patterns as directed
+        if !self.opts.includes.is_empty() && !matches(&self.opts.includes, &entry) {
+            return None;
+        }
+
+        // Filter on file contents as directed
+        #[cfg(feature = "regex")]
+        if let Some(regex) = &self.opts.contents_match {
+            if !entry.is_file() || !trying!(self.contents_match(&entry, regex)) {
+                return None;
+            }
+        }
+
         Some(Ok(entry))
     }
 
-    /// Filter on entries such that only entries that match the given predicate are returned
-    /// by calls to next(). This is convenient as you don't have to deal with a result type
-    /// using this function.
-    ///
-    /// ### Examples
-    /// ```
-    /// use rivia::prelude::*;
-    ///
-    /// let vfs = Vfs::memfs();
-    /// let dir1 = vfs.root().mash("dir1");
-    /// let file1 = vfs.root().mash("file1");
-    /// let dir2 = vfs.root().mash("dir2");
-    /// assert_vfs_mkdir_p!(vfs, &dir1);
-    /// assert_vfs_mkfile!(vfs, &file1);
-    /// assert_vfs_mkdir_p!(vfs, &dir2);
-    /// let mut iter = vfs.entries(vfs.root()).unwrap().sort_by_name().into_iter().filter_p(|x| x.path().has_suffix("1"));
-    /// assert_eq!(iter.next().unwrap().unwrap().path(), &dir1);
-    /// assert_eq!(iter.next().unwrap().unwrap().path(), &file1);
-    /// assert!(iter.next().is_none());
-    /// ```
-    pub fn filter_p(mut self, predicate: impl FnMut(&VfsEntry) -> bool + 'static) -> Self {
-        self.filter = Some(Box::new(predicate));
-        self
+    // Record the path of the given error and return true if it indicates an entry that was
+    // removed while being traversed and such entries are being skipped.
+    fn vanish(&mut self, err: &RvError) -> bool {
+        match err.downcast_ref::<PathError>() {
+            Some(PathError::Vanished(path)) if self.opts.skip_vanished => {
+                self.vanished.push(path.clone());
+                true
+            },
+            _ => false,
+        }
     }
-}
 
-impl Iterator for EntriesIter {
-    type Item = RvResult<VfsEntry>;
+    // Open an iterator over the given directory. When the file descriptors have run out the
+    // directories currently held open are read into memory to release their descriptors and the
+    // open is tried again.
+    fn open_iter(&mut self, path: &Path) -> RvResult<EntryIter> {
+        match (self.opts.iter_from)(path, self.opts.follow) {
+            Err(err)
+                if self.open_descriptors > 0
+                    && matches!(err.downcast_ref::<VfsError>(), Some(VfsError::DescriptorLimit(_))) =>
+            {
+                for iter in self.iters.iter_mut() {
+                    iter.cache();
+                }
+                self.open_descriptors = 0;
+                (self.opts.iter_from)(path, self.opts.follow)
+            },
+            result => result,
+        }
+    }
+
+    // Returns true if the given file's contents match the given regex. Files larger than the
+    // content size limit and binary files are skipped.
+    #[cfg(feature = "regex")]
+    fn contents_match(&self, entry: &VfsEntry, regex: &Regex) -> RvResult<bool> {
+        if entry.size() > self.opts.max_content_size {
+            return Ok(false);
+        }
+        // Open links via their targets
+        let path = if entry.is_symlink() && !entry.following() { entry.alt() } else { entry.path() };
+        let mut data = Vec::with_capacity(entry.size() as usize);
+        (self.opts.read_from)(path)?.take(self.opts.max_content_size).read_to_end(&mut data)?;
+        if data.iter().take(BINARY_CHECK_SIZE).any(|x| *x == 0) {
+            return Ok(false);
+        }
+        Ok(regex.is_match(&String::from_utf8_lossy(&data)))
+    }
+
+    // Get the next entry from the traversal ignoring any early termination options
+    fn next_entry(&mut self) -> Option<RvResult<VfsEntry>> {
+        // Walk in parallel unless an ordered traversal is required
+        let ordered = self.opts.sort.is_some() || self.opts.contents_first || self.opts.pre_op.is_some();
+        if self.opts.parallel > 1 && !ordered {
+            return self.next_walked();
+        }
 
-    fn next(&mut self) -> Option<RvResult<VfsEntry>> {
         if !self.started {
             self.started = true;
 
@@ -490,6 +1311,7 @@ impl Iterator for EntriesIter {
                     Some(result) => return Some(result),
                     None => continue, // None indicates filtered out so get another
                 },
+                Some(Err(err)) if self.vanish(&err) => continue,
                 Some(Err(err)) => return Some(Err(err)),
                 None => {
                     // Decrement open file descriptors appropriately
@@ -511,12 +1333,329 @@ impl Iterator for EntriesIter {
 
         None
     }
+
+    // Get the next entry from the parallel workers starting them on the first call
+    fn next_walked(&mut self) -> Option<RvResult<VfsEntry>> {
+        if !self.started {
+            self.started = true;
+            self.walked = Some(Walker::spawn(&self.opts));
+        }
+        while let Some(walked) = self.walked.as_ref().and_then(|x| x.recv().ok()) {
+            match walked {
+                Walked::Dir => self.stats.dirs += 1,
+                Walked::Entry(entry, depth) => {
+                    self.stats.max_depth = self.stats.max_depth.max(depth);
+                    if let Some(result) = self.filter_entry(*entry, depth) {
+                        return Some(result);
+                    }
+                },
+                Walked::Error(err) => return Some(Err(err)),
+                Walked::Vanished(path) => self.vanished.push(path),
+            }
+        }
+        None
+    }
+
+    /// Filter on entries such that only entries that match the given predicate are returned
+    /// by calls to next(). This is convenient as you don't have to deal with a result type
+    /// using this function.
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let dir1 = vfs.root().mash("dir1");
+    /// let file1 = vfs.root().mash("file1");
+    /// let dir2 = vfs.root().mash("dir2");
+    /// assert_vfs_mkdir_p!(vfs, &dir1);
+    /// assert_vfs_mkfile!(vfs, &file1);
+    /// assert_vfs_mkdir_p!(vfs, &dir2);
+    /// let mut iter = vfs.entries(vfs.root()).unwrap().sort_by_name().into_iter().filter_p(|x| x.path().has_suffix("1"));
+    /// assert_eq!(iter.next().unwrap().unwrap().path(), &dir1);
+    /// assert_eq!(iter.next().unwrap().unwrap().path(), &file1);
+    /// assert!(iter.next().is_none());
+    /// ```
+    pub fn filter_p(mut self, predicate: impl FnMut(&VfsEntry) -> bool + 'static) -> Self {
+        self.filter = Some(Box::new(predicate));
+        self
+    }
+
+    /// Returns true if the traversal was terminated early by `max_items` or `timeout` leaving
+    /// entries unvisited
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// assert_vfs_mkfile!(vfs, "file1");
+    /// let mut iter = vfs.entries(vfs.root()).unwrap().max_items(2).into_iter();
+    /// assert_eq!(iter.by_ref().count(), 2);
+    /// assert_eq!(iter.truncated(), false);
+    /// ```
+    pub fn truncated(&self) -> bool {
+        self.truncated
+    }
+
+    /// Returns the paths of the entries skipped so far because they were removed while being
+    /// traversed
+    ///
+    /// * Only populated when `skip_vanished` is `true` which is the default
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// assert_vfs_mkfile!(vfs, "file1");
+    /// let mut iter = vfs.entries(vfs.root()).unwrap().into_iter();
+    /// assert_eq!(iter.by_ref().count(), 2);
+    /// assert!(iter.vanished().is_empty());
+    /// ```
+    pub fn vanished(&self) -> &[PathBuf] {
+        &self.vanished
+    }
+
+    /// Returns the coverage of the traversal so far i.e. the directories visited, the files, links
+    /// and errors yielded and the deepest level reached
+    ///
+    /// * See [`EntriesStats`] for details
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// assert_vfs_mkfile!(vfs, "file1");
+    /// let mut iter = vfs.entries(vfs.root()).unwrap().files().into_iter();
+    /// assert_eq!(iter.by_ref().count(), 1);
+    /// assert_eq!(iter.stats().dirs, 1);
+    /// assert_eq!(iter.stats().files, 1);
+    /// ```
+    pub fn stats(&self) -> EntriesStats {
+        self.stats
+    }
+}
+
+// Returns true if the given entry matches any of the given patterns. Links are matched by their
+// own path rather than their target's.
+fn matches(patterns: &[String], entry: &VfsEntry) -> bool {
+    if patterns.is_empty() {
+        return false;
+    }
+    let path = if entry.following() { entry.alt() } else { entry.path() }.to_string_lossy();
+    patterns.iter().any(|x| sys::glob_match(x, &path))
+}
+
+// Results sent from the parallel workers to the iterator
+enum Walked {
+    Dir,                         // a directory was opened for traversal
+    Entry(Box<VfsEntry>, usize), // an entry found at the given depth
+    Error(RvError),              // an error to be yielded in place of an entry
+    Vanished(PathBuf),           // a directory removed before it could be opened
+}
+
+// Directories waiting to be read by the parallel workers
+#[derive(Default)]
+struct WalkQueue {
+    dirs: Vec<(PathBuf, usize, Vec<PathBuf>)>, // directory, its depth and the directories above it
+    busy: usize,                               // workers currently reading a directory
+    done: bool,                                // traversal completed or abandoned
+}
+
+// Shared state of a parallel traversal across its workers
+struct Walker {
+    follow: bool,
+    skip_vanished: bool,
+    max_depth: usize,
+    max_links: usize,
+    excludes: Vec<String>,
+    #[allow(clippy::type_complexity)]
+    iter_from: Arc<dyn Fn(&Path, bool) -> RvResult<EntryIter> + Send + Sync + 'static>,
+    queue: Mutex<WalkQueue>,
+    ready: Condvar,
+}
+
+impl Walker {
+    // Visit the root of the given options and spawn the workers to traverse from there returning
+    // the channel the results are received over
+    fn spawn(opts: &Entries) -> Receiver<Walked> {
+        let (tx, rx) = mpsc::sync_channel(WALK_CHANNEL_SIZE);
+        let walker = Arc::new(Walker {
+            follow: opts.follow,
+            skip_vanished: opts.skip_vanished,
+            max_depth: opts.max_depth,
+            max_links: opts.max_links,
+            excludes: opts.excludes.clone(),
+            iter_from: opts.iter_from.clone(),
+            queue: Mutex::new(WalkQueue::default()),
+            ready: Condvar::new(),
+        });
+        walker.visit(&tx, opts.root.clone().follow(opts.follow), 0, &[]);
+        for _ in 0..opts.parallel {
+            let (walker, tx) = (walker.clone(), tx.clone());
+            thread::spawn(move || walker.work(&tx));
+        }
+        rx
+    }
+
+    // Read directories off the queue until the traversal is completed or the iterator is dropped
+    fn work(&self, tx: &SyncSender<Walked>) {
+        loop {
+            let (path, depth, parents) = {
+                let mut queue = self.queue.lock().unwrap();
+                loop {
+                    if queue.done {
+                        return;
+                    }
+                    if let Some(dir) = queue.dirs.pop() {
+                        queue.busy += 1;
+                        break dir;
+                    }
+
+                    // Nothing queued and nothing being read that could queue more
+                    if queue.busy == 0 {
+                        queue.done = true;
+                        self.ready.notify_all();
+                        return;
+                    }
+                    queue = self.ready.wait(queue).unwrap();
+                }
+            };
+            let sent = self.read(tx, &path, depth, parents);
+            let mut queue = self.queue.lock().unwrap();
+            queue.busy -= 1;
+            queue.done |= !sent;
+            self.ready.notify_all();
+        }
+    }
+
+    // Read the given directory visiting each of its entries. Returns false if the iterator was
+    // dropped.
+    fn read(&self, tx: &SyncSender<Walked>, path: &Path, depth: usize, mut parents: Vec<PathBuf>) -> bool {
+        let iter = match (self.iter_from)(path, self.follow) {
+            Ok(iter) => iter,
+            Err(err) => return tx.send(self.error(err)).is_ok(),
+        };
+        if tx.send(Walked::Dir).is_err() {
+            return false;
+        }
+
+        // Only links being followed can loop back to a parent
+        if self.follow {
+            parents.push(path.to_path_buf());
+        }
+        for entry in iter {
+            let sent = match entry {
+                Ok(entry) => self.visit(tx, entry, depth + 1, &parents),
+                Err(err) => tx.send(self.error(err)).is_ok(),
+            };
+            if !sent {
+                return false;
+            }
+        }
+        true
+    }
+
+    // Send the given entry found at the given depth and queue it to be read if it's a directory
+    // to descend into. Returns false if the iterator was dropped.
+    fn visit(&self, tx: &SyncSender<Walked>, entry: VfsEntry, depth: usize, parents: &[PathBuf]) -> bool {
+        // Throw an error if the link requires too many hops to resolve
+        if entry.link_hops() > self.max_links {
+            let path = if entry.following() { entry.alt() } else { entry.path() };
+            return tx.send(Walked::Error(PathError::too_many_links(path).into())).is_ok();
+        }
+
+        // Prune excluded entries before their contents are read
+        if matches(&self.excludes, &entry) {
+            return true;
+        }
+
+        let mut dir = None;
+        if entry.is_dir() && (!entry.is_symlink() || self.follow) {
+            // Throw an error if link looping is detected
+            if entry.is_symlink() && parents.iter().any(|x| x == entry.path()) {
+                return tx.send(Walked::Error(PathError::link_looping(entry.path()).into())).is_ok();
+            }
+            if depth < self.max_depth {
+                dir = Some(entry.path_buf());
+            }
+        }
+
+        // Send before queueing so that directories are always yielded before their contents
+        if tx.send(Walked::Entry(Box::new(entry), depth)).is_err() {
+            return false;
+        }
+        if let Some(dir) = dir {
+            self.queue.lock().unwrap().dirs.push((dir, depth, parents.to_vec()));
+            self.ready.notify_one();
+        }
+        true
+    }
+
+    // Wrap the given error as vanished if it indicates a directory that was removed while being
+    // traversed and such directories are being skipped
+    fn error(&self, err: RvError) -> Walked {
+        match err.downcast_ref::<PathError>() {
+            Some(PathError::Vanished(path)) if self.skip_vanished => Walked::Vanished(path.clone()),
+            _ => Walked::Error(err),
+        }
+    }
+}
+
+impl Iterator for EntriesIter {
+    type Item = RvResult<VfsEntry>;
+
+    fn next(&mut self) -> Option<RvResult<VfsEntry>> {
+        if self.truncated {
+            return None;
+        }
+
+        // Terminate with an error once cancelled
+        if let Some(Err(err)) = self.opts.ctx.as_ref().map(|x| x.check()) {
+            self.truncated = true;
+            return Some(Err(err));
+        }
+
+        // Terminate early once the item budget or the timeout has been exhausted
+        let expired = matches!(self.deadline, Some(x) if Instant::now() >= x);
+        if self.yielded >= self.opts.max_items || expired {
+            // Only flag as truncated if there were entries left unvisited
+            self.truncated = self.next_entry().is_some();
+            return None;
+        }
+
+        let result = self.next_entry();
+        match &result {
+            Some(Ok(entry)) if entry.is_symlink() => self.stats.symlinks += 1,
+            Some(Ok(entry)) if !entry.is_dir() => self.stats.files += 1,
+            Some(Err(_)) => self.stats.errors += 1,
+            _ => {},
+        }
+        if result.is_some() {
+            self.yielded += 1;
+        }
+        if let (Some(ctx), Some(Ok(entry))) = (&self.opts.ctx, &result) {
+            ctx.add_entry(entry.path());
+        }
+        result
+    }
 }
 
 // Unit tests
 // -------------------------------------------------------------------------------------------------
 #[cfg(test)]
 mod tests {
+    use std::{
+        mem,
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc,
+        },
+        time::{Duration, SystemTime},
+    };
+
     use crate::prelude::*;
 
     fn assert_iter_eq(iter: EntriesIter, paths: Vec<&PathBuf>) {
@@ -577,18 +1716,62 @@ mod tests {
         assert_vfs_mkdir_p!(vfs, &dir1);
         assert_vfs_mkfile!(vfs, &file1);
 
-        // Without files set, but sorted to get consistency
-        let mut iter = vfs.entries(&tmpdir).unwrap().sort_by_name().into_iter();
-        assert_eq!(iter.next().unwrap().unwrap().path(), &tmpdir);
-        assert_eq!(iter.next().unwrap().unwrap().path(), &file1);
-        assert_eq!(iter.next().unwrap().unwrap().path(), &dir1);
+        // Without files set, but sorted to get consistency
+        let mut iter = vfs.entries(&tmpdir).unwrap().sort_by_name().into_iter();
+        assert_eq!(iter.next().unwrap().unwrap().path(), &tmpdir);
+        assert_eq!(iter.next().unwrap().unwrap().path(), &file1);
+        assert_eq!(iter.next().unwrap().unwrap().path(), &dir1);
+        assert!(iter.next().is_none());
+
+        // Filter on files
+        let mut iter = vfs.entries(&tmpdir).unwrap().files().sort_by_name().into_iter();
+        assert_eq!(iter.next().unwrap().unwrap().path(), &file1);
+        assert!(iter.next().is_none());
+
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+
+    #[test]
+    fn test_vfs_symlinks() {
+        test_symlinks(assert_vfs_setup!(Vfs::memfs()));
+        test_symlinks(assert_vfs_setup!(Vfs::stdfs()));
+    }
+    fn test_symlinks((vfs, tmpdir): (Vfs, PathBuf)) {
+        let dir1 = tmpdir.mash("dir1");
+        let file1 = tmpdir.mash("file1");
+        let link1 = tmpdir.mash("link1");
+        let link2 = tmpdir.mash("link2");
+        assert_vfs_mkdir_p!(vfs, &dir1);
+        assert_vfs_mkfile!(vfs, &file1);
+        assert_vfs_symlink!(vfs, &link1, &dir1);
+        assert_vfs_symlink!(vfs, &link2, &file1);
+
+        // Links only regardless of target kind
+        let mut iter = vfs.entries(&tmpdir).unwrap().symlinks().sort_by_name().into_iter();
+        assert_eq!(iter.next().unwrap().unwrap().path(), &link1);
+        assert_eq!(iter.next().unwrap().unwrap().path(), &link2);
         assert!(iter.next().is_none());
 
-        // Filter on files
-        let mut iter = vfs.entries(&tmpdir).unwrap().files().sort_by_name().into_iter();
+        // Files includes links to files
+        let mut iter = vfs.entries(&tmpdir).unwrap().any_of(&[Kind::File]).sort_by_name().into_iter();
         assert_eq!(iter.next().unwrap().unwrap().path(), &file1);
+        assert_eq!(iter.next().unwrap().unwrap().path(), &link2);
         assert!(iter.next().is_none());
 
+        // Any of multiple kinds
+        let kinds = [Kind::Dir, Kind::Symlink];
+        let mut iter = vfs.entries(&tmpdir).unwrap().min_depth(1).any_of(&kinds).sort_by_name().into_iter();
+        assert_eq!(iter.next().unwrap().unwrap().path(), &dir1);
+        assert_eq!(iter.next().unwrap().unwrap().path(), &link1);
+        assert_eq!(iter.next().unwrap().unwrap().path(), &link2);
+        assert!(iter.next().is_none());
+
+        // Later filters replace earlier ones and empty kinds disables filtering
+        let iter = vfs.entries(&tmpdir).unwrap().symlinks().files().into_iter();
+        assert_eq!(iter.count(), 2);
+        let iter = vfs.entries(&tmpdir).unwrap().symlinks().any_of(&[]).into_iter();
+        assert_eq!(iter.count(), 5);
+
         assert_vfs_remove_all!(vfs, &tmpdir);
     }
 
@@ -862,6 +2045,107 @@ mod tests {
         assert_vfs_remove_all!(vfs, &tmpdir);
     }
 
+    #[test]
+    fn test_vfs_descriptor_limit() {
+        test_descriptor_limit(assert_vfs_setup!(Vfs::memfs()));
+        test_descriptor_limit(assert_vfs_setup!(Vfs::stdfs()));
+    }
+    fn test_descriptor_limit((vfs, tmpdir): (Vfs, PathBuf)) {
+        let dir1 = tmpdir.mash("dir1");
+        let file1 = dir1.mash("file1");
+        let dir2 = dir1.mash("dir2");
+        let file2 = dir2.mash("file2");
+
+        assert_vfs_mkdir_p!(vfs, &dir2);
+        assert_vfs_mkfile!(vfs, &file1);
+        assert_vfs_mkfile!(vfs, &file2);
+
+        // Simulate running out of descriptors the first time the given dir is opened
+        let limit = |paths: &mut Entries, dir: PathBuf| {
+            let failed = AtomicBool::new(false);
+            let iter_from = mem::replace(&mut paths.iter_from, Arc::new(|_, _| unreachable!()));
+            paths.iter_from = Arc::new(move |path, follow| {
+                if path == dir && !failed.swap(true, Ordering::SeqCst) {
+                    return Err(VfsError::DescriptorLimit(path.to_path_buf()).into());
+                }
+                (iter_from)(path, follow)
+            });
+        };
+
+        // Open directories are released and the open retried
+        let mut paths = vfs.entries(&tmpdir).unwrap();
+        limit(&mut paths, dir2.clone());
+        let iter = paths.into_iter();
+        assert_iter_eq(iter, vec![&tmpdir, &dir1, &dir2, &file2, &file1]);
+
+        // Nothing to release when opening the root
+        let mut paths = vfs.entries(&tmpdir).unwrap();
+        limit(&mut paths, tmpdir.clone());
+        let mut iter = paths.into_iter();
+        assert_eq!(
+            iter.next().unwrap().unwrap_err().downcast_ref::<VfsError>(),
+            Some(&VfsError::DescriptorLimit(tmpdir.clone()))
+        );
+
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+
+    #[test]
+    fn test_stdfs_skip_vanished() {
+        let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs());
+        let dir1 = tmpdir.mash("dir1");
+        let file1 = dir1.mash("file1");
+        let file2 = dir1.mash("file2");
+        let dir2 = tmpdir.mash("dir2");
+        assert_vfs_mkdir_p!(vfs, &dir1);
+        assert_vfs_mkdir_p!(vfs, &dir2);
+
+        // Remove whichever file wasn't yielded first after the directory has been read
+        let traverse = |skip: bool| -> (PathBuf, EntriesIter) {
+            assert_vfs_mkfile!(vfs, &file1);
+            assert_vfs_mkfile!(vfs, &file2);
+            let mut iter = vfs.entries(&dir1).unwrap().skip_vanished(skip).into_iter();
+            assert_eq!(iter.next().unwrap().unwrap().path(), &dir1);
+            let first = iter.next().unwrap().unwrap().path_buf();
+            let removed = if first == file1 { file2.clone() } else { file1.clone() };
+            assert_vfs_remove!(vfs, &removed);
+            (removed, iter)
+        };
+
+        // Skipped by default and reported separately
+        let (removed, mut iter) = traverse(true);
+        assert!(iter.next().is_none());
+        assert_eq!(iter.vanished(), &[removed]);
+
+        // Reported as errors when not skipping
+        assert_vfs_remove_all!(vfs, &dir1);
+        assert_vfs_mkdir_p!(vfs, &dir1);
+        let (removed, mut iter) = traverse(false);
+        assert_eq!(
+            iter.next().unwrap().unwrap_err().downcast_ref::<PathError>(),
+            Some(&PathError::vanished(&removed))
+        );
+        assert!(iter.next().is_none());
+        assert!(iter.vanished().is_empty());
+
+        // Directories removed before being opened are skipped as well
+        let target = dir2.clone();
+        let mut iter = vfs
+            .entries(&tmpdir)
+            .unwrap()
+            .pre_op(move |x| {
+                if x.path() == target {
+                    Stdfs::remove_all(&target)?;
+                }
+                Ok(())
+            })
+            .into_iter();
+        assert!(!iter.by_ref().filter_map(|x| x.ok()).any(|x| x.path() == dir2));
+        assert_eq!(iter.vanished(), &[dir2]);
+
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+
     #[test]
     fn test_vfs_loop_detection() {
         test_loop_detection(assert_vfs_setup!(Vfs::memfs()));
@@ -890,6 +2174,481 @@ mod tests {
         assert_vfs_remove_all!(vfs, &tmpdir);
     }
 
+    #[test]
+    fn test_vfs_stats() {
+        test_stats(assert_vfs_setup!(Vfs::memfs()));
+        test_stats(assert_vfs_setup!(Vfs::stdfs()));
+    }
+    fn test_stats((vfs, tmpdir): (Vfs, PathBuf)) {
+        let dir1 = tmpdir.mash("dir1");
+        let dir2 = dir1.mash("dir2");
+        let file1 = tmpdir.mash("file1");
+        let file2 = dir2.mash("file2");
+        let link1 = dir2.mash("link1");
+        assert_vfs_mkdir_p!(vfs, &dir2);
+        assert_vfs_mkfile!(vfs, &file1);
+        assert_vfs_mkfile!(vfs, &file2);
+        assert_vfs_symlink!(vfs, &link1, &dir1);
+
+        // Nothing before iterating
+        let mut iter = vfs.entries(&tmpdir).unwrap().into_iter();
+        assert_eq!(iter.stats(), EntriesStats::default());
+
+        // Full traversal
+        assert_eq!(iter.by_ref().count(), 6);
+        assert_eq!(iter.stats(), EntriesStats { dirs: 3, files: 2, symlinks: 1, errors: 0, max_depth: 3 });
+
+        // Filtered entries aren't counted but their directories are still visited
+        let mut iter = vfs.entries(&tmpdir).unwrap().symlinks().into_iter();
+        assert_eq!(iter.by_ref().count(), 1);
+        assert_eq!(iter.stats(), EntriesStats { dirs: 3, files: 0, symlinks: 1, errors: 0, max_depth: 3 });
+
+        // Depth limits the directories visited
+        let mut iter = vfs.entries(&tmpdir).unwrap().max_depth(1).into_iter();
+        assert_eq!(iter.by_ref().count(), 3);
+        assert_eq!(iter.stats(), EntriesStats { dirs: 1, files: 1, symlinks: 0, errors: 0, max_depth: 1 });
+
+        // Errors are counted
+        let mut iter = vfs.entries(&tmpdir).unwrap().follow(true).into_iter();
+        assert_eq!(iter.by_ref().filter(|x| x.is_err()).count(), 1);
+        assert_eq!(iter.stats().errors, 1);
+        assert_eq!(iter.stats().files, 2);
+
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+
+    #[test]
+    fn test_vfs_parallel() {
+        test_parallel(assert_vfs_setup!(Vfs::memfs()));
+        test_parallel(assert_vfs_setup!(Vfs::stdfs()));
+    }
+    fn test_parallel((vfs, tmpdir): (Vfs, PathBuf)) {
+        let dir0 = tmpdir.mash("dir0");
+        let link1 = dir0.mash("sub0/link1");
+        for i in 0..5 {
+            for j in 0..5 {
+                let sub = tmpdir.mash(format!("dir{}/sub{}", i, j));
+                assert_vfs_mkdir_p!(vfs, &sub);
+                assert_vfs_mkfile!(vfs, sub.mash("file"));
+            }
+        }
+        assert_vfs_symlink!(vfs, &link1, &dir0);
+        let entries = || vfs.entries(&tmpdir).unwrap();
+        let sorted = |x: Entries| {
+            let mut paths = x.into_iter().filter_map(|x| x.ok()).map(|x| x.path_buf()).collect::<Vec<_>>();
+            paths.sort();
+            paths
+        };
+
+        // Same entries as the single threaded traversal
+        for workers in [2, 8] {
+            assert_eq!(sorted(entries().parallel(workers)), sorted(entries()));
+        }
+        assert_eq!(sorted(entries().parallel(4).max_depth(2)), sorted(entries().max_depth(2)));
+        assert_eq!(sorted(entries().parallel(4).min_depth(2).files()), sorted(entries().min_depth(2).files()));
+        assert_eq!(sorted(entries().parallel(4).exclude("dir1/**")), sorted(entries().exclude("dir1/**")));
+        assert_eq!(sorted(entries().parallel(4).follow(true)), sorted(entries().follow(true)));
+
+        // Directories are yielded before their contents
+        let mut paths: Vec<PathBuf> = vec![];
+        for entry in entries().parallel(4) {
+            let path = entry.unwrap().path_buf();
+            assert!(path == tmpdir || paths.contains(&path.dir().unwrap()));
+            paths.push(path);
+        }
+
+        // Coverage is reported the same
+        let mut iter = entries().parallel(4).into_iter();
+        assert_eq!(iter.by_ref().count(), 57);
+        assert_eq!(iter.stats(), EntriesStats { dirs: 31, files: 25, symlinks: 1, errors: 0, max_depth: 3 });
+
+        // Looping links are detected
+        let iter = entries().parallel(4).follow(true).into_iter();
+        let errors = iter.filter_map(|x| x.err()).map(|x| x.to_string()).collect::<Vec<_>>();
+        assert_eq!(errors, vec![PathError::link_looping(&dir0).to_string()]);
+
+        // Early termination still applies while ordered traversals fall back on the calling thread
+        let mut iter = entries().parallel(4).max_items(3).into_iter();
+        assert_eq!(iter.by_ref().count(), 3);
+        assert!(iter.truncated());
+        let paths = entries().parallel(4).sort_by_name().collect_paths().unwrap();
+        assert_eq!(paths, entries().sort_by_name().collect_paths().unwrap());
+
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+
+    #[test]
+    fn test_vfs_max_items() {
+        test_max_items(assert_vfs_setup!(Vfs::memfs()));
+        test_max_items(assert_vfs_setup!(Vfs::stdfs()));
+    }
+    fn test_max_items((vfs, tmpdir): (Vfs, PathBuf)) {
+        let dir1 = tmpdir.mash("dir1");
+        let file1 = dir1.mash("file1");
+        let file2 = tmpdir.mash("file2");
+
+        assert_vfs_mkdir_p!(vfs, &dir1);
+        assert_vfs_mkfile!(vfs, &file1);
+        assert_vfs_mkfile!(vfs, &file2);
+
+        // Budget exhausted with entries left
+        let mut iter = vfs.entries(&tmpdir).unwrap().max_items(2).sort_by_name().into_iter();
+        assert_eq!(iter.next().unwrap().unwrap().path(), tmpdir);
+        assert!(!iter.truncated());
+        assert_eq!(iter.next().unwrap().unwrap().path(), dir1);
+        assert!(iter.next().is_none());
+        assert!(iter.truncated());
+        assert!(iter.next().is_none());
+
+        // Budget exactly matches the entries
+        let mut iter = vfs.entries(&tmpdir).unwrap().max_items(4).into_iter();
+        assert_eq!(iter.by_ref().count(), 4);
+        assert!(!iter.truncated());
+
+        // Budget counts only the entries that pass the filters
+        let mut iter = vfs.entries(&tmpdir).unwrap().files().max_items(1).sort_by_name().into_iter();
+        assert_eq!(iter.next().unwrap().unwrap().path(), file1);
+        assert!(iter.next().is_none());
+        assert!(iter.truncated());
+
+        // Timeout already expired
+        let mut iter = vfs.entries(&tmpdir).unwrap().timeout(Duration::ZERO).into_iter();
+        assert!(iter.next().is_none());
+        assert!(iter.truncated());
+
+        // Timeout not reached
+        let mut iter = vfs.entries(&tmpdir).unwrap().timeout(Duration::from_secs(60)).into_iter();
+        assert_eq!(iter.by_ref().count(), 4);
+        assert!(!iter.truncated());
+
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+
+    #[test]
+    fn test_vfs_cancel_token() {
+        test_cancel_token(assert_vfs_setup!(Vfs::memfs()));
+        test_cancel_token(assert_vfs_setup!(Vfs::stdfs()));
+    }
+    fn test_cancel_token((vfs, tmpdir): (Vfs, PathBuf)) {
+        let file1 = tmpdir.mash("file1");
+        let file2 = tmpdir.mash("file2");
+        assert_vfs_mkfile!(vfs, &file1);
+        assert_vfs_mkfile!(vfs, &file2);
+
+        // Cancel part way through
+        let token = CancelToken::new();
+        let mut iter = vfs.entries(&tmpdir).unwrap().cancel_token(&token).sort_by_name().into_iter();
+        assert_eq!(iter.next().unwrap().unwrap().path(), tmpdir);
+        token.cancel();
+        let err = iter.next().unwrap().unwrap_err();
+        assert_eq!(err.downcast_ref::<VfsError>(), Some(&VfsError::Cancelled));
+        assert!(iter.truncated());
+        assert!(iter.next().is_none());
+
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+
+    #[test]
+    fn test_vfs_modified() {
+        test_modified(assert_vfs_setup!(Vfs::memfs()));
+        test_modified(assert_vfs_setup!(Vfs::stdfs()));
+    }
+    fn test_modified((vfs, tmpdir): (Vfs, PathBuf)) {
+        let file1 = tmpdir.mash("file1");
+        let file2 = tmpdir.mash("file2");
+        let link1 = tmpdir.mash("link1");
+
+        // Separate the two files in time allowing for coarse filesystem timestamps
+        assert_vfs_mkfile!(vfs, &file1);
+        std::thread::sleep(Duration::from_millis(50));
+        let ts = SystemTime::now();
+        std::thread::sleep(Duration::from_millis(50));
+        assert_vfs_mkfile!(vfs, &file2);
+        assert_vfs_symlink!(vfs, &link1, &file1);
+        assert_eq!(vfs.entry(&link1).unwrap().modified(), vfs.entry(&file1).unwrap().modified());
+
+        // Modified before
+        let mut iter = vfs.entries(&tmpdir).unwrap().files().modified_before(ts).sort_by_name().into_iter();
+        assert_eq!(iter.next().unwrap().unwrap().path(), file1);
+        assert_eq!(iter.next().unwrap().unwrap().path(), link1);
+        assert!(iter.next().is_none());
+
+        // Created after
+        let mut iter = vfs.entries(&tmpdir).unwrap().files().created_after(ts).into_iter();
+        assert_eq!(iter.next().unwrap().unwrap().path(), file2);
+        assert!(iter.next().is_none());
+
+        // Modified within
+        let iter = vfs.entries(&tmpdir).unwrap().files().modified_within(Duration::from_secs(3600)).into_iter();
+        assert_eq!(iter.count(), 3);
+        let iter = vfs.entries(&tmpdir).unwrap().modified_within(Duration::ZERO).into_iter();
+        assert_eq!(iter.count(), 0);
+
+        // Writing updates the modified time but not the created time
+        assert!(vfs.write_all(&file1, "foo").is_ok());
+        let iter = vfs.entries(&tmpdir).unwrap().files().modified_before(ts).into_iter();
+        assert_eq!(iter.count(), 0);
+        let iter = vfs.entries(&tmpdir).unwrap().files().created_after(ts).into_iter();
+        assert_eq!(iter.count(), 1);
+
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+
+    #[test]
+    fn test_vfs_size() {
+        test_size(assert_vfs_setup!(Vfs::memfs()));
+        test_size(assert_vfs_setup!(Vfs::stdfs()));
+    }
+    fn test_size((vfs, tmpdir): (Vfs, PathBuf)) {
+        let dir1 = tmpdir.mash("dir1");
+        let file1 = dir1.mash("file1");
+        let file2 = tmpdir.mash("file2");
+        let file3 = tmpdir.mash("file3");
+        let link1 = tmpdir.mash("link1");
+
+        assert_vfs_mkdir_p!(vfs, &dir1);
+        assert_vfs_write_all!(vfs, &file1, "1");
+        assert_vfs_write_all!(vfs, &file2, "1234");
+        assert_vfs_write_all!(vfs, &file3, "123456");
+        assert_vfs_symlink!(vfs, &link1, &file3);
+
+        // Larger than excludes directories and matches links by their target's size
+        let mut iter = vfs.entries(&tmpdir).unwrap().larger_than(4).sort_by_name().into_iter();
+        assert_eq!(iter.next().unwrap().unwrap().path(), file3);
+        assert_eq!(iter.next().unwrap().unwrap().path(), link1);
+        assert!(iter.next().is_none());
+
+        // Smaller than still traverses filtered out directories
+        let mut iter = vfs.entries(&tmpdir).unwrap().smaller_than(4).into_iter();
+        assert_eq!(iter.next().unwrap().unwrap().path(), file1);
+        assert!(iter.next().is_none());
+
+        // Combined to select a range
+        let mut iter = vfs.entries(&tmpdir).unwrap().larger_than(1).smaller_than(6).into_iter();
+        assert_eq!(iter.next().unwrap().unwrap().path(), file2);
+        assert!(iter.next().is_none());
+
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn test_vfs_contents_match() {
+        test_contents_match(assert_vfs_setup!(Vfs::memfs()));
+        test_contents_match(assert_vfs_setup!(Vfs::stdfs()));
+    }
+    #[cfg(feature = "regex")]
+    fn test_contents_match((vfs, tmpdir): (Vfs, PathBuf)) {
+        let dir1 = tmpdir.mash("dir1");
+        let file1 = tmpdir.mash("file1");
+        let file2 = dir1.mash("file2");
+        let file3 = tmpdir.mash("file3");
+        let file4 = tmpdir.mash("file4");
+        let link1 = tmpdir.mash("link1");
+        assert_vfs_mkdir_p!(vfs, &dir1);
+        assert_vfs_write_all!(vfs, &file1, "foo\nbar\n");
+        assert_vfs_write_all!(vfs, &file2, "bar\nfoo bar\n");
+        assert_vfs_write_all!(vfs, &file3, "baz\n");
+        assert!(vfs.write_all(&file4, b"foo\0bar").is_ok());
+        assert_vfs_symlink!(vfs, &link1, &file3);
+
+        // Directories are traversed but filtered out along with binary files
+        let regex = regex::Regex::new("^foo").unwrap();
+        let iter = vfs.entries(&tmpdir).unwrap().contents_match(regex.clone()).into_iter();
+        assert_iter_eq(iter, vec![&file1]);
+        let regex = regex::Regex::new("(?m)^foo").unwrap();
+        let iter = vfs.entries(&tmpdir).unwrap().contents_match(regex.clone()).into_iter();
+        assert_iter_eq(iter, vec![&file1, &file2]);
+
+        // Links to files are searched
+        let iter = vfs.entries(&tmpdir).unwrap().contents_match(regex::Regex::new("baz").unwrap()).into_iter();
+        assert_iter_eq(iter, vec![&file3, &link1]);
+
+        // Files larger than the limit are skipped
+        let iter = vfs.entries(&tmpdir).unwrap().contents_match(regex).max_content_size(8).into_iter();
+        assert_iter_eq(iter, vec![&file1]);
+
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+
+    #[test]
+    fn test_vfs_include_exclude() {
+        test_include_exclude(assert_vfs_setup!(Vfs::memfs()));
+        test_include_exclude(assert_vfs_setup!(Vfs::stdfs()));
+    }
+    fn test_include_exclude((vfs, tmpdir): (Vfs, PathBuf)) {
+        let src = tmpdir.mash("src");
+        let target = tmpdir.mash("target");
+        let file1 = tmpdir.mash("build.rs");
+        let file2 = src.mash("lib.rs");
+        let file3 = src.mash("notes.txt");
+        let file4 = target.mash("gen.rs");
+        let link1 = tmpdir.mash("link1");
+        assert_vfs_mkdir_p!(vfs, &src);
+        assert_vfs_mkdir_p!(vfs, &target);
+        assert_vfs_mkfile!(vfs, &file1);
+        assert_vfs_mkfile!(vfs, &file2);
+        assert_vfs_mkfile!(vfs, &file3);
+        assert_vfs_mkfile!(vfs, &file4);
+        assert_vfs_symlink!(vfs, &link1, &target);
+
+        // Names match at any depth while paths are relative to the root
+        let iter = vfs.entries(&tmpdir).unwrap().include("*.rs").into_iter();
+        assert_iter_eq(iter, vec![&file1, &file2, &file4]);
+        let iter = vfs.entries(&tmpdir).unwrap().include("src/*").include("*.txt").into_iter();
+        assert_iter_eq(iter, vec![&file2, &file3]);
+
+        // Excluded directories are pruned along with their contents
+        let entries = vfs.entries(&tmpdir).unwrap().exclude("target/**").include("*.rs");
+        let mut iter = entries.sort_by_name().into_iter();
+        assert_eq!(iter.next().unwrap().unwrap().path(), file1);
+        assert_eq!(iter.next().unwrap().unwrap().path(), file2);
+        assert!(iter.next().is_none());
+        assert_eq!(iter.stats().dirs, 2);
+        let iter = vfs.entries(&tmpdir).unwrap().exclude("src").exclude("*.rs").into_iter();
+        assert_iter_eq(iter, vec![&tmpdir, &target, &link1]);
+
+        // Links are matched by their own path so excluded targets are still reached through them
+        let iter = vfs.entries(&tmpdir).unwrap().follow(true).exclude("target").include("*.rs").into_iter();
+        assert_iter_eq(iter, vec![&file1, &file2, &file4]);
+        let iter = vfs.entries(&tmpdir).unwrap().follow(true).exclude("link1").include("*.rs").into_iter();
+        assert_iter_eq(iter, vec![&file1, &file2, &file4]);
+
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+
+    #[test]
+    fn test_vfs_collect() {
+        test_collect(assert_vfs_setup!(Vfs::memfs()));
+        test_collect(assert_vfs_setup!(Vfs::stdfs()));
+    }
+    fn test_collect((vfs, tmpdir): (Vfs, PathBuf)) {
+        let dir1 = tmpdir.mash("dir1");
+        let file1 = dir1.mash("file1");
+        let file2 = tmpdir.mash("file2");
+        let link1 = tmpdir.mash("link1");
+        assert_vfs_mkdir_p!(vfs, &dir1);
+        assert_vfs_write_all!(vfs, &file1, "foo");
+        assert_vfs_mkfile!(vfs, &file2);
+        assert_vfs_symlink!(vfs, &link1, &dir1);
+
+        // Filters and sorting are applied
+        let paths = vfs.entries(&tmpdir).unwrap().sort_by_name().collect_paths().unwrap();
+        assert_eq!(paths, vec![tmpdir.clone(), dir1.clone(), file1.clone(), file2.clone(), link1.clone()]);
+        let paths = vfs.entries(&tmpdir).unwrap().min_depth(1).sort_by_name().collect_dirs().unwrap();
+        assert_eq!(paths, vec![dir1.clone(), link1.clone()]);
+        let paths = vfs.entries(&tmpdir).unwrap().sort_by_name().collect_files().unwrap();
+        assert_eq!(paths, vec![file1.clone(), file2.clone()]);
+        let paths = vfs.entries(&tmpdir).unwrap().max_depth(1).larger_than(0).collect_files().unwrap();
+        assert_eq!(paths, Vec::<PathBuf>::new());
+
+        // Errors are surfaced
+        let token = CancelToken::new();
+        token.cancel();
+        let err = vfs.entries(&tmpdir).unwrap().cancel_token(&token).collect_paths().unwrap_err();
+        assert_eq!(err.downcast_ref::<VfsError>(), Some(&VfsError::Cancelled));
+
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+
+    #[test]
+    fn test_vfs_into_tree() {
+        test_into_tree(assert_vfs_setup!(Vfs::memfs()));
+        test_into_tree(assert_vfs_setup!(Vfs::stdfs()));
+    }
+    fn test_into_tree((vfs, tmpdir): (Vfs, PathBuf)) {
+        let dir1 = tmpdir.mash("dir1");
+        let dir2 = dir1.mash("dir2");
+        let file1 = dir1.mash("file1");
+        let file2 = dir2.mash("file2");
+        let file3 = tmpdir.mash("file3");
+        let link1 = tmpdir.mash("link1");
+        assert_vfs_mkdir_p!(vfs, &dir2);
+        assert_vfs_write_all!(vfs, &file1, "foo");
+        assert_vfs_mkfile!(vfs, &file2);
+        assert_vfs_mkfile!(vfs, &file3);
+        assert_vfs_symlink!(vfs, &link1, &dir2);
+        let names = |x: &TreeNode| x.children.iter().map(|x| x.name.clone()).collect::<Vec<_>>();
+
+        // Nested structure in the yielded order
+        let tree = vfs.entries(&tmpdir).unwrap().sort_by_name().into_tree().unwrap();
+        assert_eq!(tree.path, tmpdir);
+        assert_eq!(tree.name, tmpdir.base().unwrap());
+        assert_eq!(tree.kind, Kind::Dir);
+        assert_eq!(names(&tree), vec!["dir1", "file3", "link1"]);
+        assert_eq!(names(&tree.children[0]), vec!["dir2", "file1"]);
+        assert_eq!(names(&tree.children[0].children[0]), vec!["file2"]);
+        assert_eq!(tree.children[0].children[1].kind, Kind::File);
+        assert_eq!(tree.children[0].children[1].metadata, vfs.entry(&file1).unwrap().metadata());
+        assert_eq!(tree.children[2].kind, Kind::Symlink);
+        assert!(tree.children[2].children.is_empty());
+
+        // Followed links keep their own path with their target's children beneath them
+        let tree = vfs.entries(&link1).unwrap().follow(true).into_tree().unwrap();
+        assert_eq!(tree.path, link1);
+        assert_eq!(tree.kind, Kind::Symlink);
+        assert_eq!(names(&tree), vec!["file2"]);
+        assert_eq!(tree.children[0].path, file2);
+
+        // Entries of filtered out directories are attached to their nearest ancestor
+        let tree = vfs.entries(&tmpdir).unwrap().files().dirs_first().into_tree().unwrap();
+        assert_eq!(tree.path, tmpdir);
+        assert_eq!(names(&tree), vec!["file2", "file1", "file3"]);
+
+        // Errors are surfaced
+        let token = CancelToken::new();
+        token.cancel();
+        let err = vfs.entries(&tmpdir).unwrap().cancel_token(&token).into_tree().unwrap_err();
+        assert_eq!(err.downcast_ref::<VfsError>(), Some(&VfsError::Cancelled));
+
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+
+    #[test]
+    fn test_vfs_max_links() {
+        test_max_links(assert_vfs_setup!(Vfs::memfs()));
+        test_max_links(assert_vfs_setup!(Vfs::stdfs()));
+    }
+    fn test_max_links((vfs, tmpdir): (Vfs, PathBuf)) {
+        let file1 = tmpdir.mash("file1");
+        let link1 = tmpdir.mash("link1");
+        let link2 = tmpdir.mash("link2");
+        let link3 = tmpdir.mash("link3");
+        let link4 = tmpdir.mash("link4");
+
+        assert_vfs_mkfile!(vfs, &file1);
+        assert_vfs_symlink!(vfs, &link1, &file1);
+        assert_vfs_symlink!(vfs, &link2, &link1);
+
+        // Default allows both links
+        let iter = vfs.entries(&tmpdir).unwrap().into_iter();
+        assert_iter_eq(iter, vec![&tmpdir, &file1, &link1, &link2]);
+
+        // Limit the hops to reject the second link
+        let mut iter = vfs.entries(&tmpdir).unwrap().max_links(1).sort_by_name().into_iter();
+        assert_eq!(iter.next().unwrap().unwrap().path(), tmpdir);
+        assert_eq!(iter.next().unwrap().unwrap().path(), file1);
+        assert_eq!(iter.next().unwrap().unwrap().path(), link1);
+        assert_eq!(iter.next().unwrap().unwrap_err().to_string(), PathError::too_many_links(&link2).to_string());
+        assert!(iter.next().is_none());
+
+        // Reject all links
+        let iter = vfs.entries(&tmpdir).unwrap().max_links(0).into_iter();
+        assert_eq!(iter.filter(|x| x.is_err()).count(), 2);
+
+        // Looping links can't be resolved
+        assert!(vfs.symlink(&link3, &link4).is_ok());
+        assert!(vfs.symlink(&link4, &link3).is_ok());
+        assert_eq!(
+            vfs.entry(&link3).unwrap_err().downcast_ref::<PathError>(),
+            Some(&PathError::too_many_links(&link3))
+        );
+        let iter = vfs.entries(&tmpdir).unwrap().into_iter();
+        assert_eq!(iter.filter(|x| x.is_err()).count(), 2);
+
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+
     #[test]
     fn test_vfs_filter() {
         test_filter(assert_vfs_setup!(Vfs::memfs()));
@@ -1,10 +1,75 @@
 use std::{
+    any::Any,
     ffi::OsStr,
     fmt::Debug,
     path::{Path, PathBuf},
+    time::SystemTime,
 };
 
-use crate::sys::{MemfsEntry, StdfsEntry};
+use crate::{
+    errors::RvResult,
+    sys::{MemfsEntry, StdfsEntry},
+};
+
+// Max number of link hops followed when resolving a link which matches the Linux kernel's limit
+pub(crate) const DEFAULT_MAX_LINKS: usize = 40;
+
+/// Provides a classification of the kinds of entries a filesystem supports
+///
+/// * Kinds are not mutually exclusive e.g. a link to a file is both a `File` and a `Symlink`
+///
+/// ### Examples
+/// ```
+/// use rivia::prelude::*;
+///
+/// let vfs = Vfs::memfs();
+/// assert_vfs_mkfile!(vfs, "file");
+/// assert_vfs_symlink!(vfs, "link", "file");
+/// let entry = vfs.entry("link").unwrap();
+/// assert_eq!(entry.is_kind(Kind::File), true);
+/// assert_eq!(entry.is_kind(Kind::Symlink), true);
+/// assert_eq!(entry.is_kind(Kind::Dir), false);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Kind
+{
+    Dir,     // a directory or a link to a directory
+    File,    // a file or a link to a file
+    Symlink, // a link
+}
+
+/// Describes the cached filesystem properties of an [`Entry`]
+///
+/// * Returned by [`Entry::metadata`] without touching the filesystem
+/// * Returned by the Vfs function `metadata` with a single lookup of the path
+/// * Links report the properties of the target they point to except for `link`
+/// * Memfs doesn't track access times and reports the modified time instead
+///
+/// ### Examples
+/// ```
+/// use rivia::prelude::*;
+///
+/// let vfs = Vfs::memfs();
+/// assert_vfs_write_all!(vfs, "file", "foobar");
+/// let meta = vfs.entry("file").unwrap().metadata();
+/// assert_eq!(meta.file, true);
+/// assert_eq!(meta.size, 6);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct VfsMetadata
+{
+    pub dir: bool,            // is this entry a dir
+    pub file: bool,           // is this entry a file
+    pub link: bool,           // is this entry a link
+    pub mode: u32,            // permission mode of the entry
+    pub size: u64,            // size of the file in bytes
+    pub nlink: u64,           // number of hard links to the entry
+    pub uid: u32,             // user id of the entry
+    pub gid: u32,             // group id of the entry
+    pub accessed: SystemTime, // time the entry was last accessed
+    pub modified: SystemTime, // time the entry was last modified
+    pub created: SystemTime,  // time the entry was created
+}
 
 /// Defines a virtual file system entry that can be used generically across all Vfs provider
 /// backends
@@ -125,6 +190,28 @@ pub trait Entry: Debug+Send+Sync+'static
     /// ```
     fn rel_buf(&self) -> PathBuf;
 
+    /// Returns the names of the directory's immediate children sorted by name
+    ///
+    /// * Provides shallow introspection without the cost of an [`Entries`] iterator
+    /// * Links to directories report the children of the directory they point to
+    /// * Memfs reports the children cached when the entry was loaded while Stdfs reads them lazily
+    ///
+    /// ### Errors
+    /// * PathError::IsNotDir(PathBuf) when the entry is not a directory
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Memfs::new();
+    /// assert_vfs_mkdir_p!(vfs, "dir/sub");
+    /// assert_vfs_mkfile!(vfs, "dir/file");
+    /// assert_eq!(vfs.entry("dir").unwrap().children().unwrap(), vec!["file", "sub"]);
+    /// ```
+    ///
+    /// [`Entries`]: crate::sys::Entries
+    fn children(&self) -> RvResult<Vec<String>>;
+
     /// File name of the entry
     ///
     /// ### Examples
@@ -223,6 +310,30 @@ pub trait Entry: Debug+Send+Sync+'static
     /// ```
     fn is_file(&self) -> bool;
 
+    /// Returns true if the entry is of the given kind
+    ///
+    /// * Links are both a `Symlink` and the kind of their target
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Memfs::new();
+    /// let file = vfs.root().mash("file");
+    /// assert_vfs_mkfile!(vfs, &file);
+    /// let entry = vfs.entry(&file).unwrap();
+    /// assert_eq!(entry.is_kind(Kind::File), true);
+    /// assert_eq!(entry.is_kind(Kind::Symlink), false);
+    /// ```
+    fn is_kind(&self, kind: Kind) -> bool
+    {
+        match kind {
+            Kind::Dir => self.is_dir(),
+            Kind::File => self.is_file(),
+            Kind::Symlink => self.is_symlink(),
+        }
+    }
+
     /// Returns true if this path is readonly
     ///
     /// ### Examples
@@ -290,6 +401,29 @@ pub trait Entry: Debug+Send+Sync+'static
         self.is_symlink() && self.is_file()
     }
 
+    /// Reports the number of link hops followed to resolve a link to its final target
+    ///
+    /// * Non link entries report `0`
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Memfs::new();
+    /// let file = vfs.root().mash("file");
+    /// let link1 = vfs.root().mash("link1");
+    /// let link2 = vfs.root().mash("link2");
+    /// assert_vfs_mkfile!(vfs, &file);
+    /// assert_vfs_symlink!(vfs, &link1, &file);
+    /// assert_vfs_symlink!(vfs, &link2, &link1);
+    /// assert_eq!(vfs.entry(&file).unwrap().link_hops(), 0);
+    /// assert_eq!(vfs.entry(&link2).unwrap().link_hops(), 2);
+    /// ```
+    fn link_hops(&self) -> usize
+    {
+        self.target_chain().len()
+    }
+
     /// Reports the mode of the path
     ///
     /// ### Examples
@@ -304,6 +438,94 @@ pub trait Entry: Debug+Send+Sync+'static
     /// ```
     fn mode(&self) -> u32;
 
+    /// Reports the time the entry was last modified
+    ///
+    /// * Links report the modified time of the target they point to
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    /// use std::time::SystemTime;
+    ///
+    /// let vfs = Memfs::new();
+    /// let file = vfs.root().mash("file");
+    /// assert_vfs_mkfile!(vfs, &file);
+    /// assert!(vfs.entry(&file).unwrap().modified() <= SystemTime::now());
+    /// ```
+    fn modified(&self) -> SystemTime;
+
+    /// Reports the time the entry was created
+    ///
+    /// * Links report the created time of the target they point to
+    /// * Falls back on the modified time when the filesystem doesn't track creation times
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    /// use std::time::SystemTime;
+    ///
+    /// let vfs = Memfs::new();
+    /// let file = vfs.root().mash("file");
+    /// assert_vfs_mkfile!(vfs, &file);
+    /// assert!(vfs.entry(&file).unwrap().created() <= SystemTime::now());
+    /// ```
+    fn created(&self) -> SystemTime;
+
+    /// Reports the size of the file in bytes
+    ///
+    /// * Links report the size of the file they point to
+    /// * Directories report `0`
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Memfs::new();
+    /// let file = vfs.root().mash("file");
+    /// assert_vfs_write_all!(vfs, &file, "foobar");
+    /// assert_eq!(vfs.entry(&file).unwrap().size(), 6);
+    /// ```
+    fn size(&self) -> u64;
+
+    /// Returns the filesystem properties cached when the entry was loaded
+    ///
+    /// * Avoids further syscalls when checking multiple properties of traversed entries
+    /// * Links report the properties of the target they point to except for `link`
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Memfs::new();
+    /// let file = vfs.root().mash("file");
+    /// assert_vfs_write_all!(vfs, &file, "foobar");
+    /// let meta = vfs.entry(&file).unwrap().metadata();
+    /// assert_eq!(meta.mode, 0o100644);
+    /// assert_eq!(meta.size, 6);
+    /// ```
+    fn metadata(&self) -> VfsMetadata;
+
+    /// Returns the chain of targets followed to resolve a link to its final target
+    ///
+    /// * Each element is the absolute path of a link hop's target in order with the final target
+    ///   last, thus all but the last element are themselves links
+    /// * Non link entries return an empty chain
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Memfs::new();
+    /// let file = vfs.root().mash("file");
+    /// let link1 = vfs.root().mash("link1");
+    /// let link2 = vfs.root().mash("link2");
+    /// assert_vfs_mkfile!(vfs, &file);
+    /// assert_vfs_symlink!(vfs, &link1, &file);
+    /// assert_vfs_symlink!(vfs, &link2, &link1);
+    /// assert_eq!(vfs.entry(&link2).unwrap().target_chain(), &[link1, file]);
+    /// ```
+    fn target_chain(&self) -> &[PathBuf];
+
     /// Up cast the trait type to the enum wrapper
     ///
     /// ### Examples
@@ -327,6 +549,33 @@ pub enum VfsEntry
     Memfs(MemfsEntry),
 }
 
+impl VfsEntry
+{
+    /// Returns the backend specific metadata of the given type if this entry's backend provides it
+    ///
+    /// * [`StdfsMeta`](crate::sys::StdfsMeta) is provided by Stdfs entries
+    /// * [`MemfsMeta`](crate::sys::MemfsMeta) is provided by Memfs entries
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let file = vfs.root().mash("file");
+    /// assert_vfs_mkfile!(vfs, &file);
+    /// let entry = vfs.entry(&file).unwrap();
+    /// assert!(entry.raw::<MemfsMeta>().is_some());
+    /// assert!(entry.raw::<StdfsMeta>().is_none());
+    /// ```
+    pub fn raw<T: Any>(&self) -> Option<&T>
+    {
+        match self {
+            VfsEntry::Stdfs(x) => (&x.meta as &dyn Any).downcast_ref::<T>(),
+            VfsEntry::Memfs(x) => (&x.meta as &dyn Any).downcast_ref::<T>(),
+        }
+    }
+}
+
 impl Clone for VfsEntry
 {
     fn clone(&self) -> Self
@@ -351,7 +600,6 @@ impl Entry for VfsEntry
     /// ```
     /// use rivia::prelude::*;
     /// ```
-
     fn path(&self) -> &Path
     {
         match self {
@@ -435,6 +683,20 @@ impl Entry for VfsEntry
         }
     }
 
+    /// Returns the names of the directory's immediate children sorted by name
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    /// ```
+    fn children(&self) -> RvResult<Vec<String>>
+    {
+        match self {
+            VfsEntry::Stdfs(x) => x.children(),
+            VfsEntry::Memfs(x) => x.children(),
+        }
+    }
+
     /// Switch the `path` and `alt` values if `is_symlink` reports true.
     ///
     /// ### Examples
@@ -533,6 +795,76 @@ impl Entry for VfsEntry
         }
     }
 
+    /// Reports the time the entry was last modified
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    /// ```
+    fn modified(&self) -> SystemTime
+    {
+        match self {
+            VfsEntry::Stdfs(x) => x.modified(),
+            VfsEntry::Memfs(x) => x.modified(),
+        }
+    }
+
+    /// Reports the time the entry was created
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    /// ```
+    fn created(&self) -> SystemTime
+    {
+        match self {
+            VfsEntry::Stdfs(x) => x.created(),
+            VfsEntry::Memfs(x) => x.created(),
+        }
+    }
+
+    /// Reports the size of the file in bytes
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    /// ```
+    fn size(&self) -> u64
+    {
+        match self {
+            VfsEntry::Stdfs(x) => x.size(),
+            VfsEntry::Memfs(x) => x.size(),
+        }
+    }
+
+    /// Returns the filesystem properties cached when the entry was loaded
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    /// ```
+    fn metadata(&self) -> VfsMetadata
+    {
+        match self {
+            VfsEntry::Stdfs(x) => x.metadata(),
+            VfsEntry::Memfs(x) => x.metadata(),
+        }
+    }
+
+    /// Returns the chain of targets followed to resolve a link to its final target
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    /// ```
+    fn target_chain(&self) -> &[PathBuf]
+    {
+        match self {
+            VfsEntry::Stdfs(x) => x.target_chain(),
+            VfsEntry::Memfs(x) => x.target_chain(),
+        }
+    }
+
     /// Up cast the trait type to the enum wrapper
     ///
     /// ### Examples
@@ -555,6 +887,177 @@ mod tests
 {
     use crate::prelude::*;
 
+    #[test]
+    fn test_vfs_entry_target_chain()
+    {
+        test_entry_target_chain(assert_vfs_setup!(Vfs::memfs()));
+        test_entry_target_chain(assert_vfs_setup!(Vfs::stdfs()));
+    }
+    fn test_entry_target_chain((vfs, tmpdir): (Vfs, PathBuf))
+    {
+        let dir1 = tmpdir.mash("dir1");
+        let file1 = dir1.mash("file1");
+        let link1 = tmpdir.mash("link1");
+        let link2 = dir1.mash("link2");
+        let link3 = tmpdir.mash("link3");
+
+        assert_vfs_mkdir_p!(vfs, &dir1);
+        assert_vfs_mkfile!(vfs, &file1);
+        assert_vfs_symlink!(vfs, &link1, &file1);
+        assert_vfs_symlink!(vfs, &link2, &link1);
+        assert_vfs_symlink!(vfs, &link3, &link2);
+
+        // Non links have no chain
+        let entry = vfs.entry(&file1).unwrap();
+        assert_eq!(entry.link_hops(), 0);
+        assert!(entry.target_chain().is_empty());
+
+        // Single hop
+        let entry = vfs.entry(&link1).unwrap();
+        assert_eq!(entry.link_hops(), 1);
+        assert_eq!(entry.target_chain(), &vec![file1.clone()]);
+
+        // Multiple hops
+        let entry = vfs.entry(&link3).unwrap();
+        assert_eq!(entry.link_hops(), 3);
+        assert_eq!(entry.target_chain(), &[link2.clone(), link1.clone(), file1.clone()]);
+        assert_eq!(entry.alt(), &link2);
+
+        // Entries report the same chain
+        let entry = vfs.entries(&tmpdir).unwrap().into_iter().find(|x| x.as_ref().unwrap().path() == link3);
+        assert_eq!(entry.unwrap().unwrap().target_chain(), &[link2, link1, file1]);
+
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+
+    #[test]
+    fn test_vfs_entry_metadata()
+    {
+        test_entry_metadata(assert_vfs_setup!(Vfs::memfs()));
+        test_entry_metadata(assert_vfs_setup!(Vfs::stdfs()));
+    }
+    fn test_entry_metadata((vfs, tmpdir): (Vfs, PathBuf))
+    {
+        let dir1 = tmpdir.mash("dir1");
+        let file1 = dir1.mash("file1");
+        let link1 = tmpdir.mash("link1");
+        let link2 = tmpdir.mash("link2");
+
+        assert_vfs_mkdir_p!(vfs, &dir1);
+        assert_vfs_write_all!(vfs, &file1, "foobar");
+        assert_vfs_symlink!(vfs, &link1, &file1);
+
+        // Matches the individual properties and the vfs
+        let entry = vfs.entry(&file1).unwrap();
+        let meta = entry.metadata();
+        assert_eq!((meta.dir, meta.file, meta.link), (false, true, false));
+        assert_eq!(meta.mode, vfs.mode(&file1).unwrap());
+        assert_eq!(meta.size, 6);
+        assert_eq!((meta.uid, meta.gid), vfs.owner(&file1).unwrap());
+        assert_eq!((meta.modified, meta.created), (entry.modified(), entry.created()));
+
+        // Links report their target's properties
+        let meta = vfs.entry(&link1).unwrap().metadata();
+        assert_eq!((meta.dir, meta.file, meta.link), (false, true, true));
+        assert_eq!(meta.size, 6);
+
+        // Entries report the same properties
+        for entry in vfs.entries(&tmpdir).unwrap() {
+            let entry = entry.unwrap();
+            assert_eq!(entry.metadata(), vfs.entry(entry.path()).unwrap().metadata());
+        }
+
+        // Dangling links don't exist on the real filesystem
+        assert!(vfs.symlink(&link2, tmpdir.mash("missing")).is_ok());
+        if let Vfs::Stdfs(_) = vfs {
+            assert_eq!(
+                vfs.entry(&link2).unwrap_err().downcast_ref::<PathError>(),
+                Some(&PathError::does_not_exist(&link2))
+            );
+        }
+
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+
+    #[test]
+    fn test_vfs_entry_raw()
+    {
+        test_entry_raw(assert_vfs_setup!(Vfs::memfs()));
+        test_entry_raw(assert_vfs_setup!(Vfs::stdfs()));
+    }
+    fn test_entry_raw((vfs, tmpdir): (Vfs, PathBuf))
+    {
+        let file1 = tmpdir.mash("file1");
+        let file2 = tmpdir.mash("file2");
+        let file3 = tmpdir.mash("file3");
+        let link1 = tmpdir.mash("link1");
+        assert_vfs_mkfile!(vfs, &file1);
+        assert_vfs_symlink!(vfs, &link1, &file1);
+
+        // Identity of the entry by backend
+        let id = |path: &PathBuf| {
+            let entry = vfs.entry(path).unwrap();
+            match (entry.raw::<StdfsMeta>(), entry.raw::<MemfsMeta>()) {
+                (Some(x), None) => (x.dev, x.ino),
+                (None, Some(x)) => (0, x.id),
+                _ => panic!("expected exactly one backend meta"),
+            }
+        };
+
+        // Links share their target's identity
+        assert_eq!(id(&link1), id(&file1));
+
+        // Copies are new entries while moves keep their identity
+        let before = id(&file1);
+        assert!(vfs.copy(&file1, &file2).is_ok());
+        assert_ne!(id(&file2), before);
+        assert!(vfs.move_p(&file1, &file3).is_ok());
+        assert_eq!(id(&file3), before);
+
+        // Hard links are only possible on the real filesystem
+        if let Vfs::Stdfs(_) = vfs {
+            assert!(std::fs::hard_link(&file3, &file1).is_ok());
+            assert_eq!(id(&file1), before);
+            assert_eq!(vfs.entry(&file1).unwrap().raw::<StdfsMeta>().unwrap().nlink, 2);
+        }
+
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+
+    #[test]
+    fn test_vfs_entry_children()
+    {
+        test_entry_children(assert_vfs_setup!(Vfs::memfs()));
+        test_entry_children(assert_vfs_setup!(Vfs::stdfs()));
+    }
+    fn test_entry_children((vfs, tmpdir): (Vfs, PathBuf))
+    {
+        let dir1 = tmpdir.mash("dir1");
+        let dir2 = dir1.mash("dir2");
+        let file1 = dir1.mash("file1");
+        let link1 = tmpdir.mash("link1");
+        let link2 = tmpdir.mash("link2");
+        assert_vfs_mkdir_p!(vfs, &dir2);
+        assert_vfs_mkfile!(vfs, &file1);
+        assert_vfs_symlink!(vfs, &link1, &dir1);
+        assert_vfs_symlink!(vfs, &link2, &file1);
+
+        // Directories and links to directories
+        assert_eq!(vfs.entry(&dir1).unwrap().children().unwrap(), vec!["dir2", "file1"]);
+        assert_eq!(vfs.entry(&dir2).unwrap().children().unwrap(), Vec::<String>::new());
+        assert_eq!(vfs.entry(&link1).unwrap().children().unwrap(), vec!["dir2", "file1"]);
+        assert_eq!(vfs.entry(&link1).unwrap().follow(true).children().unwrap(), vec!["dir2", "file1"]);
+        assert_eq!(vfs.entry(&tmpdir).unwrap().children().unwrap(), vec!["dir1", "link1", "link2"]);
+
+        // Files and links to files
+        let err = vfs.entry(&file1).unwrap().children().unwrap_err();
+        assert_eq!(err.downcast_ref::<PathError>(), Some(&PathError::is_not_dir(&file1)));
+        let err = vfs.entry(&link2).unwrap().children().unwrap_err();
+        assert_eq!(err.downcast_ref::<PathError>(), Some(&PathError::is_not_dir(&link2)));
+
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+
     #[test]
     fn test_vfs_entry_alt_rel()
     {
@@ -589,9 +1092,9 @@ mod tests
         let file1 = tmpdir.mash("file1");
 
         assert_vfs_mkfile!(vfs, &file1);
-        assert_eq!(vfs.entry(&file1).unwrap().is_dir(), false);
+        assert!(!vfs.entry(&file1).unwrap().is_dir());
         assert_vfs_mkdir_p!(vfs, &dir1);
-        assert_eq!(vfs.entry(&dir1).unwrap().is_dir(), true);
+        assert!(vfs.entry(&dir1).unwrap().is_dir());
 
         assert_vfs_remove_all!(vfs, &tmpdir);
     }
@@ -608,9 +1111,9 @@ mod tests
         let file1 = tmpdir.mash("file1");
 
         assert_vfs_mkdir_p!(vfs, &dir1);
-        assert_eq!(vfs.entry(&dir1).unwrap().is_file(), false);
+        assert!(!vfs.entry(&dir1).unwrap().is_file());
         assert_vfs_mkfile!(vfs, &file1);
-        assert_eq!(vfs.entry(&file1).unwrap().is_file(), true);
+        assert!(vfs.entry(&file1).unwrap().is_file());
 
         assert_vfs_remove_all!(vfs, &tmpdir);
     }
@@ -626,7 +1129,7 @@ mod tests
         let file1 = tmpdir.mash("file1");
 
         assert_vfs_mkfile!(vfs, &file1);
-        assert_eq!(vfs.entry(&file1).unwrap().is_readonly(), false);
+        assert!(!vfs.entry(&file1).unwrap().is_readonly());
 
         assert_vfs_remove_all!(vfs, &tmpdir);
     }
@@ -643,9 +1146,9 @@ mod tests
         let link1 = tmpdir.mash("link1");
 
         assert_vfs_mkdir_p!(vfs, &dir1);
-        assert_eq!(vfs.entry(&dir1).unwrap().is_symlink(), false);
+        assert!(!vfs.entry(&dir1).unwrap().is_symlink());
         assert_vfs_symlink!(vfs, &link1, &dir1);
-        assert_eq!(vfs.entry(&link1).unwrap().is_symlink(), true);
+        assert!(vfs.entry(&link1).unwrap().is_symlink());
 
         assert_vfs_remove_all!(vfs, &tmpdir);
     }
@@ -663,12 +1166,12 @@ mod tests
 
         // regular dir is not a symlink dir
         assert_vfs_mkdir_p!(vfs, &dir1);
-        assert_eq!(vfs.entry(&dir1).unwrap().is_symlink_dir(), false);
+        assert!(!vfs.entry(&dir1).unwrap().is_symlink_dir());
 
         // test absolute
         assert_vfs_symlink!(vfs, &link1, &dir1);
-        assert_eq!(vfs.entry(&link1).unwrap().is_symlink_dir(), true);
-        assert_eq!(vfs.entry(&link1).unwrap().is_symlink_file(), false);
+        assert!(vfs.entry(&link1).unwrap().is_symlink_dir());
+        assert!(!vfs.entry(&link1).unwrap().is_symlink_file());
 
         assert_vfs_remove_all!(vfs, &tmpdir);
     }
@@ -686,12 +1189,12 @@ mod tests
 
         // regular dir is not a symlink dir
         assert_vfs_mkfile!(vfs, &file1);
-        assert_eq!(vfs.entry(&file1).unwrap().is_symlink_file(), false);
+        assert!(!vfs.entry(&file1).unwrap().is_symlink_file());
 
         // test absolute
         assert_vfs_symlink!(vfs, &link1, &file1);
-        assert_eq!(vfs.entry(&link1).unwrap().is_symlink_dir(), false);
-        assert_eq!(vfs.entry(&link1).unwrap().is_symlink_file(), true);
+        assert!(!vfs.entry(&link1).unwrap().is_symlink_dir());
+        assert!(vfs.entry(&link1).unwrap().is_symlink_file());
 
         assert_vfs_remove_all!(vfs, &tmpdir);
     }
@@ -711,10 +1214,10 @@ mod tests
         assert_vfs_symlink!(vfs, &link1, &file1);
         let entry = vfs.entry(&link1).unwrap();
         assert_eq!(entry.path(), &link1);
-        assert_eq!(entry.following(), false);
+        assert!(!entry.following());
         let entry = entry.follow(true);
         assert_eq!(entry.path(), &file1);
-        assert_eq!(entry.following(), true);
+        assert!(entry.following());
 
         assert_vfs_remove_all!(vfs, &tmpdir);
     }
@@ -186,7 +186,7 @@ mod tests {
         // dirs first
         let mut iter = vfs._entry_iter(&guard, &tmpdir).unwrap()(&tmpdir, false).unwrap();
         iter.dirs_first(|x, y| x.file_name().cmp(&y.file_name()));
-        assert_eq!(iter.cached(), true);
+        assert!(iter.cached());
         assert_eq!(iter.next().unwrap().unwrap().path(), dir1);
         assert_eq!(iter.next().unwrap().unwrap().path(), dir2);
         assert_eq!(iter.next().unwrap().unwrap().path(), file1);
@@ -196,7 +196,7 @@ mod tests {
         // files first
         let mut iter = vfs._entry_iter(&guard, &tmpdir).unwrap()(&tmpdir, false).unwrap();
         iter.files_first(|x, y| x.file_name().cmp(&y.file_name()));
-        assert_eq!(iter.cached(), true);
+        assert!(iter.cached());
         assert_eq!(iter.next().unwrap().unwrap().path(), file1);
         assert_eq!(iter.next().unwrap().unwrap().path(), file2);
         assert_eq!(iter.next().unwrap().unwrap().path(), dir1);
@@ -219,7 +219,7 @@ mod tests {
         let guard = vfs.read_guard();
         let mut iter = vfs._entry_iter(&guard, &tmpdir).unwrap()(&tmpdir, false).unwrap();
         iter.sort(|x, y| x.file_name().cmp(&y.file_name()));
-        assert_eq!(iter.cached(), true);
+        assert!(iter.cached());
         assert_eq!(iter.next().unwrap().unwrap().path(), file1);
         assert_eq!(iter.next().unwrap().unwrap().path(), file2);
         assert!(iter.next().is_none());
@@ -244,24 +244,24 @@ mod tests {
 
         // custom sort for files
         let iter = vfs._entry_iter(&guard, &tmpdir).unwrap()(&tmpdir, false).unwrap();
-        assert_eq!(iter.following(), false);
+        assert!(!iter.following());
         let mut iter = iter.follow(true);
-        assert_eq!(iter.following(), true);
+        assert!(iter.following());
         iter.sort(|x, y| x.file_name().cmp(&y.file_name()));
-        assert_eq!(iter.cached(), true);
+        assert!(iter.cached());
 
         // because we sort on the path and we have follow set which switches the path and alt
         // sort order will be based on the file name not the link name
         let item1 = iter.next().unwrap().unwrap();
-        assert_eq!(item1.following(), false);
+        assert!(!item1.following());
         assert_eq!(item1.path(), &file1);
 
         let item2 = iter.next().unwrap().unwrap();
-        assert_eq!(item2.following(), false);
+        assert!(!item2.following());
         assert_eq!(item2.path(), &file2);
 
         let item3 = iter.next().unwrap().unwrap();
-        assert_eq!(item3.following(), true);
+        assert!(item3.following());
         assert_eq!(item3.path(), &file3);
         assert_eq!(item3.alt(), &link1);
 
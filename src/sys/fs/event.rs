@@ -0,0 +1,117 @@
+use std::path::{Path, PathBuf};
+
+/// Provides a classification of the mutating operations a filesystem supports
+///
+/// ### Examples
+/// ```
+/// use rivia::prelude::*;
+///
+/// let event = VfsEvent::Create(PathBuf::from("/file"));
+/// assert_eq!(event.op(), VfsOp::Create);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum VfsOp
+{
+    Create, // a file, directory or link was created
+    Write,  // file data was written
+    Remove, // a file, directory or link was removed
+    Rename, // a file, directory or link was moved
+    Chmod,  // permissions were changed
+    Chown,  // ownership was changed
+}
+
+/// Describes a single filesystem mutation along with any old and new values
+///
+/// ### Examples
+/// ```
+/// use rivia::prelude::*;
+///
+/// let memfs = Memfs::new();
+/// let events = memfs.events();
+/// let file = memfs.root().mash("file");
+/// assert_vfs_mkfile!(memfs, &file);
+/// assert!(memfs.chmod(&file, 0o600).is_ok());
+/// assert_eq!(events.try_iter().collect::<Vec<_>>(), vec![
+///     VfsEvent::Create(file.clone()),
+///     VfsEvent::Chmod(file, 0o100644, 0o100600)
+/// ]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VfsEvent
+{
+    Create(PathBuf),                        // path created
+    Write(PathBuf),                         // path written to
+    Remove(PathBuf),                        // path removed
+    Rename(PathBuf, PathBuf),               // source path, destination path
+    Chmod(PathBuf, u32, u32),               // path, old mode, new mode
+    Chown(PathBuf, (u32, u32), (u32, u32)), // path, old (uid, gid), new (uid, gid)
+}
+
+impl VfsEvent
+{
+    /// Returns the operation classification for this event
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let event = VfsEvent::Remove(PathBuf::from("/file"));
+    /// assert_eq!(event.op(), VfsOp::Remove);
+    /// ```
+    pub fn op(&self) -> VfsOp
+    {
+        match self {
+            VfsEvent::Create(_) => VfsOp::Create,
+            VfsEvent::Write(_) => VfsOp::Write,
+            VfsEvent::Remove(_) => VfsOp::Remove,
+            VfsEvent::Rename(..) => VfsOp::Rename,
+            VfsEvent::Chmod(..) => VfsOp::Chmod,
+            VfsEvent::Chown(..) => VfsOp::Chown,
+        }
+    }
+
+    /// Returns the path the event occurred on
+    ///
+    /// * For `Rename` events this is the source path
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let event = VfsEvent::Rename(PathBuf::from("/file1"), PathBuf::from("/file2"));
+    /// assert_eq!(event.path(), Path::new("/file1"));
+    /// ```
+    pub fn path(&self) -> &Path
+    {
+        match self {
+            VfsEvent::Create(x) => x,
+            VfsEvent::Write(x) => x,
+            VfsEvent::Remove(x) => x,
+            VfsEvent::Rename(x, _) => x,
+            VfsEvent::Chmod(x, ..) => x,
+            VfsEvent::Chown(x, ..) => x,
+        }
+    }
+}
+
+// Unit tests
+// -------------------------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests
+{
+    use crate::prelude::*;
+
+    #[test]
+    fn test_event_op_and_path()
+    {
+        let path = PathBuf::from("/foo");
+        assert_eq!(VfsEvent::Create(path.clone()).op(), VfsOp::Create);
+        assert_eq!(VfsEvent::Write(path.clone()).op(), VfsOp::Write);
+        assert_eq!(VfsEvent::Remove(path.clone()).op(), VfsOp::Remove);
+        assert_eq!(VfsEvent::Rename(path.clone(), PathBuf::from("/bar")).op(), VfsOp::Rename);
+        assert_eq!(VfsEvent::Chmod(path.clone(), 0o40755, 0o40700).op(), VfsOp::Chmod);
+        assert_eq!(VfsEvent::Chown(path.clone(), (0, 0), (5, 5)).op(), VfsOp::Chown);
+        assert_eq!(VfsEvent::Rename(path.clone(), PathBuf::from("/bar")).path(), &path);
+        assert_eq!(VfsEvent::Chown(path.clone(), (0, 0), (5, 5)).path(), &path);
+    }
+}
@@ -0,0 +1,262 @@
+use std::path::{Path, PathBuf};
+
+use crate::{
+    errors::*,
+    sys::{Entry, VirtualFileSystem},
+};
+
+/// Returns true if the given `text` matches the given glob `pattern`
+///
+/// * `*` matches any sequence of characters excluding the path separator
+/// * `**` matches any sequence of characters including the path separator
+/// * `**/` additionally matches zero directories e.g. `/a/**/b` matches `/a/b`
+/// * `?` matches any single character excluding the path separator
+/// * `[abc]`, `[a-z]` and `[!a-z]` match a single character in or not in the given set
+/// * All other characters match themselves
+pub(crate) fn glob_match(pattern: &str, text: &str) -> bool
+{
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    matches(&pattern, &text)
+}
+
+/// Returns true if the given `path` or any of its parents within `root` match one of the `globs`
+///
+/// * Used to exclude entire subtrees from a traversal of `root`
+pub(crate) fn glob_excluded(root: &Path, path: &Path, globs: &[String]) -> bool
+{
+    !globs.is_empty()
+        && path
+            .ancestors()
+            .take_while(|x| x.starts_with(root))
+            .any(|x| globs.iter().any(|y| glob_match(y, &x.to_string_lossy())))
+}
+
+/// Returns the absolute paths matching the given glob pattern sorted by path
+///
+/// * Traversal starts from the leading components of the pattern that have no wildcards
+/// * Traversal is limited to the depth of the pattern unless it contains `**`
+/// * Links aren't followed and directories that can't be read are skipped like the shell does
+pub(crate) fn glob_paths<T: VirtualFileSystem + ?Sized>(vfs: &T, pattern: &str) -> RvResult<Vec<PathBuf>>
+{
+    let pattern = vfs.abs(pattern)?;
+    let wild = |x: &str| x.contains(['*', '?', '[']);
+
+    // Split the pattern into the base directory to traverse from and the depth below it
+    let mut base = PathBuf::new();
+    let mut depth = 0;
+    let mut recursive = false;
+    for component in pattern.components() {
+        let name = component.as_os_str().to_string_lossy();
+        if depth == 0 && !wild(&name) {
+            base.push(component);
+        } else {
+            depth += 1;
+            recursive |= name.contains("**");
+        }
+    }
+
+    // Patterns without wildcards match only themselves
+    if depth == 0 {
+        return Ok(if vfs.exists(&pattern) { vec![pattern] } else { vec![] });
+    }
+    if !vfs.is_dir(&base) {
+        return Ok(vec![]);
+    }
+    let entries = vfs.entries(&base)?.min_depth(1).max_depth(if recursive { usize::MAX } else { depth });
+    let entries = entries.glob(&pattern.to_string_lossy());
+    let mut paths: Vec<PathBuf> = entries.into_iter().filter_map(|x| x.ok()).map(|x| x.path_buf()).collect();
+    paths.sort();
+    paths.dedup();
+    Ok(paths)
+}
+
+// Recursive backtracking matcher over the pattern and text characters
+fn matches(p: &[char], t: &[char]) -> bool
+{
+    if p.is_empty() {
+        return t.is_empty();
+    }
+    match p[0] {
+        '*' if p.get(1) == Some(&'*') => {
+            if p.get(2) == Some(&'/') {
+                // Match zero or more whole directories
+                let rest = &p[3..];
+                matches(rest, t) || (0..t.len()).any(|i| t[i] == '/' && matches(rest, &t[i + 1..]))
+            } else {
+                let rest = &p[2..];
+                (0..=t.len()).any(|i| matches(rest, &t[i..]))
+            }
+        },
+        '*' => {
+            let rest = &p[1..];
+            for i in 0..=t.len() {
+                if matches(rest, &t[i..]) {
+                    return true;
+                }
+                if i < t.len() && t[i] == '/' {
+                    break;
+                }
+            }
+            false
+        },
+        '?' => !t.is_empty() && t[0] != '/' && matches(&p[1..], &t[1..]),
+        '[' => match class(&p[1..], t.first()) {
+            Some((true, len)) => matches(&p[len + 1..], &t[1..]),
+            Some((false, _)) => false,
+
+            // Treat an unterminated class as a literal
+            None => !t.is_empty() && t[0] == '[' && matches(&p[1..], &t[1..]),
+        },
+        c => !t.is_empty() && t[0] == c && matches(&p[1..], &t[1..]),
+    }
+}
+
+// Match the given character against the character class starting just after the opening bracket
+// returning whether it matched and the length of the class including the closing bracket.
+fn class(p: &[char], c: Option<&char>) -> Option<(bool, usize)>
+{
+    let negate = matches!(p.first(), Some('!') | Some('^'));
+    let start = if negate { 1 } else { 0 };
+
+    let mut i = start;
+    let mut found = false;
+    while i < p.len() {
+        // A closing bracket in the first position is treated as a literal
+        if p[i] == ']' && i > start {
+            let matched = match c {
+                Some(&c) if c != '/' => found != negate,
+                _ => false,
+            };
+            return Some((matched, i + 1));
+        }
+        if let Some(&c) = c {
+            if i + 2 < p.len() && p[i + 1] == '-' && p[i + 2] != ']' {
+                found |= p[i] <= c && c <= p[i + 2];
+                i += 3;
+                continue;
+            }
+            found |= p[i] == c;
+        }
+        i += 1;
+    }
+    None
+}
+
+// Unit tests
+// -------------------------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::prelude::*;
+
+    #[test]
+    fn test_vfs_glob()
+    {
+        test_glob(assert_vfs_setup!(Vfs::memfs()));
+        test_glob(assert_vfs_setup!(Vfs::stdfs()));
+    }
+    fn test_glob((vfs, tmpdir): (Vfs, PathBuf))
+    {
+        let etc = tmpdir.mash("etc");
+        let file1 = etc.mash("file1.conf");
+        let file2 = etc.mash("file2.conf");
+        let file3 = etc.mash("file3.txt");
+        let dir1 = etc.mash("dir1");
+        let file4 = dir1.mash("file4.conf");
+        let file5 = dir1.mash("sub/file5.conf");
+        let link1 = etc.mash("link1.conf");
+        assert_vfs_mkdir_p!(vfs, file5.dir().unwrap());
+        for file in [&file1, &file2, &file3, &file4, &file5] {
+            assert_vfs_mkfile!(vfs, file);
+        }
+        assert_vfs_symlink!(vfs, &link1, &dir1);
+        let glob = |pattern: &str| vfs.glob(etc.mash(pattern).to_string().unwrap()).unwrap();
+
+        // Single star stays within a directory
+        assert_eq!(glob("*.conf"), vec![file1.clone(), file2.clone(), link1.clone()]);
+        assert_eq!(glob("*/*.conf"), vec![file4.clone()]);
+
+        // Double star crosses directories without following links
+        let paths = vec![file4.clone(), file5.clone(), file1.clone(), file2.clone(), link1.clone()];
+        assert_eq!(glob("**/*.conf"), paths);
+        assert_eq!(glob("dir1/**"), vec![file4.clone(), dir1.mash("sub"), file5.clone()]);
+
+        // Single characters and classes
+        assert_eq!(glob("file?.conf"), vec![file1.clone(), file2.clone()]);
+        assert_eq!(glob("file[13].*"), vec![file1.clone(), file3.clone()]);
+        assert_eq!(glob("file[!1].conf"), vec![file2.clone()]);
+
+        // Literals and misses
+        assert_eq!(glob("file3.txt"), vec![file3.clone()]);
+        assert!(glob("file4.txt").is_empty());
+        assert!(glob("missing/*.conf").is_empty());
+
+        // Entries may be filtered by a glob relative to the traversal root
+        let paths = vfs.entries(&etc).unwrap().glob("*/**/*.conf").sort_by_name().collect_paths().unwrap();
+        assert_eq!(paths, vec![file4.clone(), file5.clone()]);
+
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+
+    #[test]
+    fn test_glob_relative()
+    {
+        // Relative patterns are resolved against the current directory
+        let vfs = Memfs::new();
+        let file1 = vfs.root().mash("dir1/file1.txt");
+        assert_vfs_mkdir_p!(vfs, "dir1");
+        assert_vfs_mkfile!(vfs, &file1);
+        assert!(vfs.set_cwd("dir1").is_ok());
+        assert_eq!(vfs.glob("*.txt").unwrap(), vec![file1.clone()]);
+        assert_eq!(vfs.glob("../*/*.txt").unwrap(), vec![file1]);
+    }
+
+    #[test]
+    fn test_glob_match()
+    {
+        // literals
+        assert!(glob_match("/etc/hosts", "/etc/hosts"));
+        assert!(!glob_match("/etc/hosts", "/etc/host"));
+        assert!(!glob_match("/etc/host", "/etc/hosts"));
+
+        // single star doesn't cross directories
+        assert!(glob_match("/etc/*", "/etc/hosts"));
+        assert!(glob_match("/etc/*.conf", "/etc/foo.conf"));
+        assert!(glob_match("*", ""));
+        assert!(!glob_match("/etc/*", "/etc"));
+        assert!(!glob_match("/etc/*", "/etc/foo/bar"));
+        assert!(!glob_match("*.rs", "src/lib.rs"));
+
+        // double star crosses directories
+        assert!(glob_match("/etc/**", "/etc/foo/bar"));
+        assert!(glob_match("**/*.rs", "src/lib.rs"));
+        assert!(glob_match("**/*.rs", "lib.rs"));
+        assert!(glob_match("/a/**/b", "/a/b"));
+        assert!(glob_match("/a/**/b", "/a/x/y/b"));
+        assert!(!glob_match("/a/**/b", "/a/xb"));
+
+        // single character
+        assert!(glob_match("file?", "file1"));
+        assert!(!glob_match("file?", "file"));
+        assert!(!glob_match("a?b", "a/b"));
+
+        // character classes
+        assert!(glob_match("file[12]", "file1"));
+        assert!(!glob_match("file[12]", "file3"));
+        assert!(glob_match("file[a-c]", "fileb"));
+        assert!(!glob_match("file[!a-c]", "fileb"));
+        assert!(glob_match("file[!a-c]", "filed"));
+        assert!(glob_match("file[]]", "file]"));
+
+        // subtree exclusion
+        let globs = vec!["**/vendor".to_string()];
+        assert!(glob_excluded(Path::new("/a"), Path::new("/a/vendor"), &globs));
+        assert!(glob_excluded(Path::new("/a"), Path::new("/a/vendor/b/c"), &globs));
+        assert!(!glob_excluded(Path::new("/a"), Path::new("/a/b/c"), &globs));
+        assert!(!glob_excluded(Path::new("/vendor/a"), Path::new("/vendor/a/b"), &globs));
+        assert!(!glob_excluded(Path::new("/a"), Path::new("/a/vendor"), &[]));
+        assert!(glob_match("file[", "file["));
+    }
+}
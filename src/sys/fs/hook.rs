@@ -0,0 +1,192 @@
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    errors::*,
+    sys::{self, VfsOp},
+};
+
+// Helper aliases
+pub(crate) type PreHook = Arc<dyn Fn(VfsOp, &Path) -> RvResult<()> + Send + Sync + 'static>;
+pub(crate) type PostHook = Arc<dyn Fn(VfsOp, &Path) + Send + Sync + 'static>;
+pub(crate) type SlowHook = Arc<dyn Fn(VfsOp, &Path, Duration) + Send + Sync + 'static>;
+pub(crate) type LinkReader = Arc<dyn Fn(&Path) -> RvResult<Option<PathBuf>> + Send + Sync + 'static>;
+
+// Internal type used to track the pre, post and slow operation hooks registered with a Vfs backend. Cloning
+// is cheap as the hooks themselves are shared.
+#[derive(Clone, Default)]
+pub(crate) struct VfsHooks
+{
+    pub(crate) pre: Vec<PreHook>,               // hooks called before an operation, able to veto it
+    pub(crate) post: Vec<PostHook>,             // hooks called after an operation succeeded
+    pub(crate) slow: Vec<(Duration, SlowHook)>, // hooks called after operations slower than their threshold
+    pub(crate) readlink: Option<LinkReader>,    // link reading used to vet where links lead
+}
+
+impl VfsHooks
+{
+    /// Returns true if no hooks have been registered
+    pub(crate) fn is_empty(&self) -> bool
+    {
+        self.pre.is_empty() && self.post.is_empty() && self.slow.is_empty()
+    }
+
+    /// Execute the given operation `f` wrapped in the registered hooks
+    ///
+    /// * Pre hooks are called in registration order and the first error vetoes the operation
+    /// * Pre hooks are called again with the path that links lead to when it differs so that a
+    ///   link can't carry the operation somewhere the hooks would veto. Links at the path itself
+    ///   aren't followed for `Remove` and `Rename` as those operate on the link.
+    /// * Post hooks are called in registration order only when the operation succeeded
+    /// * Slow hooks are called once the operation returns, whether it succeeded or not, when it took
+    ///   at least their threshold. They only report the duration and can neither interrupt nor fail
+    ///   the operation so a hung operation is only reported if it ever returns.
+    pub(crate) fn exec<T, F>(&self, op: VfsOp, path: &Path, f: F) -> RvResult<T>
+    where
+        F: FnOnce() -> RvResult<T>,
+    {
+        for hook in self.pre.iter() {
+            hook(op, path)?;
+        }
+        if let Some(readlink) = self.readlink.as_ref().filter(|_| !self.pre.is_empty()) {
+            let follow = !matches!(op, VfsOp::Remove | VfsOp::Rename);
+            let real = sys::real_path(path, follow, readlink.as_ref())?;
+            if real != path {
+                for hook in self.pre.iter() {
+                    hook(op, &real)?;
+                }
+            }
+        }
+        let result = match self.slow.is_empty() {
+            true => f()?,
+            false => {
+                let start = Instant::now();
+                let result = f();
+                let elapsed = start.elapsed();
+                for (_, hook) in self.slow.iter().filter(|(after, _)| elapsed >= *after) {
+                    hook(op, path, elapsed);
+                }
+                result?
+            },
+        };
+        for hook in self.post.iter() {
+            hook(op, path);
+        }
+        Ok(result)
+    }
+}
+
+impl fmt::Debug for VfsHooks
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        f.debug_struct("VfsHooks")
+            .field("pre", &self.pre.len())
+            .field("post", &self.post.len())
+            .field("slow", &self.slow.len())
+            .finish()
+    }
+}
+
+// Unit tests
+// -------------------------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests
+{
+    use std::{sync::Mutex, thread};
+
+    use super::*;
+    use crate::prelude::*;
+
+    #[test]
+    fn test_exec()
+    {
+        let calls = Arc::new(Mutex::new(vec![]));
+        let mut hooks = VfsHooks::default();
+        assert!(hooks.is_empty());
+        assert_eq!(
+            format!("{:?}", hooks),
+            "VfsHooks { pre: 0, post: 0, slow: 0 }"
+        );
+
+        // Record pre and post calls
+        let c = calls.clone();
+        hooks.pre.push(Arc::new(move |op, path| {
+            c.lock().unwrap().push(format!("pre {:?} {}", op, path.display()));
+            match path.has_prefix("/etc") {
+                true => Err(VfsError::Unavailable.into()),
+                false => Ok(()),
+            }
+        }));
+        let c = calls.clone();
+        hooks.post.push(Arc::new(move |op, path| {
+            c.lock().unwrap().push(format!("post {:?} {}", op, path.display()));
+        }));
+        assert!(!hooks.is_empty());
+
+        // Allowed operation
+        assert_eq!(hooks.exec(VfsOp::Write, Path::new("/foo"), || Ok(1)).unwrap(), 1);
+
+        // Vetoed operation doesn't execute
+        assert!(hooks.exec(VfsOp::Write, Path::new("/etc/foo"), || -> RvResult<()> { panic!() }).is_err());
+
+        // Failed operation doesn't call post hooks
+        let result = hooks.exec(VfsOp::Remove, Path::new("/bar"), || -> RvResult<()> {
+            Err(VfsError::Unavailable.into())
+        });
+        assert!(result.is_err());
+
+        assert_eq!(*calls.lock().unwrap(), vec![
+            "pre Write /foo".to_string(),
+            "post Write /foo".to_string(),
+            "pre Write /etc/foo".to_string(),
+            "pre Remove /bar".to_string(),
+        ]);
+    }
+
+    #[test]
+    fn test_exec_slow()
+    {
+        let calls = Arc::new(Mutex::new(vec![]));
+        let mut hooks = VfsHooks::default();
+        let c = calls.clone();
+        hooks.post.push(Arc::new(move |op, _| c.lock().unwrap().push(format!("post {:?}", op))));
+        let c = calls.clone();
+        hooks.slow.push((Duration::from_millis(10), Arc::new(move |op, path, elapsed| {
+            assert!(elapsed >= Duration::from_millis(10));
+            c.lock().unwrap().push(format!("slow {:?} {}", op, path.display()));
+        })));
+        let c = calls.clone();
+        let never: SlowHook = Arc::new(move |_, _, _| c.lock().unwrap().push("never".into()));
+        hooks.slow.push((Duration::from_secs(3600), never));
+        assert!(!hooks.is_empty());
+
+        // Fast operations aren't reported
+        assert_eq!(hooks.exec(VfsOp::Write, Path::new("/foo"), || Ok(1)).unwrap(), 1);
+
+        // Slow operations are reported without changing their result
+        let result = hooks.exec(VfsOp::Write, Path::new("/foo"), || {
+            thread::sleep(Duration::from_millis(20));
+            Ok(2)
+        });
+        assert_eq!(result.unwrap(), 2);
+
+        // Slow failed operations are reported and still fail
+        let result = hooks.exec(VfsOp::Remove, Path::new("/bar"), || -> RvResult<()> {
+            thread::sleep(Duration::from_millis(20));
+            Err(VfsError::Unavailable.into())
+        });
+        assert_eq!(result.unwrap_err().downcast_ref::<VfsError>(), Some(&VfsError::Unavailable));
+
+        assert_eq!(*calls.lock().unwrap(), vec![
+            "post Write".to_string(),
+            "slow Write /foo".to_string(),
+            "post Write".to_string(),
+            "slow Remove /bar".to_string(),
+        ]);
+    }
+}
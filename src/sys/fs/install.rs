@@ -0,0 +1,564 @@
+use std::{
+    io::Read,
+    path::{Component, Path, PathBuf},
+    str,
+};
+
+use crate::{
+    core::*,
+    errors::*,
+    sys::{self, user, PathExt, VirtualFileSystem},
+};
+
+// Callback called with the installed file's path when it was changed
+pub(crate) type InstallNotify = Box<dyn Fn(&Path)>;
+
+/// Provides a builder pattern for deploying files in the spirit of the coreutils `install` command
+///
+/// Use the Vfs functions `install` to create a new instance followed by one or more options and
+/// complete the operation by calling `exec`. The source file is copied to the destination, links
+/// followed, with the mode and ownership set in one call. Missing destination parent directories
+/// are created.
+///
+/// * `dst` will be installed into if it is an existing directory
+/// * `dst` will be the installed file if it isn't an existing directory
+/// * Default mode is `0o755` matching the coreutils `install` command
+/// * The new file is written to a temporary sibling with its mode and ownership set and then
+///   renamed into place so the destination is never seen partially installed
+///
+/// ```
+/// use rivia::prelude::*;
+///
+/// let vfs = Vfs::memfs();
+/// assert_vfs_mkdir_p!(vfs, "/build/bin");
+/// assert_vfs_mkdir_p!(vfs, "/usr/bin");
+/// assert_vfs_write_all!(vfs, "/build/bin/app", "app");
+/// let installer = vfs.install("build/bin/app", "/usr/bin").unwrap();
+/// assert_eq!(installer.mode(0o750).exec().unwrap(), PathBuf::from("/usr/bin/app"));
+/// assert_vfs_read_all!(vfs, "/usr/bin/app", "app");
+/// assert_eq!(vfs.mode("/usr/bin/app").unwrap(), 0o100750);
+/// ```
+pub struct Installer
+{
+    pub(crate) opts: InstallOpts,
+    pub(crate) exec: Box<dyn Fn(InstallOpts) -> RvResult<(PathBuf, bool)>>, // provider callback
+    pub(crate) notify: Option<InstallNotify>,                               // change callback
+}
+
+// Internal type used to encapsulate just the options. This separates the provider implementation
+// from the options allowing for sharing options between different vfs providers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct InstallOpts
+{
+    pub(crate) src: PathBuf,                        // source file
+    pub(crate) dst: PathBuf,                        // destination path
+    pub(crate) mode: u32,                           // mode to set on the installed file
+    pub(crate) owner: Option<(String, String)>,     // user and group names to set on the installed file
+    pub(crate) backup: bool,                        // move an existing destination file aside first
+    pub(crate) strip: Option<usize>,                // leading source components to strip into dst
+    pub(crate) vars: Option<Vec<(String, String)>>, // template variables to render the source with
+    pub(crate) compare: bool,                       // only replace the destination if it differs
+}
+
+impl Default for InstallOpts
+{
+    fn default() -> Self
+    {
+        Self {
+            src: PathBuf::new(),
+            dst: PathBuf::new(),
+            mode: 0o755,
+            owner: None,
+            backup: false,
+            strip: None,
+            vars: None,
+            compare: false,
+        }
+    }
+}
+
+impl Installer
+{
+    /// Update the `backup` option
+    ///
+    /// * Default: false
+    /// * When `true` an existing destination is moved aside to the destination path suffixed with
+    ///   `.bak` replacing any previous backup
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// assert_vfs_mkdir_p!(vfs, "/usr/bin");
+    /// assert_vfs_write_all!(vfs, "/app", "new");
+    /// assert_vfs_write_all!(vfs, "/usr/bin/app", "old");
+    /// assert!(vfs.install("/app", "/usr/bin").unwrap().backup(true).exec().is_ok());
+    /// assert_vfs_read_all!(vfs, "/usr/bin/app", "new");
+    /// assert_vfs_read_all!(vfs, "/usr/bin/app.bak", "old");
+    /// ```
+    pub fn backup(mut self, yes: bool) -> Self
+    {
+        self.opts.backup = yes;
+        self
+    }
+
+    /// Update the `compare` option
+    ///
+    /// * Default: false
+    /// * When `true` the destination is only replaced when its content differs and its mode and
+    ///   ownership only changed when they differ similar to the coreutils `install -C` option
+    /// * Combine with `notify` to act only when the installed file actually changed
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// assert_vfs_write_all!(vfs, "/app", "app");
+    /// assert!(vfs.install("/app", "/app2").unwrap().compare(true).exec().is_ok());
+    /// assert!(vfs.chmod("/app", 0o600).is_ok());
+    /// assert!(vfs.install("/app", "/app2").unwrap().compare(true).exec().is_ok());
+    /// assert_eq!(vfs.mode("/app2").unwrap(), 0o100755);
+    /// ```
+    pub fn compare(mut self, yes: bool) -> Self
+    {
+        self.opts.compare = yes;
+        self
+    }
+
+    /// Set the mode of the installed file
+    ///
+    /// * Default: 0o755
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// assert_vfs_mkfile!(vfs, "/app");
+    /// assert!(vfs.install("/app", "/usr/bin/app").unwrap().mode(0o700).exec().is_ok());
+    /// assert_eq!(vfs.mode("/usr/bin/app").unwrap(), 0o100700);
+    /// ```
+    pub fn mode(mut self, mode: u32) -> Self
+    {
+        self.opts.mode = mode;
+        self
+    }
+
+    /// Set a callback to be called with the installed file's path when it was changed
+    ///
+    /// * Without `compare` the installed file is always considered changed
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let changed = Arc::new(Mutex::new(vec![]));
+    /// assert_vfs_write_all!(vfs, "/app", "app");
+    /// for _ in 0..2 {
+    ///     let changed = changed.clone();
+    ///     let installer = vfs.install("/app", "/app2").unwrap().compare(true);
+    ///     let installer = installer.notify(move |x| changed.lock().unwrap().push(x.to_path_buf()));
+    ///     assert!(installer.exec().is_ok());
+    /// }
+    /// assert_eq!(*changed.lock().unwrap(), vec![PathBuf::from("/app2")]);
+    /// ```
+    pub fn notify<F: Fn(&Path) + 'static>(mut self, f: F) -> Self
+    {
+        self.notify = Some(Box::new(f));
+        self
+    }
+
+    /// Set the user and group names to own the installed file
+    ///
+    /// * Names are resolved to ids when executed
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// assert_vfs_mkfile!(vfs, "/app");
+    /// assert!(vfs.install("/app", "/usr/bin/app").unwrap().owner("root", "root").exec().is_ok());
+    /// assert_eq!(vfs.owner("/usr/bin/app").unwrap(), (0, 0));
+    /// ```
+    pub fn owner<T: AsRef<str>, U: AsRef<str>>(mut self, user: T, group: U) -> Self
+    {
+        self.opts.owner = Some((user.as_ref().to_string(), group.as_ref().to_string()));
+        self
+    }
+
+    /// Install the source's relative path, minus the given number of leading components, into `dst`
+    ///
+    /// * `dst` is always treated as a directory with missing parents created
+    /// * At least the source's file name is kept no matter how many components are stripped
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// assert_vfs_mkdir_p!(vfs, "/build/bin");
+    /// assert_vfs_mkfile!(vfs, "/build/bin/app");
+    /// let installer = vfs.install("build/bin/app", "/usr").unwrap().strip_dirs(1);
+    /// assert_eq!(installer.exec().unwrap(), PathBuf::from("/usr/bin/app"));
+    /// ```
+    pub fn strip_dirs(mut self, n: usize) -> Self
+    {
+        self.opts.strip = Some(n);
+        self
+    }
+
+    /// Render the source as a template with the given variables before installing it
+    ///
+    /// * Replaces each `{{ name }}` in the source with its value see [`StringExt::render`]
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// assert_vfs_write_all!(vfs, "/app.conf", "port={{ port }}");
+    /// assert!(vfs.install("/app.conf", "/etc/app.conf").unwrap().template(&[("port", "80")]).exec().is_ok());
+    /// assert_vfs_read_all!(vfs, "/etc/app.conf", "port=80");
+    /// ```
+    pub fn template<K: AsRef<str>, V: AsRef<str>>(mut self, vars: &[(K, V)]) -> Self
+    {
+        self.opts.vars = Some(vars.iter().map(|(k, v)| (k.as_ref().to_string(), v.as_ref().to_string())).collect());
+        self
+    }
+
+    /// Execute the [`Installer`] builder current options returning the installed file's path
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the source doesn't exist
+    /// * PathError::IsNotFile(PathBuf) when the source or destination exists and isn't a file
+    /// * StringError::UndefinedVariable(String) when a template variable has no value given
+    /// * UserError::DoesNotExistByName(String) when the owning user doesn't exist
+    /// * UserError::GroupDoesNotExistByName(String) when the owning group doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// assert_vfs_write_all!(vfs, "/app", "app");
+    /// assert_eq!(vfs.install("/app", "/usr/bin/app").unwrap().exec().unwrap(), PathBuf::from("/usr/bin/app"));
+    /// assert_vfs_read_all!(vfs, "/usr/bin/app", "app");
+    /// ```
+    pub fn exec(&self) -> RvResult<PathBuf>
+    {
+        let (path, changed) = (self.exec)(self.opts.clone())?;
+        if let (true, Some(notify)) = (changed, &self.notify) {
+            notify(&path);
+        }
+        Ok(path)
+    }
+}
+
+// Execute the install with the given [`InstallOpts`] using the given provider returning the
+// installed file's path and whether it was changed
+pub(crate) fn install_exec<T: VirtualFileSystem>(vfs: &T, opts: InstallOpts) -> RvResult<(PathBuf, bool)>
+{
+    // Validate the source
+    let src = vfs.abs(&opts.src)?;
+    if !vfs.exists(&src) {
+        return Err(PathError::does_not_exist(&src).into());
+    } else if !vfs.is_file(&src) {
+        return Err(PathError::is_not_file(&src).into());
+    }
+
+    // Resolve the owner before making any changes
+    let owner = match &opts.owner {
+        Some((user, group)) => Some((user::uid_from_name(user)?, user::gid_from_name(group)?)),
+        None => None,
+    };
+
+    // Determine the destination file
+    let dst = vfs.abs(&opts.dst)?;
+    let dst = match opts.strip {
+        Some(n) => {
+            let src = opts.src.expand()?;
+            let names: Vec<_> = src.components().filter(|x| matches!(x, Component::Normal(_))).collect();
+            dst.mash(names.iter().skip(n.min(names.len().saturating_sub(1))).collect::<PathBuf>())
+        },
+        None if vfs.is_dir(&dst) => dst.mash(src.base()?),
+        None => dst,
+    };
+    if vfs.exists(&dst) && !vfs.is_symlink(&dst) && !vfs.is_file(&dst) {
+        return Err(PathError::is_not_file(&dst).into());
+    }
+
+    // Read the source rendering it as a template if requested
+    let mut data = vec![];
+    vfs.read(&src)?.read_to_end(&mut data)?;
+    if let Some(vars) = &opts.vars {
+        data = str::from_utf8(&data)?.render(vars)?.into_bytes();
+    }
+
+    // Compare with the current destination only reading it when it is a regular file
+    let same = match dst == src && opts.vars.is_none() {
+        true => true,
+        false if opts.compare && vfs.is_file(&dst) && !vfs.is_symlink(&dst) => {
+            let mut current = vec![];
+            vfs.read(&dst)?.read_to_end(&mut current)?;
+            current == data
+        },
+        false => false,
+    };
+
+    // Write a temp sibling with the mode and ownership set then rename it into place so the
+    // destination is never seen partially written or with the wrong mode or ownership. The
+    // destination is replaced rather than written through existing links.
+    if !same {
+        vfs.mkdir_p(dst.dir()?)?;
+        let tmp = sys::tmp_sibling(&dst)?;
+        let replace = || -> RvResult<()> {
+            vfs.write_all(&tmp, &data)?;
+            vfs.chmod(&tmp, opts.mode)?;
+            if let Some((uid, gid)) = owner {
+                vfs.chown(&tmp, uid, gid)?;
+            }
+            if opts.backup && (vfs.exists(&dst) || vfs.is_symlink(&dst)) {
+                let mut backup = dst.clone().into_os_string();
+                backup.push(".bak");
+                if vfs.exists(&backup) {
+                    vfs.remove(&backup)?;
+                }
+                vfs.move_p(&dst, &backup)?;
+            } else if vfs.is_symlink(&dst) {
+                vfs.remove(&dst)?;
+            }
+            vfs.move_p(&tmp, &dst)
+        };
+        if let Err(err) = replace() {
+            let _ = vfs.remove(&tmp);
+            return Err(err);
+        }
+        return Ok((dst, true));
+    }
+
+    // Only change the mode and ownership when they differ
+    let mut changed = false;
+    if vfs.mode(&dst)? & 0o7777 != opts.mode & 0o7777 {
+        vfs.chmod(&dst, opts.mode)?;
+        changed = true;
+    }
+    if let Some((uid, gid)) = owner {
+        if vfs.owner(&dst)? != (uid, gid) {
+            vfs.chown(&dst, uid, gid)?;
+            changed = true;
+        }
+    }
+    Ok((dst, changed))
+}
+
+// Unit tests
+// -------------------------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests
+{
+    use crate::prelude::*;
+
+    #[test]
+    fn test_vfs_install()
+    {
+        test_install(assert_vfs_setup!(Vfs::memfs()));
+        test_install(assert_vfs_setup!(Vfs::stdfs()));
+    }
+    fn test_install((vfs, tmpdir): (Vfs, PathBuf))
+    {
+        let src = tmpdir.mash("build/bin/app");
+        let link = tmpdir.mash("link");
+        let dir = tmpdir.mash("usr");
+        let dst1 = dir.mash("app");
+        let dst2 = dir.mash("bin/app");
+        let dst3 = tmpdir.mash("opt/app");
+        assert_vfs_mkdir_p!(vfs, src.dir().unwrap());
+        assert!(vfs.mkfile_m(&src, 0o600).is_ok());
+        assert!(vfs.write_all(&src, "app").is_ok());
+        assert_vfs_mkdir_p!(vfs, &dir);
+
+        // install into an existing directory with the default mode
+        assert_eq!(vfs.install(&src, &dir).unwrap().exec().unwrap(), dst1);
+        assert_vfs_read_all!(vfs, &dst1, "app");
+        assert_eq!(vfs.mode(&dst1).unwrap(), 0o100755);
+
+        // install as a new file creating parents
+        assert_eq!(vfs.install(&src, &dst3).unwrap().mode(0o640).exec().unwrap(), dst3);
+        assert_vfs_read_all!(vfs, &dst3, "app");
+        assert_eq!(vfs.mode(&dst3).unwrap(), 0o100640);
+
+        // strip leading components
+        let strip = tmpdir.components().filter(|x| matches!(x, std::path::Component::Normal(_))).count();
+        assert_eq!(vfs.install(&src, &dir).unwrap().strip_dirs(strip + 1).exec().unwrap(), dst2);
+        assert_vfs_read_all!(vfs, &dst2, "app");
+        assert_eq!(vfs.install(&src, &dir).unwrap().strip_dirs(100).exec().unwrap(), dst1);
+
+        // backup the existing destination
+        assert!(vfs.write_all(&src, "app2").is_ok());
+        assert!(vfs.install(&src, &dir).unwrap().backup(true).exec().is_ok());
+        assert_vfs_read_all!(vfs, &dst1, "app2");
+        assert_vfs_read_all!(vfs, dir.mash("app.bak"), "app");
+        assert!(vfs.write_all(&src, "app3").is_ok());
+        assert!(vfs.install(&src, &dir).unwrap().backup(true).exec().is_ok());
+        assert_vfs_read_all!(vfs, &dst1, "app3");
+        assert_vfs_read_all!(vfs, dir.mash("app.bak"), "app2");
+
+        // links are followed for the source and replaced for the destination
+        assert_vfs_symlink!(vfs, &link, &dst3);
+        assert!(vfs.install(&src, &link).unwrap().exec().is_ok());
+        assert_vfs_no_symlink!(vfs, &link);
+        assert_vfs_read_all!(vfs, &link, "app3");
+        assert_vfs_read_all!(vfs, &dst3, "app");
+        assert!(vfs.install(&link, &dst3).unwrap().exec().is_ok());
+        assert_vfs_no_symlink!(vfs, &dst3);
+        assert_vfs_read_all!(vfs, &dst3, "app3");
+
+        // owner by name
+        if let Vfs::Memfs(_) = vfs {
+            assert!(vfs.install(&src, &dir).unwrap().owner("root", "root").exec().is_ok());
+            assert_eq!(vfs.owner(&dst1).unwrap(), (0, 0));
+        }
+        assert_eq!(
+            vfs.install(&src, &dir)
+                .unwrap()
+                .owner("rivia-foo", "root")
+                .exec()
+                .unwrap_err()
+                .downcast_ref::<UserError>(),
+            Some(&UserError::does_not_exist_by_name("rivia-foo"))
+        );
+
+        // errors
+        assert_eq!(
+            vfs.install(tmpdir.mash("foo"), &dir).unwrap().exec().unwrap_err().downcast_ref::<PathError>(),
+            Some(&PathError::does_not_exist(tmpdir.mash("foo")))
+        );
+        assert_eq!(
+            vfs.install(&dir, &tmpdir).unwrap().exec().unwrap_err().downcast_ref::<PathError>(),
+            Some(&PathError::is_not_file(&dir))
+        );
+        assert_vfs_mkdir_p!(vfs, tmpdir.mash("dir/app"));
+        assert_eq!(
+            vfs.install(&src, tmpdir.mash("dir"))
+                .unwrap()
+                .strip_dirs(strip + 2)
+                .exec()
+                .unwrap_err()
+                .downcast_ref::<PathError>(),
+            Some(&PathError::is_not_file(tmpdir.mash("dir/app")))
+        );
+
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+
+    #[test]
+    fn test_vfs_install_atomic()
+    {
+        test_install_atomic(assert_vfs_setup!(Vfs::memfs()));
+        test_install_atomic(assert_vfs_setup!(Vfs::stdfs()));
+    }
+    fn test_install_atomic((vfs, tmpdir): (Vfs, PathBuf))
+    {
+        use std::sync::atomic::{AtomicBool, Ordering};
+
+        let src = tmpdir.mash("src/app");
+        let dir = tmpdir.mash("bin");
+        let dst = dir.mash("app");
+        let hard = dir.mash("hard");
+        assert_vfs_mkdir_p!(vfs, src.dir().unwrap());
+        assert_vfs_write_all!(vfs, &src, "app2");
+        assert_vfs_mkdir_p!(vfs, &dir);
+        assert_vfs_write_all!(vfs, &dst, "app1");
+        assert!(vfs.chmod(&dst, 0o644).is_ok());
+        assert_vfs_hardlink!(vfs, &dst, &hard);
+
+        // failing to set the mode leaves the destination untouched and no temp file behind
+        if let Vfs::Memfs(_) = vfs {
+            let fail = Arc::new(AtomicBool::new(true));
+            let failing = fail.clone();
+            vfs.hook_pre(move |op, _| match op == VfsOp::Chmod && failing.load(Ordering::SeqCst) {
+                true => Err(VfsError::Unavailable.into()),
+                false => Ok(()),
+            });
+            assert!(vfs.install(&src, &dst).unwrap().mode(0o600).exec().is_err());
+            assert_vfs_read_all!(vfs, &dst, "app1");
+            assert_eq!(vfs.mode(&dst).unwrap(), 0o100644);
+            assert_eq!(vfs.paths(&dir).unwrap(), vec![dst.clone(), hard.clone()]);
+            fail.store(false, Ordering::SeqCst);
+        }
+
+        // the new file is renamed into place leaving hard links with the old data
+        assert!(vfs.install(&src, &dst).unwrap().mode(0o600).exec().is_ok());
+        assert_vfs_read_all!(vfs, &dst, "app2");
+        assert_eq!(vfs.mode(&dst).unwrap(), 0o100600);
+        assert_vfs_read_all!(vfs, &hard, "app1");
+        assert_eq!(vfs.paths(&dir).unwrap(), vec![dst, hard]);
+
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+
+    #[test]
+    fn test_vfs_install_compare()
+    {
+        test_install_compare(assert_vfs_setup!(Vfs::memfs()));
+        test_install_compare(assert_vfs_setup!(Vfs::stdfs()));
+    }
+    fn test_install_compare((vfs, tmpdir): (Vfs, PathBuf))
+    {
+        let src = tmpdir.mash("app.conf");
+        let dst = tmpdir.mash("etc/app.conf");
+        let changed = std::sync::Arc::new(std::sync::Mutex::new(0));
+        let install = |port: &str| {
+            let changed = changed.clone();
+            vfs.install(&src, &dst)
+                .unwrap()
+                .mode(0o644)
+                .compare(true)
+                .template(&[("name", "app"), ("port", port)])
+                .notify(move |_| *changed.lock().unwrap() += 1)
+                .exec()
+        };
+        assert_vfs_write_all!(vfs, &src, "name={{ name }}\nport={{port}}\n");
+
+        // rendered and notified
+        assert_eq!(install("80").unwrap(), dst);
+        assert_vfs_read_all!(vfs, &dst, "name=app\nport=80\n");
+        assert_eq!(*changed.lock().unwrap(), 1);
+
+        // no change no notification
+        assert_eq!(install("80").unwrap(), dst);
+        assert_eq!(*changed.lock().unwrap(), 1);
+
+        // content change
+        assert_eq!(install("8080").unwrap(), dst);
+        assert_vfs_read_all!(vfs, &dst, "name=app\nport=8080\n");
+        assert_eq!(*changed.lock().unwrap(), 2);
+
+        // mode change only
+        assert!(vfs.chmod(&dst, 0o600).is_ok());
+        assert_eq!(install("8080").unwrap(), dst);
+        assert_eq!(vfs.mode(&dst).unwrap(), 0o100644);
+        assert_eq!(*changed.lock().unwrap(), 3);
+
+        // without compare the file is always replaced
+        let notified = changed.clone();
+        let installer = vfs.install(&src, &dst).unwrap().template(&[("name", "app"), ("port", "8080")]);
+        assert!(installer.notify(move |_| *notified.lock().unwrap() += 1).exec().is_ok());
+        assert_eq!(*changed.lock().unwrap(), 4);
+
+        // undefined variables are errors and nothing changes
+        let installer = vfs.install(&src, &dst).unwrap().template(&[("name", "app")]);
+        assert_eq!(
+            installer.exec().unwrap_err().downcast_ref::<StringError>(),
+            Some(&StringError::undefined_variable("port"))
+        );
+        assert_vfs_read_all!(vfs, &dst, "name=app\nport=8080\n");
+
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+}
@@ -0,0 +1,165 @@
+use std::{
+    io::{BufRead, BufReader, Read, Seek, SeekFrom},
+    marker::PhantomData,
+    path::Path,
+};
+
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{
+    errors::*,
+    sys::{ReadSeek, VirtualFileSystem},
+};
+
+/// Provides a streaming iterator over the records of a JSONL file deserialized into the given type
+///
+/// Use the Vfs function `jsonl_iter` to create a new instance. Blank lines are skipped and a
+/// partial trailing line left behind by a torn write ends the iteration rather than failing it.
+///
+/// ### Examples
+/// ```
+/// use rivia::prelude::*;
+///
+/// let vfs = Vfs::memfs();
+/// let file = vfs.root().mash("file");
+/// assert_vfs_write_all!(vfs, &file, "[\"foo\",1]\n[\"bar\",2]\n[\"baz\",");
+/// let mut iter = vfs.jsonl_iter::<(String, u32), _>(&file).unwrap();
+/// assert_eq!(iter.next().unwrap().unwrap(), ("foo".to_string(), 1));
+/// assert_eq!(iter.next().unwrap().unwrap(), ("bar".to_string(), 2));
+/// assert!(iter.next().is_none());
+/// ```
+pub struct JsonlIter<T>
+{
+    reader: BufReader<Box<dyn ReadSeek>>, // vfs read handle
+    line: Vec<u8>,                        // reusable line buffer
+    _record: PhantomData<T>,              // type each record is deserialized into
+}
+
+impl<T: DeserializeOwned> JsonlIter<T>
+{
+    /// Create a new iterator over the records of the given reader
+    pub(crate) fn new(reader: Box<dyn ReadSeek>) -> Self
+    {
+        Self { reader: BufReader::new(reader), line: vec![], _record: PhantomData }
+    }
+}
+
+impl<T: DeserializeOwned> Iterator for JsonlIter<T>
+{
+    type Item = RvResult<T>;
+
+    fn next(&mut self) -> Option<RvResult<T>>
+    {
+        loop {
+            self.line.clear();
+            match self.reader.read_until(b'\n', &mut self.line) {
+                Ok(0) => return None,
+                Ok(_) => {},
+                Err(e) => return Some(Err(e.into())),
+            }
+            if self.line.iter().all(u8::is_ascii_whitespace) {
+                continue;
+            }
+            let complete = self.line.ends_with(b"\n");
+            return match serde_json::from_slice(&self.line) {
+                Ok(x) => Some(Ok(x)),
+
+                // A partial trailing line is the remains of a torn write
+                Err(e) if !complete && e.is_eof() => None,
+                Err(e) => Some(Err(e.into())),
+            };
+        }
+    }
+}
+
+/// Returns the given value serialized as a JSONL record ready to be appended to the given file
+///
+/// * Prefixes a newline when the file ends in a partial line left behind by a torn write so that
+///   the new record starts on a line of its own
+pub(crate) fn jsonl_record<T: VirtualFileSystem + ?Sized, U: Serialize>(vfs: &T, path: &Path, value: &U)
+    -> RvResult<Vec<u8>>
+{
+    let mut data = serde_json::to_vec(value)?;
+    data.push(b'\n');
+    if vfs.is_file(path) {
+        let mut file = vfs.read(path)?;
+        if file.seek(SeekFrom::End(0))? > 0 {
+            let mut last = [0u8; 1];
+            file.seek(SeekFrom::End(-1))?;
+            file.read_exact(&mut last)?;
+            if last[0] != b'\n' {
+                data.insert(0, b'\n');
+            }
+        }
+    }
+    Ok(data)
+}
+
+// Unit tests
+// -------------------------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests
+{
+    use serde::{Deserialize, Serialize};
+
+    use crate::prelude::*;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Event
+    {
+        id: u32,
+        msg: String,
+    }
+
+    #[test]
+    fn test_vfs_jsonl()
+    {
+        test_jsonl(assert_vfs_setup!(Vfs::memfs()));
+        test_jsonl(assert_vfs_setup!(Vfs::stdfs()));
+    }
+    fn test_jsonl((vfs, tmpdir): (Vfs, PathBuf))
+    {
+        let dir1 = tmpdir.mash("dir1");
+        let file1 = tmpdir.mash("file1");
+        let event = |id: u32, msg: &str| Event { id, msg: msg.into() };
+
+        // Errors
+        assert_eq!(
+            vfs.jsonl_iter::<Event, _>(&file1).err().unwrap().downcast_ref::<PathError>(),
+            Some(&PathError::does_not_exist(&file1))
+        );
+        assert_vfs_mkdir_p!(vfs, &dir1);
+        assert_eq!(
+            vfs.jsonl_append(&dir1, &event(1, "foo")).unwrap_err().downcast_ref::<PathError>(),
+            Some(&PathError::is_not_file(&dir1))
+        );
+
+        // Append creates the file with one record per line
+        assert!(vfs.jsonl_append(&file1, &event(1, "foo")).is_ok());
+        assert!(vfs.jsonl_append(&file1, &event(2, "bar\nbaz")).is_ok());
+        assert_vfs_read_all!(vfs, &file1, "{\"id\":1,\"msg\":\"foo\"}\n{\"id\":2,\"msg\":\"bar\\nbaz\"}\n");
+        let events = vfs.jsonl_iter::<Event, _>(&file1).unwrap().collect::<RvResult<Vec<_>>>().unwrap();
+        assert_eq!(events, vec![event(1, "foo"), event(2, "bar\nbaz")]);
+
+        // Torn trailing write is skipped and the next append starts on a new line
+        assert!(vfs.append_all(&file1, "{\"id\":3,\"ms").is_ok());
+        let events = vfs.jsonl_iter::<Event, _>(&file1).unwrap().collect::<RvResult<Vec<_>>>().unwrap();
+        assert_eq!(events, vec![event(1, "foo"), event(2, "bar\nbaz")]);
+        assert!(vfs.jsonl_append(&file1, &event(4, "qux")).is_ok());
+
+        // Torn write in the middle is surfaced without ending the iteration
+        let mut iter = vfs.jsonl_iter::<Event, _>(&file1).unwrap();
+        assert_eq!(iter.next().unwrap().unwrap(), event(1, "foo"));
+        assert_eq!(iter.next().unwrap().unwrap(), event(2, "bar\nbaz"));
+        assert!(iter.next().unwrap().unwrap_err().is::<serde_json::Error>());
+        assert_eq!(iter.next().unwrap().unwrap(), event(4, "qux"));
+        assert!(iter.next().is_none());
+
+        // Blank lines and a complete trailing line without a newline
+        assert!(vfs.write_all(&file1, "\n{\"id\":1,\"msg\":\"foo\"}\n\n{\"id\":2,\"msg\":\"bar\"}").is_ok());
+        let events = vfs.jsonl_iter::<Event, _>(&file1).unwrap().collect::<RvResult<Vec<_>>>().unwrap();
+        assert_eq!(events, vec![event(1, "foo"), event(2, "bar")]);
+
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+}
@@ -0,0 +1,243 @@
+use std::path::{Component, Path, PathBuf};
+
+use crate::{
+    errors::*,
+    sys::{self, Entry, VirtualFileSystem},
+};
+
+/// Describes the resolution of a chain of links as returned by `resolve_link`
+///
+/// Unlike canonicalization the chain is still reported when it dangles in which case `target` is
+/// the first path in the chain that doesn't exist and the last link in `chain` is the last point
+/// that does.
+///
+/// ### Examples
+/// ```
+/// use rivia::prelude::*;
+///
+/// let vfs = Vfs::memfs();
+/// let link1 = vfs.root().mash("link1");
+/// let link2 = vfs.root().mash("link2");
+/// assert_vfs_symlink!(vfs, &link1, &link2);
+/// assert!(vfs.symlink(&link2, "missing").is_ok());
+/// let resolved = vfs.resolve_link(&link1, 40).unwrap();
+/// assert_eq!(resolved.chain, vec![link1, link2]);
+/// assert_eq!(resolved.target, vfs.root().mash("missing"));
+/// assert_eq!(resolved.exists, false);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ResolvedLink
+{
+    pub chain: Vec<PathBuf>, // absolute paths of the links traversed in order starting with the given path
+    pub target: PathBuf,     // absolute path of the final non-link target or the first missing path
+    pub exists: bool,        // true if the target exists i.e. the chain doesn't dangle
+}
+
+// Returns the target stored in the link at the given path or None when it isn't a link
+//
+// * Reads the link directly as not every backend reports a dangling link as a link
+pub(crate) fn link_target<T>(vfs: &T, path: &Path) -> RvResult<Option<PathBuf>>
+where
+    T: VirtualFileSystem + ?Sized,
+{
+    Ok(vfs.readlink(path).ok())
+}
+
+// Resolve the links along the given path's parent chain and optionally at the path itself
+//
+// * `readlink` returns the target stored in the link at a path or None when it isn't a link
+// * Link targets are resolved in turn so a `..` after a link climbs from where the link leads
+// * Unlike canonicalization the path and the links along the way needn't exist
+pub(crate) fn real_path<F>(path: &Path, follow: bool, readlink: F) -> RvResult<PathBuf>
+where
+    F: Fn(&Path) -> RvResult<Option<PathBuf>>,
+{
+    let mut hops = 0;
+    _real_path(path, path, follow, &readlink, &mut hops)
+}
+
+// Resolve the given path counting the links followed so far while resolving `origin`
+fn _real_path(
+    origin: &Path, path: &Path, follow: bool, readlink: &dyn Fn(&Path) -> RvResult<Option<PathBuf>>,
+    hops: &mut usize,
+) -> RvResult<PathBuf>
+{
+    let mut real = PathBuf::new();
+    let mut components = path.components().peekable();
+    while let Some(component) = components.next() {
+        match component {
+            Component::CurDir => continue,
+            Component::ParentDir => {
+                real.pop();
+                continue;
+            },
+            _ => real.push(component),
+        }
+        if follow || components.peek().is_some() {
+            if let Some(target) = readlink(&real)? {
+                *hops += 1;
+                if *hops > sys::DEFAULT_MAX_LINKS {
+                    return Err(PathError::too_many_links(origin).into());
+                }
+                real.pop();
+                real = _real_path(origin, &real.join(target), true, readlink, hops)?;
+            }
+        }
+    }
+    Ok(real)
+}
+
+/// Rewrites every link in the given tree as the minimal relative path to the same target
+///
+/// * Handles path expansion and absolute path resolution
+/// * Links are not followed i.e. only links physically inside the tree are rewritten
+/// * Returns the links whose targets were changed sorted by name
+///
+/// ### Examples
+/// ```
+/// use rivia::prelude::*;
+///
+/// let vfs = Vfs::memfs();
+/// let dir = vfs.root().mash("dir");
+/// let file = vfs.root().mash("file");
+/// let link = dir.mash("link");
+/// assert_vfs_mkdir_p!(vfs, &dir);
+/// assert_vfs_mkfile!(vfs, &file);
+/// assert_vfs_symlink!(vfs, &link, &file);
+/// assert!(sys::normalize_links(&vfs, vfs.root()).is_ok());
+/// assert_vfs_readlink!(vfs, &link, PathBuf::from("../file"));
+/// ```
+pub fn normalize_links<T: VirtualFileSystem, U: AsRef<Path>>(vfs: &T, root: U) -> RvResult<Vec<PathBuf>>
+{
+    // Collect the links up front so the tree isn't modified while being traversed
+    let mut links = vec![];
+    for entry in vfs.entries(root)?.sort_by_name() {
+        let entry = entry?;
+        if entry.is_symlink() {
+            links.push(entry.path_buf());
+        }
+    }
+
+    let mut changed = vec![];
+    for link in links {
+        let target = vfs.readlink(&link)?;
+        vfs.relink(&link)?;
+        if vfs.readlink(&link)? != target {
+            changed.push(link);
+        }
+    }
+    Ok(changed)
+}
+
+// Unit tests
+// -------------------------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests
+{
+    use crate::prelude::*;
+
+    #[test]
+    fn test_vfs_resolve_link()
+    {
+        test_resolve_link(assert_vfs_setup!(Vfs::memfs()));
+        test_resolve_link(assert_vfs_setup!(Vfs::stdfs()));
+    }
+    fn test_resolve_link((vfs, tmpdir): (Vfs, PathBuf))
+    {
+        let dir1 = tmpdir.mash("dir1");
+        let file1 = dir1.mash("file1");
+        let link1 = tmpdir.mash("link1");
+        let link2 = dir1.mash("link2");
+        let link3 = tmpdir.mash("link3");
+        let link4 = tmpdir.mash("link4");
+        let link5 = tmpdir.mash("link5");
+        assert_vfs_mkdir_p!(vfs, &dir1);
+        assert_vfs_mkfile!(vfs, &file1);
+        assert!(vfs.symlink(&link1, &link2).is_ok());
+        assert!(vfs.symlink(&link2, "file1").is_ok());
+
+        // Non-links resolve to themselves
+        let resolved = vfs.resolve_link(&file1, 40).unwrap();
+        assert_eq!(resolved, ResolvedLink { chain: vec![], target: file1.clone(), exists: true });
+        let resolved = vfs.resolve_link(tmpdir.mash("missing"), 40).unwrap();
+        assert_eq!(resolved, ResolvedLink { chain: vec![], target: tmpdir.mash("missing"), exists: false });
+
+        // Chains with relative and absolute hops
+        let resolved = vfs.resolve_link(&link1, 40).unwrap();
+        assert_eq!(resolved.chain, vec![link1.clone(), link2.clone()]);
+        assert_eq!(resolved.target, file1);
+        assert!(resolved.exists);
+        assert_eq!(resolved, vfs.resolve_link(&link1, 2).unwrap());
+        assert_eq!(
+            vfs.resolve_link(&link1, 1).unwrap_err().downcast_ref::<PathError>(),
+            Some(&PathError::too_many_links(&link1))
+        );
+
+        // Dangling chains report the last existing link and the missing remainder
+        assert!(vfs.symlink(&link3, &link1).is_ok());
+        assert!(vfs.remove(&file1).is_ok());
+        let resolved = vfs.resolve_link(&link3, 40).unwrap();
+        assert_eq!(resolved.chain, vec![link3.clone(), link1.clone(), link2.clone()]);
+        assert_eq!(resolved.target, file1);
+        assert!(!resolved.exists);
+
+        // Loops are detected before the hop limit
+        assert!(vfs.symlink(&link4, &link5).is_ok());
+        assert!(vfs.symlink(&link5, &link4).is_ok());
+        assert_eq!(
+            vfs.resolve_link(&link4, 40).unwrap_err().downcast_ref::<PathError>(),
+            Some(&PathError::link_looping(&link4))
+        );
+
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+
+    #[test]
+    fn test_vfs_normalize_links()
+    {
+        test_normalize_links(assert_vfs_setup!(Vfs::memfs()));
+        test_normalize_links(assert_vfs_setup!(Vfs::stdfs()));
+    }
+    fn test_normalize_links((vfs, tmpdir): (Vfs, PathBuf))
+    {
+        let dir1 = tmpdir.mash("dir1");
+        let dir2 = dir1.mash("dir2");
+        let file1 = tmpdir.mash("file1");
+        let file2 = dir2.mash("file2");
+        let link1 = dir2.mash("link1");
+        let link2 = tmpdir.mash("link2");
+        assert_vfs_mkdir_p!(vfs, &dir2);
+        assert_vfs_mkfile!(vfs, &file1);
+        assert_vfs_mkfile!(vfs, &file2);
+        assert_vfs_symlink!(vfs, &link1, &file1);
+        assert_vfs_symlink!(vfs, &link2, &file2);
+
+        // relink errors
+        assert_eq!(
+            vfs.relink(&file1).unwrap_err().downcast_ref::<PathError>(),
+            Some(&PathError::is_not_symlink(&file1))
+        );
+        assert_eq!(
+            vfs.relink(tmpdir.mash("foo")).unwrap_err().downcast_ref::<PathError>(),
+            Some(&PathError::does_not_exist(tmpdir.mash("foo")))
+        );
+
+        // absolute links are only possible on the real filesystem
+        if let Vfs::Stdfs(_) = vfs {
+            assert!(vfs.remove(&link1).is_ok());
+            assert!(std::os::unix::fs::symlink(&file1, &link1).is_ok());
+            assert_vfs_readlink!(vfs, &link1, &file1);
+            assert_eq!(sys::normalize_links(&vfs, &tmpdir).unwrap(), vec![link1.clone()]);
+        } else {
+            assert_eq!(sys::normalize_links(&vfs, &tmpdir).unwrap(), Vec::<PathBuf>::new());
+        }
+        assert_vfs_readlink!(vfs, &link1, PathBuf::from("../../file1"));
+        assert_vfs_readlink!(vfs, &link2, PathBuf::from("dir1/dir2/file2"));
+        assert_vfs_readlink_abs!(vfs, &link1, &file1);
+
+        // already normalized
+        assert_eq!(sys::normalize_links(&vfs, &tmpdir).unwrap(), Vec::<PathBuf>::new());
+
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+}
@@ -0,0 +1,434 @@
+use std::path::{Path, PathBuf};
+
+use crate::{
+    errors::*,
+    sys::{Entry, PathExt, VirtualFileSystem},
+};
+
+/// Provides a declarative description of the desired state of a tree for use with `sys::apply`
+///
+/// Directories, files and links are declared along with their modes and owners then converged in
+/// the order they were declared. Only the paths that differ from their declared state are changed.
+/// Manifests are built in code or with the `serde` feature loaded from any serde format as a list
+/// of items tagged by `kind` with modes given as octal strings and file data as text.
+///
+/// ```json
+/// [
+///   { "kind": "dir", "path": "/etc/app", "mode": "755", "uid": 0, "gid": 0 },
+///   { "kind": "file", "path": "/etc/app/config", "data": "foo", "mode": "644" },
+///   { "kind": "link", "path": "/etc/app/current", "target": "config" }
+/// ]
+/// ```
+///
+/// ### Examples
+/// ```
+/// use rivia::prelude::*;
+///
+/// let vfs = Vfs::memfs();
+/// let manifest = Manifest::new().dir("/etc/app", 0o755, 0, 0).file("/etc/app/config", "foo", 0o644);
+/// assert_eq!(sys::apply(&vfs, &manifest).unwrap().len(), 2);
+/// assert_eq!(sys::apply(&vfs, &manifest).unwrap().len(), 0);
+/// assert_vfs_read_all!(vfs, "/etc/app/config", "foo");
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(transparent))]
+pub struct Manifest
+{
+    #[cfg_attr(feature = "serde", serde(skip))]
+    dry_run: bool,            // report changes without making them
+    items: Vec<ManifestItem>, // declared items in the order they were added
+}
+
+// Internal type used to track a single declared item
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "kind", rename_all = "lowercase", deny_unknown_fields)
+)]
+enum ManifestItem
+{
+    Dir
+    {
+        path: PathBuf,
+        #[cfg_attr(feature = "serde", serde(with = "octal"))]
+        mode: u32,
+        uid: u32,
+        gid: u32,
+    },
+    File
+    {
+        path: PathBuf,
+        #[cfg_attr(feature = "serde", serde(with = "data"))]
+        data: Vec<u8>,
+        #[cfg_attr(feature = "serde", serde(with = "octal"))]
+        mode: u32,
+    },
+    Link
+    {
+        path: PathBuf,
+        target: PathBuf,
+    },
+}
+
+impl Manifest
+{
+    /// Create a new empty manifest
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// assert_eq!(sys::apply(&vfs, &Manifest::new()).unwrap(), Vec::<PathBuf>::new());
+    /// ```
+    pub fn new() -> Self
+    {
+        Self::default()
+    }
+
+    /// Declare a directory with the given mode and ownership
+    ///
+    /// * Parent directories are created as needed
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// assert!(sys::apply(&vfs, &Manifest::new().dir("/srv", 0o700, 5, 5)).is_ok());
+    /// assert_eq!(vfs.mode("/srv").unwrap(), 0o40700);
+    /// assert_eq!(vfs.owner("/srv").unwrap(), (5, 5));
+    /// ```
+    pub fn dir<T: AsRef<Path>>(mut self, path: T, mode: u32, uid: u32, gid: u32) -> Self
+    {
+        self.items.push(ManifestItem::Dir { path: path.as_ref().to_path_buf(), mode, uid, gid });
+        self
+    }
+
+    /// Declare a file with the given data and mode
+    ///
+    /// * The file's parent directory must exist or be declared first
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// assert!(sys::apply(&vfs, &Manifest::new().file("/file", "foo", 0o600)).is_ok());
+    /// assert_vfs_read_all!(vfs, "/file", "foo");
+    /// assert_eq!(vfs.mode("/file").unwrap(), 0o100600);
+    /// ```
+    pub fn file<T: AsRef<Path>, U: AsRef<[u8]>>(mut self, path: T, data: U, mode: u32) -> Self
+    {
+        let (path, data) = (path.as_ref().to_path_buf(), data.as_ref().to_vec());
+        self.items.push(ManifestItem::File { path, data, mode });
+        self
+    }
+
+    /// Declare a link pointing to the given target
+    ///
+    /// * Relative targets are relative to the link's directory as with the Vfs `symlink`
+    /// * Links pointing elsewhere are replaced
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// assert_vfs_mkfile!(vfs, "/file");
+    /// assert!(sys::apply(&vfs, &Manifest::new().link("/link", "file")).is_ok());
+    /// assert_vfs_readlink_abs!(vfs, "/link", "/file");
+    /// ```
+    pub fn link<T: AsRef<Path>, U: AsRef<Path>>(mut self, link: T, target: U) -> Self
+    {
+        let (path, target) = (link.as_ref().to_path_buf(), target.as_ref().to_path_buf());
+        self.items.push(ManifestItem::Link { path, target });
+        self
+    }
+
+    /// Report the paths that would be changed without changing anything
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let manifest = Manifest::new().dir("/srv", 0o755, 0, 0).dry_run();
+    /// assert_eq!(sys::apply(&vfs, &manifest).unwrap(), vec![PathBuf::from("/srv")]);
+    /// assert_vfs_no_exists!(vfs, "/srv");
+    /// ```
+    pub fn dry_run(mut self) -> Self
+    {
+        self.dry_run = true;
+        self
+    }
+}
+
+/// Converge the filesystem to the state declared by the given manifest
+///
+/// * Handles path expansion and absolute path resolution
+/// * Items are converged in the order they were declared
+/// * Links are followed when checking and changing directories and files
+/// * Returns the absolute paths that were changed, or would be changed for a dry run
+///
+/// ### Errors
+/// * PathError::IsNotDir(PathBuf) when a declared directory exists but is not a directory
+/// * PathError::IsNotFile(PathBuf) when a declared file exists but is not a file
+/// * PathError::IsNotSymlink(PathBuf) when a declared link exists but is not a link
+///
+/// ### Examples
+/// ```
+/// use rivia::prelude::*;
+///
+/// let vfs = Vfs::memfs();
+/// let manifest = Manifest::new().dir("/srv", 0o755, 0, 0).file("/srv/file", "foo", 0o644);
+/// assert_eq!(sys::apply(&vfs, &manifest).unwrap(), vec![PathBuf::from("/srv"), PathBuf::from("/srv/file")]);
+/// assert_eq!(sys::apply(&vfs, &manifest).unwrap(), Vec::<PathBuf>::new());
+/// ```
+pub fn apply<T: VirtualFileSystem>(vfs: &T, manifest: &Manifest) -> RvResult<Vec<PathBuf>>
+{
+    let mut changed = vec![];
+    for item in manifest.items.iter() {
+        let (path, differs) = match item {
+            ManifestItem::Dir { path, mode, uid, gid } => {
+                let path = vfs.abs(path)?;
+                let differs = match manifest.dry_run {
+                    true if !vfs.exists(&path) => true,
+                    true if !vfs.is_dir(&path) => return Err(PathError::is_not_dir(&path).into()),
+                    true => attrs_differ(vfs, &path, *mode, Some((*uid, *gid)))?,
+                    false => vfs.ensure_dir(&path, *mode, *uid, *gid)?,
+                };
+                (path, differs)
+            },
+            ManifestItem::File { path, data, mode } => {
+                let path = vfs.abs(path)?;
+                let differs = match manifest.dry_run {
+                    true if !vfs.exists(&path) => true,
+                    true if !vfs.is_file(&path) => return Err(PathError::is_not_file(&path).into()),
+                    true => {
+                        let mut current = vec![];
+                        vfs.read(&path)?.read_to_end(&mut current)?;
+                        current != *data || attrs_differ(vfs, &path, *mode, None)?
+                    },
+                    false => vfs.ensure_file(&path, data, *mode)?,
+                };
+                (path, differs)
+            },
+            ManifestItem::Link { path: link, target } => {
+                let link = vfs.abs(link)?;
+                let abs = vfs.abs(if target.is_absolute() { target.clone() } else { link.dir()?.mash(target) })?;
+                let current = match vfs.is_symlink(&link) {
+                    true => Some(vfs.readlink_abs(&link)?),
+                    false if vfs.exists(&link) => return Err(PathError::is_not_symlink(&link).into()),
+                    false => None,
+                };
+                let differs = current.as_ref() != Some(&abs);
+                if differs && !manifest.dry_run {
+                    if current.is_some() {
+                        vfs.remove(&link)?;
+                    }
+                    vfs.symlink(&link, &abs)?;
+                }
+                (link, differs)
+            },
+        };
+        if differs {
+            changed.push(path);
+        }
+    }
+    Ok(changed)
+}
+
+// Returns true if the mode or ownership of the given existing path differ from those given
+fn attrs_differ<T: VirtualFileSystem>(vfs: &T, path: &Path, mode: u32, owner: Option<(u32, u32)>) -> RvResult<bool>
+{
+    // Check the final target of links as the ensure functions do
+    let entry = vfs.entry(path)?;
+    let target = entry.target_chain().last().map_or(entry.path(), |x| x.as_path());
+    if vfs.mode(target)? & 0o7777 != mode & 0o7777 {
+        return Ok(true);
+    }
+    Ok(match owner {
+        Some(owner) => vfs.owner(target)? != owner,
+        None => false,
+    })
+}
+
+// Serialize modes as octal strings e.g. "755" as JSON and most other formats lack octal literals
+#[cfg(feature = "serde")]
+mod octal
+{
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub(super) fn serialize<S: Serializer>(mode: &u32, serializer: S) -> Result<S::Ok, S::Error>
+    {
+        serializer.serialize_str(&format!("{:o}", mode))
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u32, D::Error>
+    {
+        let mode = String::deserialize(deserializer)?;
+        u32::from_str_radix(mode.trim_start_matches("0o"), 8)
+            .map_err(|_| D::Error::custom(format!("invalid octal mode: {}", mode)))
+    }
+}
+
+// Serialize file data as text when it's valid UTF-8 falling back on an array of bytes otherwise
+#[cfg(feature = "serde")]
+mod data
+{
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Data
+    {
+        Text(String),
+        Bytes(Vec<u8>),
+    }
+
+    pub(super) fn serialize<S: Serializer>(data: &[u8], serializer: S) -> Result<S::Ok, S::Error>
+    {
+        match std::str::from_utf8(data) {
+            Ok(text) => serializer.serialize_str(text),
+            Err(_) => serializer.collect_seq(data),
+        }
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error>
+    {
+        Ok(match Data::deserialize(deserializer)? {
+            Data::Text(text) => text.into_bytes(),
+            Data::Bytes(bytes) => bytes,
+        })
+    }
+}
+
+// Unit tests
+// -------------------------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests
+{
+    use crate::prelude::*;
+
+    #[test]
+    fn test_vfs_apply()
+    {
+        test_apply(assert_vfs_setup!(Vfs::memfs()));
+        test_apply(assert_vfs_setup!(Vfs::stdfs()));
+    }
+    fn test_apply((vfs, tmpdir): (Vfs, PathBuf))
+    {
+        let dir1 = tmpdir.mash("dir1");
+        let file1 = dir1.mash("file1");
+        let file2 = tmpdir.mash("file2");
+        let link1 = tmpdir.mash("link1");
+        let (uid, gid) = vfs.owner(&tmpdir).unwrap();
+
+        let manifest = Manifest::new()
+            .dir(&dir1, 0o750, uid, gid)
+            .file(&file1, "foo", 0o600)
+            .file(&file2, "bar", 0o644)
+            .link(&link1, "dir1/file1");
+
+        // dry run reports everything without changing anything
+        assert_eq!(sys::apply(&vfs, &manifest.clone().dry_run()).unwrap(), vec![
+            dir1.clone(),
+            file1.clone(),
+            file2.clone(),
+            link1.clone()
+        ]);
+        assert_vfs_no_exists!(vfs, &dir1);
+        assert_vfs_no_exists!(vfs, &link1);
+
+        // converge then nothing left to change
+        assert_eq!(sys::apply(&vfs, &manifest).unwrap(), vec![
+            dir1.clone(),
+            file1.clone(),
+            file2.clone(),
+            link1.clone()
+        ]);
+        assert_eq!(vfs.mode(&dir1).unwrap(), 0o40750);
+        assert_eq!(vfs.mode(&file1).unwrap(), 0o100600);
+        assert_vfs_read_all!(vfs, &file1, "foo");
+        assert_vfs_read_all!(vfs, &file2, "bar");
+        assert_vfs_readlink_abs!(vfs, &link1, &file1);
+        assert_eq!(sys::apply(&vfs, &manifest).unwrap(), Vec::<PathBuf>::new());
+        assert_eq!(sys::apply(&vfs, &manifest.clone().dry_run()).unwrap(), Vec::<PathBuf>::new());
+
+        // drift is detected and corrected
+        assert!(vfs.write_all(&file1, "foo1").is_ok());
+        assert!(vfs.chmod(&file2, 0o600).is_ok());
+        assert!(vfs.remove(&link1).is_ok());
+        assert_vfs_symlink!(vfs, &link1, &file2);
+        assert_eq!(sys::apply(&vfs, &manifest.clone().dry_run()).unwrap(), vec![
+            file1.clone(),
+            file2.clone(),
+            link1.clone()
+        ]);
+        assert_vfs_read_all!(vfs, &file1, "foo1");
+        assert_eq!(sys::apply(&vfs, &manifest).unwrap(), vec![file1.clone(), file2.clone(), link1.clone()]);
+        assert_vfs_read_all!(vfs, &file1, "foo");
+        assert_eq!(vfs.mode(&file2).unwrap(), 0o100644);
+        assert_vfs_readlink_abs!(vfs, &link1, &file1);
+
+        // mismatched types are errors
+        let manifest = Manifest::new().link(&file2, &file1);
+        assert_eq!(
+            sys::apply(&vfs, &manifest).unwrap_err().downcast_ref::<PathError>(),
+            Some(&PathError::is_not_symlink(&file2))
+        );
+        let manifest = Manifest::new().file(&dir1, "foo", 0o644).dry_run();
+        assert_eq!(
+            sys::apply(&vfs, &manifest).unwrap_err().downcast_ref::<PathError>(),
+            Some(&PathError::is_not_file(&dir1))
+        );
+        let manifest = Manifest::new().dir(&file1, 0o755, uid, gid).dry_run();
+        assert_eq!(
+            sys::apply(&vfs, &manifest).unwrap_err().downcast_ref::<PathError>(),
+            Some(&PathError::is_not_dir(&file1))
+        );
+
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+
+    #[test]
+    #[cfg(all(feature = "serde", feature = "json"))]
+    fn test_manifest_serde()
+    {
+        let manifest = Manifest::new()
+            .dir("/dir", 0o750, 5, 6)
+            .file("/dir/file1", "foo", 0o600)
+            .file("/dir/file2", [0xff, 0x00], 0o644)
+            .link("/link", "dir/file1");
+
+        // Round trip with readable modes and data
+        let json = serde_json::to_string(&manifest).unwrap();
+        assert_eq!(
+            json,
+            concat!(
+                r#"[{"kind":"dir","path":"/dir","mode":"750","uid":5,"gid":6},"#,
+                r#"{"kind":"file","path":"/dir/file1","data":"foo","mode":"600"},"#,
+                r#"{"kind":"file","path":"/dir/file2","data":[255,0],"mode":"644"},"#,
+                r#"{"kind":"link","path":"/link","target":"dir/file1"}]"#
+            )
+        );
+        assert_eq!(serde_json::from_str::<Manifest>(&json).unwrap(), manifest);
+
+        // Declared as a fixture
+        let json = r#"[
+            { "kind": "dir", "path": "/dir", "mode": "0o700", "uid": 0, "gid": 0 },
+            { "kind": "file", "path": "/dir/file", "data": "foobar", "mode": "644" }
+        ]"#;
+        let memfs = Memfs::from_manifest(&serde_json::from_str(json).unwrap()).unwrap();
+        assert_eq!(memfs.mode("/dir").unwrap(), 0o40700);
+        assert_vfs_read_all!(memfs, "/dir/file", "foobar");
+
+        // Invalid modes and unknown fields
+        let json = r#"[{ "kind": "file", "path": "/file", "data": "", "mode": "9" }]"#;
+        assert!(serde_json::from_str::<Manifest>(json).unwrap_err().to_string().contains("invalid octal mode: 9"));
+        let json = r#"[{ "kind": "link", "path": "/link", "target": "file", "mode": "644" }]"#;
+        assert!(serde_json::from_str::<Manifest>(json).is_err());
+    }
+}
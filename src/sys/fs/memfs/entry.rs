@@ -1,15 +1,27 @@
 use std::{
     collections::HashSet,
     path::{Path, PathBuf},
-    sync::Arc,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::SystemTime,
 };
 
 use super::MemfsEntries;
 use crate::{
     errors::*,
-    sys::{Entry, PathExt, VfsEntry},
+    sys::{self, Acl, Entry, Kind, PathExt, VfsEntry, VfsMetadata},
 };
 
+// Source of unique Memfs entry ids across all Memfs instances
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+// Returns a new unique Memfs entry id
+pub(crate) fn next_entry_id() -> u64 {
+    NEXT_ID.fetch_add(1, Ordering::Relaxed)
+}
+
 // MemfsEntryOpts implements the builder pattern to provide advanced options for creating
 // MemfsEntry instances
 #[derive(Debug)]
@@ -30,9 +42,12 @@ impl MemfsEntryOpts {
     pub(crate) fn build(self) -> MemfsEntry {
         // Default entry to be a directory if not specified
         let opts = if !self.dir && !self.file && !self.link { self.dir() } else { self };
+        let now = SystemTime::now();
 
         MemfsEntry {
+            meta: MemfsMeta { id: next_entry_id() },
             files: if opts.dir { Some(HashSet::new()) } else { None },
+            chain: if opts.link { vec![opts.alt.clone()] } else { vec![] },
             path: opts.path,
             alt: opts.alt,
             rel: opts.rel,
@@ -40,10 +55,17 @@ impl MemfsEntryOpts {
             file: opts.file,
             link: opts.link,
             mode: opts.mode,
+            size: 0,
+            nlink: if opts.dir { 2 } else { 1 },
+            modified: now,
+            created: now,
             gid: opts.gid,
             uid: opts.uid,
             follow: false,
             cached: false,
+            acl: None,
+            context: None,
+            immutable: false,
         }
     }
 
@@ -81,9 +103,9 @@ impl MemfsEntryOpts {
         let mode = mode.unwrap_or(if self.link {
             0o120777
         } else if self.file {
-            0o100644
+            sys::DEFAULT_FILE_MODE
         } else {
-            0o40755
+            sys::DEFAULT_DIR_MODE
         });
 
         // OR given mode with defaults for physical entries
@@ -100,6 +122,50 @@ impl MemfsEntryOpts {
     }
 }
 
+/// Provides the Memfs specific metadata of an entry via [`VfsEntry::raw`]
+///
+/// * The id is unique to the entry much like an inode number and is kept when the entry is moved
+/// * Describes the final target for links as with the entry's size and timestamps
+///
+/// ### Examples
+/// ```
+/// use rivia::prelude::*;
+///
+/// let vfs = Memfs::new();
+/// assert_vfs_mkfile!(vfs, "/file1");
+/// let id = vfs.entry("/file1").unwrap().raw::<MemfsMeta>().unwrap().id;
+/// assert!(vfs.move_p("/file1", "/file2").is_ok());
+/// assert_eq!(vfs.entry("/file2").unwrap().raw::<MemfsMeta>().unwrap().id, id);
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MemfsMeta {
+    pub id: u64, // unique id of the entry
+}
+
+/// Describes a single raw Memfs entry as reported by [`Memfs::dump`]
+///
+/// * Links are reported as `Kind::Symlink` with their absolute target rather than being followed
+///
+/// ### Examples
+/// ```
+/// use rivia::prelude::*;
+///
+/// let memfs = Memfs::new();
+/// assert_vfs_write_all!(memfs, "/file", "foobar");
+/// let info = memfs.dump().pop().unwrap();
+/// assert_eq!(info.path, PathBuf::from("/file"));
+/// assert_eq!(info.kind, Kind::File);
+/// assert_eq!(info.size, 6);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MemfsEntryInfo {
+    pub path: PathBuf,           // abs path of the entry
+    pub kind: Kind,              // dir, file or symlink
+    pub mode: u32,               // permission mode of the entry
+    pub size: u64,               // size of the file's data in bytes, zero for dirs and links
+    pub target: Option<PathBuf>, // abs path the link points to
+}
+
 /// Provides a Vfs backend [`Entry`] implementation for Memfs
 ///
 /// ### Example
@@ -114,18 +180,27 @@ impl MemfsEntryOpts {
 /// ```
 #[derive(Debug)]
 pub struct MemfsEntry {
+    pub(crate) meta: MemfsMeta,                // backend specific metadata
     pub(crate) path: PathBuf,                  // abs path
     pub(crate) alt: PathBuf,                   // abs path link is pointing to
     pub(crate) rel: PathBuf,                   // relative path link is pointing to
+    pub(crate) chain: Vec<PathBuf>,            // abs link targets followed to the final target
     pub(crate) dir: bool,                      // is this entry a dir
     pub(crate) file: bool,                     // is this entry a file
     pub(crate) link: bool,                     // is this entry a link
     pub(crate) mode: u32,                      // permission mode of the entry
+    pub(crate) size: u64,                      // size of the file in bytes
+    pub(crate) nlink: u64,                     // number of hard links to the entry
+    pub(crate) modified: SystemTime,           // time the entry was last modified
+    pub(crate) created: SystemTime,            // time the entry was created
     pub(crate) uid: u32,                       // user id of entry
     pub(crate) gid: u32,                       // group id of entry
     pub(crate) follow: bool,                   // tracks if the path and alt have been switched
     pub(crate) cached: bool,                   // tracks if properties have been cached
     pub(crate) files: Option<HashSet<String>>, // file or directory names
+    pub(crate) acl: Option<Acl>,               // access control list beyond the mode
+    pub(crate) context: Option<String>,        // SELinux security context
+    pub(crate) immutable: bool,                // immutable flag i.e. `chattr +i`
 }
 
 impl MemfsEntry {
@@ -151,7 +226,7 @@ impl MemfsEntry {
     /// ### Errors
     /// * PathError::IsNotDir(PathBuf) when this entry is not a directory.
     /// * PathError::ExistsAlready(PathBuf) when the given entry already exists.
-    /// entry's path
+    ///   entry's path
     pub(crate) fn add<T: Into<String>>(&mut self, entry: T) -> RvResult<bool> {
         let name = entry.into();
 
@@ -188,7 +263,7 @@ impl MemfsEntry {
     /// # Errors
     /// * PathError::IsNotDir(PathBuf) when this entry is not a directory.
     /// * PathError::ExistsAlready(PathBuf) when the given entry already exists.
-    /// entry's path
+    ///   entry's path
     pub(crate) fn remove<T: Into<String>>(&mut self, entry: T) -> RvResult<()> {
         let name = entry.into();
 
@@ -351,6 +426,33 @@ impl Entry for MemfsEntry {
         self.rel.clone()
     }
 
+    /// Returns the names of the directory's immediate children sorted by name
+    ///
+    /// * Reports the children cached when the entry was loaded
+    ///
+    /// ### Errors
+    /// * PathError::IsNotDir(PathBuf) when the entry is not a directory
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Memfs::new();
+    /// assert_vfs_mkdir_p!(vfs, "dir/sub");
+    /// assert_vfs_mkfile!(vfs, "dir/file");
+    /// assert_eq!(vfs.entry("dir").unwrap().children().unwrap(), vec!["file", "sub"]);
+    /// ```
+    fn children(&self) -> RvResult<Vec<String>> {
+        match &self.files {
+            Some(files) if self.dir => {
+                let mut names = files.iter().cloned().collect::<Vec<_>>();
+                names.sort();
+                Ok(names)
+            },
+            _ => Err(PathError::is_not_dir(&self.path).into()),
+        }
+    }
+
     /// Switch the `path` and `alt` values if `is_symlink` reports true.
     ///
     /// ### Examples
@@ -457,6 +559,93 @@ impl Entry for MemfsEntry {
         self.mode
     }
 
+    /// Reports the time the entry was last modified
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    /// ```
+    fn modified(&self) -> SystemTime {
+        self.modified
+    }
+
+    /// Reports the time the entry was created
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    /// ```
+    fn created(&self) -> SystemTime {
+        self.created
+    }
+
+    /// Reports the size of the file in bytes
+    ///
+    /// * Links report the size of the file they point to
+    /// * Directories report `0`
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Memfs::new();
+    /// let file = vfs.root().mash("file");
+    /// assert_vfs_write_all!(vfs, &file, "foobar");
+    /// assert_eq!(vfs.entry(&file).unwrap().size(), 6);
+    /// ```
+    fn size(&self) -> u64 {
+        self.size
+    }
+
+    /// Returns the filesystem properties cached when the entry was loaded
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Memfs::new();
+    /// let file = vfs.root().mash("file");
+    /// assert_vfs_write_all!(vfs, &file, "foobar");
+    /// let meta = vfs.entry(&file).unwrap().metadata();
+    /// assert_eq!((meta.file, meta.size, meta.uid), (true, 6, 1000));
+    /// ```
+    fn metadata(&self) -> VfsMetadata {
+        VfsMetadata {
+            dir: self.dir,
+            file: self.file,
+            link: self.link,
+            mode: self.mode,
+            size: self.size,
+            nlink: self.nlink,
+            uid: self.uid,
+            gid: self.gid,
+            accessed: self.modified,
+            modified: self.modified,
+            created: self.created,
+        }
+    }
+
+    /// Returns the chain of targets followed to resolve a link to its final target
+    ///
+    /// * Each element is the absolute path of a link hop's target in order with the final target
+    ///   last, thus all but the last element are themselves links
+    /// * Non link entries return an empty chain
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Memfs::new();
+    /// let file = vfs.root().mash("file");
+    /// let link = vfs.root().mash("link");
+    /// assert_vfs_mkfile!(vfs, &file);
+    /// assert_vfs_symlink!(vfs, &link, &file);
+    /// assert_eq!(vfs.entry(&link).unwrap().target_chain(), &[file]);
+    /// ```
+    fn target_chain(&self) -> &[PathBuf] {
+        &self.chain
+    }
+
     /// Up cast the trait type to the enum wrapper
     ///
     /// ### Examples
@@ -477,18 +666,27 @@ impl Entry for MemfsEntry {
 impl Clone for MemfsEntry {
     fn clone(&self) -> Self {
         Self {
+            meta: self.meta,
             path: self.path.clone(),
             alt: self.alt.clone(),
             rel: self.rel.clone(),
+            chain: self.chain.clone(),
             dir: self.dir,
             file: self.file,
             link: self.link,
             mode: self.mode,
+            size: self.size,
+            nlink: self.nlink,
+            modified: self.modified,
+            created: self.created,
             gid: self.gid,
             uid: self.uid,
             follow: self.follow,
             cached: self.cached,
             files: self.files.clone(),
+            acl: self.acl.clone(),
+            context: self.context.clone(),
+            immutable: self.immutable,
         }
     }
 }
@@ -583,11 +781,11 @@ mod tests {
         assert_eq!(&entry.path, &path);
         assert_eq!(&entry.alt, &PathBuf::new());
         assert_eq!(&entry.rel, &PathBuf::new());
-        assert_eq!(entry.dir, false);
-        assert_eq!(entry.file, true);
-        assert_eq!(entry.link, false);
-        assert_eq!(entry.follow, false);
-        assert_eq!(entry.cached, false);
+        assert!(!entry.dir);
+        assert!(entry.file);
+        assert!(!entry.link);
+        assert!(!entry.follow);
+        assert!(!entry.cached);
         assert_eq!(entry.mode, 0o100644);
         assert_eq!(entry.files, None);
     }
@@ -601,11 +799,11 @@ mod tests {
         assert_eq!(&entry.path, &path);
         assert_eq!(&entry.alt, &PathBuf::new());
         assert_eq!(&entry.rel, &PathBuf::new());
-        assert_eq!(entry.dir, true);
-        assert_eq!(entry.file, false);
-        assert_eq!(entry.link, false);
-        assert_eq!(entry.follow, false);
-        assert_eq!(entry.cached, false);
+        assert!(entry.dir);
+        assert!(!entry.file);
+        assert!(!entry.link);
+        assert!(!entry.follow);
+        assert!(!entry.cached);
         assert_eq!(entry.mode, 0o40755);
         assert!(entry.files.is_some());
         assert!(entry.files.unwrap().is_empty());
@@ -1,6 +1,7 @@
-use std::{cmp, io, path::PathBuf};
+use std::{cmp, io, path::PathBuf, time::SystemTime};
 
 use super::Memfs;
+use crate::{errors::RvError, sys::VfsEvent};
 
 /// `MemfsFile` is an implementation of memory based file in the memory filesytem.
 ///
@@ -12,6 +13,7 @@ use super::Memfs;
 pub(crate) struct MemfsFile {
     pub(crate) pos: u64,              // position in the memory file
     pub(crate) data: Vec<u8>,         // datastore for the memory file
+    pub(crate) dirty: bool,           // data has changed since the last sync
     pub(crate) path: Option<PathBuf>, // optional path to write to
     pub(crate) fs: Option<Memfs>,     // optional sharable filesystem for writes
 }
@@ -19,7 +21,7 @@ pub(crate) struct MemfsFile {
 impl MemfsFile {
     /// Returns the length of the file remaining from the current position
     pub(crate) fn len(&self) -> u64 {
-        self.data.len() as u64 - self.pos
+        (self.data.len() as u64).saturating_sub(self.pos)
     }
 
     /// Attempt to write the data to the data store
@@ -34,6 +36,13 @@ impl MemfsFile {
                     if let Some(f) = guard.get_file_mut(path) {
                         f.data.clone_from(&self.data);
                     }
+                    if self.dirty {
+                        if let Some(entry) = guard.get_entry_mut(path) {
+                            entry.modified = SystemTime::now();
+                        }
+                        guard.record_version(path);
+                        guard.emit(VfsEvent::Write(path.clone()));
+                    }
                 } else {
                     return Err(io::Error::new(
                         io::ErrorKind::NotFound,
@@ -42,6 +51,7 @@ impl MemfsFile {
                 }
             }
         }
+        self.dirty = false;
         Ok(())
     }
 }
@@ -51,6 +61,7 @@ impl Clone for MemfsFile {
         Self {
             pos: self.pos,
             data: self.data.clone(),
+            dirty: self.dirty,
             path: self.path.clone(),
             fs: self.fs.as_ref().map(|x| x.clone()),
         }
@@ -64,6 +75,9 @@ impl io::Read for MemfsFile {
 
         // Determine max data to read from the file
         let len = cmp::min(buf.len(), self.len() as usize);
+        if len == 0 {
+            return Ok(0);
+        }
 
         // Read the indicated data length
         buf[..len].copy_from_slice(&self.data.as_slice()[pos..pos + len]);
@@ -91,7 +105,30 @@ impl io::Seek for MemfsFile {
 // Implement the Write trait for the MemfsFile
 impl io::Write for MemfsFile {
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
-        self.data.write(buf)
+        let pos = self.pos as usize;
+        let end = pos + buf.len();
+
+        // Reject writes that would grow the file beyond any quotas
+        if let (Some(fs), Some(path)) = (&self.fs, &self.path) {
+            let size = cmp::max(self.data.len(), end) as u64;
+            match fs.read_guard().check_quota(path, size, false) {
+                Err(RvError::Vfs(err)) => return Err(io::Error::other(err)),
+                Err(err) => return Err(io::Error::other(err.to_string())),
+                Ok(_) => (),
+            }
+        }
+        self.dirty = true;
+
+        // Overwrite the data at the current position extending the file as needed, filling any
+        // gap left by seeking past the end with zeros
+        if pos > self.data.len() {
+            self.data.resize(pos, 0);
+        }
+        let overlap = cmp::min(end, self.data.len()) - pos;
+        self.data[pos..pos + overlap].copy_from_slice(&buf[..overlap]);
+        self.data.extend_from_slice(&buf[overlap..]);
+        self.pos = end as u64;
+        Ok(buf.len())
     }
 
     fn flush(&mut self) -> io::Result<()> {
@@ -125,22 +162,26 @@ mod tests {
 
         // Write using the function
         assert_eq!(memfile.len(), 0);
-        memfile.write(b"foobar1, ").unwrap();
+        assert_eq!(memfile.write(b"foobar1, ").unwrap(), 9);
         assert_eq!(memfile.data, b"foobar1, ");
-        assert_eq!(memfile.len(), 9);
+        assert_eq!(memfile.len(), 0);
 
         // Write out using the write macro
         write!(memfile, "foobar2, ").unwrap();
-        assert_eq!(memfile.len(), 18);
+        assert_eq!(memfile.len(), 0);
         assert_eq!(memfile.data, b"foobar1, foobar2, ");
 
-        memfile.write(b"foobar3").unwrap();
-        assert_eq!(memfile.len(), 25);
+        assert_eq!(memfile.write(b"foobar3").unwrap(), 7);
+        assert_eq!(memfile.len(), 0);
         assert_eq!(memfile.data, b"foobar1, foobar2, foobar3");
 
+        // Writes advance the position
+        memfile.rewind().unwrap();
+        assert_eq!(memfile.len(), 25);
+
         // read 1 byte
         let mut buf = [0; 1];
-        memfile.read(&mut buf).unwrap();
+        assert_eq!(memfile.read(&mut buf).unwrap(), 1);
         assert_eq!(memfile.len(), 24);
         assert_eq!(&buf, b"f");
 
@@ -148,7 +189,7 @@ mod tests {
         memfile.seek(SeekFrom::Start(0)).unwrap();
         assert_eq!(memfile.len(), 25);
         let mut buf = [0; 9];
-        memfile.read(&mut buf).unwrap();
+        assert_eq!(memfile.read(&mut buf).unwrap(), 9);
         assert_eq!(memfile.len(), 16);
         assert_eq!(&buf, b"foobar1, ");
 
@@ -165,5 +206,19 @@ mod tests {
         memfile.read_to_string(&mut buf).unwrap();
         assert_eq!(memfile.len(), 0);
         assert_eq!(buf, "foobar1, foobar2, foobar3".to_string());
+
+        // Overwrite in place and extend past the end
+        memfile.seek(SeekFrom::Start(3)).unwrap();
+        memfile.write_all(b"BAR").unwrap();
+        memfile.seek(SeekFrom::Start(24)).unwrap();
+        memfile.write_all(b"3, foobar4").unwrap();
+        assert_eq!(memfile.data, b"fooBAR1, foobar2, foobar3, foobar4");
+
+        // Seeking past the end leaves a zero filled gap
+        memfile.seek(SeekFrom::End(2)).unwrap();
+        memfile.write_all(b"5").unwrap();
+        assert_eq!(&memfile.data[33..], &[b'4', 0, 0, b'5']);
+        let mut buf = [0; 1];
+        assert_eq!(memfile.read(&mut buf).unwrap(), 0);
     }
 }
@@ -1,7 +1,33 @@
+/// Create a new [`Memfs`] seeded with the given fixture files embedded at compile time
+///
+/// * Paths are relative to the crate's manifest directory when read at compile time
+/// * Paths are relative to the Memfs root when seeded
+/// * Expands to an `RvResult<Memfs>`
+///
+/// ### Examples
+/// ```
+/// use rivia::prelude::*;
+///
+/// let memfs = memfs_seed!("LICENSE-MIT").unwrap();
+/// assert!(memfs.read_all("/LICENSE-MIT").unwrap().starts_with("MIT License"));
+/// ```
+#[macro_export]
+macro_rules! memfs_seed {
+    () => {
+        $crate::errors::RvResult::Ok($crate::sys::Memfs::new())
+    };
+    ($($path:literal),+ $(,)?) => {{
+        let memfs = $crate::sys::Memfs::new();
+        memfs
+            .seed(&[$(($path, &include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/", $path))[..])),*])
+            .map(|_| memfs)
+    }};
+}
+
 mod entry;
 mod file;
 mod vfs;
 
 pub use entry::*;
-use file::*;
+pub(crate) use file::*;
 pub use vfs::*;
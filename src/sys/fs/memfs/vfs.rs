@@ -1,26 +1,35 @@
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet, VecDeque},
     fmt,
-    io::{BufRead, BufReader, Read, Seek, SeekFrom, Write},
+    io::{self, BufRead, BufReader, Read, Seek, SeekFrom, Write},
     path::{Component, Path, PathBuf},
-    sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard},
+    sync::{
+        mpsc::{self, Receiver, Sender},
+        Arc, RwLock, RwLockReadGuard, RwLockWriteGuard,
+    },
+    time::{Duration, SystemTime},
 };
 
 use itertools::Itertools;
+use nix::errno::Errno;
 
-use super::{MemfsEntry, MemfsEntryIter, MemfsFile};
+use super::{next_entry_id, MemfsEntry, MemfsEntryInfo, MemfsEntryIter, MemfsFile};
 use crate::{
     core::*,
     errors::*,
     sys::{
-        self, Chmod, ChmodOpts, Chown, ChownOpts, Copier, Entries, Entry, EntryIter, PathExt, ReadSeek, Vfs,
-        VfsEntry, VirtualFileSystem,
+        self, Acl, Advice, Chmod, ChmodOpts, Chown, ChownOpts, Copier, DirHandle, DiskUsage, DiskUsageOpts,
+        Ensured, Entries, Entry, EntryIter, Installer, Kind, Manifest, MoveOpts, Mover, PathExt,
+        ReadOpts, ReadSeek, ReadWriteSeek, Reader, RemoveOpts, Remover, ResolvedLink, Vfs, VfsConfig, VfsEntry,
+        VfsEvent, VfsHooks, VfsMetadata, VfsOp, VfsStatFs, VfsWatcher, VfsWriter, VirtualFileSystem, WriteOpts,
+        Writer,
     },
 };
 
 // Helper aliases
 pub(crate) type MemfsFiles = HashMap<PathBuf, MemfsFile>;
 pub(crate) type MemfsEntries = HashMap<PathBuf, MemfsEntry>;
+pub(crate) type MemfsHistory = HashMap<PathBuf, (usize, VecDeque<Vec<u8>>)>;
 
 // Wraps the RwLock guard types to provide the ability to user either
 pub(crate) enum MemfsGuard<'a> {
@@ -29,6 +38,53 @@ pub(crate) enum MemfsGuard<'a> {
 }
 
 impl<'a> MemfsGuard<'a> {
+    // Check that setting the given path's data to `size` bytes and optionally adding it as a new
+    // entry stays within the quotas of any directories containing the path
+    pub(crate) fn check_quota(&self, path: &Path, size: u64, add: bool) -> RvResult<()> {
+        for (root, (bytes, inodes)) in self.quotas(path).into_iter().filter(|(x, _)| x != path) {
+            let (used_bytes, used_inodes) = self.usage(&root, path);
+            if used_bytes + size > bytes || (add && used_inodes + 1 > inodes) {
+                return Err(VfsError::QuotaExceeded(root).into());
+            }
+        }
+        Ok(())
+    }
+
+    // Check that the entry at the given path, optionally following links, isn't immutable i.e.
+    // `chattr +i` failing with EPERM the same as the kernel does
+    pub(crate) fn check_mutable(&self, path: &Path, follow: bool) -> RvResult<()> {
+        let mut path = path;
+        let mut hops = 0;
+        while let Some(entry) = self.get_entry(path) {
+            if entry.immutable {
+                return Err(io::Error::from(Errno::EPERM).into());
+            }
+            if !follow || !entry.is_symlink() || hops > sys::DEFAULT_MAX_LINKS {
+                break;
+            }
+            path = entry.alt();
+            hops += 1;
+        }
+        Ok(())
+    }
+
+    fn inner(&self) -> &MemfsInner {
+        match self {
+            MemfsGuard::Read(x) => x,
+            MemfsGuard::Write(x) => x,
+        }
+    }
+    // Returns the quotas of the given path and the directories containing it
+    pub(crate) fn quotas(&self, path: &Path) -> Vec<(PathBuf, (u64, u64))> {
+        self.inner().quotas.iter().filter(|(x, _)| path.starts_with(x)).map(|(x, y)| (x.clone(), *y)).collect()
+    }
+    // Returns the bytes and entries used beneath the given directory excluding the given path
+    pub(crate) fn usage(&self, root: &Path, skip: &Path) -> (u64, u64) {
+        let inner = self.inner();
+        let under = |x: &Path| x.starts_with(root) && x != root && x != skip;
+        let bytes = inner.files.iter().filter(|(x, _)| under(x)).map(|(_, x)| x.data.len() as u64).sum();
+        (bytes, inner.entries.keys().filter(|x| under(x)).count() as u64)
+    }
     pub(crate) fn contains_entry(&self, path: &Path) -> bool {
         match self {
             MemfsGuard::Read(x) => x.entries.contains_key(path),
@@ -36,10 +92,10 @@ impl<'a> MemfsGuard<'a> {
         }
     }
     pub(crate) fn contains_file(&self, path: &Path) -> bool {
-        match self {
-            MemfsGuard::Read(x) => x.files.contains_key(path),
-            MemfsGuard::Write(x) => x.files.contains_key(path),
-        }
+        self.inner().file(path).is_some()
+    }
+    pub(crate) fn config(&self) -> VfsConfig {
+        self.inner().config
     }
     pub(crate) fn cwd(&self) -> PathBuf {
         match self {
@@ -47,6 +103,11 @@ impl<'a> MemfsGuard<'a> {
             MemfsGuard::Write(x) => x.cwd.clone(),
         }
     }
+    pub(crate) fn emit(&mut self, event: VfsEvent) {
+        if let MemfsGuard::Write(x) = self {
+            x.emit(event);
+        }
+    }
     pub(crate) fn get_entry(&self, path: &Path) -> Option<&MemfsEntry> {
         match self {
             MemfsGuard::Read(x) => x.entries.get(path),
@@ -60,15 +121,21 @@ impl<'a> MemfsGuard<'a> {
         }
     }
     pub(crate) fn get_file(&self, path: &Path) -> Option<&MemfsFile> {
-        match self {
-            MemfsGuard::Read(x) => x.files.get(path),
-            MemfsGuard::Write(x) => x.files.get(path),
-        }
+        self.inner().file(path)
     }
     pub(crate) fn get_file_mut(&mut self, path: &Path) -> Option<&mut MemfsFile> {
         match self {
             MemfsGuard::Read(_) => None,
-            MemfsGuard::Write(x) => x.files.get_mut(path),
+            MemfsGuard::Write(x) => {
+                let inner: &mut MemfsInner = x;
+                inner.files.get_mut(inner.links.get(path).map_or(path, |x| x.as_path()))
+            },
+        }
+    }
+    pub(crate) fn hooks(&self) -> Arc<VfsHooks> {
+        match self {
+            MemfsGuard::Read(x) => x.hooks.clone(),
+            MemfsGuard::Write(x) => x.hooks.clone(),
         }
     }
     pub(crate) fn insert_entry(&mut self, path: PathBuf, entry: MemfsEntry) {
@@ -78,18 +145,90 @@ impl<'a> MemfsGuard<'a> {
     }
     pub(crate) fn insert_file(&mut self, path: PathBuf, file: MemfsFile) {
         if let MemfsGuard::Write(x) = self {
+            let path = x.links.get(&path).cloned().unwrap_or(path);
             x.files.insert(path, file);
         }
     }
+    // Add `link` as another name for the data of the given file
+    pub(crate) fn link_file(&mut self, path: &Path, link: PathBuf) {
+        if let MemfsGuard::Write(x) = self {
+            let path = x.links.get(path).cloned().unwrap_or(path.to_path_buf());
+            x.links.insert(link, path);
+        }
+    }
+    // Move the data of the given file to `dst` replacing any data `dst` had
+    pub(crate) fn move_file(&mut self, src: &Path, dst: &Path) {
+        self.remove_file(dst);
+        if let MemfsGuard::Write(x) = self {
+            if let Some(path) = x.links.remove(src) {
+                x.links.insert(dst.to_path_buf(), path);
+                return;
+            }
+            if let Some(mut file) = x.files.remove(src) {
+                file.path = Some(dst.to_path_buf());
+                x.files.insert(dst.to_path_buf(), file);
+            }
+            for path in x.links.values_mut().filter(|x| *x == src) {
+                *path = dst.to_path_buf();
+            }
+        }
+    }
+    // Returns the number of names the given path's data is known by with directories counting the
+    // entry of each sub directory for its parent
+    pub(crate) fn nlink(&self, path: &Path) -> u64 {
+        let inner = self.inner();
+        let is_dir = |x: &MemfsEntry| x.is_dir() && !x.is_symlink();
+        if let Some(entry) = inner.entries.get(path).filter(|x| is_dir(x)) {
+            let dirs = entry.files.iter().flatten().map(|x| path.mash(x));
+            return 2 + dirs.filter(|x| inner.entries.get(x).is_some_and(is_dir)).count() as u64;
+        }
+        let path = inner.links.get(path).map_or(path, |x| x.as_path());
+        1 + inner.links.values().filter(|x| *x == path).count() as u64
+    }
+    // Record the given file's current data as its newest version when history is enabled dropping
+    // the oldest versions beyond the limit
+    pub(crate) fn record_version(&mut self, path: &Path) {
+        if let MemfsGuard::Write(x) = self {
+            let inner: &mut MemfsInner = x;
+            if inner.history_max == 0 {
+                return;
+            }
+            if let Some(file) = inner.files.get(inner.links.get(path).map_or(path, |x| x.as_path())) {
+                let (dropped, versions) = inner.history.entry(path.to_path_buf()).or_default();
+                versions.push_back(file.data.clone());
+                while versions.len() > inner.history_max {
+                    versions.pop_front();
+                    *dropped += 1;
+                }
+            }
+        }
+    }
     pub(crate) fn remove_entry(&mut self, path: &Path) -> Option<MemfsEntry> {
         if let MemfsGuard::Write(x) = self {
             return x.entries.remove(path);
         }
         None
     }
+    // Remove the given name for its data only dropping the data once it has no other names. The
+    // data of a file with links is handed to its first link.
     pub(crate) fn remove_file(&mut self, path: &Path) -> Option<MemfsFile> {
         if let MemfsGuard::Write(x) = self {
-            return x.files.remove(path);
+            if let Some(path) = x.links.remove(path) {
+                return x.files.get(&path).cloned();
+            }
+            let file = x.files.remove(path);
+            let links = x.links.iter().filter(|(_, x)| *x == path).map(|(x, _)| x.clone()).sorted().collect_vec();
+            if let Some(first) = links.first() {
+                x.links.remove(first);
+                for link in &links[1..] {
+                    x.links.insert(link.clone(), first.clone());
+                }
+                if let Some(mut data) = file.clone() {
+                    data.path = Some(first.clone());
+                    x.files.insert(first.clone(), data);
+                }
+            }
+            return file;
         }
         None
     }
@@ -99,24 +238,90 @@ impl<'a> MemfsGuard<'a> {
             MemfsGuard::Write(x) => x.root.clone(),
         }
     }
+    pub(crate) fn unbind(&self, path: &Path) -> PathBuf {
+        let binds = match self {
+            MemfsGuard::Read(x) => &x.binds,
+            MemfsGuard::Write(x) => &x.binds,
+        };
+
+        // Resolve the deepest bind point first allowing for binds of binds
+        let mut path = path.to_path_buf();
+        for _ in 0..binds.len() {
+            let matches = binds.iter().filter(|(dst, _)| path.starts_with(dst));
+            match matches.max_by_key(|(dst, _)| dst.components().count()) {
+                Some((dst, src)) => path = src.mash(path.strip_prefix(dst).unwrap()),
+                None => break,
+            }
+        }
+        path
+    }
+    pub(crate) fn set_acl(&mut self, path: &Path, acl: Option<Acl>) {
+        if let Some(entry) = self.get_entry_mut(path) {
+            entry.acl = acl;
+        }
+    }
+    pub(crate) fn set_context(&mut self, path: &Path, context: Option<String>) {
+        if let Some(entry) = self.get_entry_mut(path) {
+            entry.context = context;
+        }
+    }
     pub(crate) fn set_cwd(&mut self, path: PathBuf) {
         if let MemfsGuard::Write(x) = self {
             x.cwd = path;
         }
     }
+    pub(crate) fn set_mode(&mut self, path: &Path, mode: u32) {
+        if let MemfsGuard::Write(x) = self {
+            if let Some(entry) = x.entries.get_mut(path) {
+                let old = entry.mode;
+                entry.set_mode(Some(mode));
+                let event = VfsEvent::Chmod(path.to_path_buf(), old, entry.mode);
+                x.emit(event);
+            }
+        }
+    }
+    pub(crate) fn set_owner(&mut self, path: &Path, uid: Option<u32>, gid: Option<u32>) {
+        if let MemfsGuard::Write(x) = self {
+            if let Some(entry) = x.entries.get_mut(path) {
+                let old = (entry.uid, entry.gid);
+                entry.set_owner(uid, gid);
+                let event = VfsEvent::Chown(path.to_path_buf(), old, (entry.uid, entry.gid));
+                x.emit(event);
+            }
+        }
+    }
 }
 
 /// Provides a purely memory based, multi-thread safe [`VirtualFileSystem`] backend implementation
-#[derive(Debug)]
 pub struct Memfs(Arc<RwLock<MemfsInner>>);
 
 // Encapsulate the Memfs implementation for interior mutability and transparent multi-thread safety
 #[derive(Debug)]
 pub(crate) struct MemfsInner {
-    pub(crate) cwd: PathBuf,          // Current working directory
-    pub(crate) root: PathBuf,         // Current root directory
-    pub(crate) entries: MemfsEntries, // Filesystem of path to entry
-    pub(crate) files: MemfsFiles,     // Filesystem of path to entry
+    pub(crate) cwd: PathBuf,                         // Current working directory
+    pub(crate) root: PathBuf,                        // Current root directory
+    pub(crate) entries: MemfsEntries,                // Filesystem of path to entry
+    pub(crate) files: MemfsFiles,                    // Filesystem of path to entry
+    pub(crate) links: HashMap<PathBuf, PathBuf>,     // Hard links to the path holding their data
+    pub(crate) events: Vec<Sender<VfsEvent>>,        // Mutation event subscribers
+    pub(crate) hooks: Arc<VfsHooks>,                 // Pre and post operation hooks
+    pub(crate) binds: Vec<(PathBuf, PathBuf)>,       // Bind points and the source directories they alias
+    pub(crate) quotas: HashMap<PathBuf, (u64, u64)>, // Directory byte and inode limits
+    pub(crate) history: MemfsHistory,                // Prior versions of files by path
+    pub(crate) history_max: usize,                   // Versions kept per file, zero disables history
+    pub(crate) config: VfsConfig,                    // Tunable defaults of the instance
+}
+
+impl MemfsInner {
+    // Returns the data of the given file following any hard link
+    fn file(&self, path: &Path) -> Option<&MemfsFile> {
+        self.files.get(self.links.get(path).map_or(path, |x| x.as_path()))
+    }
+
+    // Send the given event to all subscribers dropping any that have gone away
+    fn emit(&mut self, event: VfsEvent) {
+        self.events.retain(|tx| tx.send(event.clone()).is_ok());
+    }
 }
 
 impl Default for Memfs {
@@ -128,33 +333,465 @@ impl Default for Memfs {
 impl Memfs {
     /// Create a new Memfs instance
     pub fn new() -> Self {
+        Self::with_config(VfsConfig::default())
+    }
+
+    /// Create a new Memfs instance with the given config
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Memfs::with_config(VfsConfig { dir_mode: 0o700, ..Default::default() });
+    /// assert_vfs_mkdir_p!(vfs, "dir");
+    /// assert_eq!(vfs.mode("dir").unwrap(), 0o40700);
+    /// ```
+    pub fn with_config(config: VfsConfig) -> Self {
         let mut root = PathBuf::new();
         root.push(Component::RootDir);
 
         // Add the default root entry
         let mut entries = HashMap::new();
-        entries.insert(root.clone(), MemfsEntry::opts(root.clone()).build());
+        entries.insert(root.clone(), MemfsEntry::opts(root.clone()).mode(Some(config.dir_mode)).build());
 
         Self(Arc::new(RwLock::new(MemfsInner {
             cwd: root.clone(),
             root,
             entries,
             files: HashMap::new(),
+            links: HashMap::new(),
+            events: vec![],
+            hooks: Default::default(),
+            binds: vec![],
+            quotas: HashMap::new(),
+            history: HashMap::new(),
+            history_max: 0,
+            config,
         })))
     }
 
+    /// Subscribe to the mutation events emitted by this Memfs instance
+    ///
+    /// * Every write operation emits one or more [`VfsEvent`]s in the order they occurred
+    /// * Only events occurring after the subscription are received
+    /// * Useful for asserting the exact filesystem effects of a function under test
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let memfs = Memfs::new();
+    /// let events = memfs.events();
+    /// let file = memfs.root().mash("file");
+    /// assert_vfs_write_all!(memfs, &file, "foobar");
+    /// assert!(memfs.remove(&file).is_ok());
+    /// assert_eq!(events.try_iter().collect::<Vec<_>>(), vec![
+    ///     VfsEvent::Create(file.clone()),
+    ///     VfsEvent::Write(file.clone()),
+    ///     VfsEvent::Remove(file)
+    /// ]);
+    /// ```
+    pub fn events(&self) -> Receiver<VfsEvent> {
+        let (tx, rx) = mpsc::channel();
+        self.0.write().unwrap().events.push(tx);
+        rx
+    }
+
+    /// Bind the given source directory to the given destination directory
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Both paths then refer to the same underlying subtree i.e. changes made via one are visible
+    ///   via the other without duplicating data
+    /// * Much like a bind mount the destination's own contents are hidden until `unbind` is called
+    /// * Paths under the destination resolve to the source, thus operations report source paths
+    ///   much like following a link with the exception of traversals from above the destination
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when either the source or destination doesn't exist
+    /// * PathError::IsNotDir(PathBuf) when either the source or destination is not a directory
+    /// * PathError::LinkLooping(PathBuf) when the destination and source are nested
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let memfs = Memfs::new();
+    /// assert_vfs_mkdir_p!(memfs, "/src");
+    /// assert_vfs_mkdir_p!(memfs, "/dst");
+    /// assert!(memfs.bind("/src", "/dst").is_ok());
+    /// assert_vfs_write_all!(memfs, "/dst/file", "foobar");
+    /// assert_vfs_read_all!(memfs, "/src/file", "foobar");
+    /// ```
+    pub fn bind<T: AsRef<Path>, U: AsRef<Path>>(&self, src: T, dst: U) -> RvResult<()> {
+        let mut guard = self.write_guard();
+        let src = self._abs(&guard, src)?;
+        let dst = self._abs_unbound(&guard, dst)?;
+
+        // Both sides must be existing directories
+        for path in [&src, &dst] {
+            match guard.get_entry(&guard.unbind(path)) {
+                Some(entry) if entry.is_dir() => (),
+                Some(_) => return Err(PathError::is_not_dir(path).into()),
+                None => return Err(PathError::does_not_exist(path).into()),
+            }
+        }
+
+        // Nested binds would make the subtree infinitely deep
+        if src.starts_with(&dst) || dst.starts_with(&src) {
+            return Err(PathError::link_looping(&dst).into());
+        }
+
+        if let MemfsGuard::Write(ref mut x) = guard {
+            x.binds.retain(|(x, _)| x != &dst);
+            x.binds.push((dst, src));
+        }
+        Ok(())
+    }
+
+    /// Remove the binding for the given destination directory
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Restores the destination's own hidden contents
+    /// * Does nothing when the given path isn't bound
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let memfs = Memfs::new();
+    /// assert_vfs_mkdir_p!(memfs, "/src");
+    /// assert_vfs_mkdir_p!(memfs, "/dst");
+    /// assert_vfs_mkfile!(memfs, "/src/file");
+    /// assert!(memfs.bind("/src", "/dst").is_ok());
+    /// assert_vfs_exists!(memfs, "/dst/file");
+    /// assert!(memfs.unbind("/dst").is_ok());
+    /// assert_vfs_no_exists!(memfs, "/dst/file");
+    /// ```
+    pub fn unbind<T: AsRef<Path>>(&self, dst: T) -> RvResult<()> {
+        let mut guard = self.write_guard();
+        let dst = self._abs_unbound(&guard, dst)?;
+        if let MemfsGuard::Write(ref mut x) = guard {
+            x.binds.retain(|(x, _)| x != &dst);
+        }
+        Ok(())
+    }
+
+    /// Set the max number of bytes and entries allowed beneath the given directory
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Creating entries or writing data beyond either limit fails with `VfsError::QuotaExceeded`
+    /// * Existing usage over the new limits is left as is but further growth is rejected
+    /// * Renames into the directory are not checked against its quota
+    /// * Use `u64::MAX` for both limits to effectively remove the quota
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    /// * PathError::IsNotDir(PathBuf) when the given path is not a directory
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let memfs = Memfs::new();
+    /// assert_vfs_mkdir_p!(memfs, "/tenant");
+    /// assert!(memfs.set_quota("/tenant", 3, 1).is_ok());
+    /// assert!(memfs.write_all("/tenant/file1", "foobar").is_err());
+    /// assert_vfs_write_all!(memfs, "/tenant/file1", "foo");
+    /// assert!(memfs.mkfile("/tenant/file2").is_err());
+    /// ```
+    pub fn set_quota<T: AsRef<Path>>(&self, path: T, bytes: u64, inodes: u64) -> RvResult<()> {
+        let mut guard = self.write_guard();
+        let path = self._abs(&guard, path)?;
+        match guard.get_entry(&path) {
+            Some(entry) if entry.is_dir() => (),
+            Some(_) => return Err(PathError::is_not_dir(&path).into()),
+            None => return Err(PathError::does_not_exist(&path).into()),
+        }
+        if let MemfsGuard::Write(ref mut x) = guard {
+            x.quotas.insert(path, (bytes, inodes));
+        }
+        Ok(())
+    }
+
+    /// Seed this Memfs with the given files and their data
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Parent directories are created as needed and existing files overwritten
+    /// * See [`memfs_seed!`] for embedding fixture files at compile time
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let memfs = Memfs::new();
+    /// assert!(memfs.seed(&[("/fixtures/file1", "foo"), ("/fixtures/dir/file2", "bar")]).is_ok());
+    /// assert_vfs_read_all!(memfs, "/fixtures/file1", "foo");
+    /// assert_vfs_read_all!(memfs, "/fixtures/dir/file2", "bar");
+    /// ```
+    pub fn seed<T: AsRef<Path>, U: AsRef<[u8]>>(&self, files: &[(T, U)]) -> RvResult<()> {
+        for (path, data) in files {
+            let path = self.abs(path)?;
+            self.mkdir_p(path.dir()?)?;
+            self.write_all(&path, data)?;
+        }
+        Ok(())
+    }
+
+    /// Enable history mode keeping up to `max` versions of each file
+    ///
+    /// * Every change to a file's data records the resulting data as a new version
+    /// * Versions are numbered from 1 in the order they were recorded and keep their numbers when
+    ///   the oldest are dropped to stay within `max`
+    /// * History is kept by path and survives the file being removed
+    /// * A `max` of zero disables history mode and drops all recorded versions
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let memfs = Memfs::new();
+    /// let file = memfs.root().mash("file");
+    /// memfs.set_history(2);
+    /// assert_vfs_write_all!(memfs, &file, "1");
+    /// assert!(memfs.write_all(&file, "2").is_ok());
+    /// assert!(memfs.write_all(&file, "3").is_ok());
+    /// assert_eq!(memfs.versions(&file).unwrap(), vec![2, 3]);
+    /// ```
+    pub fn set_history(&self, max: usize) {
+        let mut guard = self.0.write().unwrap();
+        guard.history_max = max;
+        if max == 0 {
+            guard.history.clear();
+        }
+        for (dropped, versions) in guard.history.values_mut() {
+            while versions.len() > max {
+                versions.pop_front();
+                *dropped += 1;
+            }
+        }
+    }
+
+    /// Read the given version of a file from its history
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * See `set_history` to enable history mode
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist and has no history
+    /// * PathError::IsNotFile(PathBuf) when the given path isn't a file
+    /// * VfsError::VersionNotFound(PathBuf, usize) when the given version isn't in the history
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let memfs = Memfs::new();
+    /// let file = memfs.root().mash("file");
+    /// memfs.set_history(5);
+    /// assert_vfs_write_all!(memfs, &file, "1");
+    /// assert!(memfs.write_all(&file, "2").is_ok());
+    /// assert_eq!(memfs.read_at_version(&file, 1).unwrap(), "1");
+    /// assert_eq!(memfs.read_at_version(&file, 2).unwrap(), "2");
+    /// ```
+    pub fn read_at_version<T: AsRef<Path>>(&self, path: T, version: usize) -> RvResult<String> {
+        let guard = self.read_guard();
+        let path = self._history_path(&guard, path)?;
+        let data = match guard.inner().history.get(&path) {
+            Some((dropped, versions)) if version > *dropped => versions.get(version - dropped - 1),
+            _ => None,
+        };
+        match data {
+            Some(data) => String::from_utf8(data.clone())
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err).into()),
+            None => Err(VfsError::VersionNotFound(path, version).into()),
+        }
+    }
+
+    /// Returns the version numbers of the given file's history oldest first
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * The last version is the file's current data unless it has since been removed
+    /// * See `set_history` to enable history mode
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist and has no history
+    /// * PathError::IsNotFile(PathBuf) when the given path isn't a file
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let memfs = Memfs::new();
+    /// let file = memfs.root().mash("file");
+    /// memfs.set_history(5);
+    /// assert_vfs_write_all!(memfs, &file, "1");
+    /// assert!(memfs.append_all(&file, "2").is_ok());
+    /// assert_eq!(memfs.versions(&file).unwrap(), vec![1, 2]);
+    /// ```
+    pub fn versions<T: AsRef<Path>>(&self, path: T) -> RvResult<Vec<usize>> {
+        let guard = self.read_guard();
+        let path = self._history_path(&guard, path)?;
+        Ok(match guard.inner().history.get(&path) {
+            Some((dropped, versions)) => (dropped + 1..=dropped + versions.len()).collect(),
+            None => vec![],
+        })
+    }
+
+    /// Returns a snapshot of every entry in the filesystem sorted by path
+    ///
+    /// * Reports the raw entries without following links or resolving bind points
+    /// * Provides a stable alternative to parsing the `Display` output for debugging and assertions
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let memfs = Memfs::new();
+    /// assert_vfs_mkdir_p!(memfs, "/dir");
+    /// assert_vfs_symlink!(memfs, "/link", "/dir");
+    /// let paths = memfs.dump().into_iter().map(|x| x.path).collect::<Vec<_>>();
+    /// assert_eq!(paths, vec![PathBuf::from("/"), PathBuf::from("/dir"), PathBuf::from("/link")]);
+    /// ```
+    pub fn dump(&self) -> Vec<MemfsEntryInfo> {
+        Self::_dump(&self.0.read().unwrap())
+    }
+
+    /// Returns true if the given Memfs has the same tree as this one
+    ///
+    /// * Compares paths, kinds, modes, link targets and file contents
+    /// * Ignores timestamps, ownership, the current working directory and history
+    /// * Also available via `==` so that `assert_eq!` reports the differing trees
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let memfs1 = Memfs::new();
+    /// let memfs2 = Memfs::new();
+    /// assert_vfs_write_all!(memfs1, "/file", "foobar");
+    /// assert!(!memfs1.tree_eq(&memfs2));
+    /// assert_vfs_write_all!(memfs2, "/file", "foobar");
+    /// assert!(memfs1.tree_eq(&memfs2));
+    /// assert_eq!(memfs1, memfs2);
+    /// ```
+    pub fn tree_eq(&self, other: &Memfs) -> bool {
+        if Arc::ptr_eq(&self.0, &other.0) {
+            return true;
+        }
+
+        // Snapshot each in turn so that both locks are never held at once
+        let snapshot = self._snapshot();
+        snapshot == other._snapshot()
+    }
+
+    /// Returns a [`Manifest`] declaring every directory, file and link in this Memfs
+    ///
+    /// * Items are declared in path order so parents come before their contents
+    /// * Captures the permission bits of directories and files, the ownership of directories, the
+    ///   data of files and the targets of links as they were given
+    /// * The root directory itself isn't declared
+    /// * Enable the `serde` feature to save the manifest as e.g. JSON for use as a fixture
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let memfs = Memfs::new();
+    /// assert_vfs_mkdir_p!(memfs, "/dir");
+    /// assert_vfs_write_all!(memfs, "/dir/file", "foobar");
+    /// assert_vfs_symlink!(memfs, "/link", "dir/file");
+    /// assert_eq!(Memfs::from_manifest(&memfs.to_manifest()).unwrap(), memfs);
+    /// ```
+    pub fn to_manifest(&self) -> Manifest {
+        let guard = self.0.read().unwrap();
+        Self::_dump(&guard).into_iter().filter(|x| x.path != guard.root).fold(Manifest::new(), |manifest, x| {
+            let entry = &guard.entries[&x.path];
+            match x.kind {
+                Kind::Dir => manifest.dir(&x.path, x.mode & 0o7777, entry.uid, entry.gid),
+                Kind::File => {
+                    let data = guard.file(&x.path).map(|f| f.data.clone()).unwrap_or_default();
+                    manifest.file(&x.path, data, x.mode & 0o7777)
+                },
+                Kind::Symlink => manifest.link(&x.path, entry.rel_buf()),
+            }
+        })
+    }
+
+    /// Create a new Memfs instance populated from the given [`Manifest`]
+    ///
+    /// * Converges a fresh Memfs to the manifest with `sys::apply` so items are created in the
+    ///   order they were declared
+    /// * Enable the `serde` feature to load the manifest from e.g. a JSON fixture
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when a file is declared before its parent directory
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let manifest = Manifest::new().dir("/dir", 0o700, 0, 0).file("/dir/file", "foobar", 0o600);
+    /// let memfs = Memfs::from_manifest(&manifest).unwrap();
+    /// assert_vfs_read_all!(memfs, "/dir/file", "foobar");
+    /// assert_eq!(memfs.mode("/dir").unwrap(), 0o40700);
+    /// ```
+    pub fn from_manifest(manifest: &Manifest) -> RvResult<Memfs> {
+        let memfs = Memfs::new();
+        sys::apply(&memfs, manifest)?;
+        Ok(memfs)
+    }
+
     /// Make a clone of the Memfs as a shallow Arc clone
     pub(crate) fn clone(&self) -> Memfs {
         Memfs(self.0.clone())
     }
 
+    // Describe every entry in the given filesystem sorted by path
+    fn _dump(inner: &MemfsInner) -> Vec<MemfsEntryInfo> {
+        inner
+            .entries
+            .keys()
+            .sorted()
+            .map(|path| {
+                let entry = &inner.entries[path];
+                let kind = if entry.link {
+                    Kind::Symlink
+                } else if entry.dir {
+                    Kind::Dir
+                } else {
+                    Kind::File
+                };
+                MemfsEntryInfo {
+                    path: path.clone(),
+                    kind,
+                    mode: entry.mode,
+                    size: if entry.file && !entry.link { inner.file(path).map_or(0, |x| x.len()) } else { 0 },
+                    target: if entry.link { Some(entry.alt_buf()) } else { None },
+                }
+            })
+            .collect()
+    }
+
+    // Describe every entry along with the file's data for comparisons
+    fn _snapshot(&self) -> Vec<(MemfsEntryInfo, Option<Vec<u8>>)> {
+        let guard = self.0.read().unwrap();
+        Self::_dump(&guard)
+            .into_iter()
+            .map(|x| {
+                let file = guard.file(&x.path).filter(|_| x.kind == Kind::File);
+                let data = file.map(|f| f.data.clone());
+                (x, data)
+            })
+            .collect()
+    }
+
     // Create a MemfsGuard::Read
-    pub(crate) fn read_guard(&self) -> MemfsGuard {
+    pub(crate) fn read_guard(&self) -> MemfsGuard<'_> {
         MemfsGuard::Read(self.0.read().unwrap())
     }
 
     // Create a MemfsGuard::write
-    pub(crate) fn write_guard(&self) -> MemfsGuard {
+    pub(crate) fn write_guard(&self) -> MemfsGuard<'_> {
         MemfsGuard::Write(self.0.write().unwrap())
     }
 
@@ -168,11 +805,18 @@ impl Memfs {
     }
 
     /// Resolve the absolute path for the given path
+    ///
+    /// * Paths under a bind point are resolved to the bound source directory
     pub(crate) fn _abs<T: AsRef<Path>>(&self, guard: &MemfsGuard, path: T) -> RvResult<PathBuf> {
+        Ok(guard.unbind(&self._abs_unbound(guard, path)?))
+    }
+
+    /// Resolve the absolute path for the given path ignoring bind points
+    pub(crate) fn _abs_unbound<T: AsRef<Path>>(&self, guard: &MemfsGuard, path: T) -> RvResult<PathBuf> {
         let path = path.as_ref();
 
         // Check for empty string
-        if path.is_empty() {
+        if PathExt::is_empty(path) {
             return Err(PathError::Empty.into());
         }
 
@@ -221,7 +865,7 @@ impl Memfs {
         let path = entry.path_buf();
 
         // Skip creation of root as `new` will take care of that
-        if path == PathBuf::from(Component::RootDir.to_string()?) {
+        if path == Component::RootDir.to_string()? {
             return Ok(path);
         }
 
@@ -245,6 +889,9 @@ impl Memfs {
                 return Err(PathError::is_not_dir(&path).into());
             }
         } else {
+            guard.check_mutable(&dir, false)?;
+            guard.check_quota(&path, 0, true)?;
+
             // Add the new file to the data system if not a link
             if !entry.is_symlink() && entry.is_file() {
                 guard.insert_file(path.clone(), MemfsFile::default());
@@ -259,11 +906,20 @@ impl Memfs {
                     return Err(PathError::exists_already(path).into());
                 }
             }
+            guard.emit(VfsEvent::Create(path.clone()));
         }
 
         Ok(path)
     }
 
+    /// Create the given file with the configured default mode if it doesn't already exist
+    ///
+    /// * Expects the path to already be in absolute form
+    pub(crate) fn _add_file(&self, guard: &mut MemfsGuard, path: &Path) -> RvResult<PathBuf> {
+        let entry = MemfsEntry::opts(path).mode(Some(guard.config().file_mode)).file().build();
+        self._add(guard, entry)
+    }
+
     // Execute chmod with the given options
     fn _chmod(&self, opts: ChmodOpts) -> RvResult<()> {
         // Using `contents_first` to yield directories last so that revoking permissions happen to
@@ -283,19 +939,25 @@ impl Memfs {
         let m = opts.clone();
         let vfs = self.clone();
         entries = entries.follow(opts.follow).dirs_first().pre_op(move |x| {
-            let m1 = sys::mode(x, m.dirs, &m.sym)?;
+            if m.excluded(x) {
+                return Ok(());
+            }
+            let m1 = sys::umask_mode(x.mode(), sys::mode(x, m.dirs, &m.sym)?, m.umask);
             if (!x.is_symlink() || m.follow) && x.is_dir() && !sys::revoking_mode(x.mode(), m1) && x.mode() != m1 {
-                let mut guard = vfs.write_guard();
-                if let Some(entry) = guard.get_entry_mut(x.path()) {
-                    entry.set_mode(Some(m1));
-                }
+                vfs.write_guard().set_mode(x.path(), m1);
             }
             Ok(())
         });
 
         // Set permissions on the way out for everything specified
-        for entry in entries {
-            let src = entry?;
+        let apply = |mut src: VfsEntry| -> RvResult<()> {
+            // Links don't carry permissions of their own so target them when requested
+            if src.is_symlink() && opts.links {
+                match src.target_chain().last().map(|x| self.entry(x)) {
+                    Some(Ok(target)) => src = target,
+                    _ => return Ok(()),
+                }
+            }
 
             // Compute mode based on octal and symbolic values
             let m2 = if src.is_dir() {
@@ -305,16 +967,28 @@ impl Memfs {
             } else {
                 0
             };
+            let m2 = sys::umask_mode(src.mode(), m2, opts.umask);
 
             // Apply permission to entry if set
             if (!src.is_symlink() || opts.follow) && m2 != src.mode() && m2 != 0 {
-                let mut guard = self.write_guard();
-                if let Some(entry) = guard.get_entry_mut(src.path()) {
-                    entry.set_mode(Some(m2));
-                }
+                self.write_guard().set_mode(src.path(), m2);
+            }
+            Ok(())
+        };
+
+        let mut batch = Batch::new(opts.cont);
+        for entry in entries {
+            let src = match batch.check(&opts.path, entry)? {
+                Some(x) => x,
+                None => continue,
+            };
+            if opts.excluded(&src) {
+                continue;
             }
+            let path = src.path().to_owned();
+            batch.check(path, apply(src))?;
         }
-        Ok(())
+        batch.finish()
     }
 
     // Execute chown with the given options
@@ -323,13 +997,128 @@ impl Memfs {
         let max_depth = if opts.recursive { usize::MAX } else { 0 };
         let entries = self.entries(&opts.path)?.max_depth(max_depth).follow(opts.follow);
 
+        let mut batch = Batch::new(opts.cont);
         let mut guard = self.write_guard();
         for entry in entries {
-            let src = entry?;
-            if let Some(entry) = guard.get_entry_mut(src.path()) {
-                entry.set_owner(opts.uid, opts.gid);
+            let src = match batch.check(&opts.path, entry)? {
+                Some(x) => x,
+                None => continue,
+            };
+            if opts.excluded(&src) {
+                continue;
+            }
+
+            // Links are changed themselves when requested and their targets only when following
+            if src.is_symlink() {
+                if opts.links {
+                    let link = if src.following() { src.alt() } else { src.path() };
+                    guard.set_owner(link, opts.uid, opts.gid);
+                }
+                if !src.following() {
+                    continue;
+                }
+            }
+            guard.set_owner(src.path(), opts.uid, opts.gid);
+        }
+        batch.finish()
+    }
+
+    // Execute disk_usage with the given [`DiskUsage`] options summing the file buffers
+    fn _disk_usage(&self, opts: DiskUsageOpts) -> RvResult<u64> {
+        // Get entries separately to avoid a context collisions
+        let entries = self.entries(&opts.path)?.follow(opts.follow);
+
+        let mut paths = HashSet::new();
+        let mut total = 0;
+        for entry in entries {
+            let entry = entry?;
+            if !entry.is_file() || (entry.is_symlink() && !entry.following()) || opts.excluded(&entry) {
+                continue;
+            }
+
+            // Hard linked files share the data of the same path and are only counted once
+            let guard = self.read_guard();
+            let inner = guard.inner();
+            let path = inner.links.get(entry.path()).map_or(entry.path(), |x| x.as_path());
+            if paths.insert(path.to_path_buf()) {
+                total += inner.files.get(path).map_or(0, |x| x.len());
+            }
+        }
+        Ok(total)
+    }
+
+    // Execute remove_all with the given [`Remover`] options
+    fn _remove_all(&self, mut opts: RemoveOpts) -> RvResult<()> {
+        if let Some(base) = opts.within.take() {
+            opts.within = Some(self._abs(&self.read_guard(), base)?);
+        }
+        opts.check(|x| sys::link_target(self, x))?;
+
+        // Memfs has no devices so there is no boundary to cross
+        if opts.confirm.is_none() {
+            return self._remove_tree(&mut self.write_guard(), &opts.path);
+        }
+
+        // Confirm with the guard released as the callback may call back into the vfs
+        if !self.exists(&opts.path) {
+            return Ok(());
+        }
+        let is_dir = self.is_dir(&opts.path) && !self.is_symlink(&opts.path);
+        let approved = opts.confirmed(self.entries(&opts.path)?, is_dir)?;
+
+        // Remove the approved entries deepest first then the path if it ended up empty
+        let mut guard = self.write_guard();
+        let is_empty = |guard: &MemfsGuard, path: &Path| {
+            guard.get_entry(path).and_then(|x| x.files.as_ref()).is_none_or(|x| x.is_empty())
+        };
+        for entry in approved.into_iter().rev() {
+            if !opts.only_if_empty(&entry) || is_empty(&guard, entry.path()) {
+                self._remove_tree(&mut guard, entry.path())?;
+            }
+        }
+        if is_dir && is_empty(&guard, &opts.path) {
+            self._remove_tree(&mut guard, &opts.path)?;
+        }
+        Ok(())
+    }
+
+    // Remove the given path and everything below it
+    fn _remove_tree(&self, guard: &mut MemfsGuard, path: &Path) -> RvResult<()> {
+        let mut paths = vec![path.to_path_buf()];
+        while let Some(path) = paths.pop() {
+            if !guard.contains_entry(&path) {
+                continue;
+            }
+
+            // First process the entry's children
+            if let Some(entry) = guard.get_entry(&path) {
+                if let Some(ref files) = entry.files {
+                    if !files.is_empty() {
+                        paths.push(path.clone()); // remove after children
+                        for name in files {
+                            paths.push(path.mash(name));
+                        }
+                        continue;
+                    }
+                }
+            }
+
+            // Remove the file from its parent
+            guard.check_mutable(&path, false)?;
+            guard.check_mutable(&path.dir()?, false)?;
+            if let Some(parent) = guard.get_entry_mut(&path.dir()?) {
+                parent.remove(path.base()?)?;
+            }
+
+            // Next remove its data file if it exists
+            guard.remove_file(&path);
+
+            // Finally remove the entry from the filesystem
+            if guard.remove_entry(&path).is_some() {
+                guard.emit(VfsEvent::Remove(path));
             }
         }
+
         Ok(())
     }
 
@@ -344,13 +1133,16 @@ impl Memfs {
 
         let mut paths = vec![abs];
         while let Some(path) = paths.pop() {
-            if let Some(entry) = guard.get_entry(&path) {
-                entries.insert(entry.path_buf(), entry.clone());
+            // Bind points are cloned from their source but keep their bound path for traversal
+            if let Some(entry) = guard.get_entry(&guard.unbind(&path)) {
+                let mut clone = self._resolve_entry(guard, entry);
+                clone.path = path.clone();
+                entries.insert(path.clone(), clone);
 
                 // Recursively clone children
                 if let Some(ref files) = entry.files {
                     for name in files {
-                        paths.push(entry.path().mash(name));
+                        paths.push(path.mash(name));
                     }
                 }
 
@@ -372,7 +1164,7 @@ impl Memfs {
     pub(crate) fn _clone_entry<T: AsRef<Path>>(&self, guard: &MemfsGuard, path: T) -> RvResult<MemfsEntry> {
         let abs = self._abs(guard, path)?;
         match guard.get_entry(&abs) {
-            Some(entry) => Ok(entry.clone()),
+            Some(entry) => Ok(self._resolve_entry(guard, entry)),
             None => Err(PathError::does_not_exist(&abs).into()),
         }
     }
@@ -398,14 +1190,28 @@ impl Memfs {
         }
     }
 
+    // Returns the path the given file's history is kept under following any links
+    fn _history_path<T: AsRef<Path>>(&self, guard: &MemfsGuard, path: T) -> RvResult<PathBuf> {
+        let abs = self._abs(guard, path)?;
+        if guard.inner().history.contains_key(&abs) {
+            return Ok(abs);
+        }
+        let entry = self._clone_entry(guard, &abs)?;
+        if !entry.is_file() {
+            return Err(PathError::is_not_file(&abs).into());
+        }
+        Ok(if entry.is_symlink() { entry.alt() } else { entry.path() }.to_path_buf())
+    }
+
     // Execute copy with the given [`CopyOpts`] option
     fn _copy(&self, guard: &mut MemfsGuard, cp: sys::CopyOpts) -> RvResult<()> {
         // Resolve abs paths
         let src_root = self._abs(guard, &cp.src)?;
         let dst_root = self._abs(guard, &cp.dst)?;
 
-        // Detect source is destination
-        if src_root == dst_root {
+        // Detect source is destination including aliases through links
+        if src_root == dst_root || self._same_file(guard, &src_root, &dst_root) {
+            cp.record(|x| x.skipped.push(src_root));
             return Ok(());
         }
 
@@ -424,27 +1230,70 @@ impl Memfs {
 
         // Iterate over source taking into account link following
         let src_root = self._clone_entry(guard, src_root)?.follow(cp.follow);
-        for entry in self._entries(guard, src_root.path())?.follow(cp.follow) {
-            let src = entry?;
 
-            // Set destination path based on source path
-            let dst_path = if copy_into {
-                dst_root.mash(src.path().trim_prefix(src_root.path().dir()?))
-            } else {
-                dst_root.mash(src.path().trim_prefix(src_root.path()))
+        // Map source paths to their destination paths
+        let dst_of = |path: &Path| -> RvResult<Option<PathBuf>> {
+            let base = if copy_into { src_root.path().dir()? } else { src_root.path_buf() };
+            Ok(cp.dst_of(&dst_root, &path.trim_prefix(base)))
+        };
+
+        // Sum up the bytes to copy up front so progress can be reported against a total
+        if let Some(progress) = &cp.progress {
+            for src in self._entries(guard, src_root.path())?.follow(cp.follow).into_iter().flatten() {
+                if (cp.follow || !src.is_symlink()) && !src.is_dir() && dst_of(src.path())?.is_some() {
+                    progress.add_total(src.size());
+                }
+            }
+        }
+
+        let entries = self._entries(guard, src_root.path())?.follow(cp.follow);
+        let mut apply = |src: &VfsEntry| -> RvResult<()> {
+            // Set destination path based on source path skipping stripped entries
+            let dst_path = match dst_of(src.path())? {
+                Some(x) => x,
+                None => {
+                    cp.record(|x| x.skipped.push(src.path_buf()));
+                    return Ok(());
+                },
+            };
+
+            // Optionally carry over the security context of the source
+            let context = match cp.context {
+                true => guard.get_entry(src.path()).and_then(|x| x.context.clone()),
+                false => None,
             };
 
             // Recreate links if were not following them
             if !cp.follow && src.is_symlink() {
-                self._symlink(guard, dst_path, src.alt())?;
+                let target = src.alt();
+                let inside = target.starts_with(src_root.path());
+                if cp.rewrite && inside {
+                    self._symlink(guard, &dst_path, dst_of(target)?.unwrap_or_else(|| target.to_owned()))?;
+                    guard.set_context(&dst_path, context);
+                    cp.record(|x| x.symlinks.push(dst_path));
+                } else if cp.deref && !inside && guard.contains_entry(target) {
+                    let cp = sys::CopyOpts { src: target.to_owned(), dst: dst_path, strip: 0, ..cp.clone() };
+                    self._copy(guard, cp)?;
+                } else {
+                    self._symlink(guard, &dst_path, target)?;
+                    guard.set_context(&dst_path, context);
+                    cp.record(|x| x.symlinks.push(dst_path));
+                }
             } else {
                 // `follow`, i.e. pass through to target for links else get a fresh
                 // copy of the same entry which should be fast as we still have a lock
                 let src = self._clone_entry(guard, src.path())?;
 
                 // Create the directory using the given mode or src mode
+                // Flattening skips all directories but the destination itself
                 if src.is_dir() {
-                    self._mkdir_m(guard, &dst_path, dir_mode.or(Some(src.mode())))?;
+                    if !cp.flatten || dst_path == dst_root {
+                        self._mkdir_m(guard, &dst_path, dir_mode.or(Some(src.mode())))?;
+                        guard.set_context(&dst_path, context);
+                        cp.record(|x| x.dirs.push(dst_path));
+                    } else {
+                        cp.record(|x| x.skipped.push(src.path_buf()));
+                    }
                 } else {
                     // Copying into a directory might require creating it first
                     if !guard.contains_entry(&dst_path.dir()?) {
@@ -456,28 +1305,90 @@ impl Memfs {
                                 None => Some(self._clone_entry(guard, src.path().dir()?)?.mode()),
                             },
                         )?;
+                        cp.record(|x| x.dirs.push(dst_path.dir().unwrap_or_default()));
+                    }
+
+                    // Hard link the file when directed sharing the source's identity and data the
+                    // same way `hardlink` does replacing any existing destination entry. Memfs has
+                    // no devices so there is nothing to fall back on.
+                    if cp.link && !src.is_symlink() {
+                        let mut dst = src.clone();
+                        dst.path.clone_from(&dst_path);
+                        self._add(guard, dst.clone())?;
+                        guard.insert_entry(dst_path.clone(), dst);
+                        guard.remove_file(&dst_path);
+                        guard.link_file(src.path(), dst_path.clone());
+                        cp.record(|x| x.linked.push(dst_path));
+                        if let Some(progress) = &cp.progress {
+                            progress.add_file(src.path(), src.size());
+                        }
+                        return Ok(());
                     }
 
-                    // Clone the src entry and override its paths
+                    // Clone the src entry and override its paths and identity. Like a plain
+                    // `cp` the copy doesn't carry over the ACL, the immutable flag and only
+                    // optionally the context.
                     let mut dst = src.clone();
                     dst.path.clone_from(&dst_path);
+                    dst.meta.id = next_entry_id();
+                    dst.acl = None;
+                    dst.context = context;
+                    dst.immutable = false;
 
                     // Update mode as directed
                     dst.set_mode(file_mode.or(Some(src.mode())));
 
                     // Add the new dst entry to the filesystem
+                    if !src.is_symlink() {
+                        guard.check_mutable(&dst_path, true)?;
+                        guard.check_quota(&dst_path, src.size(), false)?;
+                    }
                     self._add(guard, dst)?;
 
                     // Copy the src file over as well
                     if !src.is_symlink() {
                         let dst_file = self._clone_file(guard, src.path())?;
-                        guard.insert_file(dst_path, dst_file);
+                        if let Some(ctx) = &cp.ctx {
+                            ctx.add_bytes(src.size());
+                        }
+                        guard.insert_file(dst_path.clone(), dst_file);
+                        guard.record_version(&dst_path);
+                        if let Some(progress) = &cp.progress {
+                            progress.add_file(src.path(), src.size());
+                        }
                     }
+
+                    // Memfs always clones the data so the strategy is only reported as directed
+                    cp.record(|x| {
+                        match (src.is_symlink(), cp.reflink) {
+                            (true, _) => x.symlinks.push(dst_path),
+                            (false, sys::Reflink::Never) => x.copied.push(dst_path),
+                            (false, _) => x.cloned.push(dst_path),
+                        }
+                        x.bytes += if src.is_symlink() { 0 } else { src.size() };
+                    });
                 }
             }
-        }
+            Ok(())
+        };
 
-        Ok(())
+        let mut batch = Batch::new(cp.cont);
+        for entry in entries {
+            // Cancellation aborts the copy even when continuing on error
+            if let Some(ctx) = &cp.ctx {
+                ctx.check()?;
+            }
+            let src = match batch.check(src_root.path(), entry)? {
+                Some(x) => x,
+                None => continue,
+            };
+            if batch.check(src.path(), apply(&src))?.is_some() {
+                if let Some(ctx) = &cp.ctx {
+                    ctx.add_entry(src.path());
+                }
+            }
+        }
+        batch.finish()
     }
 
     /// Uses `_clone_entries` to make a copy of the tree branch that is implicated and returns it as
@@ -487,9 +1398,9 @@ impl Memfs {
     #[allow(clippy::type_complexity)]
     pub(crate) fn _entry_iter<T: AsRef<Path>>(
         &self, guard: &MemfsGuard, path: T,
-    ) -> RvResult<Box<dyn Fn(&Path, bool) -> RvResult<EntryIter> + Send + Sync + 'static>> {
+    ) -> RvResult<Arc<dyn Fn(&Path, bool) -> RvResult<EntryIter> + Send + Sync + 'static>> {
         let entries = Arc::new(self._clone_entries(guard, path)?);
-        Ok(Box::new(move |path: &Path, follow: bool| -> RvResult<EntryIter> {
+        Ok(Arc::new(move |path: &Path, follow: bool| -> RvResult<EntryIter> {
             let entries = entries.clone();
             Ok(EntryIter {
                 path: path.to_path_buf(),
@@ -507,19 +1418,34 @@ impl Memfs {
     pub(crate) fn _entries<T: AsRef<Path>>(&self, guard: &MemfsGuard, path: T) -> RvResult<Entries> {
         // Clone the target entry
         let path = self._abs(guard, path)?;
-        let entry = match guard.get_entry(&path) {
-            Some(entry) => entry.clone().upcast(),
-            None => return Err(PathError::does_not_exist(&path).into()),
-        };
+        let entry = self._clone_entry(guard, &path)?.upcast();
 
         Ok(Entries {
             root: entry,
             dirs: false,
             files: false,
+            symlinks: false,
             follow: false,
+            skip_vanished: true,
             min_depth: 0,
             max_depth: usize::MAX,
-            max_descriptors: sys::DEFAULT_MAX_DESCRIPTORS,
+            max_links: sys::DEFAULT_MAX_LINKS,
+            max_items: usize::MAX,
+            timeout: None,
+            ctx: None,
+            modified_after: None,
+            modified_before: None,
+            created_after: None,
+            larger_than: None,
+            smaller_than: None,
+            #[cfg(feature = "regex")]
+            contents_match: None,
+            glob: None,
+            includes: vec![],
+            excludes: vec![],
+            max_content_size: guard.config().max_content_size,
+            max_descriptors: guard.config().max_descriptors,
+            parallel: 1,
             dirs_first: false,
             files_first: false,
             contents_first: false,
@@ -527,9 +1453,29 @@ impl Memfs {
             pre_op: None,
             sort: None,
             iter_from: self._entry_iter(guard, &path)?,
+            #[cfg(feature = "regex")]
+            read_from: {
+                let vfs = self.clone();
+                Box::new(move |path: &Path| vfs.read(path))
+            },
         })
     }
 
+    /// Execute the given operation wrapped with the registered pre and post operation hooks
+    ///
+    /// * Handles converting path to absolute form only when hooks have been registered
+    /// * Must be called without holding a guard as the hooks may operate on the filesystem
+    pub(crate) fn _hooked<T, F>(&self, op: VfsOp, path: &Path, f: F) -> RvResult<T>
+    where
+        F: FnOnce() -> RvResult<T>,
+    {
+        let hooks = self.read_guard().hooks();
+        if hooks.is_empty() {
+            return f();
+        }
+        hooks.exec(op, &self.abs(path)?, f)
+    }
+
     /// Returns true if the given path exists and is a directory
     ///
     /// * Handles path expansion and absolute path resolution
@@ -546,6 +1492,7 @@ impl Memfs {
     ///
     /// * path is required to be abs already
     fn _mkdir_m(&self, guard: &mut MemfsGuard, abs: &Path, mode: Option<u32>) -> RvResult<()> {
+        let mode = mode.or(Some(guard.config().dir_mode));
         let mut path = PathBuf::new();
         for component in abs.components() {
             path.push(component);
@@ -554,6 +1501,37 @@ impl Memfs {
         Ok(())
     }
 
+    /// Clone the given entry resolving its link chain, timestamps, file size and link count from the
+    /// filesystem
+    pub(crate) fn _resolve_entry(&self, guard: &MemfsGuard, entry: &MemfsEntry) -> MemfsEntry {
+        let mut entry = entry.clone();
+        if entry.is_symlink() {
+            entry.chain = self._target_chain(guard, &entry.path);
+            if let Some(target) = entry.chain.last().and_then(|x| guard.get_entry(x)) {
+                entry.meta = target.meta;
+                entry.modified = target.modified;
+                entry.created = target.created;
+                if target.is_dir() {
+                    entry.files = target.files.clone();
+                }
+            }
+        }
+        let target = entry.chain.last().unwrap_or(&entry.path);
+        if entry.is_file() {
+            entry.size = guard.get_file(target).map_or(0, |x| x.data.len() as u64);
+        }
+        entry.nlink = guard.nlink(target);
+        entry
+    }
+
+    /// Returns true if the given paths resolve to the same underlying entry
+    fn _same_file<T: AsRef<Path>, U: AsRef<Path>>(&self, guard: &MemfsGuard, a: T, b: U) -> bool {
+        match (self._clone_entry(guard, a), self._clone_entry(guard, b)) {
+            (Ok(a), Ok(b)) => a.meta == b.meta,
+            _ => false,
+        }
+    }
+
     /// Creates a new symbolic link
     ///
     /// * Handles path expansion and absolute path resolution
@@ -584,6 +1562,23 @@ impl Memfs {
 
         Ok(link)
     }
+
+    /// Walk the chain of links starting at the given path returning the absolute path of each link
+    /// target in order with the final target last.
+    ///
+    /// * Resolution stops one hop beyond DEFAULT_MAX_LINKS to allow for detecting looping links
+    pub(crate) fn _target_chain(&self, guard: &MemfsGuard, path: &Path) -> Vec<PathBuf> {
+        let mut chain: Vec<PathBuf> = vec![];
+        let mut link = path;
+        while let Some(entry) = guard.get_entry(link) {
+            if !entry.is_symlink() || chain.len() > sys::DEFAULT_MAX_LINKS {
+                break;
+            }
+            chain.push(entry.alt_buf());
+            link = entry.alt();
+        }
+        chain
+    }
 }
 
 impl fmt::Display for Memfs {
@@ -607,6 +1602,36 @@ impl fmt::Display for Memfs {
     }
 }
 
+// Lists the tree one entry per line so that `assert_eq!` failures show where two trees differ
+impl fmt::Debug for Memfs {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let guard = match self.0.try_read() {
+            Ok(x) => x,
+            Err(_) => return write!(f, "Memfs {{ <locked> }}"),
+        };
+        writeln!(f, "Memfs {{")?;
+        for x in Self::_dump(&guard) {
+            write!(f, "    {} {:o}", x.path.display(), x.mode)?;
+            if let Some(target) = x.target {
+                write!(f, " -> {}", target.display())?;
+            }
+            if let Some(file) = guard.file(&x.path).filter(|_| x.kind == Kind::File) {
+                write!(f, " {:?}", String::from_utf8_lossy(&file.data))?;
+            }
+            writeln!(f)?;
+        }
+        write!(f, "}}")
+    }
+}
+
+impl PartialEq for Memfs {
+    fn eq(&self, other: &Self) -> bool {
+        self.tree_eq(other)
+    }
+}
+
+impl Eq for Memfs {}
+
 impl VirtualFileSystem for Memfs {
     /// Return the path in an absolute clean form
     ///
@@ -629,6 +1654,35 @@ impl VirtualFileSystem for Memfs {
         self._abs(&self.read_guard(), path)
     }
 
+    /// Advise the kernel how the given file's data will be accessed
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Stdfs uses `posix_fadvise` on the whole file e.g. `Advice::DontNeed` drops the file from
+    ///   the page cache so that streaming scanners don't pollute it
+    /// * `Advice::Sequential` and `Advice::Random` only apply to the handle they are given on Linux
+    ///   so use `Reader::advise` for those instead
+    /// * Memfs has no page cache so this is a no-op
+    ///
+    /// ### Errors
+    /// * PathError::IsNotFile(PathBuf) when the given path isn't a file
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let file = vfs.root().mash("file");
+    /// assert_vfs_write_all!(vfs, &file, "this is a test");
+    /// assert!(vfs.advise(&file, Advice::DontNeed).is_ok());
+    /// assert_vfs_read_all!(vfs, &file, "this is a test");
+    /// ```
+    fn advise<T: AsRef<Path>>(&self, path: T, _advice: Advice) -> RvResult<()> {
+        // Validate the target is a file as there is no page cache to advise
+        self._clone_file(&self.read_guard(), path)?;
+        Ok(())
+    }
+
     /// Returns all dirs for the given path recursively
     ///
     /// * Results are sorted by filename, are distict and don't include the given path
@@ -647,16 +1701,10 @@ impl VirtualFileSystem for Memfs {
     /// assert_iter_eq(vfs.all_dirs(&tmpdir).unwrap(), vec![dir1, dir2]);
     /// ```
     fn all_dirs<T: AsRef<Path>>(&self, path: T) -> RvResult<Vec<PathBuf>> {
-        let mut paths: Vec<PathBuf> = vec![];
         if !self.is_dir(&path) {
             return Err(PathError::is_not_dir(&path).into());
         }
-        for entry in self.entries(path)?.min_depth(1).sort_by_name().dirs() {
-            let entry = entry?;
-            paths.push(entry.path_buf());
-        }
-
-        Ok(paths)
+        self.entries(path)?.min_depth(1).sort_by_name().collect_dirs()
     }
 
     /// Returns all files for the given path recursively
@@ -680,16 +1728,10 @@ impl VirtualFileSystem for Memfs {
     /// assert_iter_eq(vfs.all_files(&tmpdir).unwrap(), vec![file2, file1]);
     /// ```
     fn all_files<T: AsRef<Path>>(&self, path: T) -> RvResult<Vec<PathBuf>> {
-        let mut paths: Vec<PathBuf> = vec![];
         if !self.is_dir(&path) {
             return Err(PathError::is_not_dir(&path).into());
         }
-        for entry in self.entries(path)?.min_depth(1).sort_by_name().files() {
-            let entry = entry?;
-            paths.push(entry.path_buf());
-        }
-
-        Ok(paths)
+        self.entries(path)?.min_depth(1).sort_by_name().collect_files()
     }
 
     /// Returns all paths for the given path recursively
@@ -715,22 +1757,67 @@ impl VirtualFileSystem for Memfs {
     /// assert_iter_eq(vfs.all_paths(&tmpdir).unwrap(), vec![dir1, file2, file3, file1]);
     /// ```
     fn all_paths<T: AsRef<Path>>(&self, path: T) -> RvResult<Vec<PathBuf>> {
-        let mut paths: Vec<PathBuf> = vec![];
         if !self.is_dir(&path) {
             return Err(PathError::is_not_dir(&path).into());
         }
-        for entry in self.entries(path)?.min_depth(1).sort_by_name() {
-            let entry = entry?;
-            paths.push(entry.path_buf());
-        }
+        self.entries(path)?.min_depth(1).sort_by_name().collect_paths()
+    }
+
+    /// Allocate space for the given file up front to avoid fragmentation and fail early when there
+    /// isn't enough space
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Creates the file if it doesn't exist
+    /// * Grows the file to `len` bytes filled with zeros if smaller, existing data is left intact
+    /// * Stdfs uses `posix_fallocate` while Memfs reserves the file's buffer
+    ///
+    /// ### Errors
+    /// * PathError::IsNotDir(PathBuf) when the given path's parent exists but is not a directory
+    /// * PathError::DoesNotExist(PathBuf) when the given path's parent doesn't exist
+    /// * PathError::IsNotFile(PathBuf) when the given path exists but is not a file
+    /// * VfsError::QuotaExceeded(PathBuf) when Memfs quota would be exceeded
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let file = vfs.root().mash("file");
+    /// assert!(vfs.allocate(&file, 1024).is_ok());
+    /// assert_eq!(vfs.entry(&file).unwrap().size(), 1024);
+    /// ```
+    fn allocate<T: AsRef<Path>>(&self, path: T, len: u64) -> RvResult<()> {
+        self._hooked(VfsOp::Write, path.as_ref(), || {
+            let mut guard = self.write_guard();
 
-        Ok(paths)
+            // Make sure the file exists and may be written
+            let path = self._abs(&guard, &path)?;
+            self._add_file(&mut guard, &path)?;
+            guard.check_mutable(&path, true)?;
+
+            // Reserve and zero fill the additional space
+            let size = guard.get_file(&path).map_or(0, |x| x.data.len() as u64);
+            if len > size {
+                guard.check_quota(&path, len, false)?;
+                if let Some(file) = guard.get_file_mut(&path) {
+                    file.data.reserve_exact((len - size) as usize);
+                    file.data.resize(len as usize, 0);
+                }
+                if let Some(entry) = guard.get_entry_mut(&path) {
+                    entry.modified = SystemTime::now();
+                }
+                guard.record_version(&path);
+                guard.emit(VfsEvent::Write(path));
+            }
+            Ok(())
+        })
     }
 
     /// Opens a file in append mode
     ///
     /// * Handles path expansion and absolute path resolution
     /// * Creates a file if it does not exist or appends to it if it does
+    /// * Returns a [`VfsWriter`] reporting the path and number of bytes written
     ///
     /// ### Errors
     /// * PathError::IsNotDir(PathBuf) when the given path's parent exists but is not a directory
@@ -751,26 +1838,29 @@ impl VirtualFileSystem for Memfs {
     /// f.flush().unwrap();
     /// assert_vfs_read_all!(vfs, &file, "foobar123");
     /// ```
-    fn append<T: AsRef<Path>>(&self, path: T) -> RvResult<Box<dyn Write>> {
-        let mut guard = self.write_guard();
+    fn append<T: AsRef<Path>>(&self, path: T) -> RvResult<VfsWriter> {
+        self._hooked(VfsOp::Write, path.as_ref(), || -> RvResult<VfsWriter> {
+            let mut guard = self.write_guard();
 
-        // Make sure the file exists
-        let path = self._abs(&guard, path)?;
-        self._add(&mut guard, MemfsEntry::opts(&path).file().build())?;
-
-        if let Some(file) = guard.get_file(&path) {
-            // Clone the file to append to
-            let mut clone = file.clone();
-            clone.path = Some(path.clone());
-            // Set the filesystem callback to write out
-            clone.fs = Some(self.clone());
-
-            // Seek to the end for appending
-            clone.seek(SeekFrom::End(0))?;
-            Ok(Box::new(clone))
-        } else {
-            Err(PathError::does_not_exist(path).into())
-        }
+            // Make sure the file exists and may be written
+            let path = self._abs(&guard, &path)?;
+            self._add_file(&mut guard, &path)?;
+            guard.check_mutable(&path, true)?;
+
+            if let Some(file) = guard.get_file(&path) {
+                // Clone the file to append to
+                let mut clone = file.clone();
+                clone.path = Some(path.clone());
+                // Set the filesystem callback to write out
+                clone.fs = Some(self.clone());
+
+                // Seek to the end for appending
+                clone.seek(SeekFrom::End(0))?;
+                Ok(VfsWriter::memfs(path, clone))
+            } else {
+                Err(PathError::does_not_exist(path).into())
+            }
+        })
     }
 
     /// Append the given data to to the target file
@@ -914,7 +2004,9 @@ impl VirtualFileSystem for Memfs {
 
         // Construct the chmod closure callback
         let vfs = self.clone();
-        let exec_func = move |mode: ChmodOpts| -> RvResult<()> { vfs._chmod(mode) };
+        let exec_func = move |mode: ChmodOpts| -> RvResult<()> {
+            vfs._hooked(VfsOp::Chmod, &mode.path.clone(), || vfs._chmod(mode))
+        };
 
         // Return the new Chmod builder
         Ok(Chmod {
@@ -923,8 +2015,12 @@ impl VirtualFileSystem for Memfs {
                 dirs: 0,
                 files: 0,
                 follow: false,
+                links: false,
                 recursive: true,
                 sym: "".to_string(),
+                umask: 0,
+                excludes: vec![],
+                cont: false,
             },
             exec: Box::new(exec_func),
         })
@@ -969,7 +2065,9 @@ impl VirtualFileSystem for Memfs {
 
         // Construct the closure callback
         let vfs = self.clone();
-        let exec_func = move |opts: ChownOpts| -> RvResult<()> { vfs._chown(opts) };
+        let exec_func = move |opts: ChownOpts| -> RvResult<()> {
+            vfs._hooked(VfsOp::Chown, &opts.path.clone(), || vfs._chown(opts))
+        };
 
         Ok(Chown {
             opts: ChownOpts {
@@ -977,12 +2075,30 @@ impl VirtualFileSystem for Memfs {
                 uid: None,
                 gid: None,
                 follow: false,
+                links: false,
                 recursive: true,
+                excludes: vec![],
+                cont: false,
             },
             exec: Box::new(exec_func),
         })
     }
 
+    /// Returns the config of the Vfs backend instance
+    ///
+    /// * Set with `Memfs::with_config` else the defaults apply
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Memfs::new();
+    /// assert_eq!(vfs.config(), VfsConfig::default());
+    /// ```
+    fn config(&self) -> VfsConfig {
+        self.read_guard().config()
+    }
+
     /// Returns the highest priority active configuration directory.
     ///
     /// * Searches first the $XDG_CONFIG_HOME directory, then the $XDG_CONFIG_DIRS directories.
@@ -1068,8 +2184,10 @@ impl VirtualFileSystem for Memfs {
         // Construct the copy closure callback
         let vfs = self.clone();
         let exec_func = move |cp: sys::CopyOpts| -> RvResult<()> {
-            let mut guard = vfs.write_guard();
-            vfs._copy(&mut guard, cp)
+            vfs._hooked(VfsOp::Write, &cp.dst.clone(), || {
+                let mut guard = vfs.write_guard();
+                vfs._copy(&mut guard, cp)
+            })
         };
 
         // Return the new Copy builder
@@ -1081,6 +2199,17 @@ impl VirtualFileSystem for Memfs {
                 cdirs: Default::default(),
                 cfiles: Default::default(),
                 follow: Default::default(),
+                link: Default::default(),
+                reflink: Default::default(),
+                rewrite: Default::default(),
+                deref: Default::default(),
+                cont: Default::default(),
+                ctx: Default::default(),
+                flatten: Default::default(),
+                strip: Default::default(),
+                context: Default::default(),
+                progress: Default::default(),
+                report: Default::default(),
             },
             exec: Box::new(exec_func),
         })
@@ -1124,15 +2253,40 @@ impl VirtualFileSystem for Memfs {
     /// assert_iter_eq(vfs.dirs(&tmpdir).unwrap(), vec![dir1, dir2]);
     /// ```
     fn dirs<T: AsRef<Path>>(&self, path: T) -> RvResult<Vec<PathBuf>> {
-        let mut paths: Vec<PathBuf> = vec![];
         if !self.is_dir(&path) {
             return Err(PathError::is_not_dir(&path).into());
         }
-        for entry in self.entries(path)?.min_depth(1).max_depth(1).sort_by_name().dirs() {
-            let entry = entry?;
-            paths.push(entry.path_buf());
-        }
-        Ok(paths)
+        self.entries(path)?.min_depth(1).max_depth(1).sort_by_name().collect_dirs()
+    }
+
+    /// Creates a new [`DiskUsage`] for summing up the size of the given path much like `du`
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Recursively sums the apparent size of the files in the tree counting hard links once
+    /// * Options for excluding globs and following links
+    /// * Execute by calling `exec`
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Memfs::new();
+    /// let tmpdir = vfs.root();
+    /// let file1 = tmpdir.mash("file1");
+    /// let file2 = tmpdir.mash("dir/file2");
+    /// assert_vfs_mkdir_p!(vfs, tmpdir.mash("dir"));
+    /// assert_vfs_write_all!(vfs, &file1, "foo");
+    /// assert_vfs_write_all!(vfs, &file2, "foobar");
+    /// assert_eq!(vfs.disk_usage(&tmpdir).unwrap().exec().unwrap(), 9);
+    /// ```
+    fn disk_usage<T: AsRef<Path>>(&self, path: T) -> RvResult<DiskUsage> {
+        let path = self.abs(path)?;
+
+        // Construct the closure callback
+        let vfs = self.clone();
+        let exec_func = move |opts: DiskUsageOpts| -> RvResult<u64> { vfs._disk_usage(opts) };
+
+        Ok(DiskUsage { opts: DiskUsageOpts { path, follow: false, excludes: vec![] }, exec: Box::new(exec_func) })
     }
 
     /// Returns an iterator over the given path
@@ -1172,32 +2326,186 @@ impl VirtualFileSystem for Memfs {
     /// assert!(vfs.entry(&file).unwrap().is_file());
     /// ```
     fn entry<T: AsRef<Path>>(&self, path: T) -> RvResult<VfsEntry> {
-        match self._clone_entry(&self.read_guard(), path) {
-            Ok(x) => Ok(x.upcast()),
-            Err(e) => Err(e),
+        let entry = self._clone_entry(&self.read_guard(), path)?;
+        if entry.link_hops() > sys::DEFAULT_MAX_LINKS {
+            return Err(PathError::too_many_links(entry.path()).into());
         }
+        Ok(entry.upcast())
     }
 
-    /// Returns true if the `path` exists
+    /// Ensures the given directory exists with the given mode and ownership
     ///
     /// * Handles path expansion and absolute path resolution
+    /// * Creates the directory and any parent directories needed if it doesn't exist
+    /// * Only changes the mode and ownership when they differ from those given
+    /// * Links are followed so the directories they point to are affected
+    /// * Returns true if anything was changed
+    ///
+    /// ### Errors
+    /// * PathError::IsNotDir(PathBuf) when the given path exists but is not a directory
     ///
     /// ### Examples
     /// ```
     /// use rivia::prelude::*;
     ///
     /// let vfs = Vfs::memfs();
-    /// let dir = vfs.root().mash("foo");
-    /// assert_eq!(vfs.exists(&dir), false);
-    /// assert_vfs_mkdir_p!(vfs, &dir);
-    /// assert_eq!(vfs.exists(&dir), true);
+    /// let dir = vfs.root().mash("dir");
+    /// assert_eq!(vfs.ensure_dir(&dir, 0o700, 5, 5).unwrap(), true);
+    /// assert_eq!(vfs.ensure_dir(&dir, 0o700, 5, 5).unwrap(), false);
+    /// assert_eq!(vfs.mode(&dir).unwrap(), 0o40700);
+    /// assert_eq!(vfs.owner(&dir).unwrap(), (5, 5));
     /// ```
-    fn exists<T: AsRef<Path>>(&self, path: T) -> bool {
+    fn ensure_dir<T: AsRef<Path>>(&self, path: T, mode: u32, uid: u32, gid: u32) -> RvResult<bool> {
+        let path = self.abs(path)?;
+        let mut changed = false;
+        if !self.exists(&path) {
+            self.mkdir_p(&path)?;
+            changed = true;
+        } else if !self.is_dir(&path) {
+            return Err(PathError::is_not_dir(&path).into());
+        }
+        // Memfs links carry their own mode and ownership so operate on the final target
+        let path = self.entry(&path)?.target_chain().last().cloned().unwrap_or(path);
+        if self.mode(&path)? & 0o7777 != mode & 0o7777 {
+            self.chmod_b(&path)?.no_recurse().mode_str(&format!("{:o}", mode & 0o7777)).exec()?;
+            changed = true;
+        }
+        if self.owner(&path)? != (uid, gid) {
+            self.chown_b(&path)?.recurse(false).owner(uid, gid).exec()?;
+            changed = true;
+        }
+        Ok(changed)
+    }
+
+    /// Ensures the given file exists with the given data and mode
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Only writes the data when it differs from the file's current content
+    /// * Only changes the mode when it differs from the one given
+    /// * Links are followed so the files they point to are affected
+    /// * Returns true if anything was changed
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path's parent doesn't exist
+    /// * PathError::IsNotFile(PathBuf) when the given path exists but is not a file
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let file = vfs.root().mash("file");
+    /// assert_eq!(vfs.ensure_file(&file, "foo", 0o600).unwrap(), true);
+    /// assert_eq!(vfs.ensure_file(&file, "foo", 0o600).unwrap(), false);
+    /// assert_vfs_read_all!(vfs, &file, "foo");
+    /// assert_eq!(vfs.mode(&file).unwrap(), 0o100600);
+    /// ```
+    fn ensure_file<T: AsRef<Path>, U: AsRef<[u8]>>(&self, path: T, data: U, mode: u32) -> RvResult<bool> {
+        // Links are resolved up front so that the checks and writes all apply to the final target
+        let path = self.resolve_link(path, sys::DEFAULT_MAX_LINKS)?.target;
+        let data = data.as_ref();
+        let mut changed = false;
+        let exists = self.exists(&path);
+        if exists && !self.is_file(&path) {
+            return Err(PathError::is_not_file(&path).into());
+        }
+
+        // Only write the data when it differs from the current content
+        let mut current = vec![];
+        if exists {
+            self.read(&path)?.read_to_end(&mut current)?;
+        }
+        if !exists || current != data {
+            self.write_all(&path, data)?;
+            changed = true;
+        }
+        if self.mode(&path)? & 0o7777 != mode & 0o7777 {
+            self.chmod_b(&path)?.no_recurse().mode_str(&format!("{:o}", mode & 0o7777)).exec()?;
+            changed = true;
+        }
+        Ok(changed)
+    }
+
+    /// Ensures the given link exists and points to the given target
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Creates the link if it doesn't exist
+    /// * Replaces the link if it points somewhere else
+    /// * Dangling links are supported
+    /// * Returns true if anything was changed
+    ///
+    /// ### Errors
+    /// * PathError::IsNotSymlink(PathBuf) when the given link path exists but is not a link
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let link = vfs.root().mash("link");
+    /// assert_eq!(vfs.ensure_symlink(&link, "file1").unwrap(), true);
+    /// assert_eq!(vfs.ensure_symlink(&link, "file1").unwrap(), false);
+    /// assert_eq!(vfs.ensure_symlink(&link, "file2").unwrap(), true);
+    /// assert_eq!(vfs.readlink(&link).unwrap(), PathBuf::from("file2"));
+    /// ```
+    fn ensure_symlink<T: AsRef<Path>, U: AsRef<Path>>(&self, link: T, target: U) -> RvResult<bool> {
+        let link = self.abs(link)?;
+        match self.symlink_ensured(&link, &target) {
+            Ok(x) => Ok(x.created()),
+            Err(err) => {
+                // Only links are replaced never anything else
+                if !self.is_symlink(&link) {
+                    if self.exists(&link) {
+                        return Err(PathError::is_not_symlink(&link).into());
+                    }
+                    return Err(err);
+                }
+                self.remove(&link)?;
+                self.symlink(&link, target)?;
+                Ok(true)
+            },
+        }
+    }
+
+    /// Returns true if the `path` exists
+    ///
+    /// * Handles path expansion and absolute path resolution
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let dir = vfs.root().mash("foo");
+    /// assert_eq!(vfs.exists(&dir), false);
+    /// assert_vfs_mkdir_p!(vfs, &dir);
+    /// assert_eq!(vfs.exists(&dir), true);
+    /// ```
+    fn exists<T: AsRef<Path>>(&self, path: T) -> bool {
         let guard = self.read_guard();
         let abs = unwrap_or_false!(self._abs(&guard, path));
         guard.contains_entry(&abs)
     }
 
+    /// Returns whether each of the given `paths` exists in the same order
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Checks all paths in a single pass e.g. Memfs only acquires its lock once
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let dir = vfs.root().mash("dir");
+    /// assert_vfs_mkdir_p!(vfs, &dir);
+    /// assert_eq!(vfs.exists_all(&[vfs.root().mash("foo"), dir]), vec![false, true]);
+    /// ```
+    fn exists_all<T: AsRef<Path>>(&self, paths: &[T]) -> Vec<bool> {
+        let guard = self.read_guard();
+        paths.iter().map(|x| matches!(self._abs(&guard, x), Ok(abs) if guard.contains_entry(&abs))).collect()
+    }
+
     /// Returns all files for the given path, sorted by name
     ///
     /// * Handles path expansion and absolute path resolution
@@ -1219,15 +2527,89 @@ impl VirtualFileSystem for Memfs {
     /// assert_iter_eq(vfs.files(&tmpdir).unwrap(), vec![file1, file2]);
     /// ```
     fn files<T: AsRef<Path>>(&self, path: T) -> RvResult<Vec<PathBuf>> {
-        let mut paths: Vec<PathBuf> = vec![];
         if !self.is_dir(&path) {
             return Err(PathError::is_not_dir(&path).into());
         }
-        for entry in self.entries(path)?.min_depth(1).max_depth(1).sort_by_name().files() {
-            let entry = entry?;
-            paths.push(entry.path_buf());
+        self.entries(path)?.min_depth(1).max_depth(1).sort_by_name().collect_files()
+    }
+
+    /// Returns the absolute path of the first of the given `paths` that exists
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Checks all paths in a single pass stopping at the first that exists
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let dir = vfs.root().mash("dir");
+    /// assert_vfs_mkdir_p!(vfs, &dir);
+    /// assert_eq!(vfs.first_existing(&[vfs.root().mash("foo"), dir.clone()]), Some(dir));
+    /// assert_eq!(vfs.first_existing(&["foo"]), None);
+    /// ```
+    fn first_existing<T: AsRef<Path>>(&self, paths: &[T]) -> Option<PathBuf> {
+        let guard = self.read_guard();
+        paths.iter().filter_map(|x| self._abs(&guard, x).ok()).find(|x| guard.contains_entry(x))
+    }
+
+    /// Returns the POSIX access control list of the given path
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Links are followed
+    /// * Paths without an ACL of their own return the minimal ACL matching their mode
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let file = vfs.root().mash("file");
+    /// assert!(vfs.mkfile_m(&file, 0o640).is_ok());
+    /// assert_eq!(vfs.get_acl(&file).unwrap(), Acl::from_mode(0o640));
+    /// ```
+    fn get_acl<T: AsRef<Path>>(&self, path: T) -> RvResult<Acl> {
+        let guard = self.read_guard();
+        let path = self._abs(&guard, path)?;
+        let path = self._target_chain(&guard, &path).pop().unwrap_or(path);
+        match guard.get_entry(&path) {
+            Some(entry) => Ok(match entry.acl.clone() {
+                Some(acl) => acl.with_mode(entry.mode),
+                None => Acl::from_mode(entry.mode),
+            }),
+            None => Err(PathError::does_not_exist(&path).into()),
+        }
+    }
+
+    /// Returns the SELinux security context of the given path
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Links aren't followed so that the context of the link itself is returned
+    /// * Returns None when the path isn't labeled e.g. SELinux isn't supported by the filesystem
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let file = vfs.root().mash("file");
+    /// assert_vfs_mkfile!(vfs, &file);
+    /// assert_eq!(vfs.get_selinux_context(&file).unwrap(), None);
+    /// ```
+    #[cfg(feature = "selinux")]
+    fn get_selinux_context<T: AsRef<Path>>(&self, path: T) -> RvResult<Option<String>> {
+        let guard = self.read_guard();
+        let path = self._abs(&guard, path)?;
+        match guard.get_entry(&path) {
+            Some(entry) => Ok(entry.context.clone()),
+            None => Err(PathError::does_not_exist(&path).into()),
         }
-        Ok(paths)
     }
 
     /// Returns the group ID of the owner of this file
@@ -1250,6 +2632,200 @@ impl VirtualFileSystem for Memfs {
         }
     }
 
+    /// Returns the paths matching the given glob pattern sorted by path
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * `*` matches any sequence of characters within a single path component
+    /// * `**` matches any sequence of characters across path components
+    /// * `?` matches any single character and `[abc]`, `[a-z]` or `[!a-z]` match a character class
+    /// * Paths are returned in absolute form and an empty list is returned when nothing matches
+    /// * Links aren't followed and directories that can't be read are skipped
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let dir = vfs.root().mash("dir");
+    /// let file1 = dir.mash("file1.conf");
+    /// let file2 = dir.mash("sub/file2.conf");
+    /// assert_vfs_mkdir_p!(vfs, dir.mash("sub"));
+    /// assert_vfs_mkfile!(vfs, &file1);
+    /// assert_vfs_mkfile!(vfs, &file2);
+    /// assert_eq!(vfs.glob(dir.mash("*.conf").to_string().unwrap()).unwrap(), vec![file1.clone()]);
+    /// assert_eq!(vfs.glob(dir.mash("**/*.conf").to_string().unwrap()).unwrap(), vec![file1, file2]);
+    /// ```
+    fn glob<T: AsRef<str>>(&self, pattern: T) -> RvResult<Vec<PathBuf>> {
+        sys::glob_paths(self, pattern.as_ref())
+    }
+
+    /// Creates a new hard link `dst` giving the data of the `src` file another name
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Links aren't followed i.e. a link to a symlink is a second name for the symlink itself
+    /// * Writes through either name are visible through the other and the data is only dropped once
+    ///   all of its names have been removed
+    /// * The mode, owner and times of each name are kept separately
+    /// * Returns the link path
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when `src` or the parent of `dst` doesn't exist
+    /// * PathError::IsNotFile(PathBuf) when `src` is a directory
+    /// * PathError::ExistsAlready(PathBuf) when `dst` already exists
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Memfs::new();
+    /// assert_vfs_write_all!(vfs, "file", "foobar");
+    /// assert_eq!(vfs.hardlink("file", "link").unwrap(), vfs.root().mash("link"));
+    /// assert_vfs_read_all!(vfs, "link", "foobar");
+    /// assert_eq!(vfs.nlink("file").unwrap(), 2);
+    /// ```
+    fn hardlink<T: AsRef<Path>, U: AsRef<Path>>(&self, src: T, dst: U) -> RvResult<PathBuf> {
+        let dst = dst.as_ref();
+        self._hooked(VfsOp::Create, dst, || {
+            let mut guard = self.write_guard();
+            let src = self._abs(&guard, &src)?;
+            let dst = self._abs(&guard, dst)?;
+            let mut entry = match guard.get_entry(&src) {
+                Some(x) if x.is_dir() && !x.is_symlink() => return Err(PathError::is_not_file(&src).into()),
+                Some(x) => x.clone(),
+                None => return Err(PathError::does_not_exist(&src).into()),
+            };
+            if guard.contains_entry(&dst) {
+                return Err(PathError::exists_already(&dst).into());
+            }
+            guard.check_mutable(&src, false)?;
+
+            // The link shares the entry's identity and data but not the empty data `_add` creates
+            entry.path.clone_from(&dst);
+            self._add(&mut guard, entry)?;
+            guard.remove_file(&dst);
+            guard.link_file(&src, dst.clone());
+            Ok(dst)
+        })
+    }
+
+    /// Register a hook to be called after every successful mutating operation
+    ///
+    /// * Hooks are given the operation's [`VfsOp`] classification and the absolute path
+    /// * Hooks are called in the order they were registered
+    /// * Useful for logging or tracing the filesystem effects of a function under test
+    ///
+    /// ### Examples
+    /// ```
+    /// use std::sync::Mutex;
+    ///
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Memfs::new();
+    /// let ops = Arc::new(Mutex::new(vec![]));
+    /// let ops_clone = ops.clone();
+    /// vfs.hook_post(move |op, path| ops_clone.lock().unwrap().push((op, path.to_path_buf())));
+    /// let file = vfs.root().mash("file");
+    /// assert_vfs_mkfile!(vfs, &file);
+    /// assert_eq!(*ops.lock().unwrap(), vec![(VfsOp::Create, file)]);
+    /// ```
+    fn hook_post<F: Fn(VfsOp, &Path) + Send + Sync + 'static>(&self, hook: F) {
+        let mut guard = self.0.write().unwrap();
+        Arc::make_mut(&mut guard.hooks).post.push(Arc::new(hook));
+    }
+
+    /// Register a hook to be called before every mutating operation with the ability to veto it
+    ///
+    /// * Hooks are given the operation's [`VfsOp`] classification and the absolute path
+    /// * Hooks are called in the order they were registered
+    /// * Returning an error from a hook vetoes the operation and is passed back to the caller
+    /// * Operations implicating multiple paths e.g. `move_p` call the hooks once for each path
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Memfs::new();
+    /// vfs.hook_pre(|_, path| match path.starts_with("/etc") {
+    ///     true => Err(VfsError::Unavailable.into()),
+    ///     false => Ok(()),
+    /// });
+    /// assert!(vfs.mkdir_p("/etc").is_err());
+    /// assert_vfs_no_exists!(vfs, "/etc");
+    /// assert_vfs_mkdir_p!(vfs, "/tmp");
+    /// ```
+    fn hook_pre<F: Fn(VfsOp, &Path) -> RvResult<()> + Send + Sync + 'static>(&self, hook: F) {
+        // Resolve links through a weak reference so the hooks don't keep the filesystem alive
+        let weak = Arc::downgrade(&self.0);
+        let readlink = move |path: &Path| match weak.upgrade() {
+            Some(inner) => sys::link_target(&Memfs(inner), path),
+            None => Ok(None),
+        };
+        let mut guard = self.0.write().unwrap();
+        let hooks = Arc::make_mut(&mut guard.hooks);
+        hooks.readlink.get_or_insert_with(|| Arc::new(readlink));
+        hooks.pre.push(Arc::new(hook));
+    }
+
+    /// Register a hook to be called after every mutating operation slower than the given duration
+    ///
+    /// * Hooks are given the operation's [`VfsOp`] classification, the absolute path and the time
+    ///   the operation took e.g. to report operations stalling on a hung network filesystem
+    /// * Covers the same mutating operations as the pre and post hooks, reads and stats aren't timed
+    /// * Hooks are called once the operation returns, whether it succeeded or not, and can neither
+    ///   interrupt nor fail it so an operation that never returns is never reported
+    ///
+    /// ### Examples
+    /// ```
+    /// use std::{sync::Mutex, time::Duration};
+    ///
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Memfs::new();
+    /// let ops = Arc::new(Mutex::new(vec![]));
+    /// let ops_clone = ops.clone();
+    /// vfs.hook_slow(Duration::ZERO, move |op, path, _| ops_clone.lock().unwrap().push((op, path.to_path_buf())));
+    /// let file = vfs.root().mash("file");
+    /// assert_vfs_mkfile!(vfs, &file);
+    /// assert_eq!(*ops.lock().unwrap(), vec![(VfsOp::Create, file)]);
+    /// ```
+    fn hook_slow<F: Fn(VfsOp, &Path, Duration) + Send + Sync + 'static>(&self, after: Duration, hook: F) {
+        let mut guard = self.0.write().unwrap();
+        Arc::make_mut(&mut guard.hooks).slow.push((after, Arc::new(hook)));
+    }
+
+    /// Creates a new [`Installer`] for use with the builder pattern
+    ///
+    /// * `dst` will be installed into if it is an existing directory
+    /// * `dst` will be the installed file if it isn't an existing directory
+    /// * Handles path expansion and absolute path resolution
+    /// * Options for mode, ownership, backups and stripping leading source directories
+    /// * Execute by calling `exec`
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Memfs::new();
+    /// let file1 = vfs.root().mash("file1");
+    /// let file2 = vfs.root().mash("dir/file2");
+    /// assert_vfs_write_all!(vfs, &file1, "this is a test");
+    /// assert!(vfs.install(&file1, &file2).unwrap().mode(0o700).exec().is_ok());
+    /// assert_vfs_read_all!(vfs, &file2, "this is a test");
+    /// assert_eq!(vfs.mode(&file2).unwrap(), 0o100700);
+    /// ```
+    fn install<T: AsRef<Path>, U: AsRef<Path>>(&self, src: T, dst: U) -> RvResult<Installer> {
+        let vfs = self.clone();
+        Ok(Installer {
+            opts: sys::InstallOpts {
+                src: src.as_ref().to_owned(),
+                dst: dst.as_ref().to_owned(),
+                ..Default::default()
+            },
+            exec: Box::new(move |opts| sys::install_exec(&vfs, opts)),
+            notify: None,
+        })
+    }
+
     /// Returns true if the given path exists and is readonly
     ///
     /// * Handles path expansion and absolute path resolution
@@ -1317,6 +2893,59 @@ impl VirtualFileSystem for Memfs {
         }
     }
 
+    /// Returns true if the given path exists and is a hard link i.e. its data has more than one name
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Links aren't followed
+    /// * As all names of the data are equal the original file is a hard link as well
+    /// * Directories always return false
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Memfs::new();
+    /// assert_vfs_mkfile!(vfs, "file");
+    /// assert_eq!(vfs.is_hardlink("file"), false);
+    /// assert_vfs_hardlink!(vfs, "file", "link");
+    /// assert_eq!(vfs.is_hardlink("file"), true);
+    /// assert_eq!(vfs.is_hardlink("link"), true);
+    /// ```
+    fn is_hardlink<T: AsRef<Path>>(&self, path: T) -> bool {
+        let guard = self.read_guard();
+        let abs = unwrap_or_false!(self._abs(&guard, path));
+        match guard.get_entry(&abs) {
+            Some(entry) => (!entry.is_dir() || entry.is_symlink()) && guard.nlink(&abs) > 1,
+            None => false,
+        }
+    }
+
+    /// Returns true if the given path exists and is immutable i.e. `chattr +i`
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Links are followed
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Memfs::new();
+    /// let file = vfs.root().mash("file");
+    /// assert_vfs_mkfile!(vfs, &file);
+    /// assert_eq!(vfs.is_immutable(&file), false);
+    /// assert!(vfs.set_immutable(&file, true).is_ok());
+    /// assert_eq!(vfs.is_immutable(&file), true);
+    /// ```
+    fn is_immutable<T: AsRef<Path>>(&self, path: T) -> bool {
+        let guard = self.read_guard();
+        let abs = unwrap_or_false!(self._abs(&guard, path));
+        let abs = self._target_chain(&guard, &abs).pop().unwrap_or(abs);
+        match guard.get_entry(&abs) {
+            Some(entry) => entry.immutable,
+            None => false,
+        }
+    }
+
     /// Returns true if the given path exists and is readonly
     ///
     /// * Handles path expansion and absolute path resolution
@@ -1427,10 +3056,55 @@ impl VirtualFileSystem for Memfs {
         }
     }
 
-    /// Creates the given directory and any parent directories needed with the given mode
+    /// Returns the number of lines in the given file without reading it all into memory
     ///
-    /// ### Examples
-    /// ```
+    /// * Handles path expansion and absolute path resolution
+    /// * Counts a final line without a trailing newline i.e. matches the length of `read_lines`
+    ///
+    /// ### Errors
+    /// * PathError::IsNotFile(PathBuf) when the given path isn't a file
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Memfs::new();
+    /// let file = vfs.root().mash("file");
+    /// assert_vfs_write_all!(vfs, &file, "1\n2");
+    /// assert_eq!(vfs.line_count(&file).unwrap(), 2);
+    /// ```
+    fn line_count<T: AsRef<Path>>(&self, path: T) -> RvResult<u64> {
+        Ok(sys::wc_reader(self.read(path)?)?.lines)
+    }
+
+    /// Returns the filesystem properties of the given path with a single lookup
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Links report the properties of the target they point to except for `link`
+    /// * Access times aren't tracked so the modified time is reported instead
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Memfs::new();
+    /// assert_vfs_write_all!(vfs, "file", "foobar");
+    /// let meta = vfs.metadata("file").unwrap();
+    /// assert_eq!((meta.file, meta.size, meta.nlink), (true, 6, 1));
+    /// ```
+    fn metadata<T: AsRef<Path>>(&self, path: T) -> RvResult<VfsMetadata> {
+        let guard = self.read_guard();
+        Ok(self._clone_entry(&guard, path)?.metadata())
+    }
+
+    /// Creates the given directory and any parent directories needed with the given mode
+    ///
+    /// ### Examples
+    /// ```
     /// use rivia::prelude::*;
     ///
     /// let vfs = Vfs::memfs();
@@ -1439,10 +3113,12 @@ impl VirtualFileSystem for Memfs {
     /// assert_eq!(vfs.mode(&dir).unwrap(), 0o40555);
     /// ```
     fn mkdir_m<T: AsRef<Path>>(&self, path: T, mode: u32) -> RvResult<PathBuf> {
-        let mut guard = self.write_guard();
-        let abs = self._abs(&guard, path)?;
-        self._mkdir_m(&mut guard, &abs, Some(mode))?;
-        Ok(abs)
+        self._hooked(VfsOp::Create, path.as_ref(), || {
+            let mut guard = self.write_guard();
+            let abs = self._abs(&guard, &path)?;
+            self._mkdir_m(&mut guard, &abs, Some(mode))?;
+            Ok(abs)
+        })
     }
 
     /// Creates the given directory and any parent directories needed
@@ -1463,10 +3139,43 @@ impl VirtualFileSystem for Memfs {
     /// assert_vfs_is_dir!(vfs, &dir);
     /// ```
     fn mkdir_p<'a, T: AsRef<Path>>(&self, path: T) -> RvResult<PathBuf> {
-        let mut guard = self.write_guard();
-        let abs = self._abs(&guard, path)?;
-        self._mkdir_m(&mut guard, &abs, None)?;
-        Ok(abs)
+        self._hooked(VfsOp::Create, path.as_ref(), || {
+            let mut guard = self.write_guard();
+            let abs = self._abs(&guard, &path)?;
+            self._mkdir_m(&mut guard, &abs, None)?;
+            Ok(abs)
+        })
+    }
+
+    /// Wraps `mkdir_p` reporting whether the directory was created or already existed
+    ///
+    /// * Handles path expansion and absolute path resolution
+    ///
+    /// ### Errors
+    /// * PathError::IsNotDir(PathBuf) when the path already exists and is not a directory
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let dir = vfs.root().mash("dir");
+    /// assert_eq!(vfs.mkdir_p_ensured(&dir).unwrap(), Ensured::Created(dir.clone()));
+    /// assert_eq!(vfs.mkdir_p_ensured(&dir).unwrap(), Ensured::Existed(dir.clone()));
+    /// ```
+    fn mkdir_p_ensured<T: AsRef<Path>>(&self, path: T) -> RvResult<Ensured> {
+        self._hooked(VfsOp::Create, path.as_ref(), || {
+            let mut guard = self.write_guard();
+            let abs = self._abs(&guard, &path)?;
+            if let Some(entry) = guard.get_entry(&abs) {
+                return match entry.is_dir() {
+                    true => Ok(Ensured::Existed(abs)),
+                    false => Err(PathError::is_not_dir(abs).into()),
+                };
+            }
+            self._mkdir_m(&mut guard, &abs, None)?;
+            Ok(Ensured::Created(abs))
+        })
     }
 
     /// Create an empty file similar to the linux touch command
@@ -1490,9 +3199,44 @@ impl VirtualFileSystem for Memfs {
     /// assert_vfs_is_file!(vfs, &file);
     /// ```
     fn mkfile<T: AsRef<Path>>(&self, path: T) -> RvResult<PathBuf> {
-        let mut guard = self.write_guard();
-        let path = self._abs(&guard, path)?;
-        self._add(&mut guard, MemfsEntry::opts(path).file().build())
+        self._hooked(VfsOp::Create, path.as_ref(), || {
+            let mut guard = self.write_guard();
+            let path = self._abs(&guard, &path)?;
+            self._add_file(&mut guard, &path)
+        })
+    }
+
+    /// Wraps `mkfile` reporting whether the file was created or already existed
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Links to files are considered to be files
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path's parent doesn't exist
+    /// * PathError::IsNotDir(PathBuf) when the given path's parent isn't a directory
+    /// * PathError::IsNotFile(PathBuf) when the given path exists but isn't a file
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let file = vfs.root().mash("file");
+    /// assert_eq!(vfs.mkfile_ensured(&file).unwrap(), Ensured::Created(file.clone()));
+    /// assert_eq!(vfs.mkfile_ensured(&file).unwrap(), Ensured::Existed(file.clone()));
+    /// ```
+    fn mkfile_ensured<T: AsRef<Path>>(&self, path: T) -> RvResult<Ensured> {
+        self._hooked(VfsOp::Create, path.as_ref(), || {
+            let mut guard = self.write_guard();
+            let path = self._abs(&guard, &path)?;
+            if let Some(entry) = guard.get_entry(&path) {
+                return match entry.is_file() {
+                    true => Ok(Ensured::Existed(path)),
+                    false => Err(PathError::is_not_file(path).into()),
+                };
+            }
+            Ok(Ensured::Created(self._add_file(&mut guard, &path)?))
+        })
     }
 
     /// Wraps `mkfile` allowing for setting the file's mode.
@@ -1507,13 +3251,15 @@ impl VirtualFileSystem for Memfs {
     /// assert_eq!(vfs.mode(&file).unwrap(), 0o100555);
     /// ```
     fn mkfile_m<T: AsRef<Path>>(&self, path: T, mode: u32) -> RvResult<PathBuf> {
-        let path = {
-            let mut guard = self.write_guard();
-            let path = self._abs(&guard, path)?;
-            self._add(&mut guard, MemfsEntry::opts(path).file().build())?
-        };
-        self.chmod(&path, mode)?;
-        Ok(path)
+        self._hooked(VfsOp::Create, path.as_ref(), || {
+            let path = {
+                let mut guard = self.write_guard();
+                let path = self._abs(&guard, &path)?;
+                self._add_file(&mut guard, &path)?
+            };
+            self.chmod(&path, mode)?;
+            Ok(path)
+        })
     }
 
     /// Returns the permissions for a file, directory or link
@@ -1544,6 +3290,43 @@ impl VirtualFileSystem for Memfs {
         }
     }
 
+    /// Creates a new [`Mover`] for use with the builder pattern
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Always moves `src` into `dst` if `dst` is an existing directory
+    /// * Replaces destination files if they exist
+    /// * Falls back on copy and remove when moving across filesystems
+    /// * Options for preserving attributes
+    /// * Execute by calling `exec`
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Memfs::new();
+    /// let file1 = vfs.root().mash("file1");
+    /// let file2 = vfs.root().mash("file2");
+    /// assert_vfs_write_all!(vfs, &file1, "this is a test");
+    /// assert!(vfs.move_b(&file1, &file2).unwrap().exec().is_ok());
+    /// assert_vfs_no_exists!(vfs, &file1);
+    /// assert_vfs_read_all!(vfs, &file2, "this is a test");
+    /// ```
+    fn move_b<T: AsRef<Path>, U: AsRef<Path>>(&self, src: T, dst: U) -> RvResult<Mover> {
+        // Construct the move closure callback
+        let vfs = self.clone();
+        let exec_func = move |opts: MoveOpts| -> RvResult<()> { vfs.move_p(&opts.src, &opts.dst) };
+
+        // Return the new Mover builder
+        Ok(Mover {
+            opts: MoveOpts {
+                src: src.as_ref().to_owned(),
+                dst: dst.as_ref().to_owned(),
+                preserve: true,
+            },
+            exec: Box::new(exec_func),
+        })
+    }
+
     /// Move a file or directory
     ///
     /// * Handles path expansion and absolute path resolution
@@ -1568,54 +3351,166 @@ impl VirtualFileSystem for Memfs {
     /// assert_vfs_is_file!(vfs, &dirfile);
     /// ```
     fn move_p<T: AsRef<Path>, U: AsRef<Path>>(&self, src: T, dst: U) -> RvResult<()> {
-        let mut guard = self.write_guard();
-        let src_root = self._abs(&guard, src)?;
-        let dst_root = self._abs(&guard, dst)?;
-        let copy_into = self._is_dir(&guard, &dst_root);
-
-        let mut paths = vec![src_root.clone()];
-        while let Some(src_path) = paths.pop() {
-            let dst_path = if copy_into {
-                dst_root.mash(src_path.trim_prefix(src_root.dir()?))
-            } else {
-                dst_root.mash(src_path.trim_prefix(&src_root))
-            };
-
-            // 1. Move the entry to its new `dst_path`
-            let src_entry = if let Some(mut dst_entry) = guard.remove_entry(&src_path) {
-                let src_entry = dst_entry.clone();
-                dst_entry.path.clone_from(&dst_path);
-                guard.insert_entry(dst_path.clone(), dst_entry);
-                src_entry
-            } else {
-                return Err(PathError::does_not_exist(src_path).into());
-            };
+        self._hooked(VfsOp::Rename, src.as_ref(), || {
+            self._hooked(VfsOp::Rename, dst.as_ref(), || {
+                let mut guard = self.write_guard();
+                let src_root = self._abs(&guard, &src)?;
+                let dst_root = self._abs(&guard, &dst)?;
+                let copy_into = self._is_dir(&guard, &dst_root);
+                let dst_top = if copy_into { dst_root.mash(src_root.base()?) } else { dst_root.clone() };
+
+                // Immutable entries and directories can't be renamed from, into or over
+                for path in [&src_root, &src_root.dir()?, &dst_top.dir()?, &dst_top] {
+                    guard.check_mutable(path, false)?;
+                }
+                let event = VfsEvent::Rename(src_root.clone(), dst_top);
+
+                let mut paths = vec![src_root.clone()];
+                while let Some(src_path) = paths.pop() {
+                    let dst_path = if copy_into {
+                        dst_root.mash(src_path.trim_prefix(src_root.dir()?))
+                    } else {
+                        dst_root.mash(src_path.trim_prefix(&src_root))
+                    };
+
+                    // 1. Move the entry to its new `dst_path`
+                    let src_entry = if let Some(mut dst_entry) = guard.remove_entry(&src_path) {
+                        let src_entry = dst_entry.clone();
+                        dst_entry.path.clone_from(&dst_path);
+                        guard.insert_entry(dst_path.clone(), dst_entry);
+                        src_entry
+                    } else {
+                        return Err(PathError::does_not_exist(src_path).into());
+                    };
+
+                    // 2. Move the associated file if exists to `dst_path`
+                    guard.move_file(&src_path, &dst_path);
+                    if guard.contains_file(&dst_path) {
+                        guard.record_version(&dst_path);
+                    }
 
-            // 2. Move the associated file if exists to `dst_path`
-            if let Some(mut dst_file) = guard.remove_file(&src_path) {
-                dst_file.path = Some(dst_path.clone());
-                guard.insert_file(dst_path.clone(), dst_file);
-            }
+                    // 3. Move child's parent if parent exists else parent was moved already
+                    // and child doesn't need any more changes
+                    if let Some(old_parent) = guard.get_entry_mut(&src_path.dir()?) {
+                        old_parent.remove(src_path.base()?)?;
+                        if let Some(new_parent) = guard.get_entry_mut(&dst_path.dir()?) {
+                            new_parent.add(dst_path.base()?)?;
+                        } else {
+                            return Err(PathError::parent_not_found(dst_path.dir()?).into());
+                        }
+                    }
 
-            // 3. Move child's parent if parent exists else parent was moved already
-            // and child doesn't need any more changes
-            if let Some(old_parent) = guard.get_entry_mut(&src_path.dir()?) {
-                old_parent.remove(src_path.base()?)?;
-                if let Some(new_parent) = guard.get_entry_mut(&dst_path.dir()?) {
-                    new_parent.add(dst_path.base()?)?;
-                } else {
-                    return Err(PathError::parent_not_found(dst_path.dir()?).into());
+                    // Recursive on children
+                    if let Some(ref files) = src_entry.files {
+                        for name in files {
+                            paths.push(src_entry.path().mash(name));
+                        }
+                    }
                 }
-            }
+                guard.emit(event);
+                Ok(())
+            })
+        })
+    }
 
-            // Recursive on children
-            if let Some(ref files) = src_entry.files {
-                for name in files {
-                    paths.push(src_entry.path().mash(name));
-                }
-            }
+    /// Returns the number of hard links to the given path's data
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Links aren't followed
+    /// * Directories report two plus the number of directories they contain
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Memfs::new();
+    /// assert_vfs_mkfile!(vfs, "file");
+    /// assert_eq!(vfs.nlink("file").unwrap(), 1);
+    /// assert_vfs_hardlink!(vfs, "file", "link");
+    /// assert_eq!(vfs.nlink("file").unwrap(), 2);
+    /// ```
+    fn nlink<T: AsRef<Path>>(&self, path: T) -> RvResult<u64> {
+        let guard = self.read_guard();
+        let path = self._abs(&guard, path)?;
+        match guard.contains_entry(&path) {
+            true => Ok(guard.nlink(&path)),
+            false => Err(PathError::does_not_exist(&path).into()),
         }
-        Ok(())
+    }
+
+    /// Opens the given directory returning a [`DirHandle`] for operating on its entries by name
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Stdfs holds the directory open and uses the `openat` family of syscalls to avoid races and
+    ///   repeated path resolution
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    /// * PathError::IsNotDir(PathBuf) when the given path is not a directory
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Memfs::new();
+    /// let dir = vfs.root().mash("dir");
+    /// assert_vfs_mkdir_p!(vfs, &dir);
+    /// let handle = vfs.open_dir(&dir).unwrap();
+    /// assert_eq!(handle.mkfile("file").unwrap(), dir.mash("file"));
+    /// assert_vfs_is_file!(vfs, dir.mash("file"));
+    /// ```
+    fn open_dir<T: AsRef<Path>>(&self, path: T) -> RvResult<DirHandle> {
+        DirHandle::memfs(self.clone(), self.abs(path)?)
+    }
+
+    /// Opens a file in read-write mode for modifying regions of it in place
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Creates a file if it does not exist without truncating it if it does
+    /// * Writes overwrite the data at the current position extending the file as needed
+    ///
+    /// ### Errors
+    /// * PathError::IsNotDir(PathBuf) when the given path's parent exists but is not a directory
+    /// * PathError::DoesNotExist(PathBuf) when the given path's parent doesn't exist
+    /// * PathError::IsNotFile(PathBuf) when the given path exists but is not a file
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Memfs::new();
+    /// let file = vfs.root().mash("file");
+    /// assert_vfs_write_all!(vfs, &file, "foobar");
+    /// let mut f = vfs.open_rw(&file).unwrap();
+    /// f.seek(SeekFrom::Start(3)).unwrap();
+    /// f.write_all(b"BAR").unwrap();
+    /// f.flush().unwrap();
+    /// assert_vfs_read_all!(vfs, &file, "fooBAR");
+    /// ```
+    fn open_rw<T: AsRef<Path>>(&self, path: T) -> RvResult<Box<dyn ReadWriteSeek>> {
+        self._hooked(VfsOp::Write, path.as_ref(), || -> RvResult<Box<dyn ReadWriteSeek>> {
+            let mut guard = self.write_guard();
+
+            // Make sure the file exists and may be written
+            let path = self._abs(&guard, &path)?;
+            self._add_file(&mut guard, &path)?;
+            guard.check_mutable(&path, true)?;
+
+            if let Some(file) = guard.get_file(&path) {
+                // Clone the file to modify in place
+                let mut clone = file.clone();
+                clone.path = Some(path.clone());
+                // Set the filesystem callback to write out
+                clone.fs = Some(self.clone());
+                clone.pos = 0;
+                Ok(Box::new(clone))
+            } else {
+                Err(PathError::does_not_exist(path).into())
+            }
+        })
     }
 
     /// Returns the (user ID, group ID) of the owner of this file
@@ -1659,15 +3554,10 @@ impl VirtualFileSystem for Memfs {
     /// assert_iter_eq(vfs.paths(&tmpdir).unwrap(), vec![dir1, dir2, file1]);
     /// ```
     fn paths<T: AsRef<Path>>(&self, path: T) -> RvResult<Vec<PathBuf>> {
-        let mut paths: Vec<PathBuf> = vec![];
         if !self.is_dir(&path) {
             return Err(PathError::is_not_dir(&path).into());
         }
-        for entry in self.entries(path)?.min_depth(1).max_depth(1).sort_by_name() {
-            let entry = entry?;
-            paths.push(entry.path_buf());
-        }
-        Ok(paths)
+        self.entries(path)?.min_depth(1).max_depth(1).sort_by_name().collect_paths()
     }
 
     /// Open a file in readonly mode
@@ -1723,6 +3613,41 @@ impl VirtualFileSystem for Memfs {
         }
     }
 
+    /// Creates a new [`Reader`] for use with the builder pattern
+    ///
+    /// * Provides a handle to a Read + Seek implementation
+    /// * Handles path expansion and absolute path resolution
+    /// * Options for bypassing the page cache
+    /// * Execute by calling `exec`
+    ///
+    /// ### Errors
+    /// * PathError::IsNotFile(PathBuf) when the given path isn't a file
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let file = vfs.root().mash("file");
+    /// assert_vfs_write_all!(vfs, &file, b"foobar 1");
+    /// let mut file = vfs.read_b(&file).unwrap().direct(true).exec().unwrap();
+    /// let mut buf = String::new();
+    /// file.read_to_string(&mut buf);
+    /// assert_eq!(buf, "foobar 1".to_string());
+    /// ```
+    fn read_b<T: AsRef<Path>>(&self, path: T) -> RvResult<Reader> {
+        // Construct the read closure callback, there is no page cache to bypass
+        let vfs = self.clone();
+        let exec_func = move |opts: ReadOpts| -> RvResult<Box<dyn ReadSeek>> { vfs.read(&opts.path) };
+
+        // Return the new Reader builder
+        Ok(Reader {
+            opts: ReadOpts { path: path.as_ref().to_owned(), direct: false, advice: None },
+            exec: Box::new(exec_func),
+        })
+    }
+
     /// Read the given file and returns it as lines in a vector
     ///
     /// * Handles path expansion and absolute path resolution
@@ -1808,6 +3733,47 @@ impl VirtualFileSystem for Memfs {
         }
     }
 
+    /// Rewrites the given link's target as the minimal relative path to the same target
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Absolute link targets are converted to their relative equivalent
+    /// * Links already in their minimal relative form are left untouched
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given link doesn't exist
+    /// * PathError::IsNotSymlink(PathBuf) when the given path isn't a link
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Memfs::new();
+    /// let dir = vfs.root().mash("dir");
+    /// let file = vfs.root().mash("file");
+    /// let link = dir.mash("link");
+    /// assert_vfs_mkdir_p!(vfs, &dir);
+    /// assert_vfs_mkfile!(vfs, &file);
+    /// assert_vfs_symlink!(vfs, &link, &file);
+    /// assert_eq!(vfs.relink(&link).unwrap(), link);
+    /// assert_vfs_readlink!(vfs, &link, PathBuf::from("../file"));
+    /// ```
+    fn relink<T: AsRef<Path>>(&self, link: T) -> RvResult<PathBuf> {
+        self._hooked(VfsOp::Create, link.as_ref(), || {
+            let mut guard = self.write_guard();
+            let path = self._abs(&guard, &link)?;
+
+            // Recompute the relative target from the link's current location
+            match guard.get_entry_mut(&path) {
+                Some(entry) if entry.is_symlink() => {
+                    entry.rel = entry.alt.relative(path.dir()?)?;
+                    Ok(path)
+                },
+                Some(_) => Err(PathError::is_not_symlink(path).into()),
+                None => Err(PathError::does_not_exist(path).into()),
+            }
+        })
+    }
+
     /// Removes the given empty directory or file
     ///
     /// * Handles path expansion and absolute path resolution
@@ -1827,40 +3793,45 @@ impl VirtualFileSystem for Memfs {
     /// assert_vfs_no_exists!(vfs, &file);
     /// ```
     fn remove<T: AsRef<Path>>(&self, path: T) -> RvResult<()> {
-        let mut guard = self.write_guard();
-        let path = self._abs(&guard, path)?;
+        self._hooked(VfsOp::Remove, path.as_ref(), || {
+            let mut guard = self.write_guard();
+            let path = self._abs(&guard, &path)?;
+            if guard.contains_entry(&path) {
+                guard.check_mutable(&path, false)?;
+                guard.check_mutable(&path.dir()?, false)?;
+            }
 
-        // First check if the target contains files
-        if let Some(entry) = guard.get_entry(&path) {
-            if let Some(ref files) = entry.files {
-                if !files.is_empty() {
-                    return Err(PathError::dir_contains_files(path).into());
+            // First check if the target contains files
+            if let Some(entry) = guard.get_entry(&path) {
+                if let Some(ref files) = entry.files {
+                    if !files.is_empty() {
+                        return Err(PathError::dir_contains_files(path).into());
+                    }
                 }
             }
-        }
 
-        // Next remove the file from its parent
-        let dir = path.dir()?;
-        if let Some(entry) = guard.get_entry_mut(&dir) {
-            entry.remove(path.base()?)?;
-        }
-
-        // Next remove its data file if it exists
-        if let Some(entry) = guard.get_entry(&path) {
-            if entry.is_file() {
-                guard.remove_file(&path);
+            // Next remove the file from its parent
+            let dir = path.dir()?;
+            if let Some(entry) = guard.get_entry_mut(&dir) {
+                entry.remove(path.base()?)?;
             }
-        }
 
-        // Finally remove the entry from the filesystem
-        guard.remove_entry(&path);
-        Ok(())
+            // Next remove its data file if it exists
+            guard.remove_file(&path);
+
+            // Finally remove the entry from the filesystem
+            if guard.remove_entry(&path).is_some() {
+                guard.emit(VfsEvent::Remove(path));
+            }
+            Ok(())
+        })
     }
 
     /// Removes the given directory after removing all of its contents
     ///
     /// * Handles path expansion and absolute path resolution
     /// * Provides link exclusion i.e. removes the link themselves not what its points to
+    /// * Refuses to remove the root directory, use `remove_all_b` for more guards
     ///
     /// ### Examples
     /// ```
@@ -1876,43 +3847,83 @@ impl VirtualFileSystem for Memfs {
     /// assert_vfs_no_exists!(vfs, &dir);
     /// ```
     fn remove_all<T: AsRef<Path>>(&self, path: T) -> RvResult<()> {
-        let mut guard = self.write_guard();
-        let path = self._abs(&guard, path)?;
+        self.remove_all_b(path)?.exec()
+    }
 
-        let mut paths = vec![path];
-        while let Some(path) = paths.pop() {
-            if !guard.contains_entry(&path) {
-                continue;
-            }
+    /// Creates a new [`Remover`] for use with the builder pattern
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Provides link exclusion i.e. removes the link themselves not what its points to
+    /// * Refuses to remove the root directory
+    /// * Provides optional guards for the path being within a base and not crossing devices
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let dir = vfs.root().mash("dir");
+    /// assert_vfs_mkdir_p!(vfs, &dir);
+    /// assert!(vfs.remove_all_b(&dir).unwrap().within(vfs.root()).exec().is_ok());
+    /// assert_vfs_no_exists!(vfs, &dir);
+    /// ```
+    fn remove_all_b<T: AsRef<Path>>(&self, path: T) -> RvResult<Remover> {
+        let path = self.abs(path)?;
 
-            // First process the entry's children
-            if let Some(entry) = guard.get_entry(&path) {
-                if let Some(ref files) = entry.files {
-                    if !files.is_empty() {
-                        paths.push(path.clone()); // remove after children
-                        for name in files {
-                            paths.push(path.mash(name));
-                        }
-                        continue;
-                    }
-                }
-            }
+        // Construct the closure callback
+        let vfs = self.clone();
+        let exec_func = move |opts: RemoveOpts| -> RvResult<()> {
+            vfs._hooked(VfsOp::Remove, &opts.path.clone(), || vfs._remove_all(opts))
+        };
 
-            // Remove the file from its parent
-            if let Some(parent) = guard.get_entry_mut(&path.dir()?) {
-                parent.remove(path.base()?)?;
-            }
+        Ok(Remover {
+            opts: RemoveOpts { path, within: None, same_device: false, confirm: None, each: false },
+            exec: Box::new(exec_func),
+        })
+    }
 
-            // Next remove its data file if it exists
-            if guard.contains_file(&path) {
-                guard.remove_file(&path);
+    /// Resolve the given link one hop at a time returning the final target and the chain traversed
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Follows each link one hop at a time recording the links traversed
+    /// * Works on dangling chains returning the last existing link and the missing remainder
+    /// * Non-links resolve to themselves with an empty chain
+    ///
+    /// ### Errors
+    /// * PathError::LinkLooping(PathBuf) when the chain loops back on itself
+    /// * PathError::TooManyLinks(PathBuf) when more than `max_hops` hops are required
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let link = vfs.root().mash("link");
+    /// assert!(vfs.symlink(&link, "missing").is_ok());
+    /// let resolved = vfs.resolve_link(&link, 40).unwrap();
+    /// assert_eq!(resolved.chain, vec![link]);
+    /// assert_eq!(resolved.target, vfs.root().mash("missing"));
+    /// assert_eq!(resolved.exists, false);
+    /// ```
+    fn resolve_link<T: AsRef<Path>>(&self, path: T, max_hops: usize) -> RvResult<ResolvedLink> {
+        let guard = self.read_guard();
+        let mut path = self._abs(&guard, path)?;
+        let mut chain: Vec<PathBuf> = vec![];
+        loop {
+            let target = match guard.get_entry(&path) {
+                Some(entry) if entry.is_symlink() => entry.alt_buf(),
+                Some(_) => return Ok(ResolvedLink { chain, target: path, exists: true }),
+                None => return Ok(ResolvedLink { chain, target: path, exists: false }),
+            };
+            if chain.contains(&path) {
+                return Err(PathError::link_looping(&chain[0]).into());
             }
-
-            // Finally remove the entry from the filesystem
-            guard.remove_entry(&path);
+            if chain.len() >= max_hops {
+                return Err(PathError::too_many_links(chain.first().unwrap_or(&path)).into());
+            }
+            chain.push(path);
+            path = target;
         }
-
-        Ok(())
     }
 
     /// Returns the current root directory
@@ -1930,6 +3941,71 @@ impl VirtualFileSystem for Memfs {
         self.read_guard().root()
     }
 
+    /// Returns true if the given paths refer to the same underlying file
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Follows links so that a link and its target are considered the same file
+    /// * Returns false if either path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Memfs::new();
+    /// assert_vfs_mkfile!(vfs, "file1");
+    /// assert_vfs_mkfile!(vfs, "file2");
+    /// assert_vfs_symlink!(vfs, "link", "file1");
+    /// assert_eq!(vfs.same_file("link", "file1"), true);
+    /// assert_eq!(vfs.same_file("link", "file2"), false);
+    /// ```
+    fn same_file<T: AsRef<Path>, U: AsRef<Path>>(&self, a: T, b: U) -> bool {
+        self._same_file(&self.read_guard(), a, b)
+    }
+
+    /// Set the POSIX access control list of the given path
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Links are followed
+    /// * The permission bits of the path's mode are updated to match the ACL
+    /// * A mask entry is added when named users or groups are given without one
+    /// * Default entries are only valid for directories, empty default entries remove them
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    /// * PathError::IsNotDir(PathBuf) when default entries are given for a non directory
+    /// * VfsError::InvalidAcl(PathBuf) when the entries are malformed
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let file = vfs.root().mash("file");
+    /// assert!(vfs.mkfile_m(&file, 0o640).is_ok());
+    /// assert!(vfs.set_acl(&file, Acl::from_mode(0o640).group(7, 0o6)).is_ok());
+    /// assert_eq!(vfs.get_acl(&file).unwrap().perms(AclTag::Group(7)), Some(0o6));
+    /// assert_eq!(vfs.mode(&file).unwrap(), 0o100660);
+    /// ```
+    fn set_acl<T: AsRef<Path>>(&self, path: T, acl: Acl) -> RvResult<()> {
+        let path = path.as_ref();
+        self._hooked(VfsOp::Chmod, path, || {
+            let mut guard = self.write_guard();
+            let path = self._abs(&guard, path)?;
+            let path = self._target_chain(&guard, &path).pop().unwrap_or(path);
+            let (mode, dir) = match guard.get_entry(&path) {
+                Some(entry) => (entry.mode, entry.is_dir()),
+                None => return Err(PathError::does_not_exist(&path).into()),
+            };
+            let acl = acl.normalize(&path)?;
+            if !acl.default.is_empty() && !dir {
+                return Err(PathError::is_not_dir(&path).into());
+            }
+            guard.set_mode(&path, (mode & !0o777) | acl.mode());
+            guard.set_acl(&path, if acl.is_minimal() { None } else { Some(acl) });
+            Ok(())
+        })
+    }
+
     /// Set the current working directory
     ///
     /// * Handles path expansion and absolute path resolution
@@ -1959,6 +4035,210 @@ impl VirtualFileSystem for Memfs {
         Ok(path)
     }
 
+    /// Set the access and modification times of the given path with nanosecond precision
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Doesn't follow links i.e. sets the times of the link itself
+    /// * Access times aren't tracked so only the modification time is set
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    /// use std::time::{Duration, UNIX_EPOCH};
+    ///
+    /// let vfs = Memfs::new();
+    /// let file = vfs.root().mash("file");
+    /// assert_vfs_mkfile!(vfs, &file);
+    /// let time = UNIX_EPOCH + Duration::new(1_000_000_000, 123_456_789);
+    /// assert!(vfs.set_file_time(&file, time, time).is_ok());
+    /// assert_eq!(vfs.entry(&file).unwrap().modified(), time);
+    /// ```
+    fn set_file_time<T: AsRef<Path>>(&self, path: T, _atime: SystemTime, mtime: SystemTime) -> RvResult<()> {
+        self.set_mtime_only(path, mtime)
+    }
+
+    /// Set or clear the immutable flag of the given path i.e. `chattr +i` and `chattr -i`
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Links are followed
+    /// * Immutable files can't be written, removed, renamed or linked to and immutable directories
+    ///   can't have entries added, removed or renamed until the flag is cleared failing with EPERM
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Memfs::new();
+    /// let file = vfs.root().mash("file");
+    /// assert_vfs_mkfile!(vfs, &file);
+    /// assert!(vfs.set_immutable(&file, true).is_ok());
+    /// assert_eq!(vfs.is_immutable(&file), true);
+    /// assert!(vfs.set_immutable(&file, false).is_ok());
+    /// assert_eq!(vfs.is_immutable(&file), false);
+    /// ```
+    fn set_immutable<T: AsRef<Path>>(&self, path: T, yes: bool) -> RvResult<()> {
+        let path = path.as_ref();
+        self._hooked(VfsOp::Chmod, path, || {
+            let mut guard = self.write_guard();
+            let path = self._abs(&guard, path)?;
+            let path = self._target_chain(&guard, &path).pop().unwrap_or(path);
+            match guard.get_entry_mut(&path) {
+                Some(entry) => entry.immutable = yes,
+                None => return Err(PathError::does_not_exist(&path).into()),
+            }
+            Ok(())
+        })
+    }
+
+    /// Set the modification time of the given path with nanosecond precision
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Doesn't follow links i.e. sets the time of the link itself
+    /// * Leaves the access time untouched
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    /// use std::time::{Duration, UNIX_EPOCH};
+    ///
+    /// let vfs = Memfs::new();
+    /// let file = vfs.root().mash("file");
+    /// assert_vfs_mkfile!(vfs, &file);
+    /// let time = UNIX_EPOCH + Duration::new(1_000_000_000, 123_456_789);
+    /// assert!(vfs.set_mtime_only(&file, time).is_ok());
+    /// assert_eq!(vfs.entry(&file).unwrap().modified(), time);
+    /// ```
+    fn set_mtime_only<T: AsRef<Path>>(&self, path: T, mtime: SystemTime) -> RvResult<()> {
+        self._hooked(VfsOp::Write, path.as_ref(), || {
+            let mut guard = self.write_guard();
+            let path = self._abs(&guard, &path)?;
+            match guard.get_entry_mut(&path) {
+                Some(entry) => entry.modified = mtime,
+                None => return Err(PathError::does_not_exist(&path).into()),
+            }
+            guard.emit(VfsEvent::Write(path));
+            Ok(())
+        })
+    }
+
+    /// Set the SELinux security context of the given path
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Links aren't followed so that the context of the link itself is set
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let file = vfs.root().mash("file");
+    /// assert_vfs_mkfile!(vfs, &file);
+    /// assert!(vfs.set_selinux_context(&file, "system_u:object_r:etc_t:s0").is_ok());
+    /// assert_eq!(vfs.get_selinux_context(&file).unwrap().unwrap(), "system_u:object_r:etc_t:s0");
+    /// ```
+    #[cfg(feature = "selinux")]
+    fn set_selinux_context<T: AsRef<Path>, U: AsRef<str>>(&self, path: T, context: U) -> RvResult<()> {
+        let path = path.as_ref();
+        self._hooked(VfsOp::Chmod, path, || {
+            let mut guard = self.write_guard();
+            let path = self._abs(&guard, path)?;
+            if !guard.contains_entry(&path) {
+                return Err(PathError::does_not_exist(&path).into());
+            }
+            guard.set_context(&path, Some(context.as_ref().to_string()));
+            Ok(())
+        })
+    }
+
+    /// Returns the size in bytes of the given file
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Links are followed
+    /// * Use `disk_usage` to sum up the size of a directory tree
+    ///
+    /// ### Errors
+    /// * PathError::IsNotFile(PathBuf) when the given path isn't a file
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Memfs::new();
+    /// let tmpdir = vfs.root();
+    /// let file = tmpdir.mash("file");
+    /// assert_vfs_write_all!(vfs, &file, "foobar");
+    /// assert_eq!(vfs.size(&file).unwrap(), 6);
+    /// ```
+    fn size<T: AsRef<Path>>(&self, path: T) -> RvResult<u64> {
+        let guard = self.read_guard();
+        let entry = self._clone_entry(&guard, path)?;
+        if !entry.is_file() {
+            return Err(PathError::is_not_file(entry.path()).into());
+        }
+
+        // Links report the size of the file they point to
+        let path = if entry.is_symlink() { entry.alt() } else { entry.path() };
+        match guard.get_file(path) {
+            Some(file) => Ok(file.len()),
+            None => Err(PathError::does_not_exist(path).into()),
+        }
+    }
+
+    /// Returns the capacity and usage of the filesystem containing the given path
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Reports the values of the deepest quota containing the path else the whole filesystem
+    ///   with `u64::MAX` as its capacity
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let memfs = Memfs::new();
+    /// assert_vfs_mkdir_p!(memfs, "/tenant");
+    /// assert!(memfs.set_quota("/tenant", 10, 5).is_ok());
+    /// assert_vfs_write_all!(memfs, "/tenant/file", "foobar");
+    /// let stat = memfs.stat_fs("/tenant").unwrap();
+    /// assert_eq!((stat.total, stat.used, stat.free), (10, 6, 4));
+    /// assert_eq!((stat.inodes, stat.inodes_used, stat.inodes_free), (5, 1, 4));
+    /// ```
+    fn stat_fs<T: AsRef<Path>>(&self, path: T) -> RvResult<VfsStatFs> {
+        let guard = self.read_guard();
+        let path = self._abs(&guard, path)?;
+        if !guard.contains_entry(&path) {
+            return Err(PathError::does_not_exist(&path).into());
+        }
+
+        // Use the deepest quota containing the path else the whole filesystem
+        let quota = guard.quotas(&path).into_iter().max_by_key(|(x, _)| x.components().count());
+        let (root, (bytes, inodes)) = quota.unwrap_or((guard.root(), (u64::MAX, u64::MAX)));
+        let (used, inodes_used) = guard.usage(&root, &root);
+        Ok(VfsStatFs {
+            total: bytes,
+            used,
+            free: bytes.saturating_sub(used),
+            inodes,
+            inodes_used,
+            inodes_free: inodes.saturating_sub(inodes_used),
+        })
+    }
+
     /// Creates a new symbolic link
     ///
     /// * Handles path expansion and absolute path resolution
@@ -1982,12 +4262,50 @@ impl VirtualFileSystem for Memfs {
     /// assert_vfs_readlink!(vfs, &link, PathBuf::from("file"));
     /// ```
     fn symlink<T: AsRef<Path>, U: AsRef<Path>>(&self, link: T, target: U) -> RvResult<PathBuf> {
-        self._symlink(&mut self.write_guard(), link, target)
+        self._hooked(VfsOp::Create, link.as_ref(), || {
+            self._symlink(&mut self.write_guard(), &link, target)
+        })
+    }
+
+    /// Wraps `symlink` reporting whether the link was created or already existed
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * An existing link is only considered to exist if it points to the given target
+    /// * Dangling links are supported
+    ///
+    /// ### Errors
+    /// * PathError::ExistsAlready(PathBuf) when the link path exists but isn't a link to the target
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let link = vfs.root().mash("link");
+    /// assert_eq!(vfs.symlink_ensured(&link, "file").unwrap(), Ensured::Created(link.clone()));
+    /// assert_eq!(vfs.symlink_ensured(&link, "file").unwrap(), Ensured::Existed(link.clone()));
+    /// ```
+    fn symlink_ensured<T: AsRef<Path>, U: AsRef<Path>>(&self, link: T, target: U) -> RvResult<Ensured> {
+        self._hooked(VfsOp::Create, link.as_ref(), || {
+            let mut guard = self.write_guard();
+            let link = self._abs(&guard, &link)?;
+            if let Some(entry) = guard.get_entry(&link) {
+                let target = target.as_ref().to_owned();
+                let target = if !target.is_absolute() { link.dir()?.mash(target) } else { target };
+                let target = self._abs(&guard, target)?;
+                return match entry.is_symlink() && entry.alt() == target {
+                    true => Ok(Ensured::Existed(link)),
+                    false => Err(PathError::exists_already(link).into()),
+                };
+            }
+            Ok(Ensured::Created(self._symlink(&mut guard, &link, &target)?))
+        })
     }
 
     /// Opens a file in write-only mode
     ///
     /// * Creates a file if it does not exist or truncates it if it does
+    /// * Returns a [`VfsWriter`] reporting the path and number of bytes written
     ///
     /// ### Errors
     /// * PathError::IsNotDir(PathBuf) when the given path's parent exists but is not a directory
@@ -2005,20 +4323,24 @@ impl VirtualFileSystem for Memfs {
     /// f.flush().unwrap();
     /// assert_vfs_read_all!(vfs, &file, "foobar");
     /// ```
-    fn write<T: AsRef<Path>>(&self, path: T) -> RvResult<Box<dyn Write>> {
-        let mut guard = self.write_guard();
+    fn write<T: AsRef<Path>>(&self, path: T) -> RvResult<VfsWriter> {
+        self._hooked(VfsOp::Write, path.as_ref(), || -> RvResult<VfsWriter> {
+            let mut guard = self.write_guard();
 
-        // Make sure the file exists
-        let path = self._abs(&guard, path)?;
-        self._add(&mut guard, MemfsEntry::opts(&path).file().build())?;
-
-        // Create an empty file to write to
-        Ok(Box::new(MemfsFile {
-            pos: 0,
-            data: vec![],
-            path: Some(path),
-            fs: Some(self.clone()),
-        }))
+            // Make sure the file exists and may be written
+            let path = self._abs(&guard, &path)?;
+            self._add_file(&mut guard, &path)?;
+            guard.check_mutable(&path, true)?;
+
+            // Create an empty file to write to
+            Ok(VfsWriter::memfs(&path, MemfsFile {
+                pos: 0,
+                data: vec![],
+                dirty: true, // truncation counts as a write
+                path: Some(path.clone()),
+                fs: Some(self.clone()),
+            }))
+        })
     }
 
     /// Write the given data to to the target file
@@ -2048,6 +4370,93 @@ impl VirtualFileSystem for Memfs {
         Ok(())
     }
 
+    /// Write the given data to the target file atomically
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Writes to a temporary sibling file which is then renamed into place so that readers only
+    ///   ever see the old or the new data and a failure never leaves a partially written file
+    /// * Replaces the final target of links rather than the links themselves
+    /// * Preserves the mode and ownership of an existing file
+    /// * Breaks any hard links to an existing file as it is replaced by a new file
+    ///
+    /// ### Errors
+    /// * PathError::IsNotDir(PathBuf) when the given path's parent exists but is not a directory
+    /// * PathError::DoesNotExist(PathBuf) when the given path's parent doesn't exist
+    /// * PathError::IsNotFile(PathBuf) when the given path exists but is not a file
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Memfs::new();
+    /// let tmpdir = vfs.root();
+    /// let file = tmpdir.mash("file");
+    /// assert!(vfs.write_all_atomic(&file, "foobar").is_ok());
+    /// assert_vfs_read_all!(vfs, &file, "foobar");
+    /// ```
+    fn write_all_atomic<T: AsRef<Path>, U: AsRef<[u8]>>(&self, path: T, data: U) -> RvResult<()> {
+        self._hooked(VfsOp::Write, path.as_ref(), || {
+            let mut path = self.abs(&path)?;
+            if self.is_symlink(&path) {
+                path = self.resolve_link(&path, sys::DEFAULT_MAX_LINKS)?.target;
+            }
+
+            // Validate the file
+            let exists = self.exists(&path);
+            if exists && !self.is_file(&path) {
+                return Err(PathError::is_not_file(&path).into());
+            }
+
+            // Write the temp file carrying over the mode and ownership of the existing file
+            let tmp = sys::tmp_sibling(&path)?;
+            let write = || -> RvResult<()> {
+                self.write_all(&tmp, &data)?;
+                if exists {
+                    self.chmod(&tmp, self.mode(&path)?)?;
+                    let (uid, gid) = self.owner(&path)?;
+                    self.chown(&tmp, uid, gid)?;
+                }
+                self.move_p(&tmp, &path)
+            };
+            if let Err(err) = write() {
+                let _ = self.remove(&tmp);
+                return Err(err);
+            }
+            Ok(())
+        })
+    }
+
+    /// Creates a new [`Writer`] for use with the builder pattern
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Options for writing atomically
+    /// * Execute by calling `exec`
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Memfs::new();
+    /// let tmpdir = vfs.root();
+    /// let file = tmpdir.mash("file");
+    /// assert!(vfs.write_b(&file, "foobar").unwrap().atomic(true).exec().is_ok());
+    /// assert_vfs_read_all!(vfs, &file, "foobar");
+    /// ```
+    fn write_b<T: AsRef<Path>, U: AsRef<[u8]>>(&self, path: T, data: U) -> RvResult<Writer> {
+        let vfs = self.clone();
+        let exec_func = move |opts: WriteOpts| -> RvResult<()> {
+            match opts.atomic {
+                true => vfs.write_all_atomic(&opts.path, &opts.data),
+                false => vfs.write_all(&opts.path, &opts.data),
+            }
+        };
+
+        Ok(Writer {
+            opts: WriteOpts { path: path.as_ref().to_owned(), data: data.as_ref().to_vec(), atomic: false },
+            exec: Box::new(exec_func),
+        })
+    }
+
     /// Write the given lines to to the target file including final newline
     ///
     /// * Handles path expansion and absolute path resolution
@@ -2097,6 +4506,37 @@ impl VirtualFileSystem for Memfs {
         }
     }
 
+    /// Watch the given path for changes returning a stream of the events that occur
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Directories are watched recursively
+    /// * Reports files, directories and links being created, written to, removed and renamed
+    /// * Permission and ownership changes aren't reported
+    /// * Events are synthesized from the mutations made through this Memfs instance
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Memfs::new();
+    /// let mut watcher = vfs.watch(vfs.root()).unwrap();
+    /// let file = vfs.root().mash("file");
+    /// assert_vfs_mkfile!(vfs, &file);
+    /// assert!(vfs.remove(&file).is_ok());
+    /// assert_eq!(watcher.try_recv(), Some(VfsEvent::Create(file.clone())));
+    /// assert_eq!(watcher.try_recv(), Some(VfsEvent::Remove(file)));
+    /// ```
+    fn watch<T: AsRef<Path>>(&self, path: T) -> RvResult<VfsWatcher> {
+        let path = self.abs(path)?;
+        if !self.exists(&path) {
+            return Err(PathError::does_not_exist(&path).into());
+        }
+        Ok(VfsWatcher::memfs(self.events(), path))
+    }
+
     /// Up cast the trait type to the enum wrapper
     ///
     /// ### Examples
@@ -2122,6 +4562,14 @@ mod tests {
     fn test_debug() {
         let memfs = Memfs::new();
         assert_eq!(format!("{}", &memfs), format!("{}", &memfs));
+        assert_vfs_write_all!(memfs, "/file", "foo\nbar");
+        assert_vfs_symlink!(memfs, "/link", "/file");
+        assert_eq!(
+            format!("{:?}", &memfs),
+            "Memfs {\n    / 40755\n    /file 100644 \"foo\\nbar\"\n    /link 120777 -> /file\n}"
+        );
+        let _guard = memfs.write_guard();
+        assert_eq!(format!("{:?}", &memfs), "Memfs { <locked> }");
     }
 
     #[test]
@@ -2172,6 +4620,29 @@ mod tests {
         assert_eq!(memfs.abs("").unwrap_err().to_string(), PathError::Empty.to_string());
     }
 
+    #[test]
+    fn test_advise() {
+        let memfs = Memfs::new();
+        let dir1 = memfs.root().mash("dir1");
+        let file1 = memfs.root().mash("file1");
+
+        // Errors
+        assert_eq!(
+            memfs.advise(&file1, Advice::DontNeed).unwrap_err().downcast_ref::<PathError>(),
+            Some(&PathError::does_not_exist(&file1))
+        );
+        assert_vfs_mkdir_p!(memfs, &dir1);
+        assert_eq!(
+            memfs.advise(&dir1, Advice::DontNeed).unwrap_err().downcast_ref::<PathError>(),
+            Some(&PathError::is_not_file(&dir1))
+        );
+
+        // No-op for files
+        assert_vfs_write_all!(memfs, &file1, "foobar");
+        assert!(memfs.advise(&file1, Advice::WillNeed).is_ok());
+        assert_vfs_read_all!(memfs, &file1, "foobar");
+    }
+
     #[test]
     fn test_append() {
         let vfs = Memfs::new();
@@ -2297,6 +4768,93 @@ mod tests {
         assert_iter_eq(vfs.all_paths(&tmpdir).unwrap(), vec![dir1, file2, file3, file1]);
     }
 
+    #[test]
+    fn test_allocate() {
+        let memfs = Memfs::new();
+        let dir1 = memfs.root().mash("dir1");
+        let file1 = dir1.mash("file1");
+        let file2 = dir1.mash("file2");
+
+        // Errors
+        assert_eq!(
+            memfs.allocate(&file1, 10).unwrap_err().downcast_ref::<PathError>(),
+            Some(&PathError::does_not_exist(&dir1))
+        );
+        assert_vfs_mkdir_p!(memfs, &dir1);
+        assert_eq!(
+            memfs.allocate(&dir1, 10).unwrap_err().downcast_ref::<PathError>(),
+            Some(&PathError::is_not_file(&dir1))
+        );
+
+        // Creates the file and grows it keeping existing data
+        assert!(memfs.allocate(&file1, 10).is_ok());
+        assert_eq!(memfs.entry(&file1).unwrap().size(), 10);
+        assert!(memfs.write_all(&file1, "foo").is_ok());
+        assert!(memfs.allocate(&file1, 5).is_ok());
+        assert_eq!(memfs.read_all(&file1).unwrap(), "foo\0\0");
+
+        // Never shrinks the file
+        assert!(memfs.allocate(&file1, 2).is_ok());
+        assert_eq!(memfs.entry(&file1).unwrap().size(), 5);
+
+        // Fails early when the quota would be exceeded
+        assert!(memfs.set_quota(&dir1, 10, 10).is_ok());
+        let err = memfs.allocate(&file2, 6).unwrap_err();
+        assert_eq!(err.downcast_ref::<VfsError>(), Some(&VfsError::QuotaExceeded(dir1.clone())));
+        assert!(memfs.allocate(&file2, 5).is_ok());
+    }
+
+    #[test]
+    fn test_bind() {
+        let memfs = Memfs::new();
+        let src = memfs.root().mash("src");
+        let dst = memfs.root().mash("dst");
+        let file1 = src.mash("file1");
+        let file2 = dst.mash("file2");
+        assert_vfs_mkdir_p!(memfs, &src);
+        assert_vfs_mkdir_p!(memfs, &dst);
+        assert_vfs_write_all!(memfs, &file1, "foo");
+        assert_vfs_mkfile!(memfs, &file2);
+
+        // Errors
+        assert_eq!(
+            memfs.bind(&file1, &dst).unwrap_err().downcast_ref::<PathError>(),
+            Some(&PathError::is_not_dir(&file1))
+        );
+        assert_eq!(
+            memfs.bind(&src, memfs.root().mash("foo")).unwrap_err().downcast_ref::<PathError>(),
+            Some(&PathError::does_not_exist(memfs.root().mash("foo")))
+        );
+        assert_eq!(
+            memfs.bind(&src, memfs.root()).unwrap_err().downcast_ref::<PathError>(),
+            Some(&PathError::link_looping(memfs.root()))
+        );
+
+        // Both paths share the same data and the destination's contents are hidden
+        assert!(memfs.bind(&src, &dst).is_ok());
+        assert_vfs_read_all!(memfs, dst.mash("file1"), "foo");
+        assert_vfs_no_exists!(memfs, &file2);
+        assert_vfs_write_all!(memfs, dst.mash("file3"), "bar");
+        assert_vfs_read_all!(memfs, src.mash("file3"), "bar");
+        assert!(memfs.write_all(&file1, "foobar").is_ok());
+        assert_vfs_read_all!(memfs, dst.mash("file1"), "foobar");
+
+        // Traversing from above keeps the bound paths
+        assert_eq!(memfs.all_files(memfs.root()).unwrap(), vec![
+            dst.mash("file1"),
+            dst.mash("file3"),
+            src.mash("file1"),
+            src.mash("file3")
+        ]);
+
+        // Unbinding restores the destination's contents
+        assert!(memfs.unbind(&dst).is_ok());
+        assert!(memfs.unbind(&dst).is_ok());
+        assert_vfs_exists!(memfs, &file2);
+        assert_vfs_no_exists!(memfs, dst.mash("file1"));
+        assert_eq!(memfs.all_files(&dst).unwrap(), vec![file2]);
+    }
+
     #[test]
     fn test_chmod() {
         let vfs = Memfs::new();
@@ -2558,6 +5116,35 @@ mod tests {
         assert_iter_eq(vfs.dirs(&tmpdir).unwrap(), vec![dir1, dir2]);
     }
 
+    #[test]
+    fn test_dump() {
+        let memfs = Memfs::new();
+        let root = memfs.root();
+        let dir1 = root.mash("dir1");
+        let file1 = dir1.mash("file1");
+        let link1 = root.mash("link1");
+
+        // Only the root to begin with
+        assert_eq!(memfs.dump(), vec![MemfsEntryInfo {
+            path: root.clone(),
+            kind: Kind::Dir,
+            mode: 0o40755,
+            size: 0,
+            target: None
+        }]);
+
+        assert_vfs_mkdir_p!(memfs, &dir1);
+        assert_vfs_write_all!(memfs, &file1, "foobar");
+        assert!(memfs.chmod(&file1, 0o600).is_ok());
+        assert_vfs_symlink!(memfs, &link1, &file1);
+        assert_eq!(memfs.dump(), vec![
+            MemfsEntryInfo { path: root, kind: Kind::Dir, mode: 0o40755, size: 0, target: None },
+            MemfsEntryInfo { path: dir1, kind: Kind::Dir, mode: 0o40755, size: 0, target: None },
+            MemfsEntryInfo { path: file1.clone(), kind: Kind::File, mode: 0o100600, size: 6, target: None },
+            MemfsEntryInfo { path: link1, kind: Kind::Symlink, mode: 0o120777, size: 0, target: Some(file1) },
+        ]);
+    }
+
     #[test]
     fn test_entries() {
         let memfs = Memfs::new();
@@ -2575,7 +5162,7 @@ mod tests {
         assert_eq!(iter.next().unwrap().unwrap().path(), &dir1);
         assert_eq!(iter.next().unwrap().unwrap().path(), &dir2);
         assert_eq!(iter.next().unwrap().unwrap().path(), &file);
-        assert_eq!(iter.next().is_none(), true);
+        assert!(iter.next().is_none());
     }
 
     #[test]
@@ -2596,25 +5183,205 @@ mod tests {
         let file = vfs.root().mash("file");
         assert_vfs_mkfile!(vfs, &file);
         let guard = vfs.read_guard();
-        let mut iter = vfs._entry_iter(&guard, &vfs.root()).unwrap()(&vfs.root(), false).unwrap();
+        let mut iter = vfs._entry_iter(&guard, vfs.root()).unwrap()(&vfs.root(), false).unwrap();
         assert_eq!(iter.next().unwrap().unwrap().path(), file);
         assert!(iter.next().is_none());
     }
 
+    #[test]
+    fn test_ensure_dir() {
+        let vfs = Memfs::new();
+        let dir1 = vfs.root().mash("dir1");
+        let dir2 = dir1.mash("dir2");
+        let file1 = vfs.root().mash("file1");
+        let link1 = vfs.root().mash("link1");
+
+        // creates parents as needed
+        assert!(vfs.ensure_dir(&dir2, 0o750, 5, 6).unwrap());
+        assert_eq!(vfs.mode(&dir2).unwrap(), 0o40750);
+        assert_eq!(vfs.owner(&dir2).unwrap(), (5, 6));
+        assert_eq!(vfs.owner(&dir1).unwrap(), (1000, 1000));
+
+        // converged
+        assert!(!vfs.ensure_dir(&dir2, 0o750, 5, 6).unwrap());
+
+        // only the differing attributes change
+        assert!(vfs.ensure_dir(&dir2, 0o40700, 5, 6).unwrap());
+        assert_eq!(vfs.mode(&dir2).unwrap(), 0o40700);
+        assert!(vfs.ensure_dir(&dir2, 0o700, 5, 7).unwrap());
+        assert_eq!(vfs.owner(&dir2).unwrap(), (5, 7));
+
+        // links are followed
+        assert_vfs_symlink!(vfs, &link1, &dir2);
+        assert!(!vfs.ensure_dir(&link1, 0o700, 5, 7).unwrap());
+        assert!(vfs.ensure_dir(&link1, 0o755, 5, 7).unwrap());
+        assert_eq!(vfs.mode(&dir2).unwrap(), 0o40755);
+
+        // not a dir
+        assert_vfs_mkfile!(vfs, &file1);
+        assert_eq!(
+            vfs.ensure_dir(&file1, 0o755, 5, 7).unwrap_err().to_string(),
+            PathError::is_not_dir(&file1).to_string()
+        );
+    }
+
+    #[test]
+    fn test_ensure_file() {
+        let vfs = Memfs::new();
+        let dir1 = vfs.root().mash("dir1");
+        let file1 = dir1.mash("file1");
+
+        // parent doesn't exist
+        assert!(vfs.ensure_file(&file1, "foo", 0o600).is_err());
+
+        // creates the file
+        assert_vfs_mkdir_p!(vfs, &dir1);
+        assert!(vfs.ensure_file(&file1, "foo", 0o600).unwrap());
+        assert_vfs_read_all!(vfs, &file1, "foo");
+        assert_eq!(vfs.mode(&file1).unwrap(), 0o100600);
+
+        // converged
+        assert!(!vfs.ensure_file(&file1, b"foo", 0o600).unwrap());
+
+        // only the differing attributes change
+        assert!(vfs.ensure_file(&file1, "bar", 0o600).unwrap());
+        assert_vfs_read_all!(vfs, &file1, "bar");
+        assert!(vfs.ensure_file(&file1, "bar", 0o644).unwrap());
+        assert_eq!(vfs.mode(&file1).unwrap(), 0o100644);
+        assert!(vfs.ensure_file(&file1, "", 0o644).unwrap());
+        assert_vfs_read_all!(vfs, &file1, "");
+
+        // links are followed
+        let link1 = dir1.mash("link1");
+        assert_vfs_symlink!(vfs, &link1, &file1);
+        assert!(!vfs.ensure_file(&link1, "", 0o644).unwrap());
+        assert!(vfs.ensure_file(&link1, "foo", 0o600).unwrap());
+        assert_vfs_read_all!(vfs, &file1, "foo");
+        assert_eq!(vfs.mode(&file1).unwrap(), 0o100600);
+        assert_vfs_is_symlink!(vfs, &link1);
+
+        // not a file
+        assert_eq!(
+            vfs.ensure_file(&dir1, "foo", 0o644).unwrap_err().to_string(),
+            PathError::is_not_file(&dir1).to_string()
+        );
+    }
+
+    #[test]
+    fn test_ensure_symlink() {
+        let vfs = Memfs::new();
+        let tmpdir = vfs.root().mash("tmpdir");
+        let dir1 = tmpdir.mash("dir1");
+        let file1 = tmpdir.mash("file1");
+        let link1 = tmpdir.mash("link1");
+        assert_vfs_mkdir_p!(vfs, &dir1);
+        assert_vfs_mkfile!(vfs, &file1);
+
+        // creates the link
+        assert!(vfs.ensure_symlink(&link1, "file1").unwrap());
+        assert_vfs_readlink_abs!(vfs, &link1, &file1);
+
+        // converged for both relative and absolute targets
+        assert!(!vfs.ensure_symlink(&link1, "file1").unwrap());
+        assert!(!vfs.ensure_symlink(&link1, &file1).unwrap());
+
+        // replaces links pointing elsewhere including dangling ones
+        assert!(vfs.ensure_symlink(&link1, &dir1).unwrap());
+        assert_vfs_readlink_abs!(vfs, &link1, &dir1);
+        assert!(vfs.ensure_symlink(&link1, "missing").unwrap());
+        assert!(vfs.ensure_symlink(&link1, "file1").unwrap());
+        assert_vfs_readlink_abs!(vfs, &link1, &file1);
+        assert_iter_eq(vfs.all_paths(&tmpdir).unwrap(), vec![dir1.clone(), file1.clone(), link1.clone()]);
+
+        // not a link
+        assert_eq!(
+            vfs.ensure_symlink(&file1, &dir1).unwrap_err().to_string(),
+            PathError::is_not_symlink(&file1).to_string()
+        );
+        assert_eq!(
+            vfs.ensure_symlink(&dir1, &file1).unwrap_err().to_string(),
+            PathError::is_not_symlink(&dir1).to_string()
+        );
+    }
+
+    #[test]
+    fn test_events() {
+        let memfs = Memfs::new();
+        let dir1 = memfs.root().mash("dir1");
+        let dir2 = memfs.root().mash("dir2");
+        let file1 = dir1.mash("file1");
+        let file2 = dir2.mash("file1");
+
+        // Events prior to subscribing are not received
+        assert_vfs_mkdir_p!(memfs, &dir1);
+        let events = memfs.events();
+
+        // Writes and appends
+        assert_vfs_write_all!(memfs, &file1, "foo");
+        assert!(memfs.append_all(&file1, "bar").is_ok());
+        let mut f = memfs.write(&file1).unwrap();
+        f.write_all(b"foobar").unwrap();
+        f.flush().unwrap();
+        drop(f);
+
+        // Permissions and ownership
+        assert!(memfs.chmod(&file1, 0o600).is_ok());
+        assert!(memfs.chown(&file1, 5, 7).is_ok());
+
+        // Renames and removals
+        assert!(memfs.move_p(&dir1, &dir2).is_ok());
+        assert!(memfs.remove_all(&dir2).is_ok());
+        assert!(memfs.remove(&dir2).is_ok());
+
+        assert_eq!(events.try_iter().collect::<Vec<_>>(), vec![
+            VfsEvent::Create(file1.clone()),
+            VfsEvent::Write(file1.clone()),
+            VfsEvent::Write(file1.clone()),
+            VfsEvent::Write(file1.clone()),
+            VfsEvent::Chmod(file1.clone(), 0o100644, 0o100600),
+            VfsEvent::Chown(file1.clone(), (1000, 1000), (5, 7)),
+            VfsEvent::Rename(dir1.clone(), dir2.clone()),
+            VfsEvent::Remove(file2),
+            VfsEvent::Remove(dir2),
+        ]);
+
+        // Dropped subscribers are cleaned up
+        drop(events);
+        assert_vfs_mkdir_p!(memfs, &dir1);
+        assert_eq!(memfs.0.read().unwrap().events.len(), 0);
+    }
+
     #[test]
     fn test_exists() {
         let memfs = Memfs::new();
         let dir1 = memfs.root().mash("dir1");
 
         // abs fails
-        assert_eq!(memfs.exists(""), false);
+        assert!(!memfs.exists(""));
 
         // Doesn't exist
-        assert_eq!(memfs.exists(&dir1), false);
+        assert!(!memfs.exists(&dir1));
 
         // Exists
         assert_eq!(&memfs.mkdir_p(&dir1).unwrap(), &dir1);
-        assert_eq!(memfs.exists(&dir1), true);
+        assert!(memfs.exists(&dir1));
+    }
+
+    #[test]
+    fn test_exists_all() {
+        let memfs = Memfs::new();
+        let dir1 = memfs.root().mash("dir1");
+        let file1 = dir1.mash("file1");
+
+        assert_eq!(memfs.exists_all::<PathBuf>(&[]), Vec::<bool>::new());
+        assert_eq!(memfs.exists_all(&[&dir1, &file1]), vec![false, false]);
+        assert_vfs_mkdir_p!(memfs, &dir1);
+        assert_eq!(memfs.exists_all(&[&dir1, &file1]), vec![true, false]);
+        assert_vfs_mkfile!(memfs, &file1);
+        assert_eq!(memfs.exists_all(&[&dir1, &file1]), vec![true, true]);
+
+        // abs fails
+        assert_eq!(memfs.exists_all(&["", "/dir1/file1"]), vec![false, true]);
     }
 
     #[test]
@@ -2634,6 +5401,24 @@ mod tests {
         assert_iter_eq(vfs.files(&tmpdir).unwrap(), vec![file1, file2]);
     }
 
+    #[test]
+    fn test_first_existing() {
+        let memfs = Memfs::new();
+        let dir1 = memfs.root().mash("dir1");
+        let file1 = dir1.mash("file1");
+
+        assert_eq!(memfs.first_existing::<PathBuf>(&[]), None);
+        assert_eq!(memfs.first_existing(&[&file1, &dir1]), None);
+        assert_vfs_mkdir_p!(memfs, &dir1);
+        assert_eq!(memfs.first_existing(&[&file1, &dir1]), Some(dir1.clone()));
+        assert_vfs_mkfile!(memfs, &file1);
+        assert_eq!(memfs.first_existing(&[&file1, &dir1]), Some(file1.clone()));
+
+        // abs fails are skipped and paths are returned in absolute form
+        assert_eq!(memfs.first_existing(&["", "dir1/file1"]), Some(file1.clone()));
+        assert_eq!(memfs.first_existing(&["/dir1/../dir1/file1"]), Some(file1));
+    }
+
     #[test]
     fn test_gid_uid() {
         let vfs = Memfs::new();
@@ -2644,18 +5429,131 @@ mod tests {
         assert_vfs_mkfile!(vfs, &file);
     }
 
+    #[test]
+    fn test_hardlink() {
+        let vfs = Memfs::new();
+        let dir1 = vfs.root().mash("dir1");
+        let dir2 = dir1.mash("dir2");
+        let file1 = dir1.mash("file1");
+        let file2 = vfs.root().mash("file2");
+        let file3 = vfs.root().mash("file3");
+        let link1 = vfs.root().mash("link1");
+        let link2 = vfs.root().mash("link2");
+        assert_vfs_mkdir_p!(vfs, &dir2);
+        assert_vfs_write_all!(vfs, &file1, "foo");
+        assert_vfs_symlink!(vfs, &link1, &file1);
+
+        // Directories count themselves, their parent's entry and their sub directories
+        assert_eq!(vfs.nlink(vfs.root()).unwrap(), 3);
+        assert_eq!(vfs.nlink(&dir1).unwrap(), 3);
+        assert_eq!(vfs.nlink(&dir2).unwrap(), 2);
+
+        // Links to symlinks are links to the symlink itself
+        assert_vfs_hardlink!(vfs, &link1, &link2);
+        assert_eq!(vfs.nlink(&link1).unwrap(), 2);
+        assert_eq!(vfs.nlink(&file1).unwrap(), 1);
+        assert_vfs_readlink_abs!(vfs, &link2, &file1);
+
+        // The data and its links survive the removal of the original's directory
+        assert_vfs_hardlink!(vfs, &file1, &file2);
+        assert_vfs_hardlink!(vfs, &file1, &file3);
+        assert_vfs_remove_all!(vfs, &dir1);
+        assert_eq!(vfs.nlink(&file2).unwrap(), 2);
+        assert_vfs_read_all!(vfs, &file3, "foo");
+        assert_eq!(Memfs::_dump(&vfs.0.read().unwrap()).iter().map(|x| x.size).sum::<u64>(), 6);
+
+        // Moving a file over one of its links leaves the other name
+        assert!(vfs.move_p(&file2, &file3).is_ok());
+        assert_eq!(vfs.nlink(&file3).unwrap(), 1);
+        assert_vfs_read_all!(vfs, &file3, "foo");
+    }
+
+    #[test]
+    fn test_history() {
+        let memfs = Memfs::new();
+        let dir1 = memfs.root().mash("dir1");
+        let file1 = memfs.root().mash("file1");
+        let file2 = memfs.root().mash("file2");
+        let link1 = memfs.root().mash("link1");
+
+        // Disabled by default
+        assert_vfs_write_all!(memfs, &file1, "1");
+        assert_eq!(memfs.versions(&file1).unwrap(), Vec::<usize>::new());
+
+        // Errors
+        assert_vfs_mkdir_p!(memfs, &dir1);
+        assert_eq!(
+            memfs.versions(&dir1).unwrap_err().downcast_ref::<PathError>(),
+            Some(&PathError::is_not_file(&dir1))
+        );
+        assert_eq!(
+            memfs.versions(&file2).unwrap_err().downcast_ref::<PathError>(),
+            Some(&PathError::does_not_exist(&file2))
+        );
+        assert_eq!(
+            memfs.read_at_version(&file1, 1).unwrap_err().downcast_ref::<VfsError>(),
+            Some(&VfsError::VersionNotFound(file1.clone(), 1))
+        );
+
+        // Writes, appends and allocations are recorded
+        memfs.set_history(3);
+        assert!(memfs.write_all(&file1, "2").is_ok());
+        assert!(memfs.append_all(&file1, "3").is_ok());
+        assert!(memfs.allocate(&file1, 3).is_ok());
+        assert_eq!(memfs.versions(&file1).unwrap(), vec![1, 2, 3]);
+        assert_eq!(memfs.read_at_version(&file1, 1).unwrap(), "2");
+        assert_eq!(memfs.read_at_version(&file1, 2).unwrap(), "23");
+        assert_eq!(memfs.read_at_version(&file1, 3).unwrap(), "23\0");
+
+        // Oldest versions are dropped keeping their numbers
+        assert!(memfs.write_all(&file1, "4").is_ok());
+        assert_eq!(memfs.versions(&file1).unwrap(), vec![2, 3, 4]);
+        assert_eq!(
+            memfs.read_at_version(&file1, 1).unwrap_err().downcast_ref::<VfsError>(),
+            Some(&VfsError::VersionNotFound(file1.clone(), 1))
+        );
+        assert_eq!(memfs.read_at_version(&file1, 4).unwrap(), "4");
+
+        // Links are followed
+        assert_vfs_symlink!(memfs, &link1, &file1);
+        assert_eq!(memfs.versions(&link1).unwrap(), vec![2, 3, 4]);
+
+        // Copies and moves record the destination
+        assert!(memfs.copy(&file1, &file2).is_ok());
+        assert_eq!(memfs.versions(&file2).unwrap(), vec![1]);
+        assert!(memfs.write_all(&file1, "5").is_ok());
+        assert!(memfs.move_p(&file1, &file2).is_ok());
+        assert_eq!(memfs.versions(&file2).unwrap(), vec![1, 2]);
+        assert_eq!(memfs.read_at_version(&file2, 1).unwrap(), "4");
+        assert_eq!(memfs.read_at_version(&file2, 2).unwrap(), "5");
+
+        // History survives removal
+        assert_vfs_remove!(memfs, &file2);
+        assert_eq!(memfs.read_at_version(&file2, 2).unwrap(), "5");
+        assert_eq!(memfs.versions(&file1).unwrap(), vec![3, 4, 5]);
+
+        // Shrinking the limit drops versions and zero disables
+        memfs.set_history(1);
+        assert_eq!(memfs.versions(&file1).unwrap(), vec![5]);
+        memfs.set_history(0);
+        assert_eq!(
+            memfs.versions(&file2).unwrap_err().downcast_ref::<PathError>(),
+            Some(&PathError::does_not_exist(&file2))
+        );
+    }
+
     #[test]
     fn test_is_exec() {
         let vfs = Memfs::new();
         let file = vfs.root().mash("file");
 
         // abs fails
-        assert_eq!(vfs.is_exec(""), false);
+        assert!(!vfs.is_exec(""));
 
         assert!(vfs.mkfile_m(&file, 0o644).is_ok());
-        assert_eq!(vfs.is_exec(&file), false);
+        assert!(!vfs.is_exec(&file));
         assert!(vfs.chmod(&file, 0o777).is_ok());
-        assert_eq!(vfs.is_exec(&file), true);
+        assert!(vfs.is_exec(&file));
     }
 
     #[test]
@@ -2664,14 +5562,14 @@ mod tests {
         let dir1 = memfs.root().mash("dir1");
 
         // abs fails
-        assert_eq!(memfs.is_dir(""), false);
+        assert!(!memfs.is_dir(""));
 
         // Doesn't exist
-        assert_eq!(memfs.is_dir(&dir1), false);
+        assert!(!memfs.is_dir(&dir1));
 
         // Exists
         assert_eq!(&memfs.mkdir_p(&dir1).unwrap(), &dir1);
-        assert_eq!(memfs.is_dir(&dir1), true);
+        assert!(memfs.is_dir(&dir1));
     }
 
     #[test]
@@ -2680,14 +5578,14 @@ mod tests {
         let file = memfs.root().mash("file");
 
         // abs fails
-        assert_eq!(memfs.is_file(""), false);
+        assert!(!memfs.is_file(""));
 
         // Doesn't exist
-        assert_eq!(memfs.is_file(&file), false);
+        assert!(!memfs.is_file(&file));
 
         // Exists
         assert_eq!(&memfs.mkfile(&file).unwrap(), &file);
-        assert_eq!(memfs.is_file(&file), true);
+        assert!(memfs.is_file(&file));
     }
 
     #[test]
@@ -2696,13 +5594,13 @@ mod tests {
         let file = vfs.root().mash("file");
 
         // abs fails
-        assert_eq!(vfs.is_readonly(""), false);
+        assert!(!vfs.is_readonly(""));
 
         assert!(vfs.mkfile_m(&file, 0o644).is_ok());
-        assert_eq!(vfs.is_readonly(&file), false);
+        assert!(!vfs.is_readonly(&file));
         assert!(vfs.chmod_b(&file).unwrap().readonly().exec().is_ok());
         assert_eq!(vfs.mode(&file).unwrap(), 0o100444);
-        assert_eq!(vfs.is_readonly(&file), true);
+        assert!(vfs.is_readonly(&file));
     }
 
     #[test]
@@ -2712,14 +5610,14 @@ mod tests {
         let link = memfs.root().mash("link");
 
         // abs fails
-        assert_eq!(memfs.is_symlink(""), false);
+        assert!(!memfs.is_symlink(""));
 
         // Doesn't exist
-        assert_eq!(memfs.is_symlink(&file), false);
+        assert!(!memfs.is_symlink(&file));
 
         // Exists
         assert_eq!(&memfs.symlink(&link, &file).unwrap(), &link);
-        assert_eq!(memfs.is_symlink(&link), true);
+        assert!(memfs.is_symlink(&link));
     }
 
     #[test]
@@ -2740,19 +5638,19 @@ mod tests {
         let dir = memfs.root().mash("dir");
 
         // Check single top level
-        assert_eq!(memfs.exists(&dir), false);
+        assert!(!memfs.exists(&dir));
         assert_eq!(&memfs.mkdir_p(&dir).unwrap(), &dir);
-        assert_eq!(memfs.exists(&dir), true);
-        assert_eq!(memfs.exists("dir"), true); // check relative
+        assert!(memfs.exists(&dir));
+        assert!(memfs.exists("dir")); // check relative
 
         // Check nested
         let dir1 = memfs.root().mash("dir1");
         let dir2 = dir1.mash("dir2");
         let dir3 = dir2.mash("dir3");
         assert_eq!(&memfs.mkdir_p(&dir3).unwrap(), &dir3);
-        assert_eq!(memfs.exists(&dir3), true);
-        assert_eq!(memfs.exists(&dir2), true);
-        assert_eq!(memfs.exists(&dir1), true);
+        assert!(memfs.exists(&dir3));
+        assert!(memfs.exists(&dir2));
+        assert!(memfs.exists(&dir1));
     }
 
     #[test]
@@ -2789,14 +5687,14 @@ mod tests {
         assert_eq!(memfs.mkfile(&dir1).unwrap_err().to_string(), PathError::is_not_file(&dir1).to_string());
 
         // Make a file in the root
-        assert_eq!(memfs.exists("file2"), false);
+        assert!(!memfs.exists("file2"));
         assert_eq!(memfs.mkfile("file2").unwrap(), memfs.root().mash("file2"));
-        assert_eq!(memfs.exists("file2"), true);
+        assert!(memfs.exists("file2"));
 
         // Make a file in a directory
-        assert_eq!(memfs.exists(&file1), false);
+        assert!(!memfs.exists(&file1));
         assert_eq!(&memfs.mkfile(&file1).unwrap(), &file1);
-        assert_eq!(memfs.exists(&file1), true);
+        assert!(memfs.exists(&file1));
 
         // Error: parent exists and is not a directory
         let file2 = file1.mash("file2");
@@ -2861,13 +5759,13 @@ mod tests {
         assert_eq!(entries.len(), 2);
         assert_eq!(entries[&dir1].path(), &dir1);
         assert_eq!(entries[&dir1].files.as_ref().unwrap().len(), 1);
-        assert_eq!(entries[&dir1].files.as_ref().unwrap().contains("file2"), true);
+        assert!(entries[&dir1].files.as_ref().unwrap().contains("file2"));
         assert_eq!(entries[&dir1file2].path(), &dir1file2);
         assert_eq!(entries[&dir1file2].files, None);
         {
-            let root = vfs._clone_entry(&vfs.read_guard(), &vfs.root()).unwrap();
+            let root = vfs._clone_entry(&vfs.read_guard(), vfs.root()).unwrap();
             assert_eq!(root.files.as_ref().unwrap().len(), 1);
-            assert_eq!(root.files.as_ref().unwrap().contains("dir1"), true);
+            assert!(root.files.as_ref().unwrap().contains("dir1"));
         }
 
         // move dir1 to dir2
@@ -2881,16 +5779,16 @@ mod tests {
         assert_eq!(entries.len(), 3);
         assert_eq!(entries[&dir2].path(), &dir2);
         assert_eq!(entries[&dir2].files.as_ref().unwrap().len(), 1);
-        assert_eq!(entries[&dir2].files.as_ref().unwrap().contains("dir1"), true);
+        assert!(entries[&dir2].files.as_ref().unwrap().contains("dir1"));
         assert_eq!(entries[&dir2dir1].path(), &dir2dir1);
         assert_eq!(entries[&dir2dir1].files.as_ref().unwrap().len(), 1);
-        assert_eq!(entries[&dir2dir1].files.as_ref().unwrap().contains("file2"), true);
+        assert!(entries[&dir2dir1].files.as_ref().unwrap().contains("file2"));
         assert_eq!(entries[&dir2dir1file2].path(), &dir2dir1file2);
         assert_eq!(entries[&dir2dir1file2].files, None);
         {
-            let root = vfs._clone_entry(&vfs.read_guard(), &vfs.root()).unwrap();
+            let root = vfs._clone_entry(&vfs.read_guard(), vfs.root()).unwrap();
             assert_eq!(root.files.as_ref().unwrap().len(), 1);
-            assert_eq!(root.files.as_ref().unwrap().contains("dir2"), true);
+            assert!(root.files.as_ref().unwrap().contains("dir2"));
         }
 
         // move dir2 into dir3
@@ -2903,22 +5801,22 @@ mod tests {
         assert_eq!(entries.len(), 4);
         assert_eq!(entries[&dir3].path(), &dir3);
         assert_eq!(entries[&dir3].files.as_ref().unwrap().len(), 1);
-        assert_eq!(entries[&dir3].files.as_ref().unwrap().contains("dir2"), true);
+        assert!(entries[&dir3].files.as_ref().unwrap().contains("dir2"));
         assert_eq!(entries[&dir3dir2].path(), &dir3dir2);
         assert_eq!(entries[&dir3dir2].files.as_ref().unwrap().len(), 1);
-        assert_eq!(entries[&dir3dir2].files.as_ref().unwrap().contains("dir1"), true);
+        assert!(entries[&dir3dir2].files.as_ref().unwrap().contains("dir1"));
         assert_eq!(entries[&dir3dir2dir1].path(), &dir3dir2dir1);
         assert_eq!(entries[&dir3dir2dir1].files.as_ref().unwrap().len(), 1);
-        assert_eq!(entries[&dir3dir2dir1].files.as_ref().unwrap().contains("file2"), true);
+        assert!(entries[&dir3dir2dir1].files.as_ref().unwrap().contains("file2"));
         assert_eq!(entries[&dir3dir2dir1file2].path(), &dir3dir2dir1file2);
         assert_eq!(entries[&dir3dir2dir1file2].files, None);
         assert_vfs_exists!(vfs, &dir3dir2);
         assert_vfs_exists!(vfs, &dir3dir2dir1);
         assert_vfs_read_all!(vfs, &dir3dir2dir1file2, "file1");
         {
-            let root = vfs._clone_entry(&vfs.read_guard(), &vfs.root()).unwrap();
+            let root = vfs._clone_entry(&vfs.read_guard(), vfs.root()).unwrap();
             assert_eq!(root.files.as_ref().unwrap().len(), 1);
-            assert_eq!(root.files.as_ref().unwrap().contains("dir3"), true);
+            assert!(root.files.as_ref().unwrap().contains("dir3"));
         }
     }
 
@@ -3066,6 +5964,200 @@ mod tests {
         assert_vfs_no_exists!(vfs, &dir);
     }
 
+    #[test]
+    fn test_same_file() {
+        let vfs = Memfs::new();
+        let dir = vfs.root().mash("dir");
+        let file1 = dir.mash("file1");
+        let file2 = dir.mash("file2");
+        let link1 = vfs.root().mash("link1");
+        let link2 = vfs.root().mash("link2");
+
+        // Doesn't exist
+        assert!(!vfs.same_file(&file1, &file1));
+
+        // Links resolve to their targets
+        assert_vfs_mkdir_p!(vfs, &dir);
+        assert_vfs_write_all!(vfs, &file1, "file1");
+        assert_vfs_mkfile!(vfs, &file2);
+        assert_vfs_symlink!(vfs, &link1, &file1);
+        assert_vfs_symlink!(vfs, &link2, &link1);
+        assert!(vfs.same_file(&file1, &file1));
+        assert!(vfs.same_file(&link1, &file1));
+        assert!(vfs.same_file(&link2, &file1));
+        assert!(!vfs.same_file(&link1, &file2));
+        assert!(!vfs.same_file(&file1, vfs.root().mash("foo")));
+
+        // Copies are not the same file
+        assert!(vfs.copy(&file1, vfs.root().mash("file3")).is_ok());
+        assert!(!vfs.same_file(&file1, vfs.root().mash("file3")));
+
+        // Copying a file onto itself through a link is a no-op
+        assert!(vfs.copy_b(&link1, &file1).unwrap().follow(true).exec().is_ok());
+        assert_vfs_read_all!(vfs, &file1, "file1");
+        assert_vfs_is_symlink!(vfs, &link1);
+    }
+
+    #[test]
+    fn test_set_file_time() {
+        let memfs = Memfs::new();
+        let file1 = memfs.root().mash("file1");
+        let time = std::time::UNIX_EPOCH + std::time::Duration::new(1_000_000_000, 123_456_789);
+
+        // Errors
+        assert_eq!(
+            memfs.set_file_time(&file1, time, time).unwrap_err().downcast_ref::<PathError>(),
+            Some(&PathError::does_not_exist(&file1))
+        );
+
+        // Nanoseconds are kept
+        assert_vfs_mkfile!(memfs, &file1);
+        let events = memfs.events();
+        assert!(memfs.set_file_time(&file1, time, time).is_ok());
+        assert_eq!(memfs.entry(&file1).unwrap().modified(), time);
+        assert_eq!(events.try_recv().unwrap(), VfsEvent::Write(file1.clone()));
+
+        let time = time + std::time::Duration::from_nanos(1);
+        assert!(memfs.set_mtime_only(&file1, time).is_ok());
+        assert_eq!(memfs.entry(&file1).unwrap().modified(), time);
+    }
+
+    #[test]
+    fn test_set_quota() {
+        let memfs = Memfs::new();
+        let dir1 = memfs.root().mash("dir1");
+        let dir2 = dir1.mash("dir2");
+        let file1 = dir1.mash("file1");
+        let file2 = dir2.mash("file2");
+        let file3 = memfs.root().mash("file3");
+        assert_vfs_mkdir_p!(memfs, &dir1);
+        assert_vfs_write_all!(memfs, &file3, "foobar");
+
+        // Errors
+        assert_eq!(
+            memfs.set_quota(&file3, 1, 1).unwrap_err().downcast_ref::<PathError>(),
+            Some(&PathError::is_not_dir(&file3))
+        );
+        assert_eq!(
+            memfs.set_quota(memfs.root().mash("foo"), 1, 1).unwrap_err().downcast_ref::<PathError>(),
+            Some(&PathError::does_not_exist(memfs.root().mash("foo")))
+        );
+
+        // Byte limit covers nested directories
+        assert!(memfs.set_quota(&dir1, 6, 3).is_ok());
+        assert_vfs_mkdir_p!(memfs, &dir2);
+        assert_vfs_write_all!(memfs, &file1, "foo");
+        let err = memfs.write_all(&file2, "foobar").unwrap_err();
+        assert_eq!(err.to_string(), VfsError::QuotaExceeded(dir1.clone()).to_string());
+        assert_vfs_read_all!(memfs, &file2, "");
+        assert!(memfs.write_all(&file2, "bar").is_ok());
+
+        // Rewriting a file only counts its new size
+        assert!(memfs.write_all(&file1, "f").is_ok());
+        assert!(memfs.write_all(&file1, "foo").is_ok());
+        assert!(memfs.append_all(&file1, "o").is_err());
+        assert_vfs_read_all!(memfs, &file1, "foo");
+
+        // Inode limit
+        assert_eq!(
+            memfs.mkdir_p(dir1.mash("dir3")).unwrap_err().downcast_ref::<VfsError>(),
+            Some(&VfsError::QuotaExceeded(dir1.clone()))
+        );
+        assert_eq!(
+            memfs.copy(&file3, dir1.mash("file4")).unwrap_err().downcast_ref::<VfsError>(),
+            Some(&VfsError::QuotaExceeded(dir1.clone()))
+        );
+        assert_vfs_no_exists!(memfs, dir1.mash("file4"));
+
+        // Lifting the quota
+        assert!(memfs.set_quota(&dir1, u64::MAX, u64::MAX).is_ok());
+        assert!(memfs.copy(&file3, dir1.mash("file4")).is_ok());
+        assert_vfs_read_all!(memfs, dir1.mash("file4"), "foobar");
+    }
+
+    #[test]
+    fn test_seed() {
+        let memfs = Memfs::new();
+        let dir1 = memfs.root().mash("dir1");
+        let file1 = dir1.mash("file1");
+        let file2 = memfs.root().mash("file2");
+
+        // parents created and existing files overwritten
+        assert_vfs_write_all!(memfs, &file2, "foo");
+        assert!(memfs.seed(&[(&file1, "foo".as_bytes()), (&file2, &[0u8, 159][..])]).is_ok());
+        assert_vfs_is_dir!(memfs, &dir1);
+        assert_vfs_read_all!(memfs, &file1, "foo");
+        let mut data = vec![];
+        assert!(memfs.read(&file2).unwrap().read_to_end(&mut data).is_ok());
+        assert_eq!(data, vec![0u8, 159]);
+
+        // errors
+        assert_eq!(
+            memfs.seed(&[(file1.mash("file3"), "bar")]).unwrap_err().downcast_ref::<PathError>(),
+            Some(&PathError::is_not_dir(&file1))
+        );
+
+        // embedded at compile time
+        let memfs = memfs_seed!("LICENSE-MIT", "LICENSE-APACHE",).unwrap();
+        assert_eq!(memfs.read_all("/LICENSE-MIT").unwrap(), include_str!("../../../../LICENSE-MIT"));
+        assert_eq!(memfs.read_all("/LICENSE-APACHE").unwrap(), include_str!("../../../../LICENSE-APACHE"));
+        assert_eq!(memfs_seed!().unwrap().paths(Memfs::new().root()).unwrap(), Vec::<PathBuf>::new());
+    }
+
+    #[test]
+    fn test_stat_fs() {
+        let memfs = Memfs::new();
+        let dir1 = memfs.root().mash("dir1");
+        let dir2 = dir1.mash("dir2");
+        let file1 = dir1.mash("file1");
+        let file2 = dir2.mash("file2");
+        let file3 = memfs.root().mash("file3");
+        assert_vfs_mkdir_p!(memfs, &dir2);
+        assert_vfs_write_all!(memfs, &file1, "foo");
+        assert_vfs_write_all!(memfs, &file2, "foobar");
+        assert_vfs_write_all!(memfs, &file3, "1");
+
+        // Doesn't exist
+        assert_eq!(
+            memfs.stat_fs(memfs.root().mash("foo")).unwrap_err().downcast_ref::<PathError>(),
+            Some(&PathError::does_not_exist(memfs.root().mash("foo")))
+        );
+
+        // Whole filesystem without quotas
+        assert_eq!(memfs.stat_fs(&file1).unwrap(), VfsStatFs {
+            total: u64::MAX,
+            used: 10,
+            free: u64::MAX - 10,
+            inodes: u64::MAX,
+            inodes_used: 5,
+            inodes_free: u64::MAX - 5,
+        });
+
+        // Deepest quota containing the path
+        assert!(memfs.set_quota(&dir1, 100, 10).is_ok());
+        assert!(memfs.set_quota(&dir2, 8, 2).is_ok());
+        assert_eq!(memfs.stat_fs(&dir1).unwrap(), VfsStatFs {
+            total: 100,
+            used: 9,
+            free: 91,
+            inodes: 10,
+            inodes_used: 3,
+            inodes_free: 7,
+        });
+        assert_eq!(memfs.stat_fs(&file2).unwrap(), VfsStatFs {
+            total: 8,
+            used: 6,
+            free: 2,
+            inodes: 2,
+            inodes_used: 1,
+            inodes_free: 1,
+        });
+
+        // Usage over a lowered quota reports no free space
+        assert!(memfs.set_quota(&dir2, 4, 2).is_ok());
+        assert_eq!(memfs.stat_fs(&dir2).unwrap().free, 0);
+    }
+
     #[test]
     fn test_symlink() {
         let vfs = Memfs::new().upcast();
@@ -3086,10 +6178,10 @@ mod tests {
             let guard = memfs.read_guard();
 
             // Ensure that no file was created for the links
-            assert_eq!(guard.contains_file(&file1), true);
-            assert_eq!(guard.contains_file(&file2), false);
-            assert_eq!(guard.contains_file(&link1), false);
-            assert_eq!(guard.contains_file(&link2), false);
+            assert!(guard.contains_file(&file1));
+            assert!(!guard.contains_file(&file2));
+            assert!(!guard.contains_file(&link1));
+            assert!(!guard.contains_file(&link2));
 
             // Ensure dir link has the right properties
             if let Some(entry) = guard.get_entry(&link1) {
@@ -3117,6 +6209,87 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_to_manifest() {
+        let memfs1 = Memfs::new();
+        let dir1 = memfs1.root().mash("dir1");
+        let dir2 = dir1.mash("dir2");
+        let file1 = dir1.mash("file1");
+        let file2 = dir2.mash("file2");
+        let link1 = memfs1.root().mash("link1");
+        let link2 = dir2.mash("link2");
+
+        // Empty
+        assert_eq!(memfs1.to_manifest(), Manifest::new());
+        assert_eq!(Memfs::from_manifest(&Manifest::new()).unwrap(), memfs1);
+
+        // Round trip modes, ownership, data and links both absolute and relative
+        assert_vfs_mkdir_p!(memfs1, &dir2);
+        assert_vfs_write_all!(memfs1, &file1, "foo");
+        assert_vfs_write_all!(memfs1, &file2, [0xff, 0x00]);
+        assert_vfs_symlink!(memfs1, &link1, &file1);
+        assert_vfs_symlink!(memfs1, &link2, "../file1");
+        assert!(memfs1.chmod(&dir2, 0o700).is_ok());
+        assert!(memfs1.chmod(&file1, 0o600).is_ok());
+        assert!(memfs1.chown(&dir1, 5, 6).is_ok());
+        let manifest = memfs1.to_manifest();
+        let memfs2 = Memfs::from_manifest(&manifest).unwrap();
+        assert_eq!(memfs1, memfs2);
+        assert_eq!(memfs2.owner(&dir1).unwrap(), (5, 6));
+        assert_eq!(memfs2.readlink(&link2).unwrap(), PathBuf::from("../file1"));
+        assert_eq!(memfs2.to_manifest(), manifest);
+
+        // Files declared before their directories fail
+        let manifest = Manifest::new().file("/dir/file", "foo", 0o644);
+        assert_eq!(
+            Memfs::from_manifest(&manifest).unwrap_err().downcast_ref::<PathError>(),
+            Some(&PathError::does_not_exist("/dir"))
+        );
+    }
+
+    #[test]
+    fn test_tree_eq() {
+        let memfs1 = Memfs::new();
+        let memfs2 = Memfs::new();
+        let dir1 = memfs1.root().mash("dir1");
+        let file1 = dir1.mash("file1");
+        let link1 = memfs1.root().mash("link1");
+        assert_eq!(memfs1, memfs1);
+        assert_eq!(memfs1, memfs2);
+
+        // Build the same tree in each in a different order
+        assert_vfs_mkdir_p!(memfs1, &dir1);
+        assert_vfs_write_all!(memfs1, &file1, "foobar");
+        assert_vfs_symlink!(memfs1, &link1, &file1);
+        assert_vfs_symlink!(memfs2, &link1, &file1);
+        assert_ne!(memfs1, memfs2);
+        assert_vfs_mkdir_p!(memfs2, &dir1);
+        assert_vfs_write_all!(memfs2, &file1, "foobar");
+        assert_eq!(memfs1, memfs2);
+        assert!(memfs2.tree_eq(&memfs1));
+
+        // Timestamps and the current working directory are ignored
+        assert!(memfs2.set_mtime_only(&file1, std::time::UNIX_EPOCH).is_ok());
+        assert!(memfs2.set_cwd(&dir1).is_ok());
+        assert_eq!(memfs1, memfs2);
+
+        // Contents differ with the same size
+        assert!(memfs2.write_all(&file1, "barfoo").is_ok());
+        assert_ne!(memfs1, memfs2);
+        assert!(memfs2.write_all(&file1, "foobar").is_ok());
+
+        // Modes differ
+        assert!(memfs2.chmod(&file1, 0o600).is_ok());
+        assert_ne!(memfs1, memfs2);
+        assert!(memfs2.chmod(&file1, 0o644).is_ok());
+        assert_eq!(memfs1, memfs2);
+
+        // Link targets differ
+        assert!(memfs2.remove(&link1).is_ok());
+        assert_vfs_symlink!(memfs2, &link1, &dir1);
+        assert_ne!(memfs1, memfs2);
+    }
+
     #[test]
     fn test_write() {
         let vfs = Memfs::new();
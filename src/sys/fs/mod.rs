@@ -1,22 +1,86 @@
+mod acl;
+mod advice;
+mod builder;
+mod cancel;
 mod chmod;
 mod chown;
+mod config;
+mod context;
 mod copy;
+#[cfg(feature = "csv")]
+mod csv;
+#[cfg(feature = "digest")]
+mod digest;
+mod dir;
+mod ensured;
 mod entries;
 mod entry;
 mod entry_iter;
+mod event;
+mod glob;
+mod hook;
+mod install;
+#[cfg(feature = "json")]
+mod jsonl;
+mod link;
+mod manifest;
 mod memfs;
+mod mover;
+mod overlayfs;
 mod path;
+mod policy;
+mod read;
+mod remove;
 mod stdfs;
+mod tar;
+mod usage;
+mod validate;
 mod vfs;
+mod watch;
+mod wc;
+mod write;
+mod writer;
 
+pub use acl::*;
+pub use advice::*;
+pub use builder::*;
+pub use cancel::*;
 pub use chmod::*;
 pub use chown::*;
+pub use config::*;
+pub use context::*;
 pub use copy::*;
+#[cfg(feature = "csv")]
+pub use self::csv::*;
+#[cfg(feature = "digest")]
+pub use digest::*;
+pub use dir::*;
+pub use ensured::*;
 pub use entries::*;
 pub use entry::*;
 #[allow(unused_imports)]
 pub use entry_iter::*;
+pub use event::*;
+pub(crate) use glob::*;
+pub(crate) use hook::*;
+pub use install::*;
+#[cfg(feature = "json")]
+pub use jsonl::*;
+pub use link::*;
+pub use manifest::*;
 pub use memfs::*;
+pub use mover::*;
+pub use overlayfs::*;
 pub use path::*;
+pub use policy::*;
+pub use read::*;
+pub use remove::*;
 pub use stdfs::*;
+pub(crate) use tar::*;
+pub use usage::*;
+pub use validate::*;
 pub use vfs::*;
+pub use watch::*;
+pub use wc::*;
+pub use write::*;
+pub use writer::*;
@@ -0,0 +1,129 @@
+use std::path::PathBuf;
+
+use crate::errors::RvResult;
+
+/// Provides a builder pattern for flexibly moving files
+///
+/// Use the Vfs functions `move_b` to create a new instance followed by one or more options and
+/// complete the operation by calling `exec`.
+///
+/// ```
+/// use rivia::prelude::*;
+///
+/// let vfs = Memfs::new();
+/// let file1 = vfs.root().mash("file1");
+/// let file2 = vfs.root().mash("file2");
+/// assert_vfs_write_all!(vfs, &file1, "this is a test");
+/// assert!(vfs.move_b(&file1, &file2).unwrap().exec().is_ok());
+/// assert_vfs_no_exists!(vfs, &file1);
+/// assert_vfs_read_all!(vfs, &file2, "this is a test");
+/// ```
+pub struct Mover
+{
+    pub(crate) opts: MoveOpts,
+    pub(crate) exec: Box<dyn Fn(MoveOpts) -> RvResult<()>>, // provider callback
+}
+
+// Internal type used to encapsulate just the options. This separates the provider implementation
+// from the options allowing for sharing options between different vfs providers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct MoveOpts
+{
+    pub(crate) src: PathBuf,   // source path
+    pub(crate) dst: PathBuf,   // destination path
+    pub(crate) preserve: bool, // preserve attributes when moving across filesystems
+}
+
+impl Mover
+{
+    /// Update the `preserve` option
+    ///
+    /// * Default: true
+    /// * Only applies when the move crosses filesystems and has to fall back on copy and remove
+    /// * When `true` the mode, owner, timestamps and extended attributes of every entry are carried
+    ///   over to the destination
+    /// * When `false` entries are created as a plain copy would create them with the current user
+    ///   as owner and the current time as timestamps
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Memfs::new();
+    /// let file1 = vfs.root().mash("file1");
+    /// let file2 = vfs.root().mash("file2");
+    /// assert_vfs_mkfile!(vfs, &file1);
+    /// assert!(vfs.move_b(&file1, &file2).unwrap().preserve(false).exec().is_ok());
+    /// assert_vfs_no_exists!(vfs, &file1);
+    /// assert_vfs_is_file!(vfs, &file2);
+    /// ```
+    pub fn preserve(mut self, yes: bool) -> Self
+    {
+        self.opts.preserve = yes;
+        self
+    }
+
+    /// Execute the [`Mover`] builder current options.
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Memfs::new();
+    /// let file1 = vfs.root().mash("file1");
+    /// let file2 = vfs.root().mash("file2");
+    /// assert_vfs_write_all!(vfs, &file1, "this is a test");
+    /// assert!(vfs.move_b(&file1, &file2).unwrap().exec().is_ok());
+    /// assert_vfs_read_all!(vfs, &file2, "this is a test");
+    /// ```
+    pub fn exec(&self) -> RvResult<()>
+    {
+        (self.exec)(self.opts.clone())
+    }
+}
+
+// Unit tests
+// -------------------------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests
+{
+    use crate::prelude::*;
+
+    #[test]
+    fn test_vfs_move_b()
+    {
+        test_move_b(assert_vfs_setup!(Vfs::memfs()));
+        test_move_b(assert_vfs_setup!(Vfs::stdfs()));
+    }
+    fn test_move_b((vfs, tmpdir): (Vfs, PathBuf))
+    {
+        let dir1 = tmpdir.mash("dir1");
+        let file1 = dir1.mash("file1");
+        let dir2 = tmpdir.mash("dir2");
+        let file2 = dir2.mash("file1");
+        let dir3 = tmpdir.mash("dir3");
+        let file3 = dir3.mash("dir2/file1");
+
+        // source doesn't exist
+        assert!(vfs.move_b(&dir1, &dir2).unwrap().exec().is_err());
+        assert_vfs_no_exists!(vfs, &dir2);
+
+        // rename a directory
+        assert_vfs_mkdir_m!(vfs, &dir1, 0o40700);
+        assert_vfs_write_all!(vfs, &file1, "file1");
+        assert!(vfs.chmod(&file1, 0o600).is_ok());
+        assert!(vfs.move_b(&dir1, &dir2).unwrap().exec().is_ok());
+        assert_vfs_no_exists!(vfs, &dir1);
+        assert_vfs_read_all!(vfs, &file2, "file1");
+        assert_eq!(vfs.mode(&dir2).unwrap(), 0o40700);
+        assert_eq!(vfs.mode(&file2).unwrap(), 0o100600);
+
+        // move into an existing directory without preserving
+        assert_vfs_mkdir_p!(vfs, &dir3);
+        assert!(vfs.move_b(&dir2, &dir3).unwrap().preserve(false).exec().is_ok());
+        assert_vfs_no_exists!(vfs, &dir2);
+        assert_vfs_read_all!(vfs, &file3, "file1");
+
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+}
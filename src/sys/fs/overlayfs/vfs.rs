@@ -0,0 +1,3033 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    io::{BufRead, BufReader, Read, Write},
+    path::{Path, PathBuf},
+    sync::{Arc, RwLock},
+    time::{Duration, SystemTime},
+};
+
+use crate::{
+    errors::*,
+    sys::{
+        self, Acl, Advice, Chmod, ChmodOpts, Chown, ChownOpts, Copier, CopyOpts, DirHandle, DiskUsage,
+        DiskUsageOpts, Ensured, Entries, Entry, EntryIter, Installer, Memfs, MemfsEntry, MemfsFile, MemfsMeta,
+        MoveOpts, Mover, PathExt, ReadOpts, ReadSeek, ReadWriteSeek, Reader, RemoveOpts, Remover,
+        ResolvedLink, StdfsMeta, Vfs, VfsConfig, VfsEntry, VfsMetadata, VfsOp, VfsStatFs, VfsWatcher, VfsWriter,
+        VirtualFileSystem, WriteOpts, Writer,
+    },
+};
+
+/// Provides a [`VirtualFileSystem`] backend layering a writable [`Memfs`] over a read only lower
+/// [`Vfs`] in the same vein as the Linux overlay filesystem
+///
+/// * Reads fall through to the lower layer while writes only ever go to the upper Memfs layer
+/// * Lower entries are brought up into the upper layer as their paths are looked up with the data
+///   of lower files only copied up once it is modified
+/// * Removing a lower entry hides it from the overlay leaving the lower layer untouched
+/// * Links along a path are resolved through the overlay so that paths may pass through them
+/// * Hooks, watches and filesystem statistics are those of the upper layer
+/// * The lower layer is expected not to change once its paths have been looked up
+///
+/// ### Examples
+/// ```
+/// use rivia::prelude::*;
+///
+/// let lower = Vfs::memfs();
+/// assert_vfs_write_all!(lower, "/file", "foobar");
+/// let vfs = Overlayfs::new(lower);
+/// assert_vfs_read_all!(vfs, "/file", "foobar");
+/// assert!(vfs.write_all("/file", "barfoo").is_ok());
+/// assert_vfs_read_all!(vfs, "/file", "barfoo");
+/// assert_vfs_read_all!(vfs.lower(), "/file", "foobar");
+/// ```
+pub struct Overlayfs(Arc<OverlayfsInner>);
+
+// Encapsulate the layers along with the bookkeeping of the upper entries still mirroring the lower
+struct OverlayfsInner {
+    upper: Memfs,                  // Writable layer holding the merged view
+    lower: Vfs,                    // Read only layer reads fall through to
+    state: RwLock<OverlayfsState>, // Upper entries still mirroring lower entries
+}
+
+// Upper paths mapped to the lower paths they were brought up from
+#[derive(Debug, Default)]
+struct OverlayfsState {
+    unmerged: HashMap<PathBuf, PathBuf>,   // Dirs yet to be populated with the lower dir's entries
+    lazy: HashMap<PathBuf, (PathBuf, u64)>, // Files whose data and size are still the lower file's
+    pristine: HashMap<PathBuf, PathBuf>,   // Entries whose ACL, context and flags are still the lower's
+    ids: HashMap<(u64, u64), PathBuf>,     // Upper path holding the data of each hard linked lower file
+}
+
+impl Overlayfs {
+    /// Create a new Overlayfs instance layering a new Memfs over the given lower Vfs
+    ///
+    /// * The upper layer takes on the config of the lower layer
+    /// * The current working directory starts out as that of the lower layer
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Overlayfs::new(Vfs::stdfs());
+    /// assert_eq!(vfs.cwd().unwrap(), Vfs::stdfs().cwd().unwrap());
+    /// ```
+    pub fn new(lower: Vfs) -> Self {
+        let upper = Memfs::with_config(lower.config());
+        let mut state = OverlayfsState::default();
+        state.unmerged.insert(upper.root(), lower.root());
+        state.pristine.insert(upper.root(), lower.root());
+        let vfs = Self(Arc::new(OverlayfsInner { upper, lower, state: RwLock::new(state) }));
+
+        // Mirror the root's attributes and the working directory of the lower layer
+        if let Ok(meta) = vfs.0.lower.metadata(vfs.0.lower.root()) {
+            let mut guard = vfs.0.upper.write_guard();
+            if let Some(entry) = guard.get_entry_mut(&vfs.0.lower.root()) {
+                entry.mode = meta.mode;
+                entry.uid = meta.uid;
+                entry.gid = meta.gid;
+            }
+        }
+        if let Ok(cwd) = vfs.0.lower.cwd().and_then(|x| vfs._fault(x)) {
+            if vfs.0.upper.is_dir(&cwd) {
+                vfs.0.upper.write_guard().set_cwd(cwd);
+            }
+        }
+        vfs
+    }
+
+    /// Returns the read only lower layer of the overlay
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Overlayfs::new(Vfs::memfs());
+    /// assert_vfs_mkfile!(vfs, "/file");
+    /// assert_vfs_no_exists!(vfs.lower(), "/file");
+    /// ```
+    pub fn lower(&self) -> &Vfs {
+        &self.0.lower
+    }
+
+    // Make a clone of the Overlayfs sharing the same layers
+    pub(crate) fn clone(&self) -> Overlayfs {
+        Overlayfs(self.0.clone())
+    }
+
+    /// Bring up the lower entries along the given path so the upper layer can answer for it
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Links along the path are resolved returning the path the upper layer knows it by
+    /// * A link at the end of the path has its targets brought up but is not itself resolved
+    pub(crate) fn _fault<T: AsRef<Path>>(&self, path: T) -> RvResult<PathBuf> {
+        let abs = self.0.upper.abs(path)?;
+        self._fault_hops(&abs, 0)
+    }
+
+    // Bring up the given absolute path counting the links already followed to get here
+    fn _fault_hops(&self, abs: &Path, hops: usize) -> RvResult<PathBuf> {
+        let mut path = self.0.upper.root();
+        let mut components = abs.components().skip(1).peekable();
+        while let Some(component) = components.next() {
+            self._merge(&path)?;
+            path = path.mash(component);
+
+            // Links along the way are resolved so the rest of the path is looked up in their
+            // targets while a link at the end only needs its targets for the upper to follow
+            let last = components.peek().is_none();
+            let mut hops = hops;
+            while let Some(target) = self._link_target(&path) {
+                hops += 1;
+                if hops > sys::DEFAULT_MAX_LINKS {
+                    if last {
+                        break;
+                    }
+                    return Err(PathError::too_many_links(abs).into());
+                }
+                let target = self._fault_hops(&target, hops)?;
+                if last {
+                    break;
+                }
+                path = target;
+            }
+        }
+        Ok(path)
+    }
+
+    /// Bring up the given path returning the upper path of the file its links lead to
+    ///
+    /// * Links are resolved through the merged view so files already copied up are found in
+    ///   the upper layer rather than the lower
+    fn _fault_file<T: AsRef<Path>>(&self, path: T) -> RvResult<PathBuf> {
+        let path = self._fault(path)?;
+        Ok(self._target(&path))
+    }
+
+    /// Bring up everything beneath the given path so the upper layer sees the whole tree
+    ///
+    /// * Expects the path to already be faulted
+    /// * Link targets are brought up as well and their trees too when following
+    /// * Returns every path brought up
+    fn _fault_tree(&self, path: &Path, follow: bool) -> RvResult<Vec<PathBuf>> {
+        let mut seen = HashSet::new();
+        let mut paths = vec![path.to_path_buf()];
+        while let Some(path) = paths.pop() {
+            if !seen.insert(path.clone()) {
+                continue;
+            }
+            self._merge(&path)?;
+            if let Some(target) = self._link_target(&path) {
+                match self._fault(&target) {
+                    Ok(target) if follow => paths.push(target),
+                    _ => (),
+                }
+            }
+            let guard = self.0.upper.read_guard();
+            if let Some(files) = guard.get_entry(&path).and_then(|x| x.files.as_ref()) {
+                paths.extend(files.iter().map(|x| path.mash(x)));
+            }
+        }
+        Ok(seen.into_iter().collect())
+    }
+
+    /// Populate the given upper directory with the entries of the lower directory it mirrors
+    ///
+    /// * Entries already in the upper layer take precedence over their lower counterparts
+    /// * Does nothing for directories already merged or that don't mirror a lower directory
+    fn _merge(&self, dir: &Path) -> RvResult<()> {
+        let lower = match self.0.state.read().unwrap().unmerged.get(dir) {
+            Some(x) => x.clone(),
+            None => return Ok(()),
+        };
+
+        // Read the lower directory before taking any locks. Hard linked files have their data
+        // brought up with them so that all their names can share it.
+        let mut entries = vec![];
+        for entry in self.0.lower.entries(&lower)?.min_depth(1).max_depth(1) {
+            let entry = entry?;
+            if !entry.is_symlink() && !entry.is_dir() && !entry.is_file() {
+                continue;
+            }
+            let mut data = None;
+            if entry.is_file() && !entry.is_symlink() && entry.metadata().nlink > 1 {
+                let mut buf = vec![];
+                self.0.lower.read(entry.path())?.read_to_end(&mut buf)?;
+                data = Some(buf);
+            }
+            entries.push((entry, data));
+        }
+
+        let mut state = self.0.state.write().unwrap();
+        if state.unmerged.remove(dir).is_none() {
+            return Ok(());
+        }
+        let mut guard = self.0.upper.write_guard();
+        for (entry, data) in entries {
+            let name = entry.path().base()?;
+            let path = dir.mash(&name);
+            if guard.contains_entry(&path) {
+                continue;
+            }
+            let meta = entry.metadata();
+            let mut upper = if entry.is_symlink() {
+                let opts = MemfsEntry::opts(&path).file().link_to(entry.alt())?;
+                match entry.is_dir() {
+                    true => opts.dir().link_to(entry.alt())?,
+                    false => opts,
+                }
+                .build()
+            } else if entry.is_dir() {
+                MemfsEntry::opts(&path).dir().mode(Some(meta.mode & 0o7777)).build()
+            } else {
+                MemfsEntry::opts(&path).file().mode(Some(meta.mode & 0o7777)).build()
+            };
+            upper.uid = meta.uid;
+            upper.gid = meta.gid;
+            upper.modified = meta.modified;
+            upper.created = meta.created;
+
+            if entry.is_symlink() {
+            } else if entry.is_dir() {
+                state.unmerged.insert(path.clone(), entry.path_buf());
+            } else if let Some(data) = data {
+                // Names of the same lower file share the identity and data of the first one
+                let id = lower_id(&entry);
+                let first = id.and_then(|x| state.ids.get(&x)).filter(|x| guard.contains_file(x)).cloned();
+                match first.and_then(|x| guard.get_entry(&x).map(|y| (x, y.meta))) {
+                    Some((first, meta)) => {
+                        upper.meta = meta;
+                        guard.link_file(&first, path.clone());
+                    },
+                    None => {
+                        if let Some(id) = id {
+                            state.ids.insert(id, path.clone());
+                        }
+                        let mut file = MemfsFile::default();
+                        file.data = data;
+                        guard.insert_file(path.clone(), file);
+                    },
+                }
+            } else {
+                guard.insert_file(path.clone(), MemfsFile::default());
+                state.lazy.insert(path.clone(), (entry.path_buf(), meta.size));
+            }
+            if !entry.is_symlink() {
+                state.pristine.insert(path.clone(), entry.path_buf());
+            }
+            guard.insert_entry(path, upper);
+            if let Some(parent) = guard.get_entry_mut(dir) {
+                parent.add(name)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Copy the data of the given upper file up from the lower file it mirrors
+    ///
+    /// * Expects the path to already be faulted
+    fn _hydrate(&self, path: &Path) -> RvResult<()> {
+        let lower = match self._lazy(path) {
+            Some(x) => x,
+            None => return Ok(()),
+        };
+        let mut data = vec![];
+        self.0.lower.read(&lower)?.read_to_end(&mut data)?;
+
+        let mut state = self.0.state.write().unwrap();
+        if state.lazy.remove(path).is_some() {
+            if let Some(file) = self.0.upper.write_guard().get_file_mut(path) {
+                file.data = data;
+            }
+        }
+        Ok(())
+    }
+
+    /// Copy the ACL, SELinux context and immutable flag of the given upper entry up from the lower
+    /// entry it mirrors
+    ///
+    /// * Expects the path to already be faulted
+    fn _claim(&self, path: &Path) {
+        let lower = match self._pristine(path) {
+            Some(x) => x,
+            None => return,
+        };
+        let acl = self.0.lower.get_acl(&lower).ok().filter(|x| !x.is_minimal());
+        let immutable = self.0.lower.is_immutable(&lower);
+        #[cfg(feature = "selinux")]
+        let context = self.0.lower.get_selinux_context(&lower).ok().flatten();
+
+        let mut state = self.0.state.write().unwrap();
+        if state.pristine.remove(path).is_some() {
+            let mut guard = self.0.upper.write_guard();
+            if let Some(entry) = guard.get_entry_mut(path) {
+                entry.acl = acl;
+                entry.immutable = immutable;
+                #[cfg(feature = "selinux")]
+                {
+                    entry.context = context;
+                }
+            }
+        }
+    }
+
+    // Clone the upper entry for the given faulted path resolving its links and lazy size
+    fn _clone_entry(&self, path: &Path) -> RvResult<MemfsEntry> {
+        // Directories are merged so their link count accounts for the lower sub directories
+        self._merge(&self._target(path))?;
+        let entry = self.0.upper._clone_entry(&self.0.upper.read_guard(), path)?;
+        Ok(self._patch(entry))
+    }
+
+    // Execute disk_usage with the given options summing the sizes of the overlay's files
+    fn _disk_usage(&self, opts: DiskUsageOpts) -> RvResult<u64> {
+        let mut ids = HashSet::new();
+        let mut total = 0;
+        for entry in self.entries(&opts.path)?.follow(opts.follow) {
+            let entry = entry?;
+            if !entry.is_file() || (entry.is_symlink() && !entry.following()) || opts.excluded(&entry) {
+                continue;
+            }
+
+            // Hard linked files share the same identity and are only counted once
+            if entry.raw::<MemfsMeta>().is_none_or(|x| ids.insert(*x)) {
+                total += entry.size();
+            }
+        }
+        Ok(total)
+    }
+
+    /// Returns an iterator over the given directory bringing its entries up first
+    ///
+    /// * Link targets of the entries are brought up as well so the links resolve
+    fn _entry_iter(&self, path: &Path, follow: bool) -> RvResult<EntryIter> {
+        let path = self._fault(path)?;
+        self._merge(&path)?;
+        let names = match self.0.upper.read_guard().get_entry(&path) {
+            Some(entry) => entry.files.clone().unwrap_or_default(),
+            None => return Err(PathError::does_not_exist(&path).into()),
+        };
+        for name in &names {
+            if let Some(target) = self._link_target(&path.mash(name)) {
+                let _ = self._fault(target);
+            }
+        }
+
+        let entries = {
+            let guard = self.0.upper.read_guard();
+            let entries = names.iter().filter_map(|x| guard.get_entry(&path.mash(x)));
+            entries.map(|x| self.0.upper._resolve_entry(&guard, x)).collect::<Vec<_>>()
+        };
+        let entries = entries.into_iter().map(|x| Ok(self._patch(x).upcast())).collect::<Vec<_>>();
+        Ok(EntryIter { path, cached: false, following: follow, iter: Box::new(entries.into_iter()) })
+    }
+
+    // Drop the bookkeeping of the given path and everything beneath it that no longer exists
+    fn _forget(&self, path: &Path) {
+        let mut state = self.0.state.write().unwrap();
+        let guard = self.0.upper.read_guard();
+        let gone = |x: &Path| x.starts_with(path) && !guard.contains_entry(x);
+        state.unmerged.retain(|x, _| !gone(x));
+        state.lazy.retain(|x, _| !gone(x));
+        state.pristine.retain(|x, _| !gone(x));
+        state.ids.retain(|_, x| !gone(x));
+    }
+
+    // Returns the lower file still holding the data of the given upper file
+    fn _lazy(&self, path: &Path) -> Option<PathBuf> {
+        self.0.state.read().unwrap().lazy.get(path).map(|(x, _)| x.clone())
+    }
+
+    // Returns the target of the given upper path if it is a link
+    fn _link_target(&self, path: &Path) -> Option<PathBuf> {
+        let guard = self.0.upper.read_guard();
+        guard.get_entry(path).filter(|x| x.is_symlink()).map(|x| x.alt_buf())
+    }
+
+    // Set the size of the given file entry to that of the lower file still holding its data
+    fn _patch(&self, mut entry: MemfsEntry) -> MemfsEntry {
+        if entry.is_file() {
+            let state = self.0.state.read().unwrap();
+            if let Some((_, size)) = state.lazy.get(entry.chain.last().unwrap_or(&entry.path)) {
+                entry.size = *size;
+            }
+        }
+        entry
+    }
+
+    // Returns the lower entry still holding the ACL, context and flags of the given upper entry
+    fn _pristine(&self, path: &Path) -> Option<PathBuf> {
+        self.0.state.read().unwrap().pristine.get(path).cloned()
+    }
+
+    // Move the bookkeeping of the given path and everything beneath it to the given destination
+    fn _rename(&self, src: &Path, dst: &Path) {
+        let mut state = self.0.state.write().unwrap();
+        rename_keys(&mut state.unmerged, src, dst);
+        rename_keys(&mut state.lazy, src, dst);
+        rename_keys(&mut state.pristine, src, dst);
+        for path in state.ids.values_mut().filter(|x| x.starts_with(src)) {
+            *path = dst.mash(path.trim_prefix(src));
+        }
+    }
+
+    // Returns the final target of the given upper path following any links
+    fn _target(&self, path: &Path) -> PathBuf {
+        match self.0.upper.resolve_link(path, sys::DEFAULT_MAX_LINKS) {
+            Ok(x) => x.target,
+            Err(_) => path.to_path_buf(),
+        }
+    }
+}
+
+// Returns the identity the names of a hard linked lower file share
+fn lower_id(entry: &VfsEntry) -> Option<(u64, u64)> {
+    match entry.raw::<StdfsMeta>() {
+        Some(x) => Some((x.dev, x.ino)),
+        None => entry.raw::<MemfsMeta>().map(|x| (u64::MAX, x.id)),
+    }
+}
+
+// Move the keys of the given path and everything beneath it to the given destination dropping
+// any keys the destination had
+fn rename_keys<T>(map: &mut HashMap<PathBuf, T>, src: &Path, dst: &Path) {
+    let keys = map.keys().filter(|x| x.starts_with(src) || x.starts_with(dst)).cloned().collect::<Vec<_>>();
+    let moved = keys.into_iter().filter_map(|x| map.remove(&x).map(|y| (x, y))).collect::<Vec<_>>();
+    for (path, value) in moved.into_iter().filter(|(x, _)| x.starts_with(src)) {
+        map.insert(dst.mash(path.trim_prefix(src)), value);
+    }
+}
+
+impl fmt::Debug for Overlayfs {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Overlayfs").field("upper", &self.0.upper).field("lower", &self.0.lower).finish()
+    }
+}
+
+impl VirtualFileSystem for Overlayfs {
+    /// Return the path in an absolute clean form
+    ///
+    /// * Handles environment variable expansion
+    /// * Handles relative path resolution for `.` and `..`
+    /// * No IO resolution so it will work even with paths that don't exist
+    ///
+    /// ### Errors
+    /// * PathError::ParentNotFound(PathBuf) when parent is not found
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Overlayfs::new(Vfs::memfs());
+    /// let home = sys::home_dir().unwrap();
+    /// assert_eq!(vfs.abs("~").unwrap(), PathBuf::from(&home));
+    /// ```
+    fn abs<T: AsRef<Path>>(&self, path: T) -> RvResult<PathBuf> {
+        self.0.upper.abs(path)
+    }
+
+    /// Advise the kernel how the given file's data will be accessed
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Files whose data is still in the lower layer pass the advice on to it
+    ///
+    /// ### Errors
+    /// * PathError::IsNotFile(PathBuf) when the given path isn't a file
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Overlayfs::new(Vfs::memfs());
+    /// assert_vfs_write_all!(vfs, "/file", "this is a test");
+    /// assert!(vfs.advise("/file", Advice::DontNeed).is_ok());
+    /// ```
+    fn advise<T: AsRef<Path>>(&self, path: T, advice: Advice) -> RvResult<()> {
+        let path = self._fault_file(path)?;
+        match self._lazy(&path) {
+            Some(lower) => self.0.lower.advise(lower, advice),
+            None => self.0.upper.advise(path, advice),
+        }
+    }
+
+    /// Returns all dirs for the given path recursively
+    ///
+    /// * Results are sorted by filename, are distict and don't include the given path
+    /// * Handles path expansion and absolute path resolution
+    /// * Paths are returned in absolute form
+    ///
+    /// ### Errors
+    /// * PathError::IsNotDir(PathBuf) when the given path isn't a directory
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Overlayfs::new(Vfs::memfs());
+    /// assert_vfs_mkdir_p!(vfs, "/dir1/dir2");
+    /// assert_eq!(vfs.all_dirs("/").unwrap(), vec![PathBuf::from("/dir1"), PathBuf::from("/dir1/dir2")]);
+    /// ```
+    fn all_dirs<T: AsRef<Path>>(&self, path: T) -> RvResult<Vec<PathBuf>> {
+        if !self.is_dir(&path) {
+            return Err(PathError::is_not_dir(&path).into());
+        }
+        self.entries(path)?.min_depth(1).sort_by_name().collect_dirs()
+    }
+
+    /// Returns all files for the given path recursively
+    ///
+    /// * Results are sorted by filename, are distict and don't include the given path
+    /// * Handles path expansion and absolute path resolution
+    /// * Paths are returned in absolute form
+    ///
+    /// ### Errors
+    /// * PathError::IsNotDir(PathBuf) when the given path isn't a directory
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Overlayfs::new(Vfs::memfs());
+    /// assert_vfs_mkdir_p!(vfs, "/dir");
+    /// assert_vfs_mkfile!(vfs, "/dir/file");
+    /// assert_eq!(vfs.all_files("/").unwrap(), vec![PathBuf::from("/dir/file")]);
+    /// ```
+    fn all_files<T: AsRef<Path>>(&self, path: T) -> RvResult<Vec<PathBuf>> {
+        if !self.is_dir(&path) {
+            return Err(PathError::is_not_dir(&path).into());
+        }
+        self.entries(path)?.min_depth(1).sort_by_name().collect_files()
+    }
+
+    /// Returns all paths for the given path recursively
+    ///
+    /// * Results are sorted by filename, are distict and don't include the given path
+    /// * Handles path expansion and absolute path resolution
+    /// * Paths are returned in absolute form
+    ///
+    /// ### Errors
+    /// * PathError::IsNotDir(PathBuf) when the given path isn't a directory
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Overlayfs::new(Vfs::memfs());
+    /// assert_vfs_mkdir_p!(vfs, "/dir");
+    /// assert_vfs_mkfile!(vfs, "/dir/file");
+    /// assert_eq!(vfs.all_paths("/").unwrap(), vec![PathBuf::from("/dir"), PathBuf::from("/dir/file")]);
+    /// ```
+    fn all_paths<T: AsRef<Path>>(&self, path: T) -> RvResult<Vec<PathBuf>> {
+        if !self.is_dir(&path) {
+            return Err(PathError::is_not_dir(&path).into());
+        }
+        self.entries(path)?.min_depth(1).sort_by_name().collect_paths()
+    }
+
+    /// Reserve space for the given file so that writes up to the given length can't fail for lack
+    /// of space
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Creates the file if it doesn't exist and copies up the data of a lower file first
+    /// * Never shrinks the file
+    ///
+    /// ### Errors
+    /// * PathError::IsNotFile(PathBuf) when the given path exists but is not a file
+    /// * PathError::DoesNotExist(PathBuf) when the given path's parent doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Overlayfs::new(Vfs::memfs());
+    /// assert!(vfs.allocate("/file", 10).is_ok());
+    /// assert_eq!(vfs.size("/file").unwrap(), 10);
+    /// ```
+    fn allocate<T: AsRef<Path>>(&self, path: T, len: u64) -> RvResult<()> {
+        let path = self._fault_file(path)?;
+        self._hydrate(&path)?;
+        self.0.upper.allocate(path, len)
+    }
+
+    /// Opens a file in append mode
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Creates the file if it doesn't exist and copies up the data of a lower file first
+    ///
+    /// ### Errors
+    /// * PathError::IsNotFile(PathBuf) when the given path exists but is not a file
+    /// * PathError::DoesNotExist(PathBuf) when the given path's parent doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Overlayfs::new(Vfs::memfs());
+    /// assert_vfs_write_all!(vfs, "/file", "foo");
+    /// let mut f = vfs.append("/file").unwrap();
+    /// f.write_all(b"bar").unwrap();
+    /// f.flush().unwrap();
+    /// assert_vfs_read_all!(vfs, "/file", "foobar");
+    /// ```
+    fn append<T: AsRef<Path>>(&self, path: T) -> RvResult<VfsWriter> {
+        let path = self._fault_file(path)?;
+        self._hydrate(&path)?;
+        self.0.upper.append(path)
+    }
+
+    /// Append the given data to to the target file
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Create the file first if it doesn't exist
+    ///
+    /// ### Errors
+    /// * PathError::IsNotFile(PathBuf) when the given path exists but is not a file
+    /// * PathError::DoesNotExist(PathBuf) when the given path's parent doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Overlayfs::new(Vfs::memfs());
+    /// assert_vfs_write_all!(vfs, "/file", "foo");
+    /// assert!(vfs.append_all("/file", "bar").is_ok());
+    /// assert_vfs_read_all!(vfs, "/file", "foobar");
+    /// ```
+    fn append_all<T: AsRef<Path>, U: AsRef<[u8]>>(&self, path: T, data: U) -> RvResult<()> {
+        let mut f = self.append(path)?;
+        f.write_all(data.as_ref())?;
+        f.flush()?;
+        Ok(())
+    }
+
+    /// Append the given line to to the target file including a newline
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Create the file first if it doesn't exist
+    ///
+    /// ### Errors
+    /// * PathError::IsNotFile(PathBuf) when the given path exists but is not a file
+    /// * PathError::DoesNotExist(PathBuf) when the given path's parent doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Overlayfs::new(Vfs::memfs());
+    /// assert!(vfs.append_line("/file", "foo").is_ok());
+    /// assert_vfs_read_all!(vfs, "/file", "foo\n");
+    /// ```
+    fn append_line<T: AsRef<Path>, U: AsRef<str>>(&self, path: T, line: U) -> RvResult<()> {
+        let line = line.as_ref().to_string();
+        if !line.is_empty() {
+            self.append_all(path, line + "\n")?;
+        }
+        Ok(())
+    }
+
+    /// Append the given lines to to the target file including newlines
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Create the file first if it doesn't exist
+    ///
+    /// ### Errors
+    /// * PathError::IsNotFile(PathBuf) when the given path exists but is not a file
+    /// * PathError::DoesNotExist(PathBuf) when the given path's parent doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Overlayfs::new(Vfs::memfs());
+    /// assert!(vfs.append_lines("/file", &["foo", "bar"]).is_ok());
+    /// assert_vfs_read_all!(vfs, "/file", "foo\nbar\n");
+    /// ```
+    fn append_lines<T: AsRef<Path>, U: AsRef<str>>(&self, path: T, lines: &[U]) -> RvResult<()> {
+        let lines = lines.iter().map(|x| x.as_ref()).collect::<Vec<&str>>().join("\n");
+        if !lines.is_empty() {
+            self.append_all(path, lines + "\n")?;
+        }
+        Ok(())
+    }
+
+    /// Change all file/dir permissions recursivly to `mode`
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Lower entries are brought up and changed in the upper layer only
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Overlayfs::new(Vfs::memfs());
+    /// assert_vfs_mkfile!(vfs, "/file");
+    /// assert!(vfs.chmod("/file", 0o555).is_ok());
+    /// assert_eq!(vfs.mode("/file").unwrap(), 0o100555);
+    /// ```
+    fn chmod<T: AsRef<Path>>(&self, path: T, mode: u32) -> RvResult<()> {
+        self.chmod_b(path)?.all(mode).exec()
+    }
+
+    /// Returns a new [`Chmod`] builder for advanced chmod options
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Lower entries are brought up and changed in the upper layer only
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let lower = Vfs::memfs();
+    /// assert_vfs_mkdir_p!(lower, "/dir");
+    /// assert_vfs_mkfile!(lower, "/dir/file");
+    /// let vfs = Overlayfs::new(lower);
+    /// assert!(vfs.chmod_b("/dir").unwrap().recurse().files(0o600).exec().is_ok());
+    /// assert_eq!(vfs.mode("/dir/file").unwrap(), 0o100600);
+    /// ```
+    fn chmod_b<T: AsRef<Path>>(&self, path: T) -> RvResult<Chmod> {
+        let mut chmod = self.0.upper.chmod_b(self._fault(path)?)?;
+        let exec = chmod.exec;
+        let vfs = self.clone();
+        chmod.exec = Box::new(move |opts: ChmodOpts| -> RvResult<()> {
+            let path = vfs._fault(&opts.path)?;
+            if opts.recursive {
+                vfs._fault_tree(&path, opts.follow)?;
+            }
+            exec(ChmodOpts { path, ..opts })
+        });
+        Ok(chmod)
+    }
+
+    /// Change the ownership of the path recursivly
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Lower entries are brought up and changed in the upper layer only
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Overlayfs::new(Vfs::memfs());
+    /// assert_vfs_mkfile!(vfs, "/file");
+    /// assert!(vfs.chown("/file", 5, 7).is_ok());
+    /// assert_eq!(vfs.owner("/file").unwrap(), (5, 7));
+    /// ```
+    fn chown<T: AsRef<Path>>(&self, path: T, uid: u32, gid: u32) -> RvResult<()> {
+        self.chown_b(path)?.owner(uid, gid).exec()
+    }
+
+    /// Creates new [`Chown`] for use with the builder pattern
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Lower entries are brought up and changed in the upper layer only
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let lower = Vfs::memfs();
+    /// assert_vfs_mkdir_p!(lower, "/dir");
+    /// assert_vfs_mkfile!(lower, "/dir/file");
+    /// let vfs = Overlayfs::new(lower);
+    /// assert!(vfs.chown_b("/dir").unwrap().owner(5, 7).exec().is_ok());
+    /// assert_eq!(vfs.owner("/dir/file").unwrap(), (5, 7));
+    /// ```
+    fn chown_b<T: AsRef<Path>>(&self, path: T) -> RvResult<Chown> {
+        let mut chown = self.0.upper.chown_b(self._fault(path)?)?;
+        let exec = chown.exec;
+        let vfs = self.clone();
+        chown.exec = Box::new(move |opts: ChownOpts| -> RvResult<()> {
+            let path = vfs._fault(&opts.path)?;
+            if opts.recursive {
+                vfs._fault_tree(&path, opts.follow)?;
+            }
+            exec(ChownOpts { path, ..opts })
+        });
+        Ok(chown)
+    }
+
+    /// Returns the config of the upper layer
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Overlayfs::new(Vfs::memfs());
+    /// assert_eq!(vfs.config(), VfsConfig::default());
+    /// ```
+    fn config(&self) -> VfsConfig {
+        self.0.upper.config()
+    }
+
+    /// Returns the highest priority active configuration directory.
+    ///
+    /// * Searches first the $XDG_CONFIG_HOME directory, then the $XDG_CONFIG_DIRS directories.
+    /// * Returns the first directory that contains the given configuration file.
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Overlayfs::new(Vfs::memfs());
+    /// let dir = PathBuf::from("/etc/xdg");
+    /// assert_vfs_mkdir_p!(vfs, &dir);
+    /// assert_vfs_write_all!(vfs, dir.mash("rivia.toml"), "this is a test");
+    /// assert_eq!(vfs.config_dir("rivia.toml"), Some(dir));
+    /// ```
+    fn config_dir<T: AsRef<str>>(&self, config: T) -> Option<PathBuf> {
+        if let Ok(config_dir) = crate::sys::user::config_dir() {
+            if let Ok(mut config_dirs) = crate::sys::user::sys_config_dirs() {
+                config_dirs.insert(0, config_dir);
+                for config_dir in config_dirs {
+                    let path = config_dir.mash(config.as_ref());
+                    if self.exists(path) {
+                        return Some(config_dir);
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /// Copies src to dst recursively
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Lower sources are read through the overlay and copied into the upper layer
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the source doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let lower = Vfs::memfs();
+    /// assert_vfs_write_all!(lower, "/file1", "foobar");
+    /// let vfs = Overlayfs::new(lower);
+    /// assert!(vfs.copy("/file1", "/file2").is_ok());
+    /// assert_vfs_read_all!(vfs, "/file2", "foobar");
+    /// assert_vfs_no_exists!(vfs.lower(), "/file2");
+    /// ```
+    fn copy<T: AsRef<Path>, U: AsRef<Path>>(&self, src: T, dst: U) -> RvResult<()> {
+        self.copy_b(src, dst)?.exec()
+    }
+
+    /// Creates a new [`Copier`] for use with the builder pattern
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Lower sources are read through the overlay and copied into the upper layer
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let lower = Vfs::memfs();
+    /// assert_vfs_mkdir_p!(lower, "/dir1");
+    /// assert_vfs_write_all!(lower, "/dir1/file", "foobar");
+    /// let vfs = Overlayfs::new(lower);
+    /// assert!(vfs.copy_b("/dir1", "/dir2").unwrap().exec().is_ok());
+    /// assert_vfs_read_all!(vfs, "/dir2/file", "foobar");
+    /// ```
+    fn copy_b<T: AsRef<Path>, U: AsRef<Path>>(&self, src: T, dst: U) -> RvResult<Copier> {
+        let mut copier = self.0.upper.copy_b(src, dst)?;
+        let exec = copier.exec;
+        let vfs = self.clone();
+        copier.exec = Box::new(move |cp: CopyOpts| -> RvResult<()> {
+            // Sources need their data copied up while a destination directory only needs its
+            // entries as anything deeper is merged in beneath the copy later
+            let src = vfs._fault(&cp.src)?;
+            let dst = vfs._fault(&cp.dst)?;
+            for path in vfs._fault_tree(&src, cp.follow || cp.deref)? {
+                vfs._hydrate(&path)?;
+            }
+            vfs._merge(&vfs._target(&dst))?;
+            exec(CopyOpts { src, dst, ..cp })
+        });
+        Ok(copier)
+    }
+
+    /// Returns the current working directory
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Overlayfs::new(Vfs::memfs());
+    /// assert_eq!(vfs.cwd().unwrap(), vfs.root());
+    /// ```
+    fn cwd(&self) -> RvResult<PathBuf> {
+        self.0.upper.cwd()
+    }
+
+    /// Returns all directories for the given path, sorted by name
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Paths are returned as abs paths
+    /// * Doesn't include the path itself only its children nor is this recursive
+    ///
+    /// ### Errors
+    /// * PathError::IsNotDir(PathBuf) when the given path isn't a directory
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let lower = Vfs::memfs();
+    /// assert_vfs_mkdir_p!(lower, "/dir1");
+    /// let vfs = Overlayfs::new(lower);
+    /// assert_vfs_mkdir_p!(vfs, "/dir2");
+    /// assert_eq!(vfs.dirs("/").unwrap(), vec![PathBuf::from("/dir1"), PathBuf::from("/dir2")]);
+    /// ```
+    fn dirs<T: AsRef<Path>>(&self, path: T) -> RvResult<Vec<PathBuf>> {
+        if !self.is_dir(&path) {
+            return Err(PathError::is_not_dir(&path).into());
+        }
+        self.entries(path)?.min_depth(1).max_depth(1).sort_by_name().collect_dirs()
+    }
+
+    /// Returns a new [`DiskUsage`] builder for summing up the size of the given path
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Files whose data is still in the lower layer count with their lower size
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let lower = Vfs::memfs();
+    /// assert_vfs_mkdir_p!(lower, "/dir");
+    /// assert_vfs_write_all!(lower, "/dir/file1", "foo");
+    /// let vfs = Overlayfs::new(lower);
+    /// assert_vfs_write_all!(vfs, "/dir/file2", "foobar");
+    /// assert_eq!(vfs.disk_usage("/dir").unwrap().exec().unwrap(), 9);
+    /// ```
+    fn disk_usage<T: AsRef<Path>>(&self, path: T) -> RvResult<DiskUsage> {
+        let path = self.abs(path)?;
+
+        // Construct the closure callback
+        let vfs = self.clone();
+        let exec_func = move |opts: DiskUsageOpts| -> RvResult<u64> { vfs._disk_usage(opts) };
+
+        Ok(DiskUsage { opts: DiskUsageOpts { path, follow: false, excludes: vec![] }, exec: Box::new(exec_func) })
+    }
+
+    /// Returns an iterator over the given path
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Directories are merged with their lower counterparts as they are traversed
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let lower = Vfs::memfs();
+    /// assert_vfs_mkfile!(lower, "/file1");
+    /// let vfs = Overlayfs::new(lower);
+    /// assert_vfs_mkfile!(vfs, "/file2");
+    /// let mut iter = vfs.entries("/").unwrap().sort_by_name().into_iter();
+    /// assert_eq!(iter.next().unwrap().unwrap().path(), Path::new("/"));
+    /// assert_eq!(iter.next().unwrap().unwrap().path(), Path::new("/file1"));
+    /// assert_eq!(iter.next().unwrap().unwrap().path(), Path::new("/file2"));
+    /// assert!(iter.next().is_none());
+    /// ```
+    fn entries<T: AsRef<Path>>(&self, path: T) -> RvResult<Entries> {
+        let path = self._fault(path)?;
+        let config = self.0.upper.config();
+        Ok(Entries {
+            root: self._clone_entry(&path)?.upcast(),
+            dirs: false,
+            files: false,
+            symlinks: false,
+            follow: false,
+            skip_vanished: true,
+            min_depth: 0,
+            max_depth: usize::MAX,
+            max_links: sys::DEFAULT_MAX_LINKS,
+            max_items: usize::MAX,
+            timeout: None,
+            ctx: None,
+            modified_after: None,
+            modified_before: None,
+            created_after: None,
+            larger_than: None,
+            smaller_than: None,
+            #[cfg(feature = "regex")]
+            contents_match: None,
+            glob: None,
+            includes: vec![],
+            excludes: vec![],
+            max_content_size: config.max_content_size,
+            max_descriptors: config.max_descriptors,
+            parallel: 1,
+            dirs_first: false,
+            files_first: false,
+            contents_first: false,
+            sort_by_name: false,
+            pre_op: None,
+            sort: None,
+            iter_from: {
+                let vfs = self.clone();
+                Arc::new(move |path: &Path, follow: bool| vfs._entry_iter(path, follow))
+            },
+            #[cfg(feature = "regex")]
+            read_from: {
+                let vfs = self.clone();
+                Box::new(move |path: &Path| vfs.read(path))
+            },
+        })
+    }
+
+    /// Return a virtual filesystem entry for the given path
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Files whose data is still in the lower layer report their lower size
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let lower = Vfs::memfs();
+    /// assert_vfs_write_all!(lower, "/file", "foobar");
+    /// let vfs = Overlayfs::new(lower);
+    /// assert_eq!(vfs.entry("/file").unwrap().size(), 6);
+    /// ```
+    fn entry<T: AsRef<Path>>(&self, path: T) -> RvResult<VfsEntry> {
+        let entry = self._clone_entry(&self._fault(path)?)?;
+        if entry.link_hops() > sys::DEFAULT_MAX_LINKS {
+            return Err(PathError::too_many_links(entry.path()).into());
+        }
+        Ok(entry.upcast())
+    }
+
+    /// Ensure the given directory exists with the given mode and ownership
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Returns true if anything had to be changed
+    ///
+    /// ### Errors
+    /// * PathError::IsNotDir(PathBuf) when the given path exists but is not a directory
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Overlayfs::new(Vfs::memfs());
+    /// assert_eq!(vfs.ensure_dir("/dir", 0o700, 5, 7).unwrap(), true);
+    /// assert_eq!(vfs.ensure_dir("/dir", 0o700, 5, 7).unwrap(), false);
+    /// ```
+    fn ensure_dir<T: AsRef<Path>>(&self, path: T, mode: u32, uid: u32, gid: u32) -> RvResult<bool> {
+        let path = self.abs(path)?;
+        let mut changed = false;
+        if !self.exists(&path) {
+            self.mkdir_p(&path)?;
+            changed = true;
+        } else if !self.is_dir(&path) {
+            return Err(PathError::is_not_dir(&path).into());
+        }
+        // Memfs links carry their own mode and ownership so operate on the final target
+        let path = self.entry(&path)?.target_chain().last().cloned().unwrap_or(path);
+        if self.mode(&path)? & 0o7777 != mode & 0o7777 {
+            self.chmod_b(&path)?.no_recurse().mode_str(&format!("{:o}", mode & 0o7777)).exec()?;
+            changed = true;
+        }
+        if self.owner(&path)? != (uid, gid) {
+            self.chown_b(&path)?.recurse(false).owner(uid, gid).exec()?;
+            changed = true;
+        }
+        Ok(changed)
+    }
+
+    /// Ensure the given file exists with the given data and mode
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Returns true if anything had to be changed
+    ///
+    /// ### Errors
+    /// * PathError::IsNotFile(PathBuf) when the given path exists but is not a file
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Overlayfs::new(Vfs::memfs());
+    /// assert_eq!(vfs.ensure_file("/file", "foobar", 0o600).unwrap(), true);
+    /// assert_eq!(vfs.ensure_file("/file", "foobar", 0o600).unwrap(), false);
+    /// ```
+    fn ensure_file<T: AsRef<Path>, U: AsRef<[u8]>>(&self, path: T, data: U, mode: u32) -> RvResult<bool> {
+        // Links are resolved up front so that the checks and writes all apply to the final target
+        let path = self.resolve_link(path, sys::DEFAULT_MAX_LINKS)?.target;
+        let data = data.as_ref();
+        let mut changed = false;
+        let exists = self.exists(&path);
+        if exists && !self.is_file(&path) {
+            return Err(PathError::is_not_file(&path).into());
+        }
+
+        // Only write the data when it differs from the current content
+        let mut current = vec![];
+        if exists {
+            self.read(&path)?.read_to_end(&mut current)?;
+        }
+        if !exists || current != data {
+            self.write_all(&path, data)?;
+            changed = true;
+        }
+        if self.mode(&path)? & 0o7777 != mode & 0o7777 {
+            self.chmod_b(&path)?.no_recurse().mode_str(&format!("{:o}", mode & 0o7777)).exec()?;
+            changed = true;
+        }
+        Ok(changed)
+    }
+
+    /// Ensure the given link exists pointing to the given target
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Returns true if anything had to be changed
+    ///
+    /// ### Errors
+    /// * PathError::IsNotSymlink(PathBuf) when the given link exists but is not a link
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Overlayfs::new(Vfs::memfs());
+    /// assert_vfs_mkfile!(vfs, "/file");
+    /// assert_eq!(vfs.ensure_symlink("/link", "/file").unwrap(), true);
+    /// assert_eq!(vfs.ensure_symlink("/link", "/file").unwrap(), false);
+    /// ```
+    fn ensure_symlink<T: AsRef<Path>, U: AsRef<Path>>(&self, link: T, target: U) -> RvResult<bool> {
+        let link = self.abs(link)?;
+        match self.symlink_ensured(&link, &target) {
+            Ok(x) => Ok(x.created()),
+            Err(err) => {
+                // Only links are replaced never anything else
+                if !self.is_symlink(&link) {
+                    if self.exists(&link) {
+                        return Err(PathError::is_not_symlink(&link).into());
+                    }
+                    return Err(err);
+                }
+                self.remove(&link)?;
+                self.symlink(&link, target)?;
+                Ok(true)
+            },
+        }
+    }
+
+    /// Returns true if the `Path` exists in the overlay
+    ///
+    /// * Handles path expansion and absolute path resolution
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let lower = Vfs::memfs();
+    /// assert_vfs_mkfile!(lower, "/file");
+    /// let vfs = Overlayfs::new(lower);
+    /// assert_eq!(vfs.exists("/file"), true);
+    /// assert!(vfs.remove("/file").is_ok());
+    /// assert_eq!(vfs.exists("/file"), false);
+    /// ```
+    fn exists<T: AsRef<Path>>(&self, path: T) -> bool {
+        self._fault(path).is_ok_and(|x| self.0.upper.exists(x))
+    }
+
+    /// Returns whether each of the given paths exists in the same order
+    ///
+    /// * Handles path expansion and absolute path resolution
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Overlayfs::new(Vfs::memfs());
+    /// assert_vfs_mkfile!(vfs, "/file");
+    /// assert_eq!(vfs.exists_all(&["/file", "/foo"]), vec![true, false]);
+    /// ```
+    fn exists_all<T: AsRef<Path>>(&self, paths: &[T]) -> Vec<bool> {
+        paths.iter().map(|x| self.exists(x)).collect()
+    }
+
+    /// Returns all files for the given path, sorted by name
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Paths are returned as abs paths
+    /// * Doesn't include the path itself only its children nor is this recursive
+    ///
+    /// ### Errors
+    /// * PathError::IsNotDir(PathBuf) when the given path isn't a directory
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let lower = Vfs::memfs();
+    /// assert_vfs_mkfile!(lower, "/file1");
+    /// let vfs = Overlayfs::new(lower);
+    /// assert_vfs_mkfile!(vfs, "/file2");
+    /// assert_eq!(vfs.files("/").unwrap(), vec![PathBuf::from("/file1"), PathBuf::from("/file2")]);
+    /// ```
+    fn files<T: AsRef<Path>>(&self, path: T) -> RvResult<Vec<PathBuf>> {
+        if !self.is_dir(&path) {
+            return Err(PathError::is_not_dir(&path).into());
+        }
+        self.entries(path)?.min_depth(1).max_depth(1).sort_by_name().collect_files()
+    }
+
+    /// Returns the first of the given paths that exists in absolute form
+    ///
+    /// * Handles path expansion and absolute path resolution
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Overlayfs::new(Vfs::memfs());
+    /// assert_vfs_mkfile!(vfs, "/file");
+    /// assert_eq!(vfs.first_existing(&["/foo", "/file"]), Some(PathBuf::from("/file")));
+    /// ```
+    fn first_existing<T: AsRef<Path>>(&self, paths: &[T]) -> Option<PathBuf> {
+        paths.iter().filter_map(|x| self.abs(x).ok()).find(|x| self.exists(x))
+    }
+
+    /// Returns the ACL of the given path following links
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Entries not yet changed through the overlay report the ACL of their lower entry
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Overlayfs::new(Vfs::memfs());
+    /// assert_vfs_mkfile!(vfs, "/file");
+    /// assert!(vfs.set_acl("/file", Acl::from_mode(0o640).user(5, 0o4)).is_ok());
+    /// assert_eq!(vfs.get_acl("/file").unwrap().perms(AclTag::User(5)), Some(0o4));
+    /// ```
+    fn get_acl<T: AsRef<Path>>(&self, path: T) -> RvResult<Acl> {
+        let path = self._fault(path)?;
+        let target = self._target(&path);
+        if let Some(lower) = self._pristine(&target) {
+            if let Ok(acl) = self.0.lower.get_acl(lower) {
+                return Ok(acl.with_mode(self.0.upper.mode(&target)?));
+            }
+        }
+        self.0.upper.get_acl(path)
+    }
+
+    /// Returns the SELinux security context of the given path
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Entries not yet changed through the overlay report the context of their lower entry
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Overlayfs::new(Vfs::memfs());
+    /// assert_vfs_mkfile!(vfs, "/file");
+    /// assert_eq!(vfs.get_selinux_context("/file").unwrap(), None);
+    /// ```
+    #[cfg(feature = "selinux")]
+    fn get_selinux_context<T: AsRef<Path>>(&self, path: T) -> RvResult<Option<String>> {
+        let path = self._fault(path)?;
+        match self._pristine(&path) {
+            Some(lower) => self.0.lower.get_selinux_context(lower),
+            None => self.0.upper.get_selinux_context(path),
+        }
+    }
+
+    /// Returns the group ID of the owner of this file
+    ///
+    /// * Handles path expansion and absolute path resolution
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Overlayfs::new(Vfs::memfs());
+    /// assert_vfs_mkfile!(vfs, "/file");
+    /// assert!(vfs.chown("/file", 5, 7).is_ok());
+    /// assert_eq!(vfs.gid("/file").unwrap(), 7);
+    /// ```
+    fn gid<T: AsRef<Path>>(&self, path: T) -> RvResult<u32> {
+        self.0.upper.gid(self._fault(path)?)
+    }
+
+    /// Returns all paths matching the given glob pattern, sorted by name
+    ///
+    /// * Handles path expansion and absolute path resolution
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Overlayfs::new(Vfs::memfs());
+    /// assert_vfs_mkfile!(vfs, "/file1.txt");
+    /// assert_vfs_mkfile!(vfs, "/file2.rs");
+    /// assert_eq!(vfs.glob("/*.txt").unwrap(), vec![PathBuf::from("/file1.txt")]);
+    /// ```
+    fn glob<T: AsRef<str>>(&self, pattern: T) -> RvResult<Vec<PathBuf>> {
+        sys::glob_paths(self, pattern.as_ref())
+    }
+
+    /// Creates a new hard link `dst` sharing the data of the given file `src`
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Copies up the data of a lower file first so that both names share it
+    ///
+    /// ### Errors
+    /// * PathError::IsNotFile(PathBuf) when the given src is a directory
+    /// * PathError::ExistsAlready(PathBuf) when the given dst already exists
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Overlayfs::new(Vfs::memfs());
+    /// assert_vfs_write_all!(vfs, "/file", "foobar");
+    /// assert!(vfs.hardlink("/file", "/hard").is_ok());
+    /// assert_vfs_read_all!(vfs, "/hard", "foobar");
+    /// ```
+    fn hardlink<T: AsRef<Path>, U: AsRef<Path>>(&self, src: T, dst: U) -> RvResult<PathBuf> {
+        let src = self._fault(src)?;
+        let dst = self._fault(dst)?;
+        self._hydrate(&src)?;
+        self.0.upper.hardlink(src, dst)
+    }
+
+    /// Register a hook to run after each operation of the upper layer
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Overlayfs::new(Vfs::memfs());
+    /// vfs.hook_post(|op, path| println!("{:?} {}", op, path.display()));
+    /// assert_vfs_mkfile!(vfs, "/file");
+    /// ```
+    fn hook_post<F: Fn(VfsOp, &Path) + Send + Sync + 'static>(&self, hook: F) {
+        self.0.upper.hook_post(hook)
+    }
+
+    /// Register a hook to run before each operation of the upper layer
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Overlayfs::new(Vfs::memfs());
+    /// vfs.hook_pre(|_, path| Err(PathError::does_not_exist(path).into()));
+    /// assert!(vfs.mkfile("/file").is_err());
+    /// ```
+    fn hook_pre<F: Fn(VfsOp, &Path) -> RvResult<()> + Send + Sync + 'static>(&self, hook: F) {
+        self.0.upper.hook_pre(hook)
+    }
+
+    /// Register a hook to run after each operation of the upper layer slower than the given duration
+    ///
+    /// ### Examples
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Overlayfs::new(Vfs::memfs());
+    /// vfs.hook_slow(Duration::from_secs(1), |op, path, took| println!("{:?} {} {:?}", op, path.display(), took));
+    /// assert_vfs_mkfile!(vfs, "/file");
+    /// ```
+    fn hook_slow<F: Fn(VfsOp, &Path, Duration) + Send + Sync + 'static>(&self, after: Duration, hook: F) {
+        self.0.upper.hook_slow(after, hook)
+    }
+
+    /// Creates a new [`Installer`] for use with the builder pattern
+    ///
+    /// * Handles path expansion and absolute path resolution
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Overlayfs::new(Vfs::memfs());
+    /// assert_vfs_write_all!(vfs, "/file1", "foobar");
+    /// assert!(vfs.install("/file1", "/file2").unwrap().exec().is_ok());
+    /// assert_vfs_read_all!(vfs, "/file2", "foobar");
+    /// ```
+    fn install<T: AsRef<Path>, U: AsRef<Path>>(&self, src: T, dst: U) -> RvResult<Installer> {
+        let vfs = self.clone();
+        Ok(Installer {
+            opts: sys::InstallOpts {
+                src: src.as_ref().to_owned(),
+                dst: dst.as_ref().to_owned(),
+                ..Default::default()
+            },
+            exec: Box::new(move |opts| sys::install_exec(&vfs, opts)),
+            notify: None,
+        })
+    }
+
+    /// Returns true if the given path exists and is executable
+    ///
+    /// * Handles path expansion and absolute path resolution
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Overlayfs::new(Vfs::memfs());
+    /// assert_vfs_mkfile!(vfs, "/file");
+    /// assert!(vfs.chmod("/file", 0o755).is_ok());
+    /// assert_eq!(vfs.is_exec("/file"), true);
+    /// ```
+    fn is_exec<T: AsRef<Path>>(&self, path: T) -> bool {
+        self._fault(path).is_ok_and(|x| self.0.upper.is_exec(x))
+    }
+
+    /// Returns true if the given path exists and is a directory
+    ///
+    /// * Handles path expansion and absolute path resolution
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let lower = Vfs::memfs();
+    /// assert_vfs_mkdir_p!(lower, "/dir");
+    /// let vfs = Overlayfs::new(lower);
+    /// assert_eq!(vfs.is_dir("/dir"), true);
+    /// ```
+    fn is_dir<T: AsRef<Path>>(&self, path: T) -> bool {
+        self._fault(path).is_ok_and(|x| self.0.upper.is_dir(x))
+    }
+
+    /// Returns true if the given path exists and is a file
+    ///
+    /// * Handles path expansion and absolute path resolution
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let lower = Vfs::memfs();
+    /// assert_vfs_mkfile!(lower, "/file");
+    /// let vfs = Overlayfs::new(lower);
+    /// assert_eq!(vfs.is_file("/file"), true);
+    /// ```
+    fn is_file<T: AsRef<Path>>(&self, path: T) -> bool {
+        self._fault(path).is_ok_and(|x| self.0.upper.is_file(x))
+    }
+
+    /// Returns true if the given path is a file known by more than one name
+    ///
+    /// * Handles path expansion and absolute path resolution
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Overlayfs::new(Vfs::memfs());
+    /// assert_vfs_mkfile!(vfs, "/file");
+    /// assert!(vfs.hardlink("/file", "/hard").is_ok());
+    /// assert_eq!(vfs.is_hardlink("/file"), true);
+    /// ```
+    fn is_hardlink<T: AsRef<Path>>(&self, path: T) -> bool {
+        self._fault(path).is_ok_and(|x| self.0.upper.is_hardlink(x))
+    }
+
+    /// Returns true if the given path has its immutable flag set
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Entries not yet changed through the overlay report the flag of their lower entry
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Overlayfs::new(Vfs::memfs());
+    /// assert_vfs_mkfile!(vfs, "/file");
+    /// assert!(vfs.set_immutable("/file", true).is_ok());
+    /// assert_eq!(vfs.is_immutable("/file"), true);
+    /// ```
+    fn is_immutable<T: AsRef<Path>>(&self, path: T) -> bool {
+        let path = unwrap_or_false!(self._fault(path));
+        match self._pristine(&self._target(&path)) {
+            Some(lower) => self.0.lower.is_immutable(lower),
+            None => self.0.upper.is_immutable(path),
+        }
+    }
+
+    /// Returns true if the given path exists and is readonly
+    ///
+    /// * Handles path expansion and absolute path resolution
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Overlayfs::new(Vfs::memfs());
+    /// assert_vfs_mkfile!(vfs, "/file");
+    /// assert!(vfs.chmod("/file", 0o444).is_ok());
+    /// assert_eq!(vfs.is_readonly("/file"), true);
+    /// ```
+    fn is_readonly<T: AsRef<Path>>(&self, path: T) -> bool {
+        self._fault(path).is_ok_and(|x| self.0.upper.is_readonly(x))
+    }
+
+    /// Returns true if the given path exists and is a symlink
+    ///
+    /// * Handles path expansion and absolute path resolution
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Overlayfs::new(Vfs::memfs());
+    /// assert_vfs_symlink!(vfs, "/link", "/file");
+    /// assert_eq!(vfs.is_symlink("/link"), true);
+    /// ```
+    fn is_symlink<T: AsRef<Path>>(&self, path: T) -> bool {
+        self._fault(path).is_ok_and(|x| self.0.upper.is_symlink(x))
+    }
+
+    /// Returns true if the given path exists and is a symlink pointing to a directory
+    ///
+    /// * Handles path expansion and absolute path resolution
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Overlayfs::new(Vfs::memfs());
+    /// assert_vfs_mkdir_p!(vfs, "/dir");
+    /// assert_vfs_symlink!(vfs, "/link", "/dir");
+    /// assert_eq!(vfs.is_symlink_dir("/link"), true);
+    /// ```
+    fn is_symlink_dir<T: AsRef<Path>>(&self, path: T) -> bool {
+        self._fault(path).is_ok_and(|x| self.0.upper.is_symlink_dir(x))
+    }
+
+    /// Returns true if the given path exists and is a symlink pointing to a file
+    ///
+    /// * Handles path expansion and absolute path resolution
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Overlayfs::new(Vfs::memfs());
+    /// assert_vfs_mkfile!(vfs, "/file");
+    /// assert_vfs_symlink!(vfs, "/link", "/file");
+    /// assert_eq!(vfs.is_symlink_file("/link"), true);
+    /// ```
+    fn is_symlink_file<T: AsRef<Path>>(&self, path: T) -> bool {
+        self._fault(path).is_ok_and(|x| self.0.upper.is_symlink_file(x))
+    }
+
+    /// Returns the number of lines in the given file
+    ///
+    /// * Handles path expansion and absolute path resolution
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Overlayfs::new(Vfs::memfs());
+    /// assert_vfs_write_all!(vfs, "/file", "foo\nbar\n");
+    /// assert_eq!(vfs.line_count("/file").unwrap(), 2);
+    /// ```
+    fn line_count<T: AsRef<Path>>(&self, path: T) -> RvResult<u64> {
+        Ok(sys::wc_reader(self.read(path)?)?.lines)
+    }
+
+    /// Returns the metadata of the given path following links for the size and timestamps
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Files whose data is still in the lower layer report their lower size
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let lower = Vfs::memfs();
+    /// assert_vfs_write_all!(lower, "/file", "foobar");
+    /// let vfs = Overlayfs::new(lower);
+    /// assert_eq!(vfs.metadata("/file").unwrap().size, 6);
+    /// ```
+    fn metadata<T: AsRef<Path>>(&self, path: T) -> RvResult<VfsMetadata> {
+        Ok(self._clone_entry(&self._fault(path)?)?.metadata())
+    }
+
+    /// Creates the given directory and any parent directories needed with the given mode
+    ///
+    /// * Handles path expansion and absolute path resolution
+    ///
+    /// ### Errors
+    /// * PathError::IsNotDir(PathBuf) when the path or a parent exists but is not a directory
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Overlayfs::new(Vfs::memfs());
+    /// assert_eq!(vfs.mkdir_m("/dir", 0o700).unwrap(), PathBuf::from("/dir"));
+    /// assert_eq!(vfs.mode("/dir").unwrap(), 0o40700);
+    /// ```
+    fn mkdir_m<T: AsRef<Path>>(&self, path: T, mode: u32) -> RvResult<PathBuf> {
+        self.0.upper.mkdir_m(self._fault(path)?, mode)
+    }
+
+    /// Creates the given directory and any parent directories needed
+    ///
+    /// * Handles path expansion and absolute path resolution
+    ///
+    /// ### Errors
+    /// * PathError::IsNotDir(PathBuf) when the path or a parent exists but is not a directory
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Overlayfs::new(Vfs::memfs());
+    /// assert_eq!(vfs.mkdir_p("/dir1/dir2").unwrap(), PathBuf::from("/dir1/dir2"));
+    /// assert_vfs_is_dir!(vfs, "/dir1/dir2");
+    /// ```
+    fn mkdir_p<T: AsRef<Path>>(&self, path: T) -> RvResult<PathBuf> {
+        self.0.upper.mkdir_p(self._fault(path)?)
+    }
+
+    /// Creates the given directory and any parent directories needed reporting if it was created
+    ///
+    /// * Handles path expansion and absolute path resolution
+    ///
+    /// ### Errors
+    /// * PathError::IsNotDir(PathBuf) when the path or a parent exists but is not a directory
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Overlayfs::new(Vfs::memfs());
+    /// assert_eq!(vfs.mkdir_p_ensured("/dir").unwrap(), Ensured::Created(PathBuf::from("/dir")));
+    /// assert_eq!(vfs.mkdir_p_ensured("/dir").unwrap(), Ensured::Existed(PathBuf::from("/dir")));
+    /// ```
+    fn mkdir_p_ensured<T: AsRef<Path>>(&self, path: T) -> RvResult<Ensured> {
+        self.0.upper.mkdir_p_ensured(self._fault(path)?)
+    }
+
+    /// Create an empty file similar to the linux touch command
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Does nothing if the file already exists
+    ///
+    /// ### Errors
+    /// * PathError::IsNotFile(PathBuf) when the given path exists but is not a file
+    /// * PathError::DoesNotExist(PathBuf) when the given path's parent doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Overlayfs::new(Vfs::memfs());
+    /// assert_eq!(vfs.mkfile("/file").unwrap(), PathBuf::from("/file"));
+    /// assert_vfs_is_file!(vfs, "/file");
+    /// ```
+    fn mkfile<T: AsRef<Path>>(&self, path: T) -> RvResult<PathBuf> {
+        self.0.upper.mkfile(self._fault(path)?)
+    }
+
+    /// Create an empty file reporting if it was created
+    ///
+    /// * Handles path expansion and absolute path resolution
+    ///
+    /// ### Errors
+    /// * PathError::IsNotFile(PathBuf) when the given path exists but is not a file
+    /// * PathError::DoesNotExist(PathBuf) when the given path's parent doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Overlayfs::new(Vfs::memfs());
+    /// assert_eq!(vfs.mkfile_ensured("/file").unwrap(), Ensured::Created(PathBuf::from("/file")));
+    /// assert_eq!(vfs.mkfile_ensured("/file").unwrap(), Ensured::Existed(PathBuf::from("/file")));
+    /// ```
+    fn mkfile_ensured<T: AsRef<Path>>(&self, path: T) -> RvResult<Ensured> {
+        self.0.upper.mkfile_ensured(self._fault(path)?)
+    }
+
+    /// Wraps `mkfile` allowing for setting the file's mode
+    ///
+    /// * Handles path expansion and absolute path resolution
+    ///
+    /// ### Errors
+    /// * PathError::IsNotFile(PathBuf) when the given path exists but is not a file
+    /// * PathError::DoesNotExist(PathBuf) when the given path's parent doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Overlayfs::new(Vfs::memfs());
+    /// assert_eq!(vfs.mkfile_m("/file", 0o555).unwrap(), PathBuf::from("/file"));
+    /// assert_eq!(vfs.mode("/file").unwrap(), 0o100555);
+    /// ```
+    fn mkfile_m<T: AsRef<Path>>(&self, path: T, mode: u32) -> RvResult<PathBuf> {
+        self.0.upper.mkfile_m(self._fault(path)?, mode)
+    }
+
+    /// Returns the permissions for a file, directory or link
+    ///
+    /// * Handles path expansion and absolute path resolution
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Overlayfs::new(Vfs::memfs());
+    /// assert_eq!(vfs.mkfile_m("/file", 0o555).unwrap(), PathBuf::from("/file"));
+    /// assert_eq!(vfs.mode("/file").unwrap(), 0o100555);
+    /// ```
+    fn mode<T: AsRef<Path>>(&self, path: T) -> RvResult<u32> {
+        self.0.upper.mode(self._fault(path)?)
+    }
+
+    /// Creates a new [`Mover`] for use with the builder pattern
+    ///
+    /// * Handles path expansion and absolute path resolution
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Overlayfs::new(Vfs::memfs());
+    /// assert_vfs_mkfile!(vfs, "/file1");
+    /// assert!(vfs.move_b("/file1", "/file2").unwrap().exec().is_ok());
+    /// assert_vfs_exists!(vfs, "/file2");
+    /// ```
+    fn move_b<T: AsRef<Path>, U: AsRef<Path>>(&self, src: T, dst: U) -> RvResult<Mover> {
+        // Construct the move closure callback
+        let vfs = self.clone();
+        let exec_func = move |opts: MoveOpts| -> RvResult<()> { vfs.move_p(&opts.src, &opts.dst) };
+
+        // Return the new Mover builder
+        Ok(Mover {
+            opts: MoveOpts {
+                src: src.as_ref().to_owned(),
+                dst: dst.as_ref().to_owned(),
+                preserve: true,
+            },
+            exec: Box::new(exec_func),
+        })
+    }
+
+    /// Move a file or directory
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Moves into the destination when it is an existing directory
+    /// * Lower entries are moved in the upper layer only with their data staying in the lower
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the source doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let lower = Vfs::memfs();
+    /// assert_vfs_write_all!(lower, "/file1", "foobar");
+    /// let vfs = Overlayfs::new(lower);
+    /// assert!(vfs.move_p("/file1", "/file2").is_ok());
+    /// assert_vfs_no_exists!(vfs, "/file1");
+    /// assert_vfs_read_all!(vfs, "/file2", "foobar");
+    /// assert_vfs_exists!(vfs.lower(), "/file1");
+    /// ```
+    fn move_p<T: AsRef<Path>, U: AsRef<Path>>(&self, src: T, dst: U) -> RvResult<()> {
+        let src = self._fault(src)?;
+        let dst = self._fault(dst)?;
+
+        // Moving into a directory needs to see what the directory already holds
+        let into = self.0.upper.is_dir(&dst);
+        if into {
+            self._merge(&dst)?;
+        }
+        self.0.upper.move_p(&src, &dst)?;
+        self._rename(&src, &if into { dst.mash(src.base()?) } else { dst });
+        Ok(())
+    }
+
+    /// Returns the number of names the given path is known by
+    ///
+    /// * Handles path expansion and absolute path resolution
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Overlayfs::new(Vfs::memfs());
+    /// assert_vfs_mkfile!(vfs, "/file");
+    /// assert!(vfs.hardlink("/file", "/hard").is_ok());
+    /// assert_eq!(vfs.nlink("/file").unwrap(), 2);
+    /// ```
+    fn nlink<T: AsRef<Path>>(&self, path: T) -> RvResult<u64> {
+        let path = self._fault(path)?;
+        self._merge(&path)?;
+        self.0.upper.nlink(path)
+    }
+
+    /// Opens a handle to the given directory for operating on the entries it contains by name
+    ///
+    /// * Handles path expansion and absolute path resolution
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    /// * PathError::IsNotDir(PathBuf) when the given path is not a directory
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Overlayfs::new(Vfs::memfs());
+    /// let handle = vfs.open_dir(vfs.root()).unwrap();
+    /// assert_eq!(handle.mkfile("file").unwrap(), vfs.root().mash("file"));
+    /// assert_vfs_is_file!(vfs, vfs.root().mash("file"));
+    /// ```
+    fn open_dir<T: AsRef<Path>>(&self, path: T) -> RvResult<DirHandle> {
+        DirHandle::overlayfs(self.clone(), self.abs(path)?)
+    }
+
+    /// Opens a file for reading and writing in place
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Creates the file if it doesn't exist and copies up the data of a lower file first
+    ///
+    /// ### Errors
+    /// * PathError::IsNotFile(PathBuf) when the given path exists but is not a file
+    /// * PathError::DoesNotExist(PathBuf) when the given path's parent doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Overlayfs::new(Vfs::memfs());
+    /// assert_vfs_write_all!(vfs, "/file", "foobar");
+    /// let mut f = vfs.open_rw("/file").unwrap();
+    /// f.write_all(b"bar").unwrap();
+    /// f.flush().unwrap();
+    /// assert_vfs_read_all!(vfs, "/file", "barbar");
+    /// ```
+    fn open_rw<T: AsRef<Path>>(&self, path: T) -> RvResult<Box<dyn ReadWriteSeek>> {
+        let path = self._fault_file(path)?;
+        self._hydrate(&path)?;
+        self.0.upper.open_rw(path)
+    }
+
+    /// Returns the (user ID, group ID) of the owner of this file
+    ///
+    /// * Handles path expansion and absolute path resolution
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Overlayfs::new(Vfs::memfs());
+    /// assert_vfs_mkfile!(vfs, "/file");
+    /// assert!(vfs.chown("/file", 5, 7).is_ok());
+    /// assert_eq!(vfs.owner("/file").unwrap(), (5, 7));
+    /// ```
+    fn owner<T: AsRef<Path>>(&self, path: T) -> RvResult<(u32, u32)> {
+        self.0.upper.owner(self._fault(path)?)
+    }
+
+    /// Returns all paths for the given path, sorted by name
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Paths are returned as abs paths
+    /// * Doesn't include the path itself only its children nor is this recursive
+    ///
+    /// ### Errors
+    /// * PathError::IsNotDir(PathBuf) when the given path isn't a directory
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let lower = Vfs::memfs();
+    /// assert_vfs_mkdir_p!(lower, "/dir");
+    /// let vfs = Overlayfs::new(lower);
+    /// assert_vfs_mkfile!(vfs, "/file");
+    /// assert_eq!(vfs.paths("/").unwrap(), vec![PathBuf::from("/dir"), PathBuf::from("/file")]);
+    /// ```
+    fn paths<T: AsRef<Path>>(&self, path: T) -> RvResult<Vec<PathBuf>> {
+        if !self.is_dir(&path) {
+            return Err(PathError::is_not_dir(&path).into());
+        }
+        self.entries(path)?.min_depth(1).max_depth(1).sort_by_name().collect_paths()
+    }
+
+    /// Open a file in readonly mode
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Files whose data is still in the lower layer are read from it
+    /// * Links are followed to the file they lead to whether it was copied up or not
+    ///
+    /// ### Errors
+    /// * PathError::IsNotFile(PathBuf) when the given path isn't a file
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let lower = Vfs::memfs();
+    /// assert_vfs_write_all!(lower, "/file", "foobar");
+    /// let vfs = Overlayfs::new(lower);
+    /// let mut buf = String::new();
+    /// vfs.read("/file").unwrap().read_to_string(&mut buf).unwrap();
+    /// assert_eq!(buf, "foobar");
+    /// ```
+    fn read<T: AsRef<Path>>(&self, path: T) -> RvResult<Box<dyn ReadSeek>> {
+        let path = self._fault_file(path)?;
+        match self._lazy(&path) {
+            Some(lower) => self.0.lower.read(lower),
+            None => self.0.upper.read(path),
+        }
+    }
+
+    /// Read all data from the given file and return it as a String
+    ///
+    /// * Handles path expansion and absolute path resolution
+    ///
+    /// ### Errors
+    /// * PathError::IsNotFile(PathBuf) when the given path isn't a file
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let lower = Vfs::memfs();
+    /// assert_vfs_write_all!(lower, "/file", "foobar");
+    /// let vfs = Overlayfs::new(lower);
+    /// assert_eq!(vfs.read_all("/file").unwrap(), "foobar");
+    /// ```
+    fn read_all<T: AsRef<Path>>(&self, path: T) -> RvResult<String> {
+        let mut buf = String::new();
+        self.read(path)?.read_to_string(&mut buf)?;
+        Ok(buf)
+    }
+
+    /// Creates a new [`Reader`] for use with the builder pattern
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Files whose data is still in the lower layer are read from it with the given options
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let lower = Vfs::memfs();
+    /// assert_vfs_write_all!(lower, "/file", "foobar");
+    /// let vfs = Overlayfs::new(lower);
+    /// let mut buf = String::new();
+    /// vfs.read_b("/file").unwrap().exec().unwrap().read_to_string(&mut buf).unwrap();
+    /// assert_eq!(buf, "foobar");
+    /// ```
+    fn read_b<T: AsRef<Path>>(&self, path: T) -> RvResult<Reader> {
+        // Construct the read closure callback passing the options on to the lower layer
+        let vfs = self.clone();
+        let exec_func = move |opts: ReadOpts| -> RvResult<Box<dyn ReadSeek>> {
+            let path = vfs._fault_file(&opts.path)?;
+            match vfs._lazy(&path) {
+                Some(lower) => (vfs.0.lower.read_b(&lower)?.exec)(ReadOpts { path: lower, ..opts }),
+                None => vfs.0.upper.read(path),
+            }
+        };
+
+        // Return the new Reader builder
+        Ok(Reader {
+            opts: ReadOpts { path: path.as_ref().to_owned(), direct: false, advice: None },
+            exec: Box::new(exec_func),
+        })
+    }
+
+    /// Read the given file and returns it as lines in a vector
+    ///
+    /// * Handles path expansion and absolute path resolution
+    ///
+    /// ### Errors
+    /// * PathError::IsNotFile(PathBuf) when the given path isn't a file
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let lower = Vfs::memfs();
+    /// assert_vfs_write_all!(lower, "/file", "foo\nbar");
+    /// let vfs = Overlayfs::new(lower);
+    /// assert_eq!(vfs.read_lines("/file").unwrap(), vec!["foo".to_string(), "bar".to_string()]);
+    /// ```
+    fn read_lines<T: AsRef<Path>>(&self, path: T) -> RvResult<Vec<String>> {
+        let mut lines = vec![];
+        for line in BufReader::new(self.read(path)?).lines() {
+            lines.push(line?);
+        }
+        Ok(lines)
+    }
+
+    /// Returns the relative path of the target the link points to
+    ///
+    /// * Handles path expansion and absolute path resolution
+    ///
+    /// ### Errors
+    /// * PathError::IsNotSymlink(PathBuf) when the given path isn't a link
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Overlayfs::new(Vfs::memfs());
+    /// assert_vfs_symlink!(vfs, "/link", "/file");
+    /// assert_eq!(vfs.readlink("/link").unwrap(), PathBuf::from("file"));
+    /// ```
+    fn readlink<T: AsRef<Path>>(&self, link: T) -> RvResult<PathBuf> {
+        self.0.upper.readlink(self._fault(link)?)
+    }
+
+    /// Returns the absolute path of the target the link points to
+    ///
+    /// * Handles path expansion and absolute path resolution
+    ///
+    /// ### Errors
+    /// * PathError::IsNotSymlink(PathBuf) when the given path isn't a link
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Overlayfs::new(Vfs::memfs());
+    /// assert_vfs_symlink!(vfs, "/link", "/file");
+    /// assert_eq!(vfs.readlink_abs("/link").unwrap(), PathBuf::from("/file"));
+    /// ```
+    fn readlink_abs<T: AsRef<Path>>(&self, link: T) -> RvResult<PathBuf> {
+        self.0.upper.readlink_abs(self._fault(link)?)
+    }
+
+    /// Recompute the relative target of the given link from its current location
+    ///
+    /// * Handles path expansion and absolute path resolution
+    ///
+    /// ### Errors
+    /// * PathError::IsNotSymlink(PathBuf) when the given path isn't a link
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Overlayfs::new(Vfs::memfs());
+    /// assert_vfs_symlink!(vfs, "/link", "/file");
+    /// assert_eq!(vfs.relink("/link").unwrap(), PathBuf::from("/link"));
+    /// ```
+    fn relink<T: AsRef<Path>>(&self, link: T) -> RvResult<PathBuf> {
+        self.0.upper.relink(self._fault(link)?)
+    }
+
+    /// Removes the given empty directory or file
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Lower entries are hidden from the overlay leaving the lower layer untouched
+    ///
+    /// ### Errors
+    /// * PathError::DirContainsFiles(PathBuf) when the given directory isn't empty
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let lower = Vfs::memfs();
+    /// assert_vfs_mkfile!(lower, "/file");
+    /// let vfs = Overlayfs::new(lower);
+    /// assert!(vfs.remove("/file").is_ok());
+    /// assert_vfs_no_exists!(vfs, "/file");
+    /// assert_vfs_exists!(vfs.lower(), "/file");
+    /// ```
+    fn remove<T: AsRef<Path>>(&self, path: T) -> RvResult<()> {
+        let path = self._fault(path)?;
+        self._merge(&path)?;
+        self.0.upper.remove(&path)?;
+        self._forget(&path);
+        Ok(())
+    }
+
+    /// Removes the given directory after removing all of its contents
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Lower entries are hidden from the overlay leaving the lower layer untouched
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let lower = Vfs::memfs();
+    /// assert_vfs_mkdir_p!(lower, "/dir");
+    /// assert_vfs_mkfile!(lower, "/dir/file");
+    /// let vfs = Overlayfs::new(lower);
+    /// assert!(vfs.remove_all("/dir").is_ok());
+    /// assert_vfs_no_exists!(vfs, "/dir");
+    /// assert_vfs_exists!(vfs.lower(), "/dir/file");
+    /// ```
+    fn remove_all<T: AsRef<Path>>(&self, path: T) -> RvResult<()> {
+        self.remove_all_b(path)?.exec()
+    }
+
+    /// Creates a new [`Remover`] for use with the builder pattern
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Lower entries are hidden from the overlay leaving the lower layer untouched
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let lower = Vfs::memfs();
+    /// assert_vfs_mkdir_p!(lower, "/dir");
+    /// assert_vfs_mkfile!(lower, "/dir/file");
+    /// let vfs = Overlayfs::new(lower);
+    /// assert!(vfs.remove_all_b("/dir").unwrap().exec().is_ok());
+    /// assert_vfs_no_exists!(vfs, "/dir");
+    /// ```
+    fn remove_all_b<T: AsRef<Path>>(&self, path: T) -> RvResult<Remover> {
+        let mut remover = self.0.upper.remove_all_b(self._fault(path)?)?;
+        let exec = remover.exec;
+        let vfs = self.clone();
+        remover.exec = Box::new(move |opts: RemoveOpts| -> RvResult<()> {
+            // Confirmation is given the entries to remove so the whole tree needs to be known
+            let path = opts.path.clone();
+            if opts.confirm.is_some() {
+                vfs._fault_tree(&path, false)?;
+            }
+            let result = exec(opts);
+            vfs._forget(&path);
+            result
+        });
+        Ok(remover)
+    }
+
+    /// Resolve the chain of links starting at the given path
+    ///
+    /// * Handles path expansion and absolute path resolution
+    ///
+    /// ### Errors
+    /// * PathError::LinkLooping(PathBuf) when the links loop back on themselves
+    /// * PathError::TooManyLinks(PathBuf) when more than `max_hops` links are followed
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Overlayfs::new(Vfs::memfs());
+    /// assert_vfs_mkfile!(vfs, "/file");
+    /// assert_vfs_symlink!(vfs, "/link", "/file");
+    /// assert_eq!(vfs.resolve_link("/link", 5).unwrap().target, PathBuf::from("/file"));
+    /// ```
+    fn resolve_link<T: AsRef<Path>>(&self, path: T, max_hops: usize) -> RvResult<ResolvedLink> {
+        self.0.upper.resolve_link(self._fault(path)?, max_hops)
+    }
+
+    /// Returns the current root directory
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Overlayfs::new(Vfs::memfs());
+    /// assert_eq!(vfs.root(), PathBuf::from("/"));
+    /// ```
+    fn root(&self) -> PathBuf {
+        self.0.upper.root()
+    }
+
+    /// Returns true if the given paths are the same underlying file
+    ///
+    /// * Handles path expansion and absolute path resolution
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Overlayfs::new(Vfs::memfs());
+    /// assert_vfs_mkfile!(vfs, "/file");
+    /// assert_vfs_symlink!(vfs, "/link", "/file");
+    /// assert_eq!(vfs.same_file("/file", "/link"), true);
+    /// ```
+    fn same_file<T: AsRef<Path>, U: AsRef<Path>>(&self, a: T, b: U) -> bool {
+        match (self._fault(a), self._fault(b)) {
+            (Ok(a), Ok(b)) => self.0.upper.same_file(a, b),
+            _ => false,
+        }
+    }
+
+    /// Set the ACL of the given path following links
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Lower entries are changed in the upper layer only
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Overlayfs::new(Vfs::memfs());
+    /// assert_vfs_mkfile!(vfs, "/file");
+    /// assert!(vfs.set_acl("/file", Acl::from_mode(0o640).user(5, 0o4)).is_ok());
+    /// assert_eq!(vfs.mode("/file").unwrap(), 0o100640);
+    /// ```
+    fn set_acl<T: AsRef<Path>>(&self, path: T, acl: Acl) -> RvResult<()> {
+        let path = self._fault(path)?;
+        self._claim(&self._target(&path));
+        self.0.upper.set_acl(path, acl)
+    }
+
+    /// Set the current working directory
+    ///
+    /// * Handles path expansion and absolute path resolution
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Overlayfs::new(Vfs::memfs());
+    /// assert_vfs_mkdir_p!(vfs, "/dir");
+    /// assert_eq!(vfs.set_cwd("/dir").unwrap(), PathBuf::from("/dir"));
+    /// assert_eq!(vfs.cwd().unwrap(), PathBuf::from("/dir"));
+    /// ```
+    fn set_cwd<T: AsRef<Path>>(&self, path: T) -> RvResult<PathBuf> {
+        self.0.upper.set_cwd(self._fault(path)?)
+    }
+
+    /// Set the access and modification times of the given path
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Only the modification time is kept
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use std::time::UNIX_EPOCH;
+    ///
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Overlayfs::new(Vfs::memfs());
+    /// assert_vfs_mkfile!(vfs, "/file");
+    /// let time = UNIX_EPOCH;
+    /// assert!(vfs.set_file_time("/file", time, time).is_ok());
+    /// assert_eq!(vfs.metadata("/file").unwrap().modified, time);
+    /// ```
+    fn set_file_time<T: AsRef<Path>>(&self, path: T, atime: SystemTime, mtime: SystemTime) -> RvResult<()> {
+        self.0.upper.set_file_time(self._fault(path)?, atime, mtime)
+    }
+
+    /// Set the immutable flag of the given path following links
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Lower entries are changed in the upper layer only
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Overlayfs::new(Vfs::memfs());
+    /// assert_vfs_mkfile!(vfs, "/file");
+    /// assert!(vfs.set_immutable("/file", true).is_ok());
+    /// assert_eq!(vfs.is_immutable("/file"), true);
+    /// ```
+    fn set_immutable<T: AsRef<Path>>(&self, path: T, yes: bool) -> RvResult<()> {
+        let path = self._fault(path)?;
+        self._claim(&self._target(&path));
+        self.0.upper.set_immutable(path, yes)
+    }
+
+    /// Set the modification time of the given path
+    ///
+    /// * Handles path expansion and absolute path resolution
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use std::time::UNIX_EPOCH;
+    ///
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Overlayfs::new(Vfs::memfs());
+    /// assert_vfs_mkfile!(vfs, "/file");
+    /// assert!(vfs.set_mtime_only("/file", UNIX_EPOCH).is_ok());
+    /// assert_eq!(vfs.metadata("/file").unwrap().modified, UNIX_EPOCH);
+    /// ```
+    fn set_mtime_only<T: AsRef<Path>>(&self, path: T, mtime: SystemTime) -> RvResult<()> {
+        self.0.upper.set_mtime_only(self._fault(path)?, mtime)
+    }
+
+    /// Set the SELinux security context of the given path
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Lower entries are changed in the upper layer only
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Overlayfs::new(Vfs::memfs());
+    /// assert_vfs_mkfile!(vfs, "/file");
+    /// assert!(vfs.set_selinux_context("/file", "system_u:object_r:etc_t:s0").is_ok());
+    /// assert_eq!(vfs.get_selinux_context("/file").unwrap(), Some("system_u:object_r:etc_t:s0".into()));
+    /// ```
+    #[cfg(feature = "selinux")]
+    fn set_selinux_context<T: AsRef<Path>, U: AsRef<str>>(&self, path: T, context: U) -> RvResult<()> {
+        let path = self._fault(path)?;
+        self._claim(&path);
+        self.0.upper.set_selinux_context(path, context)
+    }
+
+    /// Returns the size of the file in bytes
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Files whose data is still in the lower layer report their lower size
+    ///
+    /// ### Errors
+    /// * PathError::IsNotFile(PathBuf) when the given path isn't a file
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let lower = Vfs::memfs();
+    /// assert_vfs_write_all!(lower, "/file", "foobar");
+    /// let vfs = Overlayfs::new(lower);
+    /// assert_eq!(vfs.size("/file").unwrap(), 6);
+    /// ```
+    fn size<T: AsRef<Path>>(&self, path: T) -> RvResult<u64> {
+        let path = self._fault(path)?;
+        let size = self.0.upper.size(&path)?;
+        let state = self.0.state.read().unwrap();
+        Ok(state.lazy.get(&self._target(&path)).map_or(size, |(_, x)| *x))
+    }
+
+    /// Returns the filesystem statistics of the upper layer
+    ///
+    /// * Handles path expansion and absolute path resolution
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Overlayfs::new(Vfs::memfs());
+    /// assert_vfs_write_all!(vfs, "/file", "foobar");
+    /// assert_eq!(vfs.stat_fs("/").unwrap().used, 6);
+    /// ```
+    fn stat_fs<T: AsRef<Path>>(&self, path: T) -> RvResult<VfsStatFs> {
+        self.0.upper.stat_fs(self._fault(path)?)
+    }
+
+    /// Creates a new symbolic link
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Computes the target path `src` relative to the `dst` link name's absolute path
+    /// * Returns the link path
+    ///
+    /// ### Errors
+    /// * PathError::ExistsAlready(PathBuf) when the given link already exists
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Overlayfs::new(Vfs::memfs());
+    /// assert_vfs_mkfile!(vfs, "/file");
+    /// assert_eq!(vfs.symlink("/link", "/file").unwrap(), PathBuf::from("/link"));
+    /// assert_vfs_is_symlink!(vfs, "/link");
+    /// ```
+    fn symlink<T: AsRef<Path>, U: AsRef<Path>>(&self, link: T, target: U) -> RvResult<PathBuf> {
+        let link = self._fault(link)?;
+        self._fault_target(&link, target.as_ref());
+        self.0.upper.symlink(link, target)
+    }
+
+    /// Creates a new symbolic link reporting if it was created
+    ///
+    /// * Handles path expansion and absolute path resolution
+    ///
+    /// ### Errors
+    /// * PathError::ExistsAlready(PathBuf) when the link exists but doesn't point to the target
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Overlayfs::new(Vfs::memfs());
+    /// assert_vfs_mkfile!(vfs, "/file");
+    /// assert_eq!(vfs.symlink_ensured("/link", "/file").unwrap(), Ensured::Created(PathBuf::from("/link")));
+    /// assert_eq!(vfs.symlink_ensured("/link", "/file").unwrap(), Ensured::Existed(PathBuf::from("/link")));
+    /// ```
+    fn symlink_ensured<T: AsRef<Path>, U: AsRef<Path>>(&self, link: T, target: U) -> RvResult<Ensured> {
+        let link = self._fault(link)?;
+        self._fault_target(&link, target.as_ref());
+        self.0.upper.symlink_ensured(link, target)
+    }
+
+    /// Opens a file in write-only mode
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Creates the file if it doesn't exist or truncates it if it does
+    /// * Links are followed so the data lands in the file they lead to
+    ///
+    /// ### Errors
+    /// * PathError::IsNotFile(PathBuf) when the given path exists but is not a file
+    /// * PathError::DoesNotExist(PathBuf) when the given path's parent doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Overlayfs::new(Vfs::memfs());
+    /// let mut f = vfs.write("/file").unwrap();
+    /// f.write_all(b"foobar").unwrap();
+    /// f.flush().unwrap();
+    /// assert_vfs_read_all!(vfs, "/file", "foobar");
+    /// ```
+    fn write<T: AsRef<Path>>(&self, path: T) -> RvResult<VfsWriter> {
+        let path = self._fault_file(path)?;
+        let writer = self.0.upper.write(&path)?;
+        self.0.state.write().unwrap().lazy.remove(&path);
+        Ok(writer)
+    }
+
+    /// Write the given data to to the target file
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Create the file first if it doesn't exist or truncating it first if it does
+    ///
+    /// ### Errors
+    /// * PathError::IsNotFile(PathBuf) when the given path exists but is not a file
+    /// * PathError::DoesNotExist(PathBuf) when the given path's parent doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let lower = Vfs::memfs();
+    /// assert_vfs_write_all!(lower, "/file", "foo");
+    /// let vfs = Overlayfs::new(lower);
+    /// assert!(vfs.write_all("/file", "bar").is_ok());
+    /// assert_vfs_read_all!(vfs, "/file", "bar");
+    /// assert_vfs_read_all!(vfs.lower(), "/file", "foo");
+    /// ```
+    fn write_all<T: AsRef<Path>, U: AsRef<[u8]>>(&self, path: T, data: U) -> RvResult<()> {
+        let mut f = self.write(path)?;
+        f.write_all(data.as_ref())?;
+        Ok(())
+    }
+
+    /// Write the given data to the target file atomically
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Writes to a temporary sibling file which is then moved into place
+    ///
+    /// ### Errors
+    /// * PathError::IsNotFile(PathBuf) when the given path exists but is not a file
+    /// * PathError::DoesNotExist(PathBuf) when the given path's parent doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Overlayfs::new(Vfs::memfs());
+    /// assert!(vfs.write_all_atomic("/file", "foobar").is_ok());
+    /// assert_vfs_read_all!(vfs, "/file", "foobar");
+    /// ```
+    fn write_all_atomic<T: AsRef<Path>, U: AsRef<[u8]>>(&self, path: T, data: U) -> RvResult<()> {
+        self.0.upper._hooked(VfsOp::Write, path.as_ref(), || {
+            let mut path = self.abs(&path)?;
+            if self.is_symlink(&path) {
+                path = self.resolve_link(&path, sys::DEFAULT_MAX_LINKS)?.target;
+            }
+
+            // Validate the file
+            let exists = self.exists(&path);
+            if exists && !self.is_file(&path) {
+                return Err(PathError::is_not_file(&path).into());
+            }
+
+            // Write the temp file carrying over the mode and ownership of the existing file
+            let tmp = sys::tmp_sibling(&path)?;
+            let write = || -> RvResult<()> {
+                self.write_all(&tmp, &data)?;
+                if exists {
+                    self.chmod(&tmp, self.mode(&path)?)?;
+                    let (uid, gid) = self.owner(&path)?;
+                    self.chown(&tmp, uid, gid)?;
+                }
+                self.move_p(&tmp, &path)
+            };
+            if let Err(err) = write() {
+                let _ = self.remove(&tmp);
+                return Err(err);
+            }
+            Ok(())
+        })
+    }
+
+    /// Creates a new [`Writer`] for use with the builder pattern
+    ///
+    /// * Handles path expansion and absolute path resolution
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Overlayfs::new(Vfs::memfs());
+    /// assert!(vfs.write_b("/file", "foobar").unwrap().atomic(true).exec().is_ok());
+    /// assert_vfs_read_all!(vfs, "/file", "foobar");
+    /// ```
+    fn write_b<T: AsRef<Path>, U: AsRef<[u8]>>(&self, path: T, data: U) -> RvResult<Writer> {
+        let vfs = self.clone();
+        let exec_func = move |opts: WriteOpts| -> RvResult<()> {
+            match opts.atomic {
+                true => vfs.write_all_atomic(&opts.path, &opts.data),
+                false => vfs.write_all(&opts.path, &opts.data),
+            }
+        };
+
+        Ok(Writer {
+            opts: WriteOpts { path: path.as_ref().to_owned(), data: data.as_ref().to_vec(), atomic: false },
+            exec: Box::new(exec_func),
+        })
+    }
+
+    /// Write the given lines to to the target file including final newline
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Create the file first if it doesn't exist or truncating it first if it does
+    ///
+    /// ### Errors
+    /// * PathError::IsNotFile(PathBuf) when the given path exists but is not a file
+    /// * PathError::DoesNotExist(PathBuf) when the given path's parent doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Overlayfs::new(Vfs::memfs());
+    /// assert!(vfs.write_lines("/file", &["foo", "bar"]).is_ok());
+    /// assert_vfs_read_all!(vfs, "/file", "foo\nbar\n");
+    /// ```
+    fn write_lines<T: AsRef<Path>, U: AsRef<str>>(&self, path: T, lines: &[U]) -> RvResult<()> {
+        let lines = lines.iter().map(|x| x.as_ref()).collect::<Vec<&str>>().join("\n");
+        if !lines.is_empty() {
+            self.write_all(path, lines + "\n")?;
+        }
+        Ok(())
+    }
+
+    /// Returns the user ID of the owner of this file
+    ///
+    /// * Handles path expansion and absolute path resolution
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Overlayfs::new(Vfs::memfs());
+    /// assert_vfs_mkfile!(vfs, "/file");
+    /// assert!(vfs.chown("/file", 5, 7).is_ok());
+    /// assert_eq!(vfs.uid("/file").unwrap(), 5);
+    /// ```
+    fn uid<T: AsRef<Path>>(&self, path: T) -> RvResult<u32> {
+        self.0.upper.uid(self._fault(path)?)
+    }
+
+    /// Watch the given path for changes made through the overlay
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Changes to the lower layer are never seen
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Overlayfs::new(Vfs::memfs());
+    /// assert_vfs_mkdir_p!(vfs, "/dir");
+    /// let mut watcher = vfs.watch("/dir").unwrap();
+    /// assert_vfs_mkfile!(vfs, "/dir/file");
+    /// assert_eq!(watcher.try_recv(), Some(VfsEvent::Create(PathBuf::from("/dir/file"))));
+    /// ```
+    fn watch<T: AsRef<Path>>(&self, path: T) -> RvResult<VfsWatcher> {
+        self.0.upper.watch(self._fault(path)?)
+    }
+
+    /// Up cast the trait type to the enum wrapper
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Overlayfs::new(Vfs::memfs()).upcast();
+    /// assert!(matches!(vfs, Vfs::Overlayfs(_)));
+    /// ```
+    fn upcast(self) -> Vfs {
+        Vfs::Overlayfs(self)
+    }
+}
+
+impl Overlayfs {
+    // Bring up the target of a link about to be created so the upper layer knows its kind
+    fn _fault_target(&self, link: &Path, target: &Path) {
+        if let Ok(dir) = link.dir() {
+            let _ = self._fault(if target.is_absolute() { target.to_path_buf() } else { dir.mash(target) });
+        }
+    }
+}
+
+// Unit tests
+// -------------------------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    #[test]
+    fn test_overlayfs_copy() {
+        test_copy(assert_vfs_setup!(Vfs::memfs()));
+        test_copy(assert_vfs_setup!(Vfs::stdfs()));
+    }
+    fn test_copy((lower, tmpdir): (Vfs, PathBuf)) {
+        let dir1 = tmpdir.mash("dir1");
+        let dir2 = tmpdir.mash("dir2");
+        let file1 = dir1.mash("file1");
+        let link1 = dir1.mash("link1");
+        assert_vfs_mkdir_p!(lower, &dir1);
+        assert_vfs_write_all!(lower, &file1, "foobar");
+        assert_vfs_symlink!(lower, &link1, &file1);
+
+        // Lower trees are copied into the upper layer only
+        let vfs = Overlayfs::new(lower);
+        assert!(vfs.copy(&dir1, &dir2).is_ok());
+        assert_vfs_read_all!(vfs, dir2.mash("file1"), "foobar");
+        assert_eq!(vfs.readlink_abs(dir2.mash("link1")).unwrap(), file1);
+        assert_vfs_no_exists!(vfs.lower(), &dir2);
+
+        // Copying into an existing lower directory keeps its lower entries
+        assert!(vfs.copy(&file1, &dir2).is_ok());
+        assert!(vfs.copy(dir2.mash("file1"), &dir1).is_ok());
+        assert_eq!(vfs.paths(&dir1).unwrap(), vec![file1.clone(), link1]);
+
+        assert_vfs_remove_all!(vfs.lower(), &tmpdir);
+    }
+
+    #[test]
+    fn test_overlayfs_copy_up() {
+        test_copy_up(assert_vfs_setup!(Vfs::memfs()));
+        test_copy_up(assert_vfs_setup!(Vfs::stdfs()));
+    }
+    fn test_copy_up((lower, tmpdir): (Vfs, PathBuf)) {
+        let dir1 = tmpdir.mash("dir1");
+        let file1 = dir1.mash("file1");
+        let file2 = dir1.mash("file2");
+        let link1 = dir1.mash("link1");
+        let link2 = dir1.mash("link2");
+        let link3 = tmpdir.mash("link3");
+        assert_vfs_mkdir_p!(lower, &dir1);
+        assert_vfs_write_all!(lower, &file1, "foo");
+        assert_vfs_write_all!(lower, &file2, "foo");
+        assert!(lower.symlink(&link1, "file1").is_ok());
+        assert!(lower.symlink(&link2, &file2).is_ok());
+        assert!(lower.symlink(&link3, &link1).is_ok());
+
+        // Links still lead to their files once the files are copied up
+        let vfs = Overlayfs::new(lower);
+        assert_vfs_read_all!(vfs, &link1, "foo");
+        assert!(vfs.write_all(&file1, "bar").is_ok());
+        assert_vfs_read_all!(vfs, &link1, "bar");
+        assert_vfs_read_all!(vfs, &link3, "bar");
+        assert!(vfs.append_all(&link3, "baz").is_ok());
+        assert_vfs_read_all!(vfs, &file1, "barbaz");
+
+        // Writes through links copy up the files they lead to
+        assert!(vfs.write_all(&link2, "bar").is_ok());
+        assert_vfs_read_all!(vfs, &file2, "bar");
+        assert_eq!(vfs.size(&file2).unwrap(), 3);
+        assert!(vfs.append_all(&link2, "baz").is_ok());
+        assert_vfs_read_all!(vfs, &link2, "barbaz");
+        assert_eq!(vfs.readlink_abs(&link2).unwrap(), file2);
+
+        // The lower layer is left untouched
+        assert_vfs_read_all!(vfs.lower(), &file1, "foo");
+        assert_vfs_read_all!(vfs.lower(), &file2, "foo");
+
+        assert_vfs_remove_all!(vfs.lower(), &tmpdir);
+    }
+
+    #[test]
+    fn test_overlayfs_disk_usage() {
+        test_disk_usage(assert_vfs_setup!(Vfs::memfs()));
+        test_disk_usage(assert_vfs_setup!(Vfs::stdfs()));
+    }
+    fn test_disk_usage((lower, tmpdir): (Vfs, PathBuf)) {
+        let dir1 = tmpdir.mash("dir1");
+        let file1 = dir1.mash("file1");
+        let file2 = dir1.mash("file2");
+        let hard1 = dir1.mash("hard1");
+        assert_vfs_mkdir_p!(lower, &dir1);
+        assert_vfs_write_all!(lower, &file1, "foo");
+        assert_vfs_write_all!(lower, &file2, "foobar");
+        assert_vfs_hardlink!(lower, &file2, &hard1);
+
+        // Lower hard links still share their data and are only counted once
+        let vfs = Overlayfs::new(lower);
+        assert_eq!(vfs.disk_usage(&dir1).unwrap().exec().unwrap(), 9);
+        assert_eq!(vfs.nlink(&hard1).unwrap(), 2);
+        assert!(vfs.write_all(&hard1, "barfoo").is_ok());
+        assert_vfs_read_all!(vfs, &file2, "barfoo");
+        assert_vfs_read_all!(vfs.lower(), &file2, "foobar");
+
+        // Upper data replaces the lower data
+        assert!(vfs.write_all(&file1, "foobarbaz").is_ok());
+        assert_eq!(vfs.disk_usage(&dir1).unwrap().exec().unwrap(), 15);
+
+        assert_vfs_remove_all!(vfs.lower(), &tmpdir);
+    }
+
+    #[test]
+    fn test_overlayfs_links() {
+        test_links(assert_vfs_setup!(Vfs::memfs()));
+        test_links(assert_vfs_setup!(Vfs::stdfs()));
+    }
+    fn test_links((lower, tmpdir): (Vfs, PathBuf)) {
+        let dir1 = tmpdir.mash("dir1");
+        let file1 = dir1.mash("sub/file1");
+        let link1 = tmpdir.mash("link1");
+        let link2 = tmpdir.mash("link2");
+        let link3 = tmpdir.mash("link3");
+        assert_vfs_mkdir_p!(lower, dir1.mash("sub"));
+        assert_vfs_write_all!(lower, &file1, "foobar");
+        assert_vfs_symlink!(lower, &link1, &dir1);
+        assert_vfs_symlink!(lower, &link2, &link1);
+
+        // Paths through lower links are resolved through the overlay
+        let vfs = Overlayfs::new(lower);
+        assert_vfs_read_all!(vfs, link2.mash("sub/file1"), "foobar");
+        assert!(vfs.is_symlink_dir(&link2));
+        assert_eq!(vfs.readlink(&link1).unwrap(), PathBuf::from("dir1"));
+        assert_eq!(vfs.entry(link1.mash("sub")).unwrap().path(), dir1.mash("sub"));
+
+        // Writes through links land on the target
+        assert!(vfs.write_all(link1.mash("sub/file1"), "barfoo").is_ok());
+        assert_vfs_read_all!(vfs, &file1, "barfoo");
+        assert_vfs_read_all!(vfs.lower(), &file1, "foobar");
+
+        // New links to lower entries
+        assert_vfs_symlink!(vfs, &link3, dir1.mash("sub"));
+        assert_vfs_read_all!(vfs, link3.mash("file1"), "barfoo");
+        assert_vfs_no_exists!(vfs.lower(), &link3);
+
+        assert_vfs_remove_all!(vfs.lower(), &tmpdir);
+    }
+
+    #[test]
+    fn test_overlayfs_merge() {
+        test_merge(assert_vfs_setup!(Vfs::memfs()));
+        test_merge(assert_vfs_setup!(Vfs::stdfs()));
+    }
+    fn test_merge((lower, tmpdir): (Vfs, PathBuf)) {
+        let dir1 = tmpdir.mash("dir1");
+        let dir2 = dir1.mash("dir2");
+        let file1 = dir1.mash("file1");
+        let file2 = dir1.mash("file2");
+        let file3 = dir2.mash("file3");
+        assert_vfs_mkdir_p!(lower, &dir2);
+        assert_vfs_write_all!(lower, &file1, "foo");
+        assert_vfs_write_all!(lower, &file3, "foo");
+
+        // Directories in both layers list the entries of both
+        let vfs = Overlayfs::new(lower);
+        assert_vfs_mkdir_p!(vfs, &dir1);
+        assert_vfs_write_all!(vfs, &file2, "bar");
+        assert_eq!(vfs.paths(&dir1).unwrap(), vec![dir2.clone(), file1.clone(), file2.clone()]);
+        assert_eq!(vfs.all_files(&dir1).unwrap(), vec![file3.clone(), file1.clone(), file2.clone()]);
+        assert_vfs_read_all!(vfs, &file3, "foo");
+
+        // Upper entries take precedence over the lower entries of the same name
+        assert!(vfs.write_all(&file1, "bar").is_ok());
+        assert_vfs_read_all!(vfs, &file1, "bar");
+        assert_eq!(vfs.paths(&dir1).unwrap(), vec![dir2.clone(), file1.clone(), file2.clone()]);
+
+        // Moving into a directory in both layers
+        assert!(vfs.move_p(&file2, &dir2).is_ok());
+        assert_eq!(vfs.paths(&dir2).unwrap(), vec![dir2.mash("file2"), file3.clone()]);
+        assert_vfs_read_all!(vfs, dir2.mash("file2"), "bar");
+        assert_vfs_no_exists!(vfs.lower(), &file2);
+        assert_eq!(vfs.lower().paths(&dir1).unwrap(), vec![dir2, file1]);
+
+        assert_vfs_remove_all!(vfs.lower(), &tmpdir);
+    }
+
+    #[test]
+    fn test_overlayfs_move_p() {
+        test_move_p(assert_vfs_setup!(Vfs::memfs()));
+        test_move_p(assert_vfs_setup!(Vfs::stdfs()));
+    }
+    fn test_move_p((lower, tmpdir): (Vfs, PathBuf)) {
+        let dir1 = tmpdir.mash("dir1");
+        let dir2 = tmpdir.mash("dir2");
+        let dir3 = tmpdir.mash("dir3");
+        let file1 = dir1.mash("sub/file1");
+        assert_vfs_mkdir_p!(lower, dir1.mash("sub"));
+        assert_vfs_mkdir_p!(lower, &dir3);
+        assert_vfs_write_all!(lower, &file1, "foobar");
+
+        // Moved lower trees keep reading from the lower layer
+        let vfs = Overlayfs::new(lower);
+        assert!(vfs.move_p(&dir1, &dir2).is_ok());
+        assert_vfs_no_exists!(vfs, &dir1);
+        assert_eq!(vfs.size(dir2.mash("sub/file1")).unwrap(), 6);
+        assert_vfs_read_all!(vfs, dir2.mash("sub/file1"), "foobar");
+        assert_vfs_exists!(vfs.lower(), &file1);
+        assert_vfs_no_exists!(vfs.lower(), &dir2);
+
+        // Moving into an existing lower directory
+        assert!(vfs.move_p(&dir2, &dir3).is_ok());
+        assert_vfs_read_all!(vfs, dir3.mash("dir2/sub/file1"), "foobar");
+        assert!(vfs.append_all(dir3.mash("dir2/sub/file1"), "baz").is_ok());
+        assert_vfs_read_all!(vfs, dir3.mash("dir2/sub/file1"), "foobarbaz");
+        assert_vfs_read_all!(vfs.lower(), &file1, "foobar");
+
+        assert_vfs_remove_all!(vfs.lower(), &tmpdir);
+    }
+
+    #[test]
+    fn test_overlayfs_read_through() {
+        test_read_through(assert_vfs_setup!(Vfs::memfs()));
+        test_read_through(assert_vfs_setup!(Vfs::stdfs()));
+    }
+    fn test_read_through((lower, tmpdir): (Vfs, PathBuf)) {
+        let dir1 = tmpdir.mash("dir1");
+        let file1 = dir1.mash("file1");
+        let file2 = dir1.mash("file2");
+        let file3 = dir1.mash("file3");
+        assert_vfs_mkdir_p!(lower, &dir1);
+        assert_vfs_write_all!(lower, &file1, "foo");
+        assert_vfs_write_all!(lower, &file2, "foobar");
+        assert!(lower.chmod(&file2, 0o600).is_ok());
+
+        // Lower entries are seen through the overlay
+        let vfs = Overlayfs::new(lower);
+        assert_vfs_is_dir!(vfs, &dir1);
+        assert_vfs_read_all!(vfs, &file1, "foo");
+        assert_eq!(vfs.mode(&file2).unwrap(), 0o100600);
+        assert_eq!(vfs.metadata(&file2).unwrap().size, 6);
+        assert_eq!(vfs.entry(&file2).unwrap().size(), 6);
+        let sizes = vfs.entries(&dir1).unwrap().min_depth(1).sort_by_name().into_iter();
+        assert_eq!(sizes.map(|x| x.unwrap().size()).collect::<Vec<_>>(), vec![3, 6]);
+
+        // Changes only land in the upper layer
+        assert!(vfs.write_all(&file1, "bar").is_ok());
+        assert!(vfs.append_all(&file2, "baz").is_ok());
+        assert_vfs_mkfile!(vfs, &file3);
+        assert!(vfs.chmod(&file2, 0o644).is_ok());
+        assert_vfs_read_all!(vfs, &file1, "bar");
+        assert_vfs_read_all!(vfs, &file2, "foobarbaz");
+        assert_eq!(vfs.paths(&dir1).unwrap(), vec![file1.clone(), file2.clone(), file3.clone()]);
+        assert_vfs_read_all!(vfs.lower(), &file1, "foo");
+        assert_vfs_read_all!(vfs.lower(), &file2, "foobar");
+        assert_eq!(vfs.lower().mode(&file2).unwrap(), 0o100600);
+        assert_vfs_no_exists!(vfs.lower(), &file3);
+
+        // Removals hide the lower entries
+        assert_vfs_remove!(vfs, &file1);
+        assert_eq!(vfs.paths(&dir1).unwrap(), vec![file2.clone(), file3]);
+        assert!(vfs.remove_all(&dir1).is_ok());
+        assert_vfs_no_exists!(vfs, &file2);
+        assert_vfs_mkdir_p!(vfs, &dir1);
+        assert_eq!(vfs.paths(&dir1).unwrap(), Vec::<PathBuf>::new());
+        assert_vfs_exists!(vfs.lower(), &file1);
+        assert_vfs_exists!(vfs.lower(), &file2);
+
+        assert_vfs_remove_all!(vfs.lower(), &tmpdir);
+    }
+
+    #[test]
+    fn test_overlayfs_remove() {
+        test_remove(assert_vfs_setup!(Vfs::memfs()));
+        test_remove(assert_vfs_setup!(Vfs::stdfs()));
+    }
+    fn test_remove((lower, tmpdir): (Vfs, PathBuf)) {
+        let dir1 = tmpdir.mash("dir1");
+        let file1 = dir1.mash("file1");
+        let file2 = dir1.mash("file2");
+        let link1 = tmpdir.mash("link1");
+        let link2 = tmpdir.mash("link2");
+        assert_vfs_mkdir_p!(lower, &dir1);
+        assert_vfs_write_all!(lower, &file1, "foo");
+        assert_vfs_write_all!(lower, &file2, "foo");
+        assert_vfs_symlink!(lower, &link1, &file1);
+        assert_vfs_symlink!(lower, &link2, &dir1);
+
+        // Removing a link leaves its target
+        let vfs = Overlayfs::new(lower);
+        assert_vfs_remove!(vfs, &link1);
+        assert_vfs_no_exists!(vfs, &link1);
+        assert_vfs_read_all!(vfs, &file1, "foo");
+
+        // Removed lower files stay hidden and don't come back with their lower data
+        assert_vfs_remove!(vfs, &file1);
+        assert_vfs_no_exists!(vfs, &file1);
+        assert_eq!(vfs.paths(&dir1).unwrap(), vec![file2.clone()]);
+        assert_vfs_mkfile!(vfs, &file1);
+        assert_vfs_read_all!(vfs, &file1, "");
+        assert_eq!(vfs.size(&file1).unwrap(), 0);
+
+        // Removing through a link removes the target's entries
+        assert_vfs_remove!(vfs, link2.mash("file2"));
+        assert_vfs_no_exists!(vfs, &file2);
+        assert!(vfs.remove_all(&link2).is_ok());
+        assert_vfs_no_exists!(vfs, &link2);
+        assert_vfs_is_dir!(vfs, &dir1);
+
+        // Removed lower trees are hidden as a whole
+        assert!(vfs.remove_all(&dir1).is_ok());
+        assert_vfs_no_exists!(vfs, &dir1);
+        assert_eq!(vfs.paths(&tmpdir).unwrap(), Vec::<PathBuf>::new());
+
+        // The lower layer is left untouched
+        assert_eq!(vfs.lower().paths(&tmpdir).unwrap(), vec![dir1.clone(), link1.clone(), link2]);
+        assert_vfs_read_all!(vfs.lower(), &file1, "foo");
+        assert_vfs_read_all!(vfs.lower(), &file2, "foo");
+
+        assert_vfs_remove_all!(vfs.lower(), &tmpdir);
+    }
+}
@@ -18,7 +18,7 @@ pub fn base<T: AsRef<Path>>(path: T) -> RvResult<String> {
 /// Return the shortest equivalent to the given `path` by purely lexical processing
 ///
 /// * Purely lexical processing may not handle links correctly in some cases, use `canonicalize` in
-/// those cases
+///   those cases
 ///
 /// ### Algorithm
 /// Applies the following rules interatively until no further processing can be done.
@@ -66,7 +66,7 @@ pub fn clean<T: AsRef<Path>>(path: T) -> PathBuf {
                     Component::Normal(_) => {
                         cnt -= 1;
                         path_buf.pop();
-                        prev = path_buf.components().last();
+                        prev = path_buf.components().next_back();
                     },
                     _ => {},
                 }
@@ -89,6 +89,50 @@ pub fn clean<T: AsRef<Path>>(path: T) -> PathBuf {
     path_buf
 }
 
+/// Compress the given variable values back into their names for display, the inverse of `expand`
+///
+/// * Values only match whole leading path components i.e. `/foo` doesn't match `/foobar`
+/// * The value with the most components wins when more than one matches
+/// * Empty and root values are ignored as they would match everything
+///
+/// ### Examples
+/// ```
+/// use rivia::prelude::*;
+///
+/// let path = sys::compress_env("/src/rivia/src/lib.rs", &[("$REPO", "/src/rivia")]);
+/// assert_eq!(path, PathBuf::from("$REPO/src/lib.rs"));
+/// ```
+pub fn compress_env<T: AsRef<Path>, U: AsRef<str>, V: AsRef<Path>>(path: T, vars: &[(U, V)]) -> PathBuf {
+    let path = path.as_ref();
+    let best = vars
+        .iter()
+        .filter(|(_, value)| !is_empty(value.as_ref()) && value.as_ref() != Path::new("/"))
+        .filter(|(_, value)| path.starts_with(value))
+        .max_by_key(|(_, value)| value.as_ref().components().count());
+    match best {
+        Some((name, value)) => {
+            let remainder = path.strip_prefix(value).unwrap_or(path);
+            mash(name.as_ref(), remainder)
+        },
+        None => path.to_path_buf(),
+    }
+}
+
+/// Compress the user's home directory prefix into `~` for display, the inverse of `expand`
+///
+/// * Only whole leading path components match i.e. `/home/user` doesn't match `/home/username`
+///
+/// ### Examples
+/// ```
+/// use rivia::prelude::*;
+///
+/// let home = sys::home_dir().unwrap();
+/// assert_eq!(sys::compress_home(home.mash("foo")).unwrap(), PathBuf::from("~/foo"));
+/// ```
+pub fn compress_home<T: AsRef<Path>>(path: T) -> RvResult<PathBuf> {
+    Ok(compress_env(path, &[("~", home_dir()?)]))
+}
+
 /// Returns the `Path` with the given string concatenated on without injecting
 /// path separators.
 ///
@@ -119,6 +163,24 @@ pub fn dir<T: AsRef<Path>>(path: T) -> RvResult<PathBuf> {
     Ok(dir.to_path_buf())
 }
 
+/// Returns true if the `Path` as a String ends with any of the given suffixes
+///
+/// * Useful for matching multiple extensions at once including compound ones e.g. `.tar.gz`
+///
+/// ### Examples
+/// ```
+/// use rivia::prelude::*;
+///
+/// assert_eq!(sys::ends_with_any("/foo/bar.tar.gz", &[".rs", ".tar.gz"]), true);
+/// assert_eq!(sys::ends_with_any("/foo/bar.gz", &[".rs", ".tar.gz"]), false);
+/// ```
+pub fn ends_with_any<T: AsRef<Path>, U: AsRef<str>>(path: T, suffixes: &[U]) -> bool {
+    match path.as_ref().to_string() {
+        Ok(path) => suffixes.iter().any(|x| path.ends_with(x.as_ref())),
+        _ => false,
+    }
+}
+
 /// Expand home variable `~` and all environment variables in the path
 ///
 /// ### Examples
@@ -311,6 +373,25 @@ pub fn is_empty<T: Into<PathBuf>>(path: T) -> bool {
     path.into() == PathBuf::new()
 }
 
+/// Returns true if the `Path` is beneath the given `base` path
+///
+/// * Compares whole components after cleaning both paths so `..` can't escape the base
+/// * The base itself is not considered to be under itself
+///
+/// ### Examples
+/// ```
+/// use rivia::prelude::*;
+///
+/// assert_eq!(sys::is_under("/foo/bar", "/foo"), true);
+/// assert_eq!(sys::is_under("/foo/../bar", "/foo"), false);
+/// assert_eq!(sys::is_under("/foobar", "/foo"), false);
+/// ```
+pub fn is_under<T: AsRef<Path>, U: AsRef<Path>>(path: T, base: U) -> bool {
+    let path = clean(path);
+    let base = clean(base);
+    path != base && path.starts_with(base)
+}
+
 /// Returns the last path component. Alias to `base`
 ///
 /// ### Examples
@@ -424,6 +505,22 @@ pub fn relative<T: AsRef<Path>, U: AsRef<Path>>(path: T, base: U) -> RvResult<Pa
     Ok(path.to_owned())
 }
 
+/// Returns true if the `Path` starts with any of the given prefixes
+///
+/// * Compares whole components i.e. `/foobar` doesn't start with `/foo`
+///
+/// ### Examples
+/// ```
+/// use rivia::prelude::*;
+///
+/// assert_eq!(sys::starts_with_any("/usr/lib/foo", &["/etc", "/usr/lib"]), true);
+/// assert_eq!(sys::starts_with_any("/usr/libexec", &["/etc", "/usr/lib"]), false);
+/// ```
+pub fn starts_with_any<T: AsRef<Path>, U: AsRef<Path>>(path: T, prefixes: &[U]) -> bool {
+    let path = path.as_ref();
+    prefixes.iter().any(|x| path.starts_with(x))
+}
+
 /// Returns a new [`PathBuf`] with the file extension trimmed off.
 ///
 /// ### Examples
@@ -565,6 +662,32 @@ pub trait PathExt {
     /// ```
     fn clean(&self) -> PathBuf;
 
+    /// Compress the given variable values back into their names for display, the inverse of
+    /// `expand`
+    ///
+    /// * Values only match whole leading path components i.e. `/foo` doesn't match `/foobar`
+    /// * The value with the most components wins when more than one matches
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let path = Path::new("/src/rivia/src/lib.rs").compress_env(&[("$REPO", "/src/rivia")]);
+    /// assert_eq!(path, PathBuf::from("$REPO/src/lib.rs"));
+    /// ```
+    fn compress_env<T: AsRef<str>, U: AsRef<Path>>(&self, vars: &[(T, U)]) -> PathBuf;
+
+    /// Compress the user's home directory prefix into `~` for display, the inverse of `expand`
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let home = sys::home_dir().unwrap();
+    /// assert_eq!(home.mash("foo").compress_home().unwrap(), PathBuf::from("~/foo"));
+    /// ```
+    fn compress_home(&self) -> RvResult<PathBuf>;
+
     /// Returns the `Path` with the given string concatenated on without injecting
     /// path separators.
     ///
@@ -589,6 +712,19 @@ pub trait PathExt {
     /// ```
     fn dir(&self) -> RvResult<PathBuf>;
 
+    /// Returns true if the `Path` as a String ends with any of the given suffixes
+    ///
+    /// * Useful for matching multiple extensions at once including compound ones e.g. `.tar.gz`
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// assert_eq!(Path::new("/foo/bar.tar.gz").ends_with_any(&[".rs", ".tar.gz"]), true);
+    /// assert_eq!(Path::new("/foo/bar.gz").ends_with_any(&[".rs", ".tar.gz"]), false);
+    /// ```
+    fn ends_with_any<T: AsRef<str>>(&self, suffixes: &[T]) -> bool;
+
     /// Expand the path to include the home prefix if necessary
     ///
     /// ### Examples
@@ -667,6 +803,21 @@ pub trait PathExt {
     /// ```
     fn is_empty(&self) -> bool;
 
+    /// Returns true if the `Path` is beneath the given `base` path
+    ///
+    /// * Compares whole components after cleaning both paths so `..` can't escape the base
+    /// * The base itself is not considered to be under itself
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// assert_eq!(Path::new("/foo/bar").is_under("/foo"), true);
+    /// assert_eq!(Path::new("/foo/../bar").is_under("/foo"), false);
+    /// assert_eq!(Path::new("/foobar").is_under("/foo"), false);
+    /// ```
+    fn is_under<T: AsRef<Path>>(&self, base: T) -> bool;
+
     /// Returns the last component of the path
     ///
     /// ### Examples
@@ -720,6 +871,19 @@ pub trait PathExt {
     /// ```
     fn relative<T: AsRef<Path>>(&self, path: T) -> RvResult<PathBuf>;
 
+    /// Returns true if the `Path` starts with any of the given prefixes
+    ///
+    /// * Compares whole components i.e. `/foobar` doesn't start with `/foo`
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// assert_eq!(Path::new("/usr/lib/foo").starts_with_any(&["/etc", "/usr/lib"]), true);
+    /// assert_eq!(Path::new("/usr/libexec").starts_with_any(&["/etc", "/usr/lib"]), false);
+    /// ```
+    fn starts_with_any<T: AsRef<Path>>(&self, prefixes: &[T]) -> bool;
+
     /// Returns a new [`PathBuf`] with the file extension trimmed off.
     ///
     /// ### Examples
@@ -823,6 +987,36 @@ impl PathExt for Path {
         clean(self)
     }
 
+    /// Compress the given variable values back into their names for display, the inverse of
+    /// `expand`
+    ///
+    /// * Values only match whole leading path components i.e. `/foo` doesn't match `/foobar`
+    /// * The value with the most components wins when more than one matches
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let path = Path::new("/src/rivia/src/lib.rs").compress_env(&[("$REPO", "/src/rivia")]);
+    /// assert_eq!(path, PathBuf::from("$REPO/src/lib.rs"));
+    /// ```
+    fn compress_env<T: AsRef<str>, U: AsRef<Path>>(&self, vars: &[(T, U)]) -> PathBuf {
+        compress_env(self, vars)
+    }
+
+    /// Compress the user's home directory prefix into `~` for display, the inverse of `expand`
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let home = sys::home_dir().unwrap();
+    /// assert_eq!(home.mash("foo").compress_home().unwrap(), PathBuf::from("~/foo"));
+    /// ```
+    fn compress_home(&self) -> RvResult<PathBuf> {
+        compress_home(self)
+    }
+
     /// Returns the `Path` with the given string concatenated on without injecting
     /// path separators.
     ///
@@ -851,6 +1045,21 @@ impl PathExt for Path {
         dir(self)
     }
 
+    /// Returns true if the `Path` as a String ends with any of the given suffixes
+    ///
+    /// * Useful for matching multiple extensions at once including compound ones e.g. `.tar.gz`
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// assert_eq!(Path::new("/foo/bar.tar.gz").ends_with_any(&[".rs", ".tar.gz"]), true);
+    /// assert_eq!(Path::new("/foo/bar.gz").ends_with_any(&[".rs", ".tar.gz"]), false);
+    /// ```
+    fn ends_with_any<T: AsRef<str>>(&self, suffixes: &[T]) -> bool {
+        ends_with_any(self, suffixes)
+    }
+
     /// Expand the path to include the home prefix if necessary
     ///
     /// ### Examples
@@ -899,6 +1108,23 @@ impl PathExt for Path {
         is_empty(self)
     }
 
+    /// Returns true if the `Path` is beneath the given `base` path
+    ///
+    /// * Compares whole components after cleaning both paths so `..` can't escape the base
+    /// * The base itself is not considered to be under itself
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// assert_eq!(Path::new("/foo/bar").is_under("/foo"), true);
+    /// assert_eq!(Path::new("/foo/../bar").is_under("/foo"), false);
+    /// assert_eq!(Path::new("/foobar").is_under("/foo"), false);
+    /// ```
+    fn is_under<T: AsRef<Path>>(&self, base: T) -> bool {
+        is_under(self, base)
+    }
+
     /// Returns true if the `Path` contains the given path or string.
     ///
     /// ### Examples
@@ -1002,6 +1228,21 @@ impl PathExt for Path {
         relative(self, path)
     }
 
+    /// Returns true if the `Path` starts with any of the given prefixes
+    ///
+    /// * Compares whole components i.e. `/foobar` doesn't start with `/foo`
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// assert_eq!(Path::new("/usr/lib/foo").starts_with_any(&["/etc", "/usr/lib"]), true);
+    /// assert_eq!(Path::new("/usr/libexec").starts_with_any(&["/etc", "/usr/lib"]), false);
+    /// ```
+    fn starts_with_any<T: AsRef<Path>>(&self, prefixes: &[T]) -> bool {
+        starts_with_any(self, prefixes)
+    }
+
     /// Returns a new [`PathBuf`] with the file extension trimmed off.
     ///
     /// ### Examples
@@ -1141,6 +1382,35 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_pathext_compress_env() {
+        let vars = [("$REPO", PathBuf::from("/src/rivia")), ("$SRC", PathBuf::from("/src/rivia/src"))];
+        assert_eq!(Path::new("/src/rivia").compress_env(&vars), PathBuf::from("$REPO"));
+        assert_eq!(Path::new("/src/rivia/").compress_env(&vars), PathBuf::from("$REPO"));
+        assert_eq!(Path::new("/src/rivia/Cargo.toml").compress_env(&vars), PathBuf::from("$REPO/Cargo.toml"));
+
+        // Most specific value wins regardless of order
+        assert_eq!(Path::new("/src/rivia/src/lib.rs").compress_env(&vars), PathBuf::from("$SRC/lib.rs"));
+
+        // Only whole components match
+        assert_eq!(Path::new("/src/rivia2/foo").compress_env(&vars), PathBuf::from("/src/rivia2/foo"));
+        assert_eq!(Path::new("/foo").compress_env(&vars), PathBuf::from("/foo"));
+
+        // Empty and root values are ignored
+        assert_eq!(Path::new("/foo").compress_env(&[("$A", ""), ("$B", "/")]), PathBuf::from("/foo"));
+        assert_eq!(Path::new("foo").compress_env::<&str, &str>(&[]), PathBuf::from("foo"));
+    }
+
+    #[test]
+    fn test_pathext_compress_home() -> RvResult<()> {
+        let home = sys::home_dir()?;
+        assert_eq!(home.compress_home()?, PathBuf::from("~"));
+        assert_eq!(home.mash("foo/bar").compress_home()?, PathBuf::from("~/foo/bar"));
+        assert_eq!(home.concat("foo")?.compress_home()?, home.concat("foo")?);
+        assert_eq!(home.mash("foo").compress_home()?.expand()?, home.mash("foo"));
+        Ok(())
+    }
+
     #[test]
     fn test_pathext_concat() {
         assert_eq!(Path::new("/foo/bar").concat(".rs").unwrap(), PathBuf::from("/foo/bar.rs"));
@@ -1183,7 +1453,7 @@ mod tests {
         assert_eq!(Path::new("/foo/${HOME}/bar").expand()?, PathBuf::from("/foo").join(&home).join("bar"));
         assert_eq!(
             Path::new("/foo${HOME}/bar").expand()?,
-            PathBuf::from("/foo".to_string() + &home.to_string()? + &"/bar".to_string())
+            PathBuf::from("/foo".to_string() + &home.to_string()? + "/bar")
         );
         assert_eq!(
             Path::new("/foo${HOME}${HOME}").expand()?,
@@ -1196,6 +1466,15 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_pathext_ends_with_any() {
+        assert!(!Path::new("foo.rs").ends_with_any::<&str>(&[]));
+        assert!(Path::new("foo.rs").ends_with_any(&[".toml", ".rs"]));
+        assert!(Path::new("foo.tar.gz").ends_with_any(&["tar.gz"]));
+        assert!(PathBuf::from("/foo/bar").ends_with_any(&["/bar".to_string()]));
+        assert!(!Path::new("foo.rs.bak").ends_with_any(&[".toml", ".rs"]));
+    }
+
     #[test]
     fn test_pathext_ext() {
         assert_eq!(
@@ -1215,24 +1494,36 @@ mod tests {
 
     #[test]
     fn test_pathext_has() {
-        assert_eq!(Path::new("").has(""), true);
-        assert_eq!(Path::new("/foo").has("fo"), true);
-        assert_eq!(Path::new("/foo/bar").has("bar"), true);
-        assert_eq!(Path::new("/foo/bar").has("bar/"), false);
+        assert!(Path::new("").has(""));
+        assert!(Path::new("/foo").has("fo"));
+        assert!(Path::new("/foo/bar").has("bar"));
+        assert!(!Path::new("/foo/bar").has("bar/"));
     }
 
     #[test]
     fn test_pathext_has_prefix() {
-        assert_eq!(Path::new("").has_prefix(""), true);
-        assert_eq!(Path::new("/foo").has_prefix("/fo"), true);
-        assert_eq!(Path::new("/foo/bar").has_prefix("bar/"), false);
+        assert!(Path::new("").has_prefix(""));
+        assert!(Path::new("/foo").has_prefix("/fo"));
+        assert!(!Path::new("/foo/bar").has_prefix("bar/"));
     }
 
     #[test]
     fn test_pathext_has_suffix() {
-        assert_eq!(Path::new("").has_suffix(""), true);
-        assert_eq!(Path::new("/foo").has_suffix("/fo"), false);
-        assert_eq!(Path::new("/foo/bar").has_suffix("bar"), true);
+        assert!(Path::new("").has_suffix(""));
+        assert!(!Path::new("/foo").has_suffix("/fo"));
+        assert!(Path::new("/foo/bar").has_suffix("bar"));
+    }
+
+    #[test]
+    fn test_pathext_is_under() {
+        assert!(Path::new("/foo/bar").is_under("/foo"));
+        assert!(Path::new("/foo/bar/").is_under("/foo/"));
+        assert!(Path::new("foo/bar").is_under("foo"));
+        assert!(Path::new("/foo").is_under("/"));
+        assert!(!Path::new("/foo").is_under("/foo"));
+        assert!(!Path::new("/foobar").is_under("/foo"));
+        assert!(!Path::new("/foo/../bar").is_under("/foo"));
+        assert!(Path::new("/bar/../foo/baz").is_under("/foo"));
     }
 
     #[test]
@@ -1252,9 +1543,9 @@ mod tests {
 
     #[test]
     fn test_pathext_is_empty() {
-        assert_eq!(Path::new("/").is_empty(), false);
-        assert_eq!(Path::new("").is_empty(), true);
-        assert_eq!(PathBuf::from("").is_empty(), true);
+        assert!(!PathExt::is_empty(Path::new("/")));
+        assert!(PathExt::is_empty(Path::new("")));
+        assert!(PathExt::is_empty(PathBuf::from("").as_path()));
     }
 
     #[test]
@@ -1304,6 +1595,15 @@ mod tests {
         assert_eq!(Path::new("/dir1").relative("/dir1/dir2").unwrap(), PathBuf::from(".."));
     }
 
+    #[test]
+    fn test_pathext_starts_with_any() {
+        assert!(!Path::new("/foo").starts_with_any::<&str>(&[]));
+        assert!(Path::new("/foo/bar").starts_with_any(&["/bar", "/foo"]));
+        assert!(Path::new("/foo/bar").starts_with_any(&[PathBuf::from("/foo/bar")]));
+        assert!(!Path::new("/foobar").starts_with_any(&["/foo"]));
+        assert!(!Path::new("foo/bar").starts_with_any(&["/foo"]));
+    }
+
     #[test]
     fn test_pathext_trim_ext() {
         assert_eq!(Path::new("/").trim_ext().unwrap(), PathBuf::from("/"));
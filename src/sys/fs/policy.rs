@@ -0,0 +1,281 @@
+use std::path::Path;
+
+use crate::{
+    errors::*,
+    sys::{self, VfsOp, VirtualFileSystem},
+};
+
+/// Provides a declarative allow/deny policy for confining the operations a Vfs may perform
+///
+/// Rules are given as a glob pattern optionally narrowed to a single operation class. Rules are
+/// evaluated in the order they were added with the last matching rule winning. When no rule
+/// matches the policy's default is used. Patterns are matched against the absolute path of the
+/// operation.
+///
+/// * `*` matches any sequence of characters excluding the path separator
+/// * `**` matches any sequence of characters including the path separator
+/// * `?` matches any single character excluding the path separator
+/// * `[abc]`, `[a-z]` and `[!a-z]` match a single character in or not in the given set
+///
+/// ### Examples
+/// ```
+/// use rivia::prelude::*;
+///
+/// let vfs = Vfs::memfs();
+/// VfsPolicy::deny_by_default().allow_all("/tmp/**").deny(VfsOp::Remove, "/tmp/keep").attach(&vfs);
+/// assert_vfs_mkdir_p!(vfs, "/tmp/keep");
+/// assert!(vfs.mkdir_p("/etc").is_err());
+/// assert!(vfs.remove("/tmp/keep").is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VfsPolicy
+{
+    allow: bool,            // default when no rules match
+    rules: Vec<PolicyRule>, // rules in the order they were added
+}
+
+// Internal type used to track a single policy rule
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct PolicyRule
+{
+    allow: bool,       // allow or deny when matched
+    op: Option<VfsOp>, // operation class the rule applies to or all when None
+    glob: String,      // glob pattern to match against the absolute path
+}
+
+impl VfsPolicy
+{
+    /// Create a new policy that allows any operation not explicitly denied
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let policy = VfsPolicy::allow_by_default();
+    /// assert!(policy.check(VfsOp::Write, "/etc/hosts").is_ok());
+    /// ```
+    pub fn allow_by_default() -> Self
+    {
+        Self { allow: true, rules: vec![] }
+    }
+
+    /// Create a new policy that denies any operation not explicitly allowed
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let policy = VfsPolicy::deny_by_default();
+    /// assert!(policy.check(VfsOp::Write, "/etc/hosts").is_err());
+    /// ```
+    pub fn deny_by_default() -> Self
+    {
+        Self { allow: false, rules: vec![] }
+    }
+
+    /// Allow the given operation class for paths matching the given glob
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let policy = VfsPolicy::deny_by_default().allow(VfsOp::Write, "/tmp/*");
+    /// assert!(policy.check(VfsOp::Write, "/tmp/file").is_ok());
+    /// assert!(policy.check(VfsOp::Remove, "/tmp/file").is_err());
+    /// ```
+    pub fn allow<T: AsRef<str>>(mut self, op: VfsOp, glob: T) -> Self
+    {
+        self.rules.push(PolicyRule { allow: true, op: Some(op), glob: glob.as_ref().to_string() });
+        self
+    }
+
+    /// Allow all operation classes for paths matching the given glob
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let policy = VfsPolicy::deny_by_default().allow_all("/tmp/**");
+    /// assert!(policy.check(VfsOp::Write, "/tmp/dir/file").is_ok());
+    /// assert!(policy.check(VfsOp::Remove, "/tmp/dir/file").is_ok());
+    /// assert!(policy.check(VfsOp::Remove, "/etc/hosts").is_err());
+    /// ```
+    pub fn allow_all<T: AsRef<str>>(mut self, glob: T) -> Self
+    {
+        self.rules.push(PolicyRule { allow: true, op: None, glob: glob.as_ref().to_string() });
+        self
+    }
+
+    /// Deny the given operation class for paths matching the given glob
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let policy = VfsPolicy::allow_by_default().deny(VfsOp::Write, "/etc/*");
+    /// assert!(policy.check(VfsOp::Write, "/etc/hosts").is_err());
+    /// assert!(policy.check(VfsOp::Chmod, "/etc/hosts").is_ok());
+    /// ```
+    pub fn deny<T: AsRef<str>>(mut self, op: VfsOp, glob: T) -> Self
+    {
+        self.rules.push(PolicyRule { allow: false, op: Some(op), glob: glob.as_ref().to_string() });
+        self
+    }
+
+    /// Deny all operation classes for paths matching the given glob
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let policy = VfsPolicy::allow_by_default().deny_all("/etc/**");
+    /// assert!(policy.check(VfsOp::Write, "/etc/hosts").is_err());
+    /// assert!(policy.check(VfsOp::Chmod, "/etc/hosts").is_err());
+    /// assert!(policy.check(VfsOp::Chmod, "/tmp/file").is_ok());
+    /// ```
+    pub fn deny_all<T: AsRef<str>>(mut self, glob: T) -> Self
+    {
+        self.rules.push(PolicyRule { allow: false, op: None, glob: glob.as_ref().to_string() });
+        self
+    }
+
+    /// Check if the given operation on the given path is allowed by the policy
+    ///
+    /// * The path is expected to be in absolute form
+    ///
+    /// ### Errors
+    /// * VfsError::PolicyDenied(VfsOp, PathBuf) when the operation is not allowed
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let policy = VfsPolicy::allow_by_default().deny(VfsOp::Write, "/etc/*");
+    /// assert_eq!(
+    ///     policy.check(VfsOp::Write, "/etc/hosts").unwrap_err().to_string(),
+    ///     "Vfs policy denied Write operation for: /etc/hosts"
+    /// );
+    /// ```
+    pub fn check<T: AsRef<Path>>(&self, op: VfsOp, path: T) -> RvResult<()>
+    {
+        let path = path.as_ref();
+        let target = path.to_string_lossy();
+        let allow = self
+            .rules
+            .iter()
+            .rev()
+            .find(|x| (x.op.is_none() || x.op == Some(op)) && sys::glob_match(&x.glob, &target))
+            .map_or(self.allow, |x| x.allow);
+        match allow {
+            true => Ok(()),
+            false => Err(VfsError::PolicyDenied(op, path.to_path_buf()).into()),
+        }
+    }
+
+    /// Attach the policy to the given Vfs backend as a pre operation hook
+    ///
+    /// * Every mutating operation on the backend is checked against the policy
+    /// * Links are resolved and the paths they lead to checked as well so that a link in an allowed
+    ///   location can't be used to operate on a denied one
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// VfsPolicy::allow_by_default().deny_all("/etc/**").attach(&vfs);
+    /// assert_vfs_mkdir_p!(vfs, "/etc");
+    /// assert!(vfs.write_all("/etc/hosts", "foo").is_err());
+    /// assert_vfs_no_exists!(vfs, "/etc/hosts");
+    /// ```
+    pub fn attach<T: VirtualFileSystem>(self, vfs: &T)
+    {
+        vfs.hook_pre(move |op, path| self.check(op, path));
+    }
+}
+
+// Unit tests
+// -------------------------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests
+{
+    use crate::prelude::*;
+
+    #[test]
+    fn test_vfs_policy()
+    {
+        test_policy(assert_vfs_setup!(Vfs::memfs()));
+        test_policy(assert_vfs_setup!(Vfs::stdfs()));
+    }
+    fn test_policy((vfs, tmpdir): (Vfs, PathBuf))
+    {
+        let dir = tmpdir.mash("dir");
+        let file = dir.mash("file");
+        let keep = dir.mash("keep");
+        let other = tmpdir.dir().unwrap().mash("other");
+        let etc = tmpdir.mash("etc");
+        let hosts = etc.mash("hosts");
+        let link1 = dir.mash("link1");
+        let link2 = dir.mash("link2");
+
+        // Confine the vfs to the tmpdir, protect the keep file from removal and deny the etc dir
+        VfsPolicy::deny_by_default()
+            .allow_all(tmpdir.to_string().unwrap())
+            .allow_all(format!("{}/**", tmpdir.display()))
+            .deny(VfsOp::Remove, keep.to_string().unwrap())
+            .deny_all(format!("{}/**", etc.display()))
+            .attach(&vfs);
+
+        assert_vfs_mkdir_p!(vfs, &dir);
+        assert_vfs_write_all!(vfs, &file, "foo");
+        assert_vfs_mkfile!(vfs, &keep);
+        assert!(vfs.remove(&file).is_ok());
+        assert_eq!(
+            vfs.remove(&keep).unwrap_err().downcast_ref::<VfsError>(),
+            Some(&VfsError::PolicyDenied(VfsOp::Remove, keep.clone()))
+        );
+        assert_vfs_exists!(vfs, &keep);
+        assert!(vfs.mkdir_p(&other).is_err());
+        assert_vfs_no_exists!(vfs, &other);
+
+        // Relative paths are checked in absolute form
+        assert!(vfs.mkdir_p(tmpdir.mash("../other")).is_err());
+        assert_vfs_no_exists!(vfs, &other);
+
+        // Links in allowed locations can't lead operations into denied ones
+        assert_vfs_mkdir_p!(vfs, &etc);
+        assert!(vfs.symlink(&link1, &hosts).is_ok());
+        assert_vfs_symlink!(vfs, &link2, &etc);
+        assert_eq!(
+            vfs.write_all(&link1, "foo").unwrap_err().downcast_ref::<VfsError>(),
+            Some(&VfsError::PolicyDenied(VfsOp::Write, hosts.clone()))
+        );
+        assert_eq!(
+            vfs.write_all_atomic(&link1, "foo").unwrap_err().downcast_ref::<VfsError>(),
+            Some(&VfsError::PolicyDenied(VfsOp::Write, hosts.clone()))
+        );
+        assert!(vfs.mkfile(link2.mash("hosts")).is_err());
+        assert_vfs_no_exists!(vfs, &hosts);
+
+        // Links themselves may still be removed
+        assert!(vfs.remove_all(&link1).is_ok());
+        assert!(vfs.remove_all(&link2).is_ok());
+        assert!(!vfs.is_symlink(&link1));
+        assert!(!vfs.is_symlink(&link2));
+        assert_vfs_is_dir!(vfs, &etc);
+
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+
+    #[test]
+    fn test_rules()
+    {
+        // Last matching rule wins
+        let policy = VfsPolicy::allow_by_default().deny_all("/etc/**").allow(VfsOp::Write, "/etc/hosts");
+        assert!(policy.check(VfsOp::Write, "/etc/hosts").is_ok());
+        assert!(policy.check(VfsOp::Remove, "/etc/hosts").is_err());
+        assert!(policy.check(VfsOp::Write, "/etc/passwd").is_err());
+        assert!(policy.check(VfsOp::Write, "/tmp/file").is_ok());
+        assert_eq!(policy, policy.clone());
+    }
+}
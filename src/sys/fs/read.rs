@@ -0,0 +1,164 @@
+use std::path::PathBuf;
+
+use crate::{
+    errors::RvResult,
+    sys::{Advice, ReadSeek},
+};
+
+/// Provides a builder pattern for flexibly opening files for reading
+///
+/// Use the Vfs functions `read_b` to create a new instance followed by one or more options and
+/// complete the operation by calling `exec`.
+///
+/// ```
+/// use rivia::prelude::*;
+///
+/// let vfs = Memfs::new();
+/// let file = vfs.root().mash("file");
+/// assert_vfs_write_all!(vfs, &file, "this is a test");
+/// let mut buf = String::new();
+/// assert!(vfs.read_b(&file).unwrap().exec().unwrap().read_to_string(&mut buf).is_ok());
+/// assert_eq!(buf, "this is a test");
+/// ```
+pub struct Reader
+{
+    pub(crate) opts: ReadOpts,
+    pub(crate) exec: Box<dyn Fn(ReadOpts) -> RvResult<Box<dyn ReadSeek>>>, // provider callback
+}
+
+// Internal type used to encapsulate just the options. This separates the provider implementation
+// from the options allowing for sharing options between different vfs providers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ReadOpts
+{
+    pub(crate) path: PathBuf,           // file to read
+    pub(crate) direct: bool,            // bypass the page cache
+    pub(crate) advice: Option<Advice>,  // access pattern hint for the opened file
+}
+
+impl Reader
+{
+    /// Set the `advice` option to hint how the opened file will be accessed
+    ///
+    /// * Default: no advice
+    /// * Stdfs applies it to the opened file with `posix_fadvise` e.g. `Advice::Sequential` to read
+    ///   ahead more aggressively
+    /// * Ignored for `direct` reads as they bypass the page cache
+    /// * Memfs has no page cache so this is a no-op
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Memfs::new();
+    /// let file = vfs.root().mash("file");
+    /// assert_vfs_write_all!(vfs, &file, "this is a test");
+    /// let mut buf = String::new();
+    /// let mut f = vfs.read_b(&file).unwrap().advise(Advice::Sequential).exec().unwrap();
+    /// assert!(f.read_to_string(&mut buf).is_ok());
+    /// assert_eq!(buf, "this is a test");
+    /// ```
+    pub fn advise(mut self, advice: Advice) -> Self
+    {
+        self.opts.advice = Some(advice);
+        self
+    }
+
+    /// Update the `direct` option
+    ///
+    /// * Default: false
+    /// * When `true` Stdfs opens the file with `O_DIRECT` bypassing the page cache so that reads
+    ///   measure the actual device throughput
+    /// * The aligned buffers `O_DIRECT` requires are handled internally so reads of any size and
+    ///   seeks to any position work as usual
+    /// * Falls back on reading through the page cache on filesystems that don't support
+    ///   `O_DIRECT` e.g. older tmpfs
+    /// * Memfs has no page cache so this is a no-op
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Memfs::new();
+    /// let file = vfs.root().mash("file");
+    /// assert_vfs_write_all!(vfs, &file, "this is a test");
+    /// let mut buf = String::new();
+    /// assert!(vfs.read_b(&file).unwrap().direct(true).exec().unwrap().read_to_string(&mut buf).is_ok());
+    /// assert_eq!(buf, "this is a test");
+    /// ```
+    pub fn direct(mut self, yes: bool) -> Self
+    {
+        self.opts.direct = yes;
+        self
+    }
+
+    /// Execute the [`Reader`] builder current options returning the opened file
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Memfs::new();
+    /// let file = vfs.root().mash("file");
+    /// assert_vfs_write_all!(vfs, &file, "this is a test");
+    /// let mut buf = String::new();
+    /// assert!(vfs.read_b(&file).unwrap().exec().unwrap().read_to_string(&mut buf).is_ok());
+    /// assert_eq!(buf, "this is a test");
+    /// ```
+    pub fn exec(&self) -> RvResult<Box<dyn ReadSeek>>
+    {
+        (self.exec)(self.opts.clone())
+    }
+}
+
+// Unit tests
+// -------------------------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests
+{
+    use crate::prelude::*;
+
+    #[test]
+    fn test_vfs_read_b()
+    {
+        test_read_b(assert_vfs_setup!(Vfs::memfs()));
+        test_read_b(assert_vfs_setup!(Vfs::stdfs()));
+    }
+    fn test_read_b((vfs, tmpdir): (Vfs, PathBuf))
+    {
+        let dir1 = tmpdir.mash("dir1");
+        let file1 = tmpdir.mash("file1");
+
+        // Errors
+        let err = vfs.read_b(&file1).unwrap().exec().err().unwrap();
+        assert_eq!(err.downcast_ref::<PathError>(), Some(&PathError::does_not_exist(&file1)));
+        assert_vfs_mkdir_p!(vfs, &dir1);
+        let err = vfs.read_b(&dir1).unwrap().direct(true).exec().err().unwrap();
+        assert_eq!(err.downcast_ref::<PathError>(), Some(&PathError::is_not_file(&dir1)));
+
+        // Spans several internal buffers and ends part way through a block
+        let data = (0..3_000_000).map(|x| (x % 251) as u8).collect::<Vec<u8>>();
+        assert!(vfs.write_all(&file1, &data).is_ok());
+        for (direct, advice) in [(false, Advice::Normal), (false, Advice::Sequential), (true, Advice::Random)] {
+            let mut f = vfs.read_b(&file1).unwrap().direct(direct).advise(advice).exec().unwrap();
+            let mut buf = vec![];
+            assert_eq!(f.read_to_end(&mut buf).unwrap(), data.len());
+            assert!(buf == data);
+
+            // Seek to unaligned positions
+            let mut buf = [0u8; 10];
+            assert_eq!(f.seek(SeekFrom::Start(4099)).unwrap(), 4099);
+            f.read_exact(&mut buf).unwrap();
+            assert_eq!(&buf, &data[4099..4109]);
+            assert_eq!(f.seek(SeekFrom::Current(-5)).unwrap(), 4104);
+            f.read_exact(&mut buf).unwrap();
+            assert_eq!(&buf, &data[4104..4114]);
+            assert_eq!(f.seek(SeekFrom::End(-3)).unwrap(), data.len() as u64 - 3);
+            assert_eq!(f.read(&mut buf).unwrap(), 3);
+            assert_eq!(&buf[..3], &data[data.len() - 3..]);
+            assert_eq!(f.read(&mut buf).unwrap(), 0);
+        }
+
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+}
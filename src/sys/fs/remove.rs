@@ -0,0 +1,403 @@
+use std::{
+    path::{Component, Path, PathBuf},
+    sync::Arc,
+};
+
+use crate::{
+    errors::{RvResult, VfsError},
+    sys::{self, Entries, Entry, VfsEntry},
+};
+
+// Callback asked whether the given entry should be removed
+pub(crate) type RemoveConfirm = Arc<dyn Fn(&VfsEntry) -> bool>;
+
+/// Provides a builder pattern for guarding the recursive removal of a directory
+///
+/// Use the Vfs functions `remove_all_b` to create a new instance followed by one or more options
+/// and complete the operation by calling `exec`. The root directory is always refused.
+///
+/// ```
+/// use rivia::prelude::*;
+///
+/// let vfs = Memfs::new();
+/// let dir = vfs.root().mash("dir");
+/// let file = dir.mash("file");
+/// assert_vfs_mkdir_p!(vfs, &dir);
+/// assert_vfs_mkfile!(vfs, &file);
+/// assert!(vfs.remove_all_b(&dir).unwrap().within(vfs.root()).exec().is_ok());
+/// assert_vfs_no_exists!(vfs, &dir);
+/// ```
+pub struct Remover
+{
+    pub(crate) opts: RemoveOpts,
+    pub(crate) exec: Box<dyn Fn(RemoveOpts) -> RvResult<()>>, // provider callback
+}
+
+// Internal type used to encapsulate just the options. This separates the provider implementation
+// from the options allowing for sharing options between different vfs providers.
+#[derive(Clone)]
+pub(crate) struct RemoveOpts
+{
+    pub(crate) path: PathBuf,                  // path to remove
+    pub(crate) within: Option<PathBuf>,        // base the path must be within
+    pub(crate) same_device: bool,              // refuse to cross into other devices
+    pub(crate) confirm: Option<RemoveConfirm>, // callback to confirm removals with
+    pub(crate) each: bool,                     // confirm every entry not just top-level children
+}
+
+impl RemoveOpts
+{
+    // Check the guards that don't depend on the tree being removed. The given `readlink` function
+    // returns the target stored in the link at a path, or None when it isn't a link, and is used
+    // to keep links along the way from carrying the path outside of the base.
+    pub(crate) fn check<F>(&self, readlink: F) -> RvResult<()>
+    where
+        F: Fn(&Path) -> RvResult<Option<PathBuf>>,
+    {
+        if self.path.components().all(|x| x == Component::RootDir) {
+            return Err(VfsError::RemoveRoot.into());
+        }
+        if let Some(base) = &self.within {
+            // The path itself isn't followed as a link is removed rather than its target
+            if !sys::real_path(&self.path, false, &readlink)?.starts_with(sys::real_path(base, true, &readlink)?) {
+                return Err(VfsError::RemoveOutsideBase(self.path.clone(), base.clone()).into());
+            }
+        }
+        Ok(())
+    }
+
+    // Ask the confirm callback about the entries to remove returning those approved in traversal
+    // order. Entries below a declined directory are kept without asking. Only a path that isn't a
+    // directory is asked about itself.
+    pub(crate) fn confirmed(&self, entries: Entries, is_dir: bool) -> RvResult<Vec<VfsEntry>>
+    {
+        let confirm = match &self.confirm {
+            Some(x) => x,
+            None => return Ok(vec![]),
+        };
+        let max_depth = if self.each { usize::MAX } else { 1 };
+        let entries = entries.min_depth(if is_dir { 1 } else { 0 }).max_depth(max_depth).sort_by_name();
+
+        let mut declined: Vec<PathBuf> = vec![];
+        let mut approved = vec![];
+        for entry in entries {
+            let entry = entry?;
+            if declined.iter().any(|x| entry.path().starts_with(x)) {
+                continue;
+            }
+            if confirm(&entry) {
+                approved.push(entry);
+            } else {
+                declined.push(entry.path_buf());
+            }
+        }
+        Ok(approved)
+    }
+
+    // Approved directories are only removed when empty when confirming every entry as anything
+    // left in them was declined
+    pub(crate) fn only_if_empty(&self, entry: &VfsEntry) -> bool
+    {
+        self.each && entry.is_dir() && !entry.is_symlink()
+    }
+}
+
+impl Remover
+{
+    /// Ask the given callback before removing each top-level child of the path
+    ///
+    /// * Declined children are kept along with everything below them
+    /// * The path itself is removed only if it ends up empty
+    /// * A path that isn't a directory is asked about itself
+    /// * Guards are checked before the callback is called
+    /// * Use `confirm_each` to be asked about every entry
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Memfs::new();
+    /// let dir = vfs.root().mash("dir");
+    /// let file1 = dir.mash("file1");
+    /// let file2 = dir.mash("file2");
+    /// assert_vfs_mkdir_p!(vfs, &dir);
+    /// assert_vfs_mkfile!(vfs, &file1);
+    /// assert_vfs_mkfile!(vfs, &file2);
+    /// assert!(vfs.remove_all_b(&dir).unwrap().confirm(|x| x.path().ends_with("file1")).exec().is_ok());
+    /// assert_vfs_no_exists!(vfs, &file1);
+    /// assert_vfs_is_file!(vfs, &file2);
+    /// ```
+    pub fn confirm<F: Fn(&VfsEntry) -> bool + 'static>(mut self, f: F) -> Self
+    {
+        self.opts.confirm = Some(Arc::new(f));
+        self.opts.each = false;
+        self
+    }
+
+    /// Ask the given callback before removing every entry below the path
+    ///
+    /// * Directories are asked about before their contents in the spirit of `rm -i`
+    /// * Declined directories are kept along with everything below them without asking
+    /// * Approved directories are only removed if they end up empty
+    /// * The path itself is removed only if it ends up empty
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Memfs::new();
+    /// let dir = vfs.root().mash("dir");
+    /// let file1 = dir.mash("sub/file1");
+    /// let file2 = dir.mash("sub/file2");
+    /// assert_vfs_mkdir_p!(vfs, dir.mash("sub"));
+    /// assert_vfs_mkfile!(vfs, &file1);
+    /// assert_vfs_mkfile!(vfs, &file2);
+    /// assert!(vfs.remove_all_b(&dir).unwrap().confirm_each(|x| !x.path().ends_with("file2")).exec().is_ok());
+    /// assert_vfs_no_exists!(vfs, &file1);
+    /// assert_vfs_is_file!(vfs, &file2);
+    /// ```
+    pub fn confirm_each<F: Fn(&VfsEntry) -> bool + 'static>(mut self, f: F) -> Self
+    {
+        self.opts.confirm = Some(Arc::new(f));
+        self.opts.each = true;
+        self
+    }
+
+    /// Refuse to remove anything on a different device than the given path
+    ///
+    /// * Default: false
+    /// * The whole tree is checked before anything is removed so a refusal leaves it untouched
+    /// * Links aren't followed so links to other devices don't count
+    /// * Memfs has no devices so this is a no-op
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Memfs::new();
+    /// let dir = vfs.root().mash("dir");
+    /// assert_vfs_mkdir_p!(vfs, &dir);
+    /// assert!(vfs.remove_all_b(&dir).unwrap().same_device().exec().is_ok());
+    /// assert_vfs_no_exists!(vfs, &dir);
+    /// ```
+    pub fn same_device(mut self) -> Self
+    {
+        self.opts.same_device = true;
+        self
+    }
+
+    /// Refuse to remove the path unless it is within the given base
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * The base itself is considered to be within the base
+    /// * Links along the way to the path and the base are resolved before comparing so that a link
+    ///   can't carry the path outside of the base
+    /// * Guards against path expansion bugs pointing the removal somewhere unexpected
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Memfs::new();
+    /// let dir = vfs.root().mash("dir");
+    /// let home = vfs.root().mash("home");
+    /// assert_vfs_mkdir_p!(vfs, &dir);
+    /// let err = vfs.remove_all_b(&home).unwrap().within(&dir).exec().unwrap_err();
+    /// assert_eq!(err.downcast_ref::<VfsError>(), Some(&VfsError::RemoveOutsideBase(home, dir)));
+    /// ```
+    pub fn within<T: Into<PathBuf>>(mut self, base: T) -> Self
+    {
+        self.opts.within = Some(base.into());
+        self
+    }
+
+    /// Execute the [`Remover`] builder current options
+    ///
+    /// ### Errors
+    /// * VfsError::RemoveRoot when the path is the root directory
+    /// * VfsError::RemoveOutsideBase(PathBuf, PathBuf) when the path isn't within the `within` base
+    /// * VfsError::RemoveCrossesDevice(PathBuf) when `same_device` is set and the tree spans
+    ///   multiple devices
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Memfs::new();
+    /// let err = vfs.remove_all_b(vfs.root()).unwrap().exec().unwrap_err();
+    /// assert_eq!(err.downcast_ref::<VfsError>(), Some(&VfsError::RemoveRoot));
+    /// ```
+    pub fn exec(&self) -> RvResult<()>
+    {
+        (self.exec)(self.opts.clone())
+    }
+}
+
+// Unit tests
+// -------------------------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests
+{
+    use std::sync::{Arc, Mutex};
+
+    use super::RemoveOpts;
+    use crate::prelude::*;
+
+    #[test]
+    fn test_vfs_remove_all_b()
+    {
+        test_remove_all_b(assert_vfs_setup!(Vfs::memfs()));
+        test_remove_all_b(assert_vfs_setup!(Vfs::stdfs()));
+    }
+    fn test_remove_all_b((vfs, tmpdir): (Vfs, PathBuf))
+    {
+        let dir1 = tmpdir.mash("dir1");
+        let dir2 = tmpdir.mash("dir2");
+        let file1 = dir1.mash("file1");
+        let link1 = dir1.mash("link1");
+
+        assert_vfs_mkdir_p!(vfs, &dir1);
+        assert_vfs_mkdir_p!(vfs, &dir2);
+        assert_vfs_mkfile!(vfs, &file1);
+        assert_vfs_symlink!(vfs, &link1, &dir2);
+
+        // Root is always refused, only exercised on Memfs to keep a regression from touching the host
+        if let Vfs::Memfs(_) = vfs {
+            for path in [PathBuf::from("/"), PathBuf::from("//"), PathBuf::from("/foo/..")] {
+                let err = vfs.remove_all(&path).unwrap_err();
+                assert_eq!(err.downcast_ref::<VfsError>(), Some(&VfsError::RemoveRoot));
+            }
+        }
+
+        // Outside the base is refused with nothing removed
+        let err = vfs.remove_all_b(&dir1).unwrap().within(&dir2).exec().unwrap_err();
+        assert_eq!(err.downcast_ref::<VfsError>(), Some(&VfsError::RemoveOutsideBase(dir1.clone(), dir2.clone())));
+        let err = vfs.remove_all_b(dir2.mash("../dir1")).unwrap().within(&dir2).exec().unwrap_err();
+        assert_eq!(err.downcast_ref::<VfsError>(), Some(&VfsError::RemoveOutsideBase(dir1.clone(), dir2.clone())));
+        assert_vfs_is_file!(vfs, &file1);
+
+        // Links along the way can't carry the path outside of the base but may lead to the base
+        let base = tmpdir.mash("base");
+        let outside = tmpdir.mash("outside");
+        let linkdir = base.mash("linkdir");
+        let baselink = tmpdir.mash("baselink");
+        assert_vfs_mkdir_p!(vfs, &base);
+        assert_vfs_mkdir_p!(vfs, outside.mash("sub"));
+        assert_vfs_symlink!(vfs, &linkdir, &outside);
+        assert_vfs_symlink!(vfs, &baselink, &base);
+        let err = vfs.remove_all_b(linkdir.mash("sub")).unwrap().within(&base).exec().unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<VfsError>(),
+            Some(&VfsError::RemoveOutsideBase(linkdir.mash("sub"), base.clone()))
+        );
+        assert_vfs_is_dir!(vfs, outside.mash("sub"));
+        assert!(vfs.remove_all_b(&linkdir).unwrap().within(&baselink).exec().is_ok());
+        assert_vfs_no_exists!(vfs, &linkdir);
+        assert_vfs_is_dir!(vfs, outside.mash("sub"));
+
+        // Within the base and on the same device, links to other directories are removed not followed
+        assert!(vfs.remove_all_b(&dir1).unwrap().within(&tmpdir).same_device().exec().is_ok());
+        assert_vfs_no_exists!(vfs, &dir1);
+        assert_vfs_is_dir!(vfs, &dir2);
+        assert!(vfs.remove_all_b(&dir2).unwrap().within(&dir2).exec().is_ok());
+        assert_vfs_no_exists!(vfs, &dir2);
+
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+
+    #[test]
+    fn test_vfs_remove_all_b_confirm()
+    {
+        test_remove_all_b_confirm(assert_vfs_setup!(Vfs::memfs()));
+        test_remove_all_b_confirm(assert_vfs_setup!(Vfs::stdfs()));
+    }
+    fn test_remove_all_b_confirm((vfs, tmpdir): (Vfs, PathBuf))
+    {
+        let dir1 = tmpdir.mash("dir1");
+        let dir2 = dir1.mash("dir2");
+        let dir3 = dir1.mash("dir3");
+        let file1 = dir1.mash("file1");
+        let file2 = dir2.mash("file2");
+        let file3 = dir3.mash("file3");
+        let file4 = dir3.mash("file4");
+        let setup = || {
+            assert_vfs_mkdir_p!(vfs, &dir2);
+            assert_vfs_mkdir_p!(vfs, &dir3);
+            for file in [&file1, &file2, &file3, &file4] {
+                assert_vfs_mkfile!(vfs, file);
+            }
+        };
+
+        // Top-level children only are asked about
+        setup();
+        let asked = Arc::new(Mutex::new(vec![]));
+        let seen = asked.clone();
+        let remover = vfs.remove_all_b(&dir1).unwrap().confirm(move |x| {
+            seen.lock().unwrap().push(x.path_buf());
+            x.path().ends_with("dir2") || x.path().ends_with("file1")
+        });
+        assert!(remover.exec().is_ok());
+        assert_eq!(*asked.lock().unwrap(), vec![dir2.clone(), dir3.clone(), file1.clone()]);
+        assert_vfs_no_exists!(vfs, &dir2);
+        assert_vfs_no_exists!(vfs, &file1);
+        assert_vfs_is_file!(vfs, &file3);
+        assert_vfs_is_file!(vfs, &file4);
+
+        // Approving everything removes the path as well
+        assert!(vfs.remove_all_b(&dir1).unwrap().confirm(|_| true).exec().is_ok());
+        assert_vfs_no_exists!(vfs, &dir1);
+
+        // Every entry is asked about with declined directories kept without asking
+        setup();
+        let asked = Arc::new(Mutex::new(vec![]));
+        let seen = asked.clone();
+        let remover = vfs.remove_all_b(&dir1).unwrap().confirm_each(move |x| {
+            seen.lock().unwrap().push(x.path_buf());
+            !x.path().ends_with("dir2") && !x.path().ends_with("file4")
+        });
+        assert!(remover.exec().is_ok());
+        assert_eq!(
+            *asked.lock().unwrap(),
+            vec![dir2.clone(), dir3.clone(), file3.clone(), file4.clone(), file1.clone()]
+        );
+        assert_vfs_is_file!(vfs, &file2);
+        assert_vfs_no_exists!(vfs, &file3);
+        assert_vfs_is_file!(vfs, &file4);
+        assert_vfs_no_exists!(vfs, &file1);
+
+        // Guards are checked before asking and a file path is asked about itself
+        let err = vfs.remove_all_b(&dir1).unwrap().within(&dir2).confirm(|_| panic!()).exec().unwrap_err();
+        assert_eq!(err.downcast_ref::<VfsError>(), Some(&VfsError::RemoveOutsideBase(dir1.clone(), dir2.clone())));
+        assert!(vfs.remove_all_b(&file4).unwrap().confirm(|_| false).exec().is_ok());
+        assert_vfs_is_file!(vfs, &file4);
+        assert!(vfs.remove_all_b(&file4).unwrap().confirm(|_| true).exec().is_ok());
+        assert_vfs_no_exists!(vfs, &file4);
+
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+
+    #[test]
+    fn test_remove_opts_check()
+    {
+        let opts = |path: &str, within: Option<&str>| RemoveOpts {
+            path: PathBuf::from(path),
+            within: within.map(PathBuf::from),
+            same_device: false,
+            confirm: None,
+            each: false,
+        };
+        let check = |opts: RemoveOpts| opts.check(|_| Ok(None));
+        let err = check(opts("/", None)).unwrap_err();
+        assert_eq!(err.downcast_ref::<VfsError>(), Some(&VfsError::RemoveRoot));
+        let err = check(opts("/", Some("/"))).unwrap_err();
+        assert_eq!(err.downcast_ref::<VfsError>(), Some(&VfsError::RemoveRoot));
+        let err = check(opts("/foo", Some("/foobar"))).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<VfsError>(),
+            Some(&VfsError::RemoveOutsideBase(PathBuf::from("/foo"), PathBuf::from("/foobar")))
+        );
+        assert!(check(opts("/foo", Some("/foo"))).is_ok());
+        assert!(check(opts("/foo/bar", Some("/foo"))).is_ok());
+        assert!(check(opts("/foo", None)).is_ok());
+    }
+}
@@ -0,0 +1,187 @@
+// LRU cache of absolute path resolutions for `Stdfs::abs`
+//
+// Resolving a path expands `~` and environment variables and, for relative paths, queries the
+// current working directory which adds up for metadata only workloads like `exists` storms. Each
+// entry records the environment variables its expansion used so that entries are revalidated on
+// every hit rather than served stale after an environment change. The working directory isn't
+// queried on a hit so the cache is cleared by `Stdfs::set_cwd`.
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+};
+
+use lazy_static::lazy_static;
+
+lazy_static! {
+    static ref CACHE: Mutex<AbsCache> = Mutex::new(AbsCache::default());
+}
+
+// Maximum number of cached entries, zero disables caching. Kept outside the lock so that the
+// disabled case costs nothing.
+static CAPACITY: AtomicUsize = AtomicUsize::new(0);
+
+// Cached resolution of a raw path
+struct AbsEntry {
+    abs: PathBuf,                       // resolved absolute path
+    env: Vec<(String, Option<String>)>, // environment variables used and their values
+    stamp: u64,                         // last use for LRU ordering
+}
+
+#[derive(Default)]
+struct AbsCache {
+    entries: HashMap<PathBuf, AbsEntry>, // raw path to its resolution
+    lru: BTreeMap<u64, PathBuf>,         // last use to raw path, oldest first
+    stamp: u64,                          // next use stamp
+}
+
+/// Set the maximum number of cached resolutions, zero disables and clears the cache
+pub(crate) fn set_capacity(max: usize) {
+    let mut cache = CACHE.lock().unwrap();
+    CAPACITY.store(max, Ordering::Relaxed);
+    while cache.entries.len() > max {
+        cache.evict();
+    }
+}
+
+/// Drop all cached resolutions
+pub(crate) fn clear() {
+    let mut cache = CACHE.lock().unwrap();
+    cache.entries.clear();
+    cache.lru.clear();
+}
+
+/// Returns the cached resolution of the given raw path if still valid
+pub(crate) fn get(path: &Path) -> Option<PathBuf> {
+    if CAPACITY.load(Ordering::Relaxed) == 0 {
+        return None;
+    }
+    let mut guard = CACHE.lock().unwrap();
+    let stamp = guard.next_stamp();
+    let cache = &mut *guard;
+    let entry = cache.entries.get_mut(path)?;
+    cache.lru.remove(&entry.stamp);
+    if entry.env.iter().any(|(var, val)| std::env::var(var).ok() != *val) {
+        cache.entries.remove(path);
+        return None;
+    }
+    entry.stamp = stamp;
+    cache.lru.insert(stamp, path.to_path_buf());
+    Some(entry.abs.clone())
+}
+
+/// Cache the resolution of the given raw path evicting the least recently used if full
+pub(crate) fn insert(path: &Path, abs: &Path) {
+    let max = CAPACITY.load(Ordering::Relaxed);
+    if max == 0 {
+        return;
+    }
+    let env = env_vars(path).into_iter().map(|x| (x.clone(), std::env::var(x).ok())).collect();
+    let mut cache = CACHE.lock().unwrap();
+    let stamp = cache.next_stamp();
+    let entry = AbsEntry { abs: abs.to_path_buf(), env, stamp };
+    if let Some(old) = cache.entries.insert(path.to_path_buf(), entry) {
+        cache.lru.remove(&old.stamp);
+    }
+    cache.lru.insert(stamp, path.to_path_buf());
+    while cache.entries.len() > max {
+        cache.evict();
+    }
+}
+
+impl AbsCache {
+    // Returns the next use stamp
+    fn next_stamp(&mut self) -> u64 {
+        self.stamp += 1;
+        self.stamp
+    }
+
+    // Drop the least recently used entry
+    fn evict(&mut self) {
+        if let Some((_, path)) = self.lru.pop_first() {
+            self.entries.remove(&path);
+        }
+    }
+}
+
+// Returns the names of the environment variables the expansion of the given path depends on
+// following the same rules as `sys::expand`
+fn env_vars(path: &Path) -> Vec<String> {
+    let pathstr = path.to_string_lossy();
+    let mut vars = vec![];
+    if pathstr.starts_with('~') {
+        vars.push("HOME".to_string());
+    }
+    for seg in pathstr.split('/') {
+        for x in seg.split('$').skip(1) {
+            let var = x.strip_prefix('{').unwrap_or(x);
+            vars.push(var.split('}').next().unwrap_or_default().to_string());
+        }
+    }
+    vars
+}
+
+// Unit tests
+// -------------------------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::prelude::*;
+
+    #[test]
+    fn test_abs_cache() {
+        // Large enough that paths resolved by tests running in parallel won't evict these
+        let path = Path::new("/$RIVIA_ABS_CACHE/bar");
+        Stdfs::set_abs_cache(10_000);
+
+        // Cached once resolved
+        std::env::set_var("RIVIA_ABS_CACHE", "foo");
+        assert_eq!(get(path), None);
+        assert_eq!(Stdfs::abs(path).unwrap(), PathBuf::from("/foo/bar"));
+        assert_eq!(get(path), Some(PathBuf::from("/foo/bar")));
+
+        // Revalidated when the environment changes
+        std::env::set_var("RIVIA_ABS_CACHE", "baz");
+        assert_eq!(Stdfs::abs(path).unwrap(), PathBuf::from("/baz/bar"));
+        std::env::remove_var("RIVIA_ABS_CACHE");
+        assert!(Stdfs::abs(path).is_err());
+        assert_eq!(get(path), None);
+
+        // Cleared explicitly and by disabling
+        assert_eq!(Stdfs::abs("/foo").unwrap(), PathBuf::from("/foo"));
+        assert_eq!(get(Path::new("/foo")), Some(PathBuf::from("/foo")));
+        Stdfs::clear_abs_cache();
+        assert_eq!(get(Path::new("/foo")), None);
+        assert_eq!(Stdfs::abs("/foo").unwrap(), PathBuf::from("/foo"));
+        Stdfs::set_abs_cache(0);
+        Stdfs::set_abs_cache(10_000);
+        assert_eq!(get(Path::new("/foo")), None);
+        Stdfs::set_abs_cache(0);
+    }
+
+    #[test]
+    fn test_env_vars() {
+        assert_eq!(env_vars(Path::new("/foo/bar")), Vec::<String>::new());
+        assert_eq!(env_vars(Path::new("~/foo")), vec!["HOME"]);
+        assert_eq!(env_vars(Path::new("$HOME/${FOO}bar/$A$B")), vec!["HOME", "FOO", "A", "B"]);
+    }
+
+    #[test]
+    fn test_lru() {
+        let mut cache = AbsCache::default();
+        for x in ["a", "b", "c"] {
+            let stamp = cache.next_stamp();
+            let entry = AbsEntry { abs: PathBuf::from(x), env: vec![], stamp };
+            cache.entries.insert(PathBuf::from(x), entry);
+            cache.lru.insert(stamp, PathBuf::from(x));
+        }
+        cache.evict();
+        assert!(!cache.entries.contains_key(Path::new("a")));
+        assert_eq!(cache.lru.values().collect::<Vec<_>>(), vec![Path::new("b"), Path::new("c")]);
+    }
+}
@@ -0,0 +1,100 @@
+use std::{
+    cmp,
+    fs::File,
+    io::{self, Read, Seek, SeekFrom},
+    os::unix::fs::{FileExt, OpenOptionsExt},
+    path::Path,
+};
+
+use nix::libc;
+
+use super::limit;
+use crate::errors::RvResult;
+
+// Alignment of the buffer, offsets and lengths used with `O_DIRECT`. Covers both 512 byte and 4K
+// logical block sizes.
+const ALIGN: usize = 4096;
+
+// Size of the aligned read buffer
+const BUF_SIZE: usize = 1024 * 1024;
+
+/// Provides a reader for files opened with `O_DIRECT` bypassing the page cache
+///
+/// `O_DIRECT` requires that the buffer, file offset and length of every read be aligned to the
+/// device's logical block size. Reads are done a block aligned window at a time into an internal
+/// aligned buffer and copied out from there so that callers can read and seek as usual.
+pub(crate) struct DirectFile {
+    file: File,    // file opened with `O_DIRECT` when supported
+    buf: Vec<u8>,  // backing storage for the aligned window
+    start: usize,  // offset into `buf` where the aligned window starts
+    offset: u64,   // file offset of the data in the window
+    len: usize,    // length of the data in the window
+    pos: u64,      // current logical position in the file
+}
+
+impl DirectFile {
+    /// Open the given file for reading with `O_DIRECT`
+    ///
+    /// * Falls back on a buffered open when the filesystem rejects `O_DIRECT` with `EINVAL` as
+    ///   older tmpfs and some FUSE filesystems do. Reads still go through the aligned window.
+    pub(crate) fn open(path: &Path) -> RvResult<DirectFile> {
+        let file = limit::retry(path, || match File::options().read(true).custom_flags(libc::O_DIRECT).open(path) {
+            Err(err) if err.raw_os_error() == Some(libc::EINVAL) => File::open(path),
+            result => result,
+        })?;
+        let buf = vec![0; BUF_SIZE + ALIGN];
+        let start = buf.as_ptr().align_offset(ALIGN);
+        Ok(DirectFile { file, buf, start, offset: 0, len: 0, pos: 0 })
+    }
+
+    // Returns true if the current position falls within the buffered window
+    fn buffered(&self) -> bool {
+        self.pos >= self.offset && self.pos < self.offset + self.len as u64
+    }
+
+    // Fill the window with the aligned block containing the current position
+    fn fill(&mut self) -> io::Result<()> {
+        self.offset = self.pos - self.pos % ALIGN as u64;
+        self.len = self.file.read_at(&mut self.buf[self.start..self.start + BUF_SIZE], self.offset)?;
+        Ok(())
+    }
+}
+
+impl Read for DirectFile {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        if !self.buffered() {
+            self.fill()?;
+
+            // End of file
+            if !self.buffered() {
+                return Ok(0);
+            }
+        }
+        let i = (self.pos - self.offset) as usize;
+        let n = cmp::min(buf.len(), self.len - i);
+        let data = &self.buf[self.start + i..self.start + i + n];
+        buf[..n].copy_from_slice(data);
+        self.pos += n as u64;
+        Ok(n)
+    }
+}
+
+impl Seek for DirectFile {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let pos = match pos {
+            SeekFrom::Start(x) => Some(x),
+            SeekFrom::End(x) => self.file.metadata()?.len().checked_add_signed(x),
+            SeekFrom::Current(x) => self.pos.checked_add_signed(x),
+        };
+        match pos {
+            Some(x) => {
+                self.pos = x;
+                Ok(x)
+            },
+            None => Err(io::Error::new(io::ErrorKind::InvalidInput, "invalid seek to a negative position")),
+        }
+    }
+}
@@ -1,16 +1,40 @@
 use std::{
     fmt::Debug,
-    fs,
-    os::unix::fs::PermissionsExt,
+    fs, io,
+    os::unix::fs::{MetadataExt, PermissionsExt},
     path::{Path, PathBuf},
+    time::SystemTime,
 };
 
+use super::limit;
 use crate::{
     errors::*,
-    sys::{Entry, PathExt, Stdfs, VfsEntry},
-    trying,
+    sys::{self, Entry, PathExt, Stdfs, VfsEntry, VfsMetadata},
 };
 
+/// Provides the Stdfs specific metadata of an entry via [`VfsEntry::raw`]
+///
+/// * Describes the final target for links as with the other entry properties
+/// * Useful for hard link and duplicate detection
+///
+/// ### Examples
+/// ```
+/// use rivia::prelude::*;
+///
+/// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_struct_meta");
+/// let file = tmpdir.mash("file");
+/// assert_vfs_mkfile!(vfs, &file);
+/// let meta = *vfs.entry(&file).unwrap().raw::<StdfsMeta>().unwrap();
+/// assert_eq!(meta.nlink, 1);
+/// assert_vfs_remove_all!(vfs, &tmpdir);
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct StdfsMeta {
+    pub dev: u64,   // id of the device containing the entry
+    pub ino: u64,   // inode number of the entry
+    pub nlink: u64, // number of hard links to the entry
+}
+
 /// Provides a Vfs backend [`Entry`] implementation for Stdfs
 ///
 /// ## Features
@@ -38,27 +62,43 @@ use crate::{
 /// behavior is desired checking the `follow` and `is_
 #[derive(Debug, PartialEq, Eq)]
 pub struct StdfsEntry {
-    pub(crate) path: PathBuf, // abs path
-    pub(crate) alt: PathBuf,  // abs path link is pointing to
-    pub(crate) rel: PathBuf,  // relative path link is pointing to
-    pub(crate) dir: bool,     // is this entry a dir
-    pub(crate) file: bool,    // is this entry a file
-    pub(crate) link: bool,    // is this entry a link
-    pub(crate) mode: u32,     // permission mode of the entry
-    pub(crate) follow: bool,  // tracks if the path and alt have been switched
-    pub(crate) cached: bool,  // tracsk if properties have been cached
+    pub(crate) meta: StdfsMeta,      // backend specific metadata
+    pub(crate) path: PathBuf,        // abs path
+    pub(crate) alt: PathBuf,         // abs path link is pointing to
+    pub(crate) rel: PathBuf,         // relative path link is pointing to
+    pub(crate) chain: Vec<PathBuf>,  // abs link targets followed to the final target
+    pub(crate) dir: bool,            // is this entry a dir
+    pub(crate) file: bool,           // is this entry a file
+    pub(crate) link: bool,           // is this entry a link
+    pub(crate) mode: u32,            // permission mode of the entry
+    pub(crate) size: u64,            // size of the file in bytes
+    pub(crate) accessed: SystemTime, // time the entry was last accessed
+    pub(crate) modified: SystemTime, // time the entry was last modified
+    pub(crate) created: SystemTime,  // time the entry was created
+    pub(crate) uid: u32,             // user id of the entry
+    pub(crate) gid: u32,             // group id of the entry
+    pub(crate) follow: bool,         // tracks if the path and alt have been switched
+    pub(crate) cached: bool,         // tracsk if properties have been cached
 }
 
 impl Default for StdfsEntry {
     fn default() -> Self {
         Self {
+            meta: StdfsMeta::default(),
             path: PathBuf::new(),
             alt: PathBuf::new(),
             rel: PathBuf::new(),
+            chain: vec![],
             dir: false,
             file: false,
             link: false,
             mode: 0,
+            size: 0,
+            accessed: SystemTime::UNIX_EPOCH,
+            modified: SystemTime::UNIX_EPOCH,
+            created: SystemTime::UNIX_EPOCH,
+            uid: 0,
+            gid: 0,
             follow: false,
             cached: false,
         }
@@ -68,13 +108,21 @@ impl Default for StdfsEntry {
 impl Clone for StdfsEntry {
     fn clone(&self) -> Self {
         Self {
+            meta: self.meta,
             path: self.path.clone(),
             alt: self.alt.clone(),
             rel: self.rel.clone(),
+            chain: self.chain.clone(),
             dir: self.dir,
             file: self.file,
             link: self.link,
             mode: self.mode,
+            size: self.size,
+            accessed: self.accessed,
+            modified: self.modified,
+            created: self.created,
+            uid: self.uid,
+            gid: self.gid,
             follow: self.follow,
             cached: self.cached,
         }
@@ -86,43 +134,73 @@ impl StdfsEntry {
     ///
     /// * Handles path expansion and absolute path resolution
     /// * Filesystem properties are cached during load
+    /// * Non link entries cost a single `lstat` syscall
     pub(crate) fn from<T: AsRef<Path>>(path: T) -> RvResult<Self> {
         let path = Stdfs::abs(path)?;
-        if !Stdfs::exists(&path) {
-            return Err(PathError::does_not_exist(&path).into());
-        }
+        let mut meta = fs::symlink_metadata(&path).map_err(|_| PathError::does_not_exist(&path))?;
         let mut link = false;
+        let mut chain = vec![];
         let mut alt = PathBuf::new();
         let mut rel = PathBuf::new();
-        let mut meta = fs::symlink_metadata(&path)?;
 
         // Load link information for links
         if meta.file_type().is_symlink() {
             link = true;
-            let target = fs::read_link(&path)?;
 
-            // Ensure target is an absolute path
-            alt = Stdfs::abs(if !target.is_absolute() { path.dir()?.mash(target) } else { target })?;
+            // Resolve the link chain before the target to report looping links correctly
+            chain = StdfsEntry::target_chain(&path)?;
+            alt = chain[0].clone();
 
             // Get the target path relative to the link path if possible
             rel = alt.relative(path.dir()?)?;
 
             // Switch to the link's source metadata
-            meta = fs::metadata(&path)?;
+            meta = fs::metadata(&path).map_err(|_| PathError::does_not_exist(&path))?;
         }
 
+        // Not all filesystems track creation times so fallback on the modified time
+        let modified = meta.modified()?;
+        let created = meta.created().unwrap_or(modified);
+
         Ok(StdfsEntry {
+            meta: StdfsMeta { dev: meta.dev(), ino: meta.ino(), nlink: meta.nlink() },
             path,
             alt,
             rel,
+            chain,
             dir: meta.is_dir(),
             file: meta.is_file(),
             link,
             mode: meta.permissions().mode(),
+            size: if meta.is_file() { meta.len() } else { 0 },
+            accessed: meta.accessed().unwrap_or(modified),
+            modified,
+            created,
+            uid: meta.uid(),
+            gid: meta.gid(),
             follow: false,
             cached: true,
         })
     }
+
+    // Walk the chain of links starting at the given path returning the absolute path of each link
+    // target in order with the final target last.
+    //
+    // ### Errors
+    // * PathError::TooManyLinks(PathBuf) when more than DEFAULT_MAX_LINKS hops are required
+    fn target_chain(path: &Path) -> RvResult<Vec<PathBuf>> {
+        let mut chain: Vec<PathBuf> = vec![];
+        let mut link = path.to_path_buf();
+        while let Ok(target) = fs::read_link(&link) {
+            if chain.len() >= sys::DEFAULT_MAX_LINKS {
+                return Err(PathError::too_many_links(path).into());
+            }
+            let target = Stdfs::abs(if !target.is_absolute() { link.dir()?.mash(target) } else { target })?;
+            chain.push(target.clone());
+            link = target;
+        }
+        Ok(chain)
+    }
 }
 
 impl Entry for StdfsEntry {
@@ -196,6 +274,29 @@ impl Entry for StdfsEntry {
         self.rel.clone()
     }
 
+    /// Returns the names of the directory's immediate children sorted by name
+    ///
+    /// * Reads the directory lazily on each call
+    ///
+    /// ### Errors
+    /// * PathError::IsNotDir(PathBuf) when the entry is not a directory
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    /// ```
+    fn children(&self) -> RvResult<Vec<String>> {
+        if !self.dir {
+            return Err(PathError::is_not_dir(&self.path).into());
+        }
+        let mut names = vec![];
+        for entry in limit::retry(&self.path, || fs::read_dir(&self.path))? {
+            names.push(entry?.path().base()?);
+        }
+        names.sort();
+        Ok(names)
+    }
+
     /// Switch the `path` and `alt` values if `is_symlink` reports true.
     ///
     /// ### Examples
@@ -260,6 +361,68 @@ impl Entry for StdfsEntry {
         self.mode
     }
 
+    /// Reports the time the entry was last modified
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    /// ```
+    fn modified(&self) -> SystemTime {
+        self.modified
+    }
+
+    /// Reports the time the entry was created
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    /// ```
+    fn created(&self) -> SystemTime {
+        self.created
+    }
+
+    /// Reports the size of the file in bytes
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    /// ```
+    fn size(&self) -> u64 {
+        self.size
+    }
+
+    /// Returns the filesystem properties cached when the entry was loaded
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    /// ```
+    fn metadata(&self) -> VfsMetadata {
+        VfsMetadata {
+            dir: self.dir,
+            file: self.file,
+            link: self.link,
+            mode: self.mode,
+            size: self.size,
+            nlink: self.meta.nlink,
+            uid: self.uid,
+            gid: self.gid,
+            accessed: self.accessed,
+            modified: self.modified,
+            created: self.created,
+        }
+    }
+
+    /// Returns the chain of targets followed to resolve a link to its final target
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    /// ```
+    fn target_chain(&self) -> &[PathBuf] {
+        &self.chain
+    }
+
     /// Up cast the trait type to the enum wrapper
     ///
     /// ### Examples
@@ -280,8 +443,14 @@ impl Iterator for StdfsEntryIter {
 
     fn next(&mut self) -> Option<RvResult<VfsEntry>> {
         if let Some(value) = self.dir.next() {
-            return Some(match StdfsEntry::from(trying!(value).path()) {
+            let path = trying!(value).path();
+            return Some(match StdfsEntry::from(&path) {
                 Ok(x) => Ok(x.upcast()),
+
+                // Distinguish entries removed since the directory was read from dangling links
+                Err(_) if fs::symlink_metadata(&path).is_err_and(|x| x.kind() == io::ErrorKind::NotFound) => {
+                    Err(PathError::vanished(path).into())
+                },
                 Err(e) => Err(e),
             });
         }
@@ -0,0 +1,90 @@
+// Recovery for Stdfs operations that fail because the file descriptors ran out
+//
+// Opening a file or directory fails with EMFILE once the process hits its RLIMIT_NOFILE and with
+// ENFILE once the system wide file table is full. Both are retried once after raising the soft
+// limit up to the hard limit when the `rlimit` feature is enabled as changing the limit affects
+// the whole process. Failures are surfaced as `VfsError::DescriptorLimit` rather than an opaque
+// io error so that callers holding descriptors of their own e.g. the traversal's open directories
+// can release them and try again.
+use std::{io, path::Path};
+
+use nix::libc;
+#[cfg(feature = "rlimit")]
+use nix::sys::resource::{self, Resource};
+
+use crate::errors::*;
+
+/// Returns true if the given error indicates that the file descriptors ran out
+pub(crate) fn is_exhausted(err: &io::Error) -> bool {
+    matches!(err.raw_os_error(), Some(libc::EMFILE) | Some(libc::ENFILE))
+}
+
+/// Run the given operation on the given path retrying once after raising the descriptor limit if
+/// it fails due to the file descriptors running out
+pub(crate) fn retry<T, F: FnMut() -> io::Result<T>>(path: &Path, mut f: F) -> RvResult<T> {
+    match f() {
+        Err(err) if is_exhausted(&err) => {
+            if raise() {
+                match f() {
+                    Err(err) if is_exhausted(&err) => {},
+                    result => return Ok(result?),
+                }
+            }
+            Err(VfsError::DescriptorLimit(path.to_path_buf()).into())
+        },
+        result => Ok(result?),
+    }
+}
+
+// Raise the soft descriptor limit to the hard limit returning true if it was raised
+#[cfg(feature = "rlimit")]
+fn raise() -> bool {
+    match resource::getrlimit(Resource::RLIMIT_NOFILE) {
+        Ok((Some(soft), Some(hard))) if soft < hard => {
+            resource::setrlimit(Resource::RLIMIT_NOFILE, Some(hard), Some(hard)).is_ok()
+        },
+        _ => false,
+    }
+}
+
+// Raising the descriptor limit is only done with the `rlimit` feature
+#[cfg(not(feature = "rlimit"))]
+fn raise() -> bool {
+    false
+}
+
+// Unit tests
+// -------------------------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use std::{io, path::Path};
+
+    use nix::libc;
+
+    use super::*;
+
+    #[test]
+    fn test_is_exhausted() {
+        assert!(is_exhausted(&io::Error::from_raw_os_error(libc::EMFILE)));
+        assert!(is_exhausted(&io::Error::from_raw_os_error(libc::ENFILE)));
+        assert!(!is_exhausted(&io::Error::from_raw_os_error(libc::ENOENT)));
+        assert!(!is_exhausted(&io::Error::other("foo")));
+    }
+
+    #[test]
+    fn test_retry() {
+        let path = Path::new("/foo");
+        let fail = |errno| move || -> io::Result<()> { Err(io::Error::from_raw_os_error(errno)) };
+
+        // Success and other errors pass straight through
+        assert_eq!(retry(path, || Ok(1)).unwrap(), 1);
+        let err = retry(path, fail(libc::ENOENT)).unwrap_err();
+        assert_eq!(err.downcast_ref::<io::Error>().unwrap().raw_os_error(), Some(libc::ENOENT));
+
+        // Running out of descriptors is surfaced as a descriptor limit error
+        let err = retry(path, fail(libc::EMFILE)).unwrap_err();
+        assert_eq!(err.downcast_ref::<VfsError>(), Some(&VfsError::DescriptorLimit(path.to_path_buf())));
+        let err = retry(path, fail(libc::ENFILE)).unwrap_err();
+        assert_eq!(err.downcast_ref::<VfsError>(), Some(&VfsError::DescriptorLimit(path.to_path_buf())));
+    }
+}
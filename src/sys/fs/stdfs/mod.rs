@@ -1,37 +1,80 @@
+mod cache;
+mod direct;
 mod entry;
+mod limit;
+mod uring;
 mod vfs;
+mod watch;
 
+use direct::DirectFile;
 pub use entry::*;
+pub(crate) use watch::StdfsWatch;
 
 use std::{
+    collections::HashSet,
+    ffi::CString,
     fs::{self, File},
-    io::{BufRead, BufReader, Write},
-    os::unix::{self, fs::MetadataExt, fs::PermissionsExt},
+    io::{self, BufRead, BufReader, Read, Write},
+    os::unix::{self, ffi::OsStrExt, fs::MetadataExt, fs::OpenOptionsExt, fs::PermissionsExt, io::AsRawFd},
     path::{Component, Path, PathBuf},
-    time::SystemTime,
+    ptr,
+    sync::{Arc, RwLock},
+    time::{Duration, SystemTime},
 };
 
-use nix::sys::{
-    stat::{self, UtimensatFlags},
-    time::TimeSpec,
+use nix::{
+    errno::Errno,
+    fcntl, libc,
+    sys::{
+        stat::{self, UtimensatFlags},
+        statvfs,
+        time::TimeSpec,
+    },
 };
 
 use crate::{
     core::*,
     errors::*,
     sys::{
-        self, Chmod, ChmodOpts, Chown, ChownOpts, Copier, CopyOpts, Entries, Entry, EntryIter, PathExt, ReadSeek,
-        VfsEntry,
+        self, Acl, AclEntry, Advice, Chmod, ChmodOpts, Chown, ChownOpts, Copier, CopyOpts, DirHandle, DiskUsage,
+        DiskUsageOpts, Ensured, Entries, Entry, EntryIter, InstallOpts, Installer, MoveOpts, Mover, OpContext,
+        PathExt, ReadOpts, ReadSeek, ReadWriteSeek, Reader, Reflink, RemoveOpts, Remover, ResolvedLink,
+        VfsConfig, VfsEntry, VfsHooks, VfsMetadata, VfsOp, VfsStatFs, VfsWatcher, VfsWriter, WriteOpts, Writer,
     },
 };
 
+// Extended attribute the kernel exposes SELinux security contexts through
+const XATTR_SELINUX: &str = "security.selinux";
+
+// Inode flag marking a path as immutable i.e. `chattr +i`
+const FS_IMMUTABLE_FL: libc::c_int = 0x10;
+
 /// Provides a wrapper around the `std::fs` module as a [`VirtualFileSystem`] backend implementation
+///
+/// * Operation hooks only apply when called via the [`VirtualFileSystem`] trait not the static functions
+/// * The config likewise only applies via the trait, the static functions use the defaults
 #[derive(Debug, Default)]
-pub struct Stdfs;
+pub struct Stdfs {
+    hooks: RwLock<Arc<VfsHooks>>, // Pre and post operation hooks
+    config: VfsConfig,            // Tunable defaults of the instance
+}
 impl Stdfs {
     /// Create a new instance of the Stdfs Vfs backend implementation
     pub fn new() -> Self {
-        Self
+        Self::default()
+    }
+
+    /// Create a new instance of the Stdfs Vfs backend implementation with the given config
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Stdfs::with_config(VfsConfig { max_descriptors: 10, ..Default::default() });
+    /// assert_eq!(vfs.config().max_descriptors, 10);
+    /// ```
+    pub fn with_config(config: VfsConfig) -> Self {
+        Self { config, ..Default::default() }
     }
 
     /// Return the path in an absolute clean form
@@ -39,6 +82,7 @@ impl Stdfs {
     /// * Handles environment variable expansion
     /// * Relative path resolution for `.` and `..`
     /// * No IO resolution so it will work even with paths that don't exist
+    /// * Served from the resolution cache when enabled see `set_abs_cache`
     ///
     /// ### Errors
     /// * PathError::ParentNotFound(PathBuf) when parent is not found
@@ -52,7 +96,16 @@ impl Stdfs {
     /// ```
     pub fn abs<T: AsRef<Path>>(path: T) -> RvResult<PathBuf> {
         let path = path.as_ref();
+        if let Some(abs) = cache::get(path) {
+            return Ok(abs);
+        }
+        let abs = Stdfs::_abs(path)?;
+        cache::insert(path, &abs);
+        Ok(abs)
+    }
 
+    // Resolve the absolute path for the given path without the cache
+    fn _abs(path: &Path) -> RvResult<PathBuf> {
         // Check for empty string
         if sys::is_empty(path) {
             return Err(PathError::Empty.into());
@@ -91,6 +144,46 @@ impl Stdfs {
         Ok(path_buf)
     }
 
+    /// Advise the kernel how the given file's data will be accessed
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Uses `posix_fadvise` on the whole file e.g. `Advice::DontNeed` drops the file from
+    ///   the page cache so that streaming scanners don't pollute it
+    /// * `Advice::Sequential` and `Advice::Random` only apply to the handle they are given on Linux
+    ///   so use `Reader::advise` for those instead
+    ///
+    /// ### Errors
+    /// * PathError::IsNotFile(PathBuf) when the given path isn't a file
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_func_advise");
+    /// let file = tmpdir.mash("file");
+    /// assert_vfs_write_all!(vfs, &file, "this is a test");
+    /// assert!(Stdfs::advise(&file, Advice::DontNeed).is_ok());
+    /// assert_vfs_read_all!(vfs, &file, "this is a test");
+    /// assert_vfs_remove_all!(vfs, &tmpdir);
+    /// ```
+    pub fn advise<T: AsRef<Path>>(path: T, advice: Advice) -> RvResult<()> {
+        let path = Stdfs::abs(path)?;
+
+        // Validate target exists and is a file
+        if Stdfs::exists(&path) {
+            if !Stdfs::is_file(&path) {
+                return Err(PathError::is_not_file(&path).into());
+            }
+        } else {
+            return Err(PathError::does_not_exist(&path).into());
+        }
+
+        let f = File::open(&path)?;
+        fcntl::posix_fadvise(f.as_raw_fd(), 0, 0, advice.into())?;
+        Ok(())
+    }
+
     /// Returns all dirs for the given path recursively
     ///
     /// * Results are sorted by filename, are distict and don't include the given path
@@ -109,16 +202,11 @@ impl Stdfs {
     /// assert_vfs_remove_all!(vfs, &tmpdir);
     /// ```
     pub fn all_dirs<T: AsRef<Path>>(path: T) -> RvResult<Vec<PathBuf>> {
-        let mut paths: Vec<PathBuf> = vec![];
         let src = StdfsEntry::from(path)?;
         if !src.is_dir() {
             return Err(PathError::is_not_dir(src.path_buf()).into());
         }
-        for entry in Stdfs::entries(src.path())?.min_depth(1).sort_by_name().dirs() {
-            let entry = entry?;
-            paths.push(entry.path_buf());
-        }
-        Ok(paths)
+        Stdfs::entries(src.path())?.min_depth(1).sort_by_name().collect_dirs()
     }
 
     /// Returns all files for the given path recursively
@@ -142,16 +230,11 @@ impl Stdfs {
     /// assert_vfs_remove_all!(vfs, &tmpdir);
     /// ```
     pub fn all_files<T: AsRef<Path>>(path: T) -> RvResult<Vec<PathBuf>> {
-        let mut paths: Vec<PathBuf> = vec![];
         let src = StdfsEntry::from(path)?;
         if !src.is_dir() {
             return Err(PathError::is_not_dir(src.path_buf()).into());
         }
-        for entry in Stdfs::entries(src.path())?.min_depth(1).sort_by_name().files() {
-            let entry = entry?;
-            paths.push(entry.path_buf());
-        }
-        Ok(paths)
+        Stdfs::entries(src.path())?.min_depth(1).sort_by_name().collect_files()
     }
 
     /// Returns all paths for the given path recursively
@@ -177,22 +260,53 @@ impl Stdfs {
     /// assert_vfs_remove_all!(vfs, &tmpdir);
     /// ```
     pub fn all_paths<T: AsRef<Path>>(path: T) -> RvResult<Vec<PathBuf>> {
-        let mut paths: Vec<PathBuf> = vec![];
         let src = StdfsEntry::from(path)?;
         if !src.is_dir() {
             return Err(PathError::is_not_dir(src.path_buf()).into());
         }
-        for entry in Stdfs::entries(src.path())?.min_depth(1).sort_by_name() {
-            let entry = entry?;
-            paths.push(entry.path_buf());
+        Stdfs::entries(src.path())?.min_depth(1).sort_by_name().collect_paths()
+    }
+
+    /// Allocate space for the given file up front to avoid fragmentation and fail early when there
+    /// isn't enough space
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Creates the file if it doesn't exist
+    /// * Grows the file to `len` bytes filled with zeros if smaller, existing data is left intact
+    ///
+    /// ### Errors
+    /// * PathError::IsNotDir(PathBuf) when the given path's parent exists but is not a directory
+    /// * PathError::DoesNotExist(PathBuf) when the given path's parent doesn't exist
+    /// * PathError::IsNotFile(PathBuf) when the given path exists but is not a file
+    /// * Errno::ENOSPC when there isn't enough space on the device
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_func_allocate");
+    /// let file = tmpdir.mash("file");
+    /// assert!(Stdfs::allocate(&file, 1024).is_ok());
+    /// assert_eq!(vfs.entry(&file).unwrap().size(), 1024);
+    /// assert_vfs_remove_all!(vfs, &tmpdir);
+    /// ```
+    pub fn allocate<T: AsRef<Path>>(path: T, len: u64) -> RvResult<()> {
+        // Ensure the file exists as the std functions don't do that
+        Stdfs::mkfile(&path)?;
+
+        // Zero length is invalid for posix_fallocate and there is nothing to allocate anyway
+        let f = File::options().write(true).open(Stdfs::abs(path)?)?;
+        if len > 0 {
+            fcntl::posix_fallocate(f.as_raw_fd(), 0, len as libc::off_t)?;
         }
-        Ok(paths)
+        Ok(())
     }
 
     /// Opens a file in append mode
     ///
     /// * Handles path expansion and absolute path resolution
     /// * Creates a file if it does not exist or appends to it if it does
+    /// * Returns a [`VfsWriter`] reporting the path and number of bytes written
     ///
     /// ### Errors
     /// * PathError::IsNotDir(PathBuf) when the given path's parent exists but is not a directory
@@ -214,11 +328,12 @@ impl Stdfs {
     /// assert_vfs_read_all!(vfs, &file, "foobar123");
     /// assert_vfs_remove_all!(vfs, &tmpdir);
     /// ```
-    pub fn append<T: AsRef<Path>>(path: T) -> RvResult<Box<dyn Write>> {
+    pub fn append<T: AsRef<Path>>(path: T) -> RvResult<VfsWriter> {
         // Ensure the file exists as the std functions don't do that
         Stdfs::mkfile(&path)?;
 
-        Ok(Box::new(File::options().append(true).open(Stdfs::abs(path)?)?))
+        let path = Stdfs::abs(path)?;
+        Ok(VfsWriter::stdfs(&path, limit::retry(&path, || File::options().append(true).open(&path))?))
     }
 
     /// Append the given data to to the target file
@@ -369,8 +484,12 @@ impl Stdfs {
                 dirs: 0,
                 files: 0,
                 follow: false,
+                links: false,
                 recursive: true,
                 sym: "".to_string(),
+                umask: 0,
+                excludes: vec![],
+                cont: false,
             },
             exec: Box::new(Stdfs::_chmod),
         })
@@ -394,7 +513,10 @@ impl Stdfs {
         // directory traversal that otherwise wouldn't be allowed.
         let m = opts.clone();
         entries = entries.follow(opts.follow).dirs_first().pre_op(move |x| {
-            let m1 = sys::mode(x, m.dirs, &m.sym)?;
+            if m.excluded(x) {
+                return Ok(());
+            }
+            let m1 = sys::umask_mode(x.mode(), sys::mode(x, m.dirs, &m.sym)?, m.umask);
             if (!x.is_symlink() || m.follow) && x.is_dir() && !sys::revoking_mode(x.mode(), m1) && x.mode() != m1 {
                 fs::set_permissions(x.path(), fs::Permissions::from_mode(m1))?;
             }
@@ -402,8 +524,14 @@ impl Stdfs {
         });
 
         // Set permissions on the way out for everything specified
-        for entry in entries {
-            let src = entry?;
+        let apply = |mut src: VfsEntry| -> RvResult<()> {
+            // Links don't carry permissions of their own so target them when requested
+            if src.is_symlink() && opts.links {
+                match src.target_chain().last().map(Stdfs::entry) {
+                    Some(Ok(target)) => src = target,
+                    _ => return Ok(()),
+                }
+            }
 
             // Compute mode based on octal and symbolic values
             let m2 = if src.is_dir() {
@@ -413,14 +541,28 @@ impl Stdfs {
             } else {
                 0
             };
+            let m2 = sys::umask_mode(src.mode(), m2, opts.umask);
 
             // Apply permission to entry if set
             if (!src.is_symlink() || opts.follow) && m2 != src.mode() && m2 != 0 {
                 fs::set_permissions(src.path(), fs::Permissions::from_mode(m2))?;
             }
-        }
+            Ok(())
+        };
 
-        Ok(())
+        let mut batch = Batch::new(opts.cont);
+        for entry in entries {
+            let src = match batch.check(&opts.path, entry)? {
+                Some(x) => x,
+                None => continue,
+            };
+            if opts.excluded(&src) {
+                continue;
+            }
+            let path = src.path().to_owned();
+            batch.check(path, apply(src))?;
+        }
+        batch.finish()
     }
 
     /// Change the ownership of the path recursivly
@@ -466,7 +608,10 @@ impl Stdfs {
                 uid: None,
                 gid: None,
                 follow: false,
+                links: false,
                 recursive: true,
+                excludes: vec![],
+                cont: false,
             },
             exec: Box::new(Stdfs::_chown),
         })
@@ -474,14 +619,36 @@ impl Stdfs {
 
     // Execute chown with the given [`Chown`] options
     fn _chown(opts: ChownOpts) -> RvResult<()> {
+        let uid = opts.uid.map(nix::unistd::Uid::from_raw);
+        let gid = opts.gid.map(nix::unistd::Gid::from_raw);
+        let apply = |src: &VfsEntry| -> RvResult<()> {
+            // Links are changed themselves when requested and their targets only when following
+            if src.is_symlink() {
+                if opts.links {
+                    let link = if src.following() { src.alt() } else { src.path() };
+                    nix::unistd::fchownat(None, link, uid, gid, nix::unistd::FchownatFlags::NoFollowSymlink)?;
+                }
+                if !src.following() {
+                    return Ok(());
+                }
+            }
+            nix::unistd::chown(src.path(), uid, gid)?;
+            Ok(())
+        };
+
+        let mut batch = Batch::new(opts.cont);
         let max_depth = if opts.recursive { usize::MAX } else { 0 };
         for entry in Stdfs::entries(&opts.path)?.max_depth(max_depth).follow(opts.follow) {
-            let src = entry?;
-            let uid = opts.uid.map(nix::unistd::Uid::from_raw);
-            let gid = opts.gid.map(nix::unistd::Gid::from_raw);
-            nix::unistd::chown(src.path(), uid, gid)?;
+            let src = match batch.check(&opts.path, entry)? {
+                Some(x) => x,
+                None => continue,
+            };
+            if opts.excluded(&src) {
+                continue;
+            }
+            batch.check(src.path(), apply(&src))?;
         }
-        Ok(())
+        batch.finish()
     }
 
     /// Returns the highest priority active configuration directory.
@@ -521,6 +688,21 @@ impl Stdfs {
         None
     }
 
+    /// Drop all cached `abs` resolutions
+    ///
+    /// * Required after changing the working directory by means other than `set_cwd` when the
+    ///   cache is enabled see `set_abs_cache`
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// Stdfs::clear_abs_cache();
+    /// ```
+    pub fn clear_abs_cache() {
+        cache::clear();
+    }
+
     /// Copies src to dst recursively
     ///
     /// * `dst` will be copied into if it is an existing directory
@@ -576,6 +758,17 @@ impl Stdfs {
                 cdirs: Default::default(),
                 cfiles: Default::default(),
                 follow: Default::default(),
+                link: Default::default(),
+                reflink: Default::default(),
+                rewrite: Default::default(),
+                deref: Default::default(),
+                cont: Default::default(),
+                ctx: Default::default(),
+                flatten: Default::default(),
+                strip: Default::default(),
+                context: Default::default(),
+                progress: Default::default(),
+                report: Default::default(),
             },
             exec: Box::new(Stdfs::_copy),
         })
@@ -587,8 +780,9 @@ impl Stdfs {
         let src_root = Stdfs::abs(&cp.src)?;
         let dst_root = Stdfs::abs(&cp.dst)?;
 
-        // Detect source is destination
-        if src_root == dst_root {
+        // Detect source is destination including aliases through links
+        if src_root == dst_root || Stdfs::same_file(&src_root, &dst_root) {
+            cp.record(|x| x.skipped.push(src_root));
             return Ok(());
         }
 
@@ -607,21 +801,66 @@ impl Stdfs {
 
         // Iterate over source taking into account link following
         let src_root = StdfsEntry::from(&src_root)?.follow(cp.follow);
-        for entry in Stdfs::entries(src_root.path())?.follow(cp.follow) {
-            let src = entry?;
 
-            // Set destination path based on source path
-            let dst_path = if copy_into {
-                dst_root.mash(src.path().trim_prefix(src_root.path().dir()?))
-            } else {
-                dst_root.mash(src.path().trim_prefix(src_root.path()))
+        // Map source paths to their destination paths
+        let dst_of = |path: &Path| -> RvResult<Option<PathBuf>> {
+            let base = if copy_into { src_root.path().dir()? } else { src_root.path_buf() };
+            Ok(cp.dst_of(&dst_root, &path.trim_prefix(base)))
+        };
+
+        // Sum up the bytes to copy up front so progress can be reported against a total
+        if let Some(progress) = &cp.progress {
+            for src in Stdfs::entries(src_root.path())?.follow(cp.follow).into_iter().flatten() {
+                if (cp.follow || !src.is_symlink()) && !src.is_dir() && dst_of(src.path())?.is_some() {
+                    progress.add_total(src.size());
+                }
+            }
+        }
+
+        let apply = |src: &VfsEntry| -> RvResult<()> {
+            // Set destination path based on source path skipping stripped entries
+            let dst_path = match dst_of(src.path())? {
+                Some(x) => x,
+                None => {
+                    cp.record(|x| x.skipped.push(src.path_buf()));
+                    return Ok(());
+                },
+            };
+
+            // Optionally carry over the security context of the source
+            let preserve = |dst: &Path| -> RvResult<()> {
+                if cp.context {
+                    if let Some(context) = Stdfs::_get_selinux_context(src.path())? {
+                        Stdfs::_set_selinux_context(dst, &context)?;
+                    }
+                }
+                Ok(())
             };
 
             // Recreate links if were not following them
             if !cp.follow && src.is_symlink() {
-                Stdfs::symlink(dst_path, src.alt())?;
+                let target = src.alt();
+                let inside = target.starts_with(src_root.path());
+                if cp.rewrite && inside {
+                    Stdfs::symlink(&dst_path, dst_of(target)?.unwrap_or_else(|| target.to_owned()))?;
+                    preserve(&dst_path)?;
+                    cp.record(|x| x.symlinks.push(dst_path));
+                } else if cp.deref && !inside && Stdfs::exists(target) {
+                    Stdfs::_copy(sys::CopyOpts { src: target.to_owned(), dst: dst_path, strip: 0, ..cp.clone() })?;
+                } else {
+                    Stdfs::symlink(&dst_path, target)?;
+                    preserve(&dst_path)?;
+                    cp.record(|x| x.symlinks.push(dst_path));
+                }
             } else if src.is_dir() {
-                Stdfs::mkdir_m(&dst_path, dir_mode.unwrap_or(src.mode()))?;
+                // Flattening skips all directories but the destination itself
+                if !cp.flatten || dst_path == dst_root {
+                    Stdfs::mkdir_m(&dst_path, dir_mode.unwrap_or(src.mode()))?;
+                    preserve(&dst_path)?;
+                    cp.record(|x| x.dirs.push(dst_path));
+                } else {
+                    cp.record(|x| x.skipped.push(src.path_buf()));
+                }
             } else {
                 // Copying into a directory might require creating it first
                 if !Stdfs::exists(&dst_path.dir()?) {
@@ -632,18 +871,135 @@ impl Stdfs {
                             None => StdfsEntry::from(src.path().dir()?)?.mode(),
                         },
                     )?;
+                    cp.record(|x| x.dirs.push(dst_path.dir().unwrap_or_default()));
                 }
 
-                // Copy over the file/link
-                fs::copy(src.path(), &dst_path)?;
+                // Hard link the file when directed falling back on a copy across devices. Linked
+                // files share the source's metadata so the mode and context are left alone.
+                if cp.link && Stdfs::_copy_link(src.path(), &dst_path)? {
+                    cp.record(|x| x.linked.push(dst_path.clone()));
+                } else {
+                    // Clone the data when directed falling back on a copy for `Reflink::Auto`
+                    let cloned = match cp.reflink {
+                        Reflink::Never => false,
+                        _ => Stdfs::_copy_reflink(src.path(), &dst_path, cp.reflink == Reflink::Always)?,
+                    };
+
+                    // Copy over the file/link
+                    match &cp.ctx {
+                        Some(ctx) if cloned => ctx.add_bytes(src.size()),
+                        Some(ctx) => Stdfs::_copy_chunked(src.path(), &dst_path, ctx)?,
+                        None if cloned => {},
+                        None => {
+                            if !uring::copy(src.path(), &dst_path)? {
+                                fs::copy(src.path(), &dst_path)?;
+                            }
+                        },
+                    }
+
+                    // Optionally set new mode
+                    if let Some(mode) = file_mode {
+                        fs::set_permissions(&dst_path, fs::Permissions::from_mode(mode))?;
+                    }
+                    preserve(&dst_path)?;
+                    cp.record(|x| {
+                        match cloned {
+                            true => x.cloned.push(dst_path),
+                            false => x.copied.push(dst_path),
+                        }
+                        x.bytes += src.size();
+                    });
+                }
+                if let Some(progress) = &cp.progress {
+                    progress.add_file(src.path(), src.size());
+                }
+            }
+            Ok(())
+        };
 
-                // Optionally set new mode
-                if let Some(mode) = file_mode {
-                    fs::set_permissions(&dst_path, fs::Permissions::from_mode(mode))?;
+        let mut batch = Batch::new(cp.cont);
+        for entry in Stdfs::entries(src_root.path())?.follow(cp.follow) {
+            // Cancellation aborts the copy even when continuing on error
+            if let Some(ctx) = &cp.ctx {
+                ctx.check()?;
+            }
+            let src = match batch.check(src_root.path(), entry)? {
+                Some(x) => x,
+                None => continue,
+            };
+            if batch.check(src.path(), apply(&src))?.is_some() {
+                if let Some(ctx) = &cp.ctx {
+                    ctx.add_entry(src.path());
                 }
             }
         }
+        batch.finish()
+    }
+
+    // Hard link the destination to the source file replacing any existing destination file. Returns
+    // false without linking when the two are on different devices.
+    fn _copy_link(src: &Path, dst: &Path) -> RvResult<bool> {
+        if fs::symlink_metadata(dst).is_ok() {
+            fs::remove_file(dst)?;
+        }
+        match fs::hard_link(src, dst) {
+            Ok(_) => Ok(true),
+            Err(e) if e.raw_os_error() == Some(libc::EXDEV) => Ok(false),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    // Clone the data of the source file into the destination sharing its extents on filesystems with
+    // copy on write support. Returns false without cloning when unsupported unless it is required.
+    fn _copy_reflink(src: &Path, dst: &Path, required: bool) -> RvResult<bool> {
+        let reader = limit::retry(src, || File::open(src))?;
+        let writer = limit::retry(dst, || File::create(dst))?;
+        let res = unsafe { libc::ioctl(writer.as_raw_fd(), libc::FICLONE, reader.as_raw_fd()) };
+        if let Err(err) = Errno::result(res) {
+            let unsupported =
+                matches!(err, Errno::EOPNOTSUPP | Errno::EXDEV | Errno::EINVAL | Errno::ENOTTY | Errno::ENOSYS);
+            if unsupported && !required {
+                return Ok(false);
+            }
+
+            // Don't leave the empty destination file behind
+            drop(writer);
+            fs::remove_file(dst)?;
+            return match unsupported {
+                true => Err(VfsError::ReflinkUnsupported(src.to_path_buf()).into()),
+                false => Err(err.into()),
+            };
+        }
+
+        // Match `fs::copy` which carries over the permissions
+        fs::set_permissions(dst, reader.metadata()?.permissions())?;
+        Ok(true)
+    }
+
+    // Copy the file in chunks checking the given context for cancellation and counting the bytes
+    // between chunks. Cancelling removes the partially written destination file.
+    fn _copy_chunked(src: &Path, dst: &Path, ctx: &OpContext) -> RvResult<()> {
+        const CHUNK_SIZE: usize = 64 * 1024;
+
+        let mut reader = limit::retry(src, || File::open(src))?;
+        let mut writer = limit::retry(dst, || File::create(dst))?;
+        let mut buf = vec![0; CHUNK_SIZE];
+        loop {
+            if let Err(err) = ctx.check() {
+                drop(writer);
+                fs::remove_file(dst)?;
+                return Err(err);
+            }
+            let n = reader.read(&mut buf)?;
+            if n == 0 {
+                break;
+            }
+            writer.write_all(&buf[..n])?;
+            ctx.add_bytes(n as u64);
+        }
 
+        // Match `fs::copy` which carries over the permissions
+        fs::set_permissions(dst, reader.metadata()?.permissions())?;
         Ok(())
     }
 
@@ -686,15 +1042,56 @@ impl Stdfs {
     /// assert_vfs_remove_all!(vfs, &tmpdir);
     /// ```
     pub fn dirs<T: AsRef<Path>>(path: T) -> RvResult<Vec<PathBuf>> {
-        let mut paths: Vec<PathBuf> = vec![];
         if !Stdfs::is_dir(&path) {
             return Err(PathError::is_not_dir(&path).into());
         }
-        for entry in Stdfs::entries(path)?.min_depth(1).max_depth(1).sort_by_name().dirs() {
+        Stdfs::entries(path)?.min_depth(1).max_depth(1).sort_by_name().collect_dirs()
+    }
+
+    /// Creates a new [`DiskUsage`] for summing up the size of the given path much like `du`
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Recursively sums the apparent size of the files in the tree counting hard links once
+    /// * Options for excluding globs and following links
+    /// * Execute by calling `exec`
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_func_disk_usage");
+    /// let file1 = tmpdir.mash("file1");
+    /// let file2 = tmpdir.mash("dir/file2");
+    /// assert_vfs_mkdir_p!(vfs, tmpdir.mash("dir"));
+    /// assert_vfs_write_all!(vfs, &file1, "foo");
+    /// assert_vfs_write_all!(vfs, &file2, "foobar");
+    /// assert_eq!(Stdfs::disk_usage(&tmpdir).unwrap().exec().unwrap(), 9);
+    /// assert_vfs_remove_all!(vfs, &tmpdir);
+    /// ```
+    pub fn disk_usage<T: AsRef<Path>>(path: T) -> RvResult<DiskUsage> {
+        Ok(DiskUsage {
+            opts: DiskUsageOpts { path: Stdfs::abs(path)?, follow: false, excludes: vec![] },
+            exec: Box::new(Stdfs::_disk_usage),
+        })
+    }
+
+    // Execute disk_usage with the given [`DiskUsage`] options
+    fn _disk_usage(opts: DiskUsageOpts) -> RvResult<u64> {
+        let mut inodes = HashSet::new();
+        let mut total = 0;
+        for entry in Stdfs::entries(&opts.path)?.follow(opts.follow) {
             let entry = entry?;
-            paths.push(entry.path_buf());
+            if !entry.is_file() || (entry.is_symlink() && !entry.following()) || opts.excluded(&entry) {
+                continue;
+            }
+
+            // Hard linked files share the same inode and are only counted once
+            let meta = fs::metadata(entry.path())?;
+            if inodes.insert((meta.dev(), meta.ino())) {
+                total += meta.len();
+            }
         }
-        Ok(paths)
+        Ok(total)
     }
 
     /// Returns an iterator over the given path
@@ -718,17 +1115,37 @@ impl Stdfs {
             root: StdfsEntry::from(path)?.upcast(),
             dirs: Default::default(),
             files: Default::default(),
+            symlinks: Default::default(),
             follow: false,
+            skip_vanished: true,
             min_depth: 0,
             max_depth: usize::MAX,
+            max_links: sys::DEFAULT_MAX_LINKS,
+            max_items: usize::MAX,
+            timeout: None,
+            ctx: None,
+            modified_after: None,
+            modified_before: None,
+            created_after: None,
+            larger_than: None,
+            smaller_than: None,
+            #[cfg(feature = "regex")]
+            contents_match: None,
+            glob: None,
+            includes: vec![],
+            excludes: vec![],
+            max_content_size: sys::DEFAULT_MAX_CONTENT_SIZE,
             max_descriptors: sys::DEFAULT_MAX_DESCRIPTORS,
+            parallel: 1,
             dirs_first: false,
             files_first: false,
             contents_first: false,
             sort_by_name: false,
             pre_op: None,
             sort: None,
-            iter_from: Box::new(Stdfs::entry_iter),
+            iter_from: Arc::new(Stdfs::entry_iter),
+            #[cfg(feature = "regex")]
+            read_from: Box::new(|path: &Path| Stdfs::read(path)),
         })
     }
 
@@ -748,15 +1165,168 @@ impl Stdfs {
         Ok(StdfsEntry::from(path)?.upcast())
     }
 
+    /// Ensures the given directory exists with the given mode and ownership
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Creates the directory and any parent directories needed if it doesn't exist
+    /// * Only changes the mode and ownership when they differ from those given
+    /// * Links are followed so the directories they point to are affected
+    /// * Returns true if anything was changed
+    ///
+    /// ### Errors
+    /// * PathError::IsNotDir(PathBuf) when the given path exists but is not a directory
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_func_ensure_dir");
+    /// let dir = tmpdir.mash("dir");
+    /// let (uid, gid) = vfs.owner(&tmpdir).unwrap();
+    /// assert_eq!(Stdfs::ensure_dir(&dir, 0o700, uid, gid).unwrap(), true);
+    /// assert_eq!(Stdfs::ensure_dir(&dir, 0o700, uid, gid).unwrap(), false);
+    /// assert_eq!(vfs.mode(&dir).unwrap(), 0o40700);
+    /// assert_vfs_remove_all!(vfs, &tmpdir);
+    /// ```
+    pub fn ensure_dir<T: AsRef<Path>>(path: T, mode: u32, uid: u32, gid: u32) -> RvResult<bool> {
+        let path = Stdfs::abs(path)?;
+        let mut changed = false;
+        if !Stdfs::exists(&path) {
+            Stdfs::mkdir_p(&path)?;
+            changed = true;
+        } else if !Stdfs::is_dir(&path) {
+            return Err(PathError::is_not_dir(&path).into());
+        }
+        if Stdfs::mode(&path)? & 0o7777 != mode & 0o7777 {
+            Stdfs::chmod_b(&path)?.follow().no_recurse().mode_str(&format!("{:o}", mode & 0o7777)).exec()?;
+            changed = true;
+        }
+        if Stdfs::owner(&path)? != (uid, gid) {
+            Stdfs::chown_b(&path)?.follow().recurse(false).owner(uid, gid).exec()?;
+            changed = true;
+        }
+        Ok(changed)
+    }
+
+    /// Ensures the given file exists with the given data and mode
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Only writes the data when it differs from the file's current content
+    /// * Only changes the mode when it differs from the one given
+    /// * Links are followed so the files they point to are affected
+    /// * Returns true if anything was changed
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path's parent doesn't exist
+    /// * PathError::IsNotFile(PathBuf) when the given path exists but is not a file
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_func_ensure_file");
+    /// let file = tmpdir.mash("file");
+    /// assert_eq!(Stdfs::ensure_file(&file, "foo", 0o600).unwrap(), true);
+    /// assert_eq!(Stdfs::ensure_file(&file, "foo", 0o600).unwrap(), false);
+    /// assert_vfs_read_all!(vfs, &file, "foo");
+    /// assert_eq!(vfs.mode(&file).unwrap(), 0o100600);
+    /// assert_vfs_remove_all!(vfs, &tmpdir);
+    /// ```
+    pub fn ensure_file<T: AsRef<Path>, U: AsRef<[u8]>>(path: T, data: U, mode: u32) -> RvResult<bool> {
+        // Links are resolved up front so that the checks and writes all apply to the final target
+        let path = Stdfs::resolve_link(path, sys::DEFAULT_MAX_LINKS)?.target;
+        let data = data.as_ref();
+        let mut changed = false;
+        let exists = Stdfs::exists(&path);
+        if exists && !Stdfs::is_file(&path) {
+            return Err(PathError::is_not_file(&path).into());
+        }
+
+        // Only write the data when it differs from the current content
+        let mut current = vec![];
+        if exists {
+            Stdfs::read(&path)?.read_to_end(&mut current)?;
+        }
+        if !exists || current != data {
+            Stdfs::write_all(&path, data)?;
+            changed = true;
+        }
+        if Stdfs::mode(&path)? & 0o7777 != mode & 0o7777 {
+            Stdfs::chmod_b(&path)?.follow().no_recurse().mode_str(&format!("{:o}", mode & 0o7777)).exec()?;
+            changed = true;
+        }
+        Ok(changed)
+    }
+
+    /// Ensures the given link exists and points to the given target
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Creates the link if it doesn't exist
+    /// * Replaces the link atomically if it points somewhere else
+    /// * Dangling links are supported
+    /// * Returns true if anything was changed
+    ///
+    /// ### Errors
+    /// * PathError::IsNotSymlink(PathBuf) when the given link path exists but is not a link
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_func_ensure_symlink");
+    /// let link = tmpdir.mash("link");
+    /// assert_eq!(Stdfs::ensure_symlink(&link, "file1").unwrap(), true);
+    /// assert_eq!(Stdfs::ensure_symlink(&link, "file1").unwrap(), false);
+    /// assert_eq!(Stdfs::ensure_symlink(&link, "file2").unwrap(), true);
+    /// assert_eq!(Stdfs::readlink(&link).unwrap(), PathBuf::from("file2"));
+    /// assert_vfs_remove_all!(vfs, &tmpdir);
+    /// ```
+    pub fn ensure_symlink<T: AsRef<Path>, U: AsRef<Path>>(link: T, target: U) -> RvResult<bool> {
+        let link = Stdfs::abs(link)?;
+        let target = target.as_ref();
+        match Stdfs::symlink_ensured(&link, target) {
+            Ok(x) => Ok(x.created()),
+            Err(err) => {
+                // Only links are replaced never anything else, checked directly to include dangling links
+                match fs::symlink_metadata(&link) {
+                    Ok(meta) if !meta.file_type().is_symlink() => {
+                        return Err(PathError::is_not_symlink(&link).into());
+                    },
+                    Err(_) => return Err(err),
+                    _ => {},
+                }
+
+                // Create the new link beside the old one and rename it over the top of it
+                let tmp = link.dir()?.mash(format!(".{}.rivia-link", link.base()?));
+                if fs::symlink_metadata(&tmp).is_ok() {
+                    fs::remove_file(&tmp)?;
+                }
+                let target = if !target.is_absolute() { link.dir()?.mash(target) } else { target.to_path_buf() };
+                Stdfs::symlink(&tmp, target)?;
+                if let Err(err) = fs::rename(&tmp, &link) {
+                    let _ = fs::remove_file(&tmp);
+                    return Err(err.into());
+                }
+                Ok(true)
+            },
+        }
+    }
+
     /// Return a EntryIter function
+    ///
+    /// * Directories removed before they could be opened are reported as `PathError::Vanished`
     pub(crate) fn entry_iter(path: &Path, follow: bool) -> RvResult<EntryIter> {
+        let dir = match limit::retry(path, || fs::read_dir(path)) {
+            Err(err) if err.downcast_ref::<io::Error>().is_some_and(|x| x.kind() == io::ErrorKind::NotFound) => {
+                return Err(PathError::vanished(path).into());
+            },
+            result => result?,
+        };
         Ok(EntryIter {
             path: path.to_path_buf(),
             cached: false,
             following: follow,
-            iter: Box::new(StdfsEntryIter {
-                dir: fs::read_dir(path)?,
-            }),
+            iter: Box::new(StdfsEntryIter { dir }),
         })
     }
 
@@ -781,48 +1351,318 @@ impl Stdfs {
     /// assert_vfs_remove_all!(vfs, &tmpdir);
     /// ```
     pub fn files<T: AsRef<Path>>(path: T) -> RvResult<Vec<PathBuf>> {
-        let mut paths: Vec<PathBuf> = vec![];
         if !Stdfs::is_dir(&path) {
             return Err(PathError::is_not_dir(&path).into());
         }
-        for entry in Stdfs::entries(path)?.min_depth(1).max_depth(1).sort_by_name().files() {
-            let entry = entry?;
-            paths.push(entry.path_buf());
-        }
-        Ok(paths)
+        Stdfs::entries(path)?.min_depth(1).max_depth(1).sort_by_name().collect_files()
     }
 
-    /// Returns the group ID of the owner of this file
+    /// Returns the absolute path of the first of the given `paths` that exists
     ///
     /// * Handles path expansion and absolute path resolution
+    /// * Checks all paths in a single pass stopping at the first that exists
     ///
     /// ### Examples
     /// ```
     /// use rivia::prelude::*;
     ///
-    /// let vfs = Vfs::stdfs();
-    /// assert_eq!(Stdfs::gid(vfs.root()).unwrap(), 0);
+    /// assert_eq!(Stdfs::first_existing(&["/foo/bar", "/etc"]), Some(PathBuf::from("/etc")));
     /// ```
-    pub fn gid<T: AsRef<Path>>(path: T) -> RvResult<u32> {
-        Ok(fs::metadata(Stdfs::abs(path)?)?.gid())
+    pub fn first_existing<T: AsRef<Path>>(paths: &[T]) -> Option<PathBuf> {
+        paths.iter().filter_map(|x| Stdfs::abs(x).ok()).find(|x| fs::metadata(x).is_ok())
     }
 
-    /// Returns true if the `path` exists
+    /// Returns the POSIX access control list of the given path
     ///
     /// * Handles path expansion and absolute path resolution
+    /// * Links are followed
+    /// * Paths without an ACL of their own return the minimal ACL matching their mode
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
     ///
     /// ### Examples
     /// ```
     /// use rivia::prelude::*;
     ///
-    /// assert_eq!(Stdfs::exists("/etc"), true);
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_func_get_acl");
+    /// let file = tmpdir.mash("file");
+    /// assert!(Stdfs::mkfile_m(&file, 0o640).is_ok());
+    /// assert_eq!(Stdfs::get_acl(&file).unwrap(), Acl::from_mode(0o640));
+    /// assert_vfs_remove_all!(vfs, &tmpdir);
     /// ```
-    pub fn exists<T: AsRef<Path>>(path: T) -> bool {
-        match Stdfs::abs(path) {
-            Ok(abs) => fs::metadata(abs).is_ok(),
-            Err(_) => false,
+    pub fn get_acl<T: AsRef<Path>>(path: T) -> RvResult<Acl> {
+        let path = Stdfs::abs(path)?;
+        if !Stdfs::exists(&path) {
+            return Err(PathError::does_not_exist(&path).into());
         }
-    }
+        let meta = fs::metadata(&path)?;
+        let mut acl = Acl::from_mode(meta.mode());
+        if let Some(entries) = Stdfs::_get_acl_xattr(&path, sys::XATTR_ACCESS)? {
+            acl.access = entries;
+        }
+        if meta.is_dir() {
+            acl.default = Stdfs::_get_acl_xattr(&path, sys::XATTR_DEFAULT)?.unwrap_or_default();
+        }
+        Ok(acl)
+    }
+
+    /// Returns the SELinux security context of the given path
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Links aren't followed so that the context of the link itself is returned
+    /// * Returns None when the path isn't labeled e.g. SELinux isn't supported by the filesystem
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_func_get_selinux_context");
+    /// let file = tmpdir.mash("file");
+    /// assert_vfs_mkfile!(vfs, &file);
+    /// assert!(Stdfs::get_selinux_context(&file).is_ok());
+    /// assert_vfs_remove_all!(vfs, &tmpdir);
+    /// ```
+    #[cfg(feature = "selinux")]
+    pub fn get_selinux_context<T: AsRef<Path>>(path: T) -> RvResult<Option<String>> {
+        let path = Stdfs::abs(path)?;
+        if fs::symlink_metadata(&path).is_err() {
+            return Err(PathError::does_not_exist(&path).into());
+        }
+        Stdfs::_get_selinux_context(&path)
+    }
+
+    // Read the SELinux security context of the given path without following links
+    //
+    // * Returns None when the path isn't labeled or the filesystem doesn't support labels
+    fn _get_selinux_context(path: &Path) -> RvResult<Option<String>> {
+        let cpath = CString::new(path.as_os_str().as_bytes()).map_err(io::Error::from)?;
+        let cname = CString::new(XATTR_SELINUX).map_err(io::Error::from)?;
+        let get = |buf: *mut u8, len: usize| unsafe {
+            libc::lgetxattr(cpath.as_ptr(), cname.as_ptr(), buf as *mut libc::c_void, len)
+        };
+
+        // Query for the size first then read the value
+        let size = match Errno::result(get(ptr::null_mut(), 0)) {
+            Err(Errno::ENODATA) | Err(Errno::ENOTSUP) => return Ok(None),
+            x => x?,
+        };
+        let mut value = vec![0u8; size as usize];
+        let size = Errno::result(get(value.as_mut_ptr(), value.len()))?;
+        value.truncate(size as usize);
+
+        // Contexts are stored null terminated
+        if value.last() == Some(&0) {
+            value.pop();
+        }
+        Ok(Some(String::from_utf8_lossy(&value).into_owned()))
+    }
+
+    // Read the ACL entries stored in the given extended attribute following links
+    //
+    // * Returns None when the attribute isn't set or the filesystem doesn't support ACLs
+    fn _get_acl_xattr(path: &Path, name: &str) -> RvResult<Option<Vec<AclEntry>>> {
+        let cpath = CString::new(path.as_os_str().as_bytes()).map_err(io::Error::from)?;
+        let cname = CString::new(name).map_err(io::Error::from)?;
+        let get = |buf: *mut u8, len: usize| unsafe {
+            libc::getxattr(cpath.as_ptr(), cname.as_ptr(), buf as *mut libc::c_void, len)
+        };
+
+        // Query for the size first then read the value
+        let size = match Errno::result(get(ptr::null_mut(), 0)) {
+            Err(Errno::ENODATA) | Err(Errno::ENOTSUP) => return Ok(None),
+            x => x?,
+        };
+        let mut value = vec![0u8; size as usize];
+        let size = Errno::result(get(value.as_mut_ptr(), value.len()))?;
+        value.truncate(size as usize);
+        match Acl::from_xattr(&value) {
+            Some(entries) => Ok(Some(entries)),
+            None => Err(VfsError::InvalidAcl(path.to_path_buf()).into()),
+        }
+    }
+
+    /// Returns the group ID of the owner of this file
+    ///
+    /// * Handles path expansion and absolute path resolution
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::stdfs();
+    /// assert_eq!(Stdfs::gid(vfs.root()).unwrap(), 0);
+    /// ```
+    pub fn gid<T: AsRef<Path>>(path: T) -> RvResult<u32> {
+        Ok(fs::metadata(Stdfs::abs(path)?)?.gid())
+    }
+
+    /// Returns the paths matching the given glob pattern sorted by path
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * `*` matches any sequence of characters within a single path component
+    /// * `**` matches any sequence of characters across path components
+    /// * `?` matches any single character and `[abc]`, `[a-z]` or `[!a-z]` match a character class
+    /// * Paths are returned in absolute form and an empty list is returned when nothing matches
+    /// * Links aren't followed and directories that can't be read are skipped
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_func_glob");
+    /// let file1 = tmpdir.mash("file1.conf");
+    /// assert_vfs_mkfile!(vfs, &file1);
+    /// assert_eq!(Stdfs::glob(tmpdir.mash("*.conf").to_string().unwrap()).unwrap(), vec![file1]);
+    /// assert_vfs_remove_all!(vfs, &tmpdir);
+    /// ```
+    pub fn glob<T: AsRef<str>>(pattern: T) -> RvResult<Vec<PathBuf>> {
+        sys::glob_paths(&Stdfs::new(), pattern.as_ref())
+    }
+
+    /// Creates a new hard link `dst` giving the data of the `src` file another name
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Links aren't followed i.e. a link to a symlink is a second name for the symlink itself
+    /// * Returns the link path
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when `src` or the parent of `dst` doesn't exist
+    /// * PathError::IsNotFile(PathBuf) when `src` is a directory
+    /// * PathError::ExistsAlready(PathBuf) when `dst` already exists
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_func_hardlink");
+    /// let file = tmpdir.mash("file");
+    /// let link = tmpdir.mash("link");
+    /// assert_vfs_write_all!(vfs, &file, "foobar");
+    /// assert_eq!(Stdfs::hardlink(&file, &link).unwrap(), link);
+    /// assert_vfs_read_all!(vfs, &link, "foobar");
+    /// assert_vfs_remove_all!(vfs, &tmpdir);
+    /// ```
+    pub fn hardlink<T: AsRef<Path>, U: AsRef<Path>>(src: T, dst: U) -> RvResult<PathBuf> {
+        let src = Stdfs::abs(src)?;
+        let dst = Stdfs::abs(dst)?;
+        match fs::symlink_metadata(&src) {
+            Ok(x) if x.is_dir() => return Err(PathError::is_not_file(&src).into()),
+            Ok(_) => {},
+            Err(_) => return Err(PathError::does_not_exist(&src).into()),
+        }
+        if fs::symlink_metadata(&dst).is_ok() {
+            return Err(PathError::exists_already(&dst).into());
+        }
+        let dir = dst.dir()?;
+        if !Stdfs::is_dir(&dir) {
+            return Err(PathError::does_not_exist(&dir).into());
+        }
+        fs::hard_link(&src, &dst)?;
+        Ok(dst)
+    }
+
+    /// Returns a snapshot of the registered pre and post operation hooks
+    pub(crate) fn _hooks(&self) -> Arc<VfsHooks> {
+        self.hooks.read().unwrap().clone()
+    }
+
+    /// Execute the given operation wrapped with the registered pre and post operation hooks
+    ///
+    /// * Handles converting path to absolute form only when hooks have been registered
+    pub(crate) fn _hooked<T, F>(&self, op: VfsOp, path: &Path, f: F) -> RvResult<T>
+    where
+        F: FnOnce() -> RvResult<T>,
+    {
+        let hooks = self._hooks();
+        if hooks.is_empty() {
+            return f();
+        }
+        hooks.exec(op, &Stdfs::abs(path)?, f)
+    }
+
+    /// Register a hook to be called after every successful mutating operation
+    pub(crate) fn _hook_post(&self, hook: sys::PostHook) {
+        Arc::make_mut(&mut self.hooks.write().unwrap()).post.push(hook);
+    }
+
+    /// Register a hook to be called before every mutating operation
+    pub(crate) fn _hook_pre(&self, hook: sys::PreHook) {
+        let mut hooks = self.hooks.write().unwrap();
+        let hooks = Arc::make_mut(&mut hooks);
+        let readlink = |x: &Path| Ok(Stdfs::readlink(x).ok());
+        hooks.readlink.get_or_insert_with(|| Arc::new(readlink));
+        hooks.pre.push(hook);
+    }
+
+    /// Register a hook to be called after every mutating operation slower than the given duration
+    pub(crate) fn _hook_slow(&self, after: Duration, hook: sys::SlowHook) {
+        Arc::make_mut(&mut self.hooks.write().unwrap()).slow.push((after, hook));
+    }
+
+    /// Creates a new [`Installer`] for use with the builder pattern
+    ///
+    /// * `dst` will be installed into if it is an existing directory
+    /// * `dst` will be the installed file if it isn't an existing directory
+    /// * Handles path expansion and absolute path resolution
+    /// * Options for mode, ownership, backups and stripping leading source directories
+    /// * Execute by calling `exec`
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_func_install");
+    /// let file1 = tmpdir.mash("file1");
+    /// let file2 = tmpdir.mash("dir/file2");
+    /// assert_vfs_write_all!(vfs, &file1, "this is a test");
+    /// assert!(Stdfs::install(&file1, &file2).unwrap().mode(0o700).exec().is_ok());
+    /// assert_vfs_read_all!(vfs, &file2, "this is a test");
+    /// assert_eq!(vfs.mode(&file2).unwrap(), 0o100700);
+    /// assert_vfs_remove_all!(vfs, &tmpdir);
+    /// ```
+    pub fn install<T: AsRef<Path>, U: AsRef<Path>>(src: T, dst: U) -> RvResult<Installer> {
+        Ok(Installer {
+            opts: InstallOpts { src: src.as_ref().to_owned(), dst: dst.as_ref().to_owned(), ..Default::default() },
+            exec: Box::new(|opts| sys::install_exec(&Stdfs::new(), opts)),
+            notify: None,
+        })
+    }
+
+    /// Returns true if the `path` exists
+    ///
+    /// * Handles path expansion and absolute path resolution
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// assert_eq!(Stdfs::exists("/etc"), true);
+    /// ```
+    pub fn exists<T: AsRef<Path>>(path: T) -> bool {
+        match Stdfs::abs(path) {
+            Ok(abs) => fs::metadata(abs).is_ok(),
+            Err(_) => false,
+        }
+    }
+
+    /// Returns whether each of the given `paths` exists in the same order
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Checks all paths in a single pass e.g. Memfs only acquires its lock once
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// assert_eq!(Stdfs::exists_all(&["/etc", "/foo/bar"]), vec![true, false]);
+    /// ```
+    pub fn exists_all<T: AsRef<Path>>(paths: &[T]) -> Vec<bool> {
+        paths.iter().map(Stdfs::exists).collect()
+    }
 
     /// Returns true if the given path exists and is readonly
     ///
@@ -893,6 +1733,66 @@ impl Stdfs {
         }
     }
 
+    /// Returns true if the given path exists and is a hard link i.e. its data has more than one name
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Links aren't followed
+    /// * As all names of the data are equal the original file is a hard link as well
+    /// * Directories always return false
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_func_is_hardlink");
+    /// let file = tmpdir.mash("file");
+    /// assert_vfs_mkfile!(vfs, &file);
+    /// assert_eq!(Stdfs::is_hardlink(&file), false);
+    /// assert_vfs_hardlink!(vfs, &file, tmpdir.mash("link"));
+    /// assert_eq!(Stdfs::is_hardlink(&file), true);
+    /// assert_vfs_remove_all!(vfs, &tmpdir);
+    /// ```
+    pub fn is_hardlink<T: AsRef<Path>>(path: T) -> bool {
+        match Stdfs::abs(path).map(fs::symlink_metadata) {
+            Ok(Ok(x)) => !x.is_dir() && x.nlink() > 1,
+            _ => false,
+        }
+    }
+
+    /// Returns true if the given path exists and is immutable i.e. `chattr +i`
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Links are followed
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_func_is_immutable");
+    /// let file = tmpdir.mash("file");
+    /// assert_vfs_mkfile!(vfs, &file);
+    /// assert_eq!(Stdfs::is_immutable(&file), false);
+    /// assert_vfs_remove_all!(vfs, &tmpdir);
+    /// ```
+    pub fn is_immutable<T: AsRef<Path>>(path: T) -> bool {
+        match Stdfs::abs(path) {
+            Ok(x) => match Stdfs::_inode_flags(&x) {
+                Ok((_, flags)) => flags & FS_IMMUTABLE_FL != 0,
+                Err(_) => false,
+            },
+            Err(_) => false,
+        }
+    }
+
+    // Read the inode flags of the given path following links returning the opened file along with
+    // them so that they can be updated through the same descriptor.
+    fn _inode_flags(path: &Path) -> RvResult<(File, libc::c_int)> {
+        let file = fs::OpenOptions::new().read(true).custom_flags(libc::O_NONBLOCK).open(path)?;
+        let mut flags: libc::c_int = 0;
+        Errno::result(unsafe { libc::ioctl(file.as_raw_fd(), libc::FS_IOC_GETFLAGS, &mut flags) })?;
+        Ok((file, flags))
+    }
+
     /// Returns true if the given path exists and is readonly
     ///
     /// * Handles path expansion and absolute path resolution
@@ -1003,6 +1903,52 @@ impl Stdfs {
         }
     }
 
+    /// Returns the number of lines in the given file without reading it all into memory
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Counts a final line without a trailing newline i.e. matches the length of `read_lines`
+    ///
+    /// ### Errors
+    /// * PathError::IsNotFile(PathBuf) when the given path isn't a file
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_func_line_count");
+    /// let file = tmpdir.mash("file");
+    /// assert_vfs_write_all!(vfs, &file, "1\n2");
+    /// assert_eq!(Stdfs::line_count(&file).unwrap(), 2);
+    /// assert_vfs_remove_all!(vfs, &tmpdir);
+    /// ```
+    pub fn line_count<T: AsRef<Path>>(path: T) -> RvResult<u64> {
+        Ok(sys::wc_reader(Stdfs::read(path)?)?.lines)
+    }
+
+    /// Returns the filesystem properties of the given path with a single lookup
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Links report the properties of the target they point to except for `link`
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_func_metadata");
+    /// let file = tmpdir.mash("file");
+    /// assert_vfs_write_all!(vfs, &file, "foobar");
+    /// let meta = Stdfs::metadata(&file).unwrap();
+    /// assert_eq!((meta.file, meta.size, meta.nlink), (true, 6, 1));
+    /// assert_vfs_remove_all!(vfs, &tmpdir);
+    /// ```
+    pub fn metadata<T: AsRef<Path>>(path: T) -> RvResult<VfsMetadata> {
+        Ok(StdfsEntry::from(path)?.metadata())
+    }
+
     /// Creates the given directory and any parent directories needed with the given mode
     ///
     /// ### Examples
@@ -1061,6 +2007,41 @@ impl Stdfs {
         Ok(path)
     }
 
+    /// Wraps `mkdir_p` reporting whether the directory was created or already existed
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Creation is attempted first so a concurrent creator doesn't cause an error
+    ///
+    /// ### Errors
+    /// * PathError::IsNotDir(PathBuf) when the path already exists and is not a directory
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_func_mkdir_p_ensured");
+    /// let dir = tmpdir.mash("dir");
+    /// assert_eq!(Stdfs::mkdir_p_ensured(&dir).unwrap(), Ensured::Created(dir.clone()));
+    /// assert_eq!(Stdfs::mkdir_p_ensured(&dir).unwrap(), Ensured::Existed(dir.clone()));
+    /// assert_vfs_remove_all!(vfs, &tmpdir);
+    /// ```
+    pub fn mkdir_p_ensured<T: AsRef<Path>>(path: T) -> RvResult<Ensured> {
+        let path = Stdfs::abs(path)?;
+        if let Ok(dir) = path.dir() {
+            if !Stdfs::exists(&dir) {
+                fs::create_dir_all(&dir)?;
+            }
+        }
+        match fs::create_dir(&path) {
+            Ok(()) => Ok(Ensured::Created(path)),
+            Err(err) if err.kind() == io::ErrorKind::AlreadyExists => match Stdfs::is_dir(&path) {
+                true => Ok(Ensured::Existed(path)),
+                false => Err(PathError::IsNotDir(path).into()),
+            },
+            Err(err) => Err(err.into()),
+        }
+    }
+
     /// Create an empty file similar to the linux touch command
     ///
     /// * Handles path expansion and absolute path resolution
@@ -1107,47 +2088,250 @@ impl Stdfs {
         Ok(path)
     }
 
-    /// Wraps `mkfile` allowing for setting the file's mode
+    /// Wraps `mkfile` reporting whether the file was created or already existed
     ///
-    /// ### Examples
-    /// ```
-    /// use rivia::prelude::*;
+    /// * Handles path expansion and absolute path resolution
+    /// * Creation is attempted first so a concurrent creator doesn't cause an error
+    /// * Links to files are considered to be files
     ///
-    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_func_mkfile_m");
-    /// let file1 = tmpdir.mash("file1");
-    /// assert!(Stdfs::mkfile_m(&file1, 0o555).is_ok());
-    /// assert_eq!(Stdfs::mode(&file1).unwrap(), 0o100555);
-    /// assert_vfs_remove_all!(vfs, &tmpdir);
-    /// ```
-    pub fn mkfile_m<T: AsRef<Path>>(path: T, mode: u32) -> RvResult<PathBuf> {
-        let path = Stdfs::mkfile(path)?;
-        fs::set_permissions(&path, fs::Permissions::from_mode(mode))?;
-        Ok(path)
-    }
-
-    /// Returns the permissions for a file
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path's parent doesn't exist
+    /// * PathError::IsNotDir(PathBuf) when the given path's parent isn't a directory
+    /// * PathError::IsNotFile(PathBuf) when the given path exists but isn't a file
     ///
     /// ### Examples
     /// ```
     /// use rivia::prelude::*;
     ///
-    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_func_mode");
-    /// let file1 = tmpdir.mash("file1");
-    /// assert!(Stdfs::mkfile_m(&file1, 0o555).is_ok());
-    /// assert_eq!(Stdfs::mode(&file1).unwrap(), 0o100555);
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_func_mkfile_ensured");
+    /// let file = tmpdir.mash("file");
+    /// assert_eq!(Stdfs::mkfile_ensured(&file).unwrap(), Ensured::Created(file.clone()));
+    /// assert_eq!(Stdfs::mkfile_ensured(&file).unwrap(), Ensured::Existed(file.clone()));
     /// assert_vfs_remove_all!(vfs, &tmpdir);
     /// ```
-    pub fn mode<T: AsRef<Path>>(path: T) -> RvResult<u32> {
+    pub fn mkfile_ensured<T: AsRef<Path>>(path: T) -> RvResult<Ensured> {
         let path = Stdfs::abs(path)?;
-        let meta = fs::symlink_metadata(path)?;
+
+        // Validate path components
+        let dir = path.dir()?;
+        if let Ok(meta) = fs::symlink_metadata(&dir) {
+            if !meta.is_dir() {
+                return Err(PathError::is_not_dir(dir).into());
+            }
+        } else {
+            return Err(PathError::does_not_exist(dir).into());
+        }
+
+        match fs::OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(_) => Ok(Ensured::Created(path)),
+            Err(err) if err.kind() == io::ErrorKind::AlreadyExists => match Stdfs::is_file(&path) {
+                true => Ok(Ensured::Existed(path)),
+                false => Err(PathError::is_not_file(path).into()),
+            },
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Wraps `mkfile` allowing for setting the file's mode
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_func_mkfile_m");
+    /// let file1 = tmpdir.mash("file1");
+    /// assert!(Stdfs::mkfile_m(&file1, 0o555).is_ok());
+    /// assert_eq!(Stdfs::mode(&file1).unwrap(), 0o100555);
+    /// assert_vfs_remove_all!(vfs, &tmpdir);
+    /// ```
+    pub fn mkfile_m<T: AsRef<Path>>(path: T, mode: u32) -> RvResult<PathBuf> {
+        let path = Stdfs::mkfile(path)?;
+        fs::set_permissions(&path, fs::Permissions::from_mode(mode))?;
+        Ok(path)
+    }
+
+    /// Returns the permissions for a file
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_func_mode");
+    /// let file1 = tmpdir.mash("file1");
+    /// assert!(Stdfs::mkfile_m(&file1, 0o555).is_ok());
+    /// assert_eq!(Stdfs::mode(&file1).unwrap(), 0o100555);
+    /// assert_vfs_remove_all!(vfs, &tmpdir);
+    /// ```
+    pub fn mode<T: AsRef<Path>>(path: T) -> RvResult<u32> {
+        let path = Stdfs::abs(path)?;
+        let meta = fs::symlink_metadata(path)?;
         Ok(meta.permissions().mode())
     }
 
+    /// Creates a new [`Mover`] for use with the builder pattern
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Always moves `src` into `dst` if `dst` is an existing directory
+    /// * Replaces destination files if they exist
+    /// * Falls back on copy and remove when moving across filesystems
+    /// * Options for preserving attributes
+    /// * Execute by calling `exec`
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_func_move_b");
+    /// let file1 = tmpdir.mash("file1");
+    /// let file2 = tmpdir.mash("file2");
+    /// assert_vfs_write_all!(vfs, &file1, "this is a test");
+    /// assert!(Stdfs::move_b(&file1, &file2).unwrap().exec().is_ok());
+    /// assert_vfs_no_exists!(vfs, &file1);
+    /// assert_vfs_read_all!(vfs, &file2, "this is a test");
+    /// assert_vfs_remove_all!(vfs, &tmpdir);
+    /// ```
+    pub fn move_b<T: AsRef<Path>, U: AsRef<Path>>(src: T, dst: U) -> RvResult<Mover> {
+        Ok(Mover {
+            opts: MoveOpts {
+                src: src.as_ref().to_owned(),
+                dst: dst.as_ref().to_owned(),
+                preserve: true,
+            },
+            exec: Box::new(Stdfs::_move),
+        })
+    }
+
+    // Execute move with the given [`MoveOpts`] option
+    fn _move(opts: MoveOpts) -> RvResult<()> {
+        let src_path = Stdfs::abs(&opts.src)?;
+        let dst_root = Stdfs::abs(&opts.dst)?;
+        let copy_into = Stdfs::is_dir(&dst_root);
+
+        let dst_path = if copy_into { dst_root.mash(src_path.base()?) } else { dst_root };
+        match fs::rename(&src_path, &dst_path) {
+            Err(err) if err.raw_os_error() == Some(Errno::EXDEV as i32) => {
+                Stdfs::_move_across(&src_path, &dst_path, opts.preserve)
+            },
+            x => Ok(x?),
+        }
+    }
+
+    // Move across filesystems by copying to a temporary sibling of the destination then renaming it
+    // into place. The source is only removed once the destination is complete and any failure along
+    // the way removes the temporary copy so that no partially moved tree is ever left behind.
+    fn _move_across(src: &Path, dst: &Path, preserve: bool) -> RvResult<()> {
+        let remove = |path: &Path| -> RvResult<()> {
+            match fs::symlink_metadata(path)?.is_dir() {
+                true => fs::remove_dir_all(path)?,
+                false => fs::remove_file(path)?,
+            }
+            Ok(())
+        };
+
+        let tmp = dst.dir()?.mash(format!(".{}.{}.move", dst.base()?, std::process::id()));
+        let res = Stdfs::_move_copy(src, &tmp, preserve).and_then(|_| Ok(fs::rename(&tmp, dst)?));
+        if res.is_err() {
+            if fs::symlink_metadata(&tmp).is_ok() {
+                remove(&tmp)?;
+            }
+            return res;
+        }
+        remove(src)
+    }
+
+    // Copy the `src` tree to `dst` without following links optionally preserving attributes
+    fn _move_copy(src: &Path, dst: &Path, preserve: bool) -> RvResult<()> {
+        let mut copied = vec![];
+        for entry in Stdfs::entries(src)? {
+            let entry = entry?;
+            let dst_path = dst.mash(entry.path().trim_prefix(src));
+            let meta = fs::symlink_metadata(entry.path())?;
+
+            if meta.file_type().is_symlink() {
+                unix::fs::symlink(fs::read_link(entry.path())?, &dst_path)?;
+            } else if meta.is_dir() {
+                fs::create_dir(&dst_path)?;
+            } else if meta.is_file() {
+                fs::copy(entry.path(), &dst_path)?;
+            } else {
+                return Err(PathError::unsupported_file_type(entry.path()).into());
+            }
+            copied.push((entry.path().to_owned(), dst_path, meta));
+        }
+
+        // Apply attributes contents first so directory timestamps aren't changed afterwards
+        if preserve {
+            for (src, dst, meta) in copied.iter().rev() {
+                Stdfs::_move_preserve(src, dst, meta)?;
+            }
+        }
+        Ok(())
+    }
+
+    // Preserve the mode, owner, timestamps and extended attributes of `src` on `dst`
+    //
+    // * Ownership and attributes the caller isn't permitted to set are skipped just like `mv` does
+    fn _move_preserve(src: &Path, dst: &Path, meta: &fs::Metadata) -> RvResult<()> {
+        Stdfs::_move_xattrs(src, dst)?;
+
+        let uid = nix::unistd::Uid::from_raw(meta.uid());
+        let gid = nix::unistd::Gid::from_raw(meta.gid());
+        match nix::unistd::fchownat(None, dst, Some(uid), Some(gid), nix::unistd::FchownatFlags::NoFollowSymlink) {
+            Err(Errno::EPERM) => (),
+            x => x?,
+        }
+        if !meta.file_type().is_symlink() {
+            fs::set_permissions(dst, fs::Permissions::from_mode(meta.mode()))?;
+        }
+        Stdfs::set_file_time(dst, meta.accessed()?, meta.modified()?)
+    }
+
+    // Copy the extended attributes of `src` to `dst` without following links
+    //
+    // * Filesystems without extended attribute support are skipped
+    fn _move_xattrs(src: &Path, dst: &Path) -> RvResult<()> {
+        // Read a variable length value by first querying for its size
+        let read = |f: &dyn Fn(*mut u8, usize) -> isize| -> nix::Result<Vec<u8>> {
+            let size = Errno::result(f(ptr::null_mut(), 0))?;
+            let mut buf = vec![0u8; size as usize];
+            let size = Errno::result(f(buf.as_mut_ptr(), buf.len()))?;
+            buf.truncate(size as usize);
+            Ok(buf)
+        };
+
+        let src = CString::new(src.as_os_str().as_bytes()).map_err(io::Error::from)?;
+        let dst = CString::new(dst.as_os_str().as_bytes()).map_err(io::Error::from)?;
+        let names =
+            match read(&|buf, len| unsafe { libc::llistxattr(src.as_ptr(), buf as *mut libc::c_char, len) }) {
+                Err(Errno::ENOTSUP) => return Ok(()),
+                x => x?,
+            };
+
+        // Names are returned as a null delimited list
+        for name in names.split(|x| *x == 0).filter(|x| !x.is_empty()) {
+            let name = CString::new(name).map_err(io::Error::from)?;
+            let value = read(&|buf, len| unsafe {
+                libc::lgetxattr(src.as_ptr(), name.as_ptr(), buf as *mut libc::c_void, len)
+            })?;
+            let res = unsafe {
+                libc::lsetxattr(dst.as_ptr(), name.as_ptr(), value.as_ptr() as *const libc::c_void, value.len(), 0)
+            };
+            match Errno::result(res) {
+                Err(Errno::ENOTSUP) | Err(Errno::EPERM) => (),
+                x => {
+                    x?;
+                },
+            }
+        }
+        Ok(())
+    }
+
     /// Move a file or directory
     ///
     /// * Handles path expansion and absolute path resolution
     /// * Always moves `src` into `dst` if `dst` is an existing directory
     /// * Replaces destination files if they exist
+    /// * Falls back on copy and remove preserving attributes when moving across filesystems
     ///
     /// ### Errors
     /// * PathError::DoesNotExist when the source doesn't exist
@@ -1166,13 +2350,94 @@ impl Stdfs {
     /// assert_vfs_remove_all!(vfs, &tmpdir);
     /// ```
     pub fn move_p<T: AsRef<Path>, U: AsRef<Path>>(src: T, dst: U) -> RvResult<()> {
-        let src_path = Stdfs::abs(src)?;
-        let dst_root = Stdfs::abs(dst)?;
-        let copy_into = Stdfs::is_dir(&dst_root);
+        Stdfs::move_b(src, dst)?.exec()
+    }
 
-        let dst_path = if copy_into { dst_root.mash(src_path.base()?) } else { dst_root.clone() };
-        fs::rename(src_path, dst_path)?;
-        Ok(())
+    /// Returns the number of hard links to the given path's data
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Links aren't followed
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_func_nlink");
+    /// let file = tmpdir.mash("file");
+    /// assert_vfs_mkfile!(vfs, &file);
+    /// assert_eq!(Stdfs::nlink(&file).unwrap(), 1);
+    /// assert_vfs_hardlink!(vfs, &file, tmpdir.mash("link"));
+    /// assert_eq!(Stdfs::nlink(&file).unwrap(), 2);
+    /// assert_vfs_remove_all!(vfs, &tmpdir);
+    /// ```
+    pub fn nlink<T: AsRef<Path>>(path: T) -> RvResult<u64> {
+        let path = Stdfs::abs(path)?;
+        match fs::symlink_metadata(&path) {
+            Ok(x) => Ok(x.nlink()),
+            Err(_) => Err(PathError::does_not_exist(&path).into()),
+        }
+    }
+
+    /// Opens the given directory returning a [`DirHandle`] for operating on its entries by name
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Stdfs holds the directory open and uses the `openat` family of syscalls to avoid races and
+    ///   repeated path resolution
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    /// * PathError::IsNotDir(PathBuf) when the given path is not a directory
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_func_open_dir");
+    /// let dir = tmpdir.mash("dir");
+    /// assert_vfs_mkdir_p!(vfs, &dir);
+    /// let handle = Stdfs::open_dir(&dir).unwrap();
+    /// assert_eq!(handle.mkfile("file").unwrap(), dir.mash("file"));
+    /// assert_vfs_is_file!(vfs, dir.mash("file"));
+    /// assert_vfs_remove_all!(vfs, &tmpdir);
+    /// ```
+    pub fn open_dir<T: AsRef<Path>>(path: T) -> RvResult<DirHandle> {
+        DirHandle::stdfs(Default::default(), Stdfs::abs(path)?)
+    }
+
+    /// Opens a file in read-write mode for modifying regions of it in place
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Creates a file if it does not exist without truncating it if it does
+    /// * Writes overwrite the data at the current position extending the file as needed
+    ///
+    /// ### Errors
+    /// * PathError::IsNotDir(PathBuf) when the given path's parent exists but is not a directory
+    /// * PathError::DoesNotExist(PathBuf) when the given path's parent doesn't exist
+    /// * PathError::IsNotFile(PathBuf) when the given path exists but is not a file
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_func_open_rw");
+    /// let file = tmpdir.mash("file");
+    /// assert_vfs_write_all!(vfs, &file, "foobar");
+    /// let mut f = Stdfs::open_rw(&file).unwrap();
+    /// f.seek(SeekFrom::Start(3)).unwrap();
+    /// f.write_all(b"BAR").unwrap();
+    /// f.flush().unwrap();
+    /// assert_vfs_read_all!(vfs, &file, "fooBAR");
+    /// assert_vfs_remove_all!(vfs, &tmpdir);
+    /// ```
+    pub fn open_rw<T: AsRef<Path>>(path: T) -> RvResult<Box<dyn ReadWriteSeek>> {
+        // Ensure the file exists as the std functions don't do that
+        Stdfs::mkfile(&path)?;
+
+        let path = Stdfs::abs(path)?;
+        Ok(Box::new(limit::retry(&path, || File::options().read(true).write(true).open(&path))?))
     }
 
     /// Returns the (user ID, group ID) of the owner of this file
@@ -1212,15 +2477,10 @@ impl Stdfs {
     /// assert_vfs_remove_all!(vfs, &tmpdir);
     /// ```
     pub fn paths<T: AsRef<Path>>(path: T) -> RvResult<Vec<PathBuf>> {
-        let mut paths: Vec<PathBuf> = vec![];
         if !Stdfs::is_dir(&path) {
             return Err(PathError::is_not_dir(&path).into());
         }
-        for entry in Stdfs::entries(path)?.min_depth(1).max_depth(1).sort_by_name() {
-            let entry = entry?;
-            paths.push(entry.path_buf());
-        }
-        Ok(paths)
+        Stdfs::entries(path)?.min_depth(1).max_depth(1).sort_by_name().collect_paths()
     }
 
     /// Open a file in readonly mode
@@ -1258,7 +2518,7 @@ impl Stdfs {
         }
 
         // Return the file handle
-        Ok(Box::new(File::open(&path)?))
+        Ok(Box::new(limit::retry(&path, || File::open(&path))?))
     }
 
     /// Returns the contents of the `path` as a `String`.
@@ -1291,10 +2551,67 @@ impl Stdfs {
             return Err(PathError::does_not_exist(&path).into());
         }
 
-        match std::fs::read_to_string(path) {
-            Ok(data) => Ok(data),
-            Err(err) => Err(err.into()),
+        // Use the io_uring fast path when enabled and supported
+        if let Some(data) = uring::read(&path)? {
+            return String::from_utf8(data).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err).into());
+        }
+
+        limit::retry(&path, || fs::read_to_string(&path))
+    }
+
+    /// Creates a new [`Reader`] for use with the builder pattern
+    ///
+    /// * Provides a handle to a Read + Seek implementation
+    /// * Handles path expansion and absolute path resolution
+    /// * Options for bypassing the page cache
+    /// * Execute by calling `exec`
+    ///
+    /// ### Errors
+    /// * PathError::IsNotFile(PathBuf) when the given path isn't a file
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_func_read_b");
+    /// let file = tmpdir.mash("file");
+    /// assert_vfs_write_all!(vfs, &file, b"foobar 1");
+    /// let mut file = Stdfs::read_b(&file).unwrap().direct(true).exec().unwrap();
+    /// let mut buf = String::new();
+    /// file.read_to_string(&mut buf);
+    /// assert_eq!(buf, "foobar 1".to_string());
+    /// assert_vfs_remove_all!(vfs, &tmpdir);
+    /// ```
+    pub fn read_b<T: AsRef<Path>>(path: T) -> RvResult<Reader> {
+        Ok(Reader {
+            opts: ReadOpts { path: path.as_ref().to_owned(), direct: false, advice: None },
+            exec: Box::new(Stdfs::_read),
+        })
+    }
+
+    // Execute read with the given [`ReadOpts`] option
+    fn _read(opts: ReadOpts) -> RvResult<Box<dyn ReadSeek>> {
+        // Validate target exists and is a file
+        let path = Stdfs::abs(&opts.path)?;
+        if Stdfs::exists(&path) {
+            if !Stdfs::is_file(&path) {
+                return Err(PathError::is_not_file(&path).into());
+            }
+        } else {
+            return Err(PathError::does_not_exist(&path).into());
         }
+
+        // Direct reads bypass the page cache so there is nothing to advise
+        if opts.direct {
+            return Ok(Box::new(DirectFile::open(&path)?));
+        }
+
+        let f = limit::retry(&path, || File::open(&path))?;
+        if let Some(advice) = opts.advice {
+            fcntl::posix_fadvise(f.as_raw_fd(), 0, 0, advice.into())?;
+        }
+        Ok(Box::new(f))
     }
 
     /// Read the given file and returns it as lines in a vector
@@ -1363,6 +2680,53 @@ impl Stdfs {
         Ok(StdfsEntry::from(link)?.alt_buf())
     }
 
+    /// Rewrites the given link's target as the minimal relative path to the same target
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Absolute link targets are converted to their relative equivalent
+    /// * Links already in their minimal relative form are left untouched
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given link doesn't exist
+    /// * PathError::IsNotSymlink(PathBuf) when the given path isn't a link
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_func_relink");
+    /// let file1 = tmpdir.mash("file1");
+    /// let link1 = tmpdir.mash("link1");
+    /// assert_vfs_mkfile!(vfs, &file1);
+    /// assert!(std::os::unix::fs::symlink(&file1, &link1).is_ok());
+    /// assert_vfs_readlink!(vfs, &link1, &file1);
+    /// assert_eq!(Stdfs::relink(&link1).unwrap(), link1);
+    /// assert_vfs_readlink!(vfs, &link1, PathBuf::from("file1"));
+    /// assert_vfs_remove_all!(vfs, &tmpdir);
+    /// ```
+    pub fn relink<T: AsRef<Path>>(link: T) -> RvResult<PathBuf> {
+        let link = Stdfs::abs(link)?;
+
+        // Validate the link path
+        match fs::symlink_metadata(&link) {
+            Ok(meta) if !meta.file_type().is_symlink() => return Err(PathError::is_not_symlink(&link).into()),
+            Err(_) => return Err(PathError::does_not_exist(&link).into()),
+            _ => {},
+        }
+
+        // Resolve the target to an absolute path then compute its minimal relative form
+        let target = fs::read_link(&link)?;
+        let alt = Stdfs::abs(if !target.is_absolute() { link.dir()?.mash(&target) } else { target.clone() })?;
+        let rel = alt.relative(link.dir()?)?;
+
+        // Only replace the link when its target changes
+        if rel != target {
+            fs::remove_file(&link)?;
+            unix::fs::symlink(rel, &link)?;
+        }
+        Ok(link)
+    }
+
     /// Removes the given empty directory or file
     ///
     /// * Handles path expansion and absolute path resolution
@@ -1388,8 +2752,7 @@ impl Stdfs {
                 let result = fs::remove_dir(&path);
 
                 // Normalize IO errors
-                if result.is_err() {
-                    let err = result.unwrap_err();
+                if let Err(err) = result {
                     if err.to_string().contains("Directory not empty") {
                         return Err(PathError::dir_contains_files(&path).into());
                     }
@@ -1404,6 +2767,7 @@ impl Stdfs {
     ///
     /// * Handles path expansion and absolute path resolution
     /// * Provides link exclusion i.e. removes the link themselves not what its points to
+    /// * Refuses to remove the root directory, use `remove_all_b` for more guards
     ///
     /// ### Examples
     /// ```
@@ -1414,29 +2778,240 @@ impl Stdfs {
     /// assert_eq!(Stdfs::exists(&tmpdir), false);
     /// ```
     pub fn remove_all<T: AsRef<Path>>(path: T) -> RvResult<()> {
-        let path = Stdfs::abs(path)?;
-        if Stdfs::exists(&path) {
-            fs::remove_dir_all(path)?;
-        }
-        Ok(())
+        Stdfs::remove_all_b(path)?.exec()
     }
 
-    /// Returns the current root directory
+    /// Creates a new [`Remover`] for use with the builder pattern
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Provides link exclusion i.e. removes the link themselves not what its points to
+    /// * Refuses to remove the root directory
+    /// * Provides optional guards for the path being within a base and not crossing devices
     ///
     /// ### Examples
     /// ```
     /// use rivia::prelude::*;
+    ///
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_func_remove_all_b");
+    /// let dir = tmpdir.mash("dir");
+    /// assert_vfs_mkdir_p!(vfs, &dir);
+    /// assert!(Stdfs::remove_all_b(&dir).unwrap().within(&tmpdir).same_device().exec().is_ok());
+    /// assert_eq!(Stdfs::exists(&dir), false);
+    /// assert_vfs_remove_all!(vfs, &tmpdir);
     /// ```
-    pub fn root() -> PathBuf {
-        let mut root = PathBuf::new();
-        root.push(Component::RootDir);
-        root
+    pub fn remove_all_b<T: AsRef<Path>>(path: T) -> RvResult<Remover> {
+        Ok(Remover {
+            opts: RemoveOpts {
+                path: Stdfs::abs(path)?,
+                within: None,
+                same_device: false,
+                confirm: None,
+                each: false,
+            },
+            exec: Box::new(Stdfs::_remove_all),
+        })
     }
 
-    /// Set the current working directory
+    /// Resolve the given link one hop at a time returning the final target and the chain traversed
     ///
     /// * Handles path expansion and absolute path resolution
-    /// * Relative path will use the current working directory
+    /// * Follows each link one hop at a time recording the links traversed
+    /// * Works on dangling chains returning the last existing link and the missing remainder
+    /// * Non-links resolve to themselves with an empty chain
+    ///
+    /// ### Errors
+    /// * PathError::LinkLooping(PathBuf) when the chain loops back on itself
+    /// * PathError::TooManyLinks(PathBuf) when more than `max_hops` hops are required
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_func_resolve_link");
+    /// let link = tmpdir.mash("link");
+    /// assert_vfs_symlink!(vfs, &link, &tmpdir);
+    /// let resolved = Stdfs::resolve_link(&link, 40).unwrap();
+    /// assert_eq!(resolved.chain, vec![link]);
+    /// assert_eq!(resolved.target, tmpdir);
+    /// assert_vfs_remove_all!(vfs, &tmpdir);
+    /// ```
+    pub fn resolve_link<T: AsRef<Path>>(path: T, max_hops: usize) -> RvResult<ResolvedLink> {
+        let mut path = Stdfs::abs(path)?;
+        let mut chain: Vec<PathBuf> = vec![];
+        loop {
+            // Check the link itself so that dangling links are still resolved
+            let exists = match fs::symlink_metadata(&path) {
+                Ok(meta) if meta.file_type().is_symlink() => true,
+                Ok(_) => return Ok(ResolvedLink { chain, target: path, exists: true }),
+                Err(_) => false,
+            };
+            if !exists {
+                return Ok(ResolvedLink { chain, target: path, exists: false });
+            }
+            if chain.contains(&path) {
+                return Err(PathError::link_looping(&chain[0]).into());
+            }
+            if chain.len() >= max_hops {
+                return Err(PathError::too_many_links(chain.first().unwrap_or(&path)).into());
+            }
+            let target = fs::read_link(&path)?;
+            let target = Stdfs::abs(if !target.is_absolute() { path.dir()?.mash(target) } else { target })?;
+            chain.push(path);
+            path = target;
+        }
+    }
+
+    // Execute remove_all with the given [`Remover`] options
+    fn _remove_all(mut opts: RemoveOpts) -> RvResult<()> {
+        opts.within = opts.within.as_ref().map(Stdfs::abs).transpose()?;
+        opts.check(|x| Ok(Stdfs::readlink(x).ok()))?;
+        if !Stdfs::exists(&opts.path) {
+            return Ok(());
+        }
+
+        // Check the whole tree before removing anything so a refusal leaves it untouched
+        if opts.same_device {
+            let dev = fs::symlink_metadata(&opts.path)?.dev();
+            for entry in Stdfs::entries(&opts.path)? {
+                let entry = entry?;
+                if entry.raw::<StdfsMeta>().map(|x| x.dev) != Some(dev) {
+                    return Err(VfsError::RemoveCrossesDevice(entry.path_buf()).into());
+                }
+            }
+        }
+        if opts.confirm.is_none() {
+            fs::remove_dir_all(&opts.path)?;
+            return Ok(());
+        }
+
+        // Remove the approved entries deepest first then the path if it ended up empty
+        let is_empty = |path: &Path| -> RvResult<bool> { Ok(fs::read_dir(path)?.next().is_none()) };
+        let is_dir = fs::symlink_metadata(&opts.path)?.is_dir();
+        for entry in opts.confirmed(Stdfs::entries(&opts.path)?, is_dir)?.into_iter().rev() {
+            if !entry.is_dir() || entry.is_symlink() {
+                fs::remove_file(entry.path())?;
+            } else if !opts.only_if_empty(&entry) || is_empty(entry.path())? {
+                fs::remove_dir_all(entry.path())?;
+            }
+        }
+        if is_dir && is_empty(&opts.path)? {
+            fs::remove_dir(&opts.path)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the current root directory
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    /// ```
+    pub fn root() -> PathBuf {
+        let mut root = PathBuf::new();
+        root.push(Component::RootDir);
+        root
+    }
+
+    /// Returns true if the given paths refer to the same underlying file
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Compares device and inode numbers so both links and hard links are detected
+    /// * Returns false if either path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_func_same_file");
+    /// let file1 = tmpdir.mash("file1");
+    /// let file2 = tmpdir.mash("file2");
+    /// let link = tmpdir.mash("link");
+    /// assert_vfs_mkfile!(vfs, &file1);
+    /// assert_vfs_mkfile!(vfs, &file2);
+    /// assert_vfs_symlink!(vfs, &link, &file1);
+    /// assert_eq!(Stdfs::same_file(&link, &file1), true);
+    /// assert_eq!(Stdfs::same_file(&link, &file2), false);
+    /// assert_vfs_remove_all!(vfs, &tmpdir);
+    /// ```
+    pub fn same_file<T: AsRef<Path>, U: AsRef<Path>>(a: T, b: U) -> bool {
+        match (StdfsEntry::from(a), StdfsEntry::from(b)) {
+            (Ok(a), Ok(b)) => a.meta.dev == b.meta.dev && a.meta.ino == b.meta.ino,
+            _ => false,
+        }
+    }
+
+    /// Set the POSIX access control list of the given path
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Links are followed
+    /// * The permission bits of the path's mode are updated to match the ACL
+    /// * A mask entry is added when named users or groups are given without one
+    /// * Default entries are only valid for directories, empty default entries remove them
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    /// * PathError::IsNotDir(PathBuf) when default entries are given for a non directory
+    /// * VfsError::InvalidAcl(PathBuf) when the entries are malformed
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_func_set_acl");
+    /// let file = tmpdir.mash("file");
+    /// assert!(Stdfs::mkfile_m(&file, 0o640).is_ok());
+    /// assert!(Stdfs::set_acl(&file, Acl::from_mode(0o600)).is_ok());
+    /// assert_eq!(Stdfs::mode(&file).unwrap(), 0o100600);
+    /// assert_vfs_remove_all!(vfs, &tmpdir);
+    /// ```
+    pub fn set_acl<T: AsRef<Path>>(path: T, acl: Acl) -> RvResult<()> {
+        let path = Stdfs::abs(path)?;
+        if !Stdfs::exists(&path) {
+            return Err(PathError::does_not_exist(&path).into());
+        }
+        let meta = fs::metadata(&path)?;
+        let acl = acl.normalize(&path)?;
+        if !acl.default.is_empty() && !meta.is_dir() {
+            return Err(PathError::is_not_dir(&path).into());
+        }
+
+        // Minimal ACLs are fully represented by the mode so store them that way
+        if acl.is_extended() {
+            Stdfs::_set_acl_xattr(&path, sys::XATTR_ACCESS, &acl.access)?;
+        } else {
+            Stdfs::_set_acl_xattr(&path, sys::XATTR_ACCESS, &[])?;
+            fs::set_permissions(&path, fs::Permissions::from_mode((meta.mode() & 0o7000) | acl.mode()))?;
+        }
+        if meta.is_dir() {
+            Stdfs::_set_acl_xattr(&path, sys::XATTR_DEFAULT, &acl.default)?;
+        }
+        Ok(())
+    }
+
+    // Store the given ACL entries in the given extended attribute following links
+    //
+    // * Empty entries remove the attribute ignoring filesystems that don't support ACLs
+    fn _set_acl_xattr(path: &Path, name: &str, entries: &[AclEntry]) -> RvResult<()> {
+        let cpath = CString::new(path.as_os_str().as_bytes()).map_err(io::Error::from)?;
+        let cname = CString::new(name).map_err(io::Error::from)?;
+        if entries.is_empty() {
+            return match Errno::result(unsafe { libc::removexattr(cpath.as_ptr(), cname.as_ptr()) }) {
+                Err(Errno::ENODATA) | Err(Errno::ENOTSUP) => Ok(()),
+                x => Ok(x.map(|_| ())?),
+            };
+        }
+        let value = Acl::to_xattr(entries);
+        let res = unsafe {
+            libc::setxattr(cpath.as_ptr(), cname.as_ptr(), value.as_ptr() as *const libc::c_void, value.len(), 0)
+        };
+        Errno::result(res)?;
+        Ok(())
+    }
+
+    /// Set the current working directory
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Relative path will use the current working directory
     ///
     /// ### Errors
     /// * io::Error, kind: NotFound when the given path doesn't exist
@@ -1451,9 +3026,247 @@ impl Stdfs {
     pub fn set_cwd<T: AsRef<Path>>(path: T) -> RvResult<PathBuf> {
         let path = Stdfs::abs(path)?;
         std::env::set_current_dir(&path)?;
+        cache::clear();
         Ok(path)
     }
 
+    /// Set the maximum number of `abs` resolutions to cache process wide
+    ///
+    /// * Defaults to 0 which disables the cache, setting 0 also clears it
+    /// * Least recently used resolutions are dropped when full
+    /// * Speeds up metadata only workloads e.g. many `exists` calls where resolution dominates
+    /// * Resolutions using environment variables are revalidated against their current values
+    /// * `set_cwd` clears the cache but changing the working directory by other means requires a
+    ///   call to `clear_abs_cache`
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// Stdfs::set_abs_cache(1024);
+    /// let home = sys::home_dir().unwrap();
+    /// assert_eq!(Stdfs::abs("~").unwrap(), PathBuf::from(&home));
+    /// assert_eq!(Stdfs::abs("~").unwrap(), PathBuf::from(&home));
+    /// Stdfs::set_abs_cache(0);
+    /// ```
+    pub fn set_abs_cache(max: usize) {
+        cache::set_capacity(max);
+    }
+
+    /// Set the access and modification times of the given path with nanosecond precision
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Doesn't follow links i.e. sets the times of the link itself
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    /// use std::time::{Duration, UNIX_EPOCH};
+    ///
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_func_set_file_time");
+    /// let file = tmpdir.mash("file");
+    /// assert_vfs_mkfile!(vfs, &file);
+    /// let time = UNIX_EPOCH + Duration::new(1_000_000_000, 123_456_789);
+    /// assert!(Stdfs::set_file_time(&file, time, time).is_ok());
+    /// assert_eq!(vfs.entry(&file).unwrap().modified(), time);
+    /// assert_vfs_remove_all!(vfs, &tmpdir);
+    /// ```
+    pub fn set_file_time<T: AsRef<Path>>(path: T, atime: SystemTime, mtime: SystemTime) -> RvResult<()> {
+        Stdfs::_set_file_time(path.as_ref(), Some(atime), mtime)
+    }
+
+    // Set the times of the given path leaving the access time untouched when `atime` is `None`
+    fn _set_file_time(path: &Path, atime: Option<SystemTime>, mtime: SystemTime) -> RvResult<()> {
+        let path = Stdfs::abs(path)?;
+        if fs::symlink_metadata(&path).is_err() {
+            return Err(PathError::does_not_exist(&path).into());
+        }
+
+        let spec = |time: SystemTime| -> RvResult<TimeSpec> {
+            Ok(TimeSpec::from(time.duration_since(std::time::UNIX_EPOCH)?))
+        };
+        let atime = match atime {
+            Some(x) => spec(x)?,
+            None => TimeSpec::from(libc::timespec { tv_sec: 0, tv_nsec: libc::UTIME_OMIT }),
+        };
+        stat::utimensat(None, &path, &atime, &spec(mtime)?, UtimensatFlags::NoFollowSymlink)?;
+        Ok(())
+    }
+
+    /// Set or clear the immutable flag of the given path i.e. `chattr +i` and `chattr -i`
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Links are followed
+    /// * Requires the `CAP_LINUX_IMMUTABLE` capability and a filesystem that supports it
+    /// * Immutable paths can't be modified or removed until the flag is cleared
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_func_set_immutable");
+    /// let file = tmpdir.mash("file");
+    /// assert_vfs_mkfile!(vfs, &file);
+    /// if Stdfs::set_immutable(&file, true).is_ok() {
+    ///     assert_eq!(Stdfs::is_immutable(&file), true);
+    ///     assert!(Stdfs::set_immutable(&file, false).is_ok());
+    /// }
+    /// assert_eq!(Stdfs::is_immutable(&file), false);
+    /// assert_vfs_remove_all!(vfs, &tmpdir);
+    /// ```
+    pub fn set_immutable<T: AsRef<Path>>(path: T, yes: bool) -> RvResult<()> {
+        let path = Stdfs::abs(path)?;
+        if !Stdfs::exists(&path) {
+            return Err(PathError::does_not_exist(&path).into());
+        }
+        let (file, flags) = Stdfs::_inode_flags(&path)?;
+        let flags = match yes {
+            true => flags | FS_IMMUTABLE_FL,
+            false => flags & !FS_IMMUTABLE_FL,
+        };
+        Errno::result(unsafe { libc::ioctl(file.as_raw_fd(), libc::FS_IOC_SETFLAGS, &flags) })?;
+        Ok(())
+    }
+
+    /// Set the modification time of the given path with nanosecond precision
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Doesn't follow links i.e. sets the time of the link itself
+    /// * Leaves the access time untouched
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    /// use std::time::{Duration, UNIX_EPOCH};
+    ///
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_func_set_mtime_only");
+    /// let file = tmpdir.mash("file");
+    /// assert_vfs_mkfile!(vfs, &file);
+    /// let time = UNIX_EPOCH + Duration::new(1_000_000_000, 123_456_789);
+    /// assert!(Stdfs::set_mtime_only(&file, time).is_ok());
+    /// assert_eq!(vfs.entry(&file).unwrap().modified(), time);
+    /// assert_vfs_remove_all!(vfs, &tmpdir);
+    /// ```
+    pub fn set_mtime_only<T: AsRef<Path>>(path: T, mtime: SystemTime) -> RvResult<()> {
+        Stdfs::_set_file_time(path.as_ref(), None, mtime)
+    }
+
+    /// Set the SELinux security context of the given path
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Links aren't followed so that the context of the link itself is set
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_func_set_selinux_context");
+    /// let file = tmpdir.mash("file");
+    /// assert_vfs_mkfile!(vfs, &file);
+    /// // Labeling requires a filesystem with security attribute support
+    /// if Stdfs::set_selinux_context(&file, "system_u:object_r:etc_t:s0").is_ok() {
+    ///     assert_eq!(Stdfs::get_selinux_context(&file).unwrap().unwrap(), "system_u:object_r:etc_t:s0");
+    /// }
+    /// assert_vfs_remove_all!(vfs, &tmpdir);
+    /// ```
+    #[cfg(feature = "selinux")]
+    pub fn set_selinux_context<T: AsRef<Path>, U: AsRef<str>>(path: T, context: U) -> RvResult<()> {
+        let path = Stdfs::abs(path)?;
+        if fs::symlink_metadata(&path).is_err() {
+            return Err(PathError::does_not_exist(&path).into());
+        }
+        Stdfs::_set_selinux_context(&path, context.as_ref())
+    }
+
+    // Set the SELinux security context of the given path without following links
+    fn _set_selinux_context(path: &Path, context: &str) -> RvResult<()> {
+        let cpath = CString::new(path.as_os_str().as_bytes()).map_err(io::Error::from)?;
+        let cname = CString::new(XATTR_SELINUX).map_err(io::Error::from)?;
+        let value = CString::new(context).map_err(io::Error::from)?;
+        let value = value.as_bytes_with_nul();
+        let res = unsafe {
+            libc::lsetxattr(cpath.as_ptr(), cname.as_ptr(), value.as_ptr() as *const libc::c_void, value.len(), 0)
+        };
+        Errno::result(res)?;
+        Ok(())
+    }
+
+    /// Returns the size in bytes of the given file
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Links are followed
+    /// * Use `disk_usage` to sum up the size of a directory tree
+    ///
+    /// ### Errors
+    /// * PathError::IsNotFile(PathBuf) when the given path isn't a file
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_func_size");
+    /// let file = tmpdir.mash("file");
+    /// assert_vfs_write_all!(vfs, &file, "foobar");
+    /// assert_eq!(Stdfs::size(&file).unwrap(), 6);
+    /// assert_vfs_remove_all!(vfs, &tmpdir);
+    /// ```
+    pub fn size<T: AsRef<Path>>(path: T) -> RvResult<u64> {
+        let path = Stdfs::abs(path)?;
+        match fs::metadata(&path) {
+            Ok(x) if x.is_file() => Ok(x.len()),
+            Ok(_) => Err(PathError::is_not_file(&path).into()),
+            Err(_) => Err(PathError::does_not_exist(&path).into()),
+        }
+    }
+
+    /// Returns the capacity and usage of the filesystem containing the given path
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Reports the values of the underlying `statvfs` call with `free` being the space available
+    ///   to unprivileged users
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_func_stat_fs");
+    /// let stat = Stdfs::stat_fs(&tmpdir).unwrap();
+    /// assert!(stat.inodes_used <= stat.inodes);
+    /// assert_vfs_remove_all!(vfs, &tmpdir);
+    /// ```
+    pub fn stat_fs<T: AsRef<Path>>(path: T) -> RvResult<VfsStatFs> {
+        let path = Stdfs::abs(path)?;
+        if !Stdfs::exists(&path) {
+            return Err(PathError::does_not_exist(&path).into());
+        }
+        let stat = statvfs::statvfs(&path)?;
+        let size = stat.fragment_size() as u64;
+        Ok(VfsStatFs {
+            total: stat.blocks() as u64 * size,
+            used: (stat.blocks() - stat.blocks_free()) as u64 * size,
+            free: stat.blocks_available() as u64 * size,
+            inodes: stat.files() as u64,
+            inodes_used: (stat.files() - stat.files_free()) as u64,
+            inodes_free: stat.files_available() as u64,
+        })
+    }
+
     /// Creates a new symbolic link
     ///
     /// * Handles path expansion and absolute path resolution
@@ -1493,17 +3306,46 @@ impl Stdfs {
         Ok(link)
     }
 
-    /// Set the access and modification times for the given file to the given times
+    /// Wraps `symlink` reporting whether the link was created or already existed
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Creation is attempted first so a concurrent creator doesn't cause an error
+    /// * An existing link is only considered to exist if it points to the given target
+    /// * Dangling links are supported
+    ///
+    /// ### Errors
+    /// * PathError::ExistsAlready(PathBuf) when the link path exists but isn't a link to the target
     ///
     /// ### Examples
     /// ```
     /// use rivia::prelude::*;
+    ///
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_func_symlink_ensured");
+    /// let link = tmpdir.mash("link");
+    /// assert_eq!(Stdfs::symlink_ensured(&link, "file").unwrap(), Ensured::Created(link.clone()));
+    /// assert_eq!(Stdfs::symlink_ensured(&link, "file").unwrap(), Ensured::Existed(link.clone()));
+    /// assert_vfs_remove_all!(vfs, &tmpdir);
     /// ```
-    pub fn set_file_time<T: AsRef<Path>>(path: T, atime: SystemTime, mtime: SystemTime) -> RvResult<()> {
-        let atime_spec = TimeSpec::from(atime.duration_since(std::time::UNIX_EPOCH)?);
-        let mtime_spec = TimeSpec::from(mtime.duration_since(std::time::UNIX_EPOCH)?);
-        stat::utimensat(None, path.as_ref(), &atime_spec, &mtime_spec, UtimensatFlags::NoFollowSymlink)?;
-        Ok(())
+    pub fn symlink_ensured<T: AsRef<Path>, U: AsRef<Path>>(link: T, target: U) -> RvResult<Ensured> {
+        let target = target.as_ref().to_owned();
+        let link = Stdfs::abs(link)?;
+        let target = Stdfs::abs(if !target.is_absolute() { link.dir()?.mash(target) } else { target })?;
+
+        match unix::fs::symlink(target.relative(link.dir()?)?, &link) {
+            Ok(()) => Ok(Ensured::Created(link)),
+            Err(err) if err.kind() == io::ErrorKind::AlreadyExists => {
+                // Read the link directly as resolving it would fail for dangling links
+                let existing = match fs::read_link(&link) {
+                    Ok(x) => Stdfs::abs(if !x.is_absolute() { link.dir()?.mash(x) } else { x })?,
+                    Err(_) => return Err(PathError::exists_already(link).into()),
+                };
+                match existing == target {
+                    true => Ok(Ensured::Existed(link)),
+                    false => Err(PathError::exists_already(link).into()),
+                }
+            },
+            Err(err) => Err(err.into()),
+        }
     }
 
     /// Returns the user ID of the owner of this file
@@ -1521,9 +3363,41 @@ impl Stdfs {
         Ok(fs::metadata(Stdfs::abs(path)?)?.uid())
     }
 
+    /// Watch the given path for changes returning a stream of the events that occur
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Directories are watched recursively via inotify
+    /// * Reports files, directories and links being created, written to, removed and renamed
+    /// * Writes are reported once the file is closed
+    /// * Permission and ownership changes aren't reported
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    /// use std::time::Duration;
+    ///
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_func_watch");
+    /// let mut watcher = Stdfs::watch(&tmpdir).unwrap();
+    /// let file = tmpdir.mash("file");
+    /// assert_vfs_mkfile!(vfs, &file);
+    /// assert_eq!(watcher.recv_timeout(Duration::from_secs(1)), Some(VfsEvent::Create(file)));
+    /// assert_vfs_remove_all!(vfs, &tmpdir);
+    /// ```
+    pub fn watch<T: AsRef<Path>>(path: T) -> RvResult<VfsWatcher> {
+        let path = Stdfs::abs(path)?;
+        if !Stdfs::exists(&path) {
+            return Err(PathError::does_not_exist(&path).into());
+        }
+        Ok(VfsWatcher::stdfs(StdfsWatch::new(&path)?))
+    }
+
     /// Opens a file in write-only mode
     ///
     /// * Creates a file if it does not exist or truncates it if it does
+    /// * Returns a [`VfsWriter`] reporting the path and number of bytes written
     ///
     /// ### Errors
     /// * PathError::IsNotDir(PathBuf) when the given path's parent exists but is not a directory
@@ -1542,8 +3416,9 @@ impl Stdfs {
     /// assert_vfs_read_all!(vfs, &file, "foobar".to_string());
     /// assert_vfs_remove_all!(vfs, &tmpdir);
     /// ```
-    pub fn write<T: AsRef<Path>>(path: T) -> RvResult<Box<dyn Write>> {
-        Ok(Box::new(File::create(Stdfs::abs(path)?)?))
+    pub fn write<T: AsRef<Path>>(path: T) -> RvResult<VfsWriter> {
+        let path = Stdfs::abs(path)?;
+        Ok(VfsWriter::stdfs(&path, limit::retry(&path, || File::create(&path))?))
     }
 
     /// Write the given data to to the target file
@@ -1586,15 +3461,120 @@ impl Stdfs {
             return Err(PathError::is_not_file(&path).into());
         }
 
-        // Create or truncate the target file
-        let mut f = File::create(&path)?;
-        f.write_all(data.as_ref())?;
+        // Create or truncate the target file using the io_uring fast path when enabled and supported
+        let mut f = limit::retry(&path, || File::create(&path))?;
+        if !uring::write(&f, data.as_ref())? {
+            f.write_all(data.as_ref())?;
+        }
 
         // f.sync_all() works better than f.flush()?
         f.sync_all()?;
         Ok(())
     }
 
+    /// Write the given data to the target file atomically
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Writes to a temporary sibling file which is then renamed into place so that readers only
+    ///   ever see the old or the new data and a failure never leaves a partially written file
+    /// * Replaces the final target of links rather than the links themselves
+    /// * Preserves the mode and ownership of an existing file
+    /// * Breaks any hard links to an existing file as it is replaced by a new file
+    ///
+    /// ### Errors
+    /// * PathError::IsNotDir(PathBuf) when the given path's parent exists but is not a directory
+    /// * PathError::DoesNotExist(PathBuf) when the given path's parent doesn't exist
+    /// * PathError::IsNotFile(PathBuf) when the given path exists but is not a file
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_func_write_all_atomic");
+    /// let file = tmpdir.mash("file");
+    /// assert!(Stdfs::write_all_atomic(&file, "foobar").is_ok());
+    /// assert_vfs_read_all!(vfs, &file, "foobar");
+    /// assert_vfs_remove_all!(vfs, &tmpdir);
+    /// ```
+    pub fn write_all_atomic<T: AsRef<Path>, U: AsRef<[u8]>>(path: T, data: U) -> RvResult<()> {
+        let mut path = Stdfs::abs(path)?;
+        if Stdfs::is_symlink(&path) {
+            path = Stdfs::resolve_link(&path, sys::DEFAULT_MAX_LINKS)?.target;
+        }
+        let dir = path.dir()?;
+
+        // Validate the parent directory
+        if Stdfs::exists(&dir) {
+            if !Stdfs::is_dir(&dir) {
+                return Err(PathError::is_not_dir(&dir).into());
+            }
+        } else {
+            return Err(PathError::does_not_exist(&dir).into());
+        }
+
+        // Validate the file
+        let meta = fs::metadata(&path).ok();
+        if meta.as_ref().is_some_and(|x| !x.is_file()) {
+            return Err(PathError::is_not_file(&path).into());
+        }
+
+        // Write the temp file carrying over the mode and ownership of the existing file
+        let tmp = sys::tmp_sibling(&path)?;
+        let write = || -> RvResult<()> {
+            let mut f = limit::retry(&tmp, || File::options().write(true).create_new(true).open(&tmp))?;
+            f.write_all(data.as_ref())?;
+            if let Some(meta) = &meta {
+                f.set_permissions(meta.permissions())?;
+                let tmp_meta = f.metadata()?;
+                if (tmp_meta.uid(), tmp_meta.gid()) != (meta.uid(), meta.gid()) {
+                    unix::fs::fchown(&f, Some(meta.uid()), Some(meta.gid()))?;
+                }
+            }
+            f.sync_all()?;
+            fs::rename(&tmp, &path)?;
+            Ok(())
+        };
+        if let Err(err) = write() {
+            let _ = fs::remove_file(&tmp);
+            return Err(err);
+        }
+
+        // Persist the rename itself
+        File::open(&dir)?.sync_all()?;
+        Ok(())
+    }
+
+    /// Creates a new [`Writer`] for use with the builder pattern
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Options for writing atomically
+    /// * Execute by calling `exec`
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_func_write_b");
+    /// let file = tmpdir.mash("file");
+    /// assert!(Stdfs::write_b(&file, "foobar").unwrap().atomic(true).exec().is_ok());
+    /// assert_vfs_read_all!(vfs, &file, "foobar");
+    /// assert_vfs_remove_all!(vfs, &tmpdir);
+    /// ```
+    pub fn write_b<T: AsRef<Path>, U: AsRef<[u8]>>(path: T, data: U) -> RvResult<Writer> {
+        Ok(Writer {
+            opts: WriteOpts { path: path.as_ref().to_owned(), data: data.as_ref().to_vec(), atomic: false },
+            exec: Box::new(Stdfs::_write),
+        })
+    }
+
+    // Execute write with the given [`WriteOpts`] options
+    fn _write(opts: WriteOpts) -> RvResult<()> {
+        match opts.atomic {
+            true => Stdfs::write_all_atomic(&opts.path, &opts.data),
+            false => Stdfs::write_all(&opts.path, &opts.data),
+        }
+    }
+
     /// Write the given lines to to the target file including final newline
     ///
     /// * Handles path expansion and absolute path resolution
@@ -0,0 +1,262 @@
+// io_uring fast path for bulk reads, writes and copies
+//
+// Each function returns `None` or `false` when io_uring isn't available e.g. older kernels or
+// sandboxes that filter out the syscalls, so that the caller can fall back on the std
+// implementation. The fallback starts over from the beginning of the file so anything already
+// transferred is simply rewritten.
+pub(crate) use imp::*;
+
+#[cfg(not(feature = "uring"))]
+mod imp {
+    use std::{fs::File, path::Path};
+
+    use crate::errors::RvResult;
+
+    /// Read the entire contents of the given file
+    pub(crate) fn read(_path: &Path) -> RvResult<Option<Vec<u8>>> {
+        Ok(None)
+    }
+
+    /// Write all the given data to the start of the given file
+    pub(crate) fn write(_file: &File, _data: &[u8]) -> RvResult<bool> {
+        Ok(false)
+    }
+
+    /// Copy the given file's contents and permissions to the destination
+    pub(crate) fn copy(_src: &Path, _dst: &Path) -> RvResult<bool> {
+        Ok(false)
+    }
+}
+
+#[cfg(feature = "uring")]
+mod imp {
+    use std::{
+        fs::{self, File},
+        io::{self, Read, Seek, SeekFrom},
+        os::unix::{fs::FileExt, io::AsRawFd},
+        path::Path,
+    };
+
+    use io_uring::{opcode, squeue, types, IoUring};
+    use nix::libc;
+
+    use crate::errors::RvResult;
+
+    // Number of chunks kept in flight per submission
+    const QUEUE_DEPTH: usize = 16;
+
+    // Size of each chunk read or written
+    const CHUNK_SIZE: usize = 128 * 1024;
+
+    /// Read the entire contents of the given file
+    pub(crate) fn read(path: &Path) -> RvResult<Option<Vec<u8>>> {
+        let mut ring = match ring()? {
+            Some(x) => x,
+            None => return Ok(None),
+        };
+        let mut file = File::open(path)?;
+        let mut data = Vec::with_capacity(file.metadata()?.len() as usize);
+        let supported = read_batches(&mut ring, &file, |_, _, chunks| {
+            chunks.iter().for_each(|x| data.extend_from_slice(x));
+            Ok(true)
+        })?;
+        if !supported {
+            return Ok(None);
+        }
+
+        // Pick up anything appended since the last batch was read
+        file.seek(SeekFrom::Start(data.len() as u64))?;
+        file.read_to_end(&mut data)?;
+        Ok(Some(data))
+    }
+
+    /// Write all the given data to the start of the given file
+    pub(crate) fn write(file: &File, data: &[u8]) -> RvResult<bool> {
+        let mut ring = match ring()? {
+            Some(x) => x,
+            None => return Ok(false),
+        };
+        let chunks: Vec<&[u8]> = data.chunks(CHUNK_SIZE).collect();
+        for (i, batch) in chunks.chunks(QUEUE_DEPTH).enumerate() {
+            if !write_batch(&mut ring, file, (i * QUEUE_DEPTH * CHUNK_SIZE) as u64, batch)? {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    /// Copy the given file's contents and permissions to the destination
+    pub(crate) fn copy(src: &Path, dst: &Path) -> RvResult<bool> {
+        let mut ring = match ring()? {
+            Some(x) => x,
+            None => return Ok(false),
+        };
+        let reader = File::open(src)?;
+        let writer = File::create(dst)?;
+        if !read_batches(&mut ring, &reader, |ring, offset, chunks| write_batch(ring, &writer, offset, chunks))? {
+            return Ok(false);
+        }
+
+        // Match `fs::copy` which carries over the permissions
+        fs::set_permissions(dst, reader.metadata()?.permissions())?;
+        Ok(true)
+    }
+
+    // Create a new ring returning `None` when io_uring isn't supported
+    fn ring() -> RvResult<Option<IoUring>> {
+        match IoUring::new(QUEUE_DEPTH as u32) {
+            Ok(ring) => Ok(Some(ring)),
+            Err(err) if unsupported(err.raw_os_error()) => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    // Returns true if the given errno indicates io_uring or the operation isn't supported
+    fn unsupported(errno: Option<i32>) -> bool {
+        matches!(errno, Some(libc::ENOSYS | libc::EPERM | libc::EINVAL | libc::EOPNOTSUPP))
+    }
+
+    // Submit the given entries and wait for them all to complete returning the bytes transferred by
+    // each in submission order. Returns `None` if the operations aren't supported.
+    fn submit(ring: &mut IoUring, entries: Vec<squeue::Entry>) -> RvResult<Option<Vec<usize>>> {
+        let count = entries.len();
+        for (i, entry) in entries.into_iter().enumerate() {
+            // Safety: the buffers referenced by the entries outlive the wait below
+            unsafe { ring.submission().push(&entry.user_data(i as u64)) }
+                .map_err(|_| io::Error::other("io_uring submission queue is full"))?;
+        }
+        ring.submit_and_wait(count)?;
+
+        let mut results = vec![0; count];
+        for cqe in ring.completion() {
+            results[cqe.user_data() as usize] = cqe.result();
+        }
+        if results.iter().any(|x| *x < 0 && unsupported(Some(-x))) {
+            return Ok(None);
+        }
+        match results.iter().find(|x| **x < 0) {
+            Some(x) => Err(io::Error::from_raw_os_error(-x).into()),
+            None => Ok(Some(results.into_iter().map(|x| x as usize).collect())),
+        }
+    }
+
+    // Read the given file in batches of chunks handing the contiguous data of each batch to the
+    // given sink along with its offset until the end of the file is reached. Returns false if
+    // either the reads or the sink aren't supported.
+    fn read_batches<F>(ring: &mut IoUring, file: &File, mut sink: F) -> RvResult<bool>
+    where
+        F: FnMut(&mut IoUring, u64, &[&[u8]]) -> RvResult<bool>,
+    {
+        let fd = types::Fd(file.as_raw_fd());
+        let mut bufs = vec![vec![0u8; CHUNK_SIZE]; QUEUE_DEPTH];
+        let mut offset = 0;
+        loop {
+            let entries = bufs
+                .iter_mut()
+                .enumerate()
+                .map(|(i, buf)| {
+                    opcode::Read::new(fd, buf.as_mut_ptr(), CHUNK_SIZE as u32)
+                        .offset(offset + (i * CHUNK_SIZE) as u64)
+                        .build()
+                })
+                .collect();
+            let sizes = match submit(ring, entries)? {
+                Some(x) => x,
+                None => return Ok(false),
+            };
+
+            // Only the data up to and including the first short read is contiguous
+            let count = sizes.iter().position(|x| *x < CHUNK_SIZE).map_or(sizes.len(), |x| x + 1);
+            let chunks: Vec<&[u8]> = bufs.iter().zip(&sizes).take(count).map(|(buf, n)| &buf[..*n]).collect();
+            let len: usize = sizes.iter().take(count).sum();
+            if len == 0 {
+                return Ok(true);
+            }
+            if !sink(ring, offset, &chunks)? {
+                return Ok(false);
+            }
+            offset += len as u64;
+        }
+    }
+
+    // Write the given chunks contiguously starting at the given offset. Returns false if the writes
+    // aren't supported.
+    fn write_batch(ring: &mut IoUring, file: &File, offset: u64, chunks: &[&[u8]]) -> RvResult<bool> {
+        let fd = types::Fd(file.as_raw_fd());
+        let mut offsets = Vec::with_capacity(chunks.len());
+        let mut next = offset;
+        for chunk in chunks {
+            offsets.push(next);
+            next += chunk.len() as u64;
+        }
+        let entries = chunks
+            .iter()
+            .zip(&offsets)
+            .map(|(chunk, x)| opcode::Write::new(fd, chunk.as_ptr(), chunk.len() as u32).offset(*x).build())
+            .collect();
+        let sizes = match submit(ring, entries)? {
+            Some(x) => x,
+            None => return Ok(false),
+        };
+
+        // Finish off any short writes synchronously
+        for ((chunk, offset), n) in chunks.iter().zip(offsets).zip(sizes) {
+            if n < chunk.len() {
+                file.write_all_at(&chunk[n..], offset + n as u64)?;
+            }
+        }
+        Ok(true)
+    }
+}
+
+// Unit tests
+// -------------------------------------------------------------------------------------------------
+#[cfg(all(test, feature = "uring"))]
+mod tests {
+    use std::fs::File;
+
+    use super::*;
+    use crate::prelude::*;
+
+    #[test]
+    fn test_uring_copy() {
+        let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs());
+        let file1 = tmpdir.mash("file1");
+        let file2 = tmpdir.mash("file2");
+        let file3 = tmpdir.mash("file3");
+
+        // Spans several batches and ends with a partial chunk
+        let data = "0123456789".repeat(500_000);
+        assert_vfs_write_all!(vfs, &file1, &data);
+        assert!(vfs.chmod(&file1, 0o640).is_ok());
+        if copy(&file1, &file2).unwrap() {
+            assert_vfs_read_all!(vfs, &file2, data);
+            assert_eq!(vfs.mode(&file2).unwrap(), 0o100640);
+        }
+
+        // Empty file
+        assert_vfs_mkfile!(vfs, &file3);
+        if copy(&file3, &file2).unwrap() {
+            assert_vfs_read_all!(vfs, &file2, "");
+        }
+
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+
+    #[test]
+    fn test_uring_read_write() {
+        let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs());
+        let file1 = tmpdir.mash("file1");
+
+        let data = "0123456789".repeat(500_000);
+        if write(&File::create(&file1).unwrap(), data.as_bytes()).unwrap() {
+            assert_vfs_read_all!(vfs, &file1, data);
+        }
+        assert_vfs_write_all!(vfs, &file1, &data);
+        if let Some(read) = read(&file1).unwrap() {
+            assert_eq!(read, data.as_bytes());
+        }
+
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+}
@@ -1,11 +1,16 @@
 use std::{
-    io::Write,
     path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, SystemTime},
 };
 
 use crate::{
     errors::*,
-    sys::{Chmod, Chown, Copier, Entries, ReadSeek, Vfs, VfsEntry, VirtualFileSystem},
+    sys::{
+        Acl, Advice, Chmod, Chown, Copier, DirHandle, DiskUsage, Ensured, Entries, Installer, Mover,
+        ReadSeek, ReadWriteSeek, Reader, Remover, ResolvedLink, Vfs, VfsConfig, VfsEntry, VfsMetadata, VfsOp,
+        VfsStatFs, VfsWatcher, VfsWriter, VirtualFileSystem, Writer,
+    },
 };
 
 use super::Stdfs;
@@ -32,6 +37,34 @@ impl VirtualFileSystem for Stdfs {
         Stdfs::abs(path)
     }
 
+    /// Advise the kernel how the given file's data will be accessed
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Stdfs uses `posix_fadvise` on the whole file e.g. `Advice::DontNeed` drops the file from
+    ///   the page cache so that streaming scanners don't pollute it
+    /// * `Advice::Sequential` and `Advice::Random` only apply to the handle they are given on Linux
+    ///   so use `Reader::advise` for those instead
+    /// * Memfs has no page cache so this is a no-op
+    ///
+    /// ### Errors
+    /// * PathError::IsNotFile(PathBuf) when the given path isn't a file
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_method_advise");
+    /// let file = tmpdir.mash("file");
+    /// assert_vfs_write_all!(vfs, &file, "this is a test");
+    /// assert!(vfs.advise(&file, Advice::DontNeed).is_ok());
+    /// assert_vfs_read_all!(vfs, &file, "this is a test");
+    /// assert_vfs_remove_all!(vfs, &tmpdir);
+    /// ```
+    fn advise<T: AsRef<Path>>(&self, path: T, advice: Advice) -> RvResult<()> {
+        Stdfs::advise(path, advice)
+    }
+
     /// Returns all dirs for the given path recursively
     ///
     /// * Results are sorted by filename, are distict and don't include the given path
@@ -103,10 +136,39 @@ impl VirtualFileSystem for Stdfs {
         Stdfs::all_paths(path)
     }
 
+    /// Allocate space for the given file up front to avoid fragmentation and fail early when there
+    /// isn't enough space
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Creates the file if it doesn't exist
+    /// * Grows the file to `len` bytes filled with zeros if smaller, existing data is left intact
+    /// * Stdfs uses `posix_fallocate` while Memfs reserves the file's buffer
+    ///
+    /// ### Errors
+    /// * PathError::IsNotDir(PathBuf) when the given path's parent exists but is not a directory
+    /// * PathError::DoesNotExist(PathBuf) when the given path's parent doesn't exist
+    /// * PathError::IsNotFile(PathBuf) when the given path exists but is not a file
+    /// * VfsError::QuotaExceeded(PathBuf) when Memfs quota would be exceeded
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_method_allocate");
+    /// let file = tmpdir.mash("file");
+    /// assert!(vfs.allocate(&file, 1024).is_ok());
+    /// assert_eq!(vfs.entry(&file).unwrap().size(), 1024);
+    /// assert_vfs_remove_all!(vfs, &tmpdir);
+    /// ```
+    fn allocate<T: AsRef<Path>>(&self, path: T, len: u64) -> RvResult<()> {
+        self._hooked(VfsOp::Write, path.as_ref(), || Stdfs::allocate(&path, len))
+    }
+
     /// Opens a file in append mode
     ///
     /// * Handles path expansion and absolute path resolution
     /// * Creates a file if it does not exist or appends to it if it does
+    /// * Returns a [`VfsWriter`] reporting the path and number of bytes written
     ///
     /// ### Errors
     /// * PathError::IsNotDir(PathBuf) when the given path's parent exists but is not a directory
@@ -128,8 +190,8 @@ impl VirtualFileSystem for Stdfs {
     /// assert_vfs_read_all!(vfs, &file, "foobar123");
     /// assert_vfs_remove_all!(vfs, &tmpdir);
     /// ```
-    fn append<T: AsRef<Path>>(&self, path: T) -> RvResult<Box<dyn Write>> {
-        Stdfs::append(path)
+    fn append<T: AsRef<Path>>(&self, path: T) -> RvResult<VfsWriter> {
+        self._hooked(VfsOp::Write, path.as_ref(), || Stdfs::append(&path))
     }
 
     /// Append the given data to to the target file
@@ -156,7 +218,7 @@ impl VirtualFileSystem for Stdfs {
     /// assert_vfs_remove_all!(vfs, &tmpdir);
     /// ```
     fn append_all<T: AsRef<Path>, U: AsRef<[u8]>>(&self, path: T, data: U) -> RvResult<()> {
-        Stdfs::append_all(path, data)
+        self._hooked(VfsOp::Write, path.as_ref(), || Stdfs::append_all(&path, data))
     }
 
     /// Append the given line to to the target file including a newline
@@ -183,7 +245,7 @@ impl VirtualFileSystem for Stdfs {
     /// assert_vfs_remove_all!(vfs, &tmpdir);
     /// ```
     fn append_line<T: AsRef<Path>, U: AsRef<str>>(&self, path: T, line: U) -> RvResult<()> {
-        Stdfs::append_line(path, line)
+        self._hooked(VfsOp::Write, path.as_ref(), || Stdfs::append_line(&path, line))
     }
 
     /// Append the given lines to to the target file including newlines
@@ -209,7 +271,7 @@ impl VirtualFileSystem for Stdfs {
     /// assert_vfs_remove_all!(vfs, &tmpdir);
     /// ```
     fn append_lines<T: AsRef<Path>, U: AsRef<str>>(&self, path: T, lines: &[U]) -> RvResult<()> {
-        Stdfs::append_lines(path, lines)
+        self._hooked(VfsOp::Write, path.as_ref(), || Stdfs::append_lines(&path, lines))
     }
 
     /// Change all file/dir permissions recursivly to `mode`
@@ -234,7 +296,7 @@ impl VirtualFileSystem for Stdfs {
     /// assert_vfs_remove_all!(vfs, &tmpdir);
     /// ```
     fn chmod<T: AsRef<Path>>(&self, path: T, mode: u32) -> RvResult<()> {
-        Stdfs::chmod(path, mode)
+        self._hooked(VfsOp::Chmod, path.as_ref(), || Stdfs::chmod(&path, mode))
     }
 
     /// Returns a new [`Chmod`] builder for advanced chmod options
@@ -263,7 +325,13 @@ impl VirtualFileSystem for Stdfs {
     /// assert_vfs_remove_all!(vfs, &tmpdir);
     /// ```
     fn chmod_b<T: AsRef<Path>>(&self, path: T) -> RvResult<Chmod> {
-        Stdfs::chmod_b(path)
+        let mut chmod = Stdfs::chmod_b(path)?;
+        let hooks = self._hooks();
+        if !hooks.is_empty() {
+            let exec = chmod.exec;
+            chmod.exec = Box::new(move |opts| hooks.exec(VfsOp::Chmod, &opts.path.clone(), || exec(opts)));
+        }
+        Ok(chmod)
     }
 
     /// Change the ownership of the path recursivly
@@ -283,7 +351,7 @@ impl VirtualFileSystem for Stdfs {
     /// assert_vfs_remove_all!(vfs, &tmpdir);
     /// ```
     fn chown<T: AsRef<Path>>(&self, path: T, uid: u32, gid: u32) -> RvResult<()> {
-        Stdfs::chown(path, uid, gid)
+        self._hooked(VfsOp::Chown, path.as_ref(), || Stdfs::chown(&path, uid, gid))
     }
 
     /// Creates new [`Chown`] for use with the builder pattern
@@ -303,7 +371,28 @@ impl VirtualFileSystem for Stdfs {
     /// assert_vfs_remove_all!(vfs, &tmpdir);
     /// ```
     fn chown_b<T: AsRef<Path>>(&self, path: T) -> RvResult<Chown> {
-        Stdfs::chown_b(path)
+        let mut chown = Stdfs::chown_b(path)?;
+        let hooks = self._hooks();
+        if !hooks.is_empty() {
+            let exec = chown.exec;
+            chown.exec = Box::new(move |opts| hooks.exec(VfsOp::Chown, &opts.path.clone(), || exec(opts)));
+        }
+        Ok(chown)
+    }
+
+    /// Returns the config of the Vfs backend instance
+    ///
+    /// * Set with `Stdfs::with_config` else the defaults apply
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::stdfs();
+    /// assert_eq!(vfs.config(), VfsConfig::default());
+    /// ```
+    fn config(&self) -> VfsConfig {
+        self.config
     }
 
     /// Returns the highest priority active configuration directory.
@@ -354,7 +443,7 @@ impl VirtualFileSystem for Stdfs {
     /// assert_vfs_remove_all!(vfs, &tmpdir);
     /// ```
     fn copy<T: AsRef<Path>, U: AsRef<Path>>(&self, src: T, dst: U) -> RvResult<()> {
-        Stdfs::copy(src, dst)
+        self._hooked(VfsOp::Write, dst.as_ref(), || Stdfs::copy(&src, &dst))
     }
 
     /// Creates a new [`Copier`] for use with the builder pattern
@@ -379,7 +468,13 @@ impl VirtualFileSystem for Stdfs {
     /// assert_vfs_remove_all!(vfs, &tmpdir);
     /// ```
     fn copy_b<T: AsRef<Path>, U: AsRef<Path>>(&self, src: T, dst: U) -> RvResult<Copier> {
-        Stdfs::copy_b(src, dst)
+        let mut copier = Stdfs::copy_b(src, dst)?;
+        let hooks = self._hooks();
+        if !hooks.is_empty() {
+            let exec = copier.exec;
+            copier.exec = Box::new(move |opts| hooks.exec(VfsOp::Write, &Stdfs::abs(&opts.dst)?, || exec(opts)));
+        }
+        Ok(copier)
     }
 
     /// Returns the current working directory
@@ -420,6 +515,30 @@ impl VirtualFileSystem for Stdfs {
         Stdfs::dirs(path)
     }
 
+    /// Creates a new [`DiskUsage`] for summing up the size of the given path much like `du`
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Recursively sums the apparent size of the files in the tree counting hard links once
+    /// * Options for excluding globs and following links
+    /// * Execute by calling `exec`
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_method_disk_usage");
+    /// let file1 = tmpdir.mash("file1");
+    /// let file2 = tmpdir.mash("dir/file2");
+    /// assert_vfs_mkdir_p!(vfs, tmpdir.mash("dir"));
+    /// assert_vfs_write_all!(vfs, &file1, "foo");
+    /// assert_vfs_write_all!(vfs, &file2, "foobar");
+    /// assert_eq!(vfs.disk_usage(&tmpdir).unwrap().exec().unwrap(), 9);
+    /// assert_vfs_remove_all!(vfs, &tmpdir);
+    /// ```
+    fn disk_usage<T: AsRef<Path>>(&self, path: T) -> RvResult<DiskUsage> {
+        Stdfs::disk_usage(path)
+    }
+
     /// Returns an iterator over the given path
     ///
     /// * Handles path expansion and absolute path resolution
@@ -437,7 +556,10 @@ impl VirtualFileSystem for Stdfs {
     /// assert_vfs_remove_all!(vfs, &tmpdir);
     /// ```
     fn entries<T: AsRef<Path>>(&self, path: T) -> RvResult<Entries> {
-        Stdfs::entries(path)
+        let mut entries = Stdfs::entries(path)?;
+        entries.max_descriptors = self.config.max_descriptors;
+        entries.max_content_size = self.config.max_content_size;
+        Ok(entries)
     }
 
     /// Return a virtual filesystem entry for the given path
@@ -456,63 +578,89 @@ impl VirtualFileSystem for Stdfs {
         Stdfs::entry(path)
     }
 
-    /// Returns true if the `path` exists
+    /// Ensures the given directory exists with the given mode and ownership
     ///
     /// * Handles path expansion and absolute path resolution
+    /// * Creates the directory and any parent directories needed if it doesn't exist
+    /// * Only changes the mode and ownership when they differ from those given
+    /// * Links are followed so the directories they point to are affected
+    /// * Returns true if anything was changed
+    ///
+    /// ### Errors
+    /// * PathError::IsNotDir(PathBuf) when the given path exists but is not a directory
     ///
     /// ### Examples
     /// ```
     /// use rivia::prelude::*;
     ///
-    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_method_exists");
-    /// assert_vfs_exists!(vfs, &tmpdir);
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_method_ensure_dir");
+    /// let dir = tmpdir.mash("dir");
+    /// let (uid, gid) = vfs.owner(&tmpdir).unwrap();
+    /// assert_eq!(vfs.ensure_dir(&dir, 0o700, uid, gid).unwrap(), true);
+    /// assert_eq!(vfs.ensure_dir(&dir, 0o700, uid, gid).unwrap(), false);
+    /// assert_eq!(vfs.mode(&dir).unwrap(), 0o40700);
     /// assert_vfs_remove_all!(vfs, &tmpdir);
-    /// assert_vfs_no_exists!(vfs, &tmpdir);
     /// ```
-    fn exists<T: AsRef<Path>>(&self, path: T) -> bool {
-        Stdfs::exists(path)
+    fn ensure_dir<T: AsRef<Path>>(&self, path: T, mode: u32, uid: u32, gid: u32) -> RvResult<bool> {
+        Stdfs::ensure_dir(path, mode, uid, gid)
     }
 
-    /// Returns all files for the given path, sorted by name
+    /// Ensures the given file exists with the given data and mode
     ///
     /// * Handles path expansion and absolute path resolution
-    /// * Paths are returned as abs paths
-    /// * Doesn't include the path itself only its children nor is this recursive
+    /// * Only writes the data when it differs from the file's current content
+    /// * Only changes the mode when it differs from the one given
+    /// * Links are followed so the files they point to are affected
+    /// * Returns true if anything was changed
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path's parent doesn't exist
+    /// * PathError::IsNotFile(PathBuf) when the given path exists but is not a file
     ///
     /// ### Examples
     /// ```
     /// use rivia::prelude::*;
     ///
-    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_method_files");
-    /// let dir = tmpdir.mash("dir");
-    /// let file1 = tmpdir.mash("file1");
-    /// let file2 = tmpdir.mash("file2");
-    /// assert_vfs_mkdir_p!(vfs, &dir);
-    /// assert_vfs_mkfile!(vfs, &file1);
-    /// assert_vfs_mkfile!(vfs, &file2);
-    /// assert_iter_eq(vfs.files(&tmpdir).unwrap(), vec![file1, file2]);
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_method_ensure_file");
+    /// let file = tmpdir.mash("file");
+    /// assert_eq!(vfs.ensure_file(&file, "foo", 0o600).unwrap(), true);
+    /// assert_eq!(vfs.ensure_file(&file, "foo", 0o600).unwrap(), false);
+    /// assert_vfs_read_all!(vfs, &file, "foo");
+    /// assert_eq!(vfs.mode(&file).unwrap(), 0o100600);
     /// assert_vfs_remove_all!(vfs, &tmpdir);
     /// ```
-    fn files<T: AsRef<Path>>(&self, path: T) -> RvResult<Vec<PathBuf>> {
-        Stdfs::files(path)
+    fn ensure_file<T: AsRef<Path>, U: AsRef<[u8]>>(&self, path: T, data: U, mode: u32) -> RvResult<bool> {
+        Stdfs::ensure_file(path, data, mode)
     }
 
-    /// Returns the group ID of the owner of this file
+    /// Ensures the given link exists and points to the given target
     ///
     /// * Handles path expansion and absolute path resolution
+    /// * Creates the link if it doesn't exist
+    /// * Replaces the link atomically if it points somewhere else
+    /// * Dangling links are supported
+    /// * Returns true if anything was changed
+    ///
+    /// ### Errors
+    /// * PathError::IsNotSymlink(PathBuf) when the given link path exists but is not a link
     ///
     /// ### Examples
     /// ```
     /// use rivia::prelude::*;
     ///
-    /// let vfs = Vfs::stdfs();
-    /// assert_eq!(Stdfs::gid(vfs.root()).unwrap(), 0);
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_method_ensure_symlink");
+    /// let link = tmpdir.mash("link");
+    /// assert_eq!(vfs.ensure_symlink(&link, "file1").unwrap(), true);
+    /// assert_eq!(vfs.ensure_symlink(&link, "file1").unwrap(), false);
+    /// assert_eq!(vfs.ensure_symlink(&link, "file2").unwrap(), true);
+    /// assert_eq!(vfs.readlink(&link).unwrap(), PathBuf::from("file2"));
+    /// assert_vfs_remove_all!(vfs, &tmpdir);
     /// ```
-    fn gid<T: AsRef<Path>>(&self, path: T) -> RvResult<u32> {
-        Stdfs::gid(path)
+    fn ensure_symlink<T: AsRef<Path>, U: AsRef<Path>>(&self, link: T, target: U) -> RvResult<bool> {
+        Stdfs::ensure_symlink(link, target)
     }
 
-    /// Returns true if the given path exists and is readonly
+    /// Returns true if the `path` exists
     ///
     /// * Handles path expansion and absolute path resolution
     ///
@@ -520,245 +668,716 @@ impl VirtualFileSystem for Stdfs {
     /// ```
     /// use rivia::prelude::*;
     ///
-    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_method_is_exec");
-    /// let file = tmpdir.mash("file");
-    /// assert!(Stdfs::mkfile_m(&file, 0o644).is_ok());
-    /// assert_eq!(Stdfs::is_exec(&file), false);
-    /// assert!(Stdfs::chmod(&file, 0o777).is_ok());
-    /// assert_eq!(Stdfs::is_exec(&file), true);
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_method_exists");
+    /// assert_vfs_exists!(vfs, &tmpdir);
     /// assert_vfs_remove_all!(vfs, &tmpdir);
+    /// assert_vfs_no_exists!(vfs, &tmpdir);
     /// ```
-    fn is_exec<T: AsRef<Path>>(&self, path: T) -> bool {
-        Stdfs::is_exec(path)
+    fn exists<T: AsRef<Path>>(&self, path: T) -> bool {
+        Stdfs::exists(path)
     }
 
-    /// Returns true if the given path exists and is a directory
+    /// Returns whether each of the given `paths` exists in the same order
     ///
     /// * Handles path expansion and absolute path resolution
-    /// * Provides link exclusion i.e. links even if pointing to a directory return false
+    /// * Checks all paths in a single pass e.g. Memfs only acquires its lock once
     ///
     /// ### Examples
     /// ```
     /// use rivia::prelude::*;
     ///
-    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_method_is_dir");
-    /// assert_vfs_is_dir!(vfs, &tmpdir);
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_method_exists_all");
+    /// assert_eq!(vfs.exists_all(&[tmpdir.mash("foo"), tmpdir.clone()]), vec![false, true]);
     /// assert_vfs_remove_all!(vfs, &tmpdir);
-    /// assert_vfs_no_dir!(vfs, &tmpdir);
     /// ```
-    fn is_dir<T: AsRef<Path>>(&self, path: T) -> bool {
-        Stdfs::is_dir(path)
+    fn exists_all<T: AsRef<Path>>(&self, paths: &[T]) -> Vec<bool> {
+        Stdfs::exists_all(paths)
     }
 
-    /// Returns true if the given path exists and is a file
+    /// Returns all files for the given path, sorted by name
     ///
     /// * Handles path expansion and absolute path resolution
-    /// * Provides link exclusion i.e. links even if pointing to a file return false
+    /// * Paths are returned as abs paths
+    /// * Doesn't include the path itself only its children nor is this recursive
     ///
     /// ### Examples
     /// ```
     /// use rivia::prelude::*;
     ///
-    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_method_is_file");
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_method_files");
+    /// let dir = tmpdir.mash("dir");
     /// let file1 = tmpdir.mash("file1");
-    /// assert_vfs_no_file!(vfs, &file1);
+    /// let file2 = tmpdir.mash("file2");
+    /// assert_vfs_mkdir_p!(vfs, &dir);
     /// assert_vfs_mkfile!(vfs, &file1);
-    /// assert_vfs_is_file!(vfs, &file1);
+    /// assert_vfs_mkfile!(vfs, &file2);
+    /// assert_iter_eq(vfs.files(&tmpdir).unwrap(), vec![file1, file2]);
     /// assert_vfs_remove_all!(vfs, &tmpdir);
     /// ```
-    fn is_file<T: AsRef<Path>>(&self, path: T) -> bool {
-        Stdfs::is_file(path)
+    fn files<T: AsRef<Path>>(&self, path: T) -> RvResult<Vec<PathBuf>> {
+        Stdfs::files(path)
     }
 
-    /// Returns true if the given path exists and is readonly
+    /// Returns the absolute path of the first of the given `paths` that exists
     ///
     /// * Handles path expansion and absolute path resolution
+    /// * Checks all paths in a single pass stopping at the first that exists
     ///
-    /// ### Example
+    /// ### Examples
     /// ```
     /// use rivia::prelude::*;
     ///
-    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_method_is_readonly");
-    /// let file = tmpdir.mash("file1");
-    /// assert!(vfs.mkfile_m(&file, 0o644).is_ok());
-    /// assert_eq!(vfs.is_readonly(&file), false);
-    /// assert!(vfs.chmod_b(&file).unwrap().readonly().exec().is_ok());
-    /// assert_eq!(vfs.mode(&file).unwrap(), 0o100444);
-    /// assert_eq!(vfs.is_readonly(&file), true);
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_method_first_existing");
+    /// assert_eq!(vfs.first_existing(&[tmpdir.mash("foo"), tmpdir.clone()]), Some(tmpdir.clone()));
     /// assert_vfs_remove_all!(vfs, &tmpdir);
     /// ```
-    fn is_readonly<T: AsRef<Path>>(&self, path: T) -> bool {
-        Stdfs::is_readonly(path)
+    fn first_existing<T: AsRef<Path>>(&self, paths: &[T]) -> Option<PathBuf> {
+        Stdfs::first_existing(paths)
     }
 
-    /// Returns true if the given path exists and is a symlink
+    /// Returns the POSIX access control list of the given path
     ///
     /// * Handles path expansion and absolute path resolution
-    /// * Checks the path itself and not what is potentially pointed to
+    /// * Links are followed
+    /// * Paths without an ACL of their own return the minimal ACL matching their mode
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
     ///
     /// ### Examples
     /// ```
     /// use rivia::prelude::*;
     ///
-    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_method_is_symlink");
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_method_get_acl");
     /// let file = tmpdir.mash("file");
-    /// let link = tmpdir.mash("link");
-    /// assert_vfs_mkfile!(vfs, &file);
-    /// assert_eq!(vfs.is_symlink(&link), false);
-    /// assert_vfs_symlink!(vfs, &link, &file);
-    /// assert_eq!(vfs.is_symlink(&link), true);
+    /// assert!(vfs.mkfile_m(&file, 0o640).is_ok());
+    /// assert_eq!(vfs.get_acl(&file).unwrap(), Acl::from_mode(0o640));
     /// assert_vfs_remove_all!(vfs, &tmpdir);
     /// ```
-    fn is_symlink<T: AsRef<Path>>(&self, path: T) -> bool {
-        Stdfs::is_symlink(path)
+    fn get_acl<T: AsRef<Path>>(&self, path: T) -> RvResult<Acl> {
+        Stdfs::get_acl(path)
     }
 
-    /// Returns true if the given path exists and is a symlink pointing to a directory
+    /// Returns the SELinux security context of the given path
     ///
     /// * Handles path expansion and absolute path resolution
-    /// * Checks the path itself and what it points to
+    /// * Links aren't followed so that the context of the link itself is returned
+    /// * Returns None when the path isn't labeled e.g. SELinux isn't supported by the filesystem
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
     ///
     /// ### Examples
     /// ```
     /// use rivia::prelude::*;
     ///
-    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_func_is_symlink_dir");
-    /// let dir = tmpdir.mash("dir");
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_method_get_selinux_context");
     /// let file = tmpdir.mash("file");
-    /// let link1 = tmpdir.mash("link1");
-    /// let link2 = tmpdir.mash("link2");
-    /// assert_vfs_mkdir_p!(vfs, &dir);
     /// assert_vfs_mkfile!(vfs, &file);
-    /// assert_vfs_symlink!(vfs, &link1, &dir);
-    /// assert_vfs_symlink!(vfs, &link2, &file);
-    /// assert_eq!(vfs.is_symlink_dir(&link1), true);
-    /// assert_eq!(vfs.is_symlink_dir(&link2), false);
+    /// assert!(vfs.get_selinux_context(&file).is_ok());
     /// assert_vfs_remove_all!(vfs, &tmpdir);
     /// ```
-    fn is_symlink_dir<T: AsRef<Path>>(&self, path: T) -> bool {
-        Stdfs::is_symlink_dir(path)
+    #[cfg(feature = "selinux")]
+    fn get_selinux_context<T: AsRef<Path>>(&self, path: T) -> RvResult<Option<String>> {
+        Stdfs::get_selinux_context(path)
     }
 
-    /// Returns true if the given path exists and is a symlink pointing to a file
+    /// Returns the group ID of the owner of this file
     ///
     /// * Handles path expansion and absolute path resolution
-    /// * Checks the path itself and what it points to
     ///
     /// ### Examples
     /// ```
     /// use rivia::prelude::*;
     ///
-    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_func_is_symlink_file");
-    /// let dir = tmpdir.mash("dir");
-    /// let file = tmpdir.mash("file");
-    /// let link1 = tmpdir.mash("link1");
-    /// let link2 = tmpdir.mash("link2");
-    /// assert_vfs_mkdir_p!(vfs, &dir);
-    /// assert_vfs_mkfile!(vfs, &file);
-    /// assert_vfs_symlink!(vfs, &link1, &dir);
-    /// assert_vfs_symlink!(vfs, &link2, &file);
-    /// assert_eq!(vfs.is_symlink_file(&link1), false);
-    /// assert_eq!(vfs.is_symlink_file(&link2), true);
-    /// assert_vfs_remove_all!(vfs, &tmpdir);
+    /// let vfs = Vfs::stdfs();
+    /// assert_eq!(Stdfs::gid(vfs.root()).unwrap(), 0);
     /// ```
-    fn is_symlink_file<T: AsRef<Path>>(&self, path: T) -> bool {
-        Stdfs::is_symlink_file(path)
+    fn gid<T: AsRef<Path>>(&self, path: T) -> RvResult<u32> {
+        Stdfs::gid(path)
     }
 
-    /// Creates the given directory and any parent directories needed with the given mode
+    /// Returns the paths matching the given glob pattern sorted by path
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * `*` matches any sequence of characters within a single path component
+    /// * `**` matches any sequence of characters across path components
+    /// * `?` matches any single character and `[abc]`, `[a-z]` or `[!a-z]` match a character class
+    /// * Paths are returned in absolute form and an empty list is returned when nothing matches
+    /// * Links aren't followed and directories that can't be read are skipped
     ///
     /// ### Examples
     /// ```
     /// use rivia::prelude::*;
     ///
-    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_method_mkdir_m");
-    /// let dir1 = tmpdir.mash("dir1");
-    /// assert!(vfs.mkdir_m(&dir1, 0o555).is_ok());
-    /// assert_eq!(vfs.mode(&dir1).unwrap(), 0o40555);
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_method_glob");
+    /// let dir = tmpdir.mash("dir");
+    /// let file1 = dir.mash("file1.conf");
+    /// let file2 = dir.mash("sub/file2.conf");
+    /// assert_vfs_mkdir_p!(vfs, dir.mash("sub"));
+    /// assert_vfs_mkfile!(vfs, &file1);
+    /// assert_vfs_mkfile!(vfs, &file2);
+    /// assert_eq!(vfs.glob(dir.mash("*.conf").to_string().unwrap()).unwrap(), vec![file1.clone()]);
+    /// assert_eq!(vfs.glob(dir.mash("**/*.conf").to_string().unwrap()).unwrap(), vec![file1, file2]);
     /// assert_vfs_remove_all!(vfs, &tmpdir);
     /// ```
-    fn mkdir_m<T: AsRef<Path>>(&self, path: T, mode: u32) -> RvResult<PathBuf> {
-        Stdfs::mkdir_m(path, mode)
+    fn glob<T: AsRef<str>>(&self, pattern: T) -> RvResult<Vec<PathBuf>> {
+        Stdfs::glob(pattern)
     }
 
-    /// Creates the given directory and any parent directories needed
+    /// Creates a new hard link `dst` giving the data of the `src` file another name
     ///
     /// * Handles path expansion and absolute path resolution
+    /// * Links aren't followed i.e. a link to a symlink is a second name for the symlink itself
+    /// * Returns the link path
     ///
     /// ### Errors
-    /// * io::Error if its unable to create the directory
-    /// * PathError::IsNotDir(PathBuf) when the path already exists and is not a directory
+    /// * PathError::DoesNotExist(PathBuf) when `src` or the parent of `dst` doesn't exist
+    /// * PathError::IsNotFile(PathBuf) when `src` is a directory
+    /// * PathError::ExistsAlready(PathBuf) when `dst` already exists
     ///
     /// ### Examples
     /// ```
     /// use rivia::prelude::*;
     ///
-    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_method_mkdir_p");
-    /// let dir1 = tmpdir.mash("dir1");
-    /// assert_vfs_no_dir!(vfs, &dir1);
-    /// assert_vfs_mkdir_p!(vfs, &dir1);
-    /// assert_vfs_is_dir!(vfs, &dir1);
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_method_hardlink");
+    /// let file = tmpdir.mash("file");
+    /// let link = tmpdir.mash("link");
+    /// assert_vfs_write_all!(vfs, &file, "foobar");
+    /// assert_eq!(vfs.hardlink(&file, &link).unwrap(), link);
+    /// assert_vfs_read_all!(vfs, &link, "foobar");
     /// assert_vfs_remove_all!(vfs, &tmpdir);
     /// ```
-    fn mkdir_p<T: AsRef<Path>>(&self, path: T) -> RvResult<PathBuf> {
-        Stdfs::mkdir_p(path)
+    fn hardlink<T: AsRef<Path>, U: AsRef<Path>>(&self, src: T, dst: U) -> RvResult<PathBuf> {
+        self._hooked(VfsOp::Create, dst.as_ref(), || Stdfs::hardlink(&src, &dst))
     }
 
-    /// Create an empty file similar to the linux touch command
-    ///
-    /// * Handles path expansion and absolute path resolution
-    /// * Default file creation permissions 0o666 with umask usually ends up being 0o644
+    /// Register a hook to be called after every successful mutating operation
     ///
-    /// ### Errors
-    /// * PathError::DoesNotExist(PathBuf) when the given path's parent doesn't exist
-    /// * PathError::IsNotDir(PathBuf) when the given path's parent isn't a directory
-    /// * PathError::IsNotFile(PathBuf) when the given path exists but isn't a file
+    /// * Hooks are given the operation's [`VfsOp`] classification and the absolute path
+    /// * Hooks are called in the order they were registered
+    /// * Useful for logging or tracing the filesystem effects of a function under test
     ///
     /// ### Examples
     /// ```
+    /// use std::sync::Mutex;
+    ///
     /// use rivia::prelude::*;
     ///
-    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_method_mkfile");
-    /// let file1 = tmpdir.mash("file1");
-    /// assert_vfs_no_file!(vfs, &file1);
-    /// assert_vfs_mkfile!(vfs, &file1);
-    /// assert_vfs_is_file!(vfs, &file1);
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_method_hook_post");
+    /// let ops = Arc::new(Mutex::new(vec![]));
+    /// let ops_clone = ops.clone();
+    /// vfs.hook_post(move |op, path| ops_clone.lock().unwrap().push((op, path.to_path_buf())));
+    /// let file = tmpdir.mash("file");
+    /// assert_vfs_mkfile!(vfs, &file);
+    /// assert_eq!(*ops.lock().unwrap(), vec![(VfsOp::Create, file)]);
     /// assert_vfs_remove_all!(vfs, &tmpdir);
     /// ```
-    fn mkfile<T: AsRef<Path>>(&self, path: T) -> RvResult<PathBuf> {
-        Stdfs::mkfile(path)
+    fn hook_post<F: Fn(VfsOp, &Path) + Send + Sync + 'static>(&self, hook: F) {
+        self._hook_post(Arc::new(hook))
     }
 
-    /// Wraps `mkfile` allowing for setting the file's mode
+    /// Register a hook to be called before every mutating operation with the ability to veto it
+    ///
+    /// * Hooks are given the operation's [`VfsOp`] classification and the absolute path
+    /// * Hooks are called in the order they were registered
+    /// * Returning an error from a hook vetoes the operation and is passed back to the caller
+    /// * Operations implicating multiple paths e.g. `move_p` call the hooks once for each path
     ///
     /// ### Examples
     /// ```
     /// use rivia::prelude::*;
     ///
-    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_method_mkfile_m");
-    /// let file1 = tmpdir.mash("file1");
-    /// assert!(vfs.mkfile_m(&file1, 0o555).is_ok());
-    /// assert_eq!(vfs.mode(&file1).unwrap(), 0o100555);
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_method_hook_pre");
+    /// let dir = tmpdir.mash("dir");
+    /// let denied = dir.clone();
+    /// vfs.hook_pre(move |_, path| match path.starts_with(&denied) {
+    ///     true => Err(VfsError::Unavailable.into()),
+    ///     false => Ok(()),
+    /// });
+    /// assert!(vfs.mkdir_p(&dir).is_err());
+    /// assert_vfs_no_exists!(vfs, &dir);
     /// assert_vfs_remove_all!(vfs, &tmpdir);
     /// ```
-    fn mkfile_m<T: AsRef<Path>>(&self, path: T, mode: u32) -> RvResult<PathBuf> {
-        Stdfs::mkfile_m(path, mode)
+    fn hook_pre<F: Fn(VfsOp, &Path) -> RvResult<()> + Send + Sync + 'static>(&self, hook: F) {
+        self._hook_pre(Arc::new(hook))
     }
 
-    /// Returns the permissions for a file
+    /// Register a hook to be called after every mutating operation slower than the given duration
+    ///
+    /// * Hooks are given the operation's [`VfsOp`] classification, the absolute path and the time
+    ///   the operation took e.g. to report operations stalling on a hung network filesystem
+    /// * Covers the same mutating operations as the pre and post hooks, reads and stats aren't timed
+    /// * Hooks are called once the operation returns, whether it succeeded or not, and can neither
+    ///   interrupt nor fail it so an operation that never returns is never reported
     ///
     /// ### Examples
     /// ```
+    /// use std::{sync::Mutex, time::Duration};
+    ///
     /// use rivia::prelude::*;
     ///
-    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_method_mode");
-    /// let file1 = tmpdir.mash("file1");
-    /// assert!(Stdfs::mkfile_m(&file1, 0o555).is_ok());
-    /// assert_eq!(vfs.mode(&file1).unwrap(), 0o100555);
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_method_hook_slow");
+    /// let ops = Arc::new(Mutex::new(vec![]));
+    /// let ops_clone = ops.clone();
+    /// vfs.hook_slow(Duration::ZERO, move |op, path, _| ops_clone.lock().unwrap().push((op, path.to_path_buf())));
+    /// let file = tmpdir.mash("file");
+    /// assert_vfs_mkfile!(vfs, &file);
+    /// assert_eq!(*ops.lock().unwrap(), vec![(VfsOp::Create, file)]);
     /// assert_vfs_remove_all!(vfs, &tmpdir);
     /// ```
-    fn mode<T: AsRef<Path>>(&self, path: T) -> RvResult<u32> {
-        Stdfs::mode(path)
+    fn hook_slow<F: Fn(VfsOp, &Path, Duration) + Send + Sync + 'static>(&self, after: Duration, hook: F) {
+        self._hook_slow(after, Arc::new(hook))
+    }
+
+    /// Creates a new [`Installer`] for use with the builder pattern
+    ///
+    /// * `dst` will be installed into if it is an existing directory
+    /// * `dst` will be the installed file if it isn't an existing directory
+    /// * Handles path expansion and absolute path resolution
+    /// * Options for mode, ownership, backups and stripping leading source directories
+    /// * Execute by calling `exec`
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_method_install");
+    /// let file1 = tmpdir.mash("file1");
+    /// let file2 = tmpdir.mash("dir/file2");
+    /// assert_vfs_write_all!(vfs, &file1, "this is a test");
+    /// assert!(vfs.install(&file1, &file2).unwrap().mode(0o700).exec().is_ok());
+    /// assert_vfs_read_all!(vfs, &file2, "this is a test");
+    /// assert_eq!(vfs.mode(&file2).unwrap(), 0o100700);
+    /// assert_vfs_remove_all!(vfs, &tmpdir);
+    /// ```
+    fn install<T: AsRef<Path>, U: AsRef<Path>>(&self, src: T, dst: U) -> RvResult<Installer> {
+        let mut installer = Stdfs::install(src, dst)?;
+        let hooks = self._hooks();
+        if !hooks.is_empty() {
+            let exec = installer.exec;
+            installer.exec = Box::new(move |opts| hooks.exec(VfsOp::Write, &Stdfs::abs(&opts.dst)?, || exec(opts)));
+        }
+        Ok(installer)
+    }
+
+    /// Returns true if the given path exists and is readonly
+    ///
+    /// * Handles path expansion and absolute path resolution
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_method_is_exec");
+    /// let file = tmpdir.mash("file");
+    /// assert!(Stdfs::mkfile_m(&file, 0o644).is_ok());
+    /// assert_eq!(Stdfs::is_exec(&file), false);
+    /// assert!(Stdfs::chmod(&file, 0o777).is_ok());
+    /// assert_eq!(Stdfs::is_exec(&file), true);
+    /// assert_vfs_remove_all!(vfs, &tmpdir);
+    /// ```
+    fn is_exec<T: AsRef<Path>>(&self, path: T) -> bool {
+        Stdfs::is_exec(path)
+    }
+
+    /// Returns true if the given path exists and is a directory
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Provides link exclusion i.e. links even if pointing to a directory return false
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_method_is_dir");
+    /// assert_vfs_is_dir!(vfs, &tmpdir);
+    /// assert_vfs_remove_all!(vfs, &tmpdir);
+    /// assert_vfs_no_dir!(vfs, &tmpdir);
+    /// ```
+    fn is_dir<T: AsRef<Path>>(&self, path: T) -> bool {
+        Stdfs::is_dir(path)
+    }
+
+    /// Returns true if the given path exists and is a file
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Provides link exclusion i.e. links even if pointing to a file return false
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_method_is_file");
+    /// let file1 = tmpdir.mash("file1");
+    /// assert_vfs_no_file!(vfs, &file1);
+    /// assert_vfs_mkfile!(vfs, &file1);
+    /// assert_vfs_is_file!(vfs, &file1);
+    /// assert_vfs_remove_all!(vfs, &tmpdir);
+    /// ```
+    fn is_file<T: AsRef<Path>>(&self, path: T) -> bool {
+        Stdfs::is_file(path)
+    }
+
+    /// Returns true if the given path exists and is a hard link i.e. its data has more than one name
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Links aren't followed
+    /// * As all names of the data are equal the original file is a hard link as well
+    /// * Directories always return false
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_method_is_hardlink");
+    /// let file = tmpdir.mash("file");
+    /// assert_vfs_mkfile!(vfs, &file);
+    /// assert_eq!(vfs.is_hardlink(&file), false);
+    /// assert_vfs_hardlink!(vfs, &file, tmpdir.mash("link"));
+    /// assert_eq!(vfs.is_hardlink(&file), true);
+    /// assert_vfs_remove_all!(vfs, &tmpdir);
+    /// ```
+    fn is_hardlink<T: AsRef<Path>>(&self, path: T) -> bool {
+        Stdfs::is_hardlink(path)
+    }
+
+    /// Returns true if the given path exists and is immutable i.e. `chattr +i`
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Links are followed
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_method_is_immutable");
+    /// let file = tmpdir.mash("file");
+    /// assert_vfs_mkfile!(vfs, &file);
+    /// assert_eq!(vfs.is_immutable(&file), false);
+    /// assert_vfs_remove_all!(vfs, &tmpdir);
+    /// ```
+    fn is_immutable<T: AsRef<Path>>(&self, path: T) -> bool {
+        Stdfs::is_immutable(path)
+    }
+
+    /// Returns true if the given path exists and is readonly
+    ///
+    /// * Handles path expansion and absolute path resolution
+    ///
+    /// ### Example
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_method_is_readonly");
+    /// let file = tmpdir.mash("file1");
+    /// assert!(vfs.mkfile_m(&file, 0o644).is_ok());
+    /// assert_eq!(vfs.is_readonly(&file), false);
+    /// assert!(vfs.chmod_b(&file).unwrap().readonly().exec().is_ok());
+    /// assert_eq!(vfs.mode(&file).unwrap(), 0o100444);
+    /// assert_eq!(vfs.is_readonly(&file), true);
+    /// assert_vfs_remove_all!(vfs, &tmpdir);
+    /// ```
+    fn is_readonly<T: AsRef<Path>>(&self, path: T) -> bool {
+        Stdfs::is_readonly(path)
+    }
+
+    /// Returns true if the given path exists and is a symlink
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Checks the path itself and not what is potentially pointed to
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_method_is_symlink");
+    /// let file = tmpdir.mash("file");
+    /// let link = tmpdir.mash("link");
+    /// assert_vfs_mkfile!(vfs, &file);
+    /// assert_eq!(vfs.is_symlink(&link), false);
+    /// assert_vfs_symlink!(vfs, &link, &file);
+    /// assert_eq!(vfs.is_symlink(&link), true);
+    /// assert_vfs_remove_all!(vfs, &tmpdir);
+    /// ```
+    fn is_symlink<T: AsRef<Path>>(&self, path: T) -> bool {
+        Stdfs::is_symlink(path)
+    }
+
+    /// Returns true if the given path exists and is a symlink pointing to a directory
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Checks the path itself and what it points to
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_func_is_symlink_dir");
+    /// let dir = tmpdir.mash("dir");
+    /// let file = tmpdir.mash("file");
+    /// let link1 = tmpdir.mash("link1");
+    /// let link2 = tmpdir.mash("link2");
+    /// assert_vfs_mkdir_p!(vfs, &dir);
+    /// assert_vfs_mkfile!(vfs, &file);
+    /// assert_vfs_symlink!(vfs, &link1, &dir);
+    /// assert_vfs_symlink!(vfs, &link2, &file);
+    /// assert_eq!(vfs.is_symlink_dir(&link1), true);
+    /// assert_eq!(vfs.is_symlink_dir(&link2), false);
+    /// assert_vfs_remove_all!(vfs, &tmpdir);
+    /// ```
+    fn is_symlink_dir<T: AsRef<Path>>(&self, path: T) -> bool {
+        Stdfs::is_symlink_dir(path)
+    }
+
+    /// Returns true if the given path exists and is a symlink pointing to a file
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Checks the path itself and what it points to
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_func_is_symlink_file");
+    /// let dir = tmpdir.mash("dir");
+    /// let file = tmpdir.mash("file");
+    /// let link1 = tmpdir.mash("link1");
+    /// let link2 = tmpdir.mash("link2");
+    /// assert_vfs_mkdir_p!(vfs, &dir);
+    /// assert_vfs_mkfile!(vfs, &file);
+    /// assert_vfs_symlink!(vfs, &link1, &dir);
+    /// assert_vfs_symlink!(vfs, &link2, &file);
+    /// assert_eq!(vfs.is_symlink_file(&link1), false);
+    /// assert_eq!(vfs.is_symlink_file(&link2), true);
+    /// assert_vfs_remove_all!(vfs, &tmpdir);
+    /// ```
+    fn is_symlink_file<T: AsRef<Path>>(&self, path: T) -> bool {
+        Stdfs::is_symlink_file(path)
+    }
+
+    /// Returns the number of lines in the given file without reading it all into memory
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Counts a final line without a trailing newline i.e. matches the length of `read_lines`
+    ///
+    /// ### Errors
+    /// * PathError::IsNotFile(PathBuf) when the given path isn't a file
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_method_line_count");
+    /// let file = tmpdir.mash("file");
+    /// assert_vfs_write_all!(vfs, &file, "1\n2");
+    /// assert_eq!(vfs.line_count(&file).unwrap(), 2);
+    /// assert_vfs_remove_all!(vfs, &tmpdir);
+    /// ```
+    fn line_count<T: AsRef<Path>>(&self, path: T) -> RvResult<u64> {
+        Stdfs::line_count(path)
+    }
+
+    /// Returns the filesystem properties of the given path with a single lookup
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Links report the properties of the target they point to except for `link`
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_method_metadata");
+    /// let file = tmpdir.mash("file");
+    /// assert_vfs_write_all!(vfs, &file, "foobar");
+    /// let meta = vfs.metadata(&file).unwrap();
+    /// assert_eq!((meta.file, meta.size, meta.nlink), (true, 6, 1));
+    /// assert_vfs_remove_all!(vfs, &tmpdir);
+    /// ```
+    fn metadata<T: AsRef<Path>>(&self, path: T) -> RvResult<VfsMetadata> {
+        Stdfs::metadata(path)
+    }
+
+    /// Creates the given directory and any parent directories needed with the given mode
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_method_mkdir_m");
+    /// let dir1 = tmpdir.mash("dir1");
+    /// assert!(vfs.mkdir_m(&dir1, 0o555).is_ok());
+    /// assert_eq!(vfs.mode(&dir1).unwrap(), 0o40555);
+    /// assert_vfs_remove_all!(vfs, &tmpdir);
+    /// ```
+    fn mkdir_m<T: AsRef<Path>>(&self, path: T, mode: u32) -> RvResult<PathBuf> {
+        self._hooked(VfsOp::Create, path.as_ref(), || Stdfs::mkdir_m(&path, mode))
+    }
+
+    /// Creates the given directory and any parent directories needed
+    ///
+    /// * Handles path expansion and absolute path resolution
+    ///
+    /// ### Errors
+    /// * io::Error if its unable to create the directory
+    /// * PathError::IsNotDir(PathBuf) when the path already exists and is not a directory
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_method_mkdir_p");
+    /// let dir1 = tmpdir.mash("dir1");
+    /// assert_vfs_no_dir!(vfs, &dir1);
+    /// assert_vfs_mkdir_p!(vfs, &dir1);
+    /// assert_vfs_is_dir!(vfs, &dir1);
+    /// assert_vfs_remove_all!(vfs, &tmpdir);
+    /// ```
+    fn mkdir_p<T: AsRef<Path>>(&self, path: T) -> RvResult<PathBuf> {
+        self._hooked(VfsOp::Create, path.as_ref(), || Stdfs::mkdir_p(&path))
+    }
+
+    /// Wraps `mkdir_p` reporting whether the directory was created or already existed
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Creation is attempted first so a concurrent creator doesn't cause an error
+    ///
+    /// ### Errors
+    /// * PathError::IsNotDir(PathBuf) when the path already exists and is not a directory
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_method_mkdir_p_ensured");
+    /// let dir = tmpdir.mash("dir");
+    /// assert_eq!(vfs.mkdir_p_ensured(&dir).unwrap(), Ensured::Created(dir.clone()));
+    /// assert_eq!(vfs.mkdir_p_ensured(&dir).unwrap(), Ensured::Existed(dir.clone()));
+    /// assert_vfs_remove_all!(vfs, &tmpdir);
+    /// ```
+    fn mkdir_p_ensured<T: AsRef<Path>>(&self, path: T) -> RvResult<Ensured> {
+        self._hooked(VfsOp::Create, path.as_ref(), || Stdfs::mkdir_p_ensured(&path))
+    }
+
+    /// Create an empty file similar to the linux touch command
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Default file creation permissions 0o666 with umask usually ends up being 0o644
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path's parent doesn't exist
+    /// * PathError::IsNotDir(PathBuf) when the given path's parent isn't a directory
+    /// * PathError::IsNotFile(PathBuf) when the given path exists but isn't a file
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_method_mkfile");
+    /// let file1 = tmpdir.mash("file1");
+    /// assert_vfs_no_file!(vfs, &file1);
+    /// assert_vfs_mkfile!(vfs, &file1);
+    /// assert_vfs_is_file!(vfs, &file1);
+    /// assert_vfs_remove_all!(vfs, &tmpdir);
+    /// ```
+    fn mkfile<T: AsRef<Path>>(&self, path: T) -> RvResult<PathBuf> {
+        self._hooked(VfsOp::Create, path.as_ref(), || Stdfs::mkfile(&path))
+    }
+
+    /// Wraps `mkfile` reporting whether the file was created or already existed
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Creation is attempted first so a concurrent creator doesn't cause an error
+    /// * Links to files are considered to be files
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path's parent doesn't exist
+    /// * PathError::IsNotDir(PathBuf) when the given path's parent isn't a directory
+    /// * PathError::IsNotFile(PathBuf) when the given path exists but isn't a file
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_method_mkfile_ensured");
+    /// let file = tmpdir.mash("file");
+    /// assert_eq!(vfs.mkfile_ensured(&file).unwrap(), Ensured::Created(file.clone()));
+    /// assert_eq!(vfs.mkfile_ensured(&file).unwrap(), Ensured::Existed(file.clone()));
+    /// assert_vfs_remove_all!(vfs, &tmpdir);
+    /// ```
+    fn mkfile_ensured<T: AsRef<Path>>(&self, path: T) -> RvResult<Ensured> {
+        self._hooked(VfsOp::Create, path.as_ref(), || Stdfs::mkfile_ensured(&path))
+    }
+
+    /// Wraps `mkfile` allowing for setting the file's mode
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_method_mkfile_m");
+    /// let file1 = tmpdir.mash("file1");
+    /// assert!(vfs.mkfile_m(&file1, 0o555).is_ok());
+    /// assert_eq!(vfs.mode(&file1).unwrap(), 0o100555);
+    /// assert_vfs_remove_all!(vfs, &tmpdir);
+    /// ```
+    fn mkfile_m<T: AsRef<Path>>(&self, path: T, mode: u32) -> RvResult<PathBuf> {
+        self._hooked(VfsOp::Create, path.as_ref(), || Stdfs::mkfile_m(&path, mode))
+    }
+
+    /// Returns the permissions for a file
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_method_mode");
+    /// let file1 = tmpdir.mash("file1");
+    /// assert!(Stdfs::mkfile_m(&file1, 0o555).is_ok());
+    /// assert_eq!(vfs.mode(&file1).unwrap(), 0o100555);
+    /// assert_vfs_remove_all!(vfs, &tmpdir);
+    /// ```
+    fn mode<T: AsRef<Path>>(&self, path: T) -> RvResult<u32> {
+        Stdfs::mode(path)
+    }
+
+    /// Creates a new [`Mover`] for use with the builder pattern
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Always moves `src` into `dst` if `dst` is an existing directory
+    /// * Replaces destination files if they exist
+    /// * Falls back on copy and remove when moving across filesystems
+    /// * Options for preserving attributes
+    /// * Execute by calling `exec`
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_method_move_b");
+    /// let file1 = tmpdir.mash("file1");
+    /// let file2 = tmpdir.mash("file2");
+    /// assert_vfs_write_all!(vfs, &file1, "this is a test");
+    /// assert!(vfs.move_b(&file1, &file2).unwrap().exec().is_ok());
+    /// assert_vfs_no_exists!(vfs, &file1);
+    /// assert_vfs_read_all!(vfs, &file2, "this is a test");
+    /// assert_vfs_remove_all!(vfs, &tmpdir);
+    /// ```
+    fn move_b<T: AsRef<Path>, U: AsRef<Path>>(&self, src: T, dst: U) -> RvResult<Mover> {
+        let mut mover = Stdfs::move_b(src, dst)?;
+        let hooks = self._hooks();
+        if !hooks.is_empty() {
+            let exec = mover.exec;
+            mover.exec = Box::new(move |opts| {
+                let (src, dst) = (Stdfs::abs(&opts.src)?, Stdfs::abs(&opts.dst)?);
+                hooks.exec(VfsOp::Rename, &src, || hooks.exec(VfsOp::Rename, &dst, || exec(opts)))
+            });
+        }
+        Ok(mover)
     }
 
     /// Move a file or directory
@@ -766,6 +1385,7 @@ impl VirtualFileSystem for Stdfs {
     /// * Handles path expansion and absolute path resolution
     /// * Always moves `src` into `dst` if `dst` is an existing directory
     /// * Replaces destination files if they exist
+    /// * Falls back on copy and remove preserving attributes when moving across filesystems
     ///
     /// ### Errors
     /// * PathError::DoesNotExist when the source doesn't exist
@@ -784,7 +1404,88 @@ impl VirtualFileSystem for Stdfs {
     /// assert_vfs_remove_all!(vfs, &tmpdir);
     /// ```
     fn move_p<T: AsRef<Path>, U: AsRef<Path>>(&self, src: T, dst: U) -> RvResult<()> {
-        Stdfs::move_p(src, dst)
+        self._hooked(VfsOp::Rename, src.as_ref(), || {
+            self._hooked(VfsOp::Rename, dst.as_ref(), || Stdfs::move_p(&src, &dst))
+        })
+    }
+
+    /// Returns the number of hard links to the given path's data
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Links aren't followed
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_method_nlink");
+    /// let file = tmpdir.mash("file");
+    /// assert_vfs_mkfile!(vfs, &file);
+    /// assert_eq!(vfs.nlink(&file).unwrap(), 1);
+    /// assert_vfs_hardlink!(vfs, &file, tmpdir.mash("link"));
+    /// assert_eq!(vfs.nlink(&file).unwrap(), 2);
+    /// assert_vfs_remove_all!(vfs, &tmpdir);
+    /// ```
+    fn nlink<T: AsRef<Path>>(&self, path: T) -> RvResult<u64> {
+        Stdfs::nlink(path)
+    }
+
+    /// Opens the given directory returning a [`DirHandle`] for operating on its entries by name
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Stdfs holds the directory open and uses the `openat` family of syscalls to avoid races and
+    ///   repeated path resolution
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    /// * PathError::IsNotDir(PathBuf) when the given path is not a directory
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_method_open_dir");
+    /// let dir = tmpdir.mash("dir");
+    /// assert_vfs_mkdir_p!(vfs, &dir);
+    /// let handle = vfs.open_dir(&dir).unwrap();
+    /// assert_eq!(handle.mkfile("file").unwrap(), dir.mash("file"));
+    /// assert_vfs_is_file!(vfs, dir.mash("file"));
+    /// assert_vfs_remove_all!(vfs, &tmpdir);
+    /// ```
+    fn open_dir<T: AsRef<Path>>(&self, path: T) -> RvResult<DirHandle> {
+        DirHandle::stdfs(self._hooks(), Stdfs::abs(path)?)
+    }
+
+    /// Opens a file in read-write mode for modifying regions of it in place
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Creates a file if it does not exist without truncating it if it does
+    /// * Writes overwrite the data at the current position extending the file as needed
+    ///
+    /// ### Errors
+    /// * PathError::IsNotDir(PathBuf) when the given path's parent exists but is not a directory
+    /// * PathError::DoesNotExist(PathBuf) when the given path's parent doesn't exist
+    /// * PathError::IsNotFile(PathBuf) when the given path exists but is not a file
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_method_open_rw");
+    /// let file = tmpdir.mash("file");
+    /// assert_vfs_write_all!(vfs, &file, "foobar");
+    /// let mut f = vfs.open_rw(&file).unwrap();
+    /// f.seek(SeekFrom::Start(3)).unwrap();
+    /// f.write_all(b"BAR").unwrap();
+    /// f.flush().unwrap();
+    /// assert_vfs_read_all!(vfs, &file, "fooBAR");
+    /// assert_vfs_remove_all!(vfs, &tmpdir);
+    /// ```
+    fn open_rw<T: AsRef<Path>>(&self, path: T) -> RvResult<Box<dyn ReadWriteSeek>> {
+        self._hooked(VfsOp::Write, path.as_ref(), || Stdfs::open_rw(&path))
     }
 
     /// Returns the (user ID, group ID) of the owner of this file
@@ -873,6 +1574,34 @@ impl VirtualFileSystem for Stdfs {
         Stdfs::read_all(path)
     }
 
+    /// Creates a new [`Reader`] for use with the builder pattern
+    ///
+    /// * Provides a handle to a Read + Seek implementation
+    /// * Handles path expansion and absolute path resolution
+    /// * Options for bypassing the page cache
+    /// * Execute by calling `exec`
+    ///
+    /// ### Errors
+    /// * PathError::IsNotFile(PathBuf) when the given path isn't a file
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_method_read_b");
+    /// let file = tmpdir.mash("file");
+    /// assert_vfs_write_all!(vfs, &file, b"foobar 1");
+    /// let mut file = vfs.read_b(&file).unwrap().direct(true).exec().unwrap();
+    /// let mut buf = String::new();
+    /// file.read_to_string(&mut buf);
+    /// assert_eq!(buf, "foobar 1".to_string());
+    /// assert_vfs_remove_all!(vfs, &tmpdir);
+    /// ```
+    fn read_b<T: AsRef<Path>>(&self, path: T) -> RvResult<Reader> {
+        Stdfs::read_b(path)
+    }
+
     /// Read the given file and returns it as lines in a vector
     ///
     /// * Handles path expansion and absolute path resolution
@@ -935,6 +1664,34 @@ impl VirtualFileSystem for Stdfs {
         Stdfs::readlink_abs(link)
     }
 
+    /// Rewrites the given link's target as the minimal relative path to the same target
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Absolute link targets are converted to their relative equivalent
+    /// * Links already in their minimal relative form are left untouched
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given link doesn't exist
+    /// * PathError::IsNotSymlink(PathBuf) when the given path isn't a link
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_method_relink");
+    /// let file1 = tmpdir.mash("file1");
+    /// let link1 = tmpdir.mash("link1");
+    /// assert_vfs_mkfile!(vfs, &file1);
+    /// assert!(std::os::unix::fs::symlink(&file1, &link1).is_ok());
+    /// assert_vfs_readlink!(vfs, &link1, &file1);
+    /// assert_eq!(vfs.relink(&link1).unwrap(), link1);
+    /// assert_vfs_readlink!(vfs, &link1, PathBuf::from("file1"));
+    /// assert_vfs_remove_all!(vfs, &tmpdir);
+    /// ```
+    fn relink<T: AsRef<Path>>(&self, link: T) -> RvResult<PathBuf> {
+        self._hooked(VfsOp::Create, link.as_ref(), || Stdfs::relink(&link))
+    }
+
     /// Removes the given empty directory or file
     ///
     /// * Handles path expansion and absolute path resolution
@@ -953,13 +1710,14 @@ impl VirtualFileSystem for Stdfs {
     /// assert_vfs_no_dir!(vfs, &tmpdir);
     /// ```
     fn remove<T: AsRef<Path>>(&self, path: T) -> RvResult<()> {
-        Stdfs::remove(path)
+        self._hooked(VfsOp::Remove, path.as_ref(), || Stdfs::remove(&path))
     }
 
     /// Removes the given directory after removing all of its contents
     ///
     /// * Handles path expansion and absolute path resolution
     /// * Provides link exclusion i.e. removes the link themselves not what its points to
+    /// * Refuses to remove the root directory, use `remove_all_b` for more guards
     ///
     /// ### Examples
     /// ```
@@ -971,7 +1729,63 @@ impl VirtualFileSystem for Stdfs {
     /// assert_vfs_no_dir!(vfs, &tmpdir);
     /// ```
     fn remove_all<T: AsRef<Path>>(&self, path: T) -> RvResult<()> {
-        Stdfs::remove_all(path)
+        self._hooked(VfsOp::Remove, path.as_ref(), || Stdfs::remove_all(&path))
+    }
+
+    /// Creates a new [`Remover`] for use with the builder pattern
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Provides link exclusion i.e. removes the link themselves not what its points to
+    /// * Refuses to remove the root directory
+    /// * Provides optional guards for the path being within a base and not crossing devices
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_method_remove_all_b");
+    /// let dir = tmpdir.mash("dir");
+    /// assert_vfs_mkdir_p!(vfs, &dir);
+    /// assert!(vfs.remove_all_b(&dir).unwrap().within(&tmpdir).same_device().exec().is_ok());
+    /// assert_vfs_no_exists!(vfs, &dir);
+    /// assert_vfs_remove_all!(vfs, &tmpdir);
+    /// ```
+    fn remove_all_b<T: AsRef<Path>>(&self, path: T) -> RvResult<Remover> {
+        let mut remover = Stdfs::remove_all_b(path)?;
+        let hooks = self._hooks();
+        if !hooks.is_empty() {
+            let exec = remover.exec;
+            remover.exec = Box::new(move |opts| hooks.exec(VfsOp::Remove, &opts.path.clone(), || exec(opts)));
+        }
+        Ok(remover)
+    }
+
+    /// Resolve the given link one hop at a time returning the final target and the chain traversed
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Follows each link one hop at a time recording the links traversed
+    /// * Works on dangling chains returning the last existing link and the missing remainder
+    /// * Non-links resolve to themselves with an empty chain
+    ///
+    /// ### Errors
+    /// * PathError::LinkLooping(PathBuf) when the chain loops back on itself
+    /// * PathError::TooManyLinks(PathBuf) when more than `max_hops` hops are required
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_method_resolve_link");
+    /// let link = tmpdir.mash("link");
+    /// assert!(vfs.symlink(&link, "missing").is_ok());
+    /// let resolved = vfs.resolve_link(&link, 40).unwrap();
+    /// assert_eq!(resolved.chain, vec![link]);
+    /// assert_eq!(resolved.target, tmpdir.mash("missing"));
+    /// assert_eq!(resolved.exists, false);
+    /// assert_vfs_remove_all!(vfs, &tmpdir);
+    /// ```
+    fn resolve_link<T: AsRef<Path>>(&self, path: T, max_hops: usize) -> RvResult<ResolvedLink> {
+        Stdfs::resolve_link(path, max_hops)
     }
 
     /// Returns the current root directory
@@ -984,6 +1798,59 @@ impl VirtualFileSystem for Stdfs {
         Stdfs::root()
     }
 
+    /// Returns true if the given paths refer to the same underlying file
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Compares device and inode numbers so both links and hard links are detected
+    /// * Returns false if either path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_method_same_file");
+    /// let file1 = tmpdir.mash("file1");
+    /// let file2 = tmpdir.mash("file2");
+    /// let link = tmpdir.mash("link");
+    /// assert_vfs_mkfile!(vfs, &file1);
+    /// assert_vfs_mkfile!(vfs, &file2);
+    /// assert_vfs_symlink!(vfs, &link, &file1);
+    /// assert_eq!(vfs.same_file(&link, &file1), true);
+    /// assert_eq!(vfs.same_file(&link, &file2), false);
+    /// assert_vfs_remove_all!(vfs, &tmpdir);
+    /// ```
+    fn same_file<T: AsRef<Path>, U: AsRef<Path>>(&self, a: T, b: U) -> bool {
+        Stdfs::same_file(a, b)
+    }
+
+    /// Set the POSIX access control list of the given path
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Links are followed
+    /// * The permission bits of the path's mode are updated to match the ACL
+    /// * A mask entry is added when named users or groups are given without one
+    /// * Default entries are only valid for directories, empty default entries remove them
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    /// * PathError::IsNotDir(PathBuf) when default entries are given for a non directory
+    /// * VfsError::InvalidAcl(PathBuf) when the entries are malformed
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_method_set_acl");
+    /// let file = tmpdir.mash("file");
+    /// assert!(vfs.mkfile_m(&file, 0o640).is_ok());
+    /// assert!(vfs.set_acl(&file, Acl::from_mode(0o600)).is_ok());
+    /// assert_eq!(vfs.mode(&file).unwrap(), 0o100600);
+    /// assert_vfs_remove_all!(vfs, &tmpdir);
+    /// ```
+    fn set_acl<T: AsRef<Path>>(&self, path: T, acl: Acl) -> RvResult<()> {
+        self._hooked(VfsOp::Chmod, path.as_ref(), || Stdfs::set_acl(&path, acl))
+    }
+
     /// Set the current working directory
     ///
     /// * Handles path expansion and absolute path resolution
@@ -1000,8 +1867,159 @@ impl VirtualFileSystem for Stdfs {
     /// stdfs.set_cwd(stdfs.cwd().unwrap().mash("tests"));
     /// assert_eq!(stdfs.cwd().unwrap().base().unwrap(), "tests".to_string());
     /// ```
-    fn set_cwd<T: AsRef<Path>>(&self, path: T) -> RvResult<PathBuf> {
-        Stdfs::set_cwd(path)
+    fn set_cwd<T: AsRef<Path>>(&self, path: T) -> RvResult<PathBuf> {
+        Stdfs::set_cwd(path)
+    }
+
+    /// Set the access and modification times of the given path with nanosecond precision
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Doesn't follow links i.e. sets the times of the link itself
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    /// use std::time::{Duration, UNIX_EPOCH};
+    ///
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_method_set_file_time");
+    /// let file = tmpdir.mash("file");
+    /// assert_vfs_mkfile!(vfs, &file);
+    /// let time = UNIX_EPOCH + Duration::new(1_000_000_000, 123_456_789);
+    /// assert!(vfs.set_file_time(&file, time, time).is_ok());
+    /// assert_eq!(vfs.entry(&file).unwrap().modified(), time);
+    /// assert_vfs_remove_all!(vfs, &tmpdir);
+    /// ```
+    fn set_file_time<T: AsRef<Path>>(&self, path: T, atime: SystemTime, mtime: SystemTime) -> RvResult<()> {
+        self._hooked(VfsOp::Write, path.as_ref(), || Stdfs::set_file_time(&path, atime, mtime))
+    }
+
+    /// Set or clear the immutable flag of the given path i.e. `chattr +i` and `chattr -i`
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Links are followed
+    /// * Requires the `CAP_LINUX_IMMUTABLE` capability and a filesystem that supports it
+    /// * Immutable paths can't be modified or removed until the flag is cleared
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_method_set_immutable");
+    /// let file = tmpdir.mash("file");
+    /// assert_vfs_mkfile!(vfs, &file);
+    /// if vfs.set_immutable(&file, true).is_ok() {
+    ///     assert_eq!(vfs.is_immutable(&file), true);
+    ///     assert!(vfs.set_immutable(&file, false).is_ok());
+    /// }
+    /// assert_eq!(vfs.is_immutable(&file), false);
+    /// assert_vfs_remove_all!(vfs, &tmpdir);
+    /// ```
+    fn set_immutable<T: AsRef<Path>>(&self, path: T, yes: bool) -> RvResult<()> {
+        self._hooked(VfsOp::Chmod, path.as_ref(), || Stdfs::set_immutable(&path, yes))
+    }
+
+    /// Set the modification time of the given path with nanosecond precision
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Doesn't follow links i.e. sets the time of the link itself
+    /// * Leaves the access time untouched
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    /// use std::time::{Duration, UNIX_EPOCH};
+    ///
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_method_set_mtime_only");
+    /// let file = tmpdir.mash("file");
+    /// assert_vfs_mkfile!(vfs, &file);
+    /// let time = UNIX_EPOCH + Duration::new(1_000_000_000, 123_456_789);
+    /// assert!(vfs.set_mtime_only(&file, time).is_ok());
+    /// assert_eq!(vfs.entry(&file).unwrap().modified(), time);
+    /// assert_vfs_remove_all!(vfs, &tmpdir);
+    /// ```
+    fn set_mtime_only<T: AsRef<Path>>(&self, path: T, mtime: SystemTime) -> RvResult<()> {
+        self._hooked(VfsOp::Write, path.as_ref(), || Stdfs::set_mtime_only(&path, mtime))
+    }
+
+    /// Set the SELinux security context of the given path
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Links aren't followed so that the context of the link itself is set
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_method_set_selinux_context");
+    /// let file = tmpdir.mash("file");
+    /// assert_vfs_mkfile!(vfs, &file);
+    /// // Labeling requires a filesystem with security attribute support
+    /// if vfs.set_selinux_context(&file, "system_u:object_r:etc_t:s0").is_ok() {
+    ///     assert_eq!(vfs.get_selinux_context(&file).unwrap().unwrap(), "system_u:object_r:etc_t:s0");
+    /// }
+    /// assert_vfs_remove_all!(vfs, &tmpdir);
+    /// ```
+    #[cfg(feature = "selinux")]
+    fn set_selinux_context<T: AsRef<Path>, U: AsRef<str>>(&self, path: T, context: U) -> RvResult<()> {
+        self._hooked(VfsOp::Chmod, path.as_ref(), || Stdfs::set_selinux_context(&path, context))
+    }
+
+    /// Returns the size in bytes of the given file
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Links are followed
+    /// * Use `disk_usage` to sum up the size of a directory tree
+    ///
+    /// ### Errors
+    /// * PathError::IsNotFile(PathBuf) when the given path isn't a file
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_method_size");
+    /// let file = tmpdir.mash("file");
+    /// assert_vfs_write_all!(vfs, &file, "foobar");
+    /// assert_eq!(vfs.size(&file).unwrap(), 6);
+    /// assert_vfs_remove_all!(vfs, &tmpdir);
+    /// ```
+    fn size<T: AsRef<Path>>(&self, path: T) -> RvResult<u64> {
+        Stdfs::size(path)
+    }
+
+    /// Returns the capacity and usage of the filesystem containing the given path
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Reports the values of the underlying `statvfs` call with `free` being the space available
+    ///   to unprivileged users
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_method_stat_fs");
+    /// let stat = vfs.stat_fs(&tmpdir).unwrap();
+    /// assert!(stat.used <= stat.total);
+    /// assert_vfs_remove_all!(vfs, &tmpdir);
+    /// ```
+    fn stat_fs<T: AsRef<Path>>(&self, path: T) -> RvResult<VfsStatFs> {
+        Stdfs::stat_fs(path)
     }
 
     /// Creates a new symbolic link
@@ -1028,7 +2046,31 @@ impl VirtualFileSystem for Stdfs {
     /// assert_vfs_remove_all!(vfs, &tmpdir);
     /// ```
     fn symlink<T: AsRef<Path>, U: AsRef<Path>>(&self, link: T, target: U) -> RvResult<PathBuf> {
-        Stdfs::symlink(link, target)
+        self._hooked(VfsOp::Create, link.as_ref(), || Stdfs::symlink(&link, target))
+    }
+
+    /// Wraps `symlink` reporting whether the link was created or already existed
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Creation is attempted first so a concurrent creator doesn't cause an error
+    /// * An existing link is only considered to exist if it points to the given target
+    /// * Dangling links are supported
+    ///
+    /// ### Errors
+    /// * PathError::ExistsAlready(PathBuf) when the link path exists but isn't a link to the target
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_method_symlink_ensured");
+    /// let link = tmpdir.mash("link");
+    /// assert_eq!(vfs.symlink_ensured(&link, "file").unwrap(), Ensured::Created(link.clone()));
+    /// assert_eq!(vfs.symlink_ensured(&link, "file").unwrap(), Ensured::Existed(link.clone()));
+    /// assert_vfs_remove_all!(vfs, &tmpdir);
+    /// ```
+    fn symlink_ensured<T: AsRef<Path>, U: AsRef<Path>>(&self, link: T, target: U) -> RvResult<Ensured> {
+        self._hooked(VfsOp::Create, link.as_ref(), || Stdfs::symlink_ensured(&link, &target))
     }
 
     /// Returns the user ID of the owner of this file
@@ -1046,9 +2088,37 @@ impl VirtualFileSystem for Stdfs {
         Stdfs::uid(path)
     }
 
+    /// Watch the given path for changes returning a stream of the events that occur
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Directories are watched recursively via inotify
+    /// * Reports files, directories and links being created, written to, removed and renamed
+    /// * Writes are reported once the file is closed
+    /// * Permission and ownership changes aren't reported
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    /// use std::time::Duration;
+    ///
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_method_watch");
+    /// let mut watcher = vfs.watch(&tmpdir).unwrap();
+    /// let file = tmpdir.mash("file");
+    /// assert_vfs_mkfile!(vfs, &file);
+    /// assert_eq!(watcher.recv_timeout(Duration::from_secs(1)), Some(VfsEvent::Create(file)));
+    /// assert_vfs_remove_all!(vfs, &tmpdir);
+    /// ```
+    fn watch<T: AsRef<Path>>(&self, path: T) -> RvResult<VfsWatcher> {
+        Stdfs::watch(path)
+    }
+
     /// Opens a file in write-only mode
     ///
     /// * Creates a file if it does not exist or truncates it if it does
+    /// * Returns a [`VfsWriter`] reporting the path and number of bytes written
     ///
     /// ### Errors
     /// * PathError::IsNotDir(PathBuf) when the given path's parent exists but is not a directory
@@ -1067,8 +2137,8 @@ impl VirtualFileSystem for Stdfs {
     /// assert_vfs_read_all!(vfs, &file, "foobar");
     /// assert_vfs_remove_all!(vfs, &tmpdir);
     /// ```
-    fn write<T: AsRef<Path>>(&self, path: T) -> RvResult<Box<dyn Write>> {
-        Stdfs::write(path)
+    fn write<T: AsRef<Path>>(&self, path: T) -> RvResult<VfsWriter> {
+        self._hooked(VfsOp::Write, path.as_ref(), || Stdfs::write(&path))
     }
 
     /// Write the given data to to the target file
@@ -1094,7 +2164,61 @@ impl VirtualFileSystem for Stdfs {
     /// assert_vfs_remove_all!(vfs, &tmpdir);
     /// ```
     fn write_all<T: AsRef<Path>, U: AsRef<[u8]>>(&self, path: T, data: U) -> RvResult<()> {
-        Stdfs::write_all(path, data)
+        self._hooked(VfsOp::Write, path.as_ref(), || Stdfs::write_all(&path, data))
+    }
+
+    /// Write the given data to the target file atomically
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Writes to a temporary sibling file which is then renamed into place so that readers only
+    ///   ever see the old or the new data and a failure never leaves a partially written file
+    /// * Replaces the final target of links rather than the links themselves
+    /// * Preserves the mode and ownership of an existing file
+    /// * Breaks any hard links to an existing file as it is replaced by a new file
+    ///
+    /// ### Errors
+    /// * PathError::IsNotDir(PathBuf) when the given path's parent exists but is not a directory
+    /// * PathError::DoesNotExist(PathBuf) when the given path's parent doesn't exist
+    /// * PathError::IsNotFile(PathBuf) when the given path exists but is not a file
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_method_write_all_atomic");
+    /// let file = tmpdir.mash("file");
+    /// assert!(vfs.write_all_atomic(&file, "foobar").is_ok());
+    /// assert_vfs_read_all!(vfs, &file, "foobar");
+    /// assert_vfs_remove_all!(vfs, &tmpdir);
+    /// ```
+    fn write_all_atomic<T: AsRef<Path>, U: AsRef<[u8]>>(&self, path: T, data: U) -> RvResult<()> {
+        self._hooked(VfsOp::Write, path.as_ref(), || Stdfs::write_all_atomic(&path, data))
+    }
+
+    /// Creates a new [`Writer`] for use with the builder pattern
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Options for writing atomically
+    /// * Execute by calling `exec`
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "stdfs_method_write_b");
+    /// let file = tmpdir.mash("file");
+    /// assert!(vfs.write_b(&file, "foobar").unwrap().atomic(true).exec().is_ok());
+    /// assert_vfs_read_all!(vfs, &file, "foobar");
+    /// assert_vfs_remove_all!(vfs, &tmpdir);
+    /// ```
+    fn write_b<T: AsRef<Path>, U: AsRef<[u8]>>(&self, path: T, data: U) -> RvResult<Writer> {
+        let mut writer = Stdfs::write_b(path, data)?;
+        let hooks = self._hooks();
+        if !hooks.is_empty() {
+            let exec = writer.exec;
+            writer.exec = Box::new(move |opts| hooks.exec(VfsOp::Write, &opts.path.clone(), || exec(opts)));
+        }
+        Ok(writer)
     }
 
     /// Write the given lines to to the target file including final newline
@@ -1120,7 +2244,7 @@ impl VirtualFileSystem for Stdfs {
     /// assert_vfs_remove_all!(vfs, &tmpdir);
     /// ```
     fn write_lines<T: AsRef<Path>, U: AsRef<str>>(&self, path: T, lines: &[U]) -> RvResult<()> {
-        Stdfs::write_lines(path, lines)
+        self._hooked(VfsOp::Write, path.as_ref(), || Stdfs::write_lines(&path, lines))
     }
 
     /// Up cast the trait type to the enum wrapper
@@ -1161,7 +2285,7 @@ mod tests {
         assert_eq!(Stdfs::abs(".").unwrap(), cwd);
 
         // home dir
-        let home = PathBuf::from(sys::home_dir().unwrap());
+        let home = sys::home_dir().unwrap();
         assert_eq!(Stdfs::abs("~").unwrap(), home);
         assert_eq!(Stdfs::abs("~/").unwrap(), home);
 
@@ -1228,6 +2352,64 @@ mod tests {
         assert_vfs_remove_all!(vfs, &tmpdir);
     }
 
+    #[test]
+    fn test_stdfs_advise() {
+        let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs());
+        let dir1 = tmpdir.mash("dir1");
+        let file1 = tmpdir.mash("file1");
+
+        // Errors
+        assert_eq!(
+            vfs.advise(&file1, Advice::DontNeed).unwrap_err().downcast_ref::<PathError>(),
+            Some(&PathError::does_not_exist(&file1))
+        );
+        assert_vfs_mkdir_p!(vfs, &dir1);
+        assert_eq!(
+            vfs.advise(&dir1, Advice::DontNeed).unwrap_err().downcast_ref::<PathError>(),
+            Some(&PathError::is_not_file(&dir1))
+        );
+
+        // Every hint is accepted and leaves the data intact
+        assert_vfs_write_all!(vfs, &file1, "foobar");
+        for advice in [Advice::Normal, Advice::Sequential, Advice::Random, Advice::WillNeed, Advice::DontNeed] {
+            assert!(vfs.advise(&file1, advice).is_ok());
+        }
+        assert_vfs_read_all!(vfs, &file1, "foobar");
+
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+
+    #[test]
+    fn test_stdfs_allocate() {
+        let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs());
+        let dir1 = tmpdir.mash("dir1");
+        let file1 = tmpdir.mash("file1");
+
+        // Errors
+        assert_eq!(
+            vfs.allocate(dir1.mash("file"), 10).unwrap_err().downcast_ref::<PathError>(),
+            Some(&PathError::does_not_exist(&dir1))
+        );
+        assert_vfs_mkdir_p!(vfs, &dir1);
+        assert_eq!(
+            vfs.allocate(&dir1, 10).unwrap_err().downcast_ref::<PathError>(),
+            Some(&PathError::is_not_file(&dir1))
+        );
+
+        // Creates the file and grows it keeping existing data
+        assert!(vfs.allocate(&file1, 0).is_ok());
+        assert_eq!(vfs.entry(&file1).unwrap().size(), 0);
+        assert!(vfs.write_all(&file1, "foo").is_ok());
+        assert!(vfs.allocate(&file1, 5).is_ok());
+        assert_eq!(vfs.read_all(&file1).unwrap(), "foo\0\0");
+
+        // Never shrinks the file
+        assert!(vfs.allocate(&file1, 2).is_ok());
+        assert_eq!(vfs.entry(&file1).unwrap().size(), 5);
+
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+
     #[test]
     fn test_stdfs_append() {
         let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs());
@@ -1388,6 +2570,32 @@ mod tests {
         assert_vfs_remove_all!(vfs, &tmpdir);
     }
 
+    #[test]
+    fn test_stdfs_copy_chunked() {
+        let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs());
+        let file1 = tmpdir.mash("file1");
+        let file2 = tmpdir.mash("file2");
+        let data = "0123456789".repeat(20_000);
+        assert_vfs_write_all!(vfs, &file1, &data);
+        assert!(vfs.chmod(&file1, 0o640).is_ok());
+
+        // Cancelled removes the partial destination
+        let ctx = OpContext::new();
+        ctx.cancel();
+        let err = Stdfs::_copy_chunked(&file1, &file2, &ctx).unwrap_err();
+        assert_eq!(err.downcast_ref::<VfsError>(), Some(&VfsError::Cancelled));
+        assert_vfs_no_exists!(vfs, &file2);
+
+        // Copies content across chunks and the permissions
+        let ctx = OpContext::new();
+        assert!(Stdfs::_copy_chunked(&file1, &file2, &ctx).is_ok());
+        assert_vfs_read_all!(vfs, &file2, data);
+        assert_eq!(ctx.metrics(), OpMetrics { entries: 0, bytes: 200_000 });
+        assert_eq!(vfs.mode(&file2).unwrap(), 0o100640);
+
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+
     #[test]
     fn test_stdfs_dirs() {
         let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs());
@@ -1447,16 +2655,116 @@ mod tests {
         assert_vfs_remove_all!(vfs, &tmpdir);
     }
 
+    #[test]
+    fn test_stdfs_ensure_dir() {
+        let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs());
+        let dir1 = tmpdir.mash("dir1");
+        let dir2 = dir1.mash("dir2");
+        let file1 = tmpdir.mash("file1");
+        let (uid, gid) = vfs.owner(&tmpdir).unwrap();
+
+        // creates parents as needed
+        assert!(vfs.ensure_dir(&dir2, 0o750, uid, gid).unwrap());
+        assert_eq!(vfs.mode(&dir2).unwrap(), 0o40750);
+        assert_eq!(vfs.owner(&dir2).unwrap(), (uid, gid));
+
+        // converged
+        assert!(!vfs.ensure_dir(&dir2, 0o750, uid, gid).unwrap());
+        assert!(vfs.ensure_dir(&dir2, 0o700, uid, gid).unwrap());
+        assert_eq!(vfs.mode(&dir2).unwrap(), 0o40700);
+
+        // not a dir
+        assert_vfs_mkfile!(vfs, &file1);
+        assert_eq!(
+            vfs.ensure_dir(&file1, 0o755, uid, gid).unwrap_err().to_string(),
+            PathError::is_not_dir(&file1).to_string()
+        );
+
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+
+    #[test]
+    fn test_stdfs_ensure_file() {
+        let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs());
+        let file1 = tmpdir.mash("file1");
+
+        // creates the file
+        assert!(vfs.ensure_file(&file1, "foo", 0o600).unwrap());
+        assert_vfs_read_all!(vfs, &file1, "foo");
+        assert_eq!(vfs.mode(&file1).unwrap(), 0o100600);
+
+        // converged
+        assert!(!vfs.ensure_file(&file1, "foo", 0o600).unwrap());
+        assert!(vfs.ensure_file(&file1, "bar", 0o644).unwrap());
+        assert_vfs_read_all!(vfs, &file1, "bar");
+        assert_eq!(vfs.mode(&file1).unwrap(), 0o100644);
+
+        // links are followed
+        let link1 = tmpdir.mash("link1");
+        assert_vfs_symlink!(vfs, &link1, &file1);
+        assert!(!vfs.ensure_file(&link1, "bar", 0o644).unwrap());
+        assert!(vfs.ensure_file(&link1, "foo", 0o600).unwrap());
+        assert_vfs_read_all!(vfs, &file1, "foo");
+        assert_eq!(vfs.mode(&file1).unwrap(), 0o100600);
+        assert_vfs_is_symlink!(vfs, &link1);
+
+        // not a file
+        assert_eq!(
+            vfs.ensure_file(&tmpdir, "foo", 0o644).unwrap_err().to_string(),
+            PathError::is_not_file(&tmpdir).to_string()
+        );
+
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+
+    #[test]
+    fn test_stdfs_ensure_symlink() {
+        let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs());
+        let dir1 = tmpdir.mash("dir1");
+        let file1 = tmpdir.mash("file1");
+        let link1 = tmpdir.mash("link1");
+        assert_vfs_mkdir_p!(vfs, &dir1);
+        assert_vfs_mkfile!(vfs, &file1);
+
+        // creates the link
+        assert!(vfs.ensure_symlink(&link1, "file1").unwrap());
+        assert_vfs_readlink_abs!(vfs, &link1, &file1);
+
+        // converged for both relative and absolute targets
+        assert!(!vfs.ensure_symlink(&link1, "file1").unwrap());
+        assert!(!vfs.ensure_symlink(&link1, &file1).unwrap());
+
+        // replaces links pointing elsewhere including dangling ones
+        assert!(vfs.ensure_symlink(&link1, &dir1).unwrap());
+        assert_vfs_readlink_abs!(vfs, &link1, &dir1);
+        assert!(vfs.ensure_symlink(&link1, "missing").unwrap());
+        assert!(vfs.ensure_symlink(&link1, "file1").unwrap());
+        assert_vfs_readlink_abs!(vfs, &link1, &file1);
+        assert_iter_eq(vfs.all_paths(&tmpdir).unwrap(), vec![dir1.clone(), file1.clone(), link1.clone()]);
+
+        // not a link
+        assert_eq!(
+            vfs.ensure_symlink(&file1, &dir1).unwrap_err().to_string(),
+            PathError::is_not_symlink(&file1).to_string()
+        );
+        assert_eq!(
+            vfs.ensure_symlink(&dir1, &file1).unwrap_err().to_string(),
+            PathError::is_not_symlink(&dir1).to_string()
+        );
+
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+
     #[test]
     fn test_stdfs_exists() {
         let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs());
         let file = tmpdir.mash("file");
 
         // abs fails
-        assert_eq!(vfs.exists(""), false);
+        assert!(!vfs.exists(""));
 
         // Doesn't exist
-        assert_eq!(vfs.exists(&file), false);
+        assert!(!vfs.exists(&file));
 
         assert_vfs_no_exists!(vfs, &file);
         assert_vfs_mkfile!(vfs, &file);
@@ -1465,6 +2773,25 @@ mod tests {
         assert_vfs_remove_all!(vfs, &tmpdir);
     }
 
+    #[test]
+    fn test_stdfs_exists_all() {
+        let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs());
+        let dir = tmpdir.mash("dir");
+        let file = dir.mash("file");
+
+        assert_eq!(vfs.exists_all::<PathBuf>(&[]), Vec::<bool>::new());
+        assert_eq!(vfs.exists_all(&[&dir, &file]), vec![false, false]);
+        assert_vfs_mkdir_p!(vfs, &dir);
+        assert_eq!(vfs.exists_all(&[&dir, &file]), vec![true, false]);
+        assert_vfs_mkfile!(vfs, &file);
+        assert_eq!(vfs.exists_all(&[&dir, &file]), vec![true, true]);
+
+        // abs fails
+        assert_eq!(vfs.exists_all(&["", file.to_str().unwrap()]), vec![false, true]);
+
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+
     #[test]
     fn test_stdfs_files() {
         let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs());
@@ -1483,18 +2810,38 @@ mod tests {
         assert_vfs_remove_all!(vfs, &tmpdir);
     }
 
+    #[test]
+    fn test_stdfs_first_existing() {
+        let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs());
+        let dir = tmpdir.mash("dir");
+        let file = dir.mash("file");
+
+        assert_eq!(vfs.first_existing::<PathBuf>(&[]), None);
+        assert_eq!(vfs.first_existing(&[&file, &dir]), None);
+        assert_vfs_mkdir_p!(vfs, &dir);
+        assert_eq!(vfs.first_existing(&[&file, &dir]), Some(dir.clone()));
+        assert_vfs_mkfile!(vfs, &file);
+        assert_eq!(vfs.first_existing(&[&file, &dir]), Some(file.clone()));
+
+        // abs fails are skipped and paths are returned in absolute form
+        assert_eq!(vfs.first_existing(&["", file.to_str().unwrap()]), Some(file.clone()));
+        assert_eq!(vfs.first_existing(&[dir.mash("../dir/file")]), Some(file));
+
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+
     #[test]
     fn test_stdfs_is_exec() {
         let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs());
         let file = tmpdir.mash("file");
 
         // abs fails
-        assert_eq!(vfs.is_exec(""), false);
+        assert!(!vfs.is_exec(""));
 
         assert!(vfs.mkfile_m(&file, 0o644).is_ok());
-        assert_eq!(vfs.is_exec(&file), false);
+        assert!(!vfs.is_exec(&file));
         assert!(vfs.chmod(&file, 0o777).is_ok());
-        assert_eq!(vfs.is_exec(&file), true);
+        assert!(vfs.is_exec(&file));
 
         assert_vfs_remove_all!(vfs, &tmpdir);
     }
@@ -1505,10 +2852,10 @@ mod tests {
         let dir = tmpdir.mash("dir");
 
         // abs fails
-        assert_eq!(vfs.is_dir(""), false);
+        assert!(!vfs.is_dir(""));
 
         // Doesn't exist
-        assert_eq!(vfs.is_dir(&dir), false);
+        assert!(!vfs.is_dir(&dir));
 
         assert_vfs_no_dir!(vfs, &dir);
         assert_vfs_mkdir_p!(vfs, &dir);
@@ -1523,10 +2870,10 @@ mod tests {
         let file = tmpdir.mash("file");
 
         // abs fails
-        assert_eq!(vfs.is_file(""), false);
+        assert!(!vfs.is_file(""));
 
         // Doesn't exist
-        assert_eq!(vfs.is_file(&file), false);
+        assert!(!vfs.is_file(&file));
 
         assert_vfs_no_file!(vfs, &file);
         assert_vfs_mkfile!(vfs, &file);
@@ -1541,13 +2888,13 @@ mod tests {
         let file = tmpdir.mash("file");
 
         // abs fails
-        assert_eq!(vfs.is_readonly(""), false);
+        assert!(!vfs.is_readonly(""));
 
         assert!(vfs.mkfile_m(&file, 0o644).is_ok());
-        assert_eq!(vfs.is_readonly(&file), false);
+        assert!(!vfs.is_readonly(&file));
         assert!(vfs.chmod_b(&file).unwrap().readonly().exec().is_ok());
         assert_eq!(vfs.mode(&file).unwrap(), 0o100444);
-        assert_eq!(vfs.is_readonly(&file), true);
+        assert!(vfs.is_readonly(&file));
 
         assert_vfs_remove_all!(vfs, &tmpdir);
     }
@@ -1559,10 +2906,10 @@ mod tests {
         let link = tmpdir.mash("link");
 
         // abs fails
-        assert_eq!(vfs.is_symlink(""), false);
+        assert!(!vfs.is_symlink(""));
 
         // Doesn't exist
-        assert_eq!(vfs.is_symlink(&file), false);
+        assert!(!vfs.is_symlink(&file));
 
         // Exists
         assert_vfs_mkfile!(vfs, &file);
@@ -1673,6 +3020,99 @@ mod tests {
         assert_vfs_remove_all!(vfs, &tmpdir);
     }
 
+    #[test]
+    fn test_stdfs_move_across() {
+        let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs());
+        let dir1 = tmpdir.mash("dir1");
+        let file1 = dir1.mash("file1");
+        let link1 = dir1.mash("link1");
+        let dir2 = tmpdir.mash("dir2");
+        let file2 = dir2.mash("file1");
+        let link2 = dir2.mash("link1");
+        let mtime = |path: &Path| std::fs::symlink_metadata(path).unwrap().modified().unwrap();
+        let past = std::time::UNIX_EPOCH + std::time::Duration::new(1_000_000_000, 123_456_789);
+
+        // Setup a source tree with attributes that a plain copy would lose
+        assert_vfs_mkdir_m!(vfs, &dir1, 0o40700);
+        assert_vfs_write_all!(vfs, &file1, "file1");
+        assert_vfs_symlink!(vfs, &link1, "file1");
+        assert!(vfs.chmod(&file1, 0o600).is_ok());
+        let name = std::ffi::CString::new("user.rivia").unwrap();
+        let xattr = std::ffi::CString::new(file1.to_string().unwrap()).unwrap();
+        let xattrs =
+            unsafe { nix::libc::lsetxattr(xattr.as_ptr(), name.as_ptr(), b"1".as_ptr() as *const _, 1, 0) } == 0;
+        let owned = user::getuid() == 0;
+        if owned {
+            assert!(vfs.chown(&file1, 1000, 1000).is_ok());
+        }
+        for path in [&file1, &link1, &dir1] {
+            assert!(Stdfs::set_file_time(path, past, past).is_ok());
+        }
+
+        // Preserve attributes
+        assert!(Stdfs::_move_across(&dir1, &dir2, true).is_ok());
+        assert_vfs_no_exists!(vfs, &dir1);
+        assert_vfs_read_all!(vfs, &file2, "file1");
+        assert_vfs_readlink!(vfs, &link2, PathBuf::from("file1"));
+        assert_eq!(vfs.mode(&dir2).unwrap(), 0o40700);
+        assert_eq!(vfs.mode(&file2).unwrap(), 0o100600);
+        assert_eq!(mtime(&dir2), past);
+        assert_eq!(mtime(&file2), past);
+        assert_eq!(mtime(&link2), past);
+        if owned {
+            assert_eq!(vfs.owner(&file2).unwrap(), (1000, 1000));
+        }
+        if xattrs {
+            let xattr = std::ffi::CString::new(file2.to_string().unwrap()).unwrap();
+            let size = unsafe { nix::libc::lgetxattr(xattr.as_ptr(), name.as_ptr(), std::ptr::null_mut(), 0) };
+            assert_eq!(size, 1);
+        }
+
+        // Skip preserving attributes
+        assert!(Stdfs::_move_across(&dir2, &dir1, false).is_ok());
+        assert_vfs_no_exists!(vfs, &dir2);
+        assert_vfs_read_all!(vfs, &dir1.mash("file1"), "file1");
+        assert_ne!(mtime(&dir1.mash("file1")), past);
+
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+
+    #[test]
+    fn test_stdfs_move_across_failure() {
+        let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs());
+        let dir1 = tmpdir.mash("dir1");
+        let file1 = dir1.mash("file1");
+        let fifo1 = dir1.mash("sub/fifo1");
+        let file2 = dir1.mash("zfile2");
+        let dir2 = tmpdir.mash("dir2");
+
+        // A fifo part way through the tree fails the move
+        assert_vfs_mkdir_p!(vfs, fifo1.dir().unwrap());
+        assert_vfs_write_all!(vfs, &file1, "file1");
+        assert!(nix::unistd::mkfifo(&fifo1, nix::sys::stat::Mode::S_IRWXU).is_ok());
+        assert_vfs_write_all!(vfs, &file2, "file2");
+        assert_eq!(
+            Stdfs::_move_across(&dir1, &dir2, true).unwrap_err().downcast_ref::<PathError>(),
+            Some(&PathError::unsupported_file_type(&fifo1))
+        );
+
+        // Neither the destination nor the temporary copy are left behind and the source is intact
+        assert_vfs_no_exists!(vfs, &dir2);
+        assert_eq!(vfs.all_paths(&tmpdir).unwrap().into_iter().filter(|x| !x.starts_with(&dir1)).count(), 0);
+        assert_vfs_read_all!(vfs, &file1, "file1");
+        assert_vfs_read_all!(vfs, &file2, "file2");
+
+        // Replacing a non empty directory fails and also cleans up
+        assert_vfs_mkdir_p!(vfs, &dir2);
+        assert_vfs_mkfile!(vfs, dir2.mash("file"));
+        assert!(vfs.remove(&fifo1).is_ok());
+        assert!(Stdfs::_move_across(&dir1, &dir2, true).is_err());
+        assert_eq!(vfs.paths(&tmpdir).unwrap(), vec![dir1.clone(), dir2.clone()]);
+        assert_vfs_read_all!(vfs, &file1, "file1");
+
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+
     #[test]
     fn test_stdfs_paths() {
         let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs());
@@ -1818,6 +3258,98 @@ mod tests {
         assert_vfs_remove_all!(vfs, &tmpdir);
     }
 
+    #[test]
+    fn test_stdfs_same_file() {
+        let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs());
+        let file1 = tmpdir.mash("file1");
+        let file2 = tmpdir.mash("file2");
+        let link = tmpdir.mash("link");
+        let hard = tmpdir.mash("hard");
+
+        // Doesn't exist
+        assert!(!vfs.same_file(&file1, &file1));
+
+        // Links and hard links resolve to the same file
+        assert_vfs_write_all!(vfs, &file1, "file1");
+        assert_vfs_mkfile!(vfs, &file2);
+        assert_vfs_symlink!(vfs, &link, &file1);
+        std::fs::hard_link(&file1, &hard).unwrap();
+        assert!(vfs.same_file(&file1, &file1));
+        assert!(vfs.same_file(&link, &file1));
+        assert!(vfs.same_file(&hard, &file1));
+        assert!(vfs.same_file(&hard, &link));
+        assert!(!vfs.same_file(&link, &file2));
+
+        // Copying a file onto itself through a link is a no-op
+        assert!(vfs.copy_b(&hard, &file1).unwrap().exec().is_ok());
+        assert!(vfs.copy_b(&link, &file1).unwrap().follow(true).exec().is_ok());
+        assert_vfs_read_all!(vfs, &file1, "file1");
+        assert_vfs_is_symlink!(vfs, &link);
+
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+
+    #[test]
+    fn test_stdfs_set_file_time() {
+        let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs());
+        let file1 = tmpdir.mash("file1");
+        let link1 = tmpdir.mash("link1");
+        let atime = |path: &Path| std::fs::symlink_metadata(path).unwrap().accessed().unwrap();
+        let past = std::time::UNIX_EPOCH + std::time::Duration::new(1_000_000_000, 123_456_789);
+        let later = past + std::time::Duration::from_nanos(1);
+
+        // Errors
+        assert_eq!(
+            vfs.set_file_time(&file1, past, past).unwrap_err().downcast_ref::<PathError>(),
+            Some(&PathError::does_not_exist(&file1))
+        );
+        assert_eq!(
+            vfs.set_mtime_only(&file1, past).unwrap_err().downcast_ref::<PathError>(),
+            Some(&PathError::does_not_exist(&file1))
+        );
+
+        // Nanoseconds are kept
+        assert_vfs_mkfile!(vfs, &file1);
+        assert!(vfs.set_file_time(&file1, past, past).is_ok());
+        assert_eq!(vfs.entry(&file1).unwrap().modified(), past);
+        assert_eq!(atime(&file1), past);
+
+        // Access time is left untouched
+        assert!(vfs.set_mtime_only(&file1, later).is_ok());
+        assert_eq!(vfs.entry(&file1).unwrap().modified(), later);
+        assert_eq!(atime(&file1), past);
+
+        // Links aren't followed
+        assert_vfs_symlink!(vfs, &link1, &file1);
+        assert!(vfs.set_mtime_only(&link1, past).is_ok());
+        assert_eq!(std::fs::symlink_metadata(&link1).unwrap().modified().unwrap(), past);
+        assert_eq!(vfs.entry(&file1).unwrap().modified(), later);
+
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+
+    #[test]
+    fn test_stdfs_stat_fs() {
+        let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs());
+        let file = tmpdir.mash("file");
+
+        // Doesn't exist
+        assert_eq!(
+            vfs.stat_fs(&file).unwrap_err().downcast_ref::<PathError>(),
+            Some(&PathError::does_not_exist(&file))
+        );
+
+        // Files share the stats of their filesystem
+        assert_vfs_mkfile!(vfs, &file);
+        let stat = vfs.stat_fs(&file).unwrap();
+        assert!(stat.total > 0);
+        assert!(stat.used <= stat.total && stat.free <= stat.total);
+        assert!(stat.inodes_used <= stat.inodes && stat.inodes_free <= stat.inodes);
+        assert_eq!(stat.total, vfs.stat_fs(&tmpdir).unwrap().total);
+
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+
     #[test]
     fn test_stdfs_symlink() {
         let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs());
@@ -1832,13 +3364,13 @@ mod tests {
         // Link to dir
         assert_vfs_mkdir_p!(vfs, &dir1);
         assert_vfs_symlink!(vfs, &link1, &dir1);
-        assert_eq!(vfs.is_symlink_dir(&link1), true);
-        assert_eq!(vfs.is_symlink_file(&link1), false);
+        assert!(vfs.is_symlink_dir(&link1));
+        assert!(!vfs.is_symlink_file(&link1));
 
         // Link to file
         assert_vfs_mkfile!(vfs, &file1);
-        assert_eq!(vfs.is_symlink_dir(&link2), false);
-        assert_eq!(vfs.is_symlink_file(&link2), true);
+        assert!(!vfs.is_symlink_dir(&link2));
+        assert!(vfs.is_symlink_file(&link2));
 
         assert_vfs_remove_all!(vfs, &tmpdir);
     }
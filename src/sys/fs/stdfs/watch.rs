@@ -0,0 +1,200 @@
+// Change notification for Stdfs paths via inotify
+//
+// Inotify watches aren't recursive so every directory beneath the watched path is given its own
+// watch with directories being added as they are created or moved in. As entries can be created
+// in a new directory before its watch is added, new directories are scanned and their contents
+// reported as created. Writes are reported once the file is closed so that a single write session
+// results in a single event rather than one per write system call.
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    fs,
+    os::unix::io::AsRawFd,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use nix::{
+    errno::Errno,
+    libc,
+    poll::{self, PollFd, PollFlags},
+    sys::inotify::{AddWatchFlags, InitFlags, Inotify, InotifyEvent, WatchDescriptor},
+    unistd,
+};
+
+use crate::{errors::*, sys::VfsEvent};
+
+// Inotify events needed to report creates, writes, removes and renames
+const WATCH_FLAGS: AddWatchFlags = AddWatchFlags::from_bits_truncate(
+    AddWatchFlags::IN_CREATE.bits()
+        | AddWatchFlags::IN_MODIFY.bits()
+        | AddWatchFlags::IN_CLOSE_WRITE.bits()
+        | AddWatchFlags::IN_DELETE.bits()
+        | AddWatchFlags::IN_DELETE_SELF.bits()
+        | AddWatchFlags::IN_MOVED_FROM.bits()
+        | AddWatchFlags::IN_MOVED_TO.bits(),
+);
+
+#[derive(Debug)]
+pub(crate) struct StdfsWatch {
+    inotify: Inotify,                        // inotify instance owning the watches
+    root: PathBuf,                           // watched path
+    dirs: HashMap<WatchDescriptor, PathBuf>, // watched directories by descriptor
+    seen: HashSet<PathBuf>,                  // paths reported while scanning new directories
+    modified: HashSet<PathBuf>,              // files written to but not yet closed
+    pending: VecDeque<VfsEvent>,             // translated events not yet returned
+}
+
+impl StdfsWatch {
+    // Watch the given absolute path and all directories beneath it
+    pub(crate) fn new(path: &Path) -> RvResult<Self> {
+        let mut watch = Self {
+            inotify: Inotify::init(InitFlags::IN_CLOEXEC | InitFlags::IN_NONBLOCK)?,
+            root: path.to_path_buf(),
+            dirs: HashMap::new(),
+            seen: HashSet::new(),
+            modified: HashSet::new(),
+            pending: VecDeque::new(),
+        };
+        watch.add(path, false)?;
+        Ok(watch)
+    }
+
+    // Wait for the next event, forever when `timeout` is `None`
+    //
+    // * Returns `None` when the timeout expires or the inotify instance fails
+    pub(crate) fn recv(&mut self, timeout: Option<Duration>) -> Option<VfsEvent> {
+        let deadline = timeout.map(|x| Instant::now() + x);
+        loop {
+            if let Some(event) = self.pending.pop_front() {
+                return Some(event);
+            }
+            let wait = match deadline.map(|x| x.saturating_duration_since(Instant::now()).as_millis()) {
+                Some(x) => x.min(libc::c_int::MAX as u128) as libc::c_int,
+                None => -1,
+            };
+            let mut fds = [PollFd::new(self.inotify.as_raw_fd(), PollFlags::POLLIN)];
+            match poll::poll(&mut fds, wait) {
+                Ok(0) => return None,
+                Ok(_) | Err(Errno::EINTR) => {},
+                Err(_) => return None,
+            }
+            match self.inotify.read_events() {
+                Ok(events) => self.translate(events),
+                Err(Errno::EAGAIN) => {},
+                Err(_) => return None,
+            }
+        }
+    }
+
+    // Add a watch for the given path and any directories beneath it optionally reporting the
+    // entries found as created
+    fn add(&mut self, path: &Path, report: bool) -> RvResult<()> {
+        let wd = self.inotify.add_watch(path, WATCH_FLAGS)?;
+        self.dirs.insert(wd, path.to_path_buf());
+        for entry in fs::read_dir(path).into_iter().flatten().flatten() {
+            let path = entry.path();
+            if report {
+                self.seen.insert(path.clone());
+                self.pending.push_back(VfsEvent::Create(path.clone()));
+            }
+
+            // Directories that vanish while being scanned are reported as removed by their parent
+            if entry.file_type().map(|x| x.is_dir()).unwrap_or(false) {
+                let _ = self.add(&path, report);
+            }
+        }
+        Ok(())
+    }
+
+    // Drop the watches of the given directory and those beneath it
+    fn forget(&mut self, path: &Path) {
+        let wds = self.dirs.iter().filter(|(_, x)| x.starts_with(path)).map(|(x, _)| *x).collect::<Vec<_>>();
+        for wd in wds {
+            self.dirs.remove(&wd);
+            let _ = self.inotify.rm_watch(wd);
+        }
+    }
+
+    // Translate the given inotify events into Vfs events
+    fn translate(&mut self, events: Vec<InotifyEvent>) {
+        // Moves are reported as a moved from event directly followed by a moved to event sharing
+        // the same cookie. A moved from event without a match was moved out of the watched tree.
+        let mut moved: Option<(u32, PathBuf)> = None;
+        for event in events {
+            let path = match self.dirs.get(&event.wd) {
+                Some(dir) => match &event.name {
+                    Some(name) => dir.join(name),
+                    None => dir.clone(),
+                },
+                None => continue,
+            };
+            let mask = event.mask;
+            let dir = mask.contains(AddWatchFlags::IN_ISDIR);
+
+            if let Some((cookie, from)) = moved.take() {
+                if mask.contains(AddWatchFlags::IN_MOVED_TO) && event.cookie == cookie {
+                    self.renamed(&from, &path);
+                    self.pending.push_back(VfsEvent::Rename(from, path));
+                    continue;
+                }
+                self.removed(from);
+            }
+
+            if mask.contains(AddWatchFlags::IN_MOVED_FROM) {
+                moved = Some((event.cookie, path));
+            } else if mask.contains(AddWatchFlags::IN_MOVED_TO) {
+                self.pending.push_back(VfsEvent::Create(path.clone()));
+                if dir {
+                    let _ = self.add(&path, true);
+                }
+            } else if mask.contains(AddWatchFlags::IN_CREATE) {
+                if !self.seen.remove(&path) {
+                    self.pending.push_back(VfsEvent::Create(path.clone()));
+                }
+                if dir {
+                    let _ = self.add(&path, true);
+                }
+            } else if mask.contains(AddWatchFlags::IN_MODIFY) {
+                self.modified.insert(path);
+            } else if mask.contains(AddWatchFlags::IN_CLOSE_WRITE) {
+                if self.modified.remove(&path) {
+                    self.pending.push_back(VfsEvent::Write(path));
+                }
+            } else if mask.contains(AddWatchFlags::IN_DELETE) {
+                self.seen.remove(&path);
+                self.modified.remove(&path);
+                self.pending.push_back(VfsEvent::Remove(path));
+            } else if mask.contains(AddWatchFlags::IN_DELETE_SELF) && path == self.root {
+                self.pending.push_back(VfsEvent::Remove(path));
+            } else if mask.contains(AddWatchFlags::IN_IGNORED) {
+                self.dirs.remove(&event.wd);
+            }
+        }
+        if let Some((_, from)) = moved {
+            self.removed(from);
+        }
+    }
+
+    // Report the given path as removed having been moved out of the watched tree
+    fn removed(&mut self, path: PathBuf) {
+        self.forget(&path);
+        self.seen.remove(&path);
+        self.modified.remove(&path);
+        self.pending.push_back(VfsEvent::Remove(path));
+    }
+
+    // Update the paths of the watched directories beneath a renamed directory
+    fn renamed(&mut self, from: &Path, to: &Path) {
+        for path in self.dirs.values_mut() {
+            if let Ok(rel) = path.strip_prefix(from) {
+                *path = to.join(rel);
+            }
+        }
+    }
+}
+
+impl Drop for StdfsWatch {
+    fn drop(&mut self) {
+        let _ = unistd::close(self.inotify.as_raw_fd());
+    }
+}
@@ -0,0 +1,677 @@
+// Tar archives packed and unpacked purely over the Vfs trait
+//
+// Archives are written in the POSIX ustar format with GNU long name entries for paths and link
+// targets that don't fit in the header. Unpacking also accepts the pax extended headers written by
+// other tools for long paths, link targets and large sizes.
+use std::{
+    ffi::OsStr,
+    io::{self, BufReader, BufWriter, Read, Write},
+    os::unix::ffi::OsStrExt,
+    path::{Path, PathBuf},
+    time::{Duration, UNIX_EPOCH},
+};
+
+use crate::{
+    errors::*,
+    sys::{self, user, Entry, PathExt, PathRules, VirtualFileSystem},
+};
+
+// Size of the blocks headers and data are padded out to
+const BLOCK_SIZE: usize = 512;
+
+// Name GNU tar gives the pseudo entries carrying names too long for the header
+const LONG_LINK: &[u8] = b"././@LongLink";
+
+// Largest pseudo entry that will be read into memory
+const MAX_EXTENDED: u64 = 1024 * 1024;
+
+// Entry types
+const TYPE_FILE: u8 = b'0';
+const TYPE_OLD_FILE: u8 = b'\0';
+const TYPE_HARDLINK: u8 = b'1';
+const TYPE_SYMLINK: u8 = b'2';
+const TYPE_DIR: u8 = b'5';
+const TYPE_CONTIGUOUS: u8 = b'7';
+const TYPE_PAX: u8 = b'x';
+const TYPE_PAX_GLOBAL: u8 = b'g';
+const TYPE_LONG_NAME: u8 = b'L';
+const TYPE_LONG_LINK: u8 = b'K';
+
+// Header of a single archive entry
+#[derive(Debug, Default)]
+struct Header
+{
+    name: Vec<u8>, // path of the entry relative to the archive root
+    mode: u32,     // permission bits
+    uid: u32,      // user id of the owner
+    gid: u32,      // group id of the owner
+    size: u64,     // bytes of data following the header
+    mtime: u64,    // modification time in seconds since the epoch
+    kind: u8,      // entry type
+    link: Vec<u8>, // target of links
+}
+
+impl Header
+{
+    // Read the next header returning `None` at the end of the archive
+    fn read<R: Read>(reader: &mut R, archive: &Path) -> RvResult<Option<Header>>
+    {
+        let mut block = [0; BLOCK_SIZE];
+        match read_block(reader, &mut block) {
+            Ok(true) => {},
+            Ok(false) => return Ok(None),
+            Err(_) => return Err(VfsError::InvalidArchive(archive.to_path_buf()).into()),
+        }
+
+        // The archive ends with zeroed blocks
+        if block.iter().all(|x| *x == 0) {
+            return Ok(None);
+        }
+        let stored = parse_num(&block[148..156]).ok_or_else(|| VfsError::InvalidArchive(archive.to_path_buf()))?;
+        if stored != checksum(&block) {
+            return Err(VfsError::InvalidArchive(archive.to_path_buf()).into());
+        }
+
+        let num = |field: &[u8]| parse_num(field).ok_or_else(|| VfsError::InvalidArchive(archive.to_path_buf()));
+        let mut name = trim_nul(&block[0..100]).to_vec();
+        if &block[257..262] == b"ustar" && block[345] != 0 {
+            let mut prefixed = trim_nul(&block[345..500]).to_vec();
+            prefixed.push(b'/');
+            prefixed.append(&mut name);
+            name = prefixed;
+        }
+        Ok(Some(Header {
+            name,
+            mode: num(&block[100..108])? as u32,
+            uid: num(&block[108..116])? as u32,
+            gid: num(&block[116..124])? as u32,
+            size: num(&block[124..136])?,
+            mtime: num(&block[136..148])?,
+            kind: block[156],
+            link: trim_nul(&block[157..257]).to_vec(),
+        }))
+    }
+
+    // Write the header preceded by GNU long name entries when the name or link target don't fit
+    fn write<W: Write>(&self, writer: &mut W) -> RvResult<()>
+    {
+        let (prefix, name) = match split_name(&self.name) {
+            Some(x) => x,
+            None => {
+                write_long(writer, TYPE_LONG_NAME, &self.name)?;
+                (&[][..], &self.name[..100])
+            },
+        };
+        if self.link.len() > 100 {
+            write_long(writer, TYPE_LONG_LINK, &self.link)?;
+        }
+        let link = &self.link[..self.link.len().min(100)];
+
+        let mut block = [0; BLOCK_SIZE];
+        block[..name.len()].copy_from_slice(name);
+        put_num(&mut block[100..108], self.mode as u64);
+        put_num(&mut block[108..116], self.uid as u64);
+        put_num(&mut block[116..124], self.gid as u64);
+        put_num(&mut block[124..136], self.size);
+        put_num(&mut block[136..148], self.mtime);
+        block[156] = self.kind;
+        block[157..157 + link.len()].copy_from_slice(link);
+        block[257..263].copy_from_slice(b"ustar\0");
+        block[263..265].copy_from_slice(b"00");
+        block[345..345 + prefix.len()].copy_from_slice(prefix);
+        let sum = format!("{:06o}\0 ", checksum(&block));
+        block[148..156].copy_from_slice(sum.as_bytes());
+        writer.write_all(&block)?;
+        Ok(())
+    }
+}
+
+/// Pack the given file or directory tree into a tar archive at the given path
+///
+/// * Paths are stored relative to the given directory or as the base name of the given file
+/// * Stores the permission bits, ownership and modification time of each entry
+/// * Links are stored as links with their target as is and are never followed
+/// * Entries are stored ordered by name so packing the same tree gives the same archive
+pub(crate) fn tar_pack<T: VirtualFileSystem + ?Sized>(vfs: &T, src: &Path, dst: &Path) -> RvResult<()>
+{
+    let root = vfs.entry(src)?;
+    let base = if root.is_dir() && !root.is_symlink() { src.to_path_buf() } else { src.dir()? };
+
+    let mut writer = BufWriter::new(vfs.write(dst)?);
+    for entry in vfs.entries(src)?.sort_by_name() {
+        let entry = entry?;
+        let rel = entry.path().strip_prefix(&base).unwrap_or(entry.path());
+
+        // Skip the root directory itself along with the archive being written into it
+        if rel.as_os_str().is_empty() || entry.path() == dst {
+            continue;
+        }
+        let meta = entry.metadata();
+        let mut header = Header {
+            name: rel.as_os_str().as_bytes().to_vec(),
+            mode: entry.mode() & 0o7777,
+            uid: meta.uid,
+            gid: meta.gid,
+            mtime: entry.modified().duration_since(UNIX_EPOCH).map(|x| x.as_secs()).unwrap_or_default(),
+            ..Default::default()
+        };
+
+        if entry.is_symlink() {
+            header.kind = TYPE_SYMLINK;
+            header.mode = 0o777;
+            header.link = vfs.readlink(entry.path())?.as_os_str().as_bytes().to_vec();
+            header.write(&mut writer)?;
+        } else if entry.is_dir() {
+            header.kind = TYPE_DIR;
+            header.name.push(b'/');
+            header.write(&mut writer)?;
+        } else if entry.is_file() {
+            header.kind = TYPE_FILE;
+            header.size = entry.size();
+            header.write(&mut writer)?;
+
+            // Files changing size while being packed would corrupt the archive
+            let copied = io::copy(&mut vfs.read(entry.path())?.take(header.size), &mut writer)?;
+            if copied != header.size {
+                return Err(PathError::vanished(entry.path()).into());
+            }
+            writer.write_all(&[0; BLOCK_SIZE][..padding(header.size)])?;
+        } else {
+            return Err(PathError::unsupported_file_type(entry.path()).into());
+        }
+    }
+
+    // The archive ends with two zeroed blocks
+    writer.write_all(&[0; BLOCK_SIZE * 2])?;
+    writer.into_inner().map_err(|x| x.into_error())?.flush()?;
+    Ok(())
+}
+
+/// Unpack the given tar archive into the given directory creating it if needed
+///
+/// * Restores the permission bits and modification time of each entry
+/// * Restores ownership where permitted i.e. failures are only reported when running as root
+/// * Entries escaping the destination either directly or through links are refused
+/// * Links leading outside of the destination are refused so nothing can be written through them
+/// * Existing files and links are replaced while existing directories are merged into
+pub(crate) fn tar_unpack<T: VirtualFileSystem + ?Sized>(vfs: &T, src: &Path, dst: &Path) -> RvResult<()>
+{
+    let mut reader = BufReader::new(vfs.read(src)?);
+    vfs.mkdir_p(dst)?;
+
+    // Links are resolved from where the links before them lead to tell where they really lead
+    let readlink = |x: &Path| sys::link_target(vfs, x);
+    let root = sys::real_path(&vfs.abs(dst)?, true, readlink)?;
+    let escapes = |x: &Path| Ok::<_, RvError>(!sys::real_path(x, true, readlink)?.starts_with(&root));
+
+    let rules = PathRules::default();
+    let (mut long_name, mut long_link, mut long_size) = (None, None, None);
+    let mut dirs = vec![];
+    while let Some(mut header) = Header::read(&mut reader, src)? {
+        // Pseudo entries carry the name, link target or size of the entry that follows
+        if matches!(header.kind, TYPE_LONG_NAME | TYPE_LONG_LINK | TYPE_PAX | TYPE_PAX_GLOBAL) {
+            if header.size > MAX_EXTENDED {
+                return Err(VfsError::InvalidArchive(src.to_path_buf()).into());
+            }
+            let mut data = vec![];
+            read_data(&mut reader, header.size, &mut data, src)?;
+            match header.kind {
+                TYPE_LONG_NAME => long_name = Some(trim_nul(&data).to_vec()),
+                TYPE_LONG_LINK => long_link = Some(trim_nul(&data).to_vec()),
+                TYPE_PAX => {
+                    for (key, value) in pax_records(&data) {
+                        match key {
+                            b"path" => long_name = Some(value.to_vec()),
+                            b"linkpath" => long_link = Some(value.to_vec()),
+                            b"size" => long_size = std::str::from_utf8(value).ok().and_then(|x| x.parse().ok()),
+                            _ => {},
+                        }
+                    }
+                },
+                _ => {},
+            }
+            continue;
+        }
+        header.name = long_name.take().unwrap_or(header.name);
+        header.link = long_link.take().unwrap_or(header.link);
+        header.size = long_size.take().unwrap_or(header.size);
+
+        // Entries for the archive root itself e.g. `./` are skipped. Links along the way are
+        // resolved up front so entries are created where the links lead.
+        let path = match member_path(vfs, dst, Path::new(OsStr::from_bytes(&header.name)), &rules) {
+            Ok(x) => sys::real_path(&x, false, readlink)?,
+            Err(err) if err.downcast_ref::<PathError>() == Some(&PathError::Empty) => {
+                read_data(&mut reader, header.size, &mut io::sink(), src)?;
+                continue;
+            },
+            Err(err) => return Err(err),
+        };
+
+        // Existing links are replaced rather than written through as are files by anything else
+        if vfs.is_symlink(&path) || (header.kind != TYPE_DIR && vfs.is_file(&path)) {
+            vfs.remove(&path)?;
+        }
+        if header.kind != TYPE_DIR {
+            vfs.mkdir_p(path.dir()?)?;
+        }
+
+        match header.kind {
+            TYPE_FILE | TYPE_OLD_FILE | TYPE_CONTIGUOUS => {
+                let mut writer = vfs.write(&path)?;
+                read_data(&mut reader, header.size, &mut writer, src)?;
+                writer.flush()?;
+                restore(vfs, &path, &header)?;
+            },
+            TYPE_DIR => {
+                vfs.mkdir_p(&path)?;
+                dirs.push((path, header));
+            },
+            TYPE_SYMLINK => {
+                // Targets are checked as the backends store them i.e. cleaned relative to the link
+                let target = Path::new(OsStr::from_bytes(&header.link));
+                let stored = match target.is_absolute() {
+                    true => target.to_path_buf(),
+                    false => path.dir()?.mash(target),
+                };
+                if escapes(&vfs.abs(stored)?)? {
+                    return Err(PathError::escapes_base(OsStr::from_bytes(&header.name)).into());
+                }
+                vfs.symlink(&path, target)?;
+                vfs.set_mtime_only(&path, UNIX_EPOCH + Duration::from_secs(header.mtime))?;
+            },
+            TYPE_HARDLINK => {
+                let target = sys::extract_path(vfs, dst, Path::new(OsStr::from_bytes(&header.link)), &rules)?;
+                vfs.hardlink(sys::real_path(&target, false, readlink)?, &path)?;
+            },
+            _ => return Err(PathError::unsupported_file_type(&path).into()),
+        }
+    }
+
+    // Directories are restored last, deepest first, so that their contents don't update their
+    // modification times and read only directories can still be populated
+    for (path, header) in dirs.iter().rev() {
+        restore(vfs, path, header)?;
+    }
+    Ok(())
+}
+
+// Returns the path the given member should be extracted to. Links at the member itself are
+// allowed to point outside the destination as they'll be replaced rather than followed.
+fn member_path<T>(vfs: &T, dst: &Path, member: &Path, rules: &PathRules) -> RvResult<PathBuf>
+where
+    T: VirtualFileSystem + ?Sized,
+{
+    let err = match sys::extract_path(vfs, dst, member, rules) {
+        Err(err) if err.downcast_ref::<PathError>() == Some(&PathError::escapes_base(member)) => err,
+        x => return x,
+    };
+    if let (Some(parent), Some(name)) = (member.parent(), member.file_name()) {
+        let dir = match sys::extract_path(vfs, dst, parent, rules) {
+            Ok(x) => x,
+            Err(e) if e.downcast_ref::<PathError>() == Some(&PathError::Empty) => vfs.abs(dst)?,
+            Err(_) => return Err(err),
+        };
+        let path = dir.mash(name);
+        if vfs.is_symlink(&path) {
+            return Ok(path);
+        }
+    }
+    Err(err)
+}
+
+// Restore the ownership, permission bits and modification time of the given path. Ownership is
+// changed first as doing so may clear the setuid and setgid bits.
+fn restore<T: VirtualFileSystem + ?Sized>(vfs: &T, path: &Path, header: &Header) -> RvResult<()>
+{
+    if vfs.owner(path)? != (header.uid, header.gid) {
+        if let Err(err) = vfs.chown_b(path)?.owner(header.uid, header.gid).recurse(false).exec() {
+            if user::getuid() == 0 {
+                return Err(err);
+            }
+        }
+    }
+    vfs.chmod_b(path)?.no_recurse().all(header.mode & 0o7777).exec()?;
+    vfs.set_mtime_only(path, UNIX_EPOCH + Duration::from_secs(header.mtime))
+}
+
+// Read a whole block returning false when the reader is already at its end
+fn read_block<R: Read>(reader: &mut R, block: &mut [u8; BLOCK_SIZE]) -> io::Result<bool>
+{
+    let mut len = 0;
+    while len < BLOCK_SIZE {
+        match reader.read(&mut block[len..])? {
+            0 if len == 0 => return Ok(false),
+            0 => return Err(io::ErrorKind::UnexpectedEof.into()),
+            n => len += n,
+        }
+    }
+    Ok(true)
+}
+
+// Copy the entry's data to the given writer skipping the padding that follows it
+fn read_data<R: Read, W: Write + ?Sized>(reader: &mut R, size: u64, writer: &mut W, archive: &Path) -> RvResult<()>
+{
+    let padded = size + padding(size) as u64;
+    let copied = io::copy(&mut reader.take(size), writer)?;
+    let skipped = io::copy(&mut reader.take(padded - size), &mut io::sink())?;
+    if copied + skipped != padded {
+        return Err(VfsError::InvalidArchive(archive.to_path_buf()).into());
+    }
+    Ok(())
+}
+
+// Write a GNU long name entry carrying the given name or link target
+fn write_long<W: Write>(writer: &mut W, kind: u8, name: &[u8]) -> RvResult<()>
+{
+    let size = name.len() as u64 + 1;
+    let header = Header { name: LONG_LINK.to_vec(), mode: 0o644, size, kind, ..Default::default() };
+    header.write(writer)?;
+    writer.write_all(name)?;
+    writer.write_all(&[0; BLOCK_SIZE][..padding(header.size) + 1])?;
+    Ok(())
+}
+
+// Split the given name into a ustar prefix and name returning `None` if it can't be split
+fn split_name(name: &[u8]) -> Option<(&[u8], &[u8])>
+{
+    if name.len() <= 100 {
+        return Some((&[], name));
+    }
+
+    // Split on the first separator leaving no more than 100 bytes for the name
+    let i = (name.len() - 101..name.len().min(156)).find(|x| name[*x] == b'/')?;
+    match i {
+        0 => None,
+        _ => Some((&name[..i], &name[i + 1..])),
+    }
+}
+
+// Returns the header checksum i.e. the sum of its bytes with the checksum field itself as spaces
+fn checksum(block: &[u8; BLOCK_SIZE]) -> u64
+{
+    block.iter().enumerate().map(|(i, x)| if (148..156).contains(&i) { b' ' } else { *x } as u64).sum()
+}
+
+// Returns the number of zero bytes needed to pad the given size out to a whole block
+fn padding(size: u64) -> usize
+{
+    (BLOCK_SIZE - (size % BLOCK_SIZE as u64) as usize) % BLOCK_SIZE
+}
+
+// Write the given number into the field as null terminated octal falling back on the GNU base-256
+// encoding for numbers too large for octal
+fn put_num(field: &mut [u8], num: u64)
+{
+    let digits = field.len() - 1;
+    if digits * 3 >= 64 || num < 1 << (digits * 3) {
+        field[..digits].copy_from_slice(format!("{:0digits$o}", num, digits = digits).as_bytes());
+    } else {
+        field[0] = 0x80;
+        let len = field.len();
+        field[len - 8..].copy_from_slice(&num.to_be_bytes());
+    }
+}
+
+// Parse the number in the given field in either octal or the GNU base-256 encoding
+fn parse_num(field: &[u8]) -> Option<u64>
+{
+    if field[0] & 0x80 != 0 {
+        let bytes = field[1..].iter().skip_while(|x| **x == 0).collect::<Vec<_>>();
+        if bytes.len() > 8 {
+            return None;
+        }
+        return Some(bytes.iter().fold(0, |acc, x| acc << 8 | **x as u64));
+    }
+    let digits = std::str::from_utf8(field).ok()?.trim_matches(|x| x == ' ' || x == '\0');
+    match digits.is_empty() {
+        true => Some(0),
+        false => u64::from_str_radix(digits, 8).ok(),
+    }
+}
+
+// Returns the given bytes up to the first null
+fn trim_nul(bytes: &[u8]) -> &[u8]
+{
+    bytes.split(|x| *x == 0).next().unwrap_or_default()
+}
+
+// Parse the `<len> <key>=<value>\n` records of a pax extended header
+fn pax_records(data: &[u8]) -> Vec<(&[u8], &[u8])>
+{
+    let mut records = vec![];
+    let mut rest = data;
+    while let Some(space) = rest.iter().position(|x| *x == b' ') {
+        let len = match std::str::from_utf8(&rest[..space]).ok().and_then(|x| x.parse::<usize>().ok()) {
+            Some(x) if x > space + 1 && x <= rest.len() => x,
+            _ => break,
+        };
+        let record = &rest[space + 1..len - 1];
+        if let Some(eq) = record.iter().position(|x| *x == b'=') {
+            records.push((&record[..eq], &record[eq + 1..]));
+        }
+        rest = &rest[len..];
+    }
+    records
+}
+
+// Unit tests
+// -------------------------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests
+{
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use super::{Header, BLOCK_SIZE, TYPE_DIR, TYPE_FILE, TYPE_SYMLINK};
+    use crate::prelude::*;
+
+    // Returns an archive of the given (name, kind, link target or file data) members
+    fn archive(members: &[(&str, u8, &str)]) -> Vec<u8>
+    {
+        let mut data = vec![];
+        for (name, kind, value) in members {
+            let file = *kind == TYPE_FILE;
+            let header = Header {
+                name: name.as_bytes().to_vec(),
+                mode: if *kind == TYPE_DIR { 0o755 } else { 0o644 },
+                size: if file { value.len() as u64 } else { 0 },
+                kind: *kind,
+                link: if file { vec![] } else { value.as_bytes().to_vec() },
+                ..Default::default()
+            };
+            header.write(&mut data).unwrap();
+            if file {
+                data.extend_from_slice(value.as_bytes());
+                data.resize(data.len().next_multiple_of(BLOCK_SIZE), 0);
+            }
+        }
+        data.extend_from_slice(&[0; BLOCK_SIZE * 2]);
+        data
+    }
+
+    #[test]
+    fn test_vfs_pack()
+    {
+        test_pack(assert_vfs_setup!(Vfs::memfs()));
+        test_pack(assert_vfs_setup!(Vfs::stdfs()));
+    }
+    fn test_pack((vfs, tmpdir): (Vfs, PathBuf))
+    {
+        let src = tmpdir.mash("src");
+        let dir1 = src.mash("dir1");
+        let file1 = src.mash("file1");
+        let file2 = dir1.mash("file2");
+        let long = dir1.mash(format!("{}/{}", "a".repeat(120), "b".repeat(120)));
+        let link1 = src.mash("link1");
+        let link2 = src.mash(format!("link2{}", "c".repeat(120)));
+        let tar = tmpdir.mash("src.tar");
+        let dst = tmpdir.mash("dst");
+        assert_vfs_mkdir_p!(vfs, long.dir().unwrap());
+        assert_vfs_write_all!(vfs, &file1, "file1");
+        assert_vfs_write_all!(vfs, &file2, "x".repeat(1000));
+        assert_vfs_write_all!(vfs, &long, "long");
+        assert!(vfs.symlink(&link1, "dir1/file2").is_ok());
+        assert!(vfs.symlink(&link2, long.trim_prefix(&src).trim_prefix("/")).is_ok());
+        assert!(vfs.chmod_b(&src).unwrap().dirs(0o750).files(0o640).exec().is_ok());
+        assert!(vfs.chmod(&file1, 0o600).is_ok());
+        let time = UNIX_EPOCH + Duration::from_secs(1_000_000_000);
+        assert!(vfs.set_mtime_only(&file1, time).is_ok());
+        assert!(vfs.set_mtime_only(&dir1, time).is_ok());
+
+        // Packing is deterministic
+        assert!(vfs.pack(&src, &tar).is_ok());
+        assert_eq!(vfs.size(&tar).unwrap() % 512, 0);
+        let digest = vfs.read_all(&tar).unwrap();
+        assert!(vfs.pack(&src, &tar).is_ok());
+        assert_eq!(vfs.read_all(&tar).unwrap(), digest);
+
+        // Round trip the tree
+        assert!(vfs.unpack(&tar, &dst).is_ok());
+        assert_vfs_read_all!(vfs, dst.mash("file1"), "file1");
+        assert_vfs_read_all!(vfs, dst.mash("dir1/file2"), "x".repeat(1000));
+        assert_vfs_read_all!(vfs, dst.mash(long.trim_prefix(&src).trim_prefix("/")), "long");
+        assert_vfs_readlink!(vfs, dst.mash("link1"), PathBuf::from("dir1/file2"));
+        assert_vfs_readlink!(vfs, dst.mash(link2.base().unwrap()), vfs.readlink(&link2).unwrap());
+        assert_eq!(vfs.mode(dst.mash("file1")).unwrap() & 0o7777, 0o600);
+        assert_eq!(vfs.mode(dst.mash("dir1/file2")).unwrap() & 0o7777, 0o640);
+        assert_eq!(vfs.mode(dst.mash("dir1")).unwrap() & 0o7777, 0o750);
+        assert_eq!(vfs.owner(dst.mash("file1")).unwrap(), vfs.owner(&file1).unwrap());
+        assert_eq!(vfs.entry(dst.mash("file1")).unwrap().modified(), time);
+        assert_eq!(vfs.entry(dst.mash("dir1")).unwrap().modified(), time);
+
+        // Unpacking again replaces files and merges into directories
+        assert!(vfs.write_all(dst.mash("file1"), "foobar").is_ok());
+        assert!(vfs.unpack(&tar, &dst).is_ok());
+        assert_vfs_read_all!(vfs, dst.mash("file1"), "file1");
+
+        // Single files are packed by name
+        assert!(vfs.pack(&file1, &tar).is_ok());
+        assert!(vfs.unpack(&tar, tmpdir.mash("single")).is_ok());
+        assert_eq!(vfs.paths(tmpdir.mash("single")).unwrap(), vec![tmpdir.mash("single/file1")]);
+
+        // Corrupt archives
+        let mut data = vfs.read_all(&tar).unwrap().into_bytes();
+        data[0] ^= 1;
+        assert!(vfs.write_all(&tar, &data).is_ok());
+        let err = vfs.unpack(&tar, &dst).unwrap_err();
+        assert_eq!(err.downcast_ref::<VfsError>(), Some(&VfsError::InvalidArchive(tar.clone())));
+        assert!(vfs.write_all(&tar, &data[..700]).is_ok());
+        let err = vfs.unpack(&tar, &dst).unwrap_err();
+        assert_eq!(err.downcast_ref::<VfsError>(), Some(&VfsError::InvalidArchive(tar.clone())));
+
+        // Doesn't exist
+        let err = vfs.pack(tmpdir.mash("foo"), &tar).unwrap_err();
+        assert_eq!(err.downcast_ref::<PathError>(), Some(&PathError::does_not_exist(tmpdir.mash("foo"))));
+
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+
+    #[test]
+    fn test_vfs_unpack_escapes()
+    {
+        test_unpack_escapes(assert_vfs_setup!(Vfs::memfs()));
+        test_unpack_escapes(assert_vfs_setup!(Vfs::stdfs()));
+    }
+    fn test_unpack_escapes((vfs, tmpdir): (Vfs, PathBuf))
+    {
+        let src = tmpdir.mash("src");
+        let tar = tmpdir.mash("src.tar");
+        let dst = tmpdir.mash("dst");
+        let outside = tmpdir.mash("outside");
+        let sibling = tmpdir.mash("dst2");
+        assert_vfs_mkdir_p!(vfs, &outside);
+        assert_vfs_mkdir_p!(vfs, &sibling);
+
+        // A link out of the destination followed by an entry written through it. The sibling
+        // shares the destination's name as a prefix and must be rejected all the same.
+        for (target, escaped) in [(outside.clone(), &outside), (PathBuf::from("../dst2"), &sibling)] {
+            assert_vfs_remove_all!(vfs, &src);
+            assert_vfs_remove_all!(vfs, &dst);
+            assert_vfs_mkdir_p!(vfs, &src);
+            assert!(vfs.symlink(src.mash("link"), &target).is_ok());
+            assert!(vfs.pack(&src, &tar).is_ok());
+            let mut data = vfs.read_all(&tar).unwrap().into_bytes();
+            data.truncate(512);
+            let mut header = data.clone();
+            header[..100].fill(0);
+            header[..9].copy_from_slice(b"link/file");
+            header[156] = b'0';
+            header[157..257].fill(0);
+            header[148..156].fill(b' ');
+            let sum: u64 = header.iter().map(|x| *x as u64).sum();
+            header[148..156].copy_from_slice(format!("{:06o}\0 ", sum).as_bytes());
+            data.extend_from_slice(&header);
+            data.extend_from_slice(&[0; 1024]);
+            assert!(vfs.write_all(&tar, &data).is_ok());
+
+            let err = vfs.unpack(&tar, &dst).unwrap_err();
+            assert_eq!(err.downcast_ref::<PathError>(), Some(&PathError::escapes_base("link")));
+            assert_vfs_no_exists!(vfs, escaped.mash("file"));
+            assert!(!vfs.is_symlink(dst.mash("link")));
+        }
+
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+
+    #[test]
+    fn test_vfs_unpack_link_escapes()
+    {
+        test_unpack_link_escapes(assert_vfs_setup!(Vfs::memfs()));
+        test_unpack_link_escapes(assert_vfs_setup!(Vfs::stdfs()));
+    }
+    fn test_unpack_link_escapes((vfs, tmpdir): (Vfs, PathBuf))
+    {
+        let tar = tmpdir.mash("src.tar");
+        let dst = tmpdir.mash("dst");
+        let outside = tmpdir.mash("outside");
+        assert_vfs_mkdir_p!(vfs, &outside);
+        let unpack = |members: &[(&str, u8, &str)]| {
+            assert!(vfs.remove_all(&dst).is_ok());
+            assert!(vfs.write_all(&tar, archive(members)).is_ok());
+            vfs.unpack(&tar, &dst)
+        };
+        let escapes = |members: &[(&str, u8, &str)], member: &str| {
+            let err = unpack(members).unwrap_err();
+            assert_eq!(err.downcast_ref::<PathError>(), Some(&PathError::escapes_base(member)));
+            assert!(!vfs.is_symlink(dst.mash(member)));
+        };
+
+        // Relative links chained into a file member written two levels above the destination
+        escapes(
+            &[
+                ("a/", TYPE_DIR, ""),
+                ("a/d1", TYPE_SYMLINK, ".."),
+                ("l2", TYPE_SYMLINK, "../.."),
+                ("a/d1/l2/pwned", TYPE_FILE, "pwned"),
+            ],
+            "l2",
+        );
+        assert_vfs_no_exists!(vfs, tmpdir.dir().unwrap().mash("pwned"));
+
+        // Absolute links and links climbing out
+        escapes(&[("l1", TYPE_SYMLINK, &outside.to_string().unwrap()), ("l1/file", TYPE_FILE, "foo")], "l1");
+        escapes(&[("l1", TYPE_SYMLINK, "../outside"), ("l1/file", TYPE_FILE, "foo")], "l1");
+        escapes(&[("a/", TYPE_DIR, ""), ("a/l1", TYPE_SYMLINK, "../../outside")], "a/l1");
+        assert_vfs_no_exists!(vfs, outside.mash("file"));
+
+        // Links within the destination may be written through by later members
+        let members = [
+            ("a/", TYPE_DIR, ""),
+            ("a/d1", TYPE_SYMLINK, ".."),
+            ("l1", TYPE_SYMLINK, &dst.mash("a").to_string().unwrap()),
+            ("a/d1/file1", TYPE_FILE, "foo"),
+            ("l1/file2", TYPE_FILE, "bar"),
+        ];
+        assert!(unpack(&members).is_ok());
+        assert_vfs_read_all!(vfs, dst.mash("file1"), "foo");
+        assert_vfs_read_all!(vfs, dst.mash("a/file2"), "bar");
+
+        // Targets are cleaned when stored so a `..` after a link climbs from the link not its target
+        let link = |target| [("a/b/", TYPE_DIR, ""), ("d1", TYPE_SYMLINK, "a/b"), ("l1", TYPE_SYMLINK, target)];
+        escapes(&link("d1/../../outside"), "l1");
+        assert!(unpack(&link("d1/../a")).is_ok());
+        assert_vfs_readlink_abs!(vfs, dst.mash("l1"), dst.mash("a"));
+        assert_vfs_is_dir!(vfs, &outside);
+
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+}
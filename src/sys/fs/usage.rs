@@ -0,0 +1,188 @@
+use std::path::PathBuf;
+
+use crate::{
+    errors::RvResult,
+    sys::{self, Entry, VfsEntry},
+};
+
+/// Provides a builder pattern for summing up the size of a directory tree much like `du`
+///
+/// Use the Vfs function `disk_usage` to create a new instance followed by one or more options and
+/// complete the operation by calling `exec`.
+///
+/// * Sums the apparent size of the files in the tree i.e. `du -sb`
+/// * Hard linked files are only counted once
+/// * Links aren't followed by default and don't count towards the total
+///
+/// ```
+/// use rivia::prelude::*;
+///
+/// let vfs = Memfs::new();
+/// let file1 = vfs.root().mash("dir/file1");
+/// let file2 = vfs.root().mash("dir/sub/file2");
+/// assert_vfs_mkdir_p!(vfs, vfs.root().mash("dir/sub"));
+/// assert_vfs_write_all!(vfs, &file1, "foo");
+/// assert_vfs_write_all!(vfs, &file2, "foobar");
+/// assert_eq!(vfs.disk_usage(vfs.root().mash("dir")).unwrap().exec().unwrap(), 9);
+/// ```
+pub struct DiskUsage
+{
+    pub(crate) opts: DiskUsageOpts,
+    pub(crate) exec: Box<dyn Fn(DiskUsageOpts) -> RvResult<u64>>, // provider callback
+}
+
+// Internal type used to encapsulate just the options. This separates the provider implementation
+// from the options allowing for sharing options between different vfs providers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct DiskUsageOpts
+{
+    pub(crate) path: PathBuf,         // path to sum up
+    pub(crate) follow: bool,          // follow links
+    pub(crate) excludes: Vec<String>, // globs of paths to skip along with their contents
+}
+
+impl DiskUsageOpts
+{
+    // Returns true if the given entry or one of its parents was excluded
+    pub(crate) fn excluded(&self, entry: &VfsEntry) -> bool
+    {
+        let path = if entry.following() { entry.alt() } else { entry.path() };
+        sys::glob_excluded(&self.path, path, &self.excludes)
+    }
+}
+
+impl DiskUsage
+{
+    /// Exclude paths matching the given glob along with everything beneath them
+    ///
+    /// * Globs are matched against the absolute path of each entry
+    /// * May be called multiple times to exclude multiple globs
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Memfs::new();
+    /// let file1 = vfs.root().mash("file1");
+    /// let file2 = vfs.root().mash("cache/file2");
+    /// assert_vfs_mkdir_p!(vfs, vfs.root().mash("cache"));
+    /// assert_vfs_write_all!(vfs, &file1, "foo");
+    /// assert_vfs_write_all!(vfs, &file2, "foobar");
+    /// assert_eq!(vfs.disk_usage(vfs.root()).unwrap().exclude_glob("**/cache").exec().unwrap(), 3);
+    /// ```
+    pub fn exclude_glob(mut self, glob: &str) -> Self
+    {
+        self.opts.excludes.push(glob.to_string());
+        self
+    }
+
+    /// Follow links so that the size of the paths they point to are also counted
+    ///
+    /// * Default: false
+    /// * Targets reachable by more than one path are still only counted once
+    ///
+    /// ### Errors
+    /// * PathError::LinkLooping(PathBuf) when a link points back to one of its parents
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Memfs::new();
+    /// let file = vfs.root().mash("file");
+    /// let dir = vfs.root().mash("dir");
+    /// let link = dir.mash("link");
+    /// assert_vfs_mkdir_p!(vfs, &dir);
+    /// assert_vfs_write_all!(vfs, &file, "foobar");
+    /// assert_vfs_symlink!(vfs, &link, &file);
+    /// assert_eq!(vfs.disk_usage(&dir).unwrap().exec().unwrap(), 0);
+    /// assert_eq!(vfs.disk_usage(&dir).unwrap().follow().exec().unwrap(), 6);
+    /// ```
+    pub fn follow(mut self) -> Self
+    {
+        self.opts.follow = true;
+        self
+    }
+
+    /// Execute the [`DiskUsage`] options against the path provided during construction with the
+    /// Vfs `disk_usage` functions returning the total size in bytes.
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Memfs::new();
+    /// let file = vfs.root().mash("file");
+    /// assert_vfs_write_all!(vfs, &file, "foobar");
+    /// assert_eq!(vfs.disk_usage(&file).unwrap().exec().unwrap(), 6);
+    /// ```
+    pub fn exec(&self) -> RvResult<u64>
+    {
+        (self.exec)(self.opts.clone())
+    }
+}
+
+// Unit tests
+// -------------------------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests
+{
+    use crate::prelude::*;
+
+    #[test]
+    fn test_vfs_disk_usage()
+    {
+        test_disk_usage(assert_vfs_setup!(Vfs::memfs()));
+        test_disk_usage(assert_vfs_setup!(Vfs::stdfs()));
+    }
+    fn test_disk_usage((vfs, tmpdir): (Vfs, PathBuf))
+    {
+        let dir1 = tmpdir.mash("dir1");
+        let dir2 = dir1.mash("dir2");
+        let file1 = dir1.mash("file1");
+        let file2 = dir2.mash("file2");
+        let file3 = tmpdir.mash("file3");
+        let hard = dir2.mash("hard");
+        let link1 = dir1.mash("link1");
+        let link2 = dir2.mash("link2");
+        assert_vfs_mkdir_p!(vfs, &dir2);
+        assert_vfs_write_all!(vfs, &file1, "foo");
+        assert_vfs_write_all!(vfs, &file2, "foobar");
+        assert_vfs_write_all!(vfs, &file3, "foobarbaz");
+
+        // Empty directories and single files
+        assert_eq!(vfs.disk_usage(&dir2).unwrap().exclude_glob("**/file2").exec().unwrap(), 0);
+        assert_eq!(vfs.disk_usage(&file3).unwrap().exec().unwrap(), 9);
+        assert_eq!(vfs.disk_usage(&dir1).unwrap().exec().unwrap(), 9);
+        assert_eq!(vfs.disk_usage(&tmpdir).unwrap().exec().unwrap(), 18);
+
+        // Hard links are only counted once
+        assert_vfs_hardlink!(vfs, &file2, &hard);
+        assert_eq!(vfs.disk_usage(&dir1).unwrap().exec().unwrap(), 9);
+
+        // Links are only counted when followed
+        assert_vfs_symlink!(vfs, &link1, &file3);
+        assert_eq!(vfs.disk_usage(&dir1).unwrap().exec().unwrap(), 9);
+        assert_eq!(vfs.disk_usage(&dir1).unwrap().follow().exec().unwrap(), 18);
+        assert_eq!(vfs.disk_usage(&link1).unwrap().follow().exec().unwrap(), 9);
+
+        // Excluded subtrees
+        let usage = vfs.disk_usage(&tmpdir).unwrap().exclude_glob("**/dir2").exclude_glob("**/*3");
+        assert_eq!(usage.exec().unwrap(), 3);
+
+        // Looping links
+        assert_vfs_symlink!(vfs, &link2, &dir1);
+        assert_eq!(vfs.disk_usage(&dir1).unwrap().exec().unwrap(), 9);
+        let err = vfs.disk_usage(&dir1).unwrap().follow().exec().unwrap_err();
+        assert_eq!(err.downcast_ref::<PathError>(), Some(&PathError::link_looping(&dir1)));
+
+        // Doesn't exist
+        let err = vfs.disk_usage(tmpdir.mash("foo")).unwrap().exec().unwrap_err();
+        assert_eq!(err.downcast_ref::<PathError>(), Some(&PathError::does_not_exist(tmpdir.mash("foo"))));
+
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+}
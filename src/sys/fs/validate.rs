@@ -0,0 +1,346 @@
+use std::{
+    fmt,
+    os::unix::ffi::OsStrExt,
+    path::{Component, Path, PathBuf},
+};
+
+use crate::{
+    errors::*,
+    sys::{self, VirtualFileSystem},
+};
+
+// Device names reserved by Windows regardless of extension or case
+const RESERVED_NAMES: [&str; 22] = [
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8", "COM9", "LPT1",
+    "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// Provides the rules a path is checked against by `validate_path`
+///
+/// * Defaults to Linux's NAME_MAX and PATH_MAX with every other check enabled
+///
+/// ### Examples
+/// ```
+/// use rivia::prelude::*;
+///
+/// let rules = PathRules { reserved_names: false, ..Default::default() };
+/// assert!(sys::validate_path("upload/con.txt", &rules).is_empty());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PathRules
+{
+    pub max_name: usize,       // maximum bytes in a single component
+    pub max_path: usize,       // maximum bytes in the path including the terminating null
+    pub reserved_names: bool,  // reject Windows device names e.g. `CON` or `lpt1.txt`
+    pub control_chars: bool,   // reject ASCII control characters
+    pub trailing_spaces: bool, // reject components ending in a space
+}
+
+impl Default for PathRules
+{
+    fn default() -> Self
+    {
+        Self { max_name: 255, max_path: 4096, reserved_names: true, control_chars: true, trailing_spaces: true }
+    }
+}
+
+/// Describes a single way in which a path breaks the rules given to `validate_path`
+///
+/// ### Examples
+/// ```
+/// use rivia::prelude::*;
+///
+/// let violation = PathViolation::ReservedName("aux".to_string());
+/// assert_eq!(violation.to_string(), "Path component is a reserved device name: aux");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PathViolation
+{
+    PathTooLong(usize),    // length of the path in bytes
+    NameTooLong(String),   // component longer than the maximum
+    ReservedName(String),  // component that is a reserved device name
+    ControlChar(String),   // component containing a control character
+    TrailingSpace(String), // component ending in a space
+}
+
+impl fmt::Display for PathViolation
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        match self {
+            PathViolation::PathTooLong(len) => write!(f, "Path is too long at {} bytes", len),
+            PathViolation::NameTooLong(name) => write!(f, "Path component is too long: {}", name),
+            PathViolation::ReservedName(name) => write!(f, "Path component is a reserved device name: {}", name),
+            PathViolation::ControlChar(name) => {
+                write!(f, "Path component contains a control character: {:?}", name)
+            },
+            PathViolation::TrailingSpace(name) => write!(f, "Path component ends in a space: {:?}", name),
+        }
+    }
+}
+
+/// Returns the ways in which the given path breaks the given rules
+///
+/// * An empty result indicates that the path is valid
+/// * Only the path as given is checked, no expansion or absolute path resolution is done
+/// * Lengths are measured in bytes as the kernel does
+/// * Components are checked in order with the path length checked first
+/// * Reserved names are matched case insensitively ignoring any extension e.g. `Con.txt`
+///
+/// ### Examples
+/// ```
+/// use rivia::prelude::*;
+///
+/// assert!(sys::validate_path("dir/file.txt", &PathRules::default()).is_empty());
+/// assert_eq!(sys::validate_path("dir/nul.txt", &PathRules::default()), vec![
+///     PathViolation::ReservedName("nul.txt".to_string())
+/// ]);
+/// ```
+pub fn validate_path<T: AsRef<Path>>(path: T, rules: &PathRules) -> Vec<PathViolation>
+{
+    let path = path.as_ref();
+    let mut violations = vec![];
+    let len = path.as_os_str().as_bytes().len();
+    if len + 1 > rules.max_path {
+        violations.push(PathViolation::PathTooLong(len));
+    }
+
+    for component in path.components() {
+        let name = match component {
+            Component::Normal(x) => x,
+            _ => continue,
+        };
+        let bytes = name.as_bytes();
+        let lossy = name.to_string_lossy().to_string();
+        if bytes.len() > rules.max_name {
+            violations.push(PathViolation::NameTooLong(lossy.clone()));
+        }
+        if rules.reserved_names {
+            let stem = lossy.split('.').next().unwrap_or_default().trim_end();
+            if RESERVED_NAMES.iter().any(|x| x.eq_ignore_ascii_case(stem)) {
+                violations.push(PathViolation::ReservedName(lossy.clone()));
+            }
+        }
+        if rules.control_chars && bytes.iter().any(|x| x.is_ascii_control()) {
+            violations.push(PathViolation::ControlChar(lossy.clone()));
+        }
+        if rules.trailing_spaces && bytes.last() == Some(&b' ') {
+            violations.push(PathViolation::TrailingSpace(lossy));
+        }
+    }
+    violations
+}
+
+/// Returns the absolute path the given archive member should be extracted to within `dest`
+///
+/// Guards archive extraction against zip-slip i.e. hostile members written outside of the
+/// destination directory. Call this for every member before creating its entry.
+///
+/// * Handles path expansion and absolute path resolution for `dest` but not for `member`
+/// * `.` and `..` components are resolved lexically and may not climb above `dest`
+/// * Links already under `dest`, including those created by earlier members, are resolved and
+///   must lead within `dest` so that a member can't be written through a link out of it. Each
+///   link is resolved from where the links before it led so chained relative links can't climb
+///   out of `dest` either.
+/// * The member's name must pass `validate_path` with the given rules
+///
+/// ### Errors
+/// * PathError::Empty when the member is empty or resolves to `dest` itself
+/// * PathError::EscapesBase(PathBuf) when the member is absolute, climbs above `dest` or passes
+///   through a link that points outside of `dest`
+/// * PathError::InvalidName(PathBuf) when the member breaks the given rules
+///
+/// ### Examples
+/// ```
+/// use rivia::prelude::*;
+///
+/// let vfs = Vfs::memfs();
+/// let dest = vfs.root().mash("dest");
+/// let rules = PathRules::default();
+/// assert_eq!(sys::extract_path(&vfs, &dest, "dir/./file", &rules).unwrap(), dest.mash("dir/file"));
+/// assert_eq!(
+///     sys::extract_path(&vfs, &dest, "../etc/passwd", &rules).unwrap_err().downcast_ref::<PathError>(),
+///     Some(&PathError::escapes_base("../etc/passwd"))
+/// );
+/// ```
+pub fn extract_path<T, U, V>(vfs: &T, dest: U, member: V, rules: &PathRules) -> RvResult<PathBuf>
+where
+    T: VirtualFileSystem + ?Sized,
+    U: AsRef<Path>,
+    V: AsRef<Path>,
+{
+    let member = member.as_ref();
+    if !validate_path(member, rules).is_empty() {
+        return Err(PathError::invalid_name(member).into());
+    }
+
+    // Resolve the member lexically refusing anything that isn't relative to the destination
+    let mut rel = PathBuf::new();
+    for component in member.components() {
+        match component {
+            Component::Normal(x) => rel.push(x),
+            Component::CurDir => {},
+            Component::ParentDir if rel.pop() => {},
+            _ => return Err(PathError::escapes_base(member).into()),
+        }
+    }
+    if rel.as_os_str().is_empty() {
+        return Err(PathError::Empty.into());
+    }
+
+    // Check that the links along the way, even dangling ones, lead to somewhere within the
+    // destination once each is resolved from where the link before it led. The comparison is
+    // component wise so that a sibling e.g. `dest2` isn't mistaken for `dest`.
+    let dest = sys::clean(vfs.abs(dest)?);
+    let path = dest.join(&rel);
+    let readlink = |x: &Path| sys::link_target(vfs, x);
+    if !sys::real_path(&path, true, readlink)?.starts_with(sys::real_path(&dest, true, readlink)?) {
+        return Err(PathError::escapes_base(member).into());
+    }
+    Ok(path)
+}
+
+// Unit tests
+// -------------------------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests
+{
+    use crate::prelude::*;
+
+    #[test]
+    fn test_vfs_extract_path()
+    {
+        test_extract_path(assert_vfs_setup!(Vfs::memfs()));
+        test_extract_path(assert_vfs_setup!(Vfs::stdfs()));
+    }
+    fn test_extract_path((vfs, tmpdir): (Vfs, PathBuf))
+    {
+        let dest = tmpdir.mash("dest");
+        let dir1 = dest.mash("dir1");
+        let outside = tmpdir.mash("outside");
+        let rules = PathRules::default();
+        assert_vfs_mkdir_p!(vfs, &dir1);
+        assert_vfs_mkdir_p!(vfs, &outside);
+        let escapes = |member: &str| {
+            assert_eq!(
+                sys::extract_path(&vfs, &dest, member, &rules).unwrap_err().downcast_ref::<PathError>(),
+                Some(&PathError::escapes_base(member))
+            );
+        };
+
+        // Well behaved members
+        assert_eq!(sys::extract_path(&vfs, &dest, "file", &rules).unwrap(), dest.mash("file"));
+        assert_eq!(sys::extract_path(&vfs, &dest, "./dir1/file", &rules).unwrap(), dir1.mash("file"));
+        assert_eq!(sys::extract_path(&vfs, &dest, "dir1/../dir2/file", &rules).unwrap(), dest.mash("dir2/file"));
+
+        // Empty and invalid members
+        for member in ["", ".", "dir1/.."] {
+            assert_eq!(
+                sys::extract_path(&vfs, &dest, member, &rules).unwrap_err().downcast_ref::<PathError>(),
+                Some(&PathError::Empty)
+            );
+        }
+        assert_eq!(
+            sys::extract_path(&vfs, &dest, "dir1/con", &rules).unwrap_err().downcast_ref::<PathError>(),
+            Some(&PathError::invalid_name("dir1/con"))
+        );
+        assert_eq!(
+            sys::extract_path(&vfs, &dest, "file\n", &rules).unwrap_err().downcast_ref::<PathError>(),
+            Some(&PathError::invalid_name("file\n"))
+        );
+
+        // Absolute members and parent escapes
+        escapes("/etc/passwd");
+        escapes("../outside/file");
+        escapes("dir1/../../outside/file");
+        escapes("dir1/../..");
+
+        // Links pointing out of the destination can't be written through or over
+        assert!(vfs.symlink(dest.mash("link1"), &outside).is_ok());
+        assert!(vfs.symlink(dest.mash("link2"), "../outside/file").is_ok());
+        assert!(vfs.symlink(dest.mash("link3"), "link1").is_ok());
+        escapes("link1/file");
+        escapes("link2");
+        escapes("link3/file");
+
+        // Links into a sibling sharing the destination's name as a prefix
+        let sibling = tmpdir.mash("dest2");
+        assert_vfs_mkdir_p!(vfs, &sibling);
+        assert!(vfs.symlink(dest.mash("link5"), "../dest2").is_ok());
+        assert!(vfs.symlink(dest.mash("link6"), &sibling).is_ok());
+        escapes("link5/file");
+        escapes("link6");
+
+        // Relative links chained so each climbs from where the one before it led
+        assert_vfs_mkdir_p!(vfs, dest.mash("a"));
+        assert!(vfs.symlink(dest.mash("a/d1"), "..").is_ok());
+        assert!(vfs.symlink(dest.mash("l2"), "../..").is_ok());
+        escapes("a/d1/l2/pwned");
+        escapes("a/d1/l2");
+        assert_vfs_no_exists!(vfs, tmpdir.dir().unwrap().mash("pwned"));
+
+        // Links within the destination are fine
+        assert!(vfs.symlink(dest.mash("link4"), "dir1").is_ok());
+        assert_eq!(sys::extract_path(&vfs, &dest, "link4/file", &rules).unwrap(), dest.mash("link4/file"));
+        assert_eq!(sys::extract_path(&vfs, &dest, "a/d1/dir1/file", &rules).unwrap(), dest.mash("a/d1/dir1/file"));
+
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+
+    #[test]
+    fn test_validate_path()
+    {
+        let rules = PathRules::default();
+
+        // Valid paths
+        assert!(sys::validate_path("", &rules).is_empty());
+        assert!(sys::validate_path("/", &rules).is_empty());
+        assert!(sys::validate_path("../dir/./file.txt", &rules).is_empty());
+        assert!(sys::validate_path("console/comet/lpt10", &rules).is_empty());
+        assert!(sys::validate_path(" leading space", &rules).is_empty());
+
+        // Lengths
+        let name = "a".repeat(255);
+        assert!(sys::validate_path(&name, &rules).is_empty());
+        assert_eq!(sys::validate_path(format!("dir/{}b", name), &rules), vec![PathViolation::NameTooLong(
+            format!("{}b", name)
+        )]);
+        let path = "a/".repeat(2047) + "a";
+        assert!(sys::validate_path(&path, &rules).is_empty());
+        assert_eq!(sys::validate_path(format!("{}b", path), &rules), vec![PathViolation::PathTooLong(4096)]);
+
+        // Reserved names regardless of case or extension
+        for name in ["CON", "con", "Aux.txt", "nul.tar.gz", "COM1", "lpt9.log", "PRN "] {
+            let violations = sys::validate_path(format!("dir/{}", name), &rules);
+            assert!(violations.contains(&PathViolation::ReservedName(name.to_string())));
+        }
+
+        // Control characters and trailing spaces
+        let violations = sys::validate_path("dir/file\n", &rules);
+        assert_eq!(violations, vec![PathViolation::ControlChar("file\n".to_string())]);
+        assert_eq!(sys::validate_path("dir/\x7f", &rules), vec![PathViolation::ControlChar("\x7f".to_string())]);
+        let violations = sys::validate_path("dir /file", &rules);
+        assert_eq!(violations, vec![PathViolation::TrailingSpace("dir ".to_string())]);
+
+        // Multiple violations are reported in order
+        assert_eq!(sys::validate_path("aux /\tfile", &rules), vec![
+            PathViolation::ReservedName("aux ".to_string()),
+            PathViolation::TrailingSpace("aux ".to_string()),
+            PathViolation::ControlChar("\tfile".to_string()),
+        ]);
+
+        // Rules can be relaxed
+        let rules = PathRules {
+            max_name: 4,
+            max_path: 9,
+            reserved_names: false,
+            control_chars: false,
+            trailing_spaces: false,
+        };
+        assert!(sys::validate_path("aux /\tf", &rules).is_empty());
+        assert_eq!(sys::validate_path("aux/files", &rules), vec![
+            PathViolation::PathTooLong(9),
+            PathViolation::NameTooLong("files".to_string())
+        ]);
+    }
+}
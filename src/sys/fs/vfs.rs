@@ -1,13 +1,24 @@
 use std::{
     fmt::Debug,
-    io::Write,
+    io::{BufRead, BufReader},
     path::{Path, PathBuf},
+    time::{Duration, SystemTime},
 };
 
+#[cfg(feature = "digest")]
+use super::Algo;
 use super::Chown;
+#[cfg(feature = "csv")]
+use super::CsvIter;
+#[cfg(feature = "json")]
+use super::JsonlIter;
 use crate::{
     errors::*,
-    sys::{Chmod, Copier, Entries, Memfs, Stdfs, VfsEntry},
+    sys::{
+        self, Acl, Advice, Chmod, Copier, DirHandle, DiskUsage, Ensured, Entries, Installer, Memfs, Mover,
+        Overlayfs, Reader, Remover, ResolvedLink, Stdfs, VfsBuilder, VfsConfig, VfsEntry, VfsMetadata,
+        VfsOp, VfsWatcher, VfsWriter, Writer,
+    },
 };
 
 /// Defines a combination of the Read + Seek traits
@@ -16,6 +27,34 @@ pub trait ReadSeek: std::io::Read + std::io::Seek {}
 // Blanket implementation for any type that implements Read + Seek
 impl<T> ReadSeek for T where T: std::io::Read + std::io::Seek {}
 
+/// Defines a combination of the Read + Write + Seek traits
+pub trait ReadWriteSeek: std::io::Read + std::io::Write + std::io::Seek {}
+
+// Blanket implementation for any type that implements Read + Write + Seek
+impl<T> ReadWriteSeek for T where T: std::io::Read + std::io::Write + std::io::Seek {}
+
+/// Describes the capacity and usage of the filesystem containing a path
+///
+/// ### Examples
+/// ```
+/// use rivia::prelude::*;
+///
+/// let vfs = Vfs::memfs();
+/// assert_vfs_write_all!(vfs, "file", "foobar");
+/// let stat = vfs.stat_fs(vfs.root()).unwrap();
+/// assert_eq!(stat.used, 6);
+/// assert_eq!(stat.free, stat.total - stat.used);
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct VfsStatFs {
+    pub total: u64,       // total size in bytes
+    pub used: u64,        // number of bytes in use
+    pub free: u64,        // number of bytes available for use
+    pub inodes: u64,      // total number of entries
+    pub inodes_used: u64, // number of entries in use
+    pub inodes_free: u64, // number of entries available for use
+}
+
 /// Defines a virtual file system that can be implemented by various backed providers
 pub trait VirtualFileSystem: Debug + Send + Sync + 'static {
     /// Return the path in an absolute clean form
@@ -37,6 +76,31 @@ pub trait VirtualFileSystem: Debug + Send + Sync + 'static {
     /// ```
     fn abs<T: AsRef<Path>>(&self, path: T) -> RvResult<PathBuf>;
 
+    /// Advise the kernel how the given file's data will be accessed
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Stdfs uses `posix_fadvise` on the whole file e.g. `Advice::DontNeed` drops the file from
+    ///   the page cache so that streaming scanners don't pollute it
+    /// * `Advice::Sequential` and `Advice::Random` only apply to the handle they are given on Linux
+    ///   so use `Reader::advise` for those instead
+    /// * Memfs has no page cache so this is a no-op
+    ///
+    /// ### Errors
+    /// * PathError::IsNotFile(PathBuf) when the given path isn't a file
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let file = vfs.root().mash("file");
+    /// assert_vfs_write_all!(vfs, &file, "this is a test");
+    /// assert!(vfs.advise(&file, Advice::DontNeed).is_ok());
+    /// assert_vfs_read_all!(vfs, &file, "this is a test");
+    /// ```
+    fn advise<T: AsRef<Path>>(&self, path: T, advice: Advice) -> RvResult<()>;
+
     /// Returns all dirs for the given path recursively
     ///
     /// * Results are sorted by filename, are distict and don't include the given path
@@ -102,10 +166,36 @@ pub trait VirtualFileSystem: Debug + Send + Sync + 'static {
     /// ```
     fn all_paths<T: AsRef<Path>>(&self, path: T) -> RvResult<Vec<PathBuf>>;
 
+    /// Allocate space for the given file up front to avoid fragmentation and fail early when there
+    /// isn't enough space
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Creates the file if it doesn't exist
+    /// * Grows the file to `len` bytes filled with zeros if smaller, existing data is left intact
+    /// * Stdfs uses `posix_fallocate` while Memfs reserves the file's buffer
+    ///
+    /// ### Errors
+    /// * PathError::IsNotDir(PathBuf) when the given path's parent exists but is not a directory
+    /// * PathError::DoesNotExist(PathBuf) when the given path's parent doesn't exist
+    /// * PathError::IsNotFile(PathBuf) when the given path exists but is not a file
+    /// * VfsError::QuotaExceeded(PathBuf) when Memfs quota would be exceeded
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let file = vfs.root().mash("file");
+    /// assert!(vfs.allocate(&file, 1024).is_ok());
+    /// assert_eq!(vfs.entry(&file).unwrap().size(), 1024);
+    /// ```
+    fn allocate<T: AsRef<Path>>(&self, path: T, len: u64) -> RvResult<()>;
+
     /// Opens a file in append mode
     ///
     /// * Handles path expansion and absolute path resolution
     /// * Creates a file if it does not exist or appends to it if it does
+    /// * Returns a [`VfsWriter`] reporting the path and number of bytes written
     ///
     /// ### Errors
     /// * PathError::IsNotDir(PathBuf) when the given path's parent exists but is not a directory
@@ -126,7 +216,7 @@ pub trait VirtualFileSystem: Debug + Send + Sync + 'static {
     /// f.flush().unwrap();
     /// assert_vfs_read_all!(vfs, &file, "foobar123".to_string());
     /// ```
-    fn append<T: AsRef<Path>>(&self, path: T) -> RvResult<Box<dyn Write>>;
+    fn append<T: AsRef<Path>>(&self, path: T) -> RvResult<VfsWriter>;
 
     /// Append the given data to to the target file
     ///
@@ -281,6 +371,19 @@ pub trait VirtualFileSystem: Debug + Send + Sync + 'static {
     /// ```
     fn chown_b<T: AsRef<Path>>(&self, path: T) -> RvResult<Chown>;
 
+    /// Returns the config of the Vfs backend instance
+    ///
+    /// * Set with `Memfs::with_config` or `Stdfs::with_config` else the defaults apply
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// assert_eq!(vfs.config(), VfsConfig::default());
+    /// ```
+    fn config(&self) -> VfsConfig;
+
     /// Returns the highest priority active configuration directory.
     ///
     /// * Searches first the $XDG_CONFIG_HOME directory, then the $XDG_CONFIG_DIRS directories.
@@ -364,6 +467,34 @@ pub trait VirtualFileSystem: Debug + Send + Sync + 'static {
     /// ```
     fn cwd(&self) -> RvResult<PathBuf>;
 
+    /// Returns the hash of the given file's contents as a lower case hex string
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Links are followed
+    /// * Streams the contents through the hasher so large files aren't read into memory
+    /// * Identical contents give the same hash regardless of the backend they're stored in
+    /// * Use `tree_digest` to hash a directory tree
+    ///
+    /// ### Errors
+    /// * PathError::IsNotFile(PathBuf) when the given path isn't a file
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// assert_vfs_write_all!(vfs, "file", "foobar");
+    /// assert_eq!(
+    ///     vfs.digest("file", Algo::Sha256).unwrap(),
+    ///     "c3ab8ff13720e8ad9047dd39466b3c8974e592c2fa383d4a3960714caef0c4f2"
+    /// );
+    /// ```
+    #[cfg(feature = "digest")]
+    fn digest<T: AsRef<Path>>(&self, path: T, algo: Algo) -> RvResult<String> {
+        super::file_digest(self, &self.abs(path)?, algo)
+    }
+
     /// Returns all directories for the given path, sorted by name
     ///
     /// * Handles path expansion and absolute path resolution
@@ -386,6 +517,28 @@ pub trait VirtualFileSystem: Debug + Send + Sync + 'static {
     /// ```
     fn dirs<T: AsRef<Path>>(&self, path: T) -> RvResult<Vec<PathBuf>>;
 
+    /// Creates a new [`DiskUsage`] for summing up the size of the given path much like `du`
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Recursively sums the apparent size of the files in the tree counting hard links once
+    /// * Options for excluding globs and following links
+    /// * Execute by calling `exec`
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let tmpdir = vfs.root();
+    /// let file1 = tmpdir.mash("file1");
+    /// let file2 = tmpdir.mash("dir/file2");
+    /// assert_vfs_mkdir_p!(vfs, tmpdir.mash("dir"));
+    /// assert_vfs_write_all!(vfs, &file1, "foo");
+    /// assert_vfs_write_all!(vfs, &file2, "foobar");
+    /// assert_eq!(vfs.disk_usage(&tmpdir).unwrap().exec().unwrap(), 9);
+    /// ```
+    fn disk_usage<T: AsRef<Path>>(&self, path: T) -> RvResult<DiskUsage>;
+
     /// Returns an iterator over the given path
     ///
     /// * Handles path expansion and absolute path resolution
@@ -420,58 +573,80 @@ pub trait VirtualFileSystem: Debug + Send + Sync + 'static {
     /// ```
     fn entry<T: AsRef<Path>>(&self, path: T) -> RvResult<VfsEntry>;
 
-    /// Returns true if the `path` exists
+    /// Ensures the given directory exists with the given mode and ownership
     ///
     /// * Handles path expansion and absolute path resolution
+    /// * Creates the directory and any parent directories needed if it doesn't exist
+    /// * Only changes the mode and ownership when they differ from those given
+    /// * Links are followed so the directories they point to are affected
+    /// * Returns true if anything was changed
+    ///
+    /// ### Errors
+    /// * PathError::IsNotDir(PathBuf) when the given path exists but is not a directory
     ///
     /// ### Examples
     /// ```
     /// use rivia::prelude::*;
     ///
     /// let vfs = Vfs::memfs();
-    /// let dir = vfs.root().mash("foo");
-    /// assert_eq!(vfs.exists(&dir), false);
-    /// assert_vfs_mkdir_p!(vfs, &dir);
-    /// assert_eq!(vfs.exists(&dir), true);
+    /// let dir = vfs.root().mash("dir");
+    /// assert_eq!(vfs.ensure_dir(&dir, 0o700, 5, 5).unwrap(), true);
+    /// assert_eq!(vfs.ensure_dir(&dir, 0o700, 5, 5).unwrap(), false);
+    /// assert_eq!(vfs.mode(&dir).unwrap(), 0o40700);
+    /// assert_eq!(vfs.owner(&dir).unwrap(), (5, 5));
     /// ```
-    fn exists<T: AsRef<Path>>(&self, path: T) -> bool;
+    fn ensure_dir<T: AsRef<Path>>(&self, path: T, mode: u32, uid: u32, gid: u32) -> RvResult<bool>;
 
-    /// Returns all files for the given path, sorted by name
+    /// Ensures the given file exists with the given data and mode
     ///
     /// * Handles path expansion and absolute path resolution
-    /// * Paths are returned as abs paths
-    /// * Doesn't include the path itself only its children nor is this recursive
+    /// * Only writes the data when it differs from the file's current content
+    /// * Only changes the mode when it differs from the one given
+    /// * Links are followed so the files they point to are affected
+    /// * Returns true if anything was changed
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path's parent doesn't exist
+    /// * PathError::IsNotFile(PathBuf) when the given path exists but is not a file
     ///
     /// ### Examples
     /// ```
     /// use rivia::prelude::*;
     ///
     /// let vfs = Vfs::memfs();
-    /// let tmpdir = vfs.root().mash("tmpdir");
-    /// let dir1 = tmpdir.mash("dir1");
-    /// let file1 = tmpdir.mash("file1");
-    /// let file2 = tmpdir.mash("file2");
-    /// assert_vfs_mkdir_p!(vfs, &dir1);
-    /// assert_vfs_mkfile!(vfs, &file1);
-    /// assert_vfs_mkfile!(vfs, &file2);
-    /// assert_iter_eq(vfs.files(&tmpdir).unwrap(), vec![file1, file2]);
+    /// let file = vfs.root().mash("file");
+    /// assert_eq!(vfs.ensure_file(&file, "foo", 0o600).unwrap(), true);
+    /// assert_eq!(vfs.ensure_file(&file, "foo", 0o600).unwrap(), false);
+    /// assert_vfs_read_all!(vfs, &file, "foo");
+    /// assert_eq!(vfs.mode(&file).unwrap(), 0o100600);
     /// ```
-    fn files<T: AsRef<Path>>(&self, path: T) -> RvResult<Vec<PathBuf>>;
+    fn ensure_file<T: AsRef<Path>, U: AsRef<[u8]>>(&self, path: T, data: U, mode: u32) -> RvResult<bool>;
 
-    /// Returns the group ID of the owner of this file
+    /// Ensures the given link exists and points to the given target
     ///
     /// * Handles path expansion and absolute path resolution
+    /// * Creates the link if it doesn't exist
+    /// * Replaces the link if it points somewhere else, atomically for Stdfs
+    /// * Dangling links are supported
+    /// * Returns true if anything was changed
+    ///
+    /// ### Errors
+    /// * PathError::IsNotSymlink(PathBuf) when the given link path exists but is not a link
     ///
     /// ### Examples
     /// ```
     /// use rivia::prelude::*;
     ///
     /// let vfs = Vfs::memfs();
-    /// assert_eq!(vfs.gid(vfs.root()).unwrap(), 1000);
+    /// let link = vfs.root().mash("link");
+    /// assert_eq!(vfs.ensure_symlink(&link, "file1").unwrap(), true);
+    /// assert_eq!(vfs.ensure_symlink(&link, "file1").unwrap(), false);
+    /// assert_eq!(vfs.ensure_symlink(&link, "file2").unwrap(), true);
+    /// assert_eq!(vfs.readlink(&link).unwrap(), PathBuf::from("file2"));
     /// ```
-    fn gid<T: AsRef<Path>>(&self, path: T) -> RvResult<u32>;
+    fn ensure_symlink<T: AsRef<Path>, U: AsRef<Path>>(&self, link: T, target: U) -> RvResult<bool>;
 
-    /// Returns true if the given path exists and is readonly
+    /// Returns true if the `path` exists
     ///
     /// * Handles path expansion and absolute path resolution
     ///
@@ -480,18 +655,17 @@ pub trait VirtualFileSystem: Debug + Send + Sync + 'static {
     /// use rivia::prelude::*;
     ///
     /// let vfs = Vfs::memfs();
-    /// let file = vfs.root().mash("file");
-    /// assert!(vfs.mkfile_m(&file, 0o644).is_ok());
-    /// assert_eq!(vfs.is_exec(&file), false);
-    /// assert!(vfs.chmod(&file, 0o777).is_ok());
-    /// assert_eq!(vfs.is_exec(&file), true);
+    /// let dir = vfs.root().mash("foo");
+    /// assert_eq!(vfs.exists(&dir), false);
+    /// assert_vfs_mkdir_p!(vfs, &dir);
+    /// assert_eq!(vfs.exists(&dir), true);
     /// ```
-    fn is_exec<T: AsRef<Path>>(&self, path: T) -> bool;
+    fn exists<T: AsRef<Path>>(&self, path: T) -> bool;
 
-    /// Returns true if the given path exists and is a directory
+    /// Returns whether each of the given `paths` exists in the same order
     ///
     /// * Handles path expansion and absolute path resolution
-    /// * Link exclusion i.e. links even if pointing to a directory return false
+    /// * Checks all paths in a single pass e.g. Memfs only acquires its lock once
     ///
     /// ### Examples
     /// ```
@@ -499,50 +673,58 @@ pub trait VirtualFileSystem: Debug + Send + Sync + 'static {
     ///
     /// let vfs = Vfs::memfs();
     /// let dir = vfs.root().mash("dir");
-    /// assert_eq!(vfs.is_dir(&dir), false);
     /// assert_vfs_mkdir_p!(vfs, &dir);
-    /// assert_eq!(vfs.is_dir(&dir), true);
+    /// assert_eq!(vfs.exists_all(&[vfs.root().mash("foo"), dir]), vec![false, true]);
     /// ```
-    fn is_dir<T: AsRef<Path>>(&self, path: T) -> bool;
+    fn exists_all<T: AsRef<Path>>(&self, paths: &[T]) -> Vec<bool>;
 
-    /// Returns true if the given path exists and is a file
+    /// Returns all files for the given path, sorted by name
     ///
     /// * Handles path expansion and absolute path resolution
-    /// * Link exclusion i.e. links even if pointing to a file return false
+    /// * Paths are returned as abs paths
+    /// * Doesn't include the path itself only its children nor is this recursive
     ///
     /// ### Examples
     /// ```
     /// use rivia::prelude::*;
     ///
     /// let vfs = Vfs::memfs();
-    /// let file = vfs.root().mash("file");
-    /// assert_eq!(vfs.is_file(&file), false);
-    /// assert_vfs_mkfile!(vfs, &file);
-    /// assert_eq!(vfs.is_file(&file), true);
+    /// let tmpdir = vfs.root().mash("tmpdir");
+    /// let dir1 = tmpdir.mash("dir1");
+    /// let file1 = tmpdir.mash("file1");
+    /// let file2 = tmpdir.mash("file2");
+    /// assert_vfs_mkdir_p!(vfs, &dir1);
+    /// assert_vfs_mkfile!(vfs, &file1);
+    /// assert_vfs_mkfile!(vfs, &file2);
+    /// assert_iter_eq(vfs.files(&tmpdir).unwrap(), vec![file1, file2]);
     /// ```
-    fn is_file<T: AsRef<Path>>(&self, path: T) -> bool;
+    fn files<T: AsRef<Path>>(&self, path: T) -> RvResult<Vec<PathBuf>>;
 
-    /// Returns true if the given path exists and is readonly
+    /// Returns the absolute path of the first of the given `paths` that exists
     ///
     /// * Handles path expansion and absolute path resolution
+    /// * Checks all paths in a single pass stopping at the first that exists
     ///
     /// ### Examples
     /// ```
     /// use rivia::prelude::*;
     ///
     /// let vfs = Vfs::memfs();
-    /// let file = vfs.root().mash("file");
-    /// assert!(vfs.mkfile_m(&file, 0o644).is_ok());
-    /// assert_eq!(vfs.is_readonly(&file), false);
-    /// assert!(vfs.chmod_b(&file).unwrap().readonly().exec().is_ok());
-    /// assert_eq!(vfs.mode(&file).unwrap(), 0o100444);
-    /// assert_eq!(vfs.is_readonly(&file), true);
+    /// let dir = vfs.root().mash("dir");
+    /// assert_vfs_mkdir_p!(vfs, &dir);
+    /// assert_eq!(vfs.first_existing(&[vfs.root().mash("foo"), dir.clone()]), Some(dir));
+    /// assert_eq!(vfs.first_existing(&["foo"]), None);
     /// ```
-    fn is_readonly<T: AsRef<Path>>(&self, path: T) -> bool;
+    fn first_existing<T: AsRef<Path>>(&self, paths: &[T]) -> Option<PathBuf>;
 
-    /// Returns true if the given path exists and is a symlink
+    /// Returns the POSIX access control list of the given path
     ///
     /// * Handles path expansion and absolute path resolution
+    /// * Links are followed
+    /// * Paths without an ACL of their own return the minimal ACL matching their mode
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
     ///
     /// ### Examples
     /// ```
@@ -550,60 +732,53 @@ pub trait VirtualFileSystem: Debug + Send + Sync + 'static {
     ///
     /// let vfs = Vfs::memfs();
     /// let file = vfs.root().mash("file");
-    /// let link = vfs.root().mash("link");
-    /// assert_eq!(vfs.is_symlink(&link), false);
-    /// assert_vfs_symlink!(vfs, &link, &file);
-    /// assert_eq!(vfs.is_symlink(&link), true);
+    /// assert!(vfs.mkfile_m(&file, 0o640).is_ok());
+    /// assert_eq!(vfs.get_acl(&file).unwrap(), Acl::from_mode(0o640));
     /// ```
-    fn is_symlink<T: AsRef<Path>>(&self, path: T) -> bool;
+    fn get_acl<T: AsRef<Path>>(&self, path: T) -> RvResult<Acl>;
 
-    /// Returns true if the given path exists and is a symlink pointing to a directory
+    /// Returns the SELinux security context of the given path
     ///
     /// * Handles path expansion and absolute path resolution
-    /// * Checks the path itself and what it points to
+    /// * Links aren't followed so that the context of the link itself is returned
+    /// * Returns None when the path isn't labeled e.g. SELinux isn't supported by the filesystem
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
     ///
     /// ### Examples
     /// ```
     /// use rivia::prelude::*;
     ///
     /// let vfs = Vfs::memfs();
-    /// let dir = vfs.root().mash("dir");
     /// let file = vfs.root().mash("file");
-    /// let link1 = vfs.root().mash("link1");
-    /// let link2 = vfs.root().mash("link2");
-    /// assert_vfs_mkdir_p!(vfs, &dir);
     /// assert_vfs_mkfile!(vfs, &file);
-    /// assert_vfs_symlink!(vfs, &link1, &dir);
-    /// assert_vfs_symlink!(vfs, &link2, &file);
-    /// assert_eq!(vfs.is_symlink_dir(&link1), true);
-    /// assert_eq!(vfs.is_symlink_dir(&link2), false);
+    /// assert_eq!(vfs.get_selinux_context(&file).unwrap(), None);
     /// ```
-    fn is_symlink_dir<T: AsRef<Path>>(&self, path: T) -> bool;
+    #[cfg(feature = "selinux")]
+    fn get_selinux_context<T: AsRef<Path>>(&self, path: T) -> RvResult<Option<String>>;
 
-    /// Returns true if the given path exists and is a symlink pointing to a file
+    /// Returns the group ID of the owner of this file
     ///
     /// * Handles path expansion and absolute path resolution
-    /// * Checks the path itself and what it points to
     ///
     /// ### Examples
     /// ```
     /// use rivia::prelude::*;
     ///
     /// let vfs = Vfs::memfs();
-    /// let dir = vfs.root().mash("dir");
-    /// let file = vfs.root().mash("file");
-    /// let link1 = vfs.root().mash("link1");
-    /// let link2 = vfs.root().mash("link2");
-    /// assert_vfs_mkdir_p!(vfs, &dir);
-    /// assert_vfs_mkfile!(vfs, &file);
-    /// assert_vfs_symlink!(vfs, &link1, &dir);
-    /// assert_vfs_symlink!(vfs, &link2, &file);
-    /// assert_eq!(vfs.is_symlink_file(&link1), false);
-    /// assert_eq!(vfs.is_symlink_file(&link2), true);
+    /// assert_eq!(vfs.gid(vfs.root()).unwrap(), 1000);
     /// ```
-    fn is_symlink_file<T: AsRef<Path>>(&self, path: T) -> bool;
+    fn gid<T: AsRef<Path>>(&self, path: T) -> RvResult<u32>;
 
-    /// Creates the given directory and any parent directories needed with the given mode
+    /// Returns the paths matching the given glob pattern sorted by path
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * `*` matches any sequence of characters within a single path component
+    /// * `**` matches any sequence of characters across path components
+    /// * `?` matches any single character and `[abc]`, `[a-z]` or `[!a-z]` match a character class
+    /// * Paths are returned in absolute form and an empty list is returned when nothing matches
+    /// * Links aren't followed and directories that can't be read are skipped
     ///
     /// ### Examples
     /// ```
@@ -611,112 +786,137 @@ pub trait VirtualFileSystem: Debug + Send + Sync + 'static {
     ///
     /// let vfs = Vfs::memfs();
     /// let dir = vfs.root().mash("dir");
-    /// assert!(vfs.mkdir_m(&dir, 0o555).is_ok());
-    /// assert_eq!(vfs.mode(&dir).unwrap(), 0o40555);
+    /// let file1 = dir.mash("file1.conf");
+    /// let file2 = dir.mash("sub/file2.conf");
+    /// assert_vfs_mkdir_p!(vfs, dir.mash("sub"));
+    /// assert_vfs_mkfile!(vfs, &file1);
+    /// assert_vfs_mkfile!(vfs, &file2);
+    /// assert_eq!(vfs.glob(dir.mash("*.conf").to_string().unwrap()).unwrap(), vec![file1.clone()]);
+    /// assert_eq!(vfs.glob(dir.mash("**/*.conf").to_string().unwrap()).unwrap(), vec![file1, file2]);
     /// ```
-    fn mkdir_m<T: AsRef<Path>>(&self, path: T, mode: u32) -> RvResult<PathBuf>;
+    fn glob<T: AsRef<str>>(&self, pattern: T) -> RvResult<Vec<PathBuf>>;
 
-    /// Creates the given directory and any parent directories needed
+    /// Creates a new hard link `dst` giving the data of the `src` file another name
     ///
     /// * Handles path expansion and absolute path resolution
+    /// * Links aren't followed i.e. a link to a symlink is a second name for the symlink itself
+    /// * Writes through either name are visible through the other and the data is only dropped once
+    ///   all of its names have been removed
+    /// * Memfs keeps the mode, owner and times of each name separately
+    /// * Returns the link path
     ///
     /// ### Errors
-    /// * PathError::IsNotDir(PathBuf) when the path already exists and is not a directory
+    /// * PathError::DoesNotExist(PathBuf) when `src` or the parent of `dst` doesn't exist
+    /// * PathError::IsNotFile(PathBuf) when `src` is a directory
+    /// * PathError::ExistsAlready(PathBuf) when `dst` already exists
+    ///
+    /// ### Arguments
+    /// * `src` - the existing file to link to
+    /// * `dst` - the path of the link being created
     ///
     /// ### Examples
     /// ```
     /// use rivia::prelude::*;
     ///
     /// let vfs = Vfs::memfs();
-    /// let dir = vfs.root().mash("dir");
-    /// assert_vfs_no_dir!(vfs, &dir);
-    /// assert_eq!(&vfs.mkdir_p(&dir).unwrap(), &dir);
-    /// assert_vfs_is_dir!(vfs, &dir);
+    /// assert_vfs_write_all!(vfs, "file", "foobar");
+    /// assert_eq!(vfs.hardlink("file", "link").unwrap(), vfs.root().mash("link"));
+    /// assert_vfs_read_all!(vfs, "link", "foobar");
+    /// assert_eq!(vfs.nlink("file").unwrap(), 2);
     /// ```
-    fn mkdir_p<T: AsRef<Path>>(&self, path: T) -> RvResult<PathBuf>;
+    fn hardlink<T: AsRef<Path>, U: AsRef<Path>>(&self, src: T, dst: U) -> RvResult<PathBuf>;
 
-    /// Create an empty file similar to the linux touch command
-    ///
-    /// * Handles path expansion and absolute path resolution
-    /// * Default file creation permissions 0o666 with umask usually ends up being 0o644
+    /// Register a hook to be called after every successful mutating operation
     ///
-    /// ### Errors
-    /// * PathError::DoesNotExist(PathBuf) when the given path's parent doesn't exist
-    /// * PathError::IsNotDir(PathBuf) when the given path's parent isn't a directory
-    /// * PathError::IsNotFile(PathBuf) when the given path exists but isn't a file
+    /// * Hooks are given the operation's [`VfsOp`] classification and the absolute path
+    /// * Hooks are called in the order they were registered
+    /// * Useful for logging or tracing the filesystem effects of a function under test
     ///
     /// ### Examples
     /// ```
+    /// use std::sync::Mutex;
+    ///
     /// use rivia::prelude::*;
     ///
     /// let vfs = Vfs::memfs();
+    /// let ops = Arc::new(Mutex::new(vec![]));
+    /// let ops_clone = ops.clone();
+    /// vfs.hook_post(move |op, path| ops_clone.lock().unwrap().push((op, path.to_path_buf())));
     /// let file = vfs.root().mash("file");
-    /// assert_vfs_no_file!(vfs, &file);
-    /// assert_eq!(&vfs.mkfile(&file).unwrap(), &file);
-    /// assert_vfs_is_file!(vfs, &file);
+    /// assert_vfs_mkfile!(vfs, &file);
+    /// assert_eq!(*ops.lock().unwrap(), vec![(VfsOp::Create, file)]);
     /// ```
-    fn mkfile<T: AsRef<Path>>(&self, path: T) -> RvResult<PathBuf>;
+    fn hook_post<F: Fn(VfsOp, &Path) + Send + Sync + 'static>(&self, hook: F);
 
-    /// Wraps `mkfile` allowing for setting the file's mode.
+    /// Register a hook to be called before every mutating operation with the ability to veto it
+    ///
+    /// * Hooks are given the operation's [`VfsOp`] classification and the absolute path
+    /// * Hooks are called in the order they were registered
+    /// * Returning an error from a hook vetoes the operation and is passed back to the caller
+    /// * Operations implicating multiple paths e.g. `move_p` call the hooks once for each path
     ///
     /// ### Examples
     /// ```
     /// use rivia::prelude::*;
     ///
     /// let vfs = Vfs::memfs();
-    /// let file = vfs.root().mash("file");
-    /// assert!(vfs.mkfile_m(&file, 0o555).is_ok());
-    /// assert_eq!(vfs.mode(&file).unwrap(), 0o100555);
-    /// ```
-    fn mkfile_m<T: AsRef<Path>>(&self, path: T, mode: u32) -> RvResult<PathBuf>;
+    /// vfs.hook_pre(|_, path| match path.starts_with("/etc") {
+    ///     true => Err(VfsError::Unavailable.into()),
+    ///     false => Ok(()),
+    /// });
+    /// assert!(vfs.mkdir_p("/etc").is_err());
+    /// assert_vfs_no_exists!(vfs, "/etc");
+    /// assert_vfs_mkdir_p!(vfs, "/tmp");
+    /// ```
+    fn hook_pre<F: Fn(VfsOp, &Path) -> RvResult<()> + Send + Sync + 'static>(&self, hook: F);
 
-    /// Returns the permissions for a file, directory or link
+    /// Register a hook to be called after every mutating operation slower than the given duration
     ///
-    /// * Handles path expansion and absolute path resolution
-    ///
-    /// ### Errors
-    /// * PathError::Empty when the given path is empty
-    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    /// * Hooks are given the operation's [`VfsOp`] classification, the absolute path and the time
+    ///   the operation took e.g. to report operations stalling on a hung network filesystem
+    /// * Covers the same mutating operations as the pre and post hooks, reads and stats aren't timed
+    /// * Hooks are called once the operation returns, whether it succeeded or not, and can neither
+    ///   interrupt nor fail it so an operation that never returns is never reported
     ///
     /// ### Examples
     /// ```
+    /// use std::{sync::Mutex, time::Duration};
+    ///
     /// use rivia::prelude::*;
     ///
     /// let vfs = Vfs::memfs();
+    /// let ops = Arc::new(Mutex::new(vec![]));
+    /// let ops_clone = ops.clone();
+    /// vfs.hook_slow(Duration::ZERO, move |op, path, _| ops_clone.lock().unwrap().push((op, path.to_path_buf())));
     /// let file = vfs.root().mash("file");
     /// assert_vfs_mkfile!(vfs, &file);
-    /// assert_eq!(vfs.mode(&file).unwrap(), 0o100644);
-    /// assert!(vfs.chmod(&file, 0o555).is_ok());
-    /// assert_eq!(vfs.mode(&file).unwrap(), 0o100555);
+    /// assert_eq!(*ops.lock().unwrap(), vec![(VfsOp::Create, file)]);
     /// ```
-    fn mode<T: AsRef<Path>>(&self, path: T) -> RvResult<u32>;
+    fn hook_slow<F: Fn(VfsOp, &Path, Duration) + Send + Sync + 'static>(&self, after: Duration, hook: F);
 
-    /// Move a file or directory
+    /// Creates a new [`Installer`] for use with the builder pattern
     ///
+    /// * `dst` will be installed into if it is an existing directory
+    /// * `dst` will be the installed file if it isn't an existing directory
     /// * Handles path expansion and absolute path resolution
-    /// * Always moves `src` into `dst` if `dst` is an existing directory
-    /// * Replaces destination files if they exist
-    ///
-    /// ### Errors
-    /// * PathError::DoesNotExist when the source doesn't exist
+    /// * Options for mode, ownership, backups and stripping leading source directories
+    /// * Execute by calling `exec`
     ///
     /// ### Examples
     /// ```
     /// use rivia::prelude::*;
     ///
     /// let vfs = Vfs::memfs();
-    /// let dir = vfs.root().mash("dir");
-    /// let file = vfs.root().mash("file");
-    /// let dirfile = dir.mash("file");
-    /// assert_vfs_mkdir_p!(vfs, &dir);
-    /// assert_vfs_mkfile!(vfs, &file);
-    /// assert!(vfs.move_p(&file, &dir).is_ok());
-    /// assert_vfs_no_file!(vfs, &file);
-    /// assert_vfs_is_file!(vfs, &dirfile);
+    /// let file1 = vfs.root().mash("file1");
+    /// let file2 = vfs.root().mash("dir/file2");
+    /// assert_vfs_write_all!(vfs, &file1, "this is a test");
+    /// assert!(vfs.install(&file1, &file2).unwrap().mode(0o700).exec().is_ok());
+    /// assert_vfs_read_all!(vfs, &file2, "this is a test");
+    /// assert_eq!(vfs.mode(&file2).unwrap(), 0o100700);
     /// ```
-    fn move_p<T: AsRef<Path>, U: AsRef<Path>>(&self, src: T, dst: U) -> RvResult<()>;
+    fn install<T: AsRef<Path>, U: AsRef<Path>>(&self, src: T, dst: U) -> RvResult<Installer>;
 
-    /// Returns the (user ID, group ID) of the owner of this file
+    /// Returns true if the given path exists and is readonly
     ///
     /// * Handles path expansion and absolute path resolution
     ///
@@ -725,40 +925,35 @@ pub trait VirtualFileSystem: Debug + Send + Sync + 'static {
     /// use rivia::prelude::*;
     ///
     /// let vfs = Vfs::memfs();
-    /// assert_eq!(vfs.owner(vfs.root()).unwrap(), (1000, 1000));
+    /// let file = vfs.root().mash("file");
+    /// assert!(vfs.mkfile_m(&file, 0o644).is_ok());
+    /// assert_eq!(vfs.is_exec(&file), false);
+    /// assert!(vfs.chmod(&file, 0o777).is_ok());
+    /// assert_eq!(vfs.is_exec(&file), true);
     /// ```
-    fn owner<T: AsRef<Path>>(&self, path: T) -> RvResult<(u32, u32)>;
+    fn is_exec<T: AsRef<Path>>(&self, path: T) -> bool;
 
-    /// Returns all paths for the given path, sorted by name
+    /// Returns true if the given path exists and is a directory
     ///
     /// * Handles path expansion and absolute path resolution
-    /// * Paths are returned as abs paths
-    /// * Doesn't include the path itself only its children nor is this recursive
+    /// * Link exclusion i.e. links even if pointing to a directory return false
     ///
     /// ### Examples
     /// ```
     /// use rivia::prelude::*;
     ///
     /// let vfs = Vfs::memfs();
-    /// let tmpdir = vfs.root().mash("tmpdir");
-    /// let dir1 = tmpdir.mash("dir1");
-    /// let dir2 = tmpdir.mash("dir2");
-    /// let file1 = tmpdir.mash("file1");
-    /// assert_vfs_mkdir_p!(vfs, &dir1);
-    /// assert_vfs_mkdir_p!(vfs, &dir2);
-    /// assert_vfs_mkfile!(vfs, &file1);
-    /// assert_iter_eq(vfs.paths(&tmpdir).unwrap(), vec![dir1, dir2, file1]);
+    /// let dir = vfs.root().mash("dir");
+    /// assert_eq!(vfs.is_dir(&dir), false);
+    /// assert_vfs_mkdir_p!(vfs, &dir);
+    /// assert_eq!(vfs.is_dir(&dir), true);
     /// ```
-    fn paths<T: AsRef<Path>>(&self, path: T) -> RvResult<Vec<PathBuf>>;
+    fn is_dir<T: AsRef<Path>>(&self, path: T) -> bool;
 
-    /// Open a file in readonly mode
+    /// Returns true if the given path exists and is a file
     ///
-    /// * Provides a handle to a Read + Seek implementation
     /// * Handles path expansion and absolute path resolution
-    ///
-    /// ### Errors
-    /// * PathError::IsNotFile(PathBuf) when the given path isn't a file
-    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    /// * Link exclusion i.e. links even if pointing to a file return false
     ///
     /// ### Examples
     /// ```
@@ -766,40 +961,36 @@ pub trait VirtualFileSystem: Debug + Send + Sync + 'static {
     ///
     /// let vfs = Vfs::memfs();
     /// let file = vfs.root().mash("file");
-    /// assert_vfs_write_all!(vfs, &file, b"foobar 1");
-    /// let mut file = vfs.read(&file).unwrap();
-    /// let mut buf = String::new();
-    /// file.read_to_string(&mut buf);
-    /// assert_eq!(buf, "foobar 1".to_string());
+    /// assert_eq!(vfs.is_file(&file), false);
+    /// assert_vfs_mkfile!(vfs, &file);
+    /// assert_eq!(vfs.is_file(&file), true);
     /// ```
-    fn read<T: AsRef<Path>>(&self, path: T) -> RvResult<Box<dyn ReadSeek>>;
+    fn is_file<T: AsRef<Path>>(&self, path: T) -> bool;
 
-    /// Read all data from the given file and return it as a String
+    /// Returns true if the given path exists and is a hard link i.e. its data has more than one name
     ///
     /// * Handles path expansion and absolute path resolution
-    ///
-    /// ### Errors
-    /// * PathError::IsNotFile(PathBuf) when the given path isn't a file
-    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    /// * Links aren't followed
+    /// * As all names of the data are equal the original file is a hard link as well
+    /// * Directories always return false
     ///
     /// ### Examples
     /// ```
     /// use rivia::prelude::*;
     ///
     /// let vfs = Vfs::memfs();
-    /// let file = vfs.root().mash("file");
-    /// assert_vfs_write_all!(vfs, &file, b"foobar 1");
-    /// assert_vfs_read_all!(vfs, &file, "foobar 1");
+    /// assert_vfs_mkfile!(vfs, "file");
+    /// assert_eq!(vfs.is_hardlink("file"), false);
+    /// assert_vfs_hardlink!(vfs, "file", "link");
+    /// assert_eq!(vfs.is_hardlink("file"), true);
+    /// assert_eq!(vfs.is_hardlink("link"), true);
     /// ```
-    fn read_all<T: AsRef<Path>>(&self, path: T) -> RvResult<String>;
+    fn is_hardlink<T: AsRef<Path>>(&self, path: T) -> bool;
 
-    /// Read the given file and returns it as lines in a vector
+    /// Returns true if the given path exists and is immutable i.e. `chattr +i`
     ///
     /// * Handles path expansion and absolute path resolution
-    ///
-    /// ### Errors
-    /// * PathError::IsNotFile(PathBuf) when the given path isn't a file
-    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    /// * Links are followed
     ///
     /// ### Examples
     /// ```
@@ -807,12 +998,14 @@ pub trait VirtualFileSystem: Debug + Send + Sync + 'static {
     ///
     /// let vfs = Vfs::memfs();
     /// let file = vfs.root().mash("file");
-    /// assert_vfs_write_all!(vfs, &file, "1\n2");
-    /// assert_eq!(vfs.read_lines(&file).unwrap(), vec!["1".to_string(), "2".to_string()]);
+    /// assert_vfs_mkfile!(vfs, &file);
+    /// assert_eq!(vfs.is_immutable(&file), false);
+    /// assert!(vfs.set_immutable(&file, true).is_ok());
+    /// assert_eq!(vfs.is_immutable(&file), true);
     /// ```
-    fn read_lines<T: AsRef<Path>>(&self, path: T) -> RvResult<Vec<String>>;
+    fn is_immutable<T: AsRef<Path>>(&self, path: T) -> bool;
 
-    /// Returns the relative path of the target the link points to
+    /// Returns true if the given path exists and is readonly
     ///
     /// * Handles path expansion and absolute path resolution
     ///
@@ -821,17 +1014,16 @@ pub trait VirtualFileSystem: Debug + Send + Sync + 'static {
     /// use rivia::prelude::*;
     ///
     /// let vfs = Vfs::memfs();
-    /// let dir = vfs.root().mash("dir");
-    /// let link = dir.mash("link");
     /// let file = vfs.root().mash("file");
-    /// assert_vfs_mkdir_p!(vfs, &dir);
-    /// assert_vfs_mkfile!(vfs, &file);
-    /// assert_vfs_symlink!(vfs, &link, &file);
-    /// assert_vfs_readlink!(vfs, &link, PathBuf::from("..").mash("file"));
+    /// assert!(vfs.mkfile_m(&file, 0o644).is_ok());
+    /// assert_eq!(vfs.is_readonly(&file), false);
+    /// assert!(vfs.chmod_b(&file).unwrap().readonly().exec().is_ok());
+    /// assert_eq!(vfs.mode(&file).unwrap(), 0o100444);
+    /// assert_eq!(vfs.is_readonly(&file), true);
     /// ```
-    fn readlink<T: AsRef<Path>>(&self, path: T) -> RvResult<PathBuf>;
+    fn is_readonly<T: AsRef<Path>>(&self, path: T) -> bool;
 
-    /// Returns the absolute path of the target the link points to
+    /// Returns true if the given path exists and is a symlink
     ///
     /// * Handles path expansion and absolute path resolution
     ///
@@ -842,37 +1034,39 @@ pub trait VirtualFileSystem: Debug + Send + Sync + 'static {
     /// let vfs = Vfs::memfs();
     /// let file = vfs.root().mash("file");
     /// let link = vfs.root().mash("link");
-    /// assert_vfs_mkfile!(vfs, &file);
+    /// assert_eq!(vfs.is_symlink(&link), false);
     /// assert_vfs_symlink!(vfs, &link, &file);
-    /// assert_vfs_readlink_abs!(vfs, &link, &file);
+    /// assert_eq!(vfs.is_symlink(&link), true);
     /// ```
-    fn readlink_abs<T: AsRef<Path>>(&self, path: T) -> RvResult<PathBuf>;
+    fn is_symlink<T: AsRef<Path>>(&self, path: T) -> bool;
 
-    /// Removes the given empty directory or file
+    /// Returns true if the given path exists and is a symlink pointing to a directory
     ///
     /// * Handles path expansion and absolute path resolution
-    /// * Link exclusion i.e. removes the link themselves not what its points to
-    ///
-    /// ### Errors
-    /// * a directory containing files will trigger an error. use `remove_all` instead
+    /// * Checks the path itself and what it points to
     ///
     /// ### Examples
     /// ```
     /// use rivia::prelude::*;
     ///
     /// let vfs = Vfs::memfs();
+    /// let dir = vfs.root().mash("dir");
     /// let file = vfs.root().mash("file");
+    /// let link1 = vfs.root().mash("link1");
+    /// let link2 = vfs.root().mash("link2");
+    /// assert_vfs_mkdir_p!(vfs, &dir);
     /// assert_vfs_mkfile!(vfs, &file);
-    /// assert_vfs_exists!(vfs, &file);
-    /// assert_vfs_remove!(vfs, &file);
-    /// assert_vfs_no_exists!(vfs, &file);
+    /// assert_vfs_symlink!(vfs, &link1, &dir);
+    /// assert_vfs_symlink!(vfs, &link2, &file);
+    /// assert_eq!(vfs.is_symlink_dir(&link1), true);
+    /// assert_eq!(vfs.is_symlink_dir(&link2), false);
     /// ```
-    fn remove<T: AsRef<Path>>(&self, path: T) -> RvResult<()>;
+    fn is_symlink_dir<T: AsRef<Path>>(&self, path: T) -> bool;
 
-    /// Removes the given directory after removing all of its contents
+    /// Returns true if the given path exists and is a symlink pointing to a file
     ///
     /// * Handles path expansion and absolute path resolution
-    /// * Link exclusion i.e. removes the link themselves not what its points to
+    /// * Checks the path itself and what it points to
     ///
     /// ### Examples
     /// ```
@@ -880,35 +1074,56 @@ pub trait VirtualFileSystem: Debug + Send + Sync + 'static {
     ///
     /// let vfs = Vfs::memfs();
     /// let dir = vfs.root().mash("dir");
-    /// let file = dir.mash("file");
+    /// let file = vfs.root().mash("file");
+    /// let link1 = vfs.root().mash("link1");
+    /// let link2 = vfs.root().mash("link2");
     /// assert_vfs_mkdir_p!(vfs, &dir);
     /// assert_vfs_mkfile!(vfs, &file);
-    /// assert_vfs_is_file!(vfs, &file);
-    /// assert_vfs_remove_all!(vfs, &dir);
-    /// assert_vfs_no_exists!(vfs, &file);
-    /// assert_vfs_no_exists!(vfs, &dir);
+    /// assert_vfs_symlink!(vfs, &link1, &dir);
+    /// assert_vfs_symlink!(vfs, &link2, &file);
+    /// assert_eq!(vfs.is_symlink_file(&link1), false);
+    /// assert_eq!(vfs.is_symlink_file(&link2), true);
     /// ```
-    fn remove_all<T: AsRef<Path>>(&self, path: T) -> RvResult<()>;
+    fn is_symlink_file<T: AsRef<Path>>(&self, path: T) -> bool;
 
-    /// Returns the current root directory
+    /// Append the given value to the target JSONL file as a single line of JSON
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Creates a file if it does not exist or appends to it if it does
+    /// * The record is written with a single append so it lands whole or not at all in most cases
+    /// * Starts on a new line when the file ends in a partial line left behind by a torn write
+    ///
+    /// ### Errors
+    /// * PathError::IsNotDir(PathBuf) when the given path's parent exists but is not a directory
+    /// * PathError::DoesNotExist(PathBuf) when the given path's parent doesn't exist
+    /// * PathError::IsNotFile(PathBuf) when the given path exists but is not a file
+    /// * serde_json::Error when the value fails to serialize
     ///
     /// ### Examples
     /// ```
     /// use rivia::prelude::*;
     ///
     /// let vfs = Vfs::memfs();
-    /// let mut root = PathBuf::new();
-    /// root.push(Component::RootDir);
-    /// assert_eq!(vfs.root(), root);
+    /// let file = vfs.root().mash("file");
+    /// assert!(vfs.jsonl_append(&file, &("foo", 1)).is_ok());
+    /// assert!(vfs.jsonl_append(&file, &("bar", 2)).is_ok());
+    /// assert_vfs_read_all!(vfs, &file, "[\"foo\",1]\n[\"bar\",2]\n");
     /// ```
-    fn root(&self) -> PathBuf;
+    #[cfg(feature = "json")]
+    fn jsonl_append<T: serde::Serialize, U: AsRef<Path>>(&self, path: U, value: &T) -> RvResult<()> {
+        self.append_all(&path, super::jsonl_record(self, path.as_ref(), value)?)
+    }
 
-    /// Set the current working directory
+    /// Returns a streaming iterator over the records of the given JSONL file
     ///
     /// * Handles path expansion and absolute path resolution
-    /// * Relative path will use the current working directory
+    /// * Records are read from the file's read handle as the iterator is advanced
+    /// * Blank lines are skipped
+    /// * A partial trailing line left behind by a torn write ends the iteration
+    /// * Records that fail to deserialize are returned as errors without ending the iteration
     ///
     /// ### Errors
+    /// * PathError::IsNotFile(PathBuf) when the given path isn't a file
     /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
     ///
     /// ### Examples
@@ -916,23 +1131,25 @@ pub trait VirtualFileSystem: Debug + Send + Sync + 'static {
     /// use rivia::prelude::*;
     ///
     /// let vfs = Vfs::memfs();
-    /// let dir = vfs.root().mash("dir");
-    /// assert_eq!(vfs.cwd().unwrap(), vfs.root());
-    /// assert_vfs_mkdir_p!(vfs, &dir);
-    /// assert_eq!(vfs.set_cwd(&dir).unwrap(), dir.clone());
-    /// assert_eq!(vfs.cwd().unwrap(), dir);
-    /// ```
-    fn set_cwd<T: AsRef<Path>>(&self, path: T) -> RvResult<PathBuf>;
+    /// let file = vfs.root().mash("file");
+    /// assert!(vfs.jsonl_append(&file, &("foo", 1)).is_ok());
+    /// let mut iter = vfs.jsonl_iter::<(String, u32), _>(&file).unwrap();
+    /// assert_eq!(iter.next().unwrap().unwrap(), ("foo".to_string(), 1));
+    /// assert!(iter.next().is_none());
+    /// ```
+    #[cfg(feature = "json")]
+    fn jsonl_iter<T: serde::de::DeserializeOwned, U: AsRef<Path>>(&self, path: U) -> RvResult<JsonlIter<T>> {
+        Ok(JsonlIter::new(self.read(path)?))
+    }
 
-    /// Creates a new symbolic link
+    /// Returns the number of lines in the given file without reading it all into memory
     ///
     /// * Handles path expansion and absolute path resolution
-    /// * Computes the target path `src` relative to the `dst` link name's absolute path
-    /// * Returns the link path
+    /// * Counts a final line without a trailing newline i.e. matches the length of `read_lines`
     ///
-    /// ### Arguments
-    /// * `link` - the path of the link being created
-    /// * `target` - the path that the link will point to
+    /// ### Errors
+    /// * PathError::IsNotFile(PathBuf) when the given path isn't a file
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
     ///
     /// ### Examples
     /// ```
@@ -940,90 +1157,91 @@ pub trait VirtualFileSystem: Debug + Send + Sync + 'static {
     ///
     /// let vfs = Vfs::memfs();
     /// let file = vfs.root().mash("file");
-    /// let link = vfs.root().mash("link");
-    /// assert_vfs_mkfile!(vfs, &file);
-    /// assert_vfs_symlink!(vfs, &link, &file);
-    /// assert_vfs_readlink_abs!(vfs, &link, &file);
+    /// assert_vfs_write_all!(vfs, &file, "1\n2");
+    /// assert_eq!(vfs.line_count(&file).unwrap(), 2);
     /// ```
-    fn symlink<T: AsRef<Path>, U: AsRef<Path>>(&self, link: T, target: U) -> RvResult<PathBuf>;
+    fn line_count<T: AsRef<Path>>(&self, path: T) -> RvResult<u64>;
 
-    /// Returns the user ID of the owner of this file
+    /// Returns the filesystem properties of the given path with a single lookup
     ///
     /// * Handles path expansion and absolute path resolution
+    /// * Links report the properties of the target they point to except for `link`
+    /// * Memfs doesn't track access times and reports the modified time instead
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
     ///
     /// ### Examples
     /// ```
     /// use rivia::prelude::*;
     ///
     /// let vfs = Vfs::memfs();
-    /// assert_eq!(vfs.uid(vfs.root()).unwrap(), 1000);
+    /// assert_vfs_write_all!(vfs, "file", "foobar");
+    /// let meta = vfs.metadata("file").unwrap();
+    /// assert_eq!((meta.file, meta.size, meta.nlink), (true, 6, 1));
     /// ```
-    fn uid<T: AsRef<Path>>(&self, path: T) -> RvResult<u32>;
+    fn metadata<T: AsRef<Path>>(&self, path: T) -> RvResult<VfsMetadata>;
 
-    /// Up cast the trait type to the enum wrapper
+    /// Creates the given directory and any parent directories needed with the given mode
     ///
     /// ### Examples
     /// ```
     /// use rivia::prelude::*;
     ///
-    /// let vfs = Memfs::new().upcast();
+    /// let vfs = Vfs::memfs();
+    /// let dir = vfs.root().mash("dir");
+    /// assert!(vfs.mkdir_m(&dir, 0o555).is_ok());
+    /// assert_eq!(vfs.mode(&dir).unwrap(), 0o40555);
     /// ```
-    fn upcast(self) -> Vfs;
+    fn mkdir_m<T: AsRef<Path>>(&self, path: T, mode: u32) -> RvResult<PathBuf>;
 
-    /// Opens a file in write-only mode
+    /// Creates the given directory and any parent directories needed
     ///
-    /// * Creates a file if it does not exist or truncates it if it does
+    /// * Handles path expansion and absolute path resolution
     ///
     /// ### Errors
-    /// * PathError::IsNotDir(PathBuf) when the given path's parent exists but is not a directory
-    /// * PathError::DoesNotExist(PathBuf) when the given path's parent doesn't exist
-    /// * PathError::IsNotFile(PathBuf) when the given path exists but is not a file
+    /// * PathError::IsNotDir(PathBuf) when the path already exists and is not a directory
     ///
     /// ### Examples
     /// ```
     /// use rivia::prelude::*;
     ///
     /// let vfs = Vfs::memfs();
-    /// let file = vfs.root().mash("file");
-    /// let mut f = vfs.write(&file).unwrap();
-    /// f.write_all(b"foobar").unwrap();
-    /// f.flush().unwrap();
-    /// assert_vfs_read_all!(vfs, &file, "foobar");
+    /// let dir = vfs.root().mash("dir");
+    /// assert_vfs_no_dir!(vfs, &dir);
+    /// assert_eq!(&vfs.mkdir_p(&dir).unwrap(), &dir);
+    /// assert_vfs_is_dir!(vfs, &dir);
     /// ```
-    fn write<T: AsRef<Path>>(&self, path: T) -> RvResult<Box<dyn Write>>;
+    fn mkdir_p<T: AsRef<Path>>(&self, path: T) -> RvResult<PathBuf>;
 
-    /// Write the given data to to the target file
+    /// Wraps `mkdir_p` reporting whether the directory was created or already existed
     ///
     /// * Handles path expansion and absolute path resolution
-    /// * Create the file first if it doesn't exist or truncating it first if it does
+    /// * Saves converge style callers from pre-checking existence which doubles the work and races
     ///
     /// ### Errors
-    /// * PathError::IsNotDir(PathBuf) when the given path's parent exists but is not a directory
-    /// * PathError::DoesNotExist(PathBuf) when the given path's parent doesn't exist
-    /// * PathError::IsNotFile(PathBuf) when the given path exists but is not a file
+    /// * PathError::IsNotDir(PathBuf) when the path already exists and is not a directory
     ///
     /// ### Examples
     /// ```
     /// use rivia::prelude::*;
     ///
     /// let vfs = Vfs::memfs();
-    /// let file = vfs.root().mash("file");
-    /// assert_vfs_no_file!(vfs, &file);
-    /// assert_vfs_write_all!(vfs, &file, "foobar 1");
-    /// assert_vfs_is_file!(vfs, &file);
-    /// assert_vfs_read_all!(vfs, &file, "foobar 1");
+    /// let dir = vfs.root().mash("dir");
+    /// assert_eq!(vfs.mkdir_p_ensured(&dir).unwrap(), Ensured::Created(dir.clone()));
+    /// assert_eq!(vfs.mkdir_p_ensured(&dir).unwrap(), Ensured::Existed(dir.clone()));
     /// ```
-    fn write_all<T: AsRef<Path>, U: AsRef<[u8]>>(&self, path: T, data: U) -> RvResult<()>;
+    fn mkdir_p_ensured<T: AsRef<Path>>(&self, path: T) -> RvResult<Ensured>;
 
-    /// Write the given lines to to the target file including final newline
+    /// Create an empty file similar to the linux touch command
     ///
     /// * Handles path expansion and absolute path resolution
-    /// * Create the file first if it doesn't exist or truncating it first if it does
+    /// * Default file creation permissions 0o666 with umask usually ends up being 0o644
     ///
     /// ### Errors
-    /// * PathError::IsNotDir(PathBuf) when the given path's parent exists but is not a directory
     /// * PathError::DoesNotExist(PathBuf) when the given path's parent doesn't exist
-    /// * PathError::IsNotFile(PathBuf) when the given path exists but is not a file
+    /// * PathError::IsNotDir(PathBuf) when the given path's parent isn't a directory
+    /// * PathError::IsNotFile(PathBuf) when the given path exists but isn't a file
     ///
     /// ### Examples
     /// ```
@@ -1032,79 +1250,2042 @@ pub trait VirtualFileSystem: Debug + Send + Sync + 'static {
     /// let vfs = Vfs::memfs();
     /// let file = vfs.root().mash("file");
     /// assert_vfs_no_file!(vfs, &file);
-    /// assert!(vfs.write_lines(&file, &["1", "2"]).is_ok());
+    /// assert_eq!(&vfs.mkfile(&file).unwrap(), &file);
     /// assert_vfs_is_file!(vfs, &file);
-    /// assert_vfs_read_all!(vfs, &file, "1\n2\n".to_string());
     /// ```
-    fn write_lines<T: AsRef<Path>, U: AsRef<str>>(&self, path: T, lines: &[U]) -> RvResult<()>;
-}
-
-/// Provides an ergonomic encapsulation of the underlying [`VirtualFileSystem`] backend
-/// implementations
-#[derive(Debug)]
-pub enum Vfs {
-    Stdfs(Stdfs),
-    Memfs(Memfs),
-}
+    fn mkfile<T: AsRef<Path>>(&self, path: T) -> RvResult<PathBuf>;
 
-impl Vfs {
-    /// Create a new instance of Memfs wrapped in the Vfs enum
+    /// Wraps `mkfile` reporting whether the file was created or already existed
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Links to files are considered to be files
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path's parent doesn't exist
+    /// * PathError::IsNotDir(PathBuf) when the given path's parent isn't a directory
+    /// * PathError::IsNotFile(PathBuf) when the given path exists but isn't a file
     ///
     /// ### Examples
     /// ```
     /// use rivia::prelude::*;
     ///
     /// let vfs = Vfs::memfs();
-    /// assert_vfs_no_exists!(vfs, "humbug5");
+    /// let file = vfs.root().mash("file");
+    /// assert_eq!(vfs.mkfile_ensured(&file).unwrap(), Ensured::Created(file.clone()));
+    /// assert_eq!(vfs.mkfile_ensured(&file).unwrap(), Ensured::Existed(file.clone()));
     /// ```
-    pub fn memfs() -> Vfs {
-        Vfs::Memfs(Memfs::new())
-    }
+    fn mkfile_ensured<T: AsRef<Path>>(&self, path: T) -> RvResult<Ensured>;
 
-    /// Create a new instance of Stdfs wrapped in the Vfs enum
+    /// Wraps `mkfile` allowing for setting the file's mode.
     ///
     /// ### Examples
     /// ```
     /// use rivia::prelude::*;
     ///
-    /// let vfs = Vfs::stdfs();
-    /// assert_vfs_no_exists!(vfs, "humbug5");
-    /// ```
+    /// let vfs = Vfs::memfs();
+    /// let file = vfs.root().mash("file");
+    /// assert!(vfs.mkfile_m(&file, 0o555).is_ok());
+    /// assert_eq!(vfs.mode(&file).unwrap(), 0o100555);
+    /// ```
+    fn mkfile_m<T: AsRef<Path>>(&self, path: T, mode: u32) -> RvResult<PathBuf>;
+
+    /// Returns the permissions for a file, directory or link
+    ///
+    /// * Handles path expansion and absolute path resolution
+    ///
+    /// ### Errors
+    /// * PathError::Empty when the given path is empty
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let file = vfs.root().mash("file");
+    /// assert_vfs_mkfile!(vfs, &file);
+    /// assert_eq!(vfs.mode(&file).unwrap(), 0o100644);
+    /// assert!(vfs.chmod(&file, 0o555).is_ok());
+    /// assert_eq!(vfs.mode(&file).unwrap(), 0o100555);
+    /// ```
+    fn mode<T: AsRef<Path>>(&self, path: T) -> RvResult<u32>;
+
+    /// Creates a new [`Mover`] for use with the builder pattern
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Always moves `src` into `dst` if `dst` is an existing directory
+    /// * Replaces destination files if they exist
+    /// * Falls back on copy and remove when moving across filesystems
+    /// * Options for preserving attributes
+    /// * Execute by calling `exec`
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let file1 = vfs.root().mash("file1");
+    /// let file2 = vfs.root().mash("file2");
+    /// assert_vfs_write_all!(vfs, &file1, "this is a test");
+    /// assert!(vfs.move_b(&file1, &file2).unwrap().exec().is_ok());
+    /// assert_vfs_no_exists!(vfs, &file1);
+    /// assert_vfs_read_all!(vfs, &file2, "this is a test");
+    /// ```
+    fn move_b<T: AsRef<Path>, U: AsRef<Path>>(&self, src: T, dst: U) -> RvResult<Mover>;
+
+    /// Move a file or directory
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Always moves `src` into `dst` if `dst` is an existing directory
+    /// * Replaces destination files if they exist
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist when the source doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let dir = vfs.root().mash("dir");
+    /// let file = vfs.root().mash("file");
+    /// let dirfile = dir.mash("file");
+    /// assert_vfs_mkdir_p!(vfs, &dir);
+    /// assert_vfs_mkfile!(vfs, &file);
+    /// assert!(vfs.move_p(&file, &dir).is_ok());
+    /// assert_vfs_no_file!(vfs, &file);
+    /// assert_vfs_is_file!(vfs, &dirfile);
+    /// ```
+    fn move_p<T: AsRef<Path>, U: AsRef<Path>>(&self, src: T, dst: U) -> RvResult<()>;
+
+    /// Returns the number of hard links to the given path's data
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Links aren't followed
+    /// * Directories report two plus the number of directories they contain
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// assert_vfs_mkfile!(vfs, "file");
+    /// assert_eq!(vfs.nlink("file").unwrap(), 1);
+    /// assert_vfs_hardlink!(vfs, "file", "link");
+    /// assert_eq!(vfs.nlink("file").unwrap(), 2);
+    /// ```
+    fn nlink<T: AsRef<Path>>(&self, path: T) -> RvResult<u64>;
+
+    /// Opens the given directory returning a [`DirHandle`] for operating on its entries by name
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Stdfs holds the directory open and uses the `openat` family of syscalls to avoid races and
+    ///   repeated path resolution
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    /// * PathError::IsNotDir(PathBuf) when the given path is not a directory
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let dir = vfs.root().mash("dir");
+    /// assert_vfs_mkdir_p!(vfs, &dir);
+    /// let handle = vfs.open_dir(&dir).unwrap();
+    /// assert_eq!(handle.mkfile("file").unwrap(), dir.mash("file"));
+    /// assert_vfs_is_file!(vfs, dir.mash("file"));
+    /// ```
+    fn open_dir<T: AsRef<Path>>(&self, path: T) -> RvResult<DirHandle>;
+
+    /// Opens a file in read-write mode for modifying regions of it in place
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Creates a file if it does not exist without truncating it if it does
+    /// * Writes overwrite the data at the current position extending the file as needed
+    ///
+    /// ### Errors
+    /// * PathError::IsNotDir(PathBuf) when the given path's parent exists but is not a directory
+    /// * PathError::DoesNotExist(PathBuf) when the given path's parent doesn't exist
+    /// * PathError::IsNotFile(PathBuf) when the given path exists but is not a file
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let file = vfs.root().mash("file");
+    /// assert_vfs_write_all!(vfs, &file, "foobar");
+    /// let mut f = vfs.open_rw(&file).unwrap();
+    /// f.seek(SeekFrom::Start(3)).unwrap();
+    /// f.write_all(b"BAR").unwrap();
+    /// f.flush().unwrap();
+    /// assert_vfs_read_all!(vfs, &file, "fooBAR");
+    /// ```
+    fn open_rw<T: AsRef<Path>>(&self, path: T) -> RvResult<Box<dyn ReadWriteSeek>>;
+
+    /// Returns the (user ID, group ID) of the owner of this file
+    ///
+    /// * Handles path expansion and absolute path resolution
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// assert_eq!(vfs.owner(vfs.root()).unwrap(), (1000, 1000));
+    /// ```
+    fn owner<T: AsRef<Path>>(&self, path: T) -> RvResult<(u32, u32)>;
+
+    /// Pack the given file or directory tree into a tar archive at the given path
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Paths are stored relative to the given directory or as the base name of the given file
+    /// * Stores the permission bits, ownership, modification time and link targets of each entry
+    /// * Links are stored as links and are never followed
+    /// * Writes through the Vfs so any backend can be packed and the archive read by `tar`
+    /// * Use `unpack` to extract the archive
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    /// * PathError::UnsupportedFileType(PathBuf) when the tree contains a device, fifo or socket
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// assert_vfs_mkdir_p!(vfs, "dir");
+    /// assert_vfs_write_all!(vfs, "dir/file", "foobar");
+    /// assert!(vfs.pack("dir", "dir.tar").is_ok());
+    /// assert!(vfs.unpack("dir.tar", "copy").is_ok());
+    /// assert_vfs_read_all!(vfs, "copy/file", "foobar");
+    /// ```
+    fn pack<T: AsRef<Path>, U: AsRef<Path>>(&self, src: T, dst: U) -> RvResult<()> {
+        super::tar_pack(self, &self.abs(src)?, &self.abs(dst)?)
+    }
+
+    /// Returns all paths for the given path, sorted by name
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Paths are returned as abs paths
+    /// * Doesn't include the path itself only its children nor is this recursive
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let tmpdir = vfs.root().mash("tmpdir");
+    /// let dir1 = tmpdir.mash("dir1");
+    /// let dir2 = tmpdir.mash("dir2");
+    /// let file1 = tmpdir.mash("file1");
+    /// assert_vfs_mkdir_p!(vfs, &dir1);
+    /// assert_vfs_mkdir_p!(vfs, &dir2);
+    /// assert_vfs_mkfile!(vfs, &file1);
+    /// assert_iter_eq(vfs.paths(&tmpdir).unwrap(), vec![dir1, dir2, file1]);
+    /// ```
+    fn paths<T: AsRef<Path>>(&self, path: T) -> RvResult<Vec<PathBuf>>;
+
+    /// Open a file in readonly mode
+    ///
+    /// * Provides a handle to a Read + Seek implementation
+    /// * Handles path expansion and absolute path resolution
+    ///
+    /// ### Errors
+    /// * PathError::IsNotFile(PathBuf) when the given path isn't a file
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let file = vfs.root().mash("file");
+    /// assert_vfs_write_all!(vfs, &file, b"foobar 1");
+    /// let mut file = vfs.read(&file).unwrap();
+    /// let mut buf = String::new();
+    /// file.read_to_string(&mut buf);
+    /// assert_eq!(buf, "foobar 1".to_string());
+    /// ```
+    fn read<T: AsRef<Path>>(&self, path: T) -> RvResult<Box<dyn ReadSeek>>;
+
+    /// Read all data from the given file and return it as a String
+    ///
+    /// * Handles path expansion and absolute path resolution
+    ///
+    /// ### Errors
+    /// * PathError::IsNotFile(PathBuf) when the given path isn't a file
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let file = vfs.root().mash("file");
+    /// assert_vfs_write_all!(vfs, &file, b"foobar 1");
+    /// assert_vfs_read_all!(vfs, &file, "foobar 1");
+    /// ```
+    fn read_all<T: AsRef<Path>>(&self, path: T) -> RvResult<String>;
+
+    /// Creates a new [`Reader`] for use with the builder pattern
+    ///
+    /// * Provides a handle to a Read + Seek implementation
+    /// * Handles path expansion and absolute path resolution
+    /// * Options for bypassing the page cache
+    /// * Execute by calling `exec`
+    ///
+    /// ### Errors
+    /// * PathError::IsNotFile(PathBuf) when the given path isn't a file
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let file = vfs.root().mash("file");
+    /// assert_vfs_write_all!(vfs, &file, b"foobar 1");
+    /// let mut file = vfs.read_b(&file).unwrap().direct(true).exec().unwrap();
+    /// let mut buf = String::new();
+    /// file.read_to_string(&mut buf);
+    /// assert_eq!(buf, "foobar 1".to_string());
+    /// ```
+    fn read_b<T: AsRef<Path>>(&self, path: T) -> RvResult<Reader>;
+
+    /// Read the given bincode file deserializing it into the given type
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Intended for small state files e.g. daemon checkpoints written with `write_bincode`
+    ///
+    /// ### Errors
+    /// * PathError::IsNotFile(PathBuf) when the given path isn't a file
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    /// * bincode::Error when the data fails to deserialize into the given type
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let file = vfs.root().mash("file");
+    /// assert!(vfs.write_bincode(&file, &("foo".to_string(), 1u32)).is_ok());
+    /// assert_eq!(vfs.read_bincode::<(String, u32), _>(&file).unwrap(), ("foo".to_string(), 1));
+    /// ```
+    #[cfg(feature = "bincode")]
+    fn read_bincode<T: serde::de::DeserializeOwned, U: AsRef<Path>>(&self, path: U) -> RvResult<T> {
+        Ok(bincode::deserialize_from(std::io::BufReader::new(self.read(path)?))?)
+    }
+
+    /// Read the given CSV file deserializing each row into the given type
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * The first row is treated as the header row and maps fields by name for structs
+    /// * Use `read_csv_iter` to stream large files a row at a time
+    ///
+    /// ### Errors
+    /// * PathError::IsNotFile(PathBuf) when the given path isn't a file
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    /// * csv::Error when a row fails to parse or deserialize
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let file = vfs.root().mash("file");
+    /// assert_vfs_write_all!(vfs, &file, "name,age\nfoo,1\n");
+    /// assert_eq!(vfs.read_csv::<(String, u32), _>(&file).unwrap(), vec![("foo".to_string(), 1)]);
+    /// ```
+    #[cfg(feature = "csv")]
+    fn read_csv<T: serde::de::DeserializeOwned, U: AsRef<Path>>(&self, path: U) -> RvResult<Vec<T>> {
+        self.read_csv_iter(path, b',')?.collect()
+    }
+
+    /// Returns a streaming iterator over the rows of the given delimited file
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Rows are read from the file's read handle as the iterator is advanced
+    /// * The first row is treated as the header row and maps fields by name for structs
+    /// * Rows that fail to parse or deserialize are returned as errors without ending the iteration
+    ///
+    /// ### Errors
+    /// * PathError::IsNotFile(PathBuf) when the given path isn't a file
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let file = vfs.root().mash("file");
+    /// assert_vfs_write_all!(vfs, &file, "name;age\nfoo;1\n");
+    /// let mut iter = vfs.read_csv_iter::<(String, u32), _>(&file, b';').unwrap();
+    /// assert_eq!(iter.next().unwrap().unwrap(), ("foo".to_string(), 1));
+    /// assert!(iter.next().is_none());
+    /// ```
+    #[cfg(feature = "csv")]
+    fn read_csv_iter<T: serde::de::DeserializeOwned, U: AsRef<Path>>(
+        &self, path: U, delimiter: u8,
+    ) -> RvResult<CsvIter<T>> {
+        Ok(CsvIter::new(self.read(path)?, delimiter))
+    }
+
+    /// Read the given file and returns it as lines in a vector
+    ///
+    /// * Handles path expansion and absolute path resolution
+    ///
+    /// ### Errors
+    /// * PathError::IsNotFile(PathBuf) when the given path isn't a file
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let file = vfs.root().mash("file");
+    /// assert_vfs_write_all!(vfs, &file, "1\n2");
+    /// assert_eq!(vfs.read_lines(&file).unwrap(), vec!["1".to_string(), "2".to_string()]);
+    /// ```
+    fn read_lines<T: AsRef<Path>>(&self, path: T) -> RvResult<Vec<String>>;
+
+    /// Read the given TSV file deserializing each row into the given type
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * The first row is treated as the header row and maps fields by name for structs
+    /// * Use `read_csv_iter` with a `b'\t'` delimiter to stream large files a row at a time
+    ///
+    /// ### Errors
+    /// * PathError::IsNotFile(PathBuf) when the given path isn't a file
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    /// * csv::Error when a row fails to parse or deserialize
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let file = vfs.root().mash("file");
+    /// assert_vfs_write_all!(vfs, &file, "name\tage\nfoo\t1\n");
+    /// assert_eq!(vfs.read_tsv::<(String, u32), _>(&file).unwrap(), vec![("foo".to_string(), 1)]);
+    /// ```
+    #[cfg(feature = "csv")]
+    fn read_tsv<T: serde::de::DeserializeOwned, U: AsRef<Path>>(&self, path: U) -> RvResult<Vec<T>> {
+        self.read_csv_iter(path, b'\t')?.collect()
+    }
+
+    /// Returns the relative path of the target the link points to
+    ///
+    /// * Handles path expansion and absolute path resolution
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let dir = vfs.root().mash("dir");
+    /// let link = dir.mash("link");
+    /// let file = vfs.root().mash("file");
+    /// assert_vfs_mkdir_p!(vfs, &dir);
+    /// assert_vfs_mkfile!(vfs, &file);
+    /// assert_vfs_symlink!(vfs, &link, &file);
+    /// assert_vfs_readlink!(vfs, &link, PathBuf::from("..").mash("file"));
+    /// ```
+    fn readlink<T: AsRef<Path>>(&self, path: T) -> RvResult<PathBuf>;
+
+    /// Returns the absolute path of the target the link points to
+    ///
+    /// * Handles path expansion and absolute path resolution
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let file = vfs.root().mash("file");
+    /// let link = vfs.root().mash("link");
+    /// assert_vfs_mkfile!(vfs, &file);
+    /// assert_vfs_symlink!(vfs, &link, &file);
+    /// assert_vfs_readlink_abs!(vfs, &link, &file);
+    /// ```
+    fn readlink_abs<T: AsRef<Path>>(&self, path: T) -> RvResult<PathBuf>;
+
+    /// Rewrites the given link's target as the minimal relative path to the same target
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Absolute link targets are converted to their relative equivalent
+    /// * Links already in their minimal relative form are left untouched
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given link doesn't exist
+    /// * PathError::IsNotSymlink(PathBuf) when the given path isn't a link
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let dir = vfs.root().mash("dir");
+    /// let file = vfs.root().mash("file");
+    /// let link = dir.mash("link");
+    /// assert_vfs_mkdir_p!(vfs, &dir);
+    /// assert_vfs_mkfile!(vfs, &file);
+    /// assert_vfs_symlink!(vfs, &link, &file);
+    /// assert_eq!(vfs.relink(&link).unwrap(), link);
+    /// assert_vfs_readlink!(vfs, &link, PathBuf::from("../file"));
+    /// ```
+    fn relink<T: AsRef<Path>>(&self, link: T) -> RvResult<PathBuf>;
+
+    /// Removes the given empty directory or file
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Link exclusion i.e. removes the link themselves not what its points to
+    ///
+    /// ### Errors
+    /// * a directory containing files will trigger an error. use `remove_all` instead
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let file = vfs.root().mash("file");
+    /// assert_vfs_mkfile!(vfs, &file);
+    /// assert_vfs_exists!(vfs, &file);
+    /// assert_vfs_remove!(vfs, &file);
+    /// assert_vfs_no_exists!(vfs, &file);
+    /// ```
+    fn remove<T: AsRef<Path>>(&self, path: T) -> RvResult<()>;
+
+    /// Removes the given directory after removing all of its contents
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Link exclusion i.e. removes the link themselves not what its points to
+    /// * Refuses to remove the root directory, use `remove_all_b` for more guards
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let dir = vfs.root().mash("dir");
+    /// let file = dir.mash("file");
+    /// assert_vfs_mkdir_p!(vfs, &dir);
+    /// assert_vfs_mkfile!(vfs, &file);
+    /// assert_vfs_is_file!(vfs, &file);
+    /// assert_vfs_remove_all!(vfs, &dir);
+    /// assert_vfs_no_exists!(vfs, &file);
+    /// assert_vfs_no_exists!(vfs, &dir);
+    /// ```
+    fn remove_all<T: AsRef<Path>>(&self, path: T) -> RvResult<()>;
+
+    /// Creates a new [`Remover`] for use with the builder pattern
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Link exclusion i.e. removes the link themselves not what its points to
+    /// * Refuses to remove the root directory
+    /// * Provides optional guards for the path being within a base and not crossing devices
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let dir = vfs.root().mash("dir");
+    /// assert_vfs_mkdir_p!(vfs, &dir);
+    /// let err = vfs.remove_all_b(vfs.root()).unwrap().exec().unwrap_err();
+    /// assert_eq!(err.downcast_ref::<VfsError>(), Some(&VfsError::RemoveRoot));
+    /// assert!(vfs.remove_all_b(&dir).unwrap().within(vfs.root()).same_device().exec().is_ok());
+    /// assert_vfs_no_exists!(vfs, &dir);
+    /// ```
+    fn remove_all_b<T: AsRef<Path>>(&self, path: T) -> RvResult<Remover>;
+
+    /// Resolve the given link one hop at a time returning the final target and the chain traversed
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Follows each link one hop at a time recording the links traversed
+    /// * Works on dangling chains returning the last existing link and the missing remainder
+    /// * Non-links resolve to themselves with an empty chain
+    ///
+    /// ### Errors
+    /// * PathError::LinkLooping(PathBuf) when the chain loops back on itself
+    /// * PathError::TooManyLinks(PathBuf) when more than `max_hops` hops are required
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let link = vfs.root().mash("link");
+    /// assert!(vfs.symlink(&link, "missing").is_ok());
+    /// let resolved = vfs.resolve_link(&link, 40).unwrap();
+    /// assert_eq!(resolved.chain, vec![link]);
+    /// assert_eq!(resolved.target, vfs.root().mash("missing"));
+    /// assert_eq!(resolved.exists, false);
+    /// ```
+    fn resolve_link<T: AsRef<Path>>(&self, path: T, max_hops: usize) -> RvResult<ResolvedLink>;
+
+    /// Returns the current root directory
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let mut root = PathBuf::new();
+    /// root.push(Component::RootDir);
+    /// assert_eq!(vfs.root(), root);
+    /// ```
+    fn root(&self) -> PathBuf;
+
+    /// Returns true if the given files have the same contents
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Links are followed
+    /// * Files of differing sizes are reported without being read
+    /// * Streams the contents in chunks so large files aren't read into memory
+    ///
+    /// ### Errors
+    /// * PathError::IsNotFile(PathBuf) when either path isn't a file
+    /// * PathError::DoesNotExist(PathBuf) when either path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// assert_vfs_write_all!(vfs, "file1", "foobar");
+    /// assert_vfs_write_all!(vfs, "file2", "foobar");
+    /// assert_vfs_write_all!(vfs, "file3", "foobaz");
+    /// assert_eq!(vfs.same_content("file1", "file2").unwrap(), true);
+    /// assert_eq!(vfs.same_content("file1", "file3").unwrap(), false);
+    /// ```
+    fn same_content<T: AsRef<Path>, U: AsRef<Path>>(&self, a: T, b: U) -> RvResult<bool> {
+        let a = self.resolve_link(a, sys::DEFAULT_MAX_LINKS)?.target;
+        let b = self.resolve_link(b, sys::DEFAULT_MAX_LINKS)?.target;
+        if self.size(&a)? != self.size(&b)? {
+            return Ok(false);
+        }
+
+        // Compare whatever both readers have buffered advancing by the shorter of the two
+        let (mut r1, mut r2) = (BufReader::new(self.read(&a)?), BufReader::new(self.read(&b)?));
+        loop {
+            let (buf1, buf2) = (r1.fill_buf()?, r2.fill_buf()?);
+            if buf1.is_empty() || buf2.is_empty() {
+                return Ok(buf1.is_empty() && buf2.is_empty());
+            }
+            let len = buf1.len().min(buf2.len());
+            if buf1[..len] != buf2[..len] {
+                return Ok(false);
+            }
+            r1.consume(len);
+            r2.consume(len);
+        }
+    }
+
+    /// Returns true if the given paths refer to the same underlying file
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Follows links so that a link and its target are considered the same file
+    /// * Returns false if either path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// assert_vfs_mkfile!(vfs, "file1");
+    /// assert_vfs_mkfile!(vfs, "file2");
+    /// assert_vfs_symlink!(vfs, "link", "file1");
+    /// assert_eq!(vfs.same_file("link", "file1"), true);
+    /// assert_eq!(vfs.same_file("link", "file2"), false);
+    /// ```
+    fn same_file<T: AsRef<Path>, U: AsRef<Path>>(&self, a: T, b: U) -> bool;
+
+    /// Set the POSIX access control list of the given path
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Links are followed
+    /// * The permission bits of the path's mode are updated to match the ACL
+    /// * A mask entry is added when named users or groups are given without one
+    /// * Default entries are only valid for directories, empty default entries remove them
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    /// * PathError::IsNotDir(PathBuf) when default entries are given for a non directory
+    /// * VfsError::InvalidAcl(PathBuf) when the entries are malformed
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let file = vfs.root().mash("file");
+    /// assert!(vfs.mkfile_m(&file, 0o640).is_ok());
+    /// assert!(vfs.set_acl(&file, Acl::from_mode(0o640).group(7, 0o6)).is_ok());
+    /// assert_eq!(vfs.get_acl(&file).unwrap().perms(AclTag::Group(7)), Some(0o6));
+    /// assert_eq!(vfs.mode(&file).unwrap(), 0o100660);
+    /// ```
+    fn set_acl<T: AsRef<Path>>(&self, path: T, acl: Acl) -> RvResult<()>;
+
+    /// Set the current working directory
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Relative path will use the current working directory
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let dir = vfs.root().mash("dir");
+    /// assert_eq!(vfs.cwd().unwrap(), vfs.root());
+    /// assert_vfs_mkdir_p!(vfs, &dir);
+    /// assert_eq!(vfs.set_cwd(&dir).unwrap(), dir.clone());
+    /// assert_eq!(vfs.cwd().unwrap(), dir);
+    /// ```
+    fn set_cwd<T: AsRef<Path>>(&self, path: T) -> RvResult<PathBuf>;
+
+    /// Set the access and modification times of the given path with nanosecond precision
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Doesn't follow links i.e. sets the times of the link itself
+    /// * Memfs doesn't track access times so only the modification time is set
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    /// use std::time::{Duration, UNIX_EPOCH};
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let file = vfs.root().mash("file");
+    /// assert_vfs_mkfile!(vfs, &file);
+    /// let time = UNIX_EPOCH + Duration::new(1_000_000_000, 123_456_789);
+    /// assert!(vfs.set_file_time(&file, time, time).is_ok());
+    /// assert_eq!(vfs.entry(&file).unwrap().modified(), time);
+    /// ```
+    fn set_file_time<T: AsRef<Path>>(&self, path: T, atime: SystemTime, mtime: SystemTime) -> RvResult<()>;
+
+    /// Set or clear the immutable flag of the given path i.e. `chattr +i` and `chattr -i`
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Links are followed
+    /// * Stdfs sets the inode flag which requires the `CAP_LINUX_IMMUTABLE` capability and a
+    ///   filesystem that supports it
+    /// * Immutable files can't be written, removed, renamed or linked to and immutable directories
+    ///   can't have entries added, removed or renamed until the flag is cleared. Memfs fails these
+    ///   with EPERM the same as the kernel.
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let file = vfs.root().mash("file");
+    /// assert_vfs_mkfile!(vfs, &file);
+    /// assert!(vfs.set_immutable(&file, true).is_ok());
+    /// assert_eq!(vfs.is_immutable(&file), true);
+    /// assert!(vfs.set_immutable(&file, false).is_ok());
+    /// assert_eq!(vfs.is_immutable(&file), false);
+    /// ```
+    fn set_immutable<T: AsRef<Path>>(&self, path: T, yes: bool) -> RvResult<()>;
+
+    /// Set the modification time of the given path with nanosecond precision
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Doesn't follow links i.e. sets the time of the link itself
+    /// * Leaves the access time untouched
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    /// use std::time::{Duration, UNIX_EPOCH};
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let file = vfs.root().mash("file");
+    /// assert_vfs_mkfile!(vfs, &file);
+    /// let time = UNIX_EPOCH + Duration::new(1_000_000_000, 123_456_789);
+    /// assert!(vfs.set_mtime_only(&file, time).is_ok());
+    /// assert_eq!(vfs.entry(&file).unwrap().modified(), time);
+    /// ```
+    fn set_mtime_only<T: AsRef<Path>>(&self, path: T, mtime: SystemTime) -> RvResult<()>;
+
+    /// Set the SELinux security context of the given path
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Links aren't followed so that the context of the link itself is set
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let file = vfs.root().mash("file");
+    /// assert_vfs_mkfile!(vfs, &file);
+    /// assert!(vfs.set_selinux_context(&file, "system_u:object_r:etc_t:s0").is_ok());
+    /// assert_eq!(vfs.get_selinux_context(&file).unwrap().unwrap(), "system_u:object_r:etc_t:s0");
+    /// ```
+    #[cfg(feature = "selinux")]
+    fn set_selinux_context<T: AsRef<Path>, U: AsRef<str>>(&self, path: T, context: U) -> RvResult<()>;
+
+    /// Returns the size in bytes of the given file
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Links are followed
+    /// * Use `disk_usage` to sum up the size of a directory tree
+    ///
+    /// ### Errors
+    /// * PathError::IsNotFile(PathBuf) when the given path isn't a file
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let tmpdir = vfs.root();
+    /// let file = tmpdir.mash("file");
+    /// assert_vfs_write_all!(vfs, &file, "foobar");
+    /// assert_eq!(vfs.size(&file).unwrap(), 6);
+    /// ```
+    fn size<T: AsRef<Path>>(&self, path: T) -> RvResult<u64>;
+
+    /// Returns the capacity and usage of the filesystem containing the given path
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Stdfs reports the values of the underlying `statvfs` call with `free` being the space
+    ///   available to unprivileged users
+    /// * Memfs reports the values of the deepest quota containing the path else the whole
+    ///   filesystem with `u64::MAX` as its capacity
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// assert_vfs_write_all!(vfs, "file", "foobar");
+    /// let stat = vfs.stat_fs(vfs.root()).unwrap();
+    /// assert_eq!(stat.used, 6);
+    /// assert_eq!(stat.inodes_used, 1);
+    /// ```
+    fn stat_fs<T: AsRef<Path>>(&self, path: T) -> RvResult<VfsStatFs>;
+
+    /// Creates a new symbolic link
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Computes the target path `src` relative to the `dst` link name's absolute path
+    /// * Returns the link path
+    ///
+    /// ### Arguments
+    /// * `link` - the path of the link being created
+    /// * `target` - the path that the link will point to
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let file = vfs.root().mash("file");
+    /// let link = vfs.root().mash("link");
+    /// assert_vfs_mkfile!(vfs, &file);
+    /// assert_vfs_symlink!(vfs, &link, &file);
+    /// assert_vfs_readlink_abs!(vfs, &link, &file);
+    /// ```
+    fn symlink<T: AsRef<Path>, U: AsRef<Path>>(&self, link: T, target: U) -> RvResult<PathBuf>;
+
+    /// Wraps `symlink` reporting whether the link was created or already existed
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * An existing link is only considered to exist if it points to the given target
+    /// * Dangling links are supported
+    ///
+    /// ### Errors
+    /// * PathError::ExistsAlready(PathBuf) when the link path exists but isn't a link to the target
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let link = vfs.root().mash("link");
+    /// assert_eq!(vfs.symlink_ensured(&link, "file").unwrap(), Ensured::Created(link.clone()));
+    /// assert_eq!(vfs.symlink_ensured(&link, "file").unwrap(), Ensured::Existed(link.clone()));
+    /// ```
+    fn symlink_ensured<T: AsRef<Path>, U: AsRef<Path>>(&self, link: T, target: U) -> RvResult<Ensured>;
+
+    /// Returns a deterministic SHA-256 Merkle hash of the given tree as a lower case hex string
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Covers the structure, file contents, permission bits and link targets of the tree
+    /// * Children are ordered by the raw bytes of their names not the backend's listing order
+    /// * Links are hashed by their target as stored and are never followed
+    /// * Timestamps, ownership and the name of the given path itself aren't part of the hash
+    /// * Identical trees give the same hash regardless of the backend they're stored in
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// assert_vfs_mkdir_p!(vfs, "dir");
+    /// assert_vfs_write_all!(vfs, "dir/file", "foobar");
+    /// let digest = vfs.tree_digest("dir").unwrap();
+    /// assert_eq!(digest.len(), 64);
+    /// assert_eq!(vfs.tree_digest("dir").unwrap(), digest);
+    /// vfs.write_all("dir/file", "foo").unwrap();
+    /// assert_ne!(vfs.tree_digest("dir").unwrap(), digest);
+    /// ```
+    #[cfg(feature = "digest")]
+    fn tree_digest<T: AsRef<Path>>(&self, path: T) -> RvResult<String> {
+        super::tree_digest(self, &self.abs(path)?)
+    }
+
+    /// Returns the user ID of the owner of this file
+    ///
+    /// * Handles path expansion and absolute path resolution
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// assert_eq!(vfs.uid(vfs.root()).unwrap(), 1000);
+    /// ```
+    fn uid<T: AsRef<Path>>(&self, path: T) -> RvResult<u32>;
+
+    /// Unpack the given tar archive into the given directory creating it if needed
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Restores the permission bits, modification time and link targets of each entry
+    /// * Restores ownership where permitted i.e. failures are only reported when running as root
+    /// * Existing files and links are replaced while existing directories are merged into
+    /// * Reads through the Vfs so archives can be unpacked into any backend
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given archive doesn't exist
+    /// * PathError::EscapesBase(PathBuf) when an entry would be written outside of the directory or
+    ///   is a link leading outside of it
+    /// * VfsError::InvalidArchive(PathBuf) when the given archive is corrupt or truncated
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// assert_vfs_mkdir_p!(vfs, "dir");
+    /// assert_vfs_write_all!(vfs, "dir/file", "foobar");
+    /// assert!(vfs.pack("dir", "dir.tar").is_ok());
+    /// assert!(vfs.unpack("dir.tar", "copy").is_ok());
+    /// assert_vfs_read_all!(vfs, "copy/file", "foobar");
+    /// ```
+    fn unpack<T: AsRef<Path>, U: AsRef<Path>>(&self, src: T, dst: U) -> RvResult<()> {
+        super::tar_unpack(self, &self.abs(src)?, &self.abs(dst)?)
+    }
+
+    /// Watch the given path for changes returning a stream of the events that occur
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Directories are watched recursively
+    /// * Reports files, directories and links being created, written to, removed and renamed
+    /// * Permission and ownership changes aren't reported
+    /// * See [`VfsWatcher`] for the details of how each backend reports changes
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let mut watcher = vfs.watch(vfs.root()).unwrap();
+    /// let file = vfs.root().mash("file");
+    /// assert_vfs_mkfile!(vfs, &file);
+    /// assert!(vfs.remove(&file).is_ok());
+    /// assert_eq!(watcher.try_recv(), Some(VfsEvent::Create(file.clone())));
+    /// assert_eq!(watcher.try_recv(), Some(VfsEvent::Remove(file)));
+    /// ```
+    fn watch<T: AsRef<Path>>(&self, path: T) -> RvResult<VfsWatcher>;
+
+    /// Up cast the trait type to the enum wrapper
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Memfs::new().upcast();
+    /// ```
+    fn upcast(self) -> Vfs;
+
+    /// Opens a file in write-only mode
+    ///
+    /// * Creates a file if it does not exist or truncates it if it does
+    /// * Returns a [`VfsWriter`] reporting the path and number of bytes written
+    ///
+    /// ### Errors
+    /// * PathError::IsNotDir(PathBuf) when the given path's parent exists but is not a directory
+    /// * PathError::DoesNotExist(PathBuf) when the given path's parent doesn't exist
+    /// * PathError::IsNotFile(PathBuf) when the given path exists but is not a file
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let file = vfs.root().mash("file");
+    /// let mut f = vfs.write(&file).unwrap();
+    /// f.write_all(b"foobar").unwrap();
+    /// f.flush().unwrap();
+    /// assert_vfs_read_all!(vfs, &file, "foobar");
+    /// ```
+    fn write<T: AsRef<Path>>(&self, path: T) -> RvResult<VfsWriter>;
+
+    /// Write the given data to to the target file
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Create the file first if it doesn't exist or truncating it first if it does
+    ///
+    /// ### Errors
+    /// * PathError::IsNotDir(PathBuf) when the given path's parent exists but is not a directory
+    /// * PathError::DoesNotExist(PathBuf) when the given path's parent doesn't exist
+    /// * PathError::IsNotFile(PathBuf) when the given path exists but is not a file
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let file = vfs.root().mash("file");
+    /// assert_vfs_no_file!(vfs, &file);
+    /// assert_vfs_write_all!(vfs, &file, "foobar 1");
+    /// assert_vfs_is_file!(vfs, &file);
+    /// assert_vfs_read_all!(vfs, &file, "foobar 1");
+    /// ```
+    fn write_all<T: AsRef<Path>, U: AsRef<[u8]>>(&self, path: T, data: U) -> RvResult<()>;
+
+    /// Write the given data to the target file atomically
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Writes to a temporary sibling file which is then renamed into place so that readers only
+    ///   ever see the old or the new data and a failure never leaves a partially written file
+    /// * Replaces the final target of links rather than the links themselves
+    /// * Preserves the mode and ownership of an existing file
+    /// * Breaks any hard links to an existing file as it is replaced by a new file
+    ///
+    /// ### Errors
+    /// * PathError::IsNotDir(PathBuf) when the given path's parent exists but is not a directory
+    /// * PathError::DoesNotExist(PathBuf) when the given path's parent doesn't exist
+    /// * PathError::IsNotFile(PathBuf) when the given path exists but is not a file
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let tmpdir = vfs.root();
+    /// let file = tmpdir.mash("file");
+    /// assert!(vfs.write_all_atomic(&file, "foobar").is_ok());
+    /// assert_vfs_read_all!(vfs, &file, "foobar");
+    /// ```
+    fn write_all_atomic<T: AsRef<Path>, U: AsRef<[u8]>>(&self, path: T, data: U) -> RvResult<()>;
+
+    /// Creates a new [`Writer`] for use with the builder pattern
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Options for writing atomically
+    /// * Execute by calling `exec`
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let tmpdir = vfs.root();
+    /// let file = tmpdir.mash("file");
+    /// assert!(vfs.write_b(&file, "foobar").unwrap().atomic(true).exec().is_ok());
+    /// assert_vfs_read_all!(vfs, &file, "foobar");
+    /// ```
+    fn write_b<T: AsRef<Path>, U: AsRef<[u8]>>(&self, path: T, data: U) -> RvResult<Writer>;
+
+    /// Write the given value to the target file serialized with bincode
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Create the file first if it doesn't exist or truncating it first if it does
+    /// * The value is fully serialized before the file is touched so a failure leaves it intact
+    ///
+    /// ### Errors
+    /// * PathError::IsNotDir(PathBuf) when the given path's parent exists but is not a directory
+    /// * PathError::DoesNotExist(PathBuf) when the given path's parent doesn't exist
+    /// * PathError::IsNotFile(PathBuf) when the given path exists but is not a file
+    /// * bincode::Error when the value fails to serialize
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let file = vfs.root().mash("file");
+    /// assert!(vfs.write_bincode(&file, &vec![1u8, 2, 3]).is_ok());
+    /// assert_eq!(vfs.read_bincode::<Vec<u8>, _>(&file).unwrap(), vec![1, 2, 3]);
+    /// ```
+    #[cfg(feature = "bincode")]
+    fn write_bincode<T: serde::Serialize, U: AsRef<Path>>(&self, path: U, value: &T) -> RvResult<()> {
+        self.write_all(path, bincode::serialize(value)?)
+    }
+
+    /// Write the given lines to to the target file including final newline
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Create the file first if it doesn't exist or truncating it first if it does
+    ///
+    /// ### Errors
+    /// * PathError::IsNotDir(PathBuf) when the given path's parent exists but is not a directory
+    /// * PathError::DoesNotExist(PathBuf) when the given path's parent doesn't exist
+    /// * PathError::IsNotFile(PathBuf) when the given path exists but is not a file
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let file = vfs.root().mash("file");
+    /// assert_vfs_no_file!(vfs, &file);
+    /// assert!(vfs.write_lines(&file, &["1", "2"]).is_ok());
+    /// assert_vfs_is_file!(vfs, &file);
+    /// assert_vfs_read_all!(vfs, &file, "1\n2\n".to_string());
+    /// ```
+    fn write_lines<T: AsRef<Path>, U: AsRef<str>>(&self, path: T, lines: &[U]) -> RvResult<()>;
+}
+
+/// Provides an ergonomic encapsulation of the underlying [`VirtualFileSystem`] backend
+/// implementations
+#[derive(Debug)]
+pub enum Vfs {
+    Stdfs(Stdfs),
+    Memfs(Memfs),
+    Overlayfs(Overlayfs),
+    Readonly(Box<Vfs>),
+}
+
+impl Vfs {
+    /// Create a new builder for constructing a Vfs along with its per instance options
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs =
+    ///     Vfs::builder().backend(Backend::Memfs).cwd("/work").umask(0o027).readonly(false).build().unwrap();
+    /// assert_eq!(vfs.cwd().unwrap(), PathBuf::from("/work"));
+    /// ```
+    pub fn builder() -> VfsBuilder {
+        VfsBuilder::default()
+    }
+
+    /// Create a new Vfs with the backend and options given by the environment
+    ///
+    /// * See `VfsBuilder::from_env` for the environment variables and config file
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// std::env::set_var("RIVIA_VFS", "memfs");
+    /// let vfs = Vfs::from_env().unwrap();
+    /// assert!(matches!(vfs, Vfs::Memfs(_)));
+    /// ```
+    pub fn from_env() -> RvResult<Vfs> {
+        VfsBuilder::from_env()?.build()
+    }
+
+    /// Create a new instance of Memfs wrapped in the Vfs enum
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// assert_vfs_no_exists!(vfs, "humbug5");
+    /// ```
+    pub fn memfs() -> Vfs {
+        Vfs::Memfs(Memfs::new())
+    }
+
+    /// Create a new instance of Overlayfs over the given lower Vfs wrapped in the Vfs enum
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::overlayfs(Vfs::memfs());
+    /// assert_vfs_no_exists!(vfs, "humbug5");
+    /// ```
+    pub fn overlayfs(lower: Vfs) -> Vfs {
+        Vfs::Overlayfs(Overlayfs::new(lower))
+    }
+
+    /// Wrap the given Vfs so that all mutating operations are rejected
+    ///
+    /// * The full read API remains available passing through to the given Vfs
+    /// * Mutations are rejected up front leaving the given Vfs untouched, thus other handles to the
+    ///   same backend e.g. Memfs clones are still writable
+    /// * Directory handles opened through the wrapper reject mutations as well
+    /// * Hooks, watches and limits are those of the given Vfs
+    ///
+    /// ### Errors
+    /// * VfsError::ReadOnly(VfsOp, PathBuf) when a mutating operation is attempted
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let memfs = Vfs::memfs();
+    /// assert_vfs_write_all!(memfs, "file", "foobar");
+    /// let vfs = Vfs::readonly(memfs);
+    /// assert_vfs_read_all!(vfs, "file", "foobar");
+    /// assert!(vfs.write_all("file", "foo").is_err());
+    /// assert_vfs_read_all!(vfs, "file", "foobar");
+    /// ```
+    pub fn readonly(inner: Vfs) -> Vfs {
+        Vfs::Readonly(Box::new(inner))
+    }
+
+    /// Create a new instance of Stdfs wrapped in the Vfs enum
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::stdfs();
+    /// assert_vfs_no_exists!(vfs, "humbug5");
+    /// ```
     pub fn stdfs() -> Vfs {
         Vfs::Stdfs(Stdfs::new())
     }
-}
+}
+
+impl VirtualFileSystem for Vfs {
+    /// Return the path in an absolute clean form
+    ///
+    /// * Environment variable expansion
+    /// * Relative path resolution for `.` and `..`
+    /// * No IO resolution so it will work even with paths that don't exist
+    ///
+    /// ### Errors
+    /// * PathError::ParentNotFound(PathBuf) when parent is not found
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let home = sys::home_dir().unwrap();
+    /// assert_eq!(vfs.abs("~").unwrap(), PathBuf::from(&home));
+    /// ```
+    fn abs<T: AsRef<Path>>(&self, path: T) -> RvResult<PathBuf> {
+        match self {
+            Vfs::Stdfs(x) => x.abs(path),
+            Vfs::Memfs(x) => x.abs(path),
+            Vfs::Overlayfs(x) => x.abs(path),
+            Vfs::Readonly(x) => x.abs(path),
+        }
+    }
+
+    /// Advise the kernel how the given file's data will be accessed
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Stdfs uses `posix_fadvise` on the whole file e.g. `Advice::DontNeed` drops the file from
+    ///   the page cache so that streaming scanners don't pollute it
+    /// * `Advice::Sequential` and `Advice::Random` only apply to the handle they are given on Linux
+    ///   so use `Reader::advise` for those instead
+    /// * Memfs has no page cache so this is a no-op
+    ///
+    /// ### Errors
+    /// * PathError::IsNotFile(PathBuf) when the given path isn't a file
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let file = vfs.root().mash("file");
+    /// assert_vfs_write_all!(vfs, &file, "this is a test");
+    /// assert!(vfs.advise(&file, Advice::DontNeed).is_ok());
+    /// assert_vfs_read_all!(vfs, &file, "this is a test");
+    /// ```
+    fn advise<T: AsRef<Path>>(&self, path: T, advice: Advice) -> RvResult<()> {
+        match self {
+            Vfs::Stdfs(x) => x.advise(path, advice),
+            Vfs::Memfs(x) => x.advise(path, advice),
+            Vfs::Overlayfs(x) => x.advise(path, advice),
+            Vfs::Readonly(x) => x.advise(path, advice),
+        }
+    }
+
+    /// Returns all dirs for the given path recursively
+    ///
+    /// * Results are sorted by filename, are distict and don't include the given path
+    /// * Handles path expansion and absolute path resolution
+    /// * Paths are returned in absolute form
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let tmpdir = vfs.root().mash("tmpdir");
+    /// let dir1 = tmpdir.mash("dir1");
+    /// let dir2 = dir1.mash("dir2");
+    /// assert_vfs_mkdir_p!(vfs, &dir2);
+    /// assert_iter_eq(vfs.all_dirs(&tmpdir).unwrap(), vec![dir1, dir2]);
+    /// ```
+    fn all_dirs<T: AsRef<Path>>(&self, path: T) -> RvResult<Vec<PathBuf>> {
+        match self {
+            Vfs::Stdfs(x) => x.all_dirs(path),
+            Vfs::Memfs(x) => x.all_dirs(path),
+            Vfs::Overlayfs(x) => x.all_dirs(path),
+            Vfs::Readonly(x) => x.all_dirs(path),
+        }
+    }
+
+    /// Returns all files for the given path recursively
+    ///
+    /// * Results are sorted by filename, are distict and don't include the given path
+    /// * Handles path expansion and absolute path resolution
+    /// * Paths are returned in absolute form
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let tmpdir = vfs.root().mash("tmpdir");
+    /// let file1 = tmpdir.mash("file1");
+    /// let dir1 = tmpdir.mash("dir1");
+    /// let file2 = dir1.mash("file2");
+    /// assert_vfs_mkdir_p!(vfs, &dir1);
+    /// assert_vfs_mkfile!(vfs, &file1);
+    /// assert_vfs_mkfile!(vfs, &file2);
+    /// assert_iter_eq(vfs.all_files(&tmpdir).unwrap(), vec![file2, file1]);
+    /// ```
+    fn all_files<T: AsRef<Path>>(&self, path: T) -> RvResult<Vec<PathBuf>> {
+        match self {
+            Vfs::Stdfs(x) => x.all_files(path),
+            Vfs::Memfs(x) => x.all_files(path),
+            Vfs::Overlayfs(x) => x.all_files(path),
+            Vfs::Readonly(x) => x.all_files(path),
+        }
+    }
+
+    /// Returns all paths for the given path recursively
+    ///
+    /// * Results are sorted by filename, are distict and don't include the given path
+    /// * Handles path expansion and absolute path resolution
+    /// * Paths are returned in absolute form
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let tmpdir = vfs.root().mash("tmpdir");
+    /// let dir1 = tmpdir.mash("dir1");
+    /// let file1 = tmpdir.mash("file1");
+    /// let file2 = dir1.mash("file2");
+    /// let file3 = dir1.mash("file3");
+    /// assert_vfs_mkdir_p!(vfs, &dir1);
+    /// assert_vfs_mkfile!(vfs, &file1);
+    /// assert_vfs_mkfile!(vfs, &file2);
+    /// assert_vfs_mkfile!(vfs, &file3);
+    /// assert_iter_eq(vfs.all_paths(&tmpdir).unwrap(), vec![dir1, file2, file3, file1]);
+    /// ```
+    fn all_paths<T: AsRef<Path>>(&self, path: T) -> RvResult<Vec<PathBuf>> {
+        match self {
+            Vfs::Stdfs(x) => x.all_paths(path),
+            Vfs::Memfs(x) => x.all_paths(path),
+            Vfs::Overlayfs(x) => x.all_paths(path),
+            Vfs::Readonly(x) => x.all_paths(path),
+        }
+    }
+
+    /// Allocate space for the given file up front to avoid fragmentation and fail early when there
+    /// isn't enough space
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Creates the file if it doesn't exist
+    /// * Grows the file to `len` bytes filled with zeros if smaller, existing data is left intact
+    /// * Stdfs uses `posix_fallocate` while Memfs reserves the file's buffer
+    ///
+    /// ### Errors
+    /// * PathError::IsNotDir(PathBuf) when the given path's parent exists but is not a directory
+    /// * PathError::DoesNotExist(PathBuf) when the given path's parent doesn't exist
+    /// * PathError::IsNotFile(PathBuf) when the given path exists but is not a file
+    /// * VfsError::QuotaExceeded(PathBuf) when Memfs quota would be exceeded
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let file = vfs.root().mash("file");
+    /// assert!(vfs.allocate(&file, 1024).is_ok());
+    /// assert_eq!(vfs.entry(&file).unwrap().size(), 1024);
+    /// ```
+    fn allocate<T: AsRef<Path>>(&self, path: T, len: u64) -> RvResult<()> {
+        match self {
+            Vfs::Stdfs(x) => x.allocate(path, len),
+            Vfs::Memfs(x) => x.allocate(path, len),
+            Vfs::Overlayfs(x) => x.allocate(path, len),
+            Vfs::Readonly(x) => rejected(x, VfsOp::Write, path),
+        }
+    }
+
+    /// Opens a file in append mode
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Creates a file if it does not exist or appends to it if it does
+    /// * Returns a [`VfsWriter`] reporting the path and number of bytes written
+    ///
+    /// ### Errors
+    /// * PathError::IsNotDir(PathBuf) when the given path's parent exists but is not a directory
+    /// * PathError::DoesNotExist(PathBuf) when the given path's parent doesn't exist
+    /// * PathError::IsNotFile(PathBuf) when the given path exists but is not a file
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let file = vfs.root().mash("file");
+    /// let mut f = vfs.write(&file).unwrap();
+    /// f.write_all(b"foobar").unwrap();
+    /// f.flush().unwrap();
+    /// let mut f = vfs.append(&file).unwrap();
+    /// f.write_all(b"123").unwrap();
+    /// f.flush().unwrap();
+    /// assert_vfs_read_all!(vfs, &file, "foobar123");
+    /// ```
+    fn append<T: AsRef<Path>>(&self, path: T) -> RvResult<VfsWriter> {
+        match self {
+            Vfs::Stdfs(x) => x.append(path),
+            Vfs::Memfs(x) => x.append(path),
+            Vfs::Overlayfs(x) => x.append(path),
+            Vfs::Readonly(x) => rejected(x, VfsOp::Write, path),
+        }
+    }
+
+    /// Append the given data to to the target file
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Creates a file if it does not exist or appends to it if it does
+    ///
+    /// ### Errors
+    /// * PathError::IsNotDir(PathBuf) when the given path's parent exists but is not a directory
+    /// * PathError::DoesNotExist(PathBuf) when the given path's parent doesn't exist
+    /// * PathError::IsNotFile(PathBuf) when the given path exists but is not a file
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let file = vfs.root().mash("file");
+    /// assert_vfs_no_file!(vfs, &file);
+    /// assert_vfs_write_all!(vfs, &file, "foobar 1");
+    /// assert!(vfs.append_all(&file, "foobar 2").is_ok());
+    /// assert_vfs_is_file!(vfs, &file);
+    /// assert_vfs_read_all!(vfs, &file, "foobar 1foobar 2");
+    /// ```
+    fn append_all<T: AsRef<Path>, U: AsRef<[u8]>>(&self, path: T, data: U) -> RvResult<()> {
+        match self {
+            Vfs::Stdfs(x) => x.append_all(path, data),
+            Vfs::Memfs(x) => x.append_all(path, data),
+            Vfs::Overlayfs(x) => x.append_all(path, data),
+            Vfs::Readonly(x) => rejected(x, VfsOp::Write, path),
+        }
+    }
+
+    /// Append the given line to to the target file including a newline
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Creates a file if it does not exist or appends to it if it does
+    ///
+    /// ### Errors
+    /// * PathError::IsNotDir(PathBuf) when the given path's parent exists but is not a directory
+    /// * PathError::DoesNotExist(PathBuf) when the given path's parent doesn't exist
+    /// * PathError::IsNotFile(PathBuf) when the given path exists but is not a file
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let file = vfs.root().mash("file");
+    /// assert_vfs_no_file!(vfs, &file);
+    /// assert_vfs_write_all!(vfs, &file, "foobar 1");
+    /// assert!(vfs.append_line(&file, "foobar 2").is_ok());
+    /// assert_vfs_is_file!(vfs, &file);
+    /// assert_vfs_read_all!(vfs, &file, "foobar 1foobar 2\n");
+    /// ```
+    fn append_line<T: AsRef<Path>, U: AsRef<str>>(&self, path: T, line: U) -> RvResult<()> {
+        match self {
+            Vfs::Stdfs(x) => x.append_line(path, line),
+            Vfs::Memfs(x) => x.append_line(path, line),
+            Vfs::Overlayfs(x) => x.append_line(path, line),
+            Vfs::Readonly(x) => rejected(x, VfsOp::Write, path),
+        }
+    }
+
+    /// Append the given lines to to the target file including newlines
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Creates a file if it does not exist or appends to it if it does
+    ///
+    /// ### Errors
+    /// * PathError::IsNotDir(PathBuf) when the given path's parent exists but is not a directory
+    /// * PathError::DoesNotExist(PathBuf) when the given path's parent doesn't exist
+    /// * PathError::IsNotFile(PathBuf) when the given path exists but is not a file
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let file = vfs.root().mash("file");
+    /// assert_vfs_no_file!(vfs, &file);
+    /// assert!(vfs.append_lines(&file, &["1", "2"]).is_ok());
+    /// assert_vfs_is_file!(vfs, &file);
+    /// assert_vfs_read_all!(vfs, &file, "1\n2\n");
+    /// ```
+    fn append_lines<T: AsRef<Path>, U: AsRef<str>>(&self, path: T, lines: &[U]) -> RvResult<()> {
+        match self {
+            Vfs::Stdfs(x) => x.append_lines(path, lines),
+            Vfs::Memfs(x) => x.append_lines(path, lines),
+            Vfs::Overlayfs(x) => x.append_lines(path, lines),
+            Vfs::Readonly(x) => rejected(x, VfsOp::Write, path),
+        }
+    }
+
+    /// Change all file/dir permissions recursivly to `mode`
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Doesn't follow links by default, use the builder `chomd_b` for this option
+    ///
+    /// ### Errors
+    /// * PathError::Empty when the given path is empty
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let file = vfs.root().mash("file");
+    /// assert_vfs_mkfile!(vfs, &file);
+    /// assert_eq!(vfs.mode(&file).unwrap(), 0o100644);
+    /// assert!(vfs.chmod(&file, 0o555).is_ok());
+    /// assert_eq!(vfs.mode(&file).unwrap(), 0o100555);
+    /// ```
+    fn chmod<T: AsRef<Path>>(&self, path: T, mode: u32) -> RvResult<()> {
+        match self {
+            Vfs::Stdfs(x) => x.chmod(path, mode),
+            Vfs::Memfs(x) => x.chmod(path, mode),
+            Vfs::Overlayfs(x) => x.chmod(path, mode),
+            Vfs::Readonly(x) => rejected(x, VfsOp::Chmod, path),
+        }
+    }
+
+    /// Returns a new [`Chmod`] builder for advanced chmod options
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Provides options for recursion, following links, narrowing in on file types etc...
+    ///
+    /// ### Errors
+    /// * PathError::Empty when the given path is empty
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let dir = vfs.root().mash("dir");
+    /// let file = dir.mash("file");
+    /// assert_vfs_mkdir_p!(vfs, &dir);
+    /// assert_vfs_mkfile!(vfs, &file);
+    /// assert_eq!(vfs.mode(&dir).unwrap(), 0o40755);
+    /// assert_eq!(vfs.mode(&file).unwrap(), 0o100644);
+    /// assert!(vfs.chmod_b(&dir).unwrap().recurse().all(0o777).exec().is_ok());
+    /// assert_eq!(vfs.mode(&dir).unwrap(), 0o40777);
+    /// assert_eq!(vfs.mode(&file).unwrap(), 0o100777);
+    /// ```
+    fn chmod_b<T: AsRef<Path>>(&self, path: T) -> RvResult<Chmod> {
+        match self {
+            Vfs::Stdfs(x) => x.chmod_b(path),
+            Vfs::Memfs(x) => x.chmod_b(path),
+            Vfs::Overlayfs(x) => x.chmod_b(path),
+            Vfs::Readonly(x) => rejected(x, VfsOp::Chmod, path),
+        }
+    }
+
+    /// Change the ownership of the path recursivly
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Use `chown_b` for more options
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let file1 = vfs.root().mash("file1");
+    /// assert_vfs_mkfile!(vfs, &file1);
+    /// assert!(vfs.chown(&file1, 5, 7).is_ok());
+    /// assert_eq!(vfs.owner(&file1).unwrap(), (5, 7));
+    /// ```
+    fn chown<T: AsRef<Path>>(&self, path: T, uid: u32, gid: u32) -> RvResult<()> {
+        match self {
+            Vfs::Stdfs(x) => x.chown(path, uid, gid),
+            Vfs::Memfs(x) => x.chown(path, uid, gid),
+            Vfs::Overlayfs(x) => x.chown(path, uid, gid),
+            Vfs::Readonly(x) => rejected(x, VfsOp::Chown, path),
+        }
+    }
+
+    /// Creates new [`Chown`] for use with the builder pattern
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Provides options for recursion, following links, narrowing in on file types etc...
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let file1 = vfs.root().mash("file1");
+    /// assert_vfs_mkfile!(vfs, &file1);
+    /// assert!(vfs.chown_b(&file1).unwrap().owner(5, 7).exec().is_ok());
+    /// assert_eq!(vfs.owner(&file1).unwrap(), (5, 7));
+    /// ```
+    fn chown_b<T: AsRef<Path>>(&self, path: T) -> RvResult<Chown> {
+        match self {
+            Vfs::Stdfs(x) => x.chown_b(path),
+            Vfs::Memfs(x) => x.chown_b(path),
+            Vfs::Overlayfs(x) => x.chown_b(path),
+            Vfs::Readonly(x) => rejected(x, VfsOp::Chown, path),
+        }
+    }
+
+    /// Returns the config of the Vfs backend instance
+    ///
+    /// * Set with `Memfs::with_config` or `Stdfs::with_config` else the defaults apply
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// assert_eq!(vfs.config(), VfsConfig::default());
+    /// ```
+    fn config(&self) -> VfsConfig {
+        match self {
+            Vfs::Stdfs(x) => x.config(),
+            Vfs::Memfs(x) => x.config(),
+            Vfs::Overlayfs(x) => x.config(),
+            Vfs::Readonly(x) => x.config(),
+        }
+    }
+
+    /// Returns the highest priority active configuration directory.
+    ///
+    /// * Searches first the $XDG_CONFIG_HOME directory, then the $XDG_CONFIG_DIRS directories.
+    /// * Returns the first directory that contains the given configuration file.
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs(); // replace this with Vfs::stdfs() for the real filesystem
+    /// let dir = PathBuf::from("/etc/xdg");
+    /// vfs.mkdir_p(&dir).unwrap();
+    /// let filepath = dir.mash("rivia.toml");
+    /// vfs.write_all(&filepath, "this is a test").unwrap();
+    /// assert_eq!(vfs.config_dir("rivia.toml").unwrap().to_str().unwrap(), "/etc/xdg");
+    ///
+    /// if let Some(config_dir) = vfs.config_dir("rivia.toml") {
+    ///    let path = config_dir.mash("rivia.toml");
+    ///    let config = vfs.read_all(&path).unwrap();
+    ///    assert_eq!(config, "this is a test");
+    /// }
+    /// ```
+    fn config_dir<T: AsRef<str>>(&self, config: T) -> Option<PathBuf> {
+        match self {
+            Vfs::Stdfs(x) => x.config_dir(config),
+            Vfs::Memfs(x) => x.config_dir(config),
+            Vfs::Overlayfs(x) => x.config_dir(config),
+            Vfs::Readonly(x) => x.config_dir(config),
+        }
+    }
+
+    /// Copies src to dst recursively
+    ///
+    /// * `dst` will be copied into if it is an existing directory
+    /// * `dst` will be a copy of the src if it doesn't exist
+    /// * Creates destination directories as needed
+    /// * Handles environment variable expansion
+    /// * Handles relative path resolution for `.` and `..`
+    /// * Doesn't follow links
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let file1 = vfs.root().mash("file1");
+    /// let file2 = vfs.root().mash("file2");
+    /// assert_vfs_write_all!(vfs, &file1, "this is a test");
+    /// assert!(vfs.copy(&file1, &file2).is_ok());
+    /// assert_vfs_read_all!(vfs, &file2, "this is a test");
+    /// ```
+    fn copy<T: AsRef<Path>, U: AsRef<Path>>(&self, src: T, dst: U) -> RvResult<()> {
+        match self {
+            Vfs::Stdfs(x) => x.copy(src, dst),
+            Vfs::Memfs(x) => x.copy(src, dst),
+            Vfs::Overlayfs(x) => x.copy(src, dst),
+            Vfs::Readonly(x) => rejected(x, VfsOp::Write, dst),
+        }
+    }
+
+    /// Creates a new [`Copier`] for use with the builder pattern
+    ///
+    /// * `dst` will be copied into if it is an existing directory
+    /// * `dst` will be a copy of the src if it doesn't exist
+    /// * Handles environment variable expansion
+    /// * Handles relative path resolution for `.` and `..`
+    /// * Options for recursion, mode setting and following links
+    /// * Execute by calling `exec`
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let file1 = vfs.root().mash("file1");
+    /// let file2 = vfs.root().mash("file2");
+    /// assert_vfs_write_all!(vfs, &file1, "this is a test");
+    /// assert!(vfs.copy_b(&file1, &file2).unwrap().exec().is_ok());
+    /// assert_vfs_read_all!(vfs, &file2, "this is a test");
+    /// ```
+    fn copy_b<T: AsRef<Path>, U: AsRef<Path>>(&self, src: T, dst: U) -> RvResult<Copier> {
+        match self {
+            Vfs::Stdfs(x) => x.copy_b(src, dst),
+            Vfs::Memfs(x) => x.copy_b(src, dst),
+            Vfs::Overlayfs(x) => x.copy_b(src, dst),
+            Vfs::Readonly(x) => rejected(x, VfsOp::Write, dst),
+        }
+    }
+
+    /// Returns the current working directory
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let dir = vfs.root().mash("dir");
+    /// assert_eq!(vfs.cwd().unwrap(), vfs.root());
+    /// assert_eq!(&vfs.mkdir_p(&dir).unwrap(), &dir);
+    /// assert_eq!(&vfs.set_cwd(&dir).unwrap(), &dir);
+    /// assert_eq!(&vfs.cwd().unwrap(), &dir);
+    /// ```
+    fn cwd(&self) -> RvResult<PathBuf> {
+        match self {
+            Vfs::Stdfs(x) => x.cwd(),
+            Vfs::Memfs(x) => x.cwd(),
+            Vfs::Overlayfs(x) => x.cwd(),
+            Vfs::Readonly(x) => x.cwd(),
+        }
+    }
+
+    /// Returns all directories for the given path, sorted by name
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Paths are returned as abs paths
+    /// * Doesn't include the path itself only its children nor is this recursive
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let tmpdir = vfs.root().mash("tmpdir");
+    /// let dir1 = tmpdir.mash("dir1");
+    /// let dir2 = tmpdir.mash("dir2");
+    /// let file1 = tmpdir.mash("file1");
+    /// assert_vfs_mkdir_p!(vfs, &dir1);
+    /// assert_vfs_mkdir_p!(vfs, &dir2);
+    /// assert_vfs_mkfile!(vfs, &file1);
+    /// assert_iter_eq(vfs.dirs(&tmpdir).unwrap(), vec![dir1, dir2]);
+    /// ```
+    fn dirs<T: AsRef<Path>>(&self, path: T) -> RvResult<Vec<PathBuf>> {
+        match self {
+            Vfs::Stdfs(x) => x.dirs(path),
+            Vfs::Memfs(x) => x.dirs(path),
+            Vfs::Overlayfs(x) => x.dirs(path),
+            Vfs::Readonly(x) => x.dirs(path),
+        }
+    }
+
+    /// Creates a new [`DiskUsage`] for summing up the size of the given path much like `du`
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Recursively sums the apparent size of the files in the tree counting hard links once
+    /// * Options for excluding globs and following links
+    /// * Execute by calling `exec`
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let tmpdir = vfs.root();
+    /// let file1 = tmpdir.mash("file1");
+    /// let file2 = tmpdir.mash("dir/file2");
+    /// assert_vfs_mkdir_p!(vfs, tmpdir.mash("dir"));
+    /// assert_vfs_write_all!(vfs, &file1, "foo");
+    /// assert_vfs_write_all!(vfs, &file2, "foobar");
+    /// assert_eq!(vfs.disk_usage(&tmpdir).unwrap().exec().unwrap(), 9);
+    /// ```
+    fn disk_usage<T: AsRef<Path>>(&self, path: T) -> RvResult<DiskUsage> {
+        match self {
+            Vfs::Stdfs(x) => x.disk_usage(path),
+            Vfs::Memfs(x) => x.disk_usage(path),
+            Vfs::Overlayfs(x) => x.disk_usage(path),
+            Vfs::Readonly(x) => x.disk_usage(path),
+        }
+    }
+
+    /// Returns an iterator over the given path
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Handles recursive path traversal
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let dir = vfs.root().mash("dir");
+    /// let file = dir.mash("file");
+    /// assert_vfs_mkdir_p!(vfs, &dir);
+    /// assert_vfs_mkfile!(vfs, &file);
+    /// let mut iter = vfs.entries(vfs.root()).unwrap().into_iter();
+    /// assert_iter_eq(iter.map(|x| x.unwrap().path_buf()), vec![vfs.root(), dir, file]);
+    /// ```
+    fn entries<T: AsRef<Path>>(&self, path: T) -> RvResult<Entries> {
+        match self {
+            Vfs::Stdfs(x) => x.entries(path),
+            Vfs::Memfs(x) => x.entries(path),
+            Vfs::Overlayfs(x) => x.entries(path),
+            Vfs::Readonly(x) => x.entries(path),
+        }
+    }
+
+    /// Return a virtual filesystem entry for the given path
+    ///
+    /// * Handles converting path to absolute form
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let file = vfs.root().mash("file");
+    /// assert_vfs_mkfile!(vfs, &file);
+    /// assert!(vfs.entry(&file).unwrap().is_file());
+    /// ```
+    fn entry<T: AsRef<Path>>(&self, path: T) -> RvResult<VfsEntry> {
+        match self {
+            Vfs::Stdfs(x) => x.entry(path),
+            Vfs::Memfs(x) => x.entry(path),
+            Vfs::Overlayfs(x) => x.entry(path),
+            Vfs::Readonly(x) => x.entry(path),
+        }
+    }
+
+    /// Ensures the given directory exists with the given mode and ownership
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Creates the directory and any parent directories needed if it doesn't exist
+    /// * Only changes the mode and ownership when they differ from those given
+    /// * Links are followed so the directories they point to are affected
+    /// * Returns true if anything was changed
+    ///
+    /// ### Errors
+    /// * PathError::IsNotDir(PathBuf) when the given path exists but is not a directory
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let dir = vfs.root().mash("dir");
+    /// assert_eq!(vfs.ensure_dir(&dir, 0o700, 5, 5).unwrap(), true);
+    /// assert_eq!(vfs.ensure_dir(&dir, 0o700, 5, 5).unwrap(), false);
+    /// assert_eq!(vfs.mode(&dir).unwrap(), 0o40700);
+    /// assert_eq!(vfs.owner(&dir).unwrap(), (5, 5));
+    /// ```
+    fn ensure_dir<T: AsRef<Path>>(&self, path: T, mode: u32, uid: u32, gid: u32) -> RvResult<bool> {
+        match self {
+            Vfs::Stdfs(x) => x.ensure_dir(path, mode, uid, gid),
+            Vfs::Memfs(x) => x.ensure_dir(path, mode, uid, gid),
+            Vfs::Overlayfs(x) => x.ensure_dir(path, mode, uid, gid),
+            Vfs::Readonly(x) => rejected(x, VfsOp::Create, path),
+        }
+    }
+
+    /// Ensures the given file exists with the given data and mode
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Only writes the data when it differs from the file's current content
+    /// * Only changes the mode when it differs from the one given
+    /// * Links are followed so the files they point to are affected
+    /// * Returns true if anything was changed
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path's parent doesn't exist
+    /// * PathError::IsNotFile(PathBuf) when the given path exists but is not a file
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let file = vfs.root().mash("file");
+    /// assert_eq!(vfs.ensure_file(&file, "foo", 0o600).unwrap(), true);
+    /// assert_eq!(vfs.ensure_file(&file, "foo", 0o600).unwrap(), false);
+    /// assert_vfs_read_all!(vfs, &file, "foo");
+    /// assert_eq!(vfs.mode(&file).unwrap(), 0o100600);
+    /// ```
+    fn ensure_file<T: AsRef<Path>, U: AsRef<[u8]>>(&self, path: T, data: U, mode: u32) -> RvResult<bool> {
+        match self {
+            Vfs::Stdfs(x) => x.ensure_file(path, data, mode),
+            Vfs::Memfs(x) => x.ensure_file(path, data, mode),
+            Vfs::Overlayfs(x) => x.ensure_file(path, data, mode),
+            Vfs::Readonly(x) => rejected(x, VfsOp::Write, path),
+        }
+    }
+
+    /// Ensures the given link exists and points to the given target
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Creates the link if it doesn't exist
+    /// * Replaces the link if it points somewhere else, atomically for Stdfs
+    /// * Dangling links are supported
+    /// * Returns true if anything was changed
+    ///
+    /// ### Errors
+    /// * PathError::IsNotSymlink(PathBuf) when the given link path exists but is not a link
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let link = vfs.root().mash("link");
+    /// assert_eq!(vfs.ensure_symlink(&link, "file1").unwrap(), true);
+    /// assert_eq!(vfs.ensure_symlink(&link, "file1").unwrap(), false);
+    /// assert_eq!(vfs.ensure_symlink(&link, "file2").unwrap(), true);
+    /// assert_eq!(vfs.readlink(&link).unwrap(), PathBuf::from("file2"));
+    /// ```
+    fn ensure_symlink<T: AsRef<Path>, U: AsRef<Path>>(&self, link: T, target: U) -> RvResult<bool> {
+        match self {
+            Vfs::Stdfs(x) => x.ensure_symlink(link, target),
+            Vfs::Memfs(x) => x.ensure_symlink(link, target),
+            Vfs::Overlayfs(x) => x.ensure_symlink(link, target),
+            Vfs::Readonly(x) => rejected(x, VfsOp::Create, link),
+        }
+    }
 
-impl VirtualFileSystem for Vfs {
-    /// Return the path in an absolute clean form
+    /// Returns true if the `path` exists
     ///
-    /// * Environment variable expansion
-    /// * Relative path resolution for `.` and `..`
-    /// * No IO resolution so it will work even with paths that don't exist
+    /// * Handles path expansion and absolute path resolution
     ///
-    /// ### Errors
-    /// * PathError::ParentNotFound(PathBuf) when parent is not found
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let dir = vfs.root().mash("foo");
+    /// assert_eq!(vfs.exists(&dir), false);
+    /// assert_vfs_mkdir_p!(vfs, &dir);
+    /// assert_eq!(vfs.exists(&dir), true);
+    /// ```
+    fn exists<T: AsRef<Path>>(&self, path: T) -> bool {
+        match self {
+            Vfs::Stdfs(x) => x.exists(path),
+            Vfs::Memfs(x) => x.exists(path),
+            Vfs::Overlayfs(x) => x.exists(path),
+            Vfs::Readonly(x) => x.exists(path),
+        }
+    }
+
+    /// Returns whether each of the given `paths` exists in the same order
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Checks all paths in a single pass e.g. Memfs only acquires its lock once
     ///
     /// ### Examples
     /// ```
     /// use rivia::prelude::*;
     ///
     /// let vfs = Vfs::memfs();
-    /// let home = sys::home_dir().unwrap();
-    /// assert_eq!(vfs.abs("~").unwrap(), PathBuf::from(&home));
+    /// let dir = vfs.root().mash("dir");
+    /// assert_vfs_mkdir_p!(vfs, &dir);
+    /// assert_eq!(vfs.exists_all(&[vfs.root().mash("foo"), dir]), vec![false, true]);
     /// ```
-    fn abs<T: AsRef<Path>>(&self, path: T) -> RvResult<PathBuf> {
+    fn exists_all<T: AsRef<Path>>(&self, paths: &[T]) -> Vec<bool> {
         match self {
-            Vfs::Stdfs(x) => x.abs(path),
-            Vfs::Memfs(x) => x.abs(path),
+            Vfs::Stdfs(x) => x.exists_all(paths),
+            Vfs::Memfs(x) => x.exists_all(paths),
+            Vfs::Overlayfs(x) => x.exists_all(paths),
+            Vfs::Readonly(x) => x.exists_all(paths),
         }
     }
 
-    /// Returns all dirs for the given path recursively
+    /// Returns all files for the given path, sorted by name
     ///
-    /// * Results are sorted by filename, are distict and don't include the given path
     /// * Handles path expansion and absolute path resolution
-    /// * Paths are returned in absolute form
+    /// * Paths are returned as abs paths
+    /// * Doesn't include the path itself only its children nor is this recursive
     ///
     /// ### Examples
     /// ```
@@ -1113,82 +3294,409 @@ impl VirtualFileSystem for Vfs {
     /// let vfs = Vfs::memfs();
     /// let tmpdir = vfs.root().mash("tmpdir");
     /// let dir1 = tmpdir.mash("dir1");
-    /// let dir2 = dir1.mash("dir2");
-    /// assert_vfs_mkdir_p!(vfs, &dir2);
-    /// assert_iter_eq(vfs.all_dirs(&tmpdir).unwrap(), vec![dir1, dir2]);
+    /// let file1 = tmpdir.mash("file1");
+    /// let file2 = tmpdir.mash("file2");
+    /// assert_vfs_mkdir_p!(vfs, &dir1);
+    /// assert_vfs_mkfile!(vfs, &file1);
+    /// assert_vfs_mkfile!(vfs, &file2);
+    /// assert_iter_eq(vfs.files(&tmpdir).unwrap(), vec![file1, file2]);
+    /// ```
+    fn files<T: AsRef<Path>>(&self, path: T) -> RvResult<Vec<PathBuf>> {
+        match self {
+            Vfs::Stdfs(x) => x.files(path),
+            Vfs::Memfs(x) => x.files(path),
+            Vfs::Overlayfs(x) => x.files(path),
+            Vfs::Readonly(x) => x.files(path),
+        }
+    }
+
+    /// Returns the absolute path of the first of the given `paths` that exists
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Checks all paths in a single pass stopping at the first that exists
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let dir = vfs.root().mash("dir");
+    /// assert_vfs_mkdir_p!(vfs, &dir);
+    /// assert_eq!(vfs.first_existing(&[vfs.root().mash("foo"), dir.clone()]), Some(dir));
+    /// assert_eq!(vfs.first_existing(&["foo"]), None);
+    /// ```
+    fn first_existing<T: AsRef<Path>>(&self, paths: &[T]) -> Option<PathBuf> {
+        match self {
+            Vfs::Stdfs(x) => x.first_existing(paths),
+            Vfs::Memfs(x) => x.first_existing(paths),
+            Vfs::Overlayfs(x) => x.first_existing(paths),
+            Vfs::Readonly(x) => x.first_existing(paths),
+        }
+    }
+
+    /// Returns the POSIX access control list of the given path
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Links are followed
+    /// * Paths without an ACL of their own return the minimal ACL matching their mode
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let file = vfs.root().mash("file");
+    /// assert!(vfs.mkfile_m(&file, 0o640).is_ok());
+    /// assert_eq!(vfs.get_acl(&file).unwrap(), Acl::from_mode(0o640));
+    /// ```
+    fn get_acl<T: AsRef<Path>>(&self, path: T) -> RvResult<Acl> {
+        match self {
+            Vfs::Stdfs(x) => x.get_acl(path),
+            Vfs::Memfs(x) => x.get_acl(path),
+            Vfs::Overlayfs(x) => x.get_acl(path),
+            Vfs::Readonly(x) => x.get_acl(path),
+        }
+    }
+
+    /// Returns the SELinux security context of the given path
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Links aren't followed so that the context of the link itself is returned
+    /// * Returns None when the path isn't labeled e.g. SELinux isn't supported by the filesystem
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let file = vfs.root().mash("file");
+    /// assert_vfs_mkfile!(vfs, &file);
+    /// assert_eq!(vfs.get_selinux_context(&file).unwrap(), None);
+    /// ```
+    #[cfg(feature = "selinux")]
+    fn get_selinux_context<T: AsRef<Path>>(&self, path: T) -> RvResult<Option<String>> {
+        match self {
+            Vfs::Stdfs(x) => x.get_selinux_context(path),
+            Vfs::Memfs(x) => x.get_selinux_context(path),
+            Vfs::Overlayfs(x) => x.get_selinux_context(path),
+            Vfs::Readonly(x) => x.get_selinux_context(path),
+        }
+    }
+
+    /// Returns the group ID of the owner of this file
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// assert_eq!(vfs.gid(vfs.root()).unwrap(), 1000);
+    /// ```
+    fn gid<T: AsRef<Path>>(&self, path: T) -> RvResult<u32> {
+        match self {
+            Vfs::Stdfs(x) => x.gid(path),
+            Vfs::Memfs(x) => x.gid(path),
+            Vfs::Overlayfs(x) => x.gid(path),
+            Vfs::Readonly(x) => x.gid(path),
+        }
+    }
+
+    /// Returns the paths matching the given glob pattern sorted by path
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * `*` matches any sequence of characters within a single path component
+    /// * `**` matches any sequence of characters across path components
+    /// * `?` matches any single character and `[abc]`, `[a-z]` or `[!a-z]` match a character class
+    /// * Paths are returned in absolute form and an empty list is returned when nothing matches
+    /// * Links aren't followed and directories that can't be read are skipped
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let dir = vfs.root().mash("dir");
+    /// let file1 = dir.mash("file1.conf");
+    /// let file2 = dir.mash("sub/file2.conf");
+    /// assert_vfs_mkdir_p!(vfs, dir.mash("sub"));
+    /// assert_vfs_mkfile!(vfs, &file1);
+    /// assert_vfs_mkfile!(vfs, &file2);
+    /// assert_eq!(vfs.glob(dir.mash("*.conf").to_string().unwrap()).unwrap(), vec![file1.clone()]);
+    /// assert_eq!(vfs.glob(dir.mash("**/*.conf").to_string().unwrap()).unwrap(), vec![file1, file2]);
+    /// ```
+    fn glob<T: AsRef<str>>(&self, pattern: T) -> RvResult<Vec<PathBuf>> {
+        match self {
+            Vfs::Stdfs(x) => x.glob(pattern),
+            Vfs::Memfs(x) => x.glob(pattern),
+            Vfs::Overlayfs(x) => x.glob(pattern),
+            Vfs::Readonly(x) => x.glob(pattern),
+        }
+    }
+
+    /// Creates a new hard link `dst` giving the data of the `src` file another name
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Links aren't followed i.e. a link to a symlink is a second name for the symlink itself
+    /// * Writes through either name are visible through the other and the data is only dropped once
+    ///   all of its names have been removed
+    /// * Memfs keeps the mode, owner and times of each name separately
+    /// * Returns the link path
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when `src` or the parent of `dst` doesn't exist
+    /// * PathError::IsNotFile(PathBuf) when `src` is a directory
+    /// * PathError::ExistsAlready(PathBuf) when `dst` already exists
+    ///
+    /// ### Arguments
+    /// * `src` - the existing file to link to
+    /// * `dst` - the path of the link being created
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// assert_vfs_write_all!(vfs, "file", "foobar");
+    /// assert_eq!(vfs.hardlink("file", "link").unwrap(), vfs.root().mash("link"));
+    /// assert_vfs_read_all!(vfs, "link", "foobar");
+    /// assert_eq!(vfs.nlink("file").unwrap(), 2);
+    /// ```
+    fn hardlink<T: AsRef<Path>, U: AsRef<Path>>(&self, src: T, dst: U) -> RvResult<PathBuf> {
+        match self {
+            Vfs::Stdfs(x) => x.hardlink(src, dst),
+            Vfs::Memfs(x) => x.hardlink(src, dst),
+            Vfs::Overlayfs(x) => x.hardlink(src, dst),
+            Vfs::Readonly(x) => rejected(x, VfsOp::Create, dst),
+        }
+    }
+
+    /// Register a hook to be called after every successful mutating operation
+    ///
+    /// * Hooks are given the operation's [`VfsOp`] classification and the absolute path
+    /// * Hooks are called in the order they were registered
+    /// * Useful for logging or tracing the filesystem effects of a function under test
+    ///
+    /// ### Examples
+    /// ```
+    /// use std::sync::Mutex;
+    ///
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let ops = Arc::new(Mutex::new(vec![]));
+    /// let ops_clone = ops.clone();
+    /// vfs.hook_post(move |op, path| ops_clone.lock().unwrap().push((op, path.to_path_buf())));
+    /// let file = vfs.root().mash("file");
+    /// assert_vfs_mkfile!(vfs, &file);
+    /// assert_eq!(*ops.lock().unwrap(), vec![(VfsOp::Create, file)]);
+    /// ```
+    fn hook_post<F: Fn(VfsOp, &Path) + Send + Sync + 'static>(&self, hook: F) {
+        match self {
+            Vfs::Stdfs(x) => x.hook_post(hook),
+            Vfs::Memfs(x) => x.hook_post(hook),
+            Vfs::Overlayfs(x) => x.hook_post(hook),
+            Vfs::Readonly(x) => x.hook_post(hook),
+        }
+    }
+
+    /// Register a hook to be called before every mutating operation with the ability to veto it
+    ///
+    /// * Hooks are given the operation's [`VfsOp`] classification and the absolute path
+    /// * Hooks are called in the order they were registered
+    /// * Returning an error from a hook vetoes the operation and is passed back to the caller
+    /// * Operations implicating multiple paths e.g. `move_p` call the hooks once for each path
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// vfs.hook_pre(|_, path| match path.starts_with("/etc") {
+    ///     true => Err(VfsError::Unavailable.into()),
+    ///     false => Ok(()),
+    /// });
+    /// assert!(vfs.mkdir_p("/etc").is_err());
+    /// assert_vfs_no_exists!(vfs, "/etc");
+    /// assert_vfs_mkdir_p!(vfs, "/tmp");
+    /// ```
+    fn hook_pre<F: Fn(VfsOp, &Path) -> RvResult<()> + Send + Sync + 'static>(&self, hook: F) {
+        match self {
+            Vfs::Stdfs(x) => x.hook_pre(hook),
+            Vfs::Memfs(x) => x.hook_pre(hook),
+            Vfs::Overlayfs(x) => x.hook_pre(hook),
+            Vfs::Readonly(x) => x.hook_pre(hook),
+        }
+    }
+
+    /// Register a hook to be called after every mutating operation slower than the given duration
+    ///
+    /// * Hooks are given the operation's [`VfsOp`] classification, the absolute path and the time
+    ///   the operation took e.g. to report operations stalling on a hung network filesystem
+    /// * Covers the same mutating operations as the pre and post hooks, reads and stats aren't timed
+    /// * Hooks are called once the operation returns, whether it succeeded or not, and can neither
+    ///   interrupt nor fail it so an operation that never returns is never reported
+    ///
+    /// ### Examples
+    /// ```
+    /// use std::{sync::Mutex, time::Duration};
+    ///
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let ops = Arc::new(Mutex::new(vec![]));
+    /// let ops_clone = ops.clone();
+    /// vfs.hook_slow(Duration::ZERO, move |op, path, _| ops_clone.lock().unwrap().push((op, path.to_path_buf())));
+    /// let file = vfs.root().mash("file");
+    /// assert_vfs_mkfile!(vfs, &file);
+    /// assert_eq!(*ops.lock().unwrap(), vec![(VfsOp::Create, file)]);
+    /// ```
+    fn hook_slow<F: Fn(VfsOp, &Path, Duration) + Send + Sync + 'static>(&self, after: Duration, hook: F) {
+        match self {
+            Vfs::Stdfs(x) => x.hook_slow(after, hook),
+            Vfs::Memfs(x) => x.hook_slow(after, hook),
+            Vfs::Overlayfs(x) => x.hook_slow(after, hook),
+            Vfs::Readonly(x) => x.hook_slow(after, hook),
+        }
+    }
+
+    /// Creates a new [`Installer`] for use with the builder pattern
+    ///
+    /// * `dst` will be installed into if it is an existing directory
+    /// * `dst` will be the installed file if it isn't an existing directory
+    /// * Handles path expansion and absolute path resolution
+    /// * Options for mode, ownership, backups and stripping leading source directories
+    /// * Execute by calling `exec`
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let file1 = vfs.root().mash("file1");
+    /// let file2 = vfs.root().mash("dir/file2");
+    /// assert_vfs_write_all!(vfs, &file1, "this is a test");
+    /// assert!(vfs.install(&file1, &file2).unwrap().mode(0o700).exec().is_ok());
+    /// assert_vfs_read_all!(vfs, &file2, "this is a test");
+    /// assert_eq!(vfs.mode(&file2).unwrap(), 0o100700);
+    /// ```
+    fn install<T: AsRef<Path>, U: AsRef<Path>>(&self, src: T, dst: U) -> RvResult<Installer> {
+        match self {
+            Vfs::Stdfs(x) => x.install(src, dst),
+            Vfs::Memfs(x) => x.install(src, dst),
+            Vfs::Overlayfs(x) => x.install(src, dst),
+            Vfs::Readonly(x) => rejected(x, VfsOp::Write, dst),
+        }
+    }
+
+    /// Returns true if the given path exists and is readonly
+    ///
+    /// * Handles path expansion and absolute path resolution
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let file = vfs.root().mash("file");
+    /// assert!(vfs.mkfile_m(&file, 0o644).is_ok());
+    /// assert_eq!(vfs.is_exec(&file), false);
+    /// assert!(vfs.chmod(&file, 0o777).is_ok());
+    /// assert_eq!(vfs.is_exec(&file), true);
+    /// ```
+    fn is_exec<T: AsRef<Path>>(&self, path: T) -> bool {
+        match self {
+            Vfs::Stdfs(x) => x.is_exec(path),
+            Vfs::Memfs(x) => x.is_exec(path),
+            Vfs::Overlayfs(x) => x.is_exec(path),
+            Vfs::Readonly(x) => x.is_exec(path),
+        }
+    }
+
+    /// Returns true if the given path exists and is a directory
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Link exclusion i.e. links even if pointing to a directory return false
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let dir = vfs.root().mash("dir");
+    /// assert_eq!(vfs.is_dir(&dir), false);
+    /// assert_vfs_mkdir_p!(vfs, &dir);
+    /// assert_eq!(vfs.is_dir(&dir), true);
     /// ```
-    fn all_dirs<T: AsRef<Path>>(&self, path: T) -> RvResult<Vec<PathBuf>> {
+    fn is_dir<T: AsRef<Path>>(&self, path: T) -> bool {
         match self {
-            Vfs::Stdfs(x) => x.all_dirs(path),
-            Vfs::Memfs(x) => x.all_dirs(path),
+            Vfs::Stdfs(x) => x.is_dir(path),
+            Vfs::Memfs(x) => x.is_dir(path),
+            Vfs::Overlayfs(x) => x.is_dir(path),
+            Vfs::Readonly(x) => x.is_dir(path),
         }
     }
 
-    /// Returns all files for the given path recursively
+    /// Returns true if the given path exists and is a file
     ///
-    /// * Results are sorted by filename, are distict and don't include the given path
     /// * Handles path expansion and absolute path resolution
-    /// * Paths are returned in absolute form
+    /// * Link exclusion i.e. links even if pointing to a file return false
     ///
     /// ### Examples
     /// ```
     /// use rivia::prelude::*;
     ///
     /// let vfs = Vfs::memfs();
-    /// let tmpdir = vfs.root().mash("tmpdir");
-    /// let file1 = tmpdir.mash("file1");
-    /// let dir1 = tmpdir.mash("dir1");
-    /// let file2 = dir1.mash("file2");
-    /// assert_vfs_mkdir_p!(vfs, &dir1);
-    /// assert_vfs_mkfile!(vfs, &file1);
-    /// assert_vfs_mkfile!(vfs, &file2);
-    /// assert_iter_eq(vfs.all_files(&tmpdir).unwrap(), vec![file2, file1]);
+    /// let file = vfs.root().mash("file");
+    /// assert_eq!(vfs.is_file(&file), false);
+    /// assert_vfs_mkfile!(vfs, &file);
+    /// assert_eq!(vfs.is_file(&file), true);
     /// ```
-    fn all_files<T: AsRef<Path>>(&self, path: T) -> RvResult<Vec<PathBuf>> {
+    fn is_file<T: AsRef<Path>>(&self, path: T) -> bool {
         match self {
-            Vfs::Stdfs(x) => x.all_files(path),
-            Vfs::Memfs(x) => x.all_files(path),
+            Vfs::Stdfs(x) => x.is_file(path),
+            Vfs::Memfs(x) => x.is_file(path),
+            Vfs::Overlayfs(x) => x.is_file(path),
+            Vfs::Readonly(x) => x.is_file(path),
         }
     }
 
-    /// Returns all paths for the given path recursively
+    /// Returns true if the given path exists and is a hard link i.e. its data has more than one name
     ///
-    /// * Results are sorted by filename, are distict and don't include the given path
     /// * Handles path expansion and absolute path resolution
-    /// * Paths are returned in absolute form
+    /// * Links aren't followed
+    /// * As all names of the data are equal the original file is a hard link as well
+    /// * Directories always return false
     ///
     /// ### Examples
     /// ```
     /// use rivia::prelude::*;
     ///
     /// let vfs = Vfs::memfs();
-    /// let tmpdir = vfs.root().mash("tmpdir");
-    /// let dir1 = tmpdir.mash("dir1");
-    /// let file1 = tmpdir.mash("file1");
-    /// let file2 = dir1.mash("file2");
-    /// let file3 = dir1.mash("file3");
-    /// assert_vfs_mkdir_p!(vfs, &dir1);
-    /// assert_vfs_mkfile!(vfs, &file1);
-    /// assert_vfs_mkfile!(vfs, &file2);
-    /// assert_vfs_mkfile!(vfs, &file3);
-    /// assert_iter_eq(vfs.all_paths(&tmpdir).unwrap(), vec![dir1, file2, file3, file1]);
+    /// assert_vfs_mkfile!(vfs, "file");
+    /// assert_eq!(vfs.is_hardlink("file"), false);
+    /// assert_vfs_hardlink!(vfs, "file", "link");
+    /// assert_eq!(vfs.is_hardlink("file"), true);
+    /// assert_eq!(vfs.is_hardlink("link"), true);
     /// ```
-    fn all_paths<T: AsRef<Path>>(&self, path: T) -> RvResult<Vec<PathBuf>> {
+    fn is_hardlink<T: AsRef<Path>>(&self, path: T) -> bool {
         match self {
-            Vfs::Stdfs(x) => x.all_paths(path),
-            Vfs::Memfs(x) => x.all_paths(path),
+            Vfs::Stdfs(x) => x.is_hardlink(path),
+            Vfs::Memfs(x) => x.is_hardlink(path),
+            Vfs::Overlayfs(x) => x.is_hardlink(path),
+            Vfs::Readonly(x) => x.is_hardlink(path),
         }
     }
 
-    /// Opens a file in append mode
+    /// Returns true if the given path exists and is immutable i.e. `chattr +i`
     ///
     /// * Handles path expansion and absolute path resolution
-    /// * Creates a file if it does not exist or appends to it if it does
-    ///
-    /// ### Errors
-    /// * PathError::IsNotDir(PathBuf) when the given path's parent exists but is not a directory
-    /// * PathError::DoesNotExist(PathBuf) when the given path's parent doesn't exist
-    /// * PathError::IsNotFile(PathBuf) when the given path exists but is not a file
+    /// * Links are followed
     ///
     /// ### Examples
     /// ```
@@ -1196,30 +3704,23 @@ impl VirtualFileSystem for Vfs {
     ///
     /// let vfs = Vfs::memfs();
     /// let file = vfs.root().mash("file");
-    /// let mut f = vfs.write(&file).unwrap();
-    /// f.write_all(b"foobar").unwrap();
-    /// f.flush().unwrap();
-    /// let mut f = vfs.append(&file).unwrap();
-    /// f.write_all(b"123").unwrap();
-    /// f.flush().unwrap();
-    /// assert_vfs_read_all!(vfs, &file, "foobar123");
+    /// assert_vfs_mkfile!(vfs, &file);
+    /// assert_eq!(vfs.is_immutable(&file), false);
+    /// assert!(vfs.set_immutable(&file, true).is_ok());
+    /// assert_eq!(vfs.is_immutable(&file), true);
     /// ```
-    fn append<T: AsRef<Path>>(&self, path: T) -> RvResult<Box<dyn Write>> {
+    fn is_immutable<T: AsRef<Path>>(&self, path: T) -> bool {
         match self {
-            Vfs::Stdfs(x) => x.append(path),
-            Vfs::Memfs(x) => x.append(path),
+            Vfs::Stdfs(x) => x.is_immutable(path),
+            Vfs::Memfs(x) => x.is_immutable(path),
+            Vfs::Overlayfs(x) => x.is_immutable(path),
+            Vfs::Readonly(x) => x.is_immutable(path),
         }
     }
 
-    /// Append the given data to to the target file
+    /// Returns true if the given path exists and is readonly
     ///
     /// * Handles path expansion and absolute path resolution
-    /// * Creates a file if it does not exist or appends to it if it does
-    ///
-    /// ### Errors
-    /// * PathError::IsNotDir(PathBuf) when the given path's parent exists but is not a directory
-    /// * PathError::DoesNotExist(PathBuf) when the given path's parent doesn't exist
-    /// * PathError::IsNotFile(PathBuf) when the given path exists but is not a file
     ///
     /// ### Examples
     /// ```
@@ -1227,28 +3728,24 @@ impl VirtualFileSystem for Vfs {
     ///
     /// let vfs = Vfs::memfs();
     /// let file = vfs.root().mash("file");
-    /// assert_vfs_no_file!(vfs, &file);
-    /// assert_vfs_write_all!(vfs, &file, "foobar 1");
-    /// assert!(vfs.append_all(&file, "foobar 2").is_ok());
-    /// assert_vfs_is_file!(vfs, &file);
-    /// assert_vfs_read_all!(vfs, &file, "foobar 1foobar 2");
+    /// assert!(vfs.mkfile_m(&file, 0o644).is_ok());
+    /// assert_eq!(vfs.is_readonly(&file), false);
+    /// assert!(vfs.chmod_b(&file).unwrap().readonly().exec().is_ok());
+    /// assert_eq!(vfs.mode(&file).unwrap(), 0o100444);
+    /// assert_eq!(vfs.is_readonly(&file), true);
     /// ```
-    fn append_all<T: AsRef<Path>, U: AsRef<[u8]>>(&self, path: T, data: U) -> RvResult<()> {
+    fn is_readonly<T: AsRef<Path>>(&self, path: T) -> bool {
         match self {
-            Vfs::Stdfs(x) => x.append_all(path, data),
-            Vfs::Memfs(x) => x.append_all(path, data),
+            Vfs::Stdfs(x) => x.is_readonly(path),
+            Vfs::Memfs(x) => x.is_readonly(path),
+            Vfs::Overlayfs(x) => x.is_readonly(path),
+            Vfs::Readonly(x) => x.is_readonly(path),
         }
     }
 
-    /// Append the given line to to the target file including a newline
+    /// Returns true if the given path exists and is a symlink
     ///
     /// * Handles path expansion and absolute path resolution
-    /// * Creates a file if it does not exist or appends to it if it does
-    ///
-    /// ### Errors
-    /// * PathError::IsNotDir(PathBuf) when the given path's parent exists but is not a directory
-    /// * PathError::DoesNotExist(PathBuf) when the given path's parent doesn't exist
-    /// * PathError::IsNotFile(PathBuf) when the given path exists but is not a file
     ///
     /// ### Examples
     /// ```
@@ -1256,81 +3753,87 @@ impl VirtualFileSystem for Vfs {
     ///
     /// let vfs = Vfs::memfs();
     /// let file = vfs.root().mash("file");
-    /// assert_vfs_no_file!(vfs, &file);
-    /// assert_vfs_write_all!(vfs, &file, "foobar 1");
-    /// assert!(vfs.append_line(&file, "foobar 2").is_ok());
-    /// assert_vfs_is_file!(vfs, &file);
-    /// assert_vfs_read_all!(vfs, &file, "foobar 1foobar 2\n");
+    /// let link = vfs.root().mash("link");
+    /// assert_vfs_no_symlink!(vfs, &link);
+    /// assert_vfs_symlink!(vfs, &link, &file);
+    /// assert_vfs_is_symlink!(vfs, &link);
     /// ```
-    fn append_line<T: AsRef<Path>, U: AsRef<str>>(&self, path: T, line: U) -> RvResult<()> {
+    fn is_symlink<T: AsRef<Path>>(&self, path: T) -> bool {
         match self {
-            Vfs::Stdfs(x) => x.append_line(path, line),
-            Vfs::Memfs(x) => x.append_line(path, line),
+            Vfs::Stdfs(x) => x.is_symlink(path),
+            Vfs::Memfs(x) => x.is_symlink(path),
+            Vfs::Overlayfs(x) => x.is_symlink(path),
+            Vfs::Readonly(x) => x.is_symlink(path),
         }
     }
 
-    /// Append the given lines to to the target file including newlines
+    /// Returns true if the given path exists and is a symlink pointing to a directory
     ///
     /// * Handles path expansion and absolute path resolution
-    /// * Creates a file if it does not exist or appends to it if it does
-    ///
-    /// ### Errors
-    /// * PathError::IsNotDir(PathBuf) when the given path's parent exists but is not a directory
-    /// * PathError::DoesNotExist(PathBuf) when the given path's parent doesn't exist
-    /// * PathError::IsNotFile(PathBuf) when the given path exists but is not a file
+    /// * Checks the path itself and what it points to
     ///
     /// ### Examples
     /// ```
     /// use rivia::prelude::*;
     ///
     /// let vfs = Vfs::memfs();
+    /// let dir = vfs.root().mash("dir");
     /// let file = vfs.root().mash("file");
-    /// assert_vfs_no_file!(vfs, &file);
-    /// assert!(vfs.append_lines(&file, &["1", "2"]).is_ok());
-    /// assert_vfs_is_file!(vfs, &file);
-    /// assert_vfs_read_all!(vfs, &file, "1\n2\n");
+    /// let link1 = vfs.root().mash("link1");
+    /// let link2 = vfs.root().mash("link2");
+    /// assert_vfs_mkdir_p!(vfs, &dir);
+    /// assert_vfs_mkfile!(vfs, &file);
+    /// assert_vfs_symlink!(vfs, &link1, &dir);
+    /// assert_vfs_symlink!(vfs, &link2, &file);
+    /// assert_eq!(vfs.is_symlink_dir(&link1), true);
+    /// assert_eq!(vfs.is_symlink_dir(&link2), false);
     /// ```
-    fn append_lines<T: AsRef<Path>, U: AsRef<str>>(&self, path: T, lines: &[U]) -> RvResult<()> {
+    fn is_symlink_dir<T: AsRef<Path>>(&self, path: T) -> bool {
         match self {
-            Vfs::Stdfs(x) => x.append_lines(path, lines),
-            Vfs::Memfs(x) => x.append_lines(path, lines),
+            Vfs::Stdfs(x) => x.is_symlink_dir(path),
+            Vfs::Memfs(x) => x.is_symlink_dir(path),
+            Vfs::Overlayfs(x) => x.is_symlink_dir(path),
+            Vfs::Readonly(x) => x.is_symlink_dir(path),
         }
     }
 
-    /// Change all file/dir permissions recursivly to `mode`
+    /// Returns true if the given path exists and is a symlink pointing to a file
     ///
     /// * Handles path expansion and absolute path resolution
-    /// * Doesn't follow links by default, use the builder `chomd_b` for this option
-    ///
-    /// ### Errors
-    /// * PathError::Empty when the given path is empty
-    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    /// * Checks the path itself and what it points to
     ///
     /// ### Examples
     /// ```
     /// use rivia::prelude::*;
     ///
     /// let vfs = Vfs::memfs();
+    /// let dir = vfs.root().mash("dir");
     /// let file = vfs.root().mash("file");
+    /// let link1 = vfs.root().mash("link1");
+    /// let link2 = vfs.root().mash("link2");
+    /// assert_vfs_mkdir_p!(vfs, &dir);
     /// assert_vfs_mkfile!(vfs, &file);
-    /// assert_eq!(vfs.mode(&file).unwrap(), 0o100644);
-    /// assert!(vfs.chmod(&file, 0o555).is_ok());
-    /// assert_eq!(vfs.mode(&file).unwrap(), 0o100555);
+    /// assert_vfs_symlink!(vfs, &link1, &dir);
+    /// assert_vfs_symlink!(vfs, &link2, &file);
+    /// assert_eq!(vfs.is_symlink_file(&link1), false);
+    /// assert_eq!(vfs.is_symlink_file(&link2), true);
     /// ```
-    fn chmod<T: AsRef<Path>>(&self, path: T, mode: u32) -> RvResult<()> {
+    fn is_symlink_file<T: AsRef<Path>>(&self, path: T) -> bool {
         match self {
-            Vfs::Stdfs(x) => x.chmod(path, mode),
-            Vfs::Memfs(x) => x.chmod(path, mode),
+            Vfs::Stdfs(x) => x.is_symlink_file(path),
+            Vfs::Memfs(x) => x.is_symlink_file(path),
+            Vfs::Overlayfs(x) => x.is_symlink_file(path),
+            Vfs::Readonly(x) => x.is_symlink_file(path),
         }
     }
 
-    /// Returns a new [`Chmod`] builder for advanced chmod options
+    /// Returns the number of lines in the given file without reading it all into memory
     ///
     /// * Handles path expansion and absolute path resolution
-    /// * Provides options for recursion, following links, narrowing in on file types etc...
+    /// * Counts a final line without a trailing newline i.e. matches the length of `read_lines`
     ///
     /// ### Errors
-    /// * PathError::Empty when the given path is empty
+    /// * PathError::IsNotFile(PathBuf) when the given path isn't a file
     /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
     ///
     /// ### Examples
@@ -1338,308 +3841,353 @@ impl VirtualFileSystem for Vfs {
     /// use rivia::prelude::*;
     ///
     /// let vfs = Vfs::memfs();
-    /// let dir = vfs.root().mash("dir");
-    /// let file = dir.mash("file");
-    /// assert_vfs_mkdir_p!(vfs, &dir);
-    /// assert_vfs_mkfile!(vfs, &file);
-    /// assert_eq!(vfs.mode(&dir).unwrap(), 0o40755);
-    /// assert_eq!(vfs.mode(&file).unwrap(), 0o100644);
-    /// assert!(vfs.chmod_b(&dir).unwrap().recurse().all(0o777).exec().is_ok());
-    /// assert_eq!(vfs.mode(&dir).unwrap(), 0o40777);
-    /// assert_eq!(vfs.mode(&file).unwrap(), 0o100777);
+    /// let file = vfs.root().mash("file");
+    /// assert_vfs_write_all!(vfs, &file, "1\n2");
+    /// assert_eq!(vfs.line_count(&file).unwrap(), 2);
     /// ```
-    fn chmod_b<T: AsRef<Path>>(&self, path: T) -> RvResult<Chmod> {
+    fn line_count<T: AsRef<Path>>(&self, path: T) -> RvResult<u64> {
         match self {
-            Vfs::Stdfs(x) => x.chmod_b(path),
-            Vfs::Memfs(x) => x.chmod_b(path),
+            Vfs::Stdfs(x) => x.line_count(path),
+            Vfs::Memfs(x) => x.line_count(path),
+            Vfs::Overlayfs(x) => x.line_count(path),
+            Vfs::Readonly(x) => x.line_count(path),
         }
     }
 
-    /// Change the ownership of the path recursivly
+    /// Returns the filesystem properties of the given path with a single lookup
     ///
     /// * Handles path expansion and absolute path resolution
-    /// * Use `chown_b` for more options
+    /// * Links report the properties of the target they point to except for `link`
+    /// * Memfs doesn't track access times and reports the modified time instead
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
     ///
     /// ### Examples
     /// ```
     /// use rivia::prelude::*;
     ///
     /// let vfs = Vfs::memfs();
-    /// let file1 = vfs.root().mash("file1");
-    /// assert_vfs_mkfile!(vfs, &file1);
-    /// assert!(vfs.chown(&file1, 5, 7).is_ok());
-    /// assert_eq!(vfs.owner(&file1).unwrap(), (5, 7));
+    /// assert_vfs_write_all!(vfs, "file", "foobar");
+    /// let meta = vfs.metadata("file").unwrap();
+    /// assert_eq!((meta.file, meta.size, meta.nlink), (true, 6, 1));
     /// ```
-    fn chown<T: AsRef<Path>>(&self, path: T, uid: u32, gid: u32) -> RvResult<()> {
+    fn metadata<T: AsRef<Path>>(&self, path: T) -> RvResult<VfsMetadata> {
         match self {
-            Vfs::Stdfs(x) => x.chown(path, uid, gid),
-            Vfs::Memfs(x) => x.chown(path, uid, gid),
+            Vfs::Stdfs(x) => x.metadata(path),
+            Vfs::Memfs(x) => x.metadata(path),
+            Vfs::Overlayfs(x) => x.metadata(path),
+            Vfs::Readonly(x) => x.metadata(path),
         }
     }
 
-    /// Creates new [`Chown`] for use with the builder pattern
-    ///
-    /// * Handles path expansion and absolute path resolution
-    /// * Provides options for recursion, following links, narrowing in on file types etc...
+    /// Creates the given directory and any parent directories needed with the given mode
     ///
     /// ### Examples
     /// ```
     /// use rivia::prelude::*;
     ///
     /// let vfs = Vfs::memfs();
-    /// let file1 = vfs.root().mash("file1");
-    /// assert_vfs_mkfile!(vfs, &file1);
-    /// assert!(vfs.chown_b(&file1).unwrap().owner(5, 7).exec().is_ok());
-    /// assert_eq!(vfs.owner(&file1).unwrap(), (5, 7));
+    /// let dir = vfs.root().mash("dir");
+    /// assert!(vfs.mkdir_m(&dir, 0o555).is_ok());
+    /// assert_eq!(vfs.mode(&dir).unwrap(), 0o40555);
     /// ```
-    fn chown_b<T: AsRef<Path>>(&self, path: T) -> RvResult<Chown> {
+    fn mkdir_m<T: AsRef<Path>>(&self, path: T, mode: u32) -> RvResult<PathBuf> {
         match self {
-            Vfs::Stdfs(x) => x.chown_b(path),
-            Vfs::Memfs(x) => x.chown_b(path),
+            Vfs::Stdfs(x) => x.mkdir_m(path, mode),
+            Vfs::Memfs(x) => x.mkdir_m(path, mode),
+            Vfs::Overlayfs(x) => x.mkdir_m(path, mode),
+            Vfs::Readonly(x) => rejected(x, VfsOp::Create, path),
         }
     }
 
-    /// Returns the highest priority active configuration directory.
+    /// Creates the given directory and any parent directories needed
     ///
-    /// * Searches first the $XDG_CONFIG_HOME directory, then the $XDG_CONFIG_DIRS directories.
-    /// * Returns the first directory that contains the given configuration file.
+    /// * Handles path expansion and absolute path resolution
+    ///
+    /// ### Errors
+    /// * PathError::IsNotDir(PathBuf) when the path already exists and is not a directory
     ///
     /// ### Examples
     /// ```
     /// use rivia::prelude::*;
     ///
-    /// let vfs = Vfs::memfs(); // replace this with Vfs::stdfs() for the real filesystem
-    /// let dir = PathBuf::from("/etc/xdg");
-    /// vfs.mkdir_p(&dir).unwrap();
-    /// let filepath = dir.mash("rivia.toml");
-    /// vfs.write_all(&filepath, "this is a test").unwrap();
-    /// assert_eq!(vfs.config_dir("rivia.toml").unwrap().to_str().unwrap(), "/etc/xdg");
-    ///
-    /// if let Some(config_dir) = vfs.config_dir("rivia.toml") {
-    ///    let path = config_dir.mash("rivia.toml");
-    ///    let config = vfs.read_all(&path).unwrap();
-    ///    assert_eq!(config, "this is a test");
-    /// }
+    /// let vfs = Vfs::memfs();
+    /// let dir = vfs.root().mash("dir");
+    /// assert_vfs_no_dir!(vfs, &dir);
+    /// assert_eq!(&vfs.mkdir_p(&dir).unwrap(), &dir);
+    /// assert_vfs_is_dir!(vfs, &dir);
     /// ```
-    fn config_dir<T: AsRef<str>>(&self, config: T) -> Option<PathBuf> {
+    fn mkdir_p<T: AsRef<Path>>(&self, path: T) -> RvResult<PathBuf> {
         match self {
-            Vfs::Stdfs(x) => x.config_dir(config),
-            Vfs::Memfs(x) => x.config_dir(config),
+            Vfs::Stdfs(x) => x.mkdir_p(path),
+            Vfs::Memfs(x) => x.mkdir_p(path),
+            Vfs::Overlayfs(x) => x.mkdir_p(path),
+            Vfs::Readonly(x) => rejected(x, VfsOp::Create, path),
         }
-    }
-
-    /// Copies src to dst recursively
-    ///
-    /// * `dst` will be copied into if it is an existing directory
-    /// * `dst` will be a copy of the src if it doesn't exist
-    /// * Creates destination directories as needed
-    /// * Handles environment variable expansion
-    /// * Handles relative path resolution for `.` and `..`
-    /// * Doesn't follow links
+    }
+
+    /// Wraps `mkdir_p` reporting whether the directory was created or already existed
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Saves converge style callers from pre-checking existence which doubles the work and races
+    ///
+    /// ### Errors
+    /// * PathError::IsNotDir(PathBuf) when the path already exists and is not a directory
     ///
     /// ### Examples
     /// ```
     /// use rivia::prelude::*;
     ///
     /// let vfs = Vfs::memfs();
-    /// let file1 = vfs.root().mash("file1");
-    /// let file2 = vfs.root().mash("file2");
-    /// assert_vfs_write_all!(vfs, &file1, "this is a test");
-    /// assert!(vfs.copy(&file1, &file2).is_ok());
-    /// assert_vfs_read_all!(vfs, &file2, "this is a test");
+    /// let dir = vfs.root().mash("dir");
+    /// assert_eq!(vfs.mkdir_p_ensured(&dir).unwrap(), Ensured::Created(dir.clone()));
+    /// assert_eq!(vfs.mkdir_p_ensured(&dir).unwrap(), Ensured::Existed(dir.clone()));
     /// ```
-    fn copy<T: AsRef<Path>, U: AsRef<Path>>(&self, src: T, dst: U) -> RvResult<()> {
+    fn mkdir_p_ensured<T: AsRef<Path>>(&self, path: T) -> RvResult<Ensured> {
         match self {
-            Vfs::Stdfs(x) => x.copy(src, dst),
-            Vfs::Memfs(x) => x.copy(src, dst),
+            Vfs::Stdfs(x) => x.mkdir_p_ensured(path),
+            Vfs::Memfs(x) => x.mkdir_p_ensured(path),
+            Vfs::Overlayfs(x) => x.mkdir_p_ensured(path),
+            Vfs::Readonly(x) => rejected(x, VfsOp::Create, path),
         }
     }
 
-    /// Creates a new [`Copier`] for use with the builder pattern
+    /// Create an empty file similar to the linux touch command
     ///
-    /// * `dst` will be copied into if it is an existing directory
-    /// * `dst` will be a copy of the src if it doesn't exist
-    /// * Handles environment variable expansion
-    /// * Handles relative path resolution for `.` and `..`
-    /// * Options for recursion, mode setting and following links
-    /// * Execute by calling `exec`
+    /// * Handles path expansion and absolute path resolution
+    /// * Default file creation permissions 0o666 with umask usually ends up being 0o644
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path's parent doesn't exist
+    /// * PathError::IsNotDir(PathBuf) when the given path's parent isn't a directory
+    /// * PathError::IsNotFile(PathBuf) when the given path exists but isn't a file
     ///
     /// ### Examples
     /// ```
     /// use rivia::prelude::*;
     ///
     /// let vfs = Vfs::memfs();
-    /// let file1 = vfs.root().mash("file1");
-    /// let file2 = vfs.root().mash("file2");
-    /// assert_vfs_write_all!(vfs, &file1, "this is a test");
-    /// assert!(vfs.copy_b(&file1, &file2).unwrap().exec().is_ok());
-    /// assert_vfs_read_all!(vfs, &file2, "this is a test");
+    /// let file = vfs.root().mash("file");
+    /// assert_vfs_no_file!(vfs, &file);
+    /// assert_eq!(&vfs.mkfile(&file).unwrap(), &file);
+    /// assert_vfs_is_file!(vfs, &file);
     /// ```
-    fn copy_b<T: AsRef<Path>, U: AsRef<Path>>(&self, src: T, dst: U) -> RvResult<Copier> {
+    fn mkfile<T: AsRef<Path>>(&self, path: T) -> RvResult<PathBuf> {
         match self {
-            Vfs::Stdfs(x) => x.copy_b(src, dst),
-            Vfs::Memfs(x) => x.copy_b(src, dst),
+            Vfs::Stdfs(x) => x.mkfile(path),
+            Vfs::Memfs(x) => x.mkfile(path),
+            Vfs::Overlayfs(x) => x.mkfile(path),
+            Vfs::Readonly(x) => rejected(x, VfsOp::Create, path),
         }
     }
 
-    /// Returns the current working directory
+    /// Wraps `mkfile` reporting whether the file was created or already existed
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Links to files are considered to be files
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path's parent doesn't exist
+    /// * PathError::IsNotDir(PathBuf) when the given path's parent isn't a directory
+    /// * PathError::IsNotFile(PathBuf) when the given path exists but isn't a file
     ///
     /// ### Examples
     /// ```
     /// use rivia::prelude::*;
     ///
     /// let vfs = Vfs::memfs();
-    /// let dir = vfs.root().mash("dir");
-    /// assert_eq!(vfs.cwd().unwrap(), vfs.root());
-    /// assert_eq!(&vfs.mkdir_p(&dir).unwrap(), &dir);
-    /// assert_eq!(&vfs.set_cwd(&dir).unwrap(), &dir);
-    /// assert_eq!(&vfs.cwd().unwrap(), &dir);
+    /// let file = vfs.root().mash("file");
+    /// assert_eq!(vfs.mkfile_ensured(&file).unwrap(), Ensured::Created(file.clone()));
+    /// assert_eq!(vfs.mkfile_ensured(&file).unwrap(), Ensured::Existed(file.clone()));
     /// ```
-    fn cwd(&self) -> RvResult<PathBuf> {
+    fn mkfile_ensured<T: AsRef<Path>>(&self, path: T) -> RvResult<Ensured> {
         match self {
-            Vfs::Stdfs(x) => x.cwd(),
-            Vfs::Memfs(x) => x.cwd(),
+            Vfs::Stdfs(x) => x.mkfile_ensured(path),
+            Vfs::Memfs(x) => x.mkfile_ensured(path),
+            Vfs::Overlayfs(x) => x.mkfile_ensured(path),
+            Vfs::Readonly(x) => rejected(x, VfsOp::Create, path),
         }
     }
 
-    /// Returns all directories for the given path, sorted by name
-    ///
-    /// * Handles path expansion and absolute path resolution
-    /// * Paths are returned as abs paths
-    /// * Doesn't include the path itself only its children nor is this recursive
+    /// Wraps `mkfile` allowing for setting the file's mode.
     ///
     /// ### Examples
     /// ```
     /// use rivia::prelude::*;
     ///
     /// let vfs = Vfs::memfs();
-    /// let tmpdir = vfs.root().mash("tmpdir");
-    /// let dir1 = tmpdir.mash("dir1");
-    /// let dir2 = tmpdir.mash("dir2");
-    /// let file1 = tmpdir.mash("file1");
-    /// assert_vfs_mkdir_p!(vfs, &dir1);
-    /// assert_vfs_mkdir_p!(vfs, &dir2);
-    /// assert_vfs_mkfile!(vfs, &file1);
-    /// assert_iter_eq(vfs.dirs(&tmpdir).unwrap(), vec![dir1, dir2]);
+    /// let file = vfs.root().mash("file");
+    /// assert!(vfs.mkfile_m(&file, 0o555).is_ok());
+    /// assert_eq!(vfs.mode(&file).unwrap(), 0o100555);
     /// ```
-    fn dirs<T: AsRef<Path>>(&self, path: T) -> RvResult<Vec<PathBuf>> {
+    fn mkfile_m<T: AsRef<Path>>(&self, path: T, mode: u32) -> RvResult<PathBuf> {
         match self {
-            Vfs::Stdfs(x) => x.dirs(path),
-            Vfs::Memfs(x) => x.dirs(path),
+            Vfs::Stdfs(x) => x.mkfile_m(path, mode),
+            Vfs::Memfs(x) => x.mkfile_m(path, mode),
+            Vfs::Overlayfs(x) => x.mkfile_m(path, mode),
+            Vfs::Readonly(x) => rejected(x, VfsOp::Create, path),
         }
     }
 
-    /// Returns an iterator over the given path
+    /// Returns the permissions for a file, directory or link
     ///
     /// * Handles path expansion and absolute path resolution
-    /// * Handles recursive path traversal
+    ///
+    /// ### Errors
+    /// * PathError::Empty when the given path is empty
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
     ///
     /// ### Examples
     /// ```
     /// use rivia::prelude::*;
     ///
     /// let vfs = Vfs::memfs();
-    /// let dir = vfs.root().mash("dir");
-    /// let file = dir.mash("file");
-    /// assert_vfs_mkdir_p!(vfs, &dir);
+    /// let file = vfs.root().mash("file");
     /// assert_vfs_mkfile!(vfs, &file);
-    /// let mut iter = vfs.entries(vfs.root()).unwrap().into_iter();
-    /// assert_iter_eq(iter.map(|x| x.unwrap().path_buf()), vec![vfs.root(), dir, file]);
+    /// assert_eq!(vfs.mode(&file).unwrap(), 0o100644);
+    /// assert!(vfs.chmod(&file, 0o555).is_ok());
+    /// assert_eq!(vfs.mode(&file).unwrap(), 0o100555);
     /// ```
-    fn entries<T: AsRef<Path>>(&self, path: T) -> RvResult<Entries> {
+    fn mode<T: AsRef<Path>>(&self, path: T) -> RvResult<u32> {
         match self {
-            Vfs::Stdfs(x) => x.entries(path),
-            Vfs::Memfs(x) => x.entries(path),
+            Vfs::Stdfs(x) => x.mode(path),
+            Vfs::Memfs(x) => x.mode(path),
+            Vfs::Overlayfs(x) => x.mode(path),
+            Vfs::Readonly(x) => x.mode(path),
         }
     }
 
-    /// Return a virtual filesystem entry for the given path
+    /// Creates a new [`Mover`] for use with the builder pattern
     ///
-    /// * Handles converting path to absolute form
+    /// * Handles path expansion and absolute path resolution
+    /// * Always moves `src` into `dst` if `dst` is an existing directory
+    /// * Replaces destination files if they exist
+    /// * Falls back on copy and remove when moving across filesystems
+    /// * Options for preserving attributes
+    /// * Execute by calling `exec`
     ///
     /// ### Examples
     /// ```
     /// use rivia::prelude::*;
     ///
     /// let vfs = Vfs::memfs();
-    /// let file = vfs.root().mash("file");
-    /// assert_vfs_mkfile!(vfs, &file);
-    /// assert!(vfs.entry(&file).unwrap().is_file());
+    /// let file1 = vfs.root().mash("file1");
+    /// let file2 = vfs.root().mash("file2");
+    /// assert_vfs_write_all!(vfs, &file1, "this is a test");
+    /// assert!(vfs.move_b(&file1, &file2).unwrap().exec().is_ok());
+    /// assert_vfs_no_exists!(vfs, &file1);
+    /// assert_vfs_read_all!(vfs, &file2, "this is a test");
     /// ```
-    fn entry<T: AsRef<Path>>(&self, path: T) -> RvResult<VfsEntry> {
+    fn move_b<T: AsRef<Path>, U: AsRef<Path>>(&self, src: T, dst: U) -> RvResult<Mover> {
         match self {
-            Vfs::Stdfs(x) => x.entry(path),
-            Vfs::Memfs(x) => x.entry(path),
+            Vfs::Stdfs(x) => x.move_b(src, dst),
+            Vfs::Memfs(x) => x.move_b(src, dst),
+            Vfs::Overlayfs(x) => x.move_b(src, dst),
+            Vfs::Readonly(x) => rejected(x, VfsOp::Rename, src),
         }
     }
 
-    /// Returns true if the `path` exists
+    /// Move a file or directory
     ///
     /// * Handles path expansion and absolute path resolution
+    /// * Always moves `src` into `dst` if `dst` is an existing directory
+    /// * Replaces destination files if they exist
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist when the source doesn't exist
     ///
     /// ### Examples
     /// ```
     /// use rivia::prelude::*;
     ///
     /// let vfs = Vfs::memfs();
-    /// let dir = vfs.root().mash("foo");
-    /// assert_eq!(vfs.exists(&dir), false);
+    /// let dir = vfs.root().mash("dir");
+    /// let file = vfs.root().mash("file");
+    /// let dirfile = dir.mash("file");
     /// assert_vfs_mkdir_p!(vfs, &dir);
-    /// assert_eq!(vfs.exists(&dir), true);
+    /// assert_vfs_mkfile!(vfs, &file);
+    /// assert!(vfs.move_p(&file, &dir).is_ok());
+    /// assert_vfs_no_file!(vfs, &file);
+    /// assert_vfs_is_file!(vfs, &dirfile);
     /// ```
-    fn exists<T: AsRef<Path>>(&self, path: T) -> bool {
+    fn move_p<T: AsRef<Path>, U: AsRef<Path>>(&self, src: T, dst: U) -> RvResult<()> {
         match self {
-            Vfs::Stdfs(x) => x.exists(path),
-            Vfs::Memfs(x) => x.exists(path),
+            Vfs::Stdfs(x) => x.move_p(src, dst),
+            Vfs::Memfs(x) => x.move_p(src, dst),
+            Vfs::Overlayfs(x) => x.move_p(src, dst),
+            Vfs::Readonly(x) => rejected(x, VfsOp::Rename, src),
         }
     }
 
-    /// Returns all files for the given path, sorted by name
+    /// Returns the number of hard links to the given path's data
     ///
     /// * Handles path expansion and absolute path resolution
-    /// * Paths are returned as abs paths
-    /// * Doesn't include the path itself only its children nor is this recursive
+    /// * Links aren't followed
+    /// * Directories report two plus the number of directories they contain
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
     ///
     /// ### Examples
     /// ```
     /// use rivia::prelude::*;
     ///
     /// let vfs = Vfs::memfs();
-    /// let tmpdir = vfs.root().mash("tmpdir");
-    /// let dir1 = tmpdir.mash("dir1");
-    /// let file1 = tmpdir.mash("file1");
-    /// let file2 = tmpdir.mash("file2");
-    /// assert_vfs_mkdir_p!(vfs, &dir1);
-    /// assert_vfs_mkfile!(vfs, &file1);
-    /// assert_vfs_mkfile!(vfs, &file2);
-    /// assert_iter_eq(vfs.files(&tmpdir).unwrap(), vec![file1, file2]);
+    /// assert_vfs_mkfile!(vfs, "file");
+    /// assert_eq!(vfs.nlink("file").unwrap(), 1);
+    /// assert_vfs_hardlink!(vfs, "file", "link");
+    /// assert_eq!(vfs.nlink("file").unwrap(), 2);
     /// ```
-    fn files<T: AsRef<Path>>(&self, path: T) -> RvResult<Vec<PathBuf>> {
+    fn nlink<T: AsRef<Path>>(&self, path: T) -> RvResult<u64> {
         match self {
-            Vfs::Stdfs(x) => x.files(path),
-            Vfs::Memfs(x) => x.files(path),
+            Vfs::Stdfs(x) => x.nlink(path),
+            Vfs::Memfs(x) => x.nlink(path),
+            Vfs::Overlayfs(x) => x.nlink(path),
+            Vfs::Readonly(x) => x.nlink(path),
         }
     }
 
-    /// Returns the group ID of the owner of this file
+    /// Opens the given directory returning a [`DirHandle`] for operating on its entries by name
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Stdfs holds the directory open and uses the `openat` family of syscalls to avoid races and
+    ///   repeated path resolution
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    /// * PathError::IsNotDir(PathBuf) when the given path is not a directory
     ///
     /// ### Examples
     /// ```
     /// use rivia::prelude::*;
     ///
     /// let vfs = Vfs::memfs();
-    /// assert_eq!(vfs.gid(vfs.root()).unwrap(), 1000);
+    /// let dir = vfs.root().mash("dir");
+    /// assert_vfs_mkdir_p!(vfs, &dir);
+    /// let handle = vfs.open_dir(&dir).unwrap();
+    /// assert_eq!(handle.mkfile("file").unwrap(), dir.mash("file"));
+    /// assert_vfs_is_file!(vfs, dir.mash("file"));
     /// ```
-    fn gid<T: AsRef<Path>>(&self, path: T) -> RvResult<u32> {
+    fn open_dir<T: AsRef<Path>>(&self, path: T) -> RvResult<DirHandle> {
         match self {
-            Vfs::Stdfs(x) => x.gid(path),
-            Vfs::Memfs(x) => x.gid(path),
+            Vfs::Stdfs(x) => x.open_dir(path),
+            Vfs::Memfs(x) => x.open_dir(path),
+            Vfs::Overlayfs(x) => x.open_dir(path),
+            Vfs::Readonly(x) => x.open_dir(path).map(DirHandle::readonly),
         }
     }
 
-    /// Returns true if the given path exists and is readonly
+    /// Opens a file in read-write mode for modifying regions of it in place
     ///
     /// * Handles path expansion and absolute path resolution
+    /// * Creates a file if it does not exist without truncating it if it does
+    /// * Writes overwrite the data at the current position extending the file as needed
+    ///
+    /// ### Errors
+    /// * PathError::IsNotDir(PathBuf) when the given path's parent exists but is not a directory
+    /// * PathError::DoesNotExist(PathBuf) when the given path's parent doesn't exist
+    /// * PathError::IsNotFile(PathBuf) when the given path exists but is not a file
     ///
     /// ### Examples
     /// ```
@@ -1647,164 +4195,188 @@ impl VirtualFileSystem for Vfs {
     ///
     /// let vfs = Vfs::memfs();
     /// let file = vfs.root().mash("file");
-    /// assert!(vfs.mkfile_m(&file, 0o644).is_ok());
-    /// assert_eq!(vfs.is_exec(&file), false);
-    /// assert!(vfs.chmod(&file, 0o777).is_ok());
-    /// assert_eq!(vfs.is_exec(&file), true);
+    /// assert_vfs_write_all!(vfs, &file, "foobar");
+    /// let mut f = vfs.open_rw(&file).unwrap();
+    /// f.seek(SeekFrom::Start(3)).unwrap();
+    /// f.write_all(b"BAR").unwrap();
+    /// f.flush().unwrap();
+    /// assert_vfs_read_all!(vfs, &file, "fooBAR");
     /// ```
-    fn is_exec<T: AsRef<Path>>(&self, path: T) -> bool {
+    fn open_rw<T: AsRef<Path>>(&self, path: T) -> RvResult<Box<dyn ReadWriteSeek>> {
         match self {
-            Vfs::Stdfs(x) => x.is_exec(path),
-            Vfs::Memfs(x) => x.is_exec(path),
+            Vfs::Stdfs(x) => x.open_rw(path),
+            Vfs::Memfs(x) => x.open_rw(path),
+            Vfs::Overlayfs(x) => x.open_rw(path),
+            Vfs::Readonly(x) => rejected(x, VfsOp::Write, path),
         }
     }
 
-    /// Returns true if the given path exists and is a directory
+    /// Returns the (user ID, group ID) of the owner of this file
     ///
     /// * Handles path expansion and absolute path resolution
-    /// * Link exclusion i.e. links even if pointing to a directory return false
     ///
     /// ### Examples
     /// ```
     /// use rivia::prelude::*;
     ///
     /// let vfs = Vfs::memfs();
-    /// let dir = vfs.root().mash("dir");
-    /// assert_eq!(vfs.is_dir(&dir), false);
-    /// assert_vfs_mkdir_p!(vfs, &dir);
-    /// assert_eq!(vfs.is_dir(&dir), true);
+    /// assert_eq!(vfs.owner(vfs.root()).unwrap(), (1000, 1000));
     /// ```
-    fn is_dir<T: AsRef<Path>>(&self, path: T) -> bool {
+    fn owner<T: AsRef<Path>>(&self, path: T) -> RvResult<(u32, u32)> {
         match self {
-            Vfs::Stdfs(x) => x.is_dir(path),
-            Vfs::Memfs(x) => x.is_dir(path),
+            Vfs::Stdfs(x) => x.owner(path),
+            Vfs::Memfs(x) => x.owner(path),
+            Vfs::Overlayfs(x) => x.owner(path),
+            Vfs::Readonly(x) => x.owner(path),
         }
     }
 
-    /// Returns true if the given path exists and is a file
+    /// Returns all paths for the given path, sorted by name
     ///
     /// * Handles path expansion and absolute path resolution
-    /// * Link exclusion i.e. links even if pointing to a file return false
+    /// * Paths are returned as abs paths
+    /// * Doesn't include the path itself only its children nor is this recursive
     ///
     /// ### Examples
     /// ```
     /// use rivia::prelude::*;
     ///
     /// let vfs = Vfs::memfs();
-    /// let file = vfs.root().mash("file");
-    /// assert_eq!(vfs.is_file(&file), false);
-    /// assert_vfs_mkfile!(vfs, &file);
-    /// assert_eq!(vfs.is_file(&file), true);
+    /// let tmpdir = vfs.root().mash("tmpdir");
+    /// let dir1 = tmpdir.mash("dir1");
+    /// let dir2 = tmpdir.mash("dir2");
+    /// let file1 = tmpdir.mash("file1");
+    /// assert_vfs_mkdir_p!(vfs, &dir1);
+    /// assert_vfs_mkdir_p!(vfs, &dir2);
+    /// assert_vfs_mkfile!(vfs, &file1);
+    /// assert_iter_eq(vfs.paths(&tmpdir).unwrap(), vec![dir1, dir2, file1]);
     /// ```
-    fn is_file<T: AsRef<Path>>(&self, path: T) -> bool {
+    fn paths<T: AsRef<Path>>(&self, path: T) -> RvResult<Vec<PathBuf>> {
         match self {
-            Vfs::Stdfs(x) => x.is_file(path),
-            Vfs::Memfs(x) => x.is_file(path),
+            Vfs::Stdfs(x) => x.paths(path),
+            Vfs::Memfs(x) => x.paths(path),
+            Vfs::Overlayfs(x) => x.paths(path),
+            Vfs::Readonly(x) => x.paths(path),
         }
     }
 
-    /// Returns true if the given path exists and is readonly
+    /// Open a file in readonly mode
     ///
+    /// * Provides a handle to a Read + Seek implementation
     /// * Handles path expansion and absolute path resolution
     ///
+    /// ### Errors
+    /// * PathError::IsNotFile(PathBuf) when the given path isn't a file
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
     /// ### Examples
     /// ```
     /// use rivia::prelude::*;
     ///
     /// let vfs = Vfs::memfs();
     /// let file = vfs.root().mash("file");
-    /// assert!(vfs.mkfile_m(&file, 0o644).is_ok());
-    /// assert_eq!(vfs.is_readonly(&file), false);
-    /// assert!(vfs.chmod_b(&file).unwrap().readonly().exec().is_ok());
-    /// assert_eq!(vfs.mode(&file).unwrap(), 0o100444);
-    /// assert_eq!(vfs.is_readonly(&file), true);
+    /// assert_vfs_write_all!(vfs, &file, b"foobar 1");
+    /// let mut file = vfs.read(&file).unwrap();
+    /// let mut buf = String::new();
+    /// file.read_to_string(&mut buf);
+    /// assert_eq!(buf, "foobar 1".to_string());
     /// ```
-    fn is_readonly<T: AsRef<Path>>(&self, path: T) -> bool {
+    fn read<T: AsRef<Path>>(&self, path: T) -> RvResult<Box<dyn ReadSeek>> {
         match self {
-            Vfs::Stdfs(x) => x.is_readonly(path),
-            Vfs::Memfs(x) => x.is_readonly(path),
+            Vfs::Stdfs(x) => x.read(path),
+            Vfs::Memfs(x) => x.read(path),
+            Vfs::Overlayfs(x) => x.read(path),
+            Vfs::Readonly(x) => x.read(path),
         }
     }
 
-    /// Returns true if the given path exists and is a symlink
+    /// Re/// Read all data from the given file and return it as a String
     ///
     /// * Handles path expansion and absolute path resolution
     ///
+    /// ### Errors
+    /// * PathError::IsNotFile(PathBuf) when the given path isn't a file
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
     /// ### Examples
     /// ```
     /// use rivia::prelude::*;
     ///
     /// let vfs = Vfs::memfs();
     /// let file = vfs.root().mash("file");
-    /// let link = vfs.root().mash("link");
-    /// assert_vfs_no_symlink!(vfs, &link);
-    /// assert_vfs_symlink!(vfs, &link, &file);
-    /// assert_vfs_is_symlink!(vfs, &link);
+    /// assert_vfs_write_all!(vfs, &file, b"foobar 1");
+    /// assert_vfs_read_all!(vfs, &file, "foobar 1");
     /// ```
-    fn is_symlink<T: AsRef<Path>>(&self, path: T) -> bool {
+    fn read_all<T: AsRef<Path>>(&self, path: T) -> RvResult<String> {
         match self {
-            Vfs::Stdfs(x) => x.is_symlink(path),
-            Vfs::Memfs(x) => x.is_symlink(path),
+            Vfs::Stdfs(x) => x.read_all(path),
+            Vfs::Memfs(x) => x.read_all(path),
+            Vfs::Overlayfs(x) => x.read_all(path),
+            Vfs::Readonly(x) => x.read_all(path),
         }
     }
 
-    /// Returns true if the given path exists and is a symlink pointing to a directory
+    /// Creates a new [`Reader`] for use with the builder pattern
     ///
+    /// * Provides a handle to a Read + Seek implementation
     /// * Handles path expansion and absolute path resolution
-    /// * Checks the path itself and what it points to
+    /// * Options for bypassing the page cache
+    /// * Execute by calling `exec`
+    ///
+    /// ### Errors
+    /// * PathError::IsNotFile(PathBuf) when the given path isn't a file
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
     ///
     /// ### Examples
     /// ```
     /// use rivia::prelude::*;
     ///
     /// let vfs = Vfs::memfs();
-    /// let dir = vfs.root().mash("dir");
     /// let file = vfs.root().mash("file");
-    /// let link1 = vfs.root().mash("link1");
-    /// let link2 = vfs.root().mash("link2");
-    /// assert_vfs_mkdir_p!(vfs, &dir);
-    /// assert_vfs_mkfile!(vfs, &file);
-    /// assert_vfs_symlink!(vfs, &link1, &dir);
-    /// assert_vfs_symlink!(vfs, &link2, &file);
-    /// assert_eq!(vfs.is_symlink_dir(&link1), true);
-    /// assert_eq!(vfs.is_symlink_dir(&link2), false);
+    /// assert_vfs_write_all!(vfs, &file, b"foobar 1");
+    /// let mut file = vfs.read_b(&file).unwrap().direct(true).exec().unwrap();
+    /// let mut buf = String::new();
+    /// file.read_to_string(&mut buf);
+    /// assert_eq!(buf, "foobar 1".to_string());
     /// ```
-    fn is_symlink_dir<T: AsRef<Path>>(&self, path: T) -> bool {
+    fn read_b<T: AsRef<Path>>(&self, path: T) -> RvResult<Reader> {
         match self {
-            Vfs::Stdfs(x) => x.is_symlink_dir(path),
-            Vfs::Memfs(x) => x.is_symlink_dir(path),
+            Vfs::Stdfs(x) => x.read_b(path),
+            Vfs::Memfs(x) => x.read_b(path),
+            Vfs::Overlayfs(x) => x.read_b(path),
+            Vfs::Readonly(x) => x.read_b(path),
         }
     }
 
-    /// Returns true if the given path exists and is a symlink pointing to a file
+    /// Read the given file and returns it as lines in a vector
     ///
     /// * Handles path expansion and absolute path resolution
-    /// * Checks the path itself and what it points to
+    ///
+    /// ### Errors
+    /// * PathError::IsNotFile(PathBuf) when the given path isn't a file
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
     ///
     /// ### Examples
     /// ```
     /// use rivia::prelude::*;
     ///
     /// let vfs = Vfs::memfs();
-    /// let dir = vfs.root().mash("dir");
     /// let file = vfs.root().mash("file");
-    /// let link1 = vfs.root().mash("link1");
-    /// let link2 = vfs.root().mash("link2");
-    /// assert_vfs_mkdir_p!(vfs, &dir);
-    /// assert_vfs_mkfile!(vfs, &file);
-    /// assert_vfs_symlink!(vfs, &link1, &dir);
-    /// assert_vfs_symlink!(vfs, &link2, &file);
-    /// assert_eq!(vfs.is_symlink_file(&link1), false);
-    /// assert_eq!(vfs.is_symlink_file(&link2), true);
+    /// assert_vfs_write_all!(vfs, &file, "1\n2");
+    /// assert_eq!(vfs.read_lines(&file).unwrap(), vec!["1".to_string(), "2".to_string()]);
     /// ```
-    fn is_symlink_file<T: AsRef<Path>>(&self, path: T) -> bool {
+    fn read_lines<T: AsRef<Path>>(&self, path: T) -> RvResult<Vec<String>> {
         match self {
-            Vfs::Stdfs(x) => x.is_symlink_file(path),
-            Vfs::Memfs(x) => x.is_symlink_file(path),
+            Vfs::Stdfs(x) => x.read_lines(path),
+            Vfs::Memfs(x) => x.read_lines(path),
+            Vfs::Overlayfs(x) => x.read_lines(path),
+            Vfs::Readonly(x) => x.read_lines(path),
         }
     }
 
-    /// Creates the given directory and any parent directories needed with the given mode
+    /// Returns the relative path of the target the link points to
+    ///
+    /// * Handles path expansion and absolute path resolution
     ///
     /// ### Examples
     /// ```
@@ -1812,68 +4384,86 @@ impl VirtualFileSystem for Vfs {
     ///
     /// let vfs = Vfs::memfs();
     /// let dir = vfs.root().mash("dir");
-    /// assert!(vfs.mkdir_m(&dir, 0o555).is_ok());
-    /// assert_eq!(vfs.mode(&dir).unwrap(), 0o40555);
+    /// let link = dir.mash("link");
+    /// let file = vfs.root().mash("file");
+    /// assert_vfs_mkdir_p!(vfs, &dir);
+    /// assert_vfs_mkfile!(vfs, &file);
+    /// assert_vfs_symlink!(vfs, &link, &file);
+    /// assert_vfs_readlink!(vfs, &link, PathBuf::from("..").mash("file"));
     /// ```
-    fn mkdir_m<T: AsRef<Path>>(&self, path: T, mode: u32) -> RvResult<PathBuf> {
+    fn readlink<T: AsRef<Path>>(&self, path: T) -> RvResult<PathBuf> {
         match self {
-            Vfs::Stdfs(x) => x.mkdir_m(path, mode),
-            Vfs::Memfs(x) => x.mkdir_m(path, mode),
+            Vfs::Stdfs(x) => x.readlink(path),
+            Vfs::Memfs(x) => x.readlink(path),
+            Vfs::Overlayfs(x) => x.readlink(path),
+            Vfs::Readonly(x) => x.readlink(path),
         }
     }
 
-    /// Creates the given directory and any parent directories needed
+    /// Returns the absolute path of the target the link points to
     ///
     /// * Handles path expansion and absolute path resolution
     ///
-    /// ### Errors
-    /// * PathError::IsNotDir(PathBuf) when the path already exists and is not a directory
-    ///
     /// ### Examples
     /// ```
     /// use rivia::prelude::*;
     ///
     /// let vfs = Vfs::memfs();
-    /// let dir = vfs.root().mash("dir");
-    /// assert_vfs_no_dir!(vfs, &dir);
-    /// assert_eq!(&vfs.mkdir_p(&dir).unwrap(), &dir);
-    /// assert_vfs_is_dir!(vfs, &dir);
+    /// let file = vfs.root().mash("file");
+    /// let link = vfs.root().mash("link");
+    /// assert_vfs_mkfile!(vfs, &file);
+    /// assert_vfs_symlink!(vfs, &link, &file);
+    /// assert_vfs_readlink_abs!(vfs, &link, &file);
     /// ```
-    fn mkdir_p<T: AsRef<Path>>(&self, path: T) -> RvResult<PathBuf> {
+    fn readlink_abs<T: AsRef<Path>>(&self, path: T) -> RvResult<PathBuf> {
         match self {
-            Vfs::Stdfs(x) => x.mkdir_p(path),
-            Vfs::Memfs(x) => x.mkdir_p(path),
+            Vfs::Stdfs(x) => x.readlink_abs(path),
+            Vfs::Memfs(x) => x.readlink_abs(path),
+            Vfs::Overlayfs(x) => x.readlink_abs(path),
+            Vfs::Readonly(x) => x.readlink_abs(path),
         }
     }
 
-    /// Create an empty file similar to the linux touch command
+    /// Rewrites the given link's target as the minimal relative path to the same target
     ///
     /// * Handles path expansion and absolute path resolution
-    /// * Default file creation permissions 0o666 with umask usually ends up being 0o644
+    /// * Absolute link targets are converted to their relative equivalent
+    /// * Links already in their minimal relative form are left untouched
     ///
     /// ### Errors
-    /// * PathError::DoesNotExist(PathBuf) when the given path's parent doesn't exist
-    /// * PathError::IsNotDir(PathBuf) when the given path's parent isn't a directory
-    /// * PathError::IsNotFile(PathBuf) when the given path exists but isn't a file
+    /// * PathError::DoesNotExist(PathBuf) when the given link doesn't exist
+    /// * PathError::IsNotSymlink(PathBuf) when the given path isn't a link
     ///
     /// ### Examples
     /// ```
     /// use rivia::prelude::*;
     ///
     /// let vfs = Vfs::memfs();
+    /// let dir = vfs.root().mash("dir");
     /// let file = vfs.root().mash("file");
-    /// assert_vfs_no_file!(vfs, &file);
-    /// assert_eq!(&vfs.mkfile(&file).unwrap(), &file);
-    /// assert_vfs_is_file!(vfs, &file);
+    /// let link = dir.mash("link");
+    /// assert_vfs_mkdir_p!(vfs, &dir);
+    /// assert_vfs_mkfile!(vfs, &file);
+    /// assert_vfs_symlink!(vfs, &link, &file);
+    /// assert_eq!(vfs.relink(&link).unwrap(), link);
+    /// assert_vfs_readlink!(vfs, &link, PathBuf::from("../file"));
     /// ```
-    fn mkfile<T: AsRef<Path>>(&self, path: T) -> RvResult<PathBuf> {
+    fn relink<T: AsRef<Path>>(&self, link: T) -> RvResult<PathBuf> {
         match self {
-            Vfs::Stdfs(x) => x.mkfile(path),
-            Vfs::Memfs(x) => x.mkfile(path),
+            Vfs::Stdfs(x) => x.relink(link),
+            Vfs::Memfs(x) => x.relink(link),
+            Vfs::Overlayfs(x) => x.relink(link),
+            Vfs::Readonly(x) => rejected(x, VfsOp::Create, link),
         }
     }
 
-    /// Wraps `mkfile` allowing for setting the file's mode.
+    /// Removes the given empty directory or file
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Link exclusion i.e. removes the link themselves not what its points to
+    ///
+    /// ### Errors
+    /// * a directory containing files will trigger an error. use `remove_all` instead
     ///
     /// ### Examples
     /// ```
@@ -1881,50 +4471,55 @@ impl VirtualFileSystem for Vfs {
     ///
     /// let vfs = Vfs::memfs();
     /// let file = vfs.root().mash("file");
-    /// assert!(vfs.mkfile_m(&file, 0o555).is_ok());
-    /// assert_eq!(vfs.mode(&file).unwrap(), 0o100555);
+    /// assert_vfs_mkfile!(vfs, &file);
+    /// assert_vfs_exists!(vfs, &file);
+    /// assert_vfs_remove!(vfs, &file);
+    /// assert_vfs_no_exists!(vfs, &file);
     /// ```
-    fn mkfile_m<T: AsRef<Path>>(&self, path: T, mode: u32) -> RvResult<PathBuf> {
+    fn remove<T: AsRef<Path>>(&self, path: T) -> RvResult<()> {
         match self {
-            Vfs::Stdfs(x) => x.mkfile_m(path, mode),
-            Vfs::Memfs(x) => x.mkfile_m(path, mode),
+            Vfs::Stdfs(x) => x.remove(path),
+            Vfs::Memfs(x) => x.remove(path),
+            Vfs::Overlayfs(x) => x.remove(path),
+            Vfs::Readonly(x) => rejected(x, VfsOp::Remove, path),
         }
     }
 
-    /// Returns the permissions for a file, directory or link
+    /// Removes the given directory after removing all of its contents
     ///
     /// * Handles path expansion and absolute path resolution
-    ///
-    /// ### Errors
-    /// * PathError::Empty when the given path is empty
-    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    /// * Link exclusion i.e. removes the link themselves not what its points to
+    /// * Refuses to remove the root directory, use `remove_all_b` for more guards
     ///
     /// ### Examples
     /// ```
     /// use rivia::prelude::*;
     ///
     /// let vfs = Vfs::memfs();
-    /// let file = vfs.root().mash("file");
+    /// let dir = vfs.root().mash("dir");
+    /// let file = dir.mash("file");
+    /// assert_vfs_mkdir_p!(vfs, &dir);
     /// assert_vfs_mkfile!(vfs, &file);
-    /// assert_eq!(vfs.mode(&file).unwrap(), 0o100644);
-    /// assert!(vfs.chmod(&file, 0o555).is_ok());
-    /// assert_eq!(vfs.mode(&file).unwrap(), 0o100555);
+    /// assert_vfs_is_file!(vfs, &file);
+    /// assert_vfs_remove_all!(vfs, &dir);
+    /// assert_vfs_no_exists!(vfs, &file);
+    /// assert_vfs_no_exists!(vfs, &dir);
     /// ```
-    fn mode<T: AsRef<Path>>(&self, path: T) -> RvResult<u32> {
+    fn remove_all<T: AsRef<Path>>(&self, path: T) -> RvResult<()> {
         match self {
-            Vfs::Stdfs(x) => x.mode(path),
-            Vfs::Memfs(x) => x.mode(path),
+            Vfs::Stdfs(x) => x.remove_all(path),
+            Vfs::Memfs(x) => x.remove_all(path),
+            Vfs::Overlayfs(x) => x.remove_all(path),
+            Vfs::Readonly(x) => rejected(x, VfsOp::Remove, path),
         }
     }
 
-    /// Move a file or directory
+    /// Creates a new [`Remover`] for use with the builder pattern
     ///
     /// * Handles path expansion and absolute path resolution
-    /// * Always moves `src` into `dst` if `dst` is an existing directory
-    /// * Replaces destination files if they exist
-    ///
-    /// ### Errors
-    /// * PathError::DoesNotExist when the source doesn't exist
+    /// * Link exclusion i.e. removes the link themselves not what its points to
+    /// * Refuses to remove the root directory
+    /// * Provides optional guards for the path being within a base and not crossing devices
     ///
     /// ### Examples
     /// ```
@@ -1932,101 +4527,111 @@ impl VirtualFileSystem for Vfs {
     ///
     /// let vfs = Vfs::memfs();
     /// let dir = vfs.root().mash("dir");
-    /// let file = vfs.root().mash("file");
-    /// let dirfile = dir.mash("file");
     /// assert_vfs_mkdir_p!(vfs, &dir);
-    /// assert_vfs_mkfile!(vfs, &file);
-    /// assert!(vfs.move_p(&file, &dir).is_ok());
-    /// assert_vfs_no_file!(vfs, &file);
-    /// assert_vfs_is_file!(vfs, &dirfile);
+    /// let err = vfs.remove_all_b(vfs.root()).unwrap().exec().unwrap_err();
+    /// assert_eq!(err.downcast_ref::<VfsError>(), Some(&VfsError::RemoveRoot));
+    /// assert!(vfs.remove_all_b(&dir).unwrap().within(vfs.root()).same_device().exec().is_ok());
+    /// assert_vfs_no_exists!(vfs, &dir);
     /// ```
-    fn move_p<T: AsRef<Path>, U: AsRef<Path>>(&self, src: T, dst: U) -> RvResult<()> {
+    fn remove_all_b<T: AsRef<Path>>(&self, path: T) -> RvResult<Remover> {
         match self {
-            Vfs::Stdfs(x) => x.move_p(src, dst),
-            Vfs::Memfs(x) => x.move_p(src, dst),
+            Vfs::Stdfs(x) => x.remove_all_b(path),
+            Vfs::Memfs(x) => x.remove_all_b(path),
+            Vfs::Overlayfs(x) => x.remove_all_b(path),
+            Vfs::Readonly(x) => rejected(x, VfsOp::Remove, path),
         }
     }
 
-    /// Returns the (user ID, group ID) of the owner of this file
+    /// Resolve the given link one hop at a time returning the final target and the chain traversed
     ///
     /// * Handles path expansion and absolute path resolution
+    /// * Follows each link one hop at a time recording the links traversed
+    /// * Works on dangling chains returning the last existing link and the missing remainder
+    /// * Non-links resolve to themselves with an empty chain
+    ///
+    /// ### Errors
+    /// * PathError::LinkLooping(PathBuf) when the chain loops back on itself
+    /// * PathError::TooManyLinks(PathBuf) when more than `max_hops` hops are required
     ///
     /// ### Examples
     /// ```
     /// use rivia::prelude::*;
     ///
     /// let vfs = Vfs::memfs();
-    /// assert_eq!(vfs.owner(vfs.root()).unwrap(), (1000, 1000));
+    /// let link = vfs.root().mash("link");
+    /// assert!(vfs.symlink(&link, "missing").is_ok());
+    /// let resolved = vfs.resolve_link(&link, 40).unwrap();
+    /// assert_eq!(resolved.chain, vec![link]);
+    /// assert_eq!(resolved.target, vfs.root().mash("missing"));
+    /// assert_eq!(resolved.exists, false);
     /// ```
-    fn owner<T: AsRef<Path>>(&self, path: T) -> RvResult<(u32, u32)> {
+    fn resolve_link<T: AsRef<Path>>(&self, path: T, max_hops: usize) -> RvResult<ResolvedLink> {
         match self {
-            Vfs::Stdfs(x) => x.owner(path),
-            Vfs::Memfs(x) => x.owner(path),
+            Vfs::Stdfs(x) => x.resolve_link(path, max_hops),
+            Vfs::Memfs(x) => x.resolve_link(path, max_hops),
+            Vfs::Overlayfs(x) => x.resolve_link(path, max_hops),
+            Vfs::Readonly(x) => x.resolve_link(path, max_hops),
         }
     }
 
-    /// Returns all paths for the given path, sorted by name
-    ///
-    /// * Handles path expansion and absolute path resolution
-    /// * Paths are returned as abs paths
-    /// * Doesn't include the path itself only its children nor is this recursive
+    /// Returns the current root directory
     ///
     /// ### Examples
     /// ```
     /// use rivia::prelude::*;
     ///
     /// let vfs = Vfs::memfs();
-    /// let tmpdir = vfs.root().mash("tmpdir");
-    /// let dir1 = tmpdir.mash("dir1");
-    /// let dir2 = tmpdir.mash("dir2");
-    /// let file1 = tmpdir.mash("file1");
-    /// assert_vfs_mkdir_p!(vfs, &dir1);
-    /// assert_vfs_mkdir_p!(vfs, &dir2);
-    /// assert_vfs_mkfile!(vfs, &file1);
-    /// assert_iter_eq(vfs.paths(&tmpdir).unwrap(), vec![dir1, dir2, file1]);
+    /// let mut root = PathBuf::new();
+    /// root.push(Component::RootDir);
+    /// assert_eq!(vfs.root(), root);
     /// ```
-    fn paths<T: AsRef<Path>>(&self, path: T) -> RvResult<Vec<PathBuf>> {
+    fn root(&self) -> PathBuf {
         match self {
-            Vfs::Stdfs(x) => x.paths(path),
-            Vfs::Memfs(x) => x.paths(path),
+            Vfs::Stdfs(x) => x.root(),
+            Vfs::Memfs(x) => x.root(),
+            Vfs::Overlayfs(x) => x.root(),
+            Vfs::Readonly(x) => x.root(),
         }
     }
 
-    /// Open a file in readonly mode
+    /// Returns true if the given paths refer to the same underlying file
     ///
-    /// * Provides a handle to a Read + Seek implementation
     /// * Handles path expansion and absolute path resolution
-    ///
-    /// ### Errors
-    /// * PathError::IsNotFile(PathBuf) when the given path isn't a file
-    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    /// * Follows links so that a link and its target are considered the same file
+    /// * Returns false if either path doesn't exist
     ///
     /// ### Examples
     /// ```
     /// use rivia::prelude::*;
     ///
     /// let vfs = Vfs::memfs();
-    /// let file = vfs.root().mash("file");
-    /// assert_vfs_write_all!(vfs, &file, b"foobar 1");
-    /// let mut file = vfs.read(&file).unwrap();
-    /// let mut buf = String::new();
-    /// file.read_to_string(&mut buf);
-    /// assert_eq!(buf, "foobar 1".to_string());
+    /// assert_vfs_mkfile!(vfs, "file1");
+    /// assert_vfs_mkfile!(vfs, "file2");
+    /// assert_vfs_symlink!(vfs, "link", "file1");
+    /// assert_eq!(vfs.same_file("link", "file1"), true);
+    /// assert_eq!(vfs.same_file("link", "file2"), false);
     /// ```
-    fn read<T: AsRef<Path>>(&self, path: T) -> RvResult<Box<dyn ReadSeek>> {
+    fn same_file<T: AsRef<Path>, U: AsRef<Path>>(&self, a: T, b: U) -> bool {
         match self {
-            Vfs::Stdfs(x) => x.read(path),
-            Vfs::Memfs(x) => x.read(path),
+            Vfs::Stdfs(x) => x.same_file(a, b),
+            Vfs::Memfs(x) => x.same_file(a, b),
+            Vfs::Overlayfs(x) => x.same_file(a, b),
+            Vfs::Readonly(x) => x.same_file(a, b),
         }
     }
 
-    /// Re/// Read all data from the given file and return it as a String
+    /// Set the POSIX access control list of the given path
     ///
     /// * Handles path expansion and absolute path resolution
+    /// * Links are followed
+    /// * The permission bits of the path's mode are updated to match the ACL
+    /// * A mask entry is added when named users or groups are given without one
+    /// * Default entries are only valid for directories, empty default entries remove them
     ///
     /// ### Errors
-    /// * PathError::IsNotFile(PathBuf) when the given path isn't a file
     /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    /// * PathError::IsNotDir(PathBuf) when default entries are given for a non directory
+    /// * VfsError::InvalidAcl(PathBuf) when the entries are malformed
     ///
     /// ### Examples
     /// ```
@@ -2034,22 +4639,26 @@ impl VirtualFileSystem for Vfs {
     ///
     /// let vfs = Vfs::memfs();
     /// let file = vfs.root().mash("file");
-    /// assert_vfs_write_all!(vfs, &file, b"foobar 1");
-    /// assert_vfs_read_all!(vfs, &file, "foobar 1");
+    /// assert!(vfs.mkfile_m(&file, 0o640).is_ok());
+    /// assert!(vfs.set_acl(&file, Acl::from_mode(0o640).group(7, 0o6)).is_ok());
+    /// assert_eq!(vfs.get_acl(&file).unwrap().perms(AclTag::Group(7)), Some(0o6));
+    /// assert_eq!(vfs.mode(&file).unwrap(), 0o100660);
     /// ```
-    fn read_all<T: AsRef<Path>>(&self, path: T) -> RvResult<String> {
+    fn set_acl<T: AsRef<Path>>(&self, path: T, acl: Acl) -> RvResult<()> {
         match self {
-            Vfs::Stdfs(x) => x.read_all(path),
-            Vfs::Memfs(x) => x.read_all(path),
+            Vfs::Stdfs(x) => x.set_acl(path, acl),
+            Vfs::Memfs(x) => x.set_acl(path, acl),
+            Vfs::Overlayfs(x) => x.set_acl(path, acl),
+            Vfs::Readonly(x) => rejected(x, VfsOp::Chmod, path),
         }
     }
 
-    /// Read the given file and returns it as lines in a vector
+    /// Set the current working directory
     ///
     /// * Handles path expansion and absolute path resolution
+    /// * Relative path will use the current working directory
     ///
     /// ### Errors
-    /// * PathError::IsNotFile(PathBuf) when the given path isn't a file
     /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
     ///
     /// ### Examples
@@ -2057,44 +4666,63 @@ impl VirtualFileSystem for Vfs {
     /// use rivia::prelude::*;
     ///
     /// let vfs = Vfs::memfs();
-    /// let file = vfs.root().mash("file");
-    /// assert_vfs_write_all!(vfs, &file, "1\n2");
-    /// assert_eq!(vfs.read_lines(&file).unwrap(), vec!["1".to_string(), "2".to_string()]);
+    /// let dir = vfs.root().mash("dir");
+    /// assert_eq!(vfs.cwd().unwrap(), vfs.root());
+    /// assert_vfs_mkdir_p!(vfs, &dir);
+    /// assert_eq!(vfs.set_cwd(&dir).unwrap(), dir.clone());
+    /// assert_eq!(vfs.cwd().unwrap(), dir);
     /// ```
-    fn read_lines<T: AsRef<Path>>(&self, path: T) -> RvResult<Vec<String>> {
+    fn set_cwd<T: AsRef<Path>>(&self, path: T) -> RvResult<PathBuf> {
         match self {
-            Vfs::Stdfs(x) => x.read_lines(path),
-            Vfs::Memfs(x) => x.read_lines(path),
+            Vfs::Stdfs(x) => x.set_cwd(path),
+            Vfs::Memfs(x) => x.set_cwd(path),
+            Vfs::Overlayfs(x) => x.set_cwd(path),
+            Vfs::Readonly(x) => x.set_cwd(path),
         }
     }
 
-    /// Returns the relative path of the target the link points to
+    /// Set the access and modification times of the given path with nanosecond precision
     ///
     /// * Handles path expansion and absolute path resolution
+    /// * Doesn't follow links i.e. sets the times of the link itself
+    /// * Memfs doesn't track access times so only the modification time is set
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
     ///
     /// ### Examples
     /// ```
     /// use rivia::prelude::*;
+    /// use std::time::{Duration, UNIX_EPOCH};
     ///
     /// let vfs = Vfs::memfs();
-    /// let dir = vfs.root().mash("dir");
-    /// let link = dir.mash("link");
     /// let file = vfs.root().mash("file");
-    /// assert_vfs_mkdir_p!(vfs, &dir);
-    /// assert_vfs_mkfile!(vfs, &file);
-    /// assert_vfs_symlink!(vfs, &link, &file);
-    /// assert_vfs_readlink!(vfs, &link, PathBuf::from("..").mash("file"));
+    /// assert_vfs_mkfile!(vfs, &file);
+    /// let time = UNIX_EPOCH + Duration::new(1_000_000_000, 123_456_789);
+    /// assert!(vfs.set_file_time(&file, time, time).is_ok());
+    /// assert_eq!(vfs.entry(&file).unwrap().modified(), time);
     /// ```
-    fn readlink<T: AsRef<Path>>(&self, path: T) -> RvResult<PathBuf> {
+    fn set_file_time<T: AsRef<Path>>(&self, path: T, atime: SystemTime, mtime: SystemTime) -> RvResult<()> {
         match self {
-            Vfs::Stdfs(x) => x.readlink(path),
-            Vfs::Memfs(x) => x.readlink(path),
+            Vfs::Stdfs(x) => x.set_file_time(path, atime, mtime),
+            Vfs::Memfs(x) => x.set_file_time(path, atime, mtime),
+            Vfs::Overlayfs(x) => x.set_file_time(path, atime, mtime),
+            Vfs::Readonly(x) => rejected(x, VfsOp::Write, path),
         }
     }
 
-    /// Returns the absolute path of the target the link points to
+    /// Set or clear the immutable flag of the given path i.e. `chattr +i` and `chattr -i`
     ///
     /// * Handles path expansion and absolute path resolution
+    /// * Links are followed
+    /// * Stdfs sets the inode flag which requires the `CAP_LINUX_IMMUTABLE` capability and a
+    ///   filesystem that supports it
+    /// * Immutable files can't be written, removed, renamed or linked to and immutable directories
+    ///   can't have entries added, removed or renamed until the flag is cleared. Memfs fails these
+    ///   with EPERM the same as the kernel.
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
     ///
     /// ### Examples
     /// ```
@@ -2102,92 +4730,115 @@ impl VirtualFileSystem for Vfs {
     ///
     /// let vfs = Vfs::memfs();
     /// let file = vfs.root().mash("file");
-    /// let link = vfs.root().mash("link");
     /// assert_vfs_mkfile!(vfs, &file);
-    /// assert_vfs_symlink!(vfs, &link, &file);
-    /// assert_vfs_readlink_abs!(vfs, &link, &file);
+    /// assert!(vfs.set_immutable(&file, true).is_ok());
+    /// assert_eq!(vfs.is_immutable(&file), true);
+    /// assert!(vfs.set_immutable(&file, false).is_ok());
+    /// assert_eq!(vfs.is_immutable(&file), false);
     /// ```
-    fn readlink_abs<T: AsRef<Path>>(&self, path: T) -> RvResult<PathBuf> {
+    fn set_immutable<T: AsRef<Path>>(&self, path: T, yes: bool) -> RvResult<()> {
         match self {
-            Vfs::Stdfs(x) => x.readlink_abs(path),
-            Vfs::Memfs(x) => x.readlink_abs(path),
+            Vfs::Stdfs(x) => x.set_immutable(path, yes),
+            Vfs::Memfs(x) => x.set_immutable(path, yes),
+            Vfs::Overlayfs(x) => x.set_immutable(path, yes),
+            Vfs::Readonly(x) => rejected(x, VfsOp::Chmod, path),
         }
     }
 
-    /// Removes the given empty directory or file
+    /// Set the modification time of the given path with nanosecond precision
     ///
     /// * Handles path expansion and absolute path resolution
-    /// * Link exclusion i.e. removes the link themselves not what its points to
+    /// * Doesn't follow links i.e. sets the time of the link itself
+    /// * Leaves the access time untouched
     ///
     /// ### Errors
-    /// * a directory containing files will trigger an error. use `remove_all` instead
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
     ///
     /// ### Examples
     /// ```
     /// use rivia::prelude::*;
+    /// use std::time::{Duration, UNIX_EPOCH};
     ///
     /// let vfs = Vfs::memfs();
     /// let file = vfs.root().mash("file");
     /// assert_vfs_mkfile!(vfs, &file);
-    /// assert_vfs_exists!(vfs, &file);
-    /// assert_vfs_remove!(vfs, &file);
-    /// assert_vfs_no_exists!(vfs, &file);
+    /// let time = UNIX_EPOCH + Duration::new(1_000_000_000, 123_456_789);
+    /// assert!(vfs.set_mtime_only(&file, time).is_ok());
+    /// assert_eq!(vfs.entry(&file).unwrap().modified(), time);
     /// ```
-    fn remove<T: AsRef<Path>>(&self, path: T) -> RvResult<()> {
+    fn set_mtime_only<T: AsRef<Path>>(&self, path: T, mtime: SystemTime) -> RvResult<()> {
         match self {
-            Vfs::Stdfs(x) => x.remove(path),
-            Vfs::Memfs(x) => x.remove(path),
+            Vfs::Stdfs(x) => x.set_mtime_only(path, mtime),
+            Vfs::Memfs(x) => x.set_mtime_only(path, mtime),
+            Vfs::Overlayfs(x) => x.set_mtime_only(path, mtime),
+            Vfs::Readonly(x) => rejected(x, VfsOp::Write, path),
         }
     }
 
-    /// Removes the given directory after removing all of its contents
+    /// Set the SELinux security context of the given path
     ///
     /// * Handles path expansion and absolute path resolution
-    /// * Link exclusion i.e. removes the link themselves not what its points to
+    /// * Links aren't followed so that the context of the link itself is set
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
     ///
     /// ### Examples
     /// ```
     /// use rivia::prelude::*;
     ///
     /// let vfs = Vfs::memfs();
-    /// let dir = vfs.root().mash("dir");
-    /// let file = dir.mash("file");
-    /// assert_vfs_mkdir_p!(vfs, &dir);
+    /// let file = vfs.root().mash("file");
     /// assert_vfs_mkfile!(vfs, &file);
-    /// assert_vfs_is_file!(vfs, &file);
-    /// assert_vfs_remove_all!(vfs, &dir);
-    /// assert_vfs_no_exists!(vfs, &file);
-    /// assert_vfs_no_exists!(vfs, &dir);
+    /// assert!(vfs.set_selinux_context(&file, "system_u:object_r:etc_t:s0").is_ok());
+    /// assert_eq!(vfs.get_selinux_context(&file).unwrap().unwrap(), "system_u:object_r:etc_t:s0");
     /// ```
-    fn remove_all<T: AsRef<Path>>(&self, path: T) -> RvResult<()> {
+    #[cfg(feature = "selinux")]
+    fn set_selinux_context<T: AsRef<Path>, U: AsRef<str>>(&self, path: T, context: U) -> RvResult<()> {
         match self {
-            Vfs::Stdfs(x) => x.remove_all(path),
-            Vfs::Memfs(x) => x.remove_all(path),
+            Vfs::Stdfs(x) => x.set_selinux_context(path, context),
+            Vfs::Memfs(x) => x.set_selinux_context(path, context),
+            Vfs::Overlayfs(x) => x.set_selinux_context(path, context),
+            Vfs::Readonly(x) => rejected(x, VfsOp::Chmod, path),
         }
     }
 
-    /// Returns the current root directory
+    /// Returns the size in bytes of the given file
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Links are followed
+    /// * Use `disk_usage` to sum up the size of a directory tree
+    ///
+    /// ### Errors
+    /// * PathError::IsNotFile(PathBuf) when the given path isn't a file
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
     ///
     /// ### Examples
     /// ```
     /// use rivia::prelude::*;
     ///
     /// let vfs = Vfs::memfs();
-    /// let mut root = PathBuf::new();
-    /// root.push(Component::RootDir);
-    /// assert_eq!(vfs.root(), root);
+    /// let tmpdir = vfs.root();
+    /// let file = tmpdir.mash("file");
+    /// assert_vfs_write_all!(vfs, &file, "foobar");
+    /// assert_eq!(vfs.size(&file).unwrap(), 6);
     /// ```
-    fn root(&self) -> PathBuf {
+    fn size<T: AsRef<Path>>(&self, path: T) -> RvResult<u64> {
         match self {
-            Vfs::Stdfs(x) => x.root(),
-            Vfs::Memfs(x) => x.root(),
+            Vfs::Stdfs(x) => x.size(path),
+            Vfs::Memfs(x) => x.size(path),
+            Vfs::Overlayfs(x) => x.size(path),
+            Vfs::Readonly(x) => x.size(path),
         }
     }
 
-    /// Set the current working directory
+    /// Returns the capacity and usage of the filesystem containing the given path
     ///
     /// * Handles path expansion and absolute path resolution
-    /// * Relative path will use the current working directory
+    /// * Stdfs reports the values of the underlying `statvfs` call with `free` being the space
+    ///   available to unprivileged users
+    /// * Memfs reports the values of the deepest quota containing the path else the whole
+    ///   filesystem with `u64::MAX` as its capacity
     ///
     /// ### Errors
     /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
@@ -2197,16 +4848,17 @@ impl VirtualFileSystem for Vfs {
     /// use rivia::prelude::*;
     ///
     /// let vfs = Vfs::memfs();
-    /// let dir = vfs.root().mash("dir");
-    /// assert_eq!(vfs.cwd().unwrap(), vfs.root());
-    /// assert_vfs_mkdir_p!(vfs, &dir);
-    /// assert_eq!(vfs.set_cwd(&dir).unwrap(), dir.clone());
-    /// assert_eq!(vfs.cwd().unwrap(), dir);
+    /// assert_vfs_write_all!(vfs, "file", "foobar");
+    /// let stat = vfs.stat_fs(vfs.root()).unwrap();
+    /// assert_eq!(stat.used, 6);
+    /// assert_eq!(stat.inodes_used, 1);
     /// ```
-    fn set_cwd<T: AsRef<Path>>(&self, path: T) -> RvResult<PathBuf> {
+    fn stat_fs<T: AsRef<Path>>(&self, path: T) -> RvResult<VfsStatFs> {
         match self {
-            Vfs::Stdfs(x) => x.set_cwd(path),
-            Vfs::Memfs(x) => x.set_cwd(path),
+            Vfs::Stdfs(x) => x.stat_fs(path),
+            Vfs::Memfs(x) => x.stat_fs(path),
+            Vfs::Overlayfs(x) => x.stat_fs(path),
+            Vfs::Readonly(x) => x.stat_fs(path),
         }
     }
 
@@ -2235,6 +4887,35 @@ impl VirtualFileSystem for Vfs {
         match self {
             Vfs::Stdfs(x) => x.symlink(link, target),
             Vfs::Memfs(x) => x.symlink(link, target),
+            Vfs::Overlayfs(x) => x.symlink(link, target),
+            Vfs::Readonly(x) => rejected(x, VfsOp::Create, link),
+        }
+    }
+
+    /// Wraps `symlink` reporting whether the link was created or already existed
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * An existing link is only considered to exist if it points to the given target
+    /// * Dangling links are supported
+    ///
+    /// ### Errors
+    /// * PathError::ExistsAlready(PathBuf) when the link path exists but isn't a link to the target
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let link = vfs.root().mash("link");
+    /// assert_eq!(vfs.symlink_ensured(&link, "file").unwrap(), Ensured::Created(link.clone()));
+    /// assert_eq!(vfs.symlink_ensured(&link, "file").unwrap(), Ensured::Existed(link.clone()));
+    /// ```
+    fn symlink_ensured<T: AsRef<Path>, U: AsRef<Path>>(&self, link: T, target: U) -> RvResult<Ensured> {
+        match self {
+            Vfs::Stdfs(x) => x.symlink_ensured(link, target),
+            Vfs::Memfs(x) => x.symlink_ensured(link, target),
+            Vfs::Overlayfs(x) => x.symlink_ensured(link, target),
+            Vfs::Readonly(x) => rejected(x, VfsOp::Create, link),
         }
     }
 
@@ -2251,12 +4932,47 @@ impl VirtualFileSystem for Vfs {
         match self {
             Vfs::Stdfs(x) => x.uid(path),
             Vfs::Memfs(x) => x.uid(path),
+            Vfs::Overlayfs(x) => x.uid(path),
+            Vfs::Readonly(x) => x.uid(path),
+        }
+    }
+
+    /// Watch the given path for changes returning a stream of the events that occur
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Directories are watched recursively
+    /// * Reports files, directories and links being created, written to, removed and renamed
+    /// * Permission and ownership changes aren't reported
+    /// * See [`VfsWatcher`] for the details of how each backend reports changes
+    ///
+    /// ### Errors
+    /// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let mut watcher = vfs.watch(vfs.root()).unwrap();
+    /// let file = vfs.root().mash("file");
+    /// assert_vfs_mkfile!(vfs, &file);
+    /// assert!(vfs.remove(&file).is_ok());
+    /// assert_eq!(watcher.try_recv(), Some(VfsEvent::Create(file.clone())));
+    /// assert_eq!(watcher.try_recv(), Some(VfsEvent::Remove(file)));
+    /// ```
+    fn watch<T: AsRef<Path>>(&self, path: T) -> RvResult<VfsWatcher> {
+        match self {
+            Vfs::Stdfs(x) => x.watch(path),
+            Vfs::Memfs(x) => x.watch(path),
+            Vfs::Overlayfs(x) => x.watch(path),
+            Vfs::Readonly(x) => x.watch(path),
         }
     }
 
     /// Opens a file in write-only mode
     ///
     /// * Creates a file if it does not exist or truncates it if it does
+    /// * Returns a [`VfsWriter`] reporting the path and number of bytes written
     ///
     /// ### Errors
     /// * PathError::IsNotDir(PathBuf) when the given path's parent exists but is not a directory
@@ -2274,10 +4990,12 @@ impl VirtualFileSystem for Vfs {
     /// f.flush().unwrap();
     /// assert_vfs_read_all!(vfs, &file, "foobar");
     /// ```
-    fn write<T: AsRef<Path>>(&self, path: T) -> RvResult<Box<dyn Write>> {
+    fn write<T: AsRef<Path>>(&self, path: T) -> RvResult<VfsWriter> {
         match self {
             Vfs::Stdfs(x) => x.write(path),
             Vfs::Memfs(x) => x.write(path),
+            Vfs::Overlayfs(x) => x.write(path),
+            Vfs::Readonly(x) => rejected(x, VfsOp::Write, path),
         }
     }
 
@@ -2306,6 +5024,66 @@ impl VirtualFileSystem for Vfs {
         match self {
             Vfs::Stdfs(x) => x.write_all(path, data),
             Vfs::Memfs(x) => x.write_all(path, data),
+            Vfs::Overlayfs(x) => x.write_all(path, data),
+            Vfs::Readonly(x) => rejected(x, VfsOp::Write, path),
+        }
+    }
+
+    /// Write the given data to the target file atomically
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Writes to a temporary sibling file which is then renamed into place so that readers only
+    ///   ever see the old or the new data and a failure never leaves a partially written file
+    /// * Replaces the final target of links rather than the links themselves
+    /// * Preserves the mode and ownership of an existing file
+    /// * Breaks any hard links to an existing file as it is replaced by a new file
+    ///
+    /// ### Errors
+    /// * PathError::IsNotDir(PathBuf) when the given path's parent exists but is not a directory
+    /// * PathError::DoesNotExist(PathBuf) when the given path's parent doesn't exist
+    /// * PathError::IsNotFile(PathBuf) when the given path exists but is not a file
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let tmpdir = vfs.root();
+    /// let file = tmpdir.mash("file");
+    /// assert!(vfs.write_all_atomic(&file, "foobar").is_ok());
+    /// assert_vfs_read_all!(vfs, &file, "foobar");
+    /// ```
+    fn write_all_atomic<T: AsRef<Path>, U: AsRef<[u8]>>(&self, path: T, data: U) -> RvResult<()> {
+        match self {
+            Vfs::Stdfs(x) => x.write_all_atomic(path, data),
+            Vfs::Memfs(x) => x.write_all_atomic(path, data),
+            Vfs::Overlayfs(x) => x.write_all_atomic(path, data),
+            Vfs::Readonly(x) => rejected(x, VfsOp::Write, path),
+        }
+    }
+
+    /// Creates a new [`Writer`] for use with the builder pattern
+    ///
+    /// * Handles path expansion and absolute path resolution
+    /// * Options for writing atomically
+    /// * Execute by calling `exec`
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let tmpdir = vfs.root();
+    /// let file = tmpdir.mash("file");
+    /// assert!(vfs.write_b(&file, "foobar").unwrap().atomic(true).exec().is_ok());
+    /// assert_vfs_read_all!(vfs, &file, "foobar");
+    /// ```
+    fn write_b<T: AsRef<Path>, U: AsRef<[u8]>>(&self, path: T, data: U) -> RvResult<Writer> {
+        match self {
+            Vfs::Stdfs(x) => x.write_b(path, data),
+            Vfs::Memfs(x) => x.write_b(path, data),
+            Vfs::Overlayfs(x) => x.write_b(path, data),
+            Vfs::Readonly(x) => rejected(x, VfsOp::Write, path),
         }
     }
 
@@ -2334,6 +5112,8 @@ impl VirtualFileSystem for Vfs {
         match self {
             Vfs::Stdfs(x) => x.write_lines(path, lines),
             Vfs::Memfs(x) => x.write_lines(path, lines),
+            Vfs::Overlayfs(x) => x.write_lines(path, lines),
+            Vfs::Readonly(x) => rejected(x, VfsOp::Write, path),
         }
     }
 
@@ -2349,16 +5129,67 @@ impl VirtualFileSystem for Vfs {
         match self {
             Vfs::Stdfs(x) => x.upcast(),
             Vfs::Memfs(x) => x.upcast(),
+            Vfs::Overlayfs(x) => x.upcast(),
+            Vfs::Readonly(x) => Vfs::Readonly(x),
         }
     }
 }
 
+// Reject the given mutating operation on a read only Vfs
+fn rejected<T, U: AsRef<Path>>(vfs: &Vfs, op: VfsOp, path: U) -> RvResult<T> {
+    Err(VfsError::ReadOnly(op, vfs.abs(path)?).into())
+}
+
 // Unit tests
 // -------------------------------------------------------------------------------------------------
 #[cfg(test)]
 mod tests {
+    use std::sync::Mutex;
+
     use crate::prelude::*;
 
+    #[cfg(feature = "bincode")]
+    #[test]
+    fn test_vfs_bincode() {
+        test_bincode(assert_vfs_setup!(Vfs::memfs()));
+        test_bincode(assert_vfs_setup!(Vfs::stdfs()));
+    }
+    #[cfg(feature = "bincode")]
+    fn test_bincode((vfs, tmpdir): (Vfs, PathBuf)) {
+        #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+        struct State {
+            offset: u64,
+            paths: Vec<PathBuf>,
+        }
+        let dir1 = tmpdir.mash("dir1");
+        let file1 = tmpdir.mash("file1");
+        let state = State { offset: 42, paths: vec![dir1.clone(), file1.clone()] };
+
+        // Errors
+        assert_eq!(
+            vfs.read_bincode::<State, _>(&file1).unwrap_err().downcast_ref::<PathError>(),
+            Some(&PathError::does_not_exist(&file1))
+        );
+        assert_vfs_mkdir_p!(vfs, &dir1);
+        assert_eq!(
+            vfs.write_bincode(&dir1, &state).unwrap_err().downcast_ref::<PathError>(),
+            Some(&PathError::is_not_file(&dir1))
+        );
+
+        // Round trip and overwrite
+        assert!(vfs.write_bincode(&file1, &state).is_ok());
+        assert_eq!(vfs.read_bincode::<State, _>(&file1).unwrap(), state);
+        let state = State { offset: 7, paths: vec![] };
+        assert!(vfs.write_bincode(&file1, &state).is_ok());
+        assert_eq!(vfs.read_bincode::<State, _>(&file1).unwrap(), state);
+
+        // Truncated data fails to deserialize
+        assert!(vfs.write_all(&file1, [1, 2, 3]).is_ok());
+        assert!(vfs.read_bincode::<State, _>(&file1).unwrap_err().is::<bincode::Error>());
+
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+
     #[test]
     fn test_vfs_cwd() {
         // Stdfs
@@ -2407,6 +5238,233 @@ mod tests {
         assert_vfs_remove_all!(vfs, &tmpdir);
     }
 
+    #[test]
+    fn test_vfs_hardlink() {
+        test_hardlink(assert_vfs_setup!(Vfs::memfs()));
+        test_hardlink(assert_vfs_setup!(Vfs::stdfs()));
+    }
+    fn test_hardlink((vfs, tmpdir): (Vfs, PathBuf)) {
+        let dir = tmpdir.mash("dir");
+        let file = tmpdir.mash("file");
+        let link1 = tmpdir.mash("link1");
+        let link2 = dir.mash("link2");
+        assert_vfs_mkdir_p!(vfs, &dir);
+        assert_vfs_write_all!(vfs, &file, "foo");
+
+        // Errors
+        assert_eq!(
+            vfs.hardlink(tmpdir.mash("foo"), &link1).unwrap_err().to_string(),
+            PathError::does_not_exist(tmpdir.mash("foo")).to_string()
+        );
+        assert_eq!(vfs.hardlink(&dir, &link1).unwrap_err().to_string(), PathError::is_not_file(&dir).to_string());
+        assert_eq!(
+            vfs.hardlink(&file, &dir).unwrap_err().to_string(),
+            PathError::exists_already(&dir).to_string()
+        );
+        assert_eq!(
+            vfs.hardlink(&file, tmpdir.mash("foo/bar")).unwrap_err().to_string(),
+            PathError::does_not_exist(tmpdir.mash("foo")).to_string()
+        );
+        assert_eq!(
+            vfs.nlink(tmpdir.mash("foo")).unwrap_err().to_string(),
+            PathError::does_not_exist(tmpdir.mash("foo")).to_string()
+        );
+        assert!(!vfs.is_hardlink(tmpdir.mash("foo")));
+        assert!(!vfs.is_hardlink(&dir));
+        assert_eq!(vfs.nlink(&file).unwrap(), 1);
+        assert_vfs_no_hardlink!(vfs, &file);
+
+        // Links share the data of the file
+        assert_vfs_hardlink!(vfs, &file, &link1);
+        assert_vfs_hardlink!(vfs, &link1, &link2);
+        assert_vfs_is_hardlink!(vfs, &file);
+        assert_eq!(vfs.nlink(&link2).unwrap(), 3);
+        assert!(vfs.same_file(&file, &link2));
+        assert!(vfs.write_all(&link2, "bar").is_ok());
+        assert_vfs_read_all!(vfs, &file, "bar");
+        assert!(vfs.append_all(&file, "1").is_ok());
+        assert_vfs_read_all!(vfs, &link1, "bar1");
+
+        // Removing the original keeps the data for the remaining links
+        assert_vfs_remove!(vfs, &file);
+        assert_eq!(vfs.nlink(&link1).unwrap(), 2);
+        assert_vfs_read_all!(vfs, &link2, "bar1");
+
+        // Renaming a link keeps it linked
+        assert!(vfs.move_p(&link1, &file).is_ok());
+        assert_eq!(vfs.nlink(&link2).unwrap(), 2);
+        assert!(vfs.write_all(&file, "foo").is_ok());
+        assert_vfs_read_all!(vfs, &link2, "foo");
+
+        // Once only one name remains it is no longer a hard link
+        assert_vfs_remove!(vfs, &link2);
+        assert_eq!(vfs.nlink(&file).unwrap(), 1);
+        assert_vfs_no_hardlink!(vfs, &file);
+        assert_vfs_read_all!(vfs, &file, "foo");
+
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+
+    #[test]
+    fn test_vfs_hooks() {
+        test_hooks(assert_vfs_setup!(Vfs::memfs()));
+        test_hooks(assert_vfs_setup!(Vfs::stdfs()));
+    }
+    fn test_hooks((vfs, tmpdir): (Vfs, PathBuf)) {
+        let dir1 = tmpdir.mash("dir1");
+        let dir2 = tmpdir.mash("dir2");
+        let file1 = dir1.mash("file1");
+        let file2 = tmpdir.mash("file2");
+        let denied = tmpdir.mash("denied");
+
+        // Log all successful operations and deny any under `denied`
+        let ops = Arc::new(Mutex::new(vec![]));
+        let ops_clone = ops.clone();
+        vfs.hook_post(move |op, path| ops_clone.lock().unwrap().push((op, path.to_path_buf())));
+        let deny = denied.clone();
+        vfs.hook_pre(move |_, path| match path.starts_with(&deny) {
+            true => Err(VfsError::Unavailable.into()),
+            false => Ok(()),
+        });
+
+        // Vetoed operations are not executed
+        assert!(vfs.mkdir_p(&denied).is_err());
+        assert!(vfs.write_all(denied.mash("file"), "foo").is_err());
+        assert_vfs_no_exists!(vfs, &denied);
+
+        // Allowed operations
+        assert_vfs_mkdir_p!(vfs, &dir1);
+        assert_vfs_write_all!(vfs, &file1, "foo");
+        assert!(vfs.chmod(&file1, 0o600).is_ok());
+        assert!(vfs.copy(&file1, &file2).is_ok());
+        assert!(vfs.move_p(&dir1, &dir2).is_ok());
+        assert!(vfs.remove(&file2).is_ok());
+
+        // Moving into a denied location is vetoed
+        assert!(vfs.move_p(&dir2, &denied).is_err());
+        assert_vfs_exists!(vfs, &dir2);
+
+        assert_eq!(*ops.lock().unwrap(), vec![
+            (VfsOp::Create, dir1.clone()),
+            (VfsOp::Write, file1.clone()),
+            (VfsOp::Chmod, file1),
+            (VfsOp::Write, file2.clone()),
+            (VfsOp::Rename, dir2.clone()),
+            (VfsOp::Rename, dir1),
+            (VfsOp::Remove, file2),
+        ]);
+
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+
+    #[test]
+    fn test_vfs_immutable() {
+        test_immutable(assert_vfs_setup!(Vfs::memfs()));
+        test_immutable(assert_vfs_setup!(Vfs::stdfs()));
+    }
+    fn test_immutable((vfs, tmpdir): (Vfs, PathBuf)) {
+        let dir = tmpdir.mash("dir");
+        let file = dir.mash("file");
+        let link = tmpdir.mash("link");
+        assert_vfs_mkdir_p!(vfs, &dir);
+        assert_vfs_write_all!(vfs, &file, "foo");
+        assert_vfs_symlink!(vfs, &link, &file);
+
+        // Doesn't exist
+        assert!(!vfs.is_immutable(tmpdir.mash("foo")));
+        assert_eq!(
+            vfs.set_immutable(tmpdir.mash("foo"), true).unwrap_err().to_string(),
+            PathError::does_not_exist(tmpdir.mash("foo")).to_string()
+        );
+
+        // Setting the flag requires privileges and filesystem support on Stdfs
+        assert!(!vfs.is_immutable(&file));
+        if vfs.set_immutable(&link, true).is_err() {
+            assert_vfs_remove_all!(vfs, &tmpdir);
+            return;
+        }
+
+        // Links are followed and the copy of an immutable file is mutable
+        assert!(vfs.is_immutable(&file));
+        assert!(vfs.is_immutable(&link));
+        assert!(vfs.copy(&file, tmpdir.mash("copy")).is_ok());
+        assert!(!vfs.is_immutable(tmpdir.mash("copy")));
+
+        // Immutable files can't be written, removed, renamed or linked to
+        assert!(vfs.write_all(&file, "bar").is_err());
+        assert!(vfs.write_all(&link, "bar").is_err());
+        assert!(vfs.append_all(&file, "bar").is_err());
+        assert!(vfs.copy(tmpdir.mash("copy"), &file).is_err());
+        assert!(vfs.remove(&file).is_err());
+        assert!(vfs.remove_all(&dir).is_err());
+        assert!(vfs.move_p(&file, tmpdir.mash("moved")).is_err());
+        assert!(vfs.move_p(tmpdir.mash("copy"), &file).is_err());
+        assert!(vfs.hardlink(&file, tmpdir.mash("hard")).is_err());
+        assert_vfs_read_all!(vfs, &file, "foo");
+        assert_vfs_no_exists!(vfs, tmpdir.mash("moved"));
+        assert_vfs_no_exists!(vfs, tmpdir.mash("hard"));
+        assert!(vfs.set_immutable(&file, false).is_ok());
+        assert!(!vfs.is_immutable(&file));
+
+        // Immutable directories can't have entries added, removed or renamed
+        assert!(vfs.set_immutable(&dir, true).is_ok());
+        assert!(vfs.is_immutable(&dir));
+        assert!(vfs.mkfile(dir.mash("new")).is_err());
+        assert!(vfs.remove(&file).is_err());
+        assert!(vfs.move_p(&file, tmpdir.mash("moved")).is_err());
+        assert!(vfs.move_p(tmpdir.mash("copy"), &dir).is_err());
+        assert_vfs_no_exists!(vfs, dir.mash("new"));
+        assert_vfs_no_exists!(vfs, dir.mash("copy"));
+        assert_vfs_no_exists!(vfs, tmpdir.mash("moved"));
+
+        // Files within an immutable directory can still be written
+        assert_vfs_write_all!(vfs, &file, "bar");
+        assert!(vfs.set_immutable(&dir, false).is_ok());
+        assert!(!vfs.is_immutable(&dir));
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+
+    #[test]
+    fn test_vfs_metadata() {
+        test_metadata(assert_vfs_setup!(Vfs::memfs()));
+        test_metadata(assert_vfs_setup!(Vfs::stdfs()));
+    }
+    fn test_metadata((vfs, tmpdir): (Vfs, PathBuf)) {
+        let dir = tmpdir.mash("dir");
+        let file = dir.mash("file");
+        let link = tmpdir.mash("link");
+        let hardlink = tmpdir.mash("hardlink");
+        assert_vfs_mkdir_p!(vfs, &dir);
+        assert_vfs_write_all!(vfs, &file, "foobar");
+        assert_vfs_symlink!(vfs, &link, &file);
+
+        // Doesn't exist
+        assert_eq!(
+            vfs.metadata(tmpdir.mash("foo")).unwrap_err().to_string(),
+            PathError::does_not_exist(tmpdir.mash("foo")).to_string()
+        );
+
+        // Matches the individual properties
+        let meta = vfs.metadata(&file).unwrap();
+        assert_eq!((meta.dir, meta.file, meta.link), (false, true, false));
+        assert_eq!(meta.mode, vfs.mode(&file).unwrap());
+        assert_eq!((meta.uid, meta.gid), vfs.owner(&file).unwrap());
+        assert_eq!((meta.size, meta.nlink), (6, 1));
+        assert_eq!(meta, vfs.entry(&file).unwrap().metadata());
+        let meta = vfs.metadata(&dir).unwrap();
+        assert_eq!((meta.dir, meta.file, meta.link), (true, false, false));
+        assert_eq!(meta.mode, vfs.mode(&dir).unwrap());
+
+        // Links report their target's properties and hard links their count
+        assert_vfs_hardlink!(vfs, &file, &hardlink);
+        let meta = vfs.metadata(&link).unwrap();
+        assert_eq!((meta.dir, meta.file, meta.link), (false, true, true));
+        assert_eq!((meta.size, meta.nlink), (6, 2));
+        assert_eq!(vfs.metadata(&hardlink).unwrap().nlink, 2);
+
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+
     #[test]
     fn test_vfs_move_p() {
         test_move_p(assert_vfs_setup!(Vfs::memfs()));
@@ -2461,6 +5519,48 @@ mod tests {
         assert_vfs_remove_all!(vfs, &tmpdir);
     }
 
+    #[test]
+    fn test_vfs_open_rw() {
+        test_open_rw(assert_vfs_setup!(Vfs::memfs()));
+        test_open_rw(assert_vfs_setup!(Vfs::stdfs()));
+    }
+    fn test_open_rw((vfs, tmpdir): (Vfs, PathBuf)) {
+        let dir1 = tmpdir.mash("dir1");
+        let file1 = tmpdir.mash("file1");
+
+        // Errors
+        assert_vfs_mkdir_p!(vfs, &dir1);
+        assert_eq!(
+            vfs.open_rw(&dir1).err().unwrap().downcast_ref::<PathError>(),
+            Some(&PathError::is_not_file(&dir1))
+        );
+
+        // Creates the file if it doesn't exist
+        let mut f = vfs.open_rw(&file1).unwrap();
+        f.write_all(b"foobar").unwrap();
+        f.flush().unwrap();
+        assert_vfs_read_all!(vfs, &file1, "foobar");
+
+        // Modifies regions in place without truncating
+        let mut f = vfs.open_rw(&file1).unwrap();
+        let mut buf = [0; 3];
+        f.read_exact(&mut buf).unwrap();
+        assert_eq!(&buf, b"foo");
+        f.write_all(b"BAR").unwrap();
+        f.seek(SeekFrom::Start(1)).unwrap();
+        f.write_all(b"OO").unwrap();
+        f.seek(SeekFrom::End(0)).unwrap();
+        f.write_all(b"123").unwrap();
+        f.rewind().unwrap();
+        let mut data = String::new();
+        f.read_to_string(&mut data).unwrap();
+        assert_eq!(data, "fOOBAR123");
+        f.flush().unwrap();
+        assert_vfs_read_all!(vfs, &file1, "fOOBAR123");
+
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+
     #[test]
     fn test_vfs_paths() {
         test_paths(assert_vfs_setup!(Vfs::memfs()));
@@ -2478,6 +5578,74 @@ mod tests {
         assert_vfs_remove_all!(vfs, &tmpdir);
     }
 
+    #[test]
+    fn test_vfs_readonly() {
+        test_readonly(assert_vfs_setup!(Vfs::memfs()));
+        test_readonly(assert_vfs_setup!(Vfs::stdfs()));
+        test_readonly(assert_vfs_setup!(Vfs::overlayfs(Vfs::memfs())));
+    }
+    fn test_readonly((vfs, tmpdir): (Vfs, PathBuf)) {
+        let dir1 = tmpdir.mash("dir1");
+        let file1 = dir1.mash("file1");
+        let file2 = tmpdir.mash("file2");
+        let link1 = tmpdir.mash("link1");
+        assert_vfs_mkdir_p!(vfs, &dir1);
+        assert_vfs_write_all!(vfs, &file1, "foo");
+        assert_vfs_symlink!(vfs, &link1, &file1);
+        let vfs = Vfs::readonly(vfs);
+
+        // Mutations are rejected
+        let err = |x: RvResult<()>| x.unwrap_err().downcast_ref::<VfsError>().cloned();
+        assert_eq!(err(vfs.write_all(&file1, "bar")), Some(VfsError::ReadOnly(VfsOp::Write, file1.clone())));
+        assert_eq!(err(vfs.mkfile(&file2).map(|_| ())), Some(VfsError::ReadOnly(VfsOp::Create, file2.clone())));
+        assert_eq!(err(vfs.remove(&file1)), Some(VfsError::ReadOnly(VfsOp::Remove, file1.clone())));
+        assert!(vfs.mkdir_p(tmpdir.mash("dir2")).is_err());
+        assert!(vfs.chmod(&file1, 0o600).is_err());
+        assert!(vfs.copy(&file1, &file2).is_err());
+        assert!(vfs.move_p(&file1, &file2).is_err());
+        assert!(vfs.symlink(tmpdir.mash("link2"), &file1).is_err());
+        assert!(vfs.remove_all(&tmpdir).is_err());
+
+        // Reads still work
+        assert_vfs_no_exists!(vfs, &file2);
+        assert_vfs_no_exists!(vfs, tmpdir.mash("dir2"));
+        assert_vfs_read_all!(vfs, &file1, "foo");
+        assert_eq!(vfs.readlink_abs(&link1).unwrap(), file1);
+        assert_eq!(vfs.paths(&tmpdir).unwrap(), vec![dir1, link1]);
+        assert_ne!(vfs.mode(&file1).unwrap(), 0o100600);
+
+        // Directory handles reject mutations as well
+        let handle = vfs.open_dir(&tmpdir).unwrap();
+        assert!(handle.is_dir("dir1"));
+        let handle = handle.open_dir("dir1").unwrap();
+        assert_eq!(handle.read_all("file1").unwrap(), "foo");
+        assert!(handle.write_all("file1", "bar").is_err());
+        assert!(handle.mkfile("file2").is_err());
+        assert!(handle.remove("file1").is_err());
+        assert_vfs_read_all!(vfs, &file1, "foo");
+
+        // The wrapped Vfs is left untouched and still writable
+        if let Vfs::Readonly(inner) = vfs {
+            assert!(inner.write_all(&file1, "bar").is_ok());
+            assert_vfs_read_all!(inner, &file1, "bar");
+            assert_vfs_remove_all!(inner, &tmpdir);
+        }
+    }
+
+    #[test]
+    fn test_vfs_readonly_clone() {
+        let memfs = Memfs::new();
+        let clone = memfs.clone();
+        let vfs = Vfs::readonly(memfs.upcast());
+
+        // Handles taken before wrapping are still writable
+        assert!(clone.write_all("/file", "foo").is_ok());
+        assert_vfs_read_all!(vfs, "/file", "foo");
+        assert!(vfs.write_all("/file", "bar").is_err());
+        assert!(clone.write_all("/file", "bar").is_ok());
+        assert_vfs_read_all!(vfs, "/file", "bar");
+    }
+
     #[test]
     fn test_vfs_root() {
         test_root(assert_vfs_setup!(Vfs::memfs()));
@@ -2490,6 +5658,72 @@ mod tests {
         assert_vfs_remove_all!(vfs, &tmpdir);
     }
 
+    #[test]
+    fn test_vfs_same_content() {
+        test_same_content(assert_vfs_setup!(Vfs::memfs()));
+        test_same_content(assert_vfs_setup!(Vfs::stdfs()));
+    }
+    fn test_same_content((vfs, tmpdir): (Vfs, PathBuf)) {
+        let dir = tmpdir.mash("dir");
+        let file1 = tmpdir.mash("file1");
+        let file2 = tmpdir.mash("file2");
+        let link = tmpdir.mash("link");
+        assert_vfs_mkdir_p!(vfs, &dir);
+        assert_vfs_mkfile!(vfs, &file1);
+        assert_vfs_mkfile!(vfs, &file2);
+        assert!(vfs.same_content(&file1, &file2).unwrap());
+
+        // Contents spanning multiple chunks that only differ at the end
+        let mut data = vec![b'a'; 20000];
+        assert!(vfs.write_all(&file1, &data).is_ok());
+        assert!(vfs.write_all(&file2, &data).is_ok());
+        assert!(vfs.same_content(&file1, &file2).unwrap());
+        data[19999] = b'b';
+        assert!(vfs.write_all(&file2, &data).is_ok());
+        assert!(!vfs.same_content(&file1, &file2).unwrap());
+
+        // Differing sizes and links
+        assert!(vfs.write_all(&file2, "foo").is_ok());
+        assert!(!vfs.same_content(&file1, &file2).unwrap());
+        assert_vfs_symlink!(vfs, &link, &file2);
+        assert!(vfs.same_content(&link, &file2).unwrap());
+
+        // Not a file or doesn't exist
+        let err = vfs.same_content(&file1, &dir).unwrap_err();
+        assert_eq!(err.to_string(), PathError::is_not_file(&dir).to_string());
+        let err = vfs.same_content(tmpdir.mash("foo"), &file1).unwrap_err();
+        assert_eq!(err.to_string(), PathError::does_not_exist(tmpdir.mash("foo")).to_string());
+
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+
+    #[test]
+    fn test_vfs_size() {
+        test_size(assert_vfs_setup!(Vfs::memfs()));
+        test_size(assert_vfs_setup!(Vfs::stdfs()));
+    }
+    fn test_size((vfs, tmpdir): (Vfs, PathBuf)) {
+        let dir = tmpdir.mash("dir");
+        let file = tmpdir.mash("file");
+        let link = tmpdir.mash("link");
+        assert_vfs_mkdir_p!(vfs, &dir);
+        assert_vfs_mkfile!(vfs, &file);
+        assert_eq!(vfs.size(&file).unwrap(), 0);
+
+        // Links are followed
+        assert!(vfs.write_all(&file, "foobar").is_ok());
+        assert_vfs_symlink!(vfs, &link, &file);
+        assert_eq!(vfs.size(&file).unwrap(), 6);
+        assert_eq!(vfs.size(&link).unwrap(), 6);
+
+        // Not a file or doesn't exist
+        assert_eq!(vfs.size(&dir).unwrap_err().to_string(), PathError::is_not_file(&dir).to_string());
+        let err = vfs.size(tmpdir.mash("foo")).unwrap_err();
+        assert_eq!(err.to_string(), PathError::does_not_exist(tmpdir.mash("foo")).to_string());
+
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+
     #[test]
     fn test_vfs_upcast() {
         test_upcast(assert_vfs_setup!(Vfs::memfs()));
@@ -2499,4 +5733,47 @@ mod tests {
         let upcast = vfs.upcast();
         assert_vfs_remove_all!(upcast, &tmpdir);
     }
+
+    #[test]
+    fn test_vfs_write_all_atomic() {
+        test_write_all_atomic(assert_vfs_setup!(Vfs::memfs()));
+        test_write_all_atomic(assert_vfs_setup!(Vfs::stdfs()));
+    }
+    fn test_write_all_atomic((vfs, tmpdir): (Vfs, PathBuf)) {
+        let dir = tmpdir.mash("dir");
+        let file = dir.mash("file");
+        let hard = dir.mash("hard");
+        let link = dir.mash("link");
+        assert_vfs_mkdir_p!(vfs, &dir);
+
+        // Errors
+        let err = vfs.write_all_atomic(&dir, "foo").unwrap_err();
+        assert_eq!(err.downcast_ref::<PathError>(), Some(&PathError::is_not_file(&dir)));
+        let err = vfs.write_all_atomic(tmpdir.mash("foo/file"), "foo").unwrap_err();
+        assert_eq!(err.downcast_ref::<PathError>(), Some(&PathError::does_not_exist(tmpdir.mash("foo"))));
+
+        // Create and replace preserving the mode and ownership
+        assert!(vfs.write_all_atomic(&file, "foo").is_ok());
+        assert_vfs_read_all!(vfs, &file, "foo");
+        assert!(vfs.chmod(&file, 0o600).is_ok());
+        let owner = vfs.owner(&file).unwrap();
+        assert!(vfs.write_all_atomic(&file, "foobar").is_ok());
+        assert_vfs_read_all!(vfs, &file, "foobar");
+        assert_eq!(vfs.mode(&file).unwrap(), 0o100600);
+        assert_eq!(vfs.owner(&file).unwrap(), owner);
+
+        // Links are kept with their target replaced while hard links are broken
+        assert_vfs_symlink!(vfs, &link, &file);
+        assert_vfs_hardlink!(vfs, &file, &hard);
+        assert!(vfs.write_all_atomic(&link, "bar").is_ok());
+        assert_vfs_is_symlink!(vfs, &link);
+        assert_vfs_read_all!(vfs, &file, "bar");
+        assert_vfs_read_all!(vfs, &hard, "foobar");
+        assert_eq!(vfs.nlink(&file).unwrap(), 1);
+
+        // No temp files are left behind
+        assert_eq!(vfs.paths(&dir).unwrap(), vec![file, hard, link]);
+
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
 }
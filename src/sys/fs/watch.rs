@@ -0,0 +1,382 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    path::{Path, PathBuf},
+    sync::mpsc::Receiver,
+    thread,
+    time::{Duration, Instant, SystemTime},
+};
+
+use crate::{
+    errors::*,
+    sys::{Entry, StdfsWatch, VfsEvent, VirtualFileSystem},
+};
+
+// Bounds on how often the watched paths are polled for changes
+const MIN_POLL: Duration = Duration::from_millis(5);
+const MAX_POLL: Duration = Duration::from_millis(250);
+
+// State of each path in a snapshot i.e. size, modified time and mode
+type Snapshot = HashMap<PathBuf, (u64, SystemTime, u32)>;
+
+/// Calls the given callback with the changed paths each time the watched paths change
+///
+/// * Handles path expansion and absolute path resolution
+/// * Paths are polled through the Vfs so both backends are supported alike
+/// * Directories are watched recursively and links aren't followed
+/// * Changes are coalesced until the paths have been quiet for the `debounce` duration such that
+///   a burst of writes results in a single call with every path that changed during the burst
+/// * Created, removed and modified paths are reported sorted by name
+/// * Directories are only reported when created, removed or their permissions change
+/// * Paths that don't exist yet are watched for creation
+/// * Watching stops once the callback returns false
+///
+/// ### Examples
+/// ```
+/// use rivia::prelude::*;
+/// use std::{sync::Arc, thread, time::Duration};
+///
+/// let vfs = Arc::new(Vfs::memfs());
+/// let file = vfs.root().mash("file");
+/// let writer = vfs.clone();
+/// thread::spawn(move || {
+///     thread::sleep(Duration::from_millis(50));
+///     writer.write_all("file", "foobar").unwrap();
+/// });
+/// let mut changed = vec![];
+/// sys::watch_run(&*vfs, &[vfs.root()], Duration::from_millis(20), |paths| {
+///     changed = paths.to_vec();
+///     false
+/// })
+/// .unwrap();
+/// assert_eq!(changed, vec![file]);
+/// ```
+pub fn watch_run<T, U, F>(vfs: &T, paths: &[U], debounce: Duration, mut callback: F) -> RvResult<()>
+where
+    T: VirtualFileSystem,
+    U: AsRef<Path>,
+    F: FnMut(&[PathBuf]) -> bool,
+{
+    let paths = paths.iter().map(|x| vfs.abs(x)).collect::<RvResult<Vec<_>>>()?;
+    let poll = (debounce / 4).clamp(MIN_POLL, MAX_POLL);
+    let mut last = snapshot(vfs, &paths);
+    loop {
+        thread::sleep(poll);
+        let mut changed = BTreeSet::new();
+        if !changes(vfs, &paths, &mut last, &mut changed) {
+            continue;
+        }
+
+        // Coalesce further changes until the paths have been quiet for the debounce duration
+        let mut quiet = Duration::ZERO;
+        while quiet < debounce {
+            thread::sleep(poll);
+            quiet = match changes(vfs, &paths, &mut last, &mut changed) {
+                true => Duration::ZERO,
+                false => quiet + poll,
+            };
+        }
+        if !callback(&changed.into_iter().collect::<Vec<_>>()) {
+            return Ok(());
+        }
+    }
+}
+
+/// Provides a stream of the changes made beneath a watched path
+///
+/// * Created with the Vfs function `watch`
+/// * Reports files, directories and links being created, written to, removed and renamed
+/// * Permission and ownership changes aren't reported
+/// * Paths moved into or out of the watched path are reported as created or removed
+/// * Iterating blocks until the next event, use `recv_timeout` or `try_recv` to limit the wait
+/// * Stdfs reports writes once the file is closed and uses inotify such that changes made by other
+///   processes are reported too. Memfs reports the changes made through the Memfs instance.
+///
+/// ### Examples
+/// ```
+/// use rivia::prelude::*;
+///
+/// let vfs = Vfs::memfs();
+/// let mut watcher = vfs.watch(vfs.root()).unwrap();
+/// let file = vfs.root().mash("file");
+/// assert_vfs_write_all!(vfs, &file, "foobar");
+/// assert_eq!(watcher.try_recv(), Some(VfsEvent::Create(file.clone())));
+/// assert_eq!(watcher.try_recv(), Some(VfsEvent::Write(file)));
+/// assert_eq!(watcher.try_recv(), None);
+/// ```
+#[derive(Debug)]
+pub struct VfsWatcher(WatchSource);
+
+#[derive(Debug)]
+enum WatchSource
+{
+    Memfs(Receiver<VfsEvent>, PathBuf), // Memfs event subscription and the watched path
+    Stdfs(StdfsWatch),                  // inotify watches of the watched path
+}
+
+impl VfsWatcher
+{
+    // Create a new watcher filtering the given Memfs events down to the given path
+    pub(crate) fn memfs(events: Receiver<VfsEvent>, path: PathBuf) -> Self
+    {
+        Self(WatchSource::Memfs(events, path))
+    }
+
+    // Create a new watcher from the given inotify watches
+    pub(crate) fn stdfs(watch: StdfsWatch) -> Self
+    {
+        Self(WatchSource::Stdfs(watch))
+    }
+
+    /// Wait up to the given duration for the next event
+    ///
+    /// * Returns `None` if no event occurred in time
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    /// use std::time::Duration;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let mut watcher = vfs.watch(vfs.root()).unwrap();
+    /// let dir = vfs.root().mash("dir");
+    /// assert_vfs_mkdir_p!(vfs, &dir);
+    /// assert_eq!(watcher.recv_timeout(Duration::from_millis(10)), Some(VfsEvent::Create(dir)));
+    /// assert_eq!(watcher.recv_timeout(Duration::from_millis(10)), None);
+    /// ```
+    pub fn recv_timeout(&mut self, timeout: Duration) -> Option<VfsEvent>
+    {
+        self.recv(Some(timeout))
+    }
+
+    /// Returns the next event if one has already occurred without waiting
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let mut watcher = vfs.watch(vfs.root()).unwrap();
+    /// assert_eq!(watcher.try_recv(), None);
+    /// assert_vfs_mkdir_p!(vfs, "dir");
+    /// assert_eq!(watcher.try_recv(), Some(VfsEvent::Create(vfs.root().mash("dir"))));
+    /// ```
+    pub fn try_recv(&mut self) -> Option<VfsEvent>
+    {
+        self.recv(Some(Duration::ZERO))
+    }
+
+    // Wait for the next event, forever when `timeout` is `None`
+    fn recv(&mut self, timeout: Option<Duration>) -> Option<VfsEvent>
+    {
+        match &mut self.0 {
+            WatchSource::Memfs(events, path) => {
+                let deadline = timeout.map(|x| Instant::now() + x);
+                loop {
+                    let event = match deadline {
+                        Some(x) => events.recv_timeout(x.saturating_duration_since(Instant::now())).ok()?,
+                        None => events.recv().ok()?,
+                    };
+                    if let Some(event) = within(path, event) {
+                        return Some(event);
+                    }
+                }
+            },
+            WatchSource::Stdfs(watch) => watch.recv(timeout),
+        }
+    }
+}
+
+impl Iterator for VfsWatcher
+{
+    type Item = VfsEvent;
+
+    /// Block until the next event occurs
+    ///
+    /// * Memfs iteration ends once the Memfs instance has been dropped
+    fn next(&mut self) -> Option<VfsEvent>
+    {
+        self.recv(None)
+    }
+}
+
+// Limit the given event to the given path reporting moves into or out of the path as creates or
+// removes and skipping permission and ownership changes as Stdfs can't report them alike.
+fn within(path: &Path, event: VfsEvent) -> Option<VfsEvent>
+{
+    match event {
+        VfsEvent::Create(ref x) | VfsEvent::Write(ref x) | VfsEvent::Remove(ref x) => {
+            x.starts_with(path).then_some(event)
+        },
+        VfsEvent::Rename(from, to) => match (from.starts_with(path), to.starts_with(path)) {
+            (true, true) => Some(VfsEvent::Rename(from, to)),
+            (true, false) => Some(VfsEvent::Remove(from)),
+            (false, true) => Some(VfsEvent::Create(to)),
+            (false, false) => None,
+        },
+        VfsEvent::Chmod(..) | VfsEvent::Chown(..) => None,
+    }
+}
+
+// Take a new snapshot adding any paths that differ from the last one to the changed set and
+// returning true if there were any.
+fn changes<T: VirtualFileSystem>(vfs: &T, paths: &[PathBuf], last: &mut Snapshot, changed: &mut BTreeSet<PathBuf>)
+    -> bool
+{
+    let next = snapshot(vfs, paths);
+    let len = changed.len();
+    changed.extend(next.iter().filter(|(k, v)| last.get(*k) != Some(v)).map(|(k, _)| k.clone()));
+    changed.extend(last.keys().filter(|k| !next.contains_key(*k)).cloned());
+    *last = next;
+    changed.len() > len
+}
+
+// Record the state of every path in the given trees. Entries that vanish while being traversed
+// are skipped as they'll be picked up as removed on the next snapshot.
+fn snapshot<T: VirtualFileSystem>(vfs: &T, paths: &[PathBuf]) -> Snapshot
+{
+    let mut snapshot = HashMap::new();
+    for path in paths {
+        if !vfs.exists(path) {
+            continue;
+        }
+        if let Ok(entries) = vfs.entries(path) {
+            for entry in entries.into_iter().flatten() {
+                let state = match entry.is_dir() && !entry.is_symlink() {
+                    true => (0, SystemTime::UNIX_EPOCH, entry.mode()),
+                    false => (entry.size(), entry.modified(), entry.mode()),
+                };
+                snapshot.insert(entry.path_buf(), state);
+            }
+        }
+    }
+    snapshot
+}
+
+// Unit tests
+// -------------------------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests
+{
+    use std::{sync::Arc, thread, time::Duration};
+
+    use crate::prelude::*;
+
+    #[test]
+    fn test_vfs_watch()
+    {
+        test_watch(assert_vfs_setup!(Vfs::memfs()));
+        test_watch(assert_vfs_setup!(Vfs::stdfs()));
+    }
+    fn test_watch((vfs, tmpdir): (Vfs, PathBuf))
+    {
+        let dir1 = tmpdir.mash("dir1");
+        let file1 = tmpdir.mash("file1");
+        let file2 = dir1.mash("file2");
+        let file3 = tmpdir.mash("file3");
+        let file4 = dir1.mash("file4");
+        let wait = Duration::from_secs(2);
+
+        // Doesn't exist
+        assert_eq!(
+            vfs.watch(tmpdir.mash("foo")).unwrap_err().to_string(),
+            PathError::does_not_exist(tmpdir.mash("foo")).to_string()
+        );
+
+        // Create and write
+        let mut watcher = vfs.watch(&tmpdir).unwrap();
+        assert_vfs_write_all!(vfs, &file1, "foo");
+        assert_eq!(watcher.recv_timeout(wait), Some(VfsEvent::Create(file1.clone())));
+        assert_eq!(watcher.recv_timeout(wait), Some(VfsEvent::Write(file1.clone())));
+
+        // New directories are watched
+        assert_vfs_mkdir_p!(vfs, &dir1);
+        assert_eq!(watcher.recv_timeout(wait), Some(VfsEvent::Create(dir1.clone())));
+        assert_vfs_write_all!(vfs, &file2, "bar");
+        assert_eq!(watcher.recv_timeout(wait), Some(VfsEvent::Create(file2.clone())));
+        assert_eq!(watcher.recv_timeout(wait), Some(VfsEvent::Write(file2.clone())));
+
+        // Permission changes aren't reported
+        assert!(vfs.chmod(&file1, 0o600).is_ok());
+        assert!(vfs.move_p(&file1, &file3).is_ok());
+        assert!(vfs.remove(&file2).is_ok());
+        assert_eq!(watcher.recv_timeout(wait), Some(VfsEvent::Rename(file1.clone(), file3.clone())));
+        assert_eq!(watcher.recv_timeout(wait), Some(VfsEvent::Remove(file2.clone())));
+
+        // Moves into a watched path are reported as creates
+        let mut watcher2 = vfs.watch(&dir1).unwrap();
+        assert!(vfs.move_p(&file3, &file4).is_ok());
+        assert_eq!(watcher.recv_timeout(wait), Some(VfsEvent::Rename(file3.clone(), file4.clone())));
+        assert_eq!(watcher2.recv_timeout(wait), Some(VfsEvent::Create(file4.clone())));
+        assert_eq!(watcher.try_recv(), None);
+        assert_eq!(watcher2.try_recv(), None);
+        drop(watcher2);
+
+        // Renamed directories are still watched under their new path
+        let dir2 = tmpdir.mash("dir2");
+        let file5 = dir2.mash("file5");
+        assert!(vfs.move_p(&dir1, &dir2).is_ok());
+        assert_eq!(watcher.recv_timeout(wait), Some(VfsEvent::Rename(dir1.clone(), dir2.clone())));
+        assert_vfs_mkfile!(vfs, &file5);
+        assert_eq!(watcher.recv_timeout(wait), Some(VfsEvent::Create(file5.clone())));
+        assert_eq!(watcher.try_recv(), None);
+
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+
+    #[test]
+    fn test_vfs_watch_run()
+    {
+        test_watch_run(assert_vfs_setup!(Vfs::memfs()));
+        test_watch_run(assert_vfs_setup!(Vfs::stdfs()));
+    }
+    fn test_watch_run((vfs, tmpdir): (Vfs, PathBuf))
+    {
+        let vfs = Arc::new(vfs);
+        let dir1 = tmpdir.mash("dir1");
+        let file1 = dir1.mash("file1");
+        let file2 = dir1.mash("file2");
+        let file3 = tmpdir.mash("file3");
+        assert_vfs_mkdir_p!(vfs, &dir1);
+        assert_vfs_write_all!(vfs, &file1, "foo");
+
+        // Bursts are coalesced into a single call and quiet periods separate calls
+        let writer = vfs.clone();
+        let (f1, f2, f3) = (file1.clone(), file2.clone(), file3.clone());
+        let thread = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(100));
+            writer.write_all(&f1, "foobar").unwrap();
+            writer.write_all(&f2, "bar").unwrap();
+            writer.write_all(&f3, "ignored").unwrap();
+            thread::sleep(Duration::from_millis(500));
+            writer.remove(&f1).unwrap();
+        });
+        let mut calls = vec![];
+        sys::watch_run(&*vfs, &[&dir1], Duration::from_millis(100), |paths| {
+            calls.push(paths.to_vec());
+            calls.len() < 2
+        })
+        .unwrap();
+        thread.join().unwrap();
+        assert_eq!(calls, vec![vec![file1.clone(), file2.clone()], vec![file1.clone()]]);
+
+        // Paths that don't exist yet are watched for creation
+        let dir2 = tmpdir.mash("dir2");
+        let writer = vfs.clone();
+        let d2 = dir2.clone();
+        let thread = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(100));
+            writer.mkdir_p(&d2).unwrap();
+        });
+        let mut calls = vec![];
+        sys::watch_run(&*vfs, &[&dir2], Duration::from_millis(20), |paths| {
+            calls.push(paths.to_vec());
+            false
+        })
+        .unwrap();
+        thread.join().unwrap();
+        assert_eq!(calls, vec![vec![dir2]]);
+
+        let vfs = Arc::try_unwrap(vfs).unwrap();
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+}
@@ -0,0 +1,126 @@
+use std::{
+    io::{BufRead, BufReader, Read},
+    path::Path,
+};
+
+use crate::{errors::*, sys::VirtualFileSystem};
+
+/// Describes the line, word and byte counts of a file
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct WcCounts
+{
+    pub lines: u64, // number of lines including a final line without a trailing newline
+    pub words: u64, // number of whitespace separated words
+    pub bytes: u64, // number of bytes
+}
+
+/// Returns the line, word and byte counts of the given file much like `wc`
+///
+/// * Handles path expansion and absolute path resolution
+/// * Streams the file's data rather than reading it all into memory
+/// * Counts a final line without a trailing newline so `lines` matches `read_lines` unlike `wc -l`
+/// * Words are separated by ASCII whitespace
+///
+/// ### Errors
+/// * PathError::IsNotFile(PathBuf) when the given path isn't a file
+/// * PathError::DoesNotExist(PathBuf) when the given path doesn't exist
+///
+/// ### Examples
+/// ```
+/// use rivia::prelude::*;
+///
+/// let vfs = Vfs::memfs();
+/// let file = vfs.root().mash("file");
+/// assert_vfs_write_all!(vfs, &file, "foo bar\nbaz");
+/// assert_eq!(sys::wc(&vfs, &file).unwrap(), sys::WcCounts { lines: 2, words: 3, bytes: 11 });
+/// ```
+pub fn wc<T: VirtualFileSystem, U: AsRef<Path>>(vfs: &T, path: U) -> RvResult<WcCounts>
+{
+    wc_reader(vfs.read(path)?)
+}
+
+/// Returns the line, word and byte counts of the given reader's data
+pub(crate) fn wc_reader<T: Read>(reader: T) -> RvResult<WcCounts>
+{
+    let mut reader = BufReader::new(reader);
+    let mut counts = WcCounts::default();
+    let mut in_word = false;
+    let mut last = b'\n';
+    loop {
+        let buf = reader.fill_buf()?;
+        if buf.is_empty() {
+            break;
+        }
+        for x in buf {
+            if *x == b'\n' {
+                counts.lines += 1;
+            }
+            if x.is_ascii_whitespace() {
+                in_word = false;
+            } else if !in_word {
+                in_word = true;
+                counts.words += 1;
+            }
+        }
+        last = buf[buf.len() - 1];
+        counts.bytes += buf.len() as u64;
+        let len = buf.len();
+        reader.consume(len);
+    }
+
+    // Count a final line without a trailing newline
+    if last != b'\n' {
+        counts.lines += 1;
+    }
+    Ok(counts)
+}
+
+// Unit tests
+// -------------------------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests
+{
+    use crate::prelude::*;
+
+    #[test]
+    fn test_vfs_wc()
+    {
+        test_wc(assert_vfs_setup!(Vfs::memfs()));
+        test_wc(assert_vfs_setup!(Vfs::stdfs()));
+    }
+    fn test_wc((vfs, tmpdir): (Vfs, PathBuf))
+    {
+        let dir1 = tmpdir.mash("dir1");
+        let file1 = tmpdir.mash("file1");
+        let file2 = tmpdir.mash("file2");
+
+        // Errors
+        assert_eq!(
+            sys::wc(&vfs, &file1).unwrap_err().downcast_ref::<PathError>(),
+            Some(&PathError::does_not_exist(&file1))
+        );
+        assert_vfs_mkdir_p!(vfs, &dir1);
+        assert_eq!(
+            sys::wc(&vfs, &dir1).unwrap_err().downcast_ref::<PathError>(),
+            Some(&PathError::is_not_file(&dir1))
+        );
+
+        // Empty file
+        assert_vfs_mkfile!(vfs, &file1);
+        assert_eq!(sys::wc(&vfs, &file1).unwrap(), sys::WcCounts::default());
+        assert_eq!(vfs.line_count(&file1).unwrap(), 0);
+
+        // Trailing newline and mixed whitespace
+        assert!(vfs.write_all(&file1, "  foo\tbar \n\nbaz\n").is_ok());
+        assert_eq!(sys::wc(&vfs, &file1).unwrap(), sys::WcCounts { lines: 3, words: 3, bytes: 16 });
+        assert_eq!(vfs.line_count(&file1).unwrap(), vfs.read_lines(&file1).unwrap().len() as u64);
+
+        // Spans several buffers without a trailing newline
+        let data = "foo bar\n".repeat(10_000) + "baz";
+        assert_vfs_write_all!(vfs, &file2, &data);
+        assert_eq!(sys::wc(&vfs, &file2).unwrap(), sys::WcCounts { lines: 10_001, words: 20_001, bytes: 80_003 });
+        assert_eq!(vfs.line_count(&file2).unwrap(), 10_001);
+
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+}
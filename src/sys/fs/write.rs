@@ -0,0 +1,132 @@
+use std::{
+    path::{Path, PathBuf},
+    process,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use crate::{errors::RvResult, sys::PathExt};
+
+// Counter keeping the temp sibling names of concurrent atomic writes unique within the process
+static TMP_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// Provides a builder pattern for flexibly writing data to files
+///
+/// Use the Vfs functions `write_b` to create a new instance followed by one or more options and
+/// complete the operation by calling `exec`.
+///
+/// ```
+/// use rivia::prelude::*;
+///
+/// let vfs = Memfs::new();
+/// let file = vfs.root().mash("file");
+/// assert!(vfs.write_b(&file, "this is a test").unwrap().exec().is_ok());
+/// assert_vfs_read_all!(vfs, &file, "this is a test");
+/// ```
+pub struct Writer
+{
+    pub(crate) opts: WriteOpts,
+    pub(crate) exec: Box<dyn Fn(WriteOpts) -> RvResult<()>>, // provider callback
+}
+
+// Internal type used to encapsulate just the options. This separates the provider implementation
+// from the options allowing for sharing options between different vfs providers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct WriteOpts
+{
+    pub(crate) path: PathBuf, // file to write
+    pub(crate) data: Vec<u8>, // data to write
+    pub(crate) atomic: bool,  // write to a temp sibling and rename it into place
+}
+
+impl Writer
+{
+    /// Update the `atomic` option
+    ///
+    /// * Default: false
+    /// * When `true` the data is written to a temporary sibling file which is then renamed into
+    ///   place so that readers only ever see the old or the new data never a partial write
+    /// * See the Vfs function `write_all_atomic` for details
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Memfs::new();
+    /// let file = vfs.root().mash("file");
+    /// assert!(vfs.write_b(&file, "this is a test").unwrap().atomic(true).exec().is_ok());
+    /// assert_vfs_read_all!(vfs, &file, "this is a test");
+    /// ```
+    pub fn atomic(mut self, yes: bool) -> Self
+    {
+        self.opts.atomic = yes;
+        self
+    }
+
+    /// Execute the [`Writer`] builder current options
+    ///
+    /// ### Errors
+    /// * PathError::IsNotDir(PathBuf) when the given path's parent exists but is not a directory
+    /// * PathError::DoesNotExist(PathBuf) when the given path's parent doesn't exist
+    /// * PathError::IsNotFile(PathBuf) when the given path exists but is not a file
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Memfs::new();
+    /// let file = vfs.root().mash("file");
+    /// assert!(vfs.write_b(&file, "this is a test").unwrap().exec().is_ok());
+    /// assert_vfs_read_all!(vfs, &file, "this is a test");
+    /// ```
+    pub fn exec(&self) -> RvResult<()>
+    {
+        (self.exec)(self.opts.clone())
+    }
+}
+
+// Returns a hidden temporary sibling of the given file for an atomic write to rename into place
+pub(crate) fn tmp_sibling(path: &Path) -> RvResult<PathBuf>
+{
+    let n = TMP_COUNTER.fetch_add(1, Ordering::SeqCst);
+    Ok(path.dir()?.mash(format!(".{}.{}.{}.tmp", path.base()?, process::id(), n)))
+}
+
+// Unit tests
+// -------------------------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests
+{
+    use crate::prelude::*;
+
+    #[test]
+    fn test_vfs_write_b()
+    {
+        test_write_b(assert_vfs_setup!(Vfs::memfs()));
+        test_write_b(assert_vfs_setup!(Vfs::stdfs()));
+    }
+    fn test_write_b((vfs, tmpdir): (Vfs, PathBuf))
+    {
+        let dir1 = tmpdir.mash("dir1");
+        let file1 = tmpdir.mash("file1");
+        assert_vfs_mkdir_p!(vfs, &dir1);
+
+        // Errors
+        for atomic in [false, true] {
+            let err = vfs.write_b(&dir1, "foo").unwrap().atomic(atomic).exec().unwrap_err();
+            assert_eq!(err.downcast_ref::<PathError>(), Some(&PathError::is_not_file(&dir1)));
+            let err = vfs.write_b(tmpdir.mash("foo/bar"), "foo").unwrap().atomic(atomic).exec().unwrap_err();
+            assert_eq!(err.downcast_ref::<PathError>(), Some(&PathError::does_not_exist(tmpdir.mash("foo"))));
+        }
+
+        // Create and replace with and without atomic
+        assert!(vfs.write_b(&file1, "foo").unwrap().exec().is_ok());
+        assert_vfs_read_all!(vfs, &file1, "foo");
+        assert!(vfs.write_b(&file1, "foobar").unwrap().atomic(true).exec().is_ok());
+        assert_vfs_read_all!(vfs, &file1, "foobar");
+        assert!(vfs.write_b(&file1, "bar").unwrap().atomic(false).exec().is_ok());
+        assert_vfs_read_all!(vfs, &file1, "bar");
+        assert_eq!(vfs.paths(&tmpdir).unwrap(), vec![dir1, file1]);
+
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+}
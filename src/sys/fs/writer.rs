@@ -0,0 +1,183 @@
+use std::{
+    fs::File,
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use crate::{errors::RvResult, sys::MemfsFile};
+
+/// Provides a handle to a file opened for writing with the Vfs functions `write` and `append`
+///
+/// * Tracks the path being written to and the number of bytes written through the handle
+/// * `sync` flushes the written data through to the backend's storage
+///
+/// ### Examples
+/// ```
+/// use rivia::prelude::*;
+///
+/// let vfs = Vfs::memfs();
+/// let file = vfs.root().mash("file");
+/// let mut f = vfs.write(&file).unwrap();
+/// f.write_all(b"foobar").unwrap();
+/// f.sync().unwrap();
+/// assert_eq!(f.path(), &file);
+/// assert_eq!(f.bytes_written(), 6);
+/// assert_vfs_read_all!(vfs, &file, "foobar");
+/// ```
+#[derive(Debug)]
+pub struct VfsWriter
+{
+    path: PathBuf,       // abs path of the file being written to
+    written: u64,        // number of bytes written through this handle
+    handle: WriteHandle, // backend specific file handle
+}
+
+// Backend specific file handles
+#[derive(Debug)]
+enum WriteHandle
+{
+    Stdfs(File),
+    Memfs(MemfsFile),
+}
+
+impl VfsWriter
+{
+    /// Create a new writer for the given Stdfs file
+    pub(crate) fn stdfs<T: Into<PathBuf>>(path: T, file: File) -> Self
+    {
+        Self { path: path.into(), written: 0, handle: WriteHandle::Stdfs(file) }
+    }
+
+    /// Create a new writer for the given Memfs file
+    pub(crate) fn memfs<T: Into<PathBuf>>(path: T, file: MemfsFile) -> Self
+    {
+        Self { path: path.into(), written: 0, handle: WriteHandle::Memfs(file) }
+    }
+
+    /// Returns the absolute path of the file being written to
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let f = vfs.write("file").unwrap();
+    /// assert_eq!(f.path(), vfs.root().mash("file"));
+    /// ```
+    pub fn path(&self) -> &Path
+    {
+        &self.path
+    }
+
+    /// Returns the number of bytes written through this handle
+    ///
+    /// * Appending only counts the bytes written through this handle not the existing data
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// assert_vfs_write_all!(vfs, "file", "foo");
+    /// let mut f = vfs.append("file").unwrap();
+    /// f.write_all(b"bar").unwrap();
+    /// assert_eq!(f.bytes_written(), 3);
+    /// ```
+    pub fn bytes_written(&self) -> u64
+    {
+        self.written
+    }
+
+    /// Flush the written data through to the backend's storage
+    ///
+    /// * Stdfs flushes the data and metadata to disk with `fsync`
+    /// * Memfs publishes the data to the filesystem as `flush` does
+    ///
+    /// ### Examples
+    /// ```
+    /// use rivia::prelude::*;
+    ///
+    /// let vfs = Vfs::memfs();
+    /// let mut f = vfs.write("file").unwrap();
+    /// f.write_all(b"foobar").unwrap();
+    /// assert!(f.sync().is_ok());
+    /// assert_vfs_read_all!(vfs, "file", "foobar");
+    /// ```
+    pub fn sync(&mut self) -> RvResult<()>
+    {
+        match &mut self.handle {
+            WriteHandle::Stdfs(x) => {
+                x.flush()?;
+                x.sync_all()?;
+            },
+            WriteHandle::Memfs(x) => x.sync()?,
+        }
+        Ok(())
+    }
+}
+
+impl Write for VfsWriter
+{
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize>
+    {
+        let len = match &mut self.handle {
+            WriteHandle::Stdfs(x) => x.write(buf)?,
+            WriteHandle::Memfs(x) => x.write(buf)?,
+        };
+        self.written += len as u64;
+        Ok(len)
+    }
+
+    fn flush(&mut self) -> io::Result<()>
+    {
+        match &mut self.handle {
+            WriteHandle::Stdfs(x) => x.flush(),
+            WriteHandle::Memfs(x) => x.flush(),
+        }
+    }
+}
+
+// Unit tests
+// -------------------------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests
+{
+    use crate::prelude::*;
+
+    #[test]
+    fn test_vfs_writer()
+    {
+        test_writer(assert_vfs_setup!(Vfs::memfs()));
+        test_writer(assert_vfs_setup!(Vfs::stdfs()));
+    }
+    fn test_writer((vfs, tmpdir): (Vfs, PathBuf))
+    {
+        let dir1 = tmpdir.mash("dir1");
+        let file1 = tmpdir.mash("file1");
+
+        // Errors
+        assert_vfs_mkdir_p!(vfs, &dir1);
+        assert!(vfs.write(&dir1).is_err());
+        assert_eq!(vfs.append(&dir1).unwrap_err().downcast_ref::<PathError>(), Some(&PathError::is_not_file(&dir1)));
+
+        // Write reports its path and counts the bytes written
+        let mut f = vfs.write(&file1).unwrap();
+        assert_eq!(f.path(), &file1);
+        assert_eq!(f.bytes_written(), 0);
+        f.write_all(b"foo").unwrap();
+        write!(f, "{}", 42).unwrap();
+        assert_eq!(f.bytes_written(), 5);
+        assert!(f.sync().is_ok());
+        assert_vfs_read_all!(vfs, &file1, "foo42");
+
+        // Append only counts the bytes it writes
+        let mut f = vfs.append(&file1).unwrap();
+        assert_eq!(f.path(), &file1);
+        f.write_all(b"123").unwrap();
+        assert_eq!(f.bytes_written(), 3);
+        assert!(f.sync().is_ok());
+        assert_vfs_read_all!(vfs, &file1, "foo42123");
+
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+}
@@ -10,4 +10,6 @@ mod fs;
 pub use fs::*;
 
 // Export directly
+pub mod format;
+pub mod report;
 pub mod user;
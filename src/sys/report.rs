@@ -0,0 +1,406 @@
+//! Provides disk usage reporting built on the Vfs traversal
+//!
+//! * All reports are gathered in a single traversal of the given tree
+//! * Links are not followed and only regular files are counted
+//!
+//! ### How to use the Rivia `report` module
+//! ```
+//! use rivia::prelude::*;
+//!
+//! let vfs = Vfs::memfs();
+//! assert_vfs_write_all!(vfs, "file1.txt", "foo");
+//! assert_vfs_write_all!(vfs, "file2.txt", "foobar");
+//! let report = report::scan(&vfs, vfs.root(), 1).unwrap();
+//! assert_eq!(report.size, 9);
+//! assert_eq!(report.largest, vec![report::FileSize { path: vfs.root().mash("file2.txt"), size: 6 }]);
+//! ```
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    errors::*,
+    sys::{Entry, VirtualFileSystem},
+};
+
+/// Describes a single file and its size in bytes
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct FileSize {
+    pub path: PathBuf, // abs path of the file
+    pub size: u64,     // size of the file in bytes
+}
+
+/// Describes the number of files and their total size in bytes for a single file extension
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct ExtSize {
+    pub ext: String,  // file extension without the dot or empty for files without one
+    pub count: usize, // number of files with this extension
+    pub size: u64,    // total size in bytes of files with this extension
+}
+
+/// Describes the number of files and their total size in bytes for a single language
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct LangSize {
+    pub lang: String, // language name as given by `language`
+    pub count: usize, // number of files in this language
+    pub size: u64,    // total size in bytes of files in this language
+}
+
+/// Describes the number of files and their total size in bytes for a single owner
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct OwnerSize {
+    pub count: usize, // number of files with this owner
+    pub size: u64,    // total size in bytes of files with this owner
+}
+
+/// Describes the disk usage of a tree
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Report {
+    pub files: usize,            // number of files in the tree
+    pub size: u64,               // total size in bytes of all files in the tree
+    pub largest: Vec<FileSize>,  // largest files sorted by size descending
+    pub histogram: Vec<ExtSize>, // per extension totals sorted by size descending
+}
+
+/// Returns the file counts and total sizes for each extension in the given tree
+///
+/// * Handles path expansion and absolute path resolution
+/// * Keyed by extension without the dot or empty for files without one
+/// * Use `language` to map the extensions to language names or `by_language` for the totals
+///
+/// ### Examples
+/// ```
+/// use rivia::prelude::*;
+///
+/// let vfs = Vfs::memfs();
+/// assert_vfs_write_all!(vfs, "main.rs", "foo");
+/// assert_vfs_write_all!(vfs, "lib.rs", "foobar");
+/// let exts = report::by_extension(&vfs, vfs.root()).unwrap();
+/// assert_eq!(exts["rs"], report::ExtSize { ext: "rs".to_string(), count: 2, size: 9 });
+/// assert_eq!(report::language("rs"), Some("Rust"));
+/// ```
+pub fn by_extension<T: VirtualFileSystem, U: AsRef<Path>>(vfs: &T, root: U) -> RvResult<HashMap<String, ExtSize>> {
+    Ok(scan(vfs, root, 0)?.histogram.into_iter().map(|x| (x.ext.clone(), x)).collect())
+}
+
+/// Returns the file counts and total sizes for each language in the given tree
+///
+/// * Handles path expansion and absolute path resolution
+/// * Extensions are mapped to languages with `language` and files in no language are skipped
+/// * Sorted by size descending then by language
+///
+/// ### Examples
+/// ```
+/// use rivia::prelude::*;
+///
+/// let vfs = Vfs::memfs();
+/// assert_vfs_write_all!(vfs, "main.rs", "foo");
+/// assert_vfs_write_all!(vfs, "main.c", "foo");
+/// assert_vfs_write_all!(vfs, "main.h", "foobar");
+/// assert_vfs_write_all!(vfs, "data.bin", "foobar");
+/// assert_eq!(report::by_language(&vfs, vfs.root()).unwrap(), vec![
+///     report::LangSize { lang: "C".to_string(), count: 2, size: 9 },
+///     report::LangSize { lang: "Rust".to_string(), count: 1, size: 3 },
+/// ]);
+/// ```
+pub fn by_language<T: VirtualFileSystem, U: AsRef<Path>>(vfs: &T, root: U) -> RvResult<Vec<LangSize>> {
+    let mut langs: HashMap<&str, LangSize> = HashMap::new();
+    for ext in scan(vfs, root, 0)?.histogram {
+        if let Some(lang) = language(&ext.ext) {
+            let totals =
+                langs.entry(lang).or_insert_with(|| LangSize { lang: lang.to_string(), ..Default::default() });
+            totals.count += ext.count;
+            totals.size += ext.size;
+        }
+    }
+    let mut langs: Vec<LangSize> = langs.into_values().collect();
+    langs.sort_by(|x, y| y.size.cmp(&x.size).then_with(|| x.lang.cmp(&y.lang)));
+    Ok(langs)
+}
+
+/// Returns the per extension file counts and total sizes for the given tree
+///
+/// * Handles path expansion and absolute path resolution
+/// * Sorted by size descending then by extension
+///
+/// ### Examples
+/// ```
+/// use rivia::prelude::*;
+///
+/// let vfs = Vfs::memfs();
+/// assert_vfs_write_all!(vfs, "file1.txt", "foo");
+/// assert_vfs_write_all!(vfs, "file2.txt", "foobar");
+/// assert_eq!(report::histogram(&vfs, vfs.root()).unwrap(), vec![report::ExtSize {
+///     ext: "txt".to_string(),
+///     count: 2,
+///     size: 9
+/// }]);
+/// ```
+pub fn histogram<T: VirtualFileSystem, U: AsRef<Path>>(vfs: &T, root: U) -> RvResult<Vec<ExtSize>> {
+    Ok(scan(vfs, root, 0)?.histogram)
+}
+
+/// Returns the language name for the given file extension if it is a well known one
+///
+/// * Extensions are matched case insensitively and without the dot
+///
+/// ### Examples
+/// ```
+/// use rivia::prelude::*;
+///
+/// assert_eq!(report::language("rs"), Some("Rust"));
+/// assert_eq!(report::language("PY"), Some("Python"));
+/// assert_eq!(report::language("bin"), None);
+/// ```
+pub fn language(ext: &str) -> Option<&'static str> {
+    Some(match ext.to_lowercase().as_str() {
+        "c" | "h" => "C",
+        "cc" | "cpp" | "cxx" | "hh" | "hpp" | "hxx" => "C++",
+        "cs" => "C#",
+        "css" | "scss" | "sass" | "less" => "CSS",
+        "go" => "Go",
+        "hs" => "Haskell",
+        "htm" | "html" => "HTML",
+        "java" => "Java",
+        "js" | "cjs" | "mjs" | "jsx" => "JavaScript",
+        "json" => "JSON",
+        "kt" | "kts" => "Kotlin",
+        "lua" => "Lua",
+        "md" | "markdown" => "Markdown",
+        "php" => "PHP",
+        "pl" | "pm" => "Perl",
+        "py" | "pyi" => "Python",
+        "rb" => "Ruby",
+        "rs" => "Rust",
+        "sh" | "bash" | "zsh" => "Shell",
+        "sql" => "SQL",
+        "swift" => "Swift",
+        "toml" => "TOML",
+        "ts" | "tsx" | "mts" | "cts" => "TypeScript",
+        "xml" => "XML",
+        "yaml" | "yml" => "YAML",
+        _ => return None,
+    })
+}
+
+/// Returns the `n` largest files in the given tree
+///
+/// * Handles path expansion and absolute path resolution
+/// * Sorted by size descending then by path
+///
+/// ### Examples
+/// ```
+/// use rivia::prelude::*;
+///
+/// let vfs = Vfs::memfs();
+/// assert_vfs_write_all!(vfs, "file1", "foo");
+/// assert_vfs_write_all!(vfs, "file2", "foobar");
+/// let largest = report::largest(&vfs, vfs.root(), 1).unwrap();
+/// assert_eq!(largest, vec![report::FileSize { path: vfs.root().mash("file2"), size: 6 }]);
+/// ```
+pub fn largest<T: VirtualFileSystem, U: AsRef<Path>>(vfs: &T, root: U, n: usize) -> RvResult<Vec<FileSize>> {
+    Ok(scan(vfs, root, n)?.largest)
+}
+
+/// Returns the file counts and total sizes for each `(uid, gid)` owner in the given tree
+///
+/// * Handles path expansion and absolute path resolution
+/// * Useful for spotting files owned by the wrong user
+///
+/// ### Examples
+/// ```
+/// use rivia::prelude::*;
+///
+/// let vfs = Vfs::memfs();
+/// let file = vfs.root().mash("file");
+/// assert_vfs_write_all!(vfs, &file, "foo");
+/// assert!(vfs.chown(&file, 5, 5).is_ok());
+/// let owners = report::ownership(&vfs, vfs.root()).unwrap();
+/// assert_eq!(owners[&(5, 5)], report::OwnerSize { count: 1, size: 3 });
+/// ```
+pub fn ownership<T: VirtualFileSystem, U: AsRef<Path>>(
+    vfs: &T, root: U,
+) -> RvResult<HashMap<(u32, u32), OwnerSize>> {
+    let mut owners: HashMap<(u32, u32), OwnerSize> = HashMap::new();
+    for entry in vfs.entries(root)? {
+        let entry = entry?;
+        if !entry.is_file() || entry.is_symlink() {
+            continue;
+        }
+        let totals = owners.entry(vfs.owner(entry.path())?).or_default();
+        totals.count += 1;
+        totals.size += entry.size();
+    }
+    Ok(owners)
+}
+
+/// Returns a full disk usage report for the given tree including the `n` largest files
+///
+/// * Handles path expansion and absolute path resolution
+///
+/// ### Examples
+/// ```
+/// use rivia::prelude::*;
+///
+/// let vfs = Vfs::memfs();
+/// assert_vfs_write_all!(vfs, "file1.txt", "foo");
+/// assert_vfs_write_all!(vfs, "file2", "foobar");
+/// let report = report::scan(&vfs, vfs.root(), 10).unwrap();
+/// assert_eq!(report.files, 2);
+/// assert_eq!(report.size, 9);
+/// assert_eq!(report.largest.len(), 2);
+/// assert_eq!(report.histogram.len(), 2);
+/// ```
+pub fn scan<T: VirtualFileSystem, U: AsRef<Path>>(vfs: &T, root: U, n: usize) -> RvResult<Report> {
+    let mut report = Report::default();
+    let mut exts: HashMap<String, ExtSize> = HashMap::new();
+
+    for entry in vfs.entries(root)? {
+        let entry = entry?;
+        if !entry.is_file() || entry.is_symlink() {
+            continue;
+        }
+        let size = entry.size();
+        report.files += 1;
+        report.size += size;
+
+        // Accumulate extension totals
+        let ext = entry.path().extension().map(|x| x.to_string_lossy().to_string()).unwrap_or_default();
+        let totals = exts.entry(ext.clone()).or_insert_with(|| ExtSize { ext, ..Default::default() });
+        totals.count += 1;
+        totals.size += size;
+
+        report.largest.push(FileSize { path: entry.path_buf(), size });
+    }
+
+    // Keep only the largest files
+    report.largest.sort_by(|x, y| y.size.cmp(&x.size).then_with(|| x.path.cmp(&y.path)));
+    report.largest.truncate(n);
+
+    report.histogram = exts.into_values().collect();
+    report.histogram.sort_by(|x, y| y.size.cmp(&x.size).then_with(|| x.ext.cmp(&y.ext)));
+    Ok(report)
+}
+
+// Unit tests
+// -------------------------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    #[test]
+    fn test_vfs_scan() {
+        test_scan(assert_vfs_setup!(Vfs::memfs()));
+        test_scan(assert_vfs_setup!(Vfs::stdfs()));
+    }
+    fn test_scan((vfs, tmpdir): (Vfs, PathBuf)) {
+        let dir1 = tmpdir.mash("dir1");
+        let file1 = tmpdir.mash("file1.txt");
+        let file2 = dir1.mash("file2.txt");
+        let file3 = dir1.mash("file3.rs");
+        let file4 = dir1.mash("file4");
+        let link1 = tmpdir.mash("link1");
+
+        assert_vfs_mkdir_p!(vfs, &dir1);
+        assert_vfs_write_all!(vfs, &file1, "1");
+        assert_vfs_write_all!(vfs, &file2, "1234");
+        assert_vfs_write_all!(vfs, &file3, "123456");
+        assert_vfs_write_all!(vfs, &file4, "12");
+        assert_vfs_symlink!(vfs, &link1, &file3);
+        assert_eq!(vfs.entry(&link1).unwrap().size(), 6);
+
+        // Links aren't counted
+        let report = report::scan(&vfs, &tmpdir, 2).unwrap();
+        assert_eq!(report.files, 4);
+        assert_eq!(report.size, 13);
+        assert_eq!(report.largest, vec![
+            report::FileSize { path: file3.clone(), size: 6 },
+            report::FileSize { path: file2.clone(), size: 4 },
+        ]);
+        assert_eq!(report.histogram, vec![
+            report::ExtSize { ext: "rs".to_string(), count: 1, size: 6 },
+            report::ExtSize { ext: "txt".to_string(), count: 2, size: 5 },
+            report::ExtSize { ext: "".to_string(), count: 1, size: 2 },
+        ]);
+
+        // Helpers match the full report
+        assert_eq!(report::largest(&vfs, &tmpdir, 2).unwrap(), report.largest);
+        assert_eq!(report::histogram(&vfs, &tmpdir).unwrap(), report.histogram);
+        let exts = report::by_extension(&vfs, &tmpdir).unwrap();
+        assert_eq!(exts.len(), 3);
+        assert_eq!(exts["txt"], report::ExtSize { ext: "txt".to_string(), count: 2, size: 5 });
+        assert_eq!(exts[""], report::ExtSize { ext: "".to_string(), count: 1, size: 2 });
+
+        // Languages skip files in no language
+        assert_eq!(report::by_language(&vfs, &tmpdir).unwrap(), vec![report::LangSize {
+            lang: "Rust".to_string(),
+            count: 1,
+            size: 6
+        }]);
+
+        // Ties are sorted by path
+        assert!(vfs.write_all(&file1, "1234").is_ok());
+        assert_eq!(report::largest(&vfs, &tmpdir, 3).unwrap(), vec![
+            report::FileSize { path: file3, size: 6 },
+            report::FileSize { path: file2, size: 4 },
+            report::FileSize { path: file1, size: 4 },
+        ]);
+
+        // Doesn't exist
+        assert!(report::scan(&vfs, tmpdir.mash("foo"), 1).is_err());
+        assert!(report::by_extension(&vfs, tmpdir.mash("foo")).is_err());
+        assert!(report::by_language(&vfs, tmpdir.mash("foo")).is_err());
+
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+
+    #[test]
+    fn test_language() {
+        assert_eq!(report::language("rs"), Some("Rust"));
+        assert_eq!(report::language("RS"), Some("Rust"));
+        assert_eq!(report::language("h"), Some("C"));
+        assert_eq!(report::language("hpp"), Some("C++"));
+        assert_eq!(report::language("yml"), Some("YAML"));
+        assert_eq!(report::language(""), None);
+        assert_eq!(report::language(".rs"), None);
+        assert_eq!(report::language("txt"), None);
+    }
+
+    #[test]
+    fn test_vfs_ownership() {
+        test_ownership(assert_vfs_setup!(Vfs::memfs()));
+        test_ownership(assert_vfs_setup!(Vfs::stdfs()));
+    }
+    fn test_ownership((vfs, tmpdir): (Vfs, PathBuf)) {
+        let dir1 = tmpdir.mash("dir1");
+        let file1 = tmpdir.mash("file1");
+        let file2 = dir1.mash("file2");
+        let link1 = tmpdir.mash("link1");
+
+        assert_vfs_mkdir_p!(vfs, &dir1);
+        assert_vfs_write_all!(vfs, &file1, "1");
+        assert_vfs_write_all!(vfs, &file2, "1234");
+        assert_vfs_symlink!(vfs, &link1, &file2);
+        let owner = vfs.owner(&file1).unwrap();
+
+        // Links aren't counted
+        let owners = report::ownership(&vfs, &tmpdir).unwrap();
+        assert_eq!(owners.len(), 1);
+        assert_eq!(owners[&owner], report::OwnerSize { count: 2, size: 5 });
+
+        // Only memfs can change to arbitrary ids without privileges
+        if let Vfs::Memfs(_) = vfs {
+            assert!(vfs.chown(&file2, 5, 6).is_ok());
+            let owners = report::ownership(&vfs, &tmpdir).unwrap();
+            assert_eq!(owners.len(), 2);
+            assert_eq!(owners[&owner], report::OwnerSize { count: 1, size: 1 });
+            assert_eq!(owners[&(5, 6)], report::OwnerSize { count: 1, size: 4 });
+        }
+
+        // Doesn't exist
+        assert!(report::ownership(&vfs, tmpdir.mash("foo")).is_err());
+
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+}
@@ -283,6 +283,42 @@ pub fn from_uid(uid: u32) -> RvResult<User> {
     }
 }
 
+/// Get the group id for the given group name
+///
+/// ### Errors
+/// * UserError::GroupDoesNotExistByName(String) when the group doesn't exist
+///
+/// ### Examples
+/// ```
+/// use rivia::prelude::*;
+///
+/// assert_eq!(user::gid_from_name("root").unwrap(), 0);
+/// ```
+pub fn gid_from_name<T: AsRef<str>>(name: T) -> RvResult<u32> {
+    match nix::unistd::Group::from_name(name.as_ref())? {
+        Some(group) => Ok(group.gid.as_raw()),
+        None => Err(UserError::group_does_not_exist_by_name(name).into()),
+    }
+}
+
+/// Get the user id for the given user name
+///
+/// ### Errors
+/// * UserError::DoesNotExistByName(String) when the user doesn't exist
+///
+/// ### Examples
+/// ```
+/// use rivia::prelude::*;
+///
+/// assert_eq!(user::uid_from_name("root").unwrap(), 0);
+/// ```
+pub fn uid_from_name<T: AsRef<str>>(name: T) -> RvResult<u32> {
+    match nix::unistd::User::from_name(name.as_ref())? {
+        Some(user) => Ok(user.uid.as_raw()),
+        None => Err(UserError::does_not_exist_by_name(name).into()),
+    }
+}
+
 /// Switches back to the original user under the sudo mask with no way to go back
 ///
 /// ### Examples
@@ -523,6 +559,20 @@ mod tests {
         assert_eq!(home_dir.to_path_buf(), user::home_dir().unwrap().dir().unwrap());
     }
 
+    #[test]
+    fn test_from_name() {
+        assert_eq!(user::uid_from_name("root").unwrap(), 0);
+        assert_eq!(user::gid_from_name("root").unwrap(), 0);
+        assert_eq!(
+            user::uid_from_name("rivia-foo").unwrap_err().downcast_ref::<UserError>(),
+            Some(&UserError::does_not_exist_by_name("rivia-foo"))
+        );
+        assert_eq!(
+            user::gid_from_name("rivia-foo").unwrap_err().downcast_ref::<UserError>(),
+            Some(&UserError::group_does_not_exist_by_name("rivia-foo"))
+        );
+    }
+
     #[test]
     fn test_user_ids() {
         assert!(user::sudo_down().is_ok());
@@ -532,11 +582,11 @@ mod tests {
         assert!(user::geteuid() != 0);
         assert!(user::getegid() != 0);
         assert_eq!(user::getrids(user::getuid(), user::getgid()), (user::getuid(), user::getgid()));
-        assert_eq!(user::is_root(), false);
+        assert!(!user::is_root());
         assert!(user::from_uid(user::getuid()).is_ok());
         assert_ne!(user::name().unwrap(), "");
         assert!(user::current().is_ok());
-        assert_eq!(user::current().unwrap().is_root(), false);
+        assert!(!user::current().unwrap().is_root());
         // assert!(user::sudo().is_err());
         // assert!(user::setegid(user::getegid()).is_ok());
         // assert!(user::setgid(user::getgid()).is_ok());
@@ -4,7 +4,8 @@
 /// switch out a Memfs backend for a Stdfs backend without modifying the testing algorithms. Vfs
 /// tests will default to using the `testing::TEST_TEMP_DIR` as the root of testing and create a new
 /// directory inside that using the derived fully qualified function name or given function name
-/// when it can't be derived.
+/// when it can't be derived. The root can be moved elsewhere with the `RIVIA_TEST_TEMP_DIR`
+/// environment variable or `testing::set_temp_root` see `testing::temp_root`.
 ///
 /// ### Warning
 /// Since doc tests always have a default function name of `rust_out::main` its required to override
@@ -31,9 +32,10 @@ macro_rules! assert_vfs_setup {
         let vfs = $vfs;
 
         // Get the absolute path to the tmpdir
-        let abs = match vfs.abs(testing::TEST_TEMP_DIR) {
+        let root = testing::temp_root();
+        let abs = match vfs.abs(&root) {
             Ok(x) => x,
-            _ => panic_msg!("assert_vfs_setup!", "failed to get absolute path", testing::TEST_TEMP_DIR),
+            _ => panic_msg!("assert_vfs_setup!", "failed to get absolute path", &root),
         };
 
         // Optionally override the derived function name with the one given
@@ -263,6 +265,60 @@ macro_rules! assert_vfs_no_file {
     };
 }
 
+/// Assert that the given path exists and is a hard link i.e. its data has more than one name
+///
+/// ### Examples
+/// ```
+/// use rivia::prelude::*;
+///
+/// let vfs = Vfs::memfs();
+/// assert_vfs_mkfile!(vfs, "foo");
+/// assert_vfs_no_hardlink!(vfs, "foo");
+/// assert_vfs_hardlink!(vfs, "foo", "bar");
+/// assert_vfs_is_hardlink!(vfs, "foo");
+/// ```
+#[macro_export]
+macro_rules! assert_vfs_is_hardlink {
+    ($vfs:expr, $path:expr) => {
+        let target = match $vfs.abs($path) {
+            Ok(x) => x,
+            _ => panic_msg!("assert_vfs_is_hardlink!", "failed to get absolute path", $path),
+        };
+        if $vfs.exists(&target) {
+            if !$vfs.is_hardlink(&target) {
+                panic_msg!("assert_vfs_is_hardlink!", "exists but is not a hard link", &target);
+            }
+        } else {
+            panic_msg!("assert_vfs_is_hardlink!", "hard link doesn't exist", &target);
+        }
+    };
+}
+
+/// Assert that the given path isn't a hard link
+///
+/// ### Examples
+/// ```
+/// use rivia::prelude::*;
+///
+/// let vfs = Vfs::memfs();
+/// assert_vfs_mkfile!(vfs, "foo");
+/// assert_vfs_no_hardlink!(vfs, "foo");
+/// ```
+#[macro_export]
+macro_rules! assert_vfs_no_hardlink {
+    ($vfs:expr, $path:expr) => {
+        let target = match $vfs.abs($path) {
+            Ok(x) => x,
+            _ => panic_msg!("assert_vfs_no_hardlink!", "failed to get absolute path", $path),
+        };
+        if $vfs.exists(&target) {
+            if $vfs.is_hardlink(&target) {
+                panic_msg!("assert_vfs_no_hardlink!", "exists and is a hard link", &target);
+            }
+        }
+    };
+}
+
 /// Assert that the given path exists and is a symlink
 ///
 /// ### Examples
@@ -315,6 +371,44 @@ macro_rules! assert_vfs_no_symlink {
     };
 }
 
+/// Assert the creation of a hard link `dst` to `src`. If the link exists no change is made
+///
+/// ### Examples
+/// ```
+/// use rivia::prelude::*;
+///
+/// let vfs = Vfs::memfs();
+/// assert_vfs_write_all!(vfs, "foo", "foobar");
+/// assert_vfs_hardlink!(vfs, "foo", "bar");
+/// assert_vfs_read_all!(vfs, "bar", "foobar");
+/// ```
+#[macro_export]
+macro_rules! assert_vfs_hardlink {
+    ($vfs:expr, $src:expr, $dst:expr) => {
+        let dst = match $vfs.abs($dst) {
+            Ok(x) => x,
+            _ => panic_msg!("assert_vfs_hardlink!", "failed to get absolute path", $dst),
+        };
+        if $vfs.exists(&dst) {
+            if !$vfs.same_file($src, &dst) {
+                panic_msg!("assert_vfs_hardlink!", "is not a hard link to the source", &dst);
+            }
+        } else {
+            match $vfs.hardlink($src, &dst) {
+                Ok(x) => {
+                    if &x != &dst {
+                        panic_compare_msg!("assert_vfs_hardlink!", "created link path doesn't match", &x, &dst);
+                    }
+                },
+                _ => panic_msg!("assert_vfs_hardlink!", "failed while creating hard link", &dst),
+            };
+            if !$vfs.is_hardlink(&dst) {
+                panic_msg!("assert_vfs_hardlink!", "hard link doesn't exist", &dst);
+            }
+        }
+    };
+}
+
 /// Assert the creation of the given directory with the given mode
 ///
 /// ### Examples
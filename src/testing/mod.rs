@@ -10,17 +10,26 @@
 #[macro_use]
 mod assert;
 use std::{
+    env,
+    io::Read,
     panic,
-    sync::{Arc, Mutex},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, RwLock},
 };
 
 use lazy_static::lazy_static;
 
-use crate::errors::*;
+use crate::{
+    errors::*,
+    sys::{Entry, Memfs, Stdfs, VfsPolicy, VirtualFileSystem},
+};
 
 /// Defines the `tests/temp` location in the current project for file based testing if required
 pub const TEST_TEMP_DIR: &str = "tests/temp";
 
+/// Defines the environment variable that overrides the `TEST_TEMP_DIR` location
+pub const TEST_TEMP_ENV: &str = "RIVIA_TEST_TEMP_DIR";
+
 // Setup a simple counter to track if a custom panic handler should be used. Mutex is used to ensure
 // a single thread is accessing the buffer at a time, but mutex itself is not thread safe so we
 // wrap it in an Arc to provide that safety.
@@ -28,6 +37,11 @@ lazy_static! {
     static ref USE_PANIC_HANDLER: Arc<Mutex<usize>> = Arc::new(Mutex::new(0));
 }
 
+// Temp root set at runtime taking precedence over the environment and the default location
+lazy_static! {
+    static ref TEMP_ROOT: RwLock<Option<PathBuf>> = RwLock::new(None);
+}
+
 /// Capture any unwinding panics in a multi-thread safe way
 ///
 /// Doesn't catch aborts, that may occur while executing the given closure. Any panics captured will
@@ -65,3 +79,162 @@ pub fn capture_panic(f: impl FnOnce() + panic::UnwindSafe) -> RvResult<()> {
     }
     Ok(())
 }
+
+/// Set the root directory that `assert_vfs_setup!` creates test directories in
+///
+/// Useful when the default `tests/temp` inside the crate isn't writable e.g. when running from a
+/// read-only checkout or a separate build directory. Affects all threads of the process from then
+/// on so it is best called once before any tests run.
+///
+/// ### Examples
+/// ```
+/// use rivia::prelude::*;
+///
+/// testing::set_temp_root("/tmp/rivia");
+/// assert_eq!(testing::temp_root(), PathBuf::from("/tmp/rivia"));
+/// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::memfs(), "testing_func_set_temp_root");
+/// assert_eq!(tmpdir, PathBuf::from("/tmp/rivia/testing_func_set_temp_root"));
+/// ```
+pub fn set_temp_root<T: AsRef<Path>>(path: T) {
+    if let Ok(mut root) = TEMP_ROOT.write() {
+        *root = Some(path.as_ref().to_path_buf());
+    }
+}
+
+/// Returns the root directory that `assert_vfs_setup!` creates test directories in
+///
+/// * The root given to `set_temp_root` takes precedence
+/// * Followed by the `RIVIA_TEST_TEMP_DIR` environment variable when set and not empty
+/// * Defaults to `TEST_TEMP_DIR` i.e. `tests/temp` relative to the current directory
+/// * The environment is read on every call so it may be set after the process has started
+///
+/// ### Examples
+/// ```
+/// use rivia::prelude::*;
+///
+/// assert_eq!(testing::temp_root(), PathBuf::from(testing::TEST_TEMP_DIR));
+/// std::env::set_var(testing::TEST_TEMP_ENV, "/tmp/rivia");
+/// assert_eq!(testing::temp_root(), PathBuf::from("/tmp/rivia"));
+/// ```
+pub fn temp_root() -> PathBuf {
+    if let Some(root) = TEMP_ROOT.read().ok().and_then(|x| x.clone()) {
+        return root;
+    }
+    match env::var(TEST_TEMP_ENV) {
+        Ok(x) if !x.is_empty() => PathBuf::from(x),
+        _ => PathBuf::from(TEST_TEMP_DIR),
+    }
+}
+
+/// Mirror the given real directories read-only into the given Memfs at the same paths
+///
+/// Useful for tests that need genuine system data like CA bundles or tzdata without access to the
+/// whole disk. Directories are mirrored when called as Memfs has no on demand loading. Once
+/// mirrored a [`VfsPolicy`] is attached to the Memfs denying any changes under the given paths.
+///
+/// * Links are mirrored as links and not followed
+/// * Modes and ownership are preserved
+/// * Entries that can't be read by the current user are skipped
+///
+/// ### Errors
+/// * PathError::DoesNotExist(PathBuf) when a given directory doesn't exist
+/// * PathError::IsNotDir(PathBuf) when a given path isn't a directory
+///
+/// ### Examples
+/// ```
+/// use rivia::prelude::*;
+///
+/// let memfs = Memfs::new();
+/// let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs(), "testing_func_with_system_configs");
+/// let file = tmpdir.mash("file");
+/// assert_vfs_write_all!(vfs, &file, "foo");
+/// assert!(testing::with_system_configs(&memfs, &[&tmpdir]).is_ok());
+/// assert_vfs_read_all!(memfs, &file, "foo");
+/// assert!(memfs.write_all(&file, "bar").is_err());
+/// assert_vfs_remove_all!(vfs, &tmpdir);
+/// ```
+pub fn with_system_configs<T: AsRef<Path>>(memfs: &Memfs, dirs: &[T]) -> RvResult<()> {
+    let mut policy = VfsPolicy::allow_by_default();
+    for dir in dirs {
+        let dir = Stdfs::abs(dir)?;
+        if !Stdfs::exists(&dir) {
+            return Err(PathError::does_not_exist(&dir).into());
+        } else if !Stdfs::is_dir(&dir) {
+            return Err(PathError::is_not_dir(&dir).into());
+        }
+
+        // Skip unreadable entries rather than failing the whole mirror
+        for entry in Stdfs::entries(&dir)?.into_iter().flatten() {
+            let path = entry.path();
+            if entry.is_symlink() {
+                memfs.symlink(path, Stdfs::readlink(path)?)?;
+                continue;
+            } else if entry.is_dir() {
+                memfs.mkdir_p(path)?;
+            } else {
+                let mut data = vec![];
+                match Stdfs::read(path).and_then(|mut x| Ok(x.read_to_end(&mut data)?)) {
+                    Ok(_) => memfs.write_all(path, &data)?,
+                    Err(_) => continue,
+                }
+            }
+            memfs.chmod(path, entry.mode())?;
+            let (uid, gid) = Stdfs::owner(path)?;
+            memfs.chown(path, uid, gid)?;
+        }
+        policy = policy.deny_all(dir.to_string_lossy()).deny_all(dir.join("**").to_string_lossy());
+    }
+    policy.attach(memfs);
+    Ok(())
+}
+
+// Unit tests
+// -------------------------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    #[test]
+    fn test_with_system_configs() {
+        let memfs = Memfs::new();
+        let (vfs, tmpdir) = assert_vfs_setup!(Vfs::stdfs());
+        let dir1 = tmpdir.mash("dir1");
+        let file1 = dir1.mash("file1");
+        let file2 = tmpdir.mash("file2");
+        let link1 = tmpdir.mash("link1");
+        assert_vfs_mkdir_p!(vfs, &dir1);
+        assert!(vfs.chmod(&dir1, 0o750).is_ok());
+        assert_vfs_write_all!(vfs, &file1, vec![0u8, 159, 146, 150]);
+        assert!(vfs.mkfile_m(&file2, 0o600).is_ok());
+        assert_vfs_symlink!(vfs, &link1, &file1);
+
+        // mirrored with modes, ownership and links intact
+        assert!(testing::with_system_configs(&memfs, &[&tmpdir]).is_ok());
+        assert_eq!(memfs.mode(&dir1).unwrap(), 0o40750);
+        assert_eq!(memfs.mode(&file2).unwrap(), 0o100600);
+        assert_eq!(memfs.owner(&file2).unwrap(), vfs.owner(&file2).unwrap());
+        let mut data = vec![];
+        assert!(memfs.read(&file1).unwrap().read_to_end(&mut data).is_ok());
+        assert_eq!(data, vec![0u8, 159, 146, 150]);
+        assert_vfs_readlink!(memfs, &link1, PathBuf::from("dir1/file1"));
+
+        // read-only inside but not outside
+        assert!(memfs.write_all(&file2, "foo").is_err());
+        assert!(memfs.remove(&link1).is_err());
+        assert!(memfs.mkfile(dir1.mash("file3")).is_err());
+        assert!(memfs.remove_all(&tmpdir).is_err());
+        assert!(memfs.mkfile(memfs.root().mash("file")).is_ok());
+
+        // errors
+        assert_eq!(
+            testing::with_system_configs(&memfs, &[tmpdir.mash("foo")]).unwrap_err().downcast_ref::<PathError>(),
+            Some(&PathError::does_not_exist(tmpdir.mash("foo")))
+        );
+        assert_eq!(
+            testing::with_system_configs(&memfs, &[&file2]).unwrap_err().downcast_ref::<PathError>(),
+            Some(&PathError::is_not_dir(&file2))
+        );
+
+        assert_vfs_remove_all!(vfs, &tmpdir);
+    }
+}